@@ -0,0 +1,241 @@
+//! ELF64 dynamic section (`PT_DYNAMIC`) parsing and symbol hashing.
+//!
+//! Provides the `DT_*` tags a dynamic linker needs, zero-copy iteration
+//! over `Elf64_Dyn` entries, and the System V and GNU symbol hash functions
+//! used by `DT_HASH` and `DT_GNU_HASH` tables.
+
+use crate::header::le_u64;
+
+/// Dynamic tag: end of the dynamic section.
+pub const DT_NULL: u64 = 0;
+
+/// Dynamic tag: string table offset of a needed library's name.
+pub const DT_NEEDED: u64 = 1;
+
+/// Dynamic tag: total size of the PLT relocations.
+pub const DT_PLTRELSZ: u64 = 2;
+
+/// Dynamic tag: address of the PLT GOT.
+pub const DT_PLTGOT: u64 = 3;
+
+/// Dynamic tag: address of the System V symbol hash table.
+pub const DT_HASH: u64 = 4;
+
+/// Dynamic tag: address of the dynamic string table.
+pub const DT_STRTAB: u64 = 5;
+
+/// Dynamic tag: address of the dynamic symbol table.
+pub const DT_SYMTAB: u64 = 6;
+
+/// Dynamic tag: address of the `Rela` relocation table.
+pub const DT_RELA: u64 = 7;
+
+/// Dynamic tag: total size of the `Rela` relocation table.
+pub const DT_RELASZ: u64 = 8;
+
+/// Dynamic tag: size of one `Rela` entry.
+pub const DT_RELAENT: u64 = 9;
+
+/// Dynamic tag: size of the dynamic string table.
+pub const DT_STRSZ: u64 = 10;
+
+/// Dynamic tag: size of one symbol table entry.
+pub const DT_SYMENT: u64 = 11;
+
+/// Dynamic tag: address of the initialization function.
+pub const DT_INIT: u64 = 12;
+
+/// Dynamic tag: address of the termination function.
+pub const DT_FINI: u64 = 13;
+
+/// Dynamic tag: string table offset of the shared object's name.
+pub const DT_SONAME: u64 = 14;
+
+/// Dynamic tag: symbol resolution starts with this object.
+pub const DT_SYMBOLIC: u64 = 16;
+
+/// Dynamic tag: type of the PLT relocations (`DT_RELA` or `DT_REL`).
+pub const DT_PLTREL: u64 = 20;
+
+/// Dynamic tag: reserved for debuggers.
+pub const DT_DEBUG: u64 = 21;
+
+/// Dynamic tag: relocations may modify a read-only segment.
+pub const DT_TEXTREL: u64 = 22;
+
+/// Dynamic tag: address of the PLT relocations.
+pub const DT_JMPREL: u64 = 23;
+
+/// Dynamic tag: process all relocations before transferring control.
+pub const DT_BIND_NOW: u64 = 24;
+
+/// Dynamic tag: address of the array of initialization functions.
+pub const DT_INIT_ARRAY: u64 = 25;
+
+/// Dynamic tag: address of the array of termination functions.
+pub const DT_FINI_ARRAY: u64 = 26;
+
+/// Dynamic tag: size of `DT_INIT_ARRAY` in bytes.
+pub const DT_INIT_ARRAYSZ: u64 = 27;
+
+/// Dynamic tag: size of `DT_FINI_ARRAY` in bytes.
+pub const DT_FINI_ARRAYSZ: u64 = 28;
+
+/// Dynamic tag: flag values (`DF_*`).
+pub const DT_FLAGS: u64 = 30;
+
+/// Dynamic tag: address of the GNU symbol hash table.
+pub const DT_GNU_HASH: u64 = 0x6fff_fef5;
+
+/// Dynamic tag: number of `R_*_RELATIVE` relocations at the start of `DT_RELA`.
+pub const DT_RELACOUNT: u64 = 0x6fff_fff9;
+
+/// Dynamic tag: extended flag values (`DF_1_*`).
+pub const DT_FLAGS_1: u64 = 0x6fff_fffb;
+
+/// `DT_FLAGS` bit: process all relocations before transferring control.
+pub const DF_BIND_NOW: u64 = 0x8;
+
+/// `DT_FLAGS` bit: the object uses the static TLS model.
+pub const DF_STATIC_TLS: u64 = 0x10;
+
+/// `DT_FLAGS_1` bit: process all relocations before transferring control.
+pub const DF_1_NOW: u64 = 0x1;
+
+/// Size of an ELF64 dynamic entry (16 bytes).
+pub const ELF64_DYN_SIZE: usize = 16;
+
+/// A parsed ELF64 dynamic section entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elf64Dyn {
+    /// Entry tag (`DT_*`).
+    pub d_tag: u64,
+    /// Value or address, depending on the tag.
+    pub d_val: u64,
+}
+
+/// An iterator over ELF64 dynamic entries, stopping at `DT_NULL`.
+pub struct DynamicIter<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> DynamicIter<'a> {
+    /// Creates an iterator over the dynamic entries in `data`, which holds
+    /// the contents of the `PT_DYNAMIC` segment.
+    ///
+    /// A trailing partial entry is ignored.
+    #[must_use]
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, offset: 0 }
+    }
+}
+
+impl Iterator for DynamicIter<'_> {
+    type Item = Elf64Dyn;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset + ELF64_DYN_SIZE > self.data.len() {
+            return None;
+        }
+        let entry = Elf64Dyn {
+            d_tag: le_u64(self.data, self.offset),
+            d_val: le_u64(self.data, self.offset + 8),
+        };
+        if entry.d_tag == DT_NULL {
+            self.offset = self.data.len();
+            return None;
+        }
+        self.offset += ELF64_DYN_SIZE;
+        Some(entry)
+    }
+}
+
+/// Computes the System V ELF hash of a symbol name, as used by `DT_HASH`.
+#[must_use]
+pub fn elf_hash(name: &[u8]) -> u32 {
+    let mut h: u32 = 0;
+    for &c in name {
+        h = (h << 4).wrapping_add(u32::from(c));
+        let g = h & 0xf000_0000;
+        if g != 0 {
+            h ^= g >> 24;
+        }
+        h &= !g;
+    }
+    h
+}
+
+/// Computes the GNU hash of a symbol name, as used by `DT_GNU_HASH`.
+#[must_use]
+pub fn gnu_hash(name: &[u8]) -> u32 {
+    name.iter().fold(5381u32, |h, &c| {
+        h.wrapping_mul(33).wrapping_add(u32::from(c))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_dynamic(entries: &[(u64, u64)]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        for &(tag, val) in entries {
+            buf.extend_from_slice(&tag.to_le_bytes());
+            buf.extend_from_slice(&val.to_le_bytes());
+        }
+        buf
+    }
+
+    #[test]
+    fn iterates_until_dt_null() {
+        let buf = make_dynamic(&[
+            (DT_NEEDED, 1),
+            (DT_STRTAB, 0x1000),
+            (DT_NULL, 0),
+            (DT_SYMTAB, 0x2000),
+        ]);
+        let entries: Vec<_> = DynamicIter::new(&buf).collect();
+        assert_eq!(
+            entries,
+            [
+                Elf64Dyn {
+                    d_tag: DT_NEEDED,
+                    d_val: 1
+                },
+                Elf64Dyn {
+                    d_tag: DT_STRTAB,
+                    d_val: 0x1000
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_partial_entry() {
+        let mut buf = make_dynamic(&[(DT_HASH, 0x100)]);
+        buf.extend_from_slice(&[0xAA; 8]);
+        assert_eq!(DynamicIter::new(&buf).count(), 1);
+    }
+
+    #[test]
+    fn empty_dynamic_section() {
+        assert_eq!(DynamicIter::new(&[]).count(), 0);
+    }
+
+    #[test]
+    fn elf_hash_known_values() {
+        assert_eq!(elf_hash(b""), 0);
+        assert_eq!(elf_hash(b"printf"), 0x0779_05a6);
+        assert_eq!(elf_hash(b"exit"), 0x0006_cf04);
+        assert_eq!(elf_hash(b"syscall"), 0x0b09_985c);
+    }
+
+    #[test]
+    fn gnu_hash_known_values() {
+        assert_eq!(gnu_hash(b""), 0x0000_1505);
+        assert_eq!(gnu_hash(b"printf"), 0x156b_2bb8);
+        assert_eq!(gnu_hash(b"exit"), 0x7c96_7e3f);
+        assert_eq!(gnu_hash(b"syscall"), 0xbac2_12a0);
+    }
+}
@@ -38,13 +38,40 @@ pub enum ElfType {
 const EM_X86_64: u16 = 62;
 
 /// Program header type: loadable segment.
-pub(crate) const PT_LOAD: u32 = 1;
+pub const PT_LOAD: u32 = 1;
+
+/// Program header type: dynamic linking information (`.dynamic`).
+pub const PT_DYNAMIC: u32 = 2;
+
+/// Program header type: path of the program interpreter.
+pub const PT_INTERP: u32 = 3;
+
+/// Program header type: the program header table itself.
+pub const PT_PHDR: u32 = 6;
+
+/// Program header type: thread-local storage template.
+pub const PT_TLS: u32 = 7;
+
+/// Program header type: stack permissions (GNU extension).
+pub const PT_GNU_STACK: u32 = 0x6474_e551;
+
+/// Program header type: read-only after relocation (GNU extension).
+pub const PT_GNU_RELRO: u32 = 0x6474_e552;
+
+/// Segment permission flag: executable.
+pub const PF_X: u32 = 1;
+
+/// Segment permission flag: writable.
+pub const PF_W: u32 = 2;
+
+/// Segment permission flag: readable.
+pub const PF_R: u32 = 4;
 
 /// Minimum size of an ELF64 file header (64 bytes).
 const ELF64_EHDR_SIZE: usize = 64;
 
 /// Size of an ELF64 program header entry (56 bytes).
-pub(crate) const ELF64_PHDR_SIZE: usize = 56;
+pub const ELF64_PHDR_SIZE: usize = 56;
 
 /// Size of an ELF64 section header entry (64 bytes).
 pub(crate) const ELF64_SHDR_SIZE: usize = 64;
@@ -252,8 +279,8 @@ impl Elf64Header {
 }
 
 /// Parsed ELF64 program header entry.
-#[derive(Debug, Clone, Copy)]
-pub(crate) struct Elf64ProgramHeader {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elf64ProgramHeader {
     /// Segment type.
     pub seg_type: u32,
     /// Segment flags (read/write/execute).
@@ -266,13 +293,16 @@ pub(crate) struct Elf64ProgramHeader {
     pub filesz: u64,
     /// Size of the segment in memory.
     pub memsz: u64,
+    /// Required alignment of the segment in memory and in the file.
+    pub align: u64,
 }
 
 impl Elf64ProgramHeader {
     /// Parse a program header entry from raw bytes at the given file offset.
     ///
     /// The caller must ensure `file_offset + ELF64_PHDR_SIZE <= data.len()`.
-    pub(crate) fn parse(data: &[u8], file_offset: usize) -> Self {
+    #[must_use]
+    pub fn parse(data: &[u8], file_offset: usize) -> Self {
         let b = &data[file_offset..];
         Self {
             seg_type: le_u32(b, 0),
@@ -282,6 +312,7 @@ impl Elf64ProgramHeader {
             // p_paddr at 24..32 — skipped
             filesz: le_u64(b, 32),
             memsz: le_u64(b, 40),
+            align: le_u64(b, 48),
         }
     }
 }
@@ -1,7 +1,8 @@
 //! Minimal ELF64 parser for Hadron OS.
 //!
-//! Parses ELF64 headers and `PT_LOAD` segments from raw byte slices using
-//! safe field extraction (`from_le_bytes`). No unsafe code, no allocations.
+//! Parses ELF64 headers, program headers, sections, relocations, and the
+//! dynamic section from raw byte slices using safe field extraction
+//! (`from_le_bytes`). No unsafe code, no allocations.
 //!
 //! # Usage
 //!
@@ -20,19 +21,26 @@
 #![cfg_attr(not(test), no_std)]
 #![forbid(unsafe_code)]
 
+pub mod dynamic;
 pub mod header;
 pub mod reloc;
 pub mod section;
 pub mod segment;
 
-pub use header::{Elf64Header, ElfError, ElfType};
+pub use dynamic::{DynamicIter, ELF64_DYN_SIZE, Elf64Dyn, elf_hash, gnu_hash};
+pub use header::{
+    ELF64_PHDR_SIZE, Elf64Header, Elf64ProgramHeader, ElfError, ElfType, PF_R, PF_W, PF_X,
+    PT_DYNAMIC, PT_GNU_RELRO, PT_GNU_STACK, PT_INTERP, PT_LOAD, PT_PHDR, PT_TLS,
+};
 pub use reloc::{
-    Elf64Rela, R_X86_64_32, R_X86_64_32S, R_X86_64_64, R_X86_64_GLOB_DAT, R_X86_64_NONE,
-    R_X86_64_PC32, R_X86_64_PLT32, R_X86_64_RELATIVE, RelaIter, RelocError, RelocValue,
-    compute_x86_64_reloc,
+    ELF64_RELA_SIZE, Elf64Rela, R_X86_64_32, R_X86_64_32S, R_X86_64_64, R_X86_64_COPY,
+    R_X86_64_DTPMOD64, R_X86_64_DTPOFF64, R_X86_64_GLOB_DAT, R_X86_64_IRELATIVE,
+    R_X86_64_JUMP_SLOT, R_X86_64_NONE, R_X86_64_PC32, R_X86_64_PLT32, R_X86_64_RELATIVE,
+    R_X86_64_TPOFF64, RelaIter, RelocError, RelocValue, compute_x86_64_reloc,
 };
 pub use section::{
-    Elf64SectionHeader, Elf64Symbol, SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE, SHN_UNDEF, SHT_DYNSYM,
-    SHT_RELA, SHT_STRTAB, SHT_SYMTAB, STB_GLOBAL, STB_WEAK, STT_FUNC, StringTable,
+    ELF64_SYM_SIZE, Elf64SectionHeader, Elf64Symbol, SHF_ALLOC, SHF_EXECINSTR, SHF_WRITE,
+    SHN_UNDEF, SHT_DYNSYM, SHT_RELA, SHT_STRTAB, SHT_SYMTAB, STB_GLOBAL, STB_LOCAL, STB_WEAK,
+    STT_FUNC, STT_GNU_IFUNC, STT_OBJECT, STT_TLS, StringTable,
 };
 pub use segment::{ElfFile, LoadSegment};
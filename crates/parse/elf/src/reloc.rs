@@ -22,9 +22,15 @@ pub const R_X86_64_PC32: u32 = 2;
 /// PLT-relative 32-bit: `S + A - P` (same as `PC32` for static linking).
 pub const R_X86_64_PLT32: u32 = 4;
 
+/// Copy the symbol's data from a shared object into the executable.
+pub const R_X86_64_COPY: u32 = 5;
+
 /// Global data: `S` (symbol value).
 pub const R_X86_64_GLOB_DAT: u32 = 6;
 
+/// PLT slot: `S` (symbol value), bound lazily by the dynamic linker.
+pub const R_X86_64_JUMP_SLOT: u32 = 7;
+
 /// Base-relative 64-bit: `B + A` (used in static-PIE / `ET_DYN`).
 pub const R_X86_64_RELATIVE: u32 = 8;
 
@@ -34,12 +40,24 @@ pub const R_X86_64_32: u32 = 10;
 /// Absolute 32-bit, sign-extended: `S + A`.
 pub const R_X86_64_32S: u32 = 11;
 
+/// TLS module ID of the symbol's module.
+pub const R_X86_64_DTPMOD64: u32 = 16;
+
+/// Offset of the symbol within its module's TLS block.
+pub const R_X86_64_DTPOFF64: u32 = 17;
+
+/// Offset of the symbol from the thread pointer (static TLS).
+pub const R_X86_64_TPOFF64: u32 = 18;
+
+/// Indirect function: `B + A` is a resolver returning the real address.
+pub const R_X86_64_IRELATIVE: u32 = 37;
+
 // ---------------------------------------------------------------------------
 // Size of a RELA entry
 // ---------------------------------------------------------------------------
 
 /// Size of an ELF64 `Rela` entry (24 bytes).
-const ELF64_RELA_SIZE: usize = 24;
+pub const ELF64_RELA_SIZE: usize = 24;
 
 // ---------------------------------------------------------------------------
 // Elf64Rela
@@ -60,11 +78,14 @@ pub struct Elf64Rela {
 
 impl Elf64Rela {
     /// Parse a single Rela entry from raw bytes at the given offset.
+    ///
+    /// The caller must ensure `offset + ELF64_RELA_SIZE <= data.len()`.
+    #[must_use]
     #[expect(
         clippy::cast_possible_truncation,
         reason = "r_info split into r_type/r_sym is defined by the ELF spec"
     )]
-    fn parse(data: &[u8], offset: usize) -> Self {
+    pub fn parse(data: &[u8], offset: usize) -> Self {
         let b = &data[offset..];
         let r_offset = le_u64(b, 0);
         let r_info = le_u64(b, 8);
@@ -92,9 +113,10 @@ pub struct RelaIter<'a> {
 impl<'a> RelaIter<'a> {
     /// Creates a new iterator over Rela entries.
     ///
-    /// `data` is the full ELF file; `offset` and `end` delimit the section
-    /// containing the Rela entries.
-    pub(crate) fn new(data: &'a [u8], offset: usize, end: usize) -> Self {
+    /// `data` is the full ELF file (or a loaded image); `offset` and `end`
+    /// delimit the section containing the Rela entries.
+    #[must_use]
+    pub fn new(data: &'a [u8], offset: usize, end: usize) -> Self {
         Self { data, offset, end }
     }
 }
@@ -212,7 +234,7 @@ pub fn compute_x86_64_reloc(
         }
 
         // S (64-bit)
-        R_X86_64_GLOB_DAT => RelocValue::U64(s),
+        R_X86_64_GLOB_DAT | R_X86_64_JUMP_SLOT => RelocValue::U64(s),
 
         // B + A (64-bit, PIE base-relative)
         R_X86_64_RELATIVE => {
@@ -420,6 +442,18 @@ mod tests {
         assert_eq!(val, RelocValue::U64(0x3000));
     }
 
+    #[test]
+    fn reloc_jump_slot() {
+        let rela = Elf64Rela {
+            r_offset: 0x18,
+            r_type: R_X86_64_JUMP_SLOT,
+            r_sym: 2,
+            r_addend: 0,
+        };
+        let (_, val) = compute_x86_64_reloc(&rela, 0x7000_1234, 0, 0).unwrap();
+        assert_eq!(val, RelocValue::U64(0x7000_1234));
+    }
+
     #[test]
     fn reloc_relative() {
         // B + A = 0x40_0000 + 0x1234 = 0x40_1234
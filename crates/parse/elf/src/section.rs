@@ -23,9 +23,21 @@ pub const SHT_REL: u32 = 9;
 /// Section type: dynamic symbol table.
 pub const SHT_DYNSYM: u32 = 11;
 
+/// Symbol type: data object.
+pub const STT_OBJECT: u8 = 1;
+
 /// Symbol type: function.
 pub const STT_FUNC: u8 = 2;
 
+/// Symbol type: thread-local storage object.
+pub const STT_TLS: u8 = 6;
+
+/// Symbol type: indirect function (GNU extension).
+pub const STT_GNU_IFUNC: u8 = 10;
+
+/// Symbol binding: local.
+pub const STB_LOCAL: u8 = 0;
+
 /// Symbol binding: global.
 pub const STB_GLOBAL: u8 = 1;
 
@@ -48,7 +60,7 @@ pub const SHF_INFO_LINK: u64 = 0x40;
 pub const SHN_UNDEF: u16 = 0;
 
 /// Size of an ELF64 symbol entry (24 bytes).
-const ELF64_SYM_SIZE: usize = 24;
+pub const ELF64_SYM_SIZE: usize = 24;
 
 /// Parsed ELF64 section header entry.
 #[derive(Debug, Clone, Copy)]
@@ -115,7 +127,8 @@ impl Elf64Symbol {
     /// Parse a symbol entry from raw bytes at the given offset.
     ///
     /// The caller must ensure `offset + ELF64_SYM_SIZE <= data.len()`.
-    fn parse(data: &[u8], offset: usize) -> Self {
+    #[must_use]
+    pub fn parse(data: &[u8], offset: usize) -> Self {
         let b = &data[offset..];
         Self {
             st_name: le_u32(b, 0),
@@ -1,9 +1,13 @@
 //! ELF64 segment (program header) iteration.
 //!
 //! Provides [`ElfFile`] as the main entry point for parsing an ELF64 binary,
-//! and [`LoadSegment`] for iterating over `PT_LOAD` segments.
+//! [`LoadSegment`] for iterating over `PT_LOAD` segments, and accessors for
+//! the program headers a dynamic loader needs (`PT_INTERP`, `PT_PHDR`).
 
-use crate::header::{ELF64_PHDR_SIZE, Elf64Header, Elf64ProgramHeader, ElfError, ElfType, PT_LOAD};
+use crate::header::{
+    ELF64_PHDR_SIZE, Elf64Header, Elf64ProgramHeader, ElfError, ElfType, PT_INTERP, PT_LOAD,
+    PT_PHDR,
+};
 
 /// A parsed ELF64 file, holding a reference to the raw data and the parsed header.
 #[derive(Debug, Clone, Copy)]
@@ -57,11 +61,8 @@ impl<'a> ElfFile<'a> {
         self.header.elf_type()
     }
 
-    /// Returns an iterator over `PT_LOAD` segments.
+    /// Returns an iterator over all program headers.
     ///
-    /// Each yielded [`LoadSegment`] contains a slice into the original data
-    /// for the file-backed portion and the total memory size (which may be
-    /// larger if the segment has a `.bss`-like zero-fill region).
     /// The header is already validated to ensure program header offsets fit in the
     /// file data, so truncation from `u64` to `usize` is safe on 64-bit targets
     /// (and would have been caught by `InvalidOffset` on 32-bit).
@@ -69,43 +70,98 @@ impl<'a> ElfFile<'a> {
         clippy::cast_possible_truncation,
         reason = "ELF segment fields fit in target width"
     )]
-    pub fn load_segments(&self) -> impl Iterator<Item = LoadSegment<'a>> {
+    pub fn program_headers(&self) -> impl Iterator<Item = Elf64ProgramHeader> + 'a {
         let data = self.data;
         let phoff = self.header.e_phoff as usize;
         let phentsize = self.header.e_phentsize as usize;
         let phnum = self.header.e_phnum as usize;
 
-        (0..phnum).filter_map(move |i| {
+        (0..phnum).map_while(move |i| {
             let offset = phoff + i * phentsize;
-            if offset + ELF64_PHDR_SIZE > data.len() {
-                return None;
-            }
-
-            let phdr = Elf64ProgramHeader::parse(data, offset);
-            if phdr.seg_type != PT_LOAD {
-                return None;
-            }
-
-            let file_offset = phdr.offset as usize;
-            let file_size = phdr.filesz as usize;
-
-            // Bounds-check the segment data within the file
-            let seg_data = if file_size == 0 {
-                &[] as &[u8]
-            } else if file_offset + file_size <= data.len() {
-                &data[file_offset..file_offset + file_size]
-            } else {
-                // Truncated segment — return what we can
-                &data[file_offset.min(data.len())..data.len()]
-            };
-
-            Some(LoadSegment {
+            (offset + ELF64_PHDR_SIZE <= data.len())
+                .then(|| Elf64ProgramHeader::parse(data, offset))
+        })
+    }
+
+    /// Returns the first program header of type `seg_type`, if any.
+    #[must_use]
+    pub fn find_program_header(&self, seg_type: u32) -> Option<Elf64ProgramHeader> {
+        self.program_headers()
+            .find(|phdr| phdr.seg_type == seg_type)
+    }
+
+    /// Returns an iterator over `PT_LOAD` segments.
+    ///
+    /// Each yielded [`LoadSegment`] contains a slice into the original data
+    /// for the file-backed portion and the total memory size (which may be
+    /// larger if the segment has a `.bss`-like zero-fill region).
+    pub fn load_segments(&self) -> impl Iterator<Item = LoadSegment<'a>> {
+        let data = self.data;
+        self.program_headers()
+            .filter(|phdr| phdr.seg_type == PT_LOAD)
+            .map(move |phdr| LoadSegment {
                 vaddr: phdr.vaddr,
-                data: seg_data,
+                data: segment_data(data, &phdr),
                 memsz: phdr.memsz,
                 flags: phdr.flags,
             })
-        })
+    }
+
+    /// Returns the program interpreter path from `PT_INTERP`, without its
+    /// NUL terminator.
+    ///
+    /// Returns `None` for statically linked images, or if the segment lies
+    /// outside the file.
+    #[must_use]
+    pub fn interpreter(&self) -> Option<&'a [u8]> {
+        let phdr = self.find_program_header(PT_INTERP)?;
+        let path = segment_data(self.data, &phdr);
+        if path.len() as u64 != phdr.filesz {
+            return None;
+        }
+        let len = path.iter().position(|&b| b == 0).unwrap_or(path.len());
+        Some(&path[..len])
+    }
+
+    /// Returns the link-time virtual address of the program header table.
+    ///
+    /// Uses `PT_PHDR` when present, and otherwise the `PT_LOAD` segment
+    /// whose file range covers the table. Returns `None` if the table is
+    /// not part of the loaded image.
+    #[must_use]
+    pub fn phdr_vaddr(&self) -> Option<u64> {
+        if let Some(phdr) = self.find_program_header(PT_PHDR) {
+            return Some(phdr.vaddr);
+        }
+        let phoff = self.header.e_phoff;
+        let table_size = u64::from(self.header.e_phnum) * u64::from(self.header.e_phentsize);
+        self.program_headers()
+            .find(|phdr| {
+                phdr.seg_type == PT_LOAD
+                    && phoff >= phdr.offset
+                    && phoff + table_size <= phdr.offset + phdr.filesz
+            })
+            .map(|phdr| phdr.vaddr + (phoff - phdr.offset))
+    }
+}
+
+/// Returns the file-backed bytes of `phdr`, truncated to the end of `data`.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "ELF segment fields fit in target width"
+)]
+fn segment_data<'a>(data: &'a [u8], phdr: &Elf64ProgramHeader) -> &'a [u8] {
+    let file_offset = phdr.offset as usize;
+    let file_size = phdr.filesz as usize;
+
+    // Bounds-check the segment data within the file
+    if file_size == 0 {
+        &[] as &[u8]
+    } else if file_offset + file_size <= data.len() {
+        &data[file_offset..file_offset + file_size]
+    } else {
+        // Truncated segment — return what we can
+        &data[file_offset.min(data.len())..data.len()]
     }
 }
 
@@ -236,6 +292,79 @@ mod tests {
         assert_eq!(elf.header().e_machine, 62);
     }
 
+    #[test]
+    fn interpreter_from_pt_interp() {
+        let mut buf = make_elf_header();
+        let interp = b"/lib/ld-hadron.so.1\0";
+        let data_offset: u64 = 64 + 56 * 2;
+
+        append_phdr(&mut buf, PT_PHDR, 4, 64, 0x40_0040, 112, 112);
+        append_phdr(
+            &mut buf,
+            PT_INTERP,
+            4,
+            data_offset,
+            0x40_00b0,
+            interp.len() as u64,
+            interp.len() as u64,
+        );
+        buf.extend_from_slice(interp);
+
+        let elf = ElfFile::parse(&buf).expect("valid ELF");
+        assert_eq!(elf.interpreter(), Some(&b"/lib/ld-hadron.so.1"[..]));
+        assert_eq!(elf.phdr_vaddr(), Some(0x40_0040));
+        assert_eq!(elf.program_headers().count(), 2);
+        assert_eq!(elf.load_segments().count(), 0);
+    }
+
+    #[test]
+    fn static_image_has_no_interpreter() {
+        let buf = make_elf_with_load_segment(b"code");
+        let elf = ElfFile::parse(&buf).expect("valid ELF");
+        assert_eq!(elf.interpreter(), None);
+    }
+
+    #[test]
+    fn truncated_interpreter_is_ignored() {
+        let mut buf = make_elf_header();
+        append_phdr(&mut buf, PT_INTERP, 4, 64 + 56, 0, 0x100, 0x100);
+        buf.extend_from_slice(b"/lib/ld");
+
+        let elf = ElfFile::parse(&buf).expect("valid ELF");
+        assert_eq!(elf.interpreter(), None);
+    }
+
+    #[test]
+    fn phdr_vaddr_from_covering_load_segment() {
+        let mut buf = make_elf_header();
+        // One PT_LOAD mapping the whole file (headers included) at 0x40_0000.
+        append_phdr(&mut buf, PT_LOAD, 4 | 1, 0, 0x40_0000, 0x200, 0x200);
+        buf.resize(0x200, 0);
+
+        let elf = ElfFile::parse(&buf).expect("valid ELF");
+        assert_eq!(elf.phdr_vaddr(), Some(0x40_0040));
+    }
+
+    #[test]
+    fn phdr_vaddr_outside_image() {
+        let mut buf = make_elf_header();
+        append_phdr(&mut buf, PT_LOAD, 4, 0x1000, 0x40_0000, 0, 0x1000);
+
+        let elf = ElfFile::parse(&buf).expect("valid ELF");
+        assert_eq!(elf.phdr_vaddr(), None);
+    }
+
+    #[test]
+    fn program_header_fields() {
+        let buf = make_elf_with_load_segment(b"abc");
+        let elf = ElfFile::parse(&buf).expect("valid ELF");
+        let phdr = elf.find_program_header(PT_LOAD).expect("PT_LOAD");
+        assert_eq!(phdr.offset, 64 + 56);
+        assert_eq!(phdr.filesz, 3);
+        assert_eq!(phdr.memsz, 3 + 0x100);
+        assert!(elf.find_program_header(PT_INTERP).is_none());
+    }
+
     #[test]
     fn parse_rejects_invalid_data() {
         assert!(ElfFile::parse(&[]).is_err());
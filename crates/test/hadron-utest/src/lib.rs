@@ -23,21 +23,31 @@
 //! fn test_one() { assert_eq!(1 + 1, 2); }
 //! fn test_two() { /* ... */ }
 //! ```
+//!
+//! Tests that need child processes can respawn their own binary
+//! (`/bin/init`) with a role in `argv[1]`; see [`utest_main!`].
 
 #![no_std]
 
+use core::sync::atomic::{AtomicPtr, Ordering};
+
 use hadron_syscall::wrappers::{sys_debug_log, sys_task_exit};
 
+/// Stack pointer at entry, pointing at `argc`; read by [`arg`].
+static ARGS: AtomicPtr<usize> = AtomicPtr::new(core::ptr::null_mut());
+
 /// ELF entry point.
 ///
-/// Aligns the stack and calls into `__utest_main`, which is generated by
-/// the [`utest_main!`] macro in the test binary.
+/// Passes the initial stack pointer (argc/argv/envp) along, aligns the
+/// stack and calls into `__utest_main`, which is generated by the
+/// [`utest_main!`] macro in the test binary.
 #[unsafe(no_mangle)]
 #[unsafe(naked)]
 pub extern "C" fn _start() -> ! {
     // SAFETY: Aligns stack to 16-byte boundary per System V ABI before calling
     // into Rust. No stack frame is needed for the naked function itself.
     core::arch::naked_asm!(
+        "mov rdi, rsp",
         "and rsp, -16",
         "call {entry}",
         entry = sym _utest_entry,
@@ -58,12 +68,39 @@ unsafe extern "C" {
     fn __utest_main() -> !;
 }
 
-fn _utest_entry() -> ! {
+extern "C" fn _utest_entry(stack: *mut usize) -> ! {
+    ARGS.store(stack, Ordering::Relaxed);
     // SAFETY: __utest_main is defined by utest_main! in the test binary
     // linked with this library. The linker ensures the symbol is present.
     unsafe { __utest_main() }
 }
 
+/// Returns argument `index` of the test binary's command line, without
+/// its NUL terminator.
+///
+/// The kernel starts PID 1 with `argv[0]` only, so `arg(1)` is `None`
+/// unless the test spawned its own binary with extra arguments.
+pub fn arg(index: usize) -> Option<&'static [u8]> {
+    let stack = ARGS.load(Ordering::Relaxed);
+    if stack.is_null() {
+        return None;
+    }
+    // SAFETY: `_start` stored the kernel's initial stack pointer, which
+    // points at argc followed by argc pointers to NUL-terminated strings
+    // that live for the whole process.
+    unsafe {
+        if index >= *stack {
+            return None;
+        }
+        let arg = *stack.add(1 + index) as *const u8;
+        let mut len = 0;
+        while *arg.add(len) != 0 {
+            len += 1;
+        }
+        Some(core::slice::from_raw_parts(arg, len))
+    }
+}
+
 /// Write a string slice to the kernel debug log (fd 1 equivalent).
 fn debug_write(s: &str) {
     let buf = s.as_bytes();
@@ -99,13 +136,27 @@ pub fn run_utests(tests: &[(&str, fn())]) -> ! {
 /// Generates `__utest_main` (called by [`_start`] in this library) and
 /// passes all listed test functions to [`run_utests`].
 ///
+/// With a leading `child = f;`, a process started with an `argv[1]` runs
+/// `f(argv[1])` instead of the tests. `f` must not return; tests use this
+/// to respawn their own binary as a helper process.
+///
 /// # Example
 ///
 /// ```ignore
 /// utest_main!(test_one, test_two);
+/// utest_main!(child = run_child; test_spawns_children);
 /// ```
 #[macro_export]
 macro_rules! utest_main {
+    (child = $child:path; $($test:ident),* $(,)?) => {
+        #[unsafe(no_mangle)]
+        pub extern "C" fn __utest_main() -> ! {
+            if let Some(role) = $crate::arg(1) {
+                $child(role)
+            }
+            $crate::run_utests(&[$((stringify!($test), $test),)*])
+        }
+    };
     ($($test:ident),* $(,)?) => {
         #[unsafe(no_mangle)]
         pub extern "C" fn __utest_main() -> ! {
@@ -1,8 +1,8 @@
 //! Initrd (initial ramdisk) CPIO archive creation.
 //!
 //! Packages pre-compiled userspace binaries into a CPIO newc archive
-//! with a Unix-like directory layout (`/bin`, `/etc`, `/tmp`), and
-//! creates symlinks for coreutils multi-call dispatch.
+//! with a Unix-like directory layout (`/bin`, `/etc`, `/home`, `/lib`,
+//! `/tmp`), and creates symlinks for coreutils multi-call dispatch.
 
 use anyhow::{Context, Result};
 use hadris_cpio::write::file_tree::{FileNode, FileTree};
@@ -16,6 +16,28 @@ const COREUTILS_COMMANDS: &[&str] = &[
     "echo", "cat", "ls", "uname", "uptime", "clear", "true", "false", "yes", "env", "pwd",
 ];
 
+/// UID of the unprivileged `user` account.
+pub const USER_ID: u32 = 1000;
+
+/// GID of the `users` group.
+pub const USERS_GID: u32 = 100;
+
+/// Contents of `/etc/passwd`.
+pub const PASSWD: &[u8] = b"root:x:0:0:root:/:/bin/sh\n\
+user:x:1000:100:user:/home/user:/bin/sh\n\
+nobody:x:65534:65534:nobody:/:/bin/sh\n";
+
+/// Contents of `/etc/group`.
+pub const GROUP: &[u8] = b"root:x:0:\n\
+users:x:100:user\n\
+nogroup:x:65534:\n";
+
+/// Crate that builds the dynamic linker.
+const DYNAMIC_LINKER_CRATE: &str = "hadron-ld";
+
+/// File name of the dynamic linker in `/lib/`, as named by `PT_INTERP`.
+const DYNAMIC_LINKER_NAME: &str = "ld-hadron.so.1";
+
 /// Mapping from lepton crate name to binary name in `/bin/`.
 fn binary_name(crate_name: &str) -> &str {
     match crate_name {
@@ -37,6 +59,10 @@ fn binary_name(crate_name: &str) -> &str {
 /// /bin/cat           → /bin/coreutils (symlink)
 /// /bin/...           → /bin/coreutils (symlink)
 /// /etc/profile       (PATH=/bin\nHOME=/\n)
+/// /etc/passwd        (root, user, nobody)
+/// /etc/group         (root, users, nogroup)
+/// /home/user/        (owned by uid 1000)
+/// /lib/ld-hadron.so.1 (hadron-ld)
 /// /tmp/              (empty directory)
 /// ```
 pub fn build_initrd(
@@ -47,13 +73,23 @@ pub fn build_initrd(
 
     let mut tree = FileTree::new();
 
-    // Build /bin/ directory contents.
+    // Build /bin/ directory contents; the dynamic linker goes in /lib/.
     let mut bin_children: Vec<FileNode> = Vec::new();
+    let mut lib_children: Vec<FileNode> = Vec::new();
 
     for (name, bin_path) in bin_artifacts {
         let data = std::fs::read(bin_path)
             .with_context(|| format!("reading userspace binary: {}", bin_path.display()))?;
 
+        if name == DYNAMIC_LINKER_CRATE {
+            println!(
+                "  Initrd: /lib/{DYNAMIC_LINKER_NAME} ({} bytes)",
+                data.len()
+            );
+            lib_children.push(FileNode::file(DYNAMIC_LINKER_NAME, data, 0o755));
+            continue;
+        }
+
         let bin_name = binary_name(name);
         println!("  Initrd: /bin/{bin_name} ({} bytes)", data.len());
         bin_children.push(FileNode::file(bin_name, data, 0o755));
@@ -66,13 +102,35 @@ pub fn build_initrd(
     }
 
     tree.add(FileNode::dir("bin", bin_children, 0o755));
+    tree.add(FileNode::dir("lib", lib_children, 0o755));
 
-    // Create /etc/profile with default environment.
+    // Create /etc/profile with default environment, and the user and
+    // group databases.
     let profile_contents = b"PATH=/bin\nHOME=/\n".to_vec();
     println!("  Initrd: /etc/profile ({} bytes)", profile_contents.len());
+    println!("  Initrd: /etc/passwd, /etc/group");
     tree.add(FileNode::dir(
         "etc",
-        vec![FileNode::file("profile", profile_contents, 0o644)],
+        vec![
+            FileNode::file("profile", profile_contents, 0o644),
+            FileNode::file("passwd", PASSWD.to_vec(), 0o644),
+            FileNode::file("group", GROUP.to_vec(), 0o644),
+        ],
+        0o755,
+    ));
+
+    // Create /home/user/, owned by the unprivileged user.
+    println!("  Initrd: /home/user/ (uid {USER_ID})");
+    tree.add(FileNode::dir(
+        "home",
+        vec![FileNode::dir_with_owner(
+            "user",
+            vec![],
+            0o755,
+            USER_ID,
+            USERS_GID,
+            0,
+        )],
         0o755,
     ));
 
@@ -9,12 +9,16 @@ use hadris_cpio::write::file_tree::{FileNode, FileTree};
 use hadris_cpio::write::{CpioWriteOptions, CpioWriter};
 use std::path::{Path, PathBuf};
 
+use super::initrd::{GROUP, PASSWD};
+
 /// Package a compiled userspace test binary into a minimal CPIO archive.
 ///
 /// Layout:
 /// ```text
 /// /bin/init     (the test binary, mode 0o755)
 /// /etc/profile  (PATH=/bin\nHOME=/\n)
+/// /etc/passwd   (same accounts as the main initrd)
+/// /etc/group
 /// /tmp/         (empty directory)
 /// ```
 ///
@@ -39,7 +43,11 @@ pub fn build_utest_cpio(root: &Path, test_name: &str, binary_path: &Path) -> Res
     let profile_contents = b"PATH=/bin\nHOME=/\n".to_vec();
     tree.add(FileNode::dir(
         "etc",
-        vec![FileNode::file("profile", profile_contents, 0o644)],
+        vec![
+            FileNode::file("profile", profile_contents, 0o644),
+            FileNode::file("passwd", PASSWD.to_vec(), 0o644),
+            FileNode::file("group", GROUP.to_vec(), 0o644),
+        ],
         0o755,
     ));
 
@@ -327,6 +327,20 @@ fn compile_crate_cross(
         }
     }
 
+    // Kernel address sanitizer for project crates linked into the kernel
+    // (config-enabled groups). Outline `__asan_*` calls only: the kernel
+    // provides the runtime, and has no stack or global shadow. The sysroot
+    // is not instrumented, hence the ABI mismatch allowance.
+    if krate.is_project_crate && config_rlib.is_some() {
+        if let Some(ResolvedValue::Bool(true)) = config.options.get("kasan") {
+            cmd.arg("-Zsanitizer=kernel-address")
+                .arg("-Cunsafe-allow-abi-mismatch=sanitizer")
+                .arg("-Cllvm-args=-asan-instrumentation-with-call-threshold=0")
+                .arg("-Cllvm-args=-asan-stack=0")
+                .arg("-Cllvm-args=-asan-globals=0");
+        }
+    }
+
     // Clippy lint flags for project crates.
     if mode == CompileMode::Clippy && krate.is_project_crate {
         cmd.warn("clippy::all").warn("clippy::pedantic");
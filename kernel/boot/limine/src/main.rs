@@ -4,6 +4,12 @@
 //! requests, converts the bootloader responses into the kernel's
 //! [`BootInfo`](hadron_kernel::boot::BootInfo) types, builds kernel-owned page
 //! tables, switches CR3, and calls [`kernel_init`](hadron_kernel::kernel_init).
+//!
+//! The stub also implements kernel image KASLR: Limine loads the PIE kernel
+//! at its link address (`kaslr: no` in `limine.conf`), and the stub maps it a
+//! second time at a random 2 MiB-aligned slide, applies the image's
+//! relocations for that address, continues there, and unmaps the original
+//! alias. The `nokaslr` command-line option disables the slide.
 
 #![no_std]
 #![no_main]
@@ -20,6 +26,7 @@ use hadron_kernel::boot::{
     MAX_MEMORY_REGIONS, MAX_SMP_CPUS, MemoryRegion, MemoryRegionKind, PagingMode, PixelFormat,
     SmpCpuEntry,
 };
+use hadron_kernel::mm::layout;
 use hadron_kernel::paging::{PhysFrame, Size4KiB};
 use planck_noalloc::vec::ArrayVec;
 
@@ -30,6 +37,9 @@ unsafe extern "C" {
     static __rodata_end: u8;
     static __data_start: u8;
     static __data_end: u8;
+    static __kernel_end: u8;
+    static __rela_dyn_start: u8;
+    static __rela_dyn_end: u8;
 }
 
 // ---------------------------------------------------------------------------
@@ -68,9 +78,33 @@ impl BumpFrameAllocator {
     }
 }
 
+/// Arguments handed from [`_start`] to [`stage2`] across the KASLR jump.
+///
+/// Lives on the Limine stack (in the HHDM), so it stays valid after the
+/// link-time alias of the kernel image is unmapped. Must not hold pointers
+/// into the kernel image.
+struct Stage2Args {
+    hhdm_offset: u64,
+    kernel_phys_base: PhysAddr,
+    /// Base the kernel was linked at (and loaded at by Limine).
+    link_virt_base: VirtAddr,
+    /// KASLR slide applied to the image (0 if disabled).
+    slide: u64,
+    /// Size of the kernel image in bytes.
+    image_size: u64,
+    pml4_phys: PhysAddr,
+    largest_start: u64,
+    largest_size: u64,
+    frames_used: u64,
+}
+
 /// Limine entry point. This is called by the bootloader after it has loaded the kernel
 /// and populated the `REQUESTS` struct with responses. This function must not return, and should
 /// call `kernel_init` to enter the kernel proper.
+///
+/// Runs at the link-time address. It builds the kernel page tables with the
+/// image mapped at its randomized (KASLR) address, applies the image's
+/// relocations for that address, and jumps to [`stage2`] there.
 #[unsafe(no_mangle)]
 extern "C" fn _start() -> ! {
     let serial = hadron_drivers::uart16550::Uart16550::new(hadron_drivers::uart16550::COM1);
@@ -100,7 +134,23 @@ extern "C" fn _start() -> ! {
         .response()
         .expect("Executable address response not available");
     let kernel_phys_base = PhysAddr::new(exec_addr.phys_base);
-    let kernel_virt_base = VirtAddr::new(exec_addr.virt_base);
+    let link_virt_base = VirtAddr::new(exec_addr.virt_base);
+
+    // 3b. Pick the KASLR slide for the kernel image.
+    let image_size = core::ptr::addr_of!(__kernel_end) as u64 - link_virt_base.as_u64();
+    let nokaslr = REQUESTS
+        .cmdline
+        .response()
+        .is_some_and(|r| r.cmdline().split_whitespace().any(|t| t == "nokaslr"));
+    let slide = if nokaslr {
+        0
+    } else if link_virt_base.as_u64() != layout::KERNEL_IMAGE_BASE {
+        // The bootloader already relocated the image; do not slide it twice.
+        hadron_kernel::kwarn!("KASLR: kernel not loaded at its link address, not sliding");
+        0
+    } else {
+        layout::randomize_kernel_slide(image_size, hadron_kernel::arch::boot_entropy())
+    };
 
     // 4. Init bump frame allocator from the largest usable region.
     let mut largest_start = 0u64;
@@ -124,7 +174,8 @@ extern "C" fn _start() -> ! {
         hhdm_offset,
         memmap_response,
         kernel_phys_base,
-        kernel_virt_base,
+        link_virt_base,
+        slide,
         &framebuffers,
         &mut alloc,
     );
@@ -150,6 +201,66 @@ extern "C" fn _start() -> ! {
 
     hadron_kernel::kdebug!("CR3 switched to kernel-owned page tables");
 
+    // Take `stage2`'s address before relocating: if it is loaded through the
+    // GOT, the relocated entry would already include the slide.
+    let entry = stage2 as *const () as u64 + slide;
+
+    // 9b. Relocate the image for its new address. Both aliases are mapped
+    // now, so pointers fixed up to the slid image are already usable while
+    // we are still running at the link address.
+    if slide != 0 {
+        // SAFETY: Both aliases of the image are mapped, the relocation table
+        // is the one emitted by the linker for this image, and no other CPU
+        // is running kernel code yet.
+        let count =
+            unsafe { apply_relocations(hhdm_offset, kernel_phys_base, link_virt_base, slide) };
+        hadron_kernel::kdebug!("KASLR: applied {} relocations", count);
+    }
+
+    let args = Stage2Args {
+        hhdm_offset,
+        kernel_phys_base,
+        link_virt_base,
+        slide,
+        image_size,
+        pml4_phys,
+        largest_start,
+        largest_size,
+        frames_used,
+    };
+
+    // 9c. Continue in the slid image.
+    // SAFETY: `entry` is `stage2` in the slid alias of the image, which is
+    // mapped with the same permissions and has been relocated for it.
+    let entry = unsafe { core::mem::transmute::<u64, extern "C" fn(&Stage2Args) -> !>(entry) };
+    entry(&args)
+}
+
+/// Second boot stage, running at the (possibly slid) final kernel address.
+///
+/// Drops the link-time alias of the image, then converts the remaining
+/// Limine responses into [`BootInfoData`] and enters the kernel.
+extern "C" fn stage2(args: &Stage2Args) -> ! {
+    let hhdm_offset = args.hhdm_offset;
+    let pml4_phys = args.pml4_phys;
+    let kernel_virt_base = args.link_virt_base + args.slide;
+
+    if args.slide != 0 {
+        // The early log hooks are function pointers taken at the link
+        // address; point them at the slid image before the alias goes away.
+        hadron_kernel::log::Log::init_early_serial();
+        // SAFETY: Nothing references the link-time alias any more: we run
+        // in the slid image, and `args` lives on the Limine stack.
+        unsafe { unmap_link_alias(hhdm_offset, pml4_phys, args.link_virt_base, args.image_size) };
+        hadron_kernel::kinfo!(
+            "KASLR: kernel image at {} (slide {:#x})",
+            kernel_virt_base,
+            args.slide
+        );
+    }
+
+    let framebuffers = build_framebuffers();
+
     // 10. Extract boot modules by cmdline string.
     let mut initrd = None;
     if let Some(resp) = REQUESTS.modules.response() {
@@ -187,13 +298,13 @@ extern "C" fn _start() -> ! {
     // 11. Build BootInfoData (after CR3 switch, using new page tables).
     let boot_info = build_boot_info(
         hhdm_offset,
-        kernel_phys_base,
+        args.kernel_phys_base,
         kernel_virt_base,
         framebuffers,
         pml4_phys,
-        largest_start,
-        largest_size,
-        frames_used,
+        args.largest_start,
+        args.largest_size,
+        args.frames_used,
         initrd,
         smp_cpus,
         bsp_lapic_id,
@@ -206,6 +317,91 @@ extern "C" fn _start() -> ! {
     hadron_kernel::kernel_init(&boot_info);
 }
 
+// ---------------------------------------------------------------------------
+// KASLR relocation
+// ---------------------------------------------------------------------------
+
+/// `R_X86_64_NONE` relocation type.
+const R_X86_64_NONE: u64 = 0;
+/// `R_X86_64_RELATIVE` relocation type: `*offset = base + addend`.
+const R_X86_64_RELATIVE: u64 = 8;
+
+/// An ELF64 `Elf64_Rela` entry.
+#[repr(C)]
+struct Rela {
+    offset: u64,
+    info: u64,
+    addend: i64,
+}
+
+/// Applies the image's `R_X86_64_RELATIVE` relocations for a load at
+/// `link_virt_base + slide`, writing through the HHDM. Returns the number
+/// of relocations applied.
+///
+/// # Safety
+///
+/// The image must be loaded at `link_virt_base` (physically at
+/// `kernel_phys_base`) and the HHDM must map it. Code that runs after this
+/// call observes pointers into the slid image, so that alias must be
+/// mapped.
+unsafe fn apply_relocations(
+    hhdm_offset: u64,
+    kernel_phys_base: PhysAddr,
+    link_virt_base: VirtAddr,
+    slide: u64,
+) -> usize {
+    let start = core::ptr::addr_of!(__rela_dyn_start) as *const Rela;
+    let end = core::ptr::addr_of!(__rela_dyn_end) as *const Rela;
+    // SAFETY: The linker script brackets `.rela.dyn` with these symbols.
+    let relas = unsafe { core::slice::from_raw_parts(start, end.offset_from(start) as usize) };
+
+    let mut applied = 0;
+    for rela in relas {
+        match rela.info & 0xFFFF_FFFF {
+            R_X86_64_NONE => {}
+            R_X86_64_RELATIVE => {
+                let phys = rela.offset - link_virt_base.as_u64() + kernel_phys_base.as_u64();
+                // The addend is the link-time address of the target.
+                let value = (rela.addend as u64).wrapping_add(slide);
+                // SAFETY: `phys` lies within the loaded image, which the HHDM
+                // maps writable. Relocation targets need not be aligned.
+                unsafe { core::ptr::write_unaligned((hhdm_offset + phys) as *mut u64, value) };
+                applied += 1;
+            }
+            ty => panic!("KASLR: unsupported kernel relocation type {ty}"),
+        }
+    }
+    applied
+}
+
+/// Unmaps the link-time alias of the kernel image left behind by the jump
+/// into the slid image.
+///
+/// The alias is mapped without `GLOBAL`, so a CR3 reload drops it from the
+/// TLB. Its page table frames stay allocated; they are part of the bump
+/// region reported as used in the memory map.
+///
+/// # Safety
+///
+/// Nothing may execute from or reference the alias afterwards.
+unsafe fn unmap_link_alias(
+    hhdm_offset: u64,
+    pml4_phys: PhysAddr,
+    link_virt_base: VirtAddr,
+    image_size: u64,
+) {
+    let mapper = PageTableMapper::new(VirtAddr::new(hhdm_offset));
+    let mut virt = link_virt_base.as_u64();
+    let end = (link_virt_base + image_size).align_up(0x1000).as_u64();
+    while virt < end {
+        // SAFETY: `pml4_phys` is the live kernel PML4; gaps between
+        // sections are simply not mapped.
+        let _ = unsafe { mapper.unmap_4k(pml4_phys, VirtAddr::new(virt)) };
+        virt += 0x1000;
+    }
+    hadron_kernel::arch::x86_64::instructions::tlb::flush_all();
+}
+
 // ---------------------------------------------------------------------------
 // Page table construction
 // ---------------------------------------------------------------------------
@@ -215,6 +411,7 @@ fn build_page_tables(
     memmap_response: &limine::MemMapResponse,
     kernel_phys_base: PhysAddr,
     kernel_virt_base: VirtAddr,
+    slide: u64,
     framebuffers: &ArrayVec<FramebufferInfo, MAX_FRAMEBUFFERS>,
     alloc: &mut BumpFrameAllocator,
 ) -> PhysAddr {
@@ -296,48 +493,57 @@ fn build_page_tables(
     let data_start = VirtAddr::new(core::ptr::addr_of!(__data_start) as u64);
     let data_end = VirtAddr::new(core::ptr::addr_of!(__data_end) as u64);
 
-    // .text: executable, read-only
-    let text_flags = PageTableFlags::PRESENT | PageTableFlags::GLOBAL;
-    map_kernel_range(
-        &mapper,
-        pml4_phys,
-        text_start,
-        text_end,
-        kernel_phys_base,
-        kernel_virt_base,
-        text_flags,
-        alloc,
-    );
-
-    // .rodata: read-only, no execute
-    let rodata_flags =
-        PageTableFlags::PRESENT | PageTableFlags::GLOBAL | PageTableFlags::NO_EXECUTE;
-    map_kernel_range(
-        &mapper,
-        pml4_phys,
-        rodata_start,
-        rodata_end,
-        kernel_phys_base,
-        kernel_virt_base,
-        rodata_flags,
-        alloc,
-    );
-
-    // .data + .bss: read-write, no execute
-    let data_flags = PageTableFlags::PRESENT
-        | PageTableFlags::WRITABLE
-        | PageTableFlags::GLOBAL
-        | PageTableFlags::NO_EXECUTE;
-    map_kernel_range(
-        &mapper,
-        pml4_phys,
-        data_start,
-        data_end,
-        kernel_phys_base,
-        kernel_virt_base,
-        data_flags,
-        alloc,
-    );
+    let sections = [
+        // .text: executable, read-only
+        (
+            text_start,
+            text_end,
+            PageTableFlags::PRESENT | PageTableFlags::GLOBAL,
+        ),
+        // .rodata: read-only, no execute
+        (
+            rodata_start,
+            rodata_end,
+            PageTableFlags::PRESENT | PageTableFlags::GLOBAL | PageTableFlags::NO_EXECUTE,
+        ),
+        // .data + .bss: read-write, no execute
+        (
+            data_start,
+            data_end,
+            PageTableFlags::PRESENT
+                | PageTableFlags::WRITABLE
+                | PageTableFlags::GLOBAL
+                | PageTableFlags::NO_EXECUTE,
+        ),
+    ];
+    for (start, end, flags) in sections {
+        map_kernel_range(
+            &mapper,
+            pml4_phys,
+            start,
+            end,
+            kernel_phys_base,
+            kernel_virt_base,
+            slide,
+            flags,
+            alloc,
+        );
+        // With KASLR, keep a non-global link-time alias so the stub can keep
+        // running until it jumps into the slid image (see `unmap_link_alias`).
+        if slide != 0 {
+            map_kernel_range(
+                &mapper,
+                pml4_phys,
+                start,
+                end,
+                kernel_phys_base,
+                kernel_virt_base,
+                0,
+                flags & !PageTableFlags::GLOBAL,
+                alloc,
+            );
+        }
+    }
 
     // --- Framebuffer mappings (2 MiB huge pages, write-combine via PAT entry 4) ---
     let fb_flags = PageTableFlags::PRESENT
@@ -366,7 +572,10 @@ fn build_page_tables(
     }
 
     // --- Identity map first 2 MiB (for CR3 switch transition) ---
-    let identity_flags = PageTableFlags::PRESENT | PageTableFlags::WRITABLE;
+    // Data only: nothing runs from low memory, and no kernel page may be
+    // both writable and executable.
+    let identity_flags =
+        PageTableFlags::PRESENT | PageTableFlags::WRITABLE | PageTableFlags::NO_EXECUTE;
     unsafe {
         mapper.map_2mib(
             pml4_phys,
@@ -380,7 +589,8 @@ fn build_page_tables(
     pml4_phys
 }
 
-/// Maps a kernel section range using 4 KiB pages.
+/// Maps a kernel section range using 4 KiB pages, `slide` bytes above the
+/// address it was loaded at.
 fn map_kernel_range(
     mapper: &PageTableMapper,
     pml4_phys: PhysAddr,
@@ -388,6 +598,7 @@ fn map_kernel_range(
     virt_end: VirtAddr,
     kernel_phys_base: PhysAddr,
     kernel_virt_base: VirtAddr,
+    slide: u64,
     flags: PageTableFlags,
     alloc: &mut BumpFrameAllocator,
 ) {
@@ -399,9 +610,13 @@ fn map_kernel_range(
     while virt < end_val {
         let phys = PhysAddr::new((virt - kernel_virt_base.as_u64()) + kernel_phys_base.as_u64());
         unsafe {
-            mapper.map_4k(pml4_phys, VirtAddr::new(virt), phys, flags, &mut || {
-                alloc.alloc_frame()
-            });
+            mapper.map_4k(
+                pml4_phys,
+                VirtAddr::new(virt + slide),
+                phys,
+                flags,
+                &mut || alloc.alloc_frame(),
+            );
         }
         virt += 0x1000;
     }
@@ -117,7 +117,8 @@ impl<F> AltFnDispatch<F> {
 /// Declares an alternative-function dispatch point.
 ///
 /// Creates:
-/// 1. A `#[doc(hidden)]` module containing the `AltFnDispatch` static.
+/// 1. A `#[doc(hidden)]` module containing the `AltFnDispatch` static,
+///    placed in `.data.ro_after_init` (see [`ro_after_init!`](crate::ro_after_init!)).
 /// 2. A public `unsafe fn` wrapper that loads and calls through the dispatch.
 /// 3. A linkset entry for each alternative registering it for patching.
 ///
@@ -154,12 +155,15 @@ macro_rules! alt_fn {
         $vis mod $name {
             use super::*;
 
-            /// The dispatch static for this alt-function.
-            pub static DISPATCH: $crate::alt_fn::AltFnDispatch<
-                unsafe fn($($ty),*) $(-> $ret)?
-            > = $crate::alt_fn::AltFnDispatch::new(
-                $baseline as unsafe fn($($ty),*) $(-> $ret)?
-            );
+            $crate::ro_after_init! {
+                /// The dispatch static for this alt-function. Patched once
+                /// at boot, then read-only.
+                pub static DISPATCH: $crate::alt_fn::AltFnDispatch<
+                    unsafe fn($($ty),*) $(-> $ret)?
+                > = $crate::alt_fn::AltFnDispatch::new(
+                    $baseline as unsafe fn($($ty),*) $(-> $ret)?
+                );
+            }
         }
 
         $(#[$meta])*
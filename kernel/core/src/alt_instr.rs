@@ -199,4 +199,7 @@ macro_rules! alt_instr_feature_bits {
     (AVX2) => {
         "65536"
     }; // 1 << 16
+    (SMAP) => {
+        "2097152"
+    }; // 1 << 21
 }
@@ -24,6 +24,12 @@ bitflags::bitflags! {
         const XSAVE     = 1 << 5;
         /// AVX (Advanced Vector Extensions).
         const AVX       = 1 << 6;
+        /// RDRAND instruction.
+        const RDRAND    = 1 << 7;
+        /// PCID (Process-Context Identifiers).
+        const PCID      = 1 << 9;
+        /// LAPIC timer TSC-deadline mode.
+        const TSC_DEADLINE = 1 << 10;
 
         // -- Leaf 1, EDX --
         /// SSE2 (baseline on all x86_64 CPUs).
@@ -38,6 +44,16 @@ bitflags::bitflags! {
         const BMI2      = 1 << 18;
         /// ERMS (Enhanced REP MOVSB/STOSB).
         const ERMS      = 1 << 19;
+        /// SMEP (Supervisor Mode Execution Prevention).
+        const SMEP      = 1 << 20;
+        /// SMAP (Supervisor Mode Access Prevention).
+        const SMAP      = 1 << 21;
+        /// INVPCID (invalidate TLB entries by PCID).
+        const INVPCID   = 1 << 23;
+
+        // -- Leaf 7, sub-leaf 0, ECX --
+        /// UMIP (User-Mode Instruction Prevention).
+        const UMIP      = 1 << 22;
 
         // -- Leaf 1, ECX (virtualisation) --
         /// VMX (Virtual Machine Extensions).
@@ -0,0 +1,275 @@
+//! CPU time accounting.
+//!
+//! Every thread carries a [`CpuTimeCounter`] that the kernel charges with
+//! the time it spends in user mode and in system calls. Threads of one
+//! process also charge a shared [`GroupCpuTime`], which outlives any single
+//! thread and collects the time of reaped children.
+//!
+//! Time is measured with the TSC and converted to nanoseconds with a
+//! [`TscScale`] derived from the calibrated TSC frequency.
+
+use crate::sync::atomic::{AtomicU64, Ordering};
+
+/// Nanoseconds per second.
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Fractional bits of [`TscScale`]'s multiplier.
+const SCALE_SHIFT: u32 = 32;
+
+/// Converts TSC cycle counts to nanoseconds.
+///
+/// Holds a 32.32 fixed-point nanoseconds-per-cycle factor, so a conversion
+/// is one widening multiply and a shift.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TscScale {
+    mult: u64,
+}
+
+impl TscScale {
+    /// A scale that converts every cycle count to 0, for an uncalibrated TSC.
+    pub const ZERO: Self = Self { mult: 0 };
+
+    /// Creates the scale for a TSC running at `freq_hz`.
+    ///
+    /// A frequency of 0 yields [`TscScale::ZERO`].
+    #[must_use]
+    pub const fn from_frequency(freq_hz: u64) -> Self {
+        if freq_hz == 0 {
+            return Self::ZERO;
+        }
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "the multiplier fits in u64 for any frequency above 0.25 Hz"
+        )]
+        let mult = ((NANOS_PER_SEC as u128) << SCALE_SHIFT) / freq_hz as u128;
+        Self { mult: mult as u64 }
+    }
+
+    /// Returns the raw 32.32 fixed-point multiplier.
+    #[must_use]
+    pub const fn mult(self) -> u64 {
+        self.mult
+    }
+
+    /// Recreates a scale from a multiplier returned by [`mult`](Self::mult).
+    #[must_use]
+    pub const fn from_mult(mult: u64) -> Self {
+        Self { mult }
+    }
+
+    /// Converts `cycles` to nanoseconds, saturating at `u64::MAX`.
+    #[must_use]
+    pub const fn cycles_to_nanos(self, cycles: u64) -> u64 {
+        let nanos = (cycles as u128 * self.mult as u128) >> SCALE_SHIFT;
+        if nanos > u64::MAX as u128 {
+            u64::MAX
+        } else {
+            #[expect(clippy::cast_possible_truncation, reason = "checked above")]
+            {
+                nanos as u64
+            }
+        }
+    }
+}
+
+/// A snapshot of accumulated user and system time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuTimes {
+    /// Time spent executing in user mode, in nanoseconds.
+    pub user_ns: u64,
+    /// Time spent in the kernel on the thread's behalf, in nanoseconds.
+    pub system_ns: u64,
+}
+
+impl CpuTimes {
+    /// No time at all.
+    pub const ZERO: Self = Self {
+        user_ns: 0,
+        system_ns: 0,
+    };
+
+    /// Returns user plus system time, in nanoseconds.
+    #[must_use]
+    pub const fn total_ns(self) -> u64 {
+        self.user_ns.saturating_add(self.system_ns)
+    }
+
+    /// Returns the field-wise saturating sum of `self` and `other`.
+    #[must_use]
+    pub const fn saturating_add(self, other: Self) -> Self {
+        Self {
+            user_ns: self.user_ns.saturating_add(other.user_ns),
+            system_ns: self.system_ns.saturating_add(other.system_ns),
+        }
+    }
+}
+
+/// Lock-free user and system time counters.
+///
+/// Charged by the CPU the owner runs on and read from anywhere; the two
+/// halves are updated independently, so a reader may see one charge
+/// applied before the other.
+#[derive(Debug, Default)]
+pub struct CpuTimeCounter {
+    user_ns: AtomicU64,
+    system_ns: AtomicU64,
+}
+
+impl CpuTimeCounter {
+    /// Creates a counter with no time accumulated.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            user_ns: AtomicU64::new(0),
+            system_ns: AtomicU64::new(0),
+        }
+    }
+
+    /// Adds `ns` nanoseconds of user time.
+    pub fn add_user(&self, ns: u64) {
+        self.user_ns.fetch_add(ns, Ordering::Relaxed);
+    }
+
+    /// Adds `ns` nanoseconds of system time.
+    pub fn add_system(&self, ns: u64) {
+        self.system_ns.fetch_add(ns, Ordering::Relaxed);
+    }
+
+    /// Adds both halves of `times`.
+    pub fn add(&self, times: CpuTimes) {
+        self.add_user(times.user_ns);
+        self.add_system(times.system_ns);
+    }
+
+    /// Returns the time accumulated so far.
+    #[must_use]
+    pub fn load(&self) -> CpuTimes {
+        CpuTimes {
+            user_ns: self.user_ns.load(Ordering::Relaxed),
+            system_ns: self.system_ns.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// CPU time of a whole process.
+///
+/// Shared by all of its threads. `threads` includes threads that have
+/// already exited; `children` holds the totals of every child reaped by
+/// `wait`, including what those children had collected from their own
+/// children.
+#[derive(Debug, Default)]
+pub struct GroupCpuTime {
+    /// Time of every thread of the process, live or exited.
+    pub threads: CpuTimeCounter,
+    /// Time of reaped children and their reaped descendants.
+    pub children: CpuTimeCounter,
+}
+
+impl GroupCpuTime {
+    /// Creates a group with no time accumulated.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            threads: CpuTimeCounter::new(),
+            children: CpuTimeCounter::new(),
+        }
+    }
+
+    /// Returns what the process contributes to its parent's children time
+    /// once reaped: its own time plus that of its reaped children.
+    #[must_use]
+    pub fn reaped_total(&self) -> CpuTimes {
+        self.threads.load().saturating_add(self.children.load())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_of_one_ghz_is_identity() {
+        let scale = TscScale::from_frequency(NANOS_PER_SEC);
+        assert_eq!(scale.cycles_to_nanos(0), 0);
+        assert_eq!(scale.cycles_to_nanos(1), 1);
+        assert_eq!(scale.cycles_to_nanos(123_456_789), 123_456_789);
+    }
+
+    #[test]
+    fn scale_converts_whole_seconds() {
+        for freq in [1_000_000, 2_400_000_000, 3_123_456_789] {
+            let scale = TscScale::from_frequency(freq);
+            let nanos = scale.cycles_to_nanos(freq);
+            // One second of cycles, within the rounding of the multiplier.
+            assert!(nanos.abs_diff(NANOS_PER_SEC) <= 1, "{freq} Hz: {nanos}");
+        }
+    }
+
+    #[test]
+    fn zero_frequency_gives_zero_scale() {
+        let scale = TscScale::from_frequency(0);
+        assert_eq!(scale, TscScale::ZERO);
+        assert_eq!(scale.cycles_to_nanos(u64::MAX), 0);
+    }
+
+    #[test]
+    fn scale_saturates() {
+        let scale = TscScale::from_frequency(1);
+        assert_eq!(scale.cycles_to_nanos(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn scale_round_trips_through_mult() {
+        let scale = TscScale::from_frequency(2_000_000_000);
+        assert_eq!(TscScale::from_mult(scale.mult()), scale);
+    }
+
+    #[test]
+    fn counter_accumulates() {
+        let counter = CpuTimeCounter::new();
+        counter.add_user(10);
+        counter.add_system(3);
+        counter.add(CpuTimes {
+            user_ns: 5,
+            system_ns: 7,
+        });
+        let times = counter.load();
+        assert_eq!(
+            times,
+            CpuTimes {
+                user_ns: 15,
+                system_ns: 10,
+            }
+        );
+        assert_eq!(times.total_ns(), 25);
+    }
+
+    #[test]
+    fn times_add_saturates() {
+        let big = CpuTimes {
+            user_ns: u64::MAX,
+            system_ns: 1,
+        };
+        let sum = big.saturating_add(big);
+        assert_eq!(sum.user_ns, u64::MAX);
+        assert_eq!(sum.system_ns, 2);
+        assert_eq!(sum.total_ns(), u64::MAX);
+    }
+
+    #[test]
+    fn reaped_total_includes_grandchildren() {
+        let group = GroupCpuTime::new();
+        group.threads.add_user(100);
+        group.children.add(CpuTimes {
+            user_ns: 20,
+            system_ns: 5,
+        });
+        assert_eq!(
+            group.reaped_total(),
+            CpuTimes {
+                user_ns: 120,
+                system_ns: 5,
+            }
+        );
+    }
+}
@@ -0,0 +1,535 @@
+//! Process credentials and file access checks.
+//!
+//! A process acts with a real, effective and saved user ID, the matching
+//! three group IDs, and a list of supplementary groups. The effective IDs
+//! and the supplementary groups decide file access; the real and saved user
+//! IDs decide who may signal the process.
+//!
+//! Transitions follow POSIX: an unprivileged process may only move among
+//! its own real, effective and saved IDs, while a process with effective
+//! UID 0 may take on any identity. There are no capabilities; effective
+//! UID 0 is all-powerful.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use bitflags::bitflags;
+
+use crate::id::{Gid, Uid};
+
+/// Maximum number of supplementary groups a process may hold.
+pub const NGROUPS_MAX: usize = 32;
+
+/// Mode bit: run with the file owner's user ID (set-user-ID).
+pub const S_ISUID: u16 = 0o4000;
+/// Mode bit: run with the file group's group ID (set-group-ID).
+pub const S_ISGID: u16 = 0o2000;
+/// Mode bit: in a directory, only owners may delete entries (sticky).
+pub const S_ISVTX: u16 = 0o1000;
+
+bitflags! {
+    /// Kinds of file access, checked against the `rwx` bits of a mode.
+    ///
+    /// The values match one class of a POSIX mode, so a class can be
+    /// compared after shifting it down.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Access: u16 {
+        /// Read a file or list a directory.
+        const READ    = 0o4;
+        /// Write a file or add and remove directory entries.
+        const WRITE   = 0o2;
+        /// Execute a file or search a directory.
+        const EXECUTE = 0o1;
+    }
+}
+
+/// Error returned when a credential change is refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredError {
+    /// The caller lacks the privilege for the change (`EPERM`).
+    NotPermitted,
+    /// More than [`NGROUPS_MAX`] supplementary groups (`EINVAL`).
+    TooManyGroups,
+}
+
+/// The identity a process acts with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credentials {
+    uid: Uid,
+    euid: Uid,
+    suid: Uid,
+    gid: Gid,
+    egid: Gid,
+    sgid: Gid,
+    groups: Vec<Gid>,
+}
+
+impl Credentials {
+    /// Credentials of the superuser, as held by init.
+    #[must_use]
+    pub const fn root() -> Self {
+        Self::new(Uid::ROOT, Gid::ROOT)
+    }
+
+    /// Credentials with every user ID set to `uid`, every group ID set to
+    /// `gid`, and no supplementary groups.
+    #[must_use]
+    pub const fn new(uid: Uid, gid: Gid) -> Self {
+        Self {
+            uid,
+            euid: uid,
+            suid: uid,
+            gid,
+            egid: gid,
+            sgid: gid,
+            groups: Vec::new(),
+        }
+    }
+
+    /// Real user ID.
+    #[must_use]
+    pub fn uid(&self) -> Uid {
+        self.uid
+    }
+
+    /// Effective user ID.
+    #[must_use]
+    pub fn euid(&self) -> Uid {
+        self.euid
+    }
+
+    /// Saved set-user-ID.
+    #[must_use]
+    pub fn suid(&self) -> Uid {
+        self.suid
+    }
+
+    /// Real group ID.
+    #[must_use]
+    pub fn gid(&self) -> Gid {
+        self.gid
+    }
+
+    /// Effective group ID.
+    #[must_use]
+    pub fn egid(&self) -> Gid {
+        self.egid
+    }
+
+    /// Saved set-group-ID.
+    #[must_use]
+    pub fn sgid(&self) -> Gid {
+        self.sgid
+    }
+
+    /// Supplementary group IDs.
+    #[must_use]
+    pub fn groups(&self) -> &[Gid] {
+        &self.groups
+    }
+
+    /// Returns `true` if the effective user is the superuser.
+    #[must_use]
+    pub fn is_privileged(&self) -> bool {
+        self.euid == Uid::ROOT
+    }
+
+    /// Returns `true` if `gid` is the effective group or a supplementary
+    /// group.
+    #[must_use]
+    pub fn in_group(&self, gid: Gid) -> bool {
+        self.egid == gid || self.groups.contains(&gid)
+    }
+
+    /// `setuid`: a privileged caller sets all three user IDs; otherwise
+    /// only the effective user ID changes, to the real or saved one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CredError::NotPermitted`] if an unprivileged caller asks
+    /// for an ID other than its real or saved user ID.
+    pub fn set_uid(&mut self, uid: Uid) -> Result<(), CredError> {
+        if self.is_privileged() {
+            self.uid = uid;
+            self.euid = uid;
+            self.suid = uid;
+        } else if uid == self.uid || uid == self.suid {
+            self.euid = uid;
+        } else {
+            return Err(CredError::NotPermitted);
+        }
+        Ok(())
+    }
+
+    /// `setresuid`: sets the real, effective and saved user IDs; `None`
+    /// leaves an ID unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CredError::NotPermitted`] if an unprivileged caller asks
+    /// for an ID that is not one of its current three user IDs.
+    pub fn set_resuid(
+        &mut self,
+        uid: Option<Uid>,
+        euid: Option<Uid>,
+        suid: Option<Uid>,
+    ) -> Result<(), CredError> {
+        let current = [self.uid, self.euid, self.suid];
+        if !self.is_privileged()
+            && [uid, euid, suid]
+                .into_iter()
+                .flatten()
+                .any(|id| !current.contains(&id))
+        {
+            return Err(CredError::NotPermitted);
+        }
+        self.uid = uid.unwrap_or(self.uid);
+        self.euid = euid.unwrap_or(self.euid);
+        self.suid = suid.unwrap_or(self.suid);
+        Ok(())
+    }
+
+    /// `setgid`: a privileged caller sets all three group IDs; otherwise
+    /// only the effective group ID changes, to the real or saved one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CredError::NotPermitted`] if an unprivileged caller asks
+    /// for an ID other than its real or saved group ID.
+    pub fn set_gid(&mut self, gid: Gid) -> Result<(), CredError> {
+        if self.is_privileged() {
+            self.gid = gid;
+            self.egid = gid;
+            self.sgid = gid;
+        } else if gid == self.gid || gid == self.sgid {
+            self.egid = gid;
+        } else {
+            return Err(CredError::NotPermitted);
+        }
+        Ok(())
+    }
+
+    /// `setresgid`: sets the real, effective and saved group IDs; `None`
+    /// leaves an ID unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CredError::NotPermitted`] if an unprivileged caller asks
+    /// for an ID that is not one of its current three group IDs.
+    pub fn set_resgid(
+        &mut self,
+        gid: Option<Gid>,
+        egid: Option<Gid>,
+        sgid: Option<Gid>,
+    ) -> Result<(), CredError> {
+        let current = [self.gid, self.egid, self.sgid];
+        if !self.is_privileged()
+            && [gid, egid, sgid]
+                .into_iter()
+                .flatten()
+                .any(|id| !current.contains(&id))
+        {
+            return Err(CredError::NotPermitted);
+        }
+        self.gid = gid.unwrap_or(self.gid);
+        self.egid = egid.unwrap_or(self.egid);
+        self.sgid = sgid.unwrap_or(self.sgid);
+        Ok(())
+    }
+
+    /// `setgroups`: replaces the supplementary groups.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CredError::NotPermitted`] if the caller is unprivileged,
+    /// or [`CredError::TooManyGroups`] if `groups` is longer than
+    /// [`NGROUPS_MAX`].
+    pub fn set_groups(&mut self, groups: &[Gid]) -> Result<(), CredError> {
+        if !self.is_privileged() {
+            return Err(CredError::NotPermitted);
+        }
+        if groups.len() > NGROUPS_MAX {
+            return Err(CredError::TooManyGroups);
+        }
+        self.groups = groups.to_vec();
+        Ok(())
+    }
+
+    /// Applies the set-user-ID and set-group-ID bits of an executed file
+    /// owned by `owner:group` with `mode`, then copies the effective IDs
+    /// into the saved ones, as `execve` does.
+    pub fn exec(&mut self, owner: Uid, group: Gid, mode: u16) {
+        if mode & S_ISUID != 0 {
+            self.euid = owner;
+        }
+        if mode & S_ISGID != 0 {
+            self.egid = group;
+        }
+        self.suid = self.euid;
+        self.sgid = self.egid;
+    }
+
+    /// Returns `true` if these credentials grant `access` to a file owned
+    /// by `owner:group` with permission bits `mode`.
+    ///
+    /// The owner class applies to the owner, the group class to members of
+    /// the group, and the other class to everyone else; classes do not fall
+    /// through. The superuser may read and write anything, and execute
+    /// anything that has at least one execute bit.
+    #[must_use]
+    pub fn may_access(&self, owner: Uid, group: Gid, mode: u16, access: Access) -> bool {
+        if self.is_privileged() {
+            return !access.contains(Access::EXECUTE) || mode & 0o111 != 0;
+        }
+        let class = if self.euid == owner {
+            mode >> 6
+        } else if self.in_group(group) {
+            mode >> 3
+        } else {
+            mode
+        };
+        Access::from_bits_truncate(class & 0o7).contains(access)
+    }
+
+    /// Returns `true` if these credentials may remove an entry owned by
+    /// `owner` from a directory owned by `dir_owner` with `dir_mode`.
+    ///
+    /// Only adds the sticky-directory rule; write and search access to the
+    /// directory are checked separately with [`may_access`](Self::may_access).
+    #[must_use]
+    pub fn may_delete(&self, dir_owner: Uid, dir_mode: u16, owner: Uid) -> bool {
+        dir_mode & S_ISVTX == 0
+            || self.is_privileged()
+            || self.euid == owner
+            || self.euid == dir_owner
+    }
+
+    /// Returns `true` if these credentials may send a signal to a process
+    /// running with `target`.
+    ///
+    /// The sender's real or effective user ID must match the target's real
+    /// or saved user ID, unless the sender is privileged.
+    #[must_use]
+    pub fn may_signal(&self, target: &Credentials) -> bool {
+        self.is_privileged()
+            || [self.uid, self.euid]
+                .iter()
+                .any(|id| *id == target.uid || *id == target.suid)
+    }
+
+    /// Returns `true` if these credentials may change the nice value or CPU
+    /// affinity of a process running with `target`.
+    ///
+    /// The caller's effective user ID must match the target's real or
+    /// effective user ID, unless the caller is privileged. Lowering a nice
+    /// value additionally requires privilege.
+    #[must_use]
+    pub fn may_reschedule(&self, target: &Credentials) -> bool {
+        self.is_privileged() || self.euid == target.uid || self.euid == target.euid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: Uid = Uid::new(1000);
+    const BOB: Uid = Uid::new(1001);
+    const USERS: Gid = Gid::new(100);
+    const STAFF: Gid = Gid::new(50);
+
+    fn alice() -> Credentials {
+        Credentials::new(ALICE, USERS)
+    }
+
+    #[test]
+    fn root_is_privileged() {
+        assert!(Credentials::root().is_privileged());
+        assert!(!alice().is_privileged());
+    }
+
+    #[test]
+    fn root_set_uid_drops_all_ids() {
+        let mut cred = Credentials::root();
+        cred.set_uid(ALICE).unwrap();
+        assert_eq!(
+            (cred.uid(), cred.euid(), cred.suid()),
+            (ALICE, ALICE, ALICE)
+        );
+        // No way back.
+        assert_eq!(cred.set_uid(Uid::ROOT), Err(CredError::NotPermitted));
+    }
+
+    #[test]
+    fn unprivileged_set_uid_toggles_effective() {
+        // A set-user-ID program: real alice, effective and saved bob.
+        let mut cred = alice();
+        cred.exec(BOB, USERS, 0o4755);
+        assert_eq!((cred.uid(), cred.euid(), cred.suid()), (ALICE, BOB, BOB));
+
+        cred.set_uid(ALICE).unwrap();
+        assert_eq!((cred.uid(), cred.euid(), cred.suid()), (ALICE, ALICE, BOB));
+        cred.set_uid(BOB).unwrap();
+        assert_eq!(cred.euid(), BOB);
+        assert_eq!(cred.set_uid(Uid::new(7)), Err(CredError::NotPermitted));
+    }
+
+    #[test]
+    fn set_resuid_keeps_unchanged_ids() {
+        let mut cred = Credentials::root();
+        cred.set_resuid(None, Some(ALICE), None).unwrap();
+        assert_eq!(
+            (cred.uid(), cred.euid(), cred.suid()),
+            (Uid::ROOT, ALICE, Uid::ROOT)
+        );
+        // Unprivileged now, but root is still the real and saved ID.
+        cred.set_resuid(None, Some(Uid::ROOT), None).unwrap();
+        assert!(cred.is_privileged());
+    }
+
+    #[test]
+    fn unprivileged_set_resuid_is_limited_to_own_ids() {
+        let mut cred = alice();
+        assert_eq!(
+            cred.set_resuid(Some(BOB), None, None),
+            Err(CredError::NotPermitted)
+        );
+        assert_eq!(cred, alice());
+        cred.set_resuid(Some(ALICE), Some(ALICE), Some(ALICE))
+            .unwrap();
+    }
+
+    #[test]
+    fn set_gid_rules_follow_effective_uid() {
+        let mut cred = alice();
+        assert_eq!(cred.set_gid(STAFF), Err(CredError::NotPermitted));
+        assert_eq!(
+            cred.set_resgid(None, Some(STAFF), None),
+            Err(CredError::NotPermitted)
+        );
+
+        let mut cred = Credentials::root();
+        cred.set_gid(STAFF).unwrap();
+        assert_eq!(
+            (cred.gid(), cred.egid(), cred.sgid()),
+            (STAFF, STAFF, STAFF)
+        );
+        cred.set_resgid(Some(USERS), None, None).unwrap();
+        assert_eq!((cred.gid(), cred.egid()), (USERS, STAFF));
+    }
+
+    #[test]
+    fn set_groups_requires_privilege() {
+        let mut cred = Credentials::root();
+        cred.set_groups(&[STAFF]).unwrap();
+        assert!(cred.in_group(STAFF));
+        assert_eq!(
+            cred.set_groups(&[Gid::new(1); NGROUPS_MAX + 1]),
+            Err(CredError::TooManyGroups)
+        );
+
+        let mut cred = alice();
+        assert_eq!(cred.set_groups(&[STAFF]), Err(CredError::NotPermitted));
+    }
+
+    #[test]
+    fn exec_without_setid_bits_resets_saved_ids() {
+        let mut cred = Credentials::root();
+        cred.set_resuid(None, Some(ALICE), None).unwrap();
+        cred.exec(BOB, STAFF, 0o755);
+        assert_eq!(
+            (cred.uid(), cred.euid(), cred.suid()),
+            (Uid::ROOT, ALICE, ALICE)
+        );
+        assert_eq!(cred.egid(), Gid::ROOT);
+    }
+
+    #[test]
+    fn exec_setgid() {
+        let mut cred = alice();
+        cred.exec(BOB, STAFF, 0o2755);
+        assert_eq!(cred.euid(), ALICE);
+        assert_eq!(
+            (cred.gid(), cred.egid(), cred.sgid()),
+            (USERS, STAFF, STAFF)
+        );
+    }
+
+    #[test]
+    fn access_uses_one_class() {
+        let cred = alice();
+        // Owner class.
+        assert!(cred.may_access(ALICE, STAFF, 0o600, Access::READ | Access::WRITE));
+        assert!(!cred.may_access(ALICE, STAFF, 0o077, Access::READ));
+        // Group class.
+        assert!(cred.may_access(BOB, USERS, 0o040, Access::READ));
+        assert!(!cred.may_access(BOB, USERS, 0o404, Access::READ));
+        // Other class.
+        assert!(cred.may_access(BOB, STAFF, 0o004, Access::READ));
+        assert!(!cred.may_access(BOB, STAFF, 0o004, Access::WRITE));
+        assert!(!cred.may_access(BOB, STAFF, 0o770, Access::READ));
+    }
+
+    #[test]
+    fn access_supplementary_groups() {
+        let mut cred = Credentials::root();
+        cred.set_groups(&[STAFF]).unwrap();
+        cred.set_gid(USERS).unwrap();
+        cred.set_uid(ALICE).unwrap();
+        assert!(cred.may_access(BOB, STAFF, 0o070, Access::WRITE));
+    }
+
+    #[test]
+    fn root_access_needs_an_execute_bit_to_execute() {
+        let root = Credentials::root();
+        assert!(root.may_access(ALICE, USERS, 0o000, Access::READ | Access::WRITE));
+        assert!(!root.may_access(ALICE, USERS, 0o666, Access::EXECUTE));
+        assert!(root.may_access(ALICE, USERS, 0o100, Access::EXECUTE));
+    }
+
+    #[test]
+    fn sticky_directory_delete() {
+        let cred = alice();
+        // Non-sticky: anyone with write access may delete.
+        assert!(cred.may_delete(Uid::ROOT, 0o777, BOB));
+        // Sticky: only the entry's owner, the directory's owner, or root.
+        assert!(!cred.may_delete(Uid::ROOT, 0o1777, BOB));
+        assert!(cred.may_delete(Uid::ROOT, 0o1777, ALICE));
+        assert!(cred.may_delete(ALICE, 0o1777, BOB));
+        assert!(Credentials::root().may_delete(ALICE, 0o1777, BOB));
+    }
+
+    #[test]
+    fn signal_permission() {
+        let root = Credentials::root();
+        let bob = Credentials::new(BOB, USERS);
+        assert!(root.may_signal(&bob));
+        assert!(!alice().may_signal(&bob));
+        assert!(!bob.may_signal(&root));
+        assert!(alice().may_signal(&alice()));
+
+        // A set-user-ID program keeps its invoker as real user, so the
+        // invoker may still signal it.
+        let mut setuid = alice();
+        setuid.exec(BOB, USERS, 0o4755);
+        assert!(alice().may_signal(&setuid));
+        assert!(bob.may_signal(&setuid));
+    }
+
+    #[test]
+    fn reschedule_permission() {
+        let root = Credentials::root();
+        let bob = Credentials::new(BOB, USERS);
+        assert!(root.may_reschedule(&bob));
+        assert!(alice().may_reschedule(&alice()));
+        assert!(!alice().may_reschedule(&bob));
+        assert!(!bob.may_reschedule(&root));
+
+        // Unlike signals, only the effective user ID of the caller counts.
+        let mut setuid = alice();
+        setuid.exec(BOB, USERS, 0o4755);
+        assert!(!setuid.may_reschedule(&alice()));
+        assert!(setuid.may_reschedule(&bob));
+    }
+}
@@ -1,7 +1,7 @@
 //! Type-safe identifiers for kernel resources.
 //!
-//! These newtypes prevent accidental mixing of PIDs, file descriptors,
-//! CPU IDs, and IRQ vectors at compile time.
+//! These newtypes prevent accidental mixing of PIDs, user and group IDs,
+//! file descriptors, CPU IDs, and IRQ vectors at compile time.
 
 use core::fmt;
 
@@ -28,6 +28,58 @@ impl fmt::Display for Pid {
     }
 }
 
+/// User identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Uid(u32);
+
+impl Uid {
+    /// The superuser.
+    pub const ROOT: Self = Self(0);
+
+    /// Creates a new `Uid`.
+    pub const fn new(val: u32) -> Self {
+        Self(val)
+    }
+
+    /// Returns the raw `u32` value.
+    pub const fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for Uid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Group identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Gid(u32);
+
+impl Gid {
+    /// The superuser's group.
+    pub const ROOT: Self = Self(0);
+
+    /// Creates a new `Gid`.
+    pub const fn new(val: u32) -> Self {
+        Self(val)
+    }
+
+    /// Returns the raw `u32` value.
+    pub const fn as_u32(self) -> u32 {
+        self.0
+    }
+}
+
+impl fmt::Display for Gid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// CPU identifier.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
@@ -172,6 +224,14 @@ mod tests {
         assert!(Pid::new(1) < Pid::new(2));
     }
 
+    #[test]
+    fn uid_gid_roundtrip() {
+        assert_eq!(Uid::new(1000).as_u32(), 1000);
+        assert_eq!(Gid::new(100).as_u32(), 100);
+        assert_eq!(Uid::ROOT, Uid::new(0));
+        assert_eq!(Gid::ROOT, Gid::new(0));
+    }
+
     #[test]
     fn cpu_id_roundtrip() {
         let id = CpuId::new(7);
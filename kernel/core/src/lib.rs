@@ -20,11 +20,16 @@ pub mod alt_instr;
 pub mod cell;
 pub mod cpu_features;
 pub mod cpu_local;
+pub mod cputime;
+pub mod cred;
 pub mod id;
 pub mod mem;
 pub mod paging;
+pub mod ro_after_init;
+pub mod rt;
 pub mod safety;
 pub mod sched;
 pub mod static_assert;
 pub mod sync;
 pub mod task;
+pub mod timer;
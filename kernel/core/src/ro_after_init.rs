@@ -0,0 +1,38 @@
+//! Read-only-after-init kernel data.
+//!
+//! Statics declared with [`ro_after_init!`] are placed in the
+//! `.data.ro_after_init` linker section. The section is part of the
+//! kernel's writable data segment while it boots, so its statics can be
+//! initialized lazily or patched (alt-fn dispatch pointers, the IDT), and
+//! the kernel remaps it read-only at the end of boot init. Any write after
+//! that faults.
+//!
+//! Interior mutability is fine as long as every write happens before the
+//! remap: a `LazyLock` must have been forced by then, and an atomic must
+//! only be stored to during boot.
+
+/// Places statics in the `.data.ro_after_init` linker section.
+///
+/// On the host (unit tests) the statics are ordinary data.
+///
+/// # Example
+///
+/// ```ignore
+/// hadron_core::ro_after_init! {
+///     /// Interrupt descriptor table, built once during boot.
+///     static IDT: LazyLock<InterruptDescriptorTable> = LazyLock::new(build_idt);
+/// }
+/// ```
+#[macro_export]
+macro_rules! ro_after_init {
+    ($(
+        $(#[$meta:meta])*
+        $vis:vis static $name:ident : $ty:ty = $init:expr;
+    )*) => {
+        $(
+            $(#[$meta])*
+            #[cfg_attr(target_os = "none", unsafe(link_section = ".data.ro_after_init"))]
+            $vis static $name: $ty = $init;
+        )*
+    };
+}
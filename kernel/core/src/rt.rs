@@ -0,0 +1,974 @@
+//! Real-time and deadline scheduling classes.
+//!
+//! User tasks normally share their tier through the weighted-fair
+//! [`FairQueue`](crate::sched::FairQueue). A task may instead pick a
+//! [`SchedPolicy`] from one of two classes that run before any fair task:
+//!
+//! - **Deadline** tasks reserve `runtime` out of every `period` with a
+//!   relative `deadline` ([`DeadlineParams`]) and are picked earliest
+//!   deadline first ([`DeadlineQueue`]). A constant-bandwidth server
+//!   throttles a task that uses up its runtime until its next period, and
+//!   [`DeadlineAdmission`] refuses reservations that would overcommit the
+//!   CPUs.
+//! - **Real-time** tasks have a static priority from [`RT_PRIO_MIN`] to
+//!   [`RT_PRIO_MAX`] and are picked highest priority first, in FIFO order
+//!   within a priority ([`RtQueue`]). Round-robin tasks also rotate every
+//!   [`RR_TIMESLICE_NS`].
+//!
+//! Real-time tasks as a whole get at most [`RT_RUNTIME_NS`] of every
+//! [`RT_PERIOD_NS`] on a CPU while fair tasks are waiting ([`RtBandwidth`]),
+//! so a runaway real-time task cannot lock the other users out.
+
+extern crate alloc;
+
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::task::TaskId;
+
+/// Lowest real-time priority.
+pub const RT_PRIO_MIN: u8 = 1;
+
+/// Highest real-time priority.
+pub const RT_PRIO_MAX: u8 = 99;
+
+/// Time slice of a round-robin task, in nanoseconds.
+pub const RR_TIMESLICE_NS: u64 = 100_000_000;
+
+/// Length of the real-time throttling period, in nanoseconds.
+pub const RT_PERIOD_NS: u64 = 1_000_000_000;
+
+/// Real-time run time allowed per [`RT_PERIOD_NS`] while fair tasks wait.
+///
+/// Also bounds the deadline bandwidth that can be reserved per CPU.
+pub const RT_RUNTIME_NS: u64 = 950_000_000;
+
+/// Smallest runtime a deadline task may reserve, in nanoseconds.
+pub const DL_MIN_RUNTIME_NS: u64 = 1 << 10;
+
+/// Longest period a deadline task may use, in nanoseconds.
+pub const DL_MAX_PERIOD_NS: u64 = 4_000_000_000;
+
+/// Fractional bits of a bandwidth: `1 << BW_SHIFT` is one whole CPU.
+const BW_SHIFT: u32 = 20;
+
+/// Scheduling policy of a User task.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchedPolicy {
+    /// Weighted-fair scheduling by nice value (`SCHED_OTHER`).
+    #[default]
+    Normal,
+    /// Static priority; runs until it blocks (`SCHED_FIFO`).
+    Fifo(u8),
+    /// Static priority with a time slice (`SCHED_RR`).
+    RoundRobin(u8),
+    /// Earliest deadline first with a CPU reservation (`SCHED_DEADLINE`).
+    Deadline(DeadlineParams),
+}
+
+impl SchedPolicy {
+    /// Returns the static priority of a real-time policy.
+    pub fn rt_priority(self) -> Option<u8> {
+        match self {
+            Self::Fifo(priority) | Self::RoundRobin(priority) => Some(priority),
+            Self::Normal | Self::Deadline(_) => None,
+        }
+    }
+
+    /// Returns the reservation of a deadline policy.
+    pub fn deadline(self) -> Option<DeadlineParams> {
+        match self {
+            Self::Deadline(params) => Some(params),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the priority or reservation is in range.
+    pub fn is_valid(self) -> bool {
+        match self {
+            Self::Normal => true,
+            Self::Fifo(priority) | Self::RoundRobin(priority) => {
+                (RT_PRIO_MIN..=RT_PRIO_MAX).contains(&priority)
+            }
+            Self::Deadline(params) => params.is_valid(),
+        }
+    }
+}
+
+/// CPU reservation of a deadline task, in nanoseconds.
+///
+/// Each period the task may run for `runtime`, which it should get within
+/// `deadline` of the period's start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineParams {
+    /// Run time per period.
+    pub runtime_ns: u64,
+    /// Relative deadline.
+    pub deadline_ns: u64,
+    /// Length of a period.
+    pub period_ns: u64,
+}
+
+impl DeadlineParams {
+    /// Returns `true` if `runtime <= deadline <= period`, the runtime is at
+    /// least [`DL_MIN_RUNTIME_NS`] and the period at most
+    /// [`DL_MAX_PERIOD_NS`].
+    pub fn is_valid(&self) -> bool {
+        self.runtime_ns >= DL_MIN_RUNTIME_NS
+            && self.runtime_ns <= self.deadline_ns
+            && self.deadline_ns <= self.period_ns
+            && self.period_ns <= DL_MAX_PERIOD_NS
+    }
+
+    /// Returns the share of one CPU the reservation takes, with
+    /// `1 << 20` meaning the whole CPU.
+    pub fn bandwidth(&self) -> u64 {
+        to_bandwidth(self.runtime_ns, self.period_ns)
+    }
+}
+
+/// Returns `runtime / period` in `BW_SHIFT` fixed point.
+fn to_bandwidth(runtime: u64, period: u64) -> u64 {
+    if period == 0 {
+        return 0;
+    }
+    let bw = (u128::from(runtime) << BW_SHIFT) / u128::from(period);
+    u64::try_from(bw).unwrap_or(u64::MAX)
+}
+
+/// System-wide admission control for deadline reservations.
+///
+/// The reservations of all deadline tasks together may not exceed
+/// [`RT_RUNTIME_NS`]` / `[`RT_PERIOD_NS`] of every online CPU, which leaves
+/// the rest for real-time, fair and kernel work.
+#[derive(Debug, Default)]
+pub struct DeadlineAdmission {
+    /// Sum of the admitted bandwidths.
+    allocated: u64,
+}
+
+impl DeadlineAdmission {
+    /// Creates an empty admission state.
+    pub const fn new() -> Self {
+        Self { allocated: 0 }
+    }
+
+    /// Returns the total admitted bandwidth, with `1 << 20` per CPU.
+    pub fn allocated(&self) -> u64 {
+        self.allocated
+    }
+
+    /// Replaces reservation `old` by `new` on a system with `cpus` CPUs.
+    ///
+    /// Either may be `None` to admit a new task or release a finished one.
+    /// Returns `false` and changes nothing if `new` does not fit. Releasing
+    /// always succeeds.
+    pub fn change(
+        &mut self,
+        old: Option<DeadlineParams>,
+        new: Option<DeadlineParams>,
+        cpus: u32,
+    ) -> bool {
+        let old_bw = old.map_or(0, |p| p.bandwidth());
+        let new_bw = new.map_or(0, |p| p.bandwidth());
+        let rest = self.allocated.saturating_sub(old_bw);
+        let capacity = to_bandwidth(RT_RUNTIME_NS, RT_PERIOD_NS) * u64::from(cpus.max(1));
+        if new_bw > old_bw && rest + new_bw > capacity {
+            return false;
+        }
+        self.allocated = rest + new_bw;
+        true
+    }
+}
+
+/// Real-time run time used on one CPU in the current period.
+#[derive(Debug, Default)]
+pub struct RtBandwidth {
+    /// Start of the current period.
+    period_start: u64,
+    /// Real-time nanoseconds charged in the current period.
+    used: u64,
+}
+
+impl RtBandwidth {
+    /// Creates an unthrottled state.
+    pub const fn new() -> Self {
+        Self {
+            period_start: 0,
+            used: 0,
+        }
+    }
+
+    /// Charges `ns` of real-time run time.
+    pub fn charge(&mut self, ns: u64) {
+        self.used = self.used.saturating_add(ns);
+    }
+
+    /// Starts a new period if the current one ended before `now`.
+    pub fn update(&mut self, now: u64) {
+        if now.saturating_sub(self.period_start) >= RT_PERIOD_NS {
+            self.period_start = now;
+            self.used = 0;
+        }
+    }
+
+    /// Returns `true` if real-time tasks used up this period's run time.
+    pub fn is_throttled(&self) -> bool {
+        self.used >= RT_RUNTIME_NS
+    }
+}
+
+/// Real-time state of one task.
+#[derive(Debug, Clone, Copy)]
+struct RtEntity {
+    priority: u8,
+    round_robin: bool,
+    /// Run time left in the current slice, for round-robin tasks.
+    slice_left: u64,
+    /// Whether the task is in `levels`.
+    queued: bool,
+}
+
+/// Ready queue of real-time tasks: one FIFO per static priority.
+///
+/// Like [`FairQueue`](crate::sched::FairQueue), tracks every real-time
+/// task this executor owns, runnable or not.
+#[derive(Default)]
+pub struct RtQueue {
+    entities: BTreeMap<TaskId, RtEntity>,
+    /// Runnable tasks by priority.
+    levels: BTreeMap<u8, VecDeque<TaskId>>,
+    /// Number of runnable tasks.
+    len: usize,
+}
+
+impl RtQueue {
+    /// Creates an empty queue.
+    pub const fn new() -> Self {
+        Self {
+            entities: BTreeMap::new(),
+            levels: BTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of runnable tasks.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no task is runnable.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if `id` is a real-time task of this queue.
+    pub fn contains(&self, id: TaskId) -> bool {
+        self.entities.contains_key(&id)
+    }
+
+    /// Returns `true` if `id` is runnable.
+    pub fn is_queued(&self, id: TaskId) -> bool {
+        self.entities.get(&id).is_some_and(|e| e.queued)
+    }
+
+    /// Starts tracking `id` at `priority`, or changes its priority and
+    /// policy. A runnable task that changes priority goes to the back of
+    /// its new priority.
+    pub fn set(&mut self, id: TaskId, priority: u8, round_robin: bool) {
+        let queued = self.is_queued(id);
+        if queued {
+            self.unlink(id);
+        }
+        let entity = self.entities.entry(id).or_insert(RtEntity {
+            priority,
+            round_robin,
+            slice_left: RR_TIMESLICE_NS,
+            queued: false,
+        });
+        entity.priority = priority;
+        entity.round_robin = round_robin;
+        if queued {
+            self.enqueue(id);
+        }
+    }
+
+    /// Makes `id` runnable at the back of its priority. Does nothing if the
+    /// task is not tracked or already runnable.
+    pub fn enqueue(&mut self, id: TaskId) {
+        let Some(entity) = self.entities.get_mut(&id) else {
+            return;
+        };
+        if entity.queued {
+            return;
+        }
+        entity.queued = true;
+        self.levels
+            .entry(entity.priority)
+            .or_default()
+            .push_back(id);
+        self.len += 1;
+    }
+
+    /// Takes the first task of the highest priority.
+    pub fn pop(&mut self) -> Option<TaskId> {
+        let mut level = self.levels.last_entry()?;
+        let id = level.get_mut().pop_front()?;
+        if level.get().is_empty() {
+            level.remove();
+        }
+        self.mark_dequeued(id);
+        Some(id)
+    }
+
+    /// Takes the last task of the lowest priority for which `allowed`
+    /// holds, for work stealing. The task stays tracked.
+    pub fn steal_where(&mut self, mut allowed: impl FnMut(TaskId) -> bool) -> Option<TaskId> {
+        let (priority, pos) = self.levels.iter().find_map(|(&priority, queue)| {
+            queue
+                .iter()
+                .rposition(|id| allowed(*id))
+                .map(|pos| (priority, pos))
+        })?;
+        let queue = self.levels.get_mut(&priority)?;
+        let id = queue.remove(pos)?;
+        if queue.is_empty() {
+            self.levels.remove(&priority);
+        }
+        self.mark_dequeued(id);
+        Some(id)
+    }
+
+    /// Charges `ns` of run time to `id`.
+    ///
+    /// If the task made itself runnable again during the poll, it was
+    /// preempted and keeps its place at the head of its priority. A
+    /// round-robin task whose slice ran out goes to the back instead, with
+    /// a new slice.
+    pub fn charge(&mut self, id: TaskId, ns: u64) {
+        let Some(entity) = self.entities.get_mut(&id) else {
+            return;
+        };
+        let mut rotate = false;
+        if entity.round_robin {
+            entity.slice_left = entity.slice_left.saturating_sub(ns);
+            if entity.slice_left == 0 {
+                entity.slice_left = RR_TIMESLICE_NS;
+                rotate = true;
+            }
+        }
+        if !entity.queued {
+            return;
+        }
+        let priority = entity.priority;
+        let Some(queue) = self.levels.get_mut(&priority) else {
+            return;
+        };
+        if let Some(pos) = queue.iter().position(|&queued| queued == id) {
+            queue.remove(pos);
+            if rotate {
+                queue.push_back(id);
+            } else {
+                queue.push_front(id);
+            }
+        }
+    }
+
+    /// Stops tracking `id`. Returns `false` if it was not tracked.
+    pub fn remove(&mut self, id: TaskId) -> bool {
+        if self.is_queued(id) {
+            self.unlink(id);
+        }
+        self.entities.remove(&id).is_some()
+    }
+
+    /// Removes runnable task `id` from its priority's FIFO.
+    fn unlink(&mut self, id: TaskId) {
+        let Some(priority) = self.entities.get(&id).map(|e| e.priority) else {
+            return;
+        };
+        if let Some(queue) = self.levels.get_mut(&priority) {
+            queue.retain(|&queued| queued != id);
+            if queue.is_empty() {
+                self.levels.remove(&priority);
+            }
+        }
+        self.mark_dequeued(id);
+    }
+
+    fn mark_dequeued(&mut self, id: TaskId) {
+        if let Some(entity) = self.entities.get_mut(&id) {
+            entity.queued = false;
+            self.len -= 1;
+        }
+    }
+}
+
+/// Budget a deadline task takes along when it moves to another CPU.
+///
+/// Deadlines are absolute times on the executor clock, which all CPUs
+/// share, so they stay valid across CPUs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineBudget {
+    /// Absolute deadline of the current period.
+    pub deadline: u64,
+    /// Run time left in the current period.
+    pub remaining: u64,
+}
+
+/// Deadline state of one task.
+#[derive(Debug, Clone, Copy)]
+struct DlEntity {
+    params: DeadlineParams,
+    budget: DeadlineBudget,
+    /// When a throttled task gets its next budget.
+    release: Option<u64>,
+    /// Whether the task is runnable (in `timeline` unless throttled).
+    queued: bool,
+    /// Whether the task was popped and not yet charged.
+    running: bool,
+}
+
+impl DlEntity {
+    /// Gives the task a full budget and a deadline `deadline_ns` after
+    /// `start`.
+    fn replenish(&mut self, start: u64) {
+        self.budget = DeadlineBudget {
+            deadline: start.saturating_add(self.params.deadline_ns),
+            remaining: self.params.runtime_ns,
+        };
+        self.release = None;
+    }
+
+    /// Returns `true` if running the remaining budget before the deadline
+    /// would exceed the reservation's density, `runtime / deadline`.
+    fn overflows(&self, now: u64) -> bool {
+        let window = self.budget.deadline.saturating_sub(now);
+        u128::from(self.budget.remaining) * u128::from(self.params.deadline_ns)
+            > u128::from(self.params.runtime_ns) * u128::from(window)
+    }
+
+    /// Start of the next period, when a throttled task gets a new budget.
+    fn next_period(&self) -> u64 {
+        self.budget
+            .deadline
+            .saturating_sub(self.params.deadline_ns)
+            .saturating_add(self.params.period_ns)
+    }
+}
+
+/// Ready queue of deadline tasks, earliest deadline first.
+///
+/// Implements a constant-bandwidth server per task: a task that wakes
+/// keeps its budget only if that does not exceed its reserved density,
+/// and a task that uses up its budget is throttled until
+/// [`release`](Self::release) reaches its next period.
+#[derive(Default)]
+pub struct DeadlineQueue {
+    entities: BTreeMap<TaskId, DlEntity>,
+    /// Runnable, unthrottled tasks by absolute deadline.
+    timeline: BTreeSet<(u64, TaskId)>,
+    /// Throttled tasks, runnable or not, by release time.
+    throttled: BTreeSet<(u64, TaskId)>,
+}
+
+impl DeadlineQueue {
+    /// Creates an empty queue.
+    pub const fn new() -> Self {
+        Self {
+            entities: BTreeMap::new(),
+            timeline: BTreeSet::new(),
+            throttled: BTreeSet::new(),
+        }
+    }
+
+    /// Returns the number of runnable, unthrottled tasks.
+    pub fn len(&self) -> usize {
+        self.timeline.len()
+    }
+
+    /// Returns `true` if no unthrottled task is runnable.
+    pub fn is_empty(&self) -> bool {
+        self.timeline.is_empty()
+    }
+
+    /// Returns `true` if `id` is a deadline task of this queue.
+    pub fn contains(&self, id: TaskId) -> bool {
+        self.entities.contains_key(&id)
+    }
+
+    /// Returns `true` if `id` is runnable, throttled or not.
+    pub fn is_queued(&self, id: TaskId) -> bool {
+        self.entities.get(&id).is_some_and(|e| e.queued)
+    }
+
+    /// Returns the budget of `id`, if this queue tracks it.
+    pub fn budget(&self, id: TaskId) -> Option<DeadlineBudget> {
+        self.entities.get(&id).map(|e| e.budget)
+    }
+
+    /// Returns the earliest time a throttled task gets a new budget.
+    pub fn next_release(&self) -> Option<u64> {
+        self.throttled.first().map(|&(release, _)| release)
+    }
+
+    /// Starts tracking `id` with `params`, or changes its reservation.
+    ///
+    /// The task gets a fresh budget the next time it becomes runnable; a
+    /// runnable task gets one at once.
+    pub fn set(&mut self, id: TaskId, params: DeadlineParams, now: u64) {
+        let queued = self.is_queued(id);
+        self.remove(id);
+        self.entities.insert(
+            id,
+            DlEntity {
+                params,
+                budget: DeadlineBudget {
+                    deadline: 0,
+                    remaining: 0,
+                },
+                release: None,
+                queued: false,
+                running: false,
+            },
+        );
+        if queued {
+            self.enqueue(id, now);
+        }
+    }
+
+    /// Makes `id` runnable at `now`.
+    ///
+    /// A waking task gets a new budget and deadline if its deadline has
+    /// passed or its remaining budget would overrun the reservation. A task
+    /// that is requeued while it runs, because it was preempted, keeps its
+    /// budget; the poll is charged afterwards. Does nothing if the task is
+    /// not tracked or already runnable.
+    pub fn enqueue(&mut self, id: TaskId, now: u64) {
+        let Some(entity) = self.entities.get_mut(&id) else {
+            return;
+        };
+        if entity.queued {
+            return;
+        }
+        entity.queued = true;
+        if entity.release.is_some() {
+            return;
+        }
+        if !entity.running && (now >= entity.budget.deadline || entity.overflows(now)) {
+            entity.replenish(now);
+        }
+        self.timeline.insert((entity.budget.deadline, id));
+    }
+
+    /// Makes `id`, set up with [`set`](Self::set), runnable with the budget
+    /// it had on another CPU.
+    ///
+    /// A task whose budget is used up stays throttled until its next
+    /// period.
+    pub fn enqueue_migrated(&mut self, id: TaskId, budget: DeadlineBudget, now: u64) {
+        let Some(params) = self.entities.get(&id).map(|e| e.params) else {
+            return;
+        };
+        self.set(id, params, now);
+        let Some(entity) = self.entities.get_mut(&id) else {
+            return;
+        };
+        entity.budget = budget;
+        if budget.remaining == 0 {
+            let release = entity.next_period();
+            entity.release = Some(release);
+            entity.queued = true;
+            self.throttled.insert((release, id));
+        } else {
+            self.enqueue(id, now);
+        }
+    }
+
+    /// Takes the runnable task with the earliest deadline.
+    pub fn pop(&mut self) -> Option<TaskId> {
+        let (_, id) = self.timeline.pop_first()?;
+        if let Some(entity) = self.entities.get_mut(&id) {
+            entity.queued = false;
+            entity.running = true;
+        }
+        Some(id)
+    }
+
+    /// Takes the runnable task with the latest deadline for which `allowed`
+    /// holds, for work stealing. The task stays tracked.
+    pub fn steal_where(&mut self, mut allowed: impl FnMut(TaskId) -> bool) -> Option<TaskId> {
+        let key = *self.timeline.iter().rev().find(|(_, id)| allowed(*id))?;
+        self.timeline.remove(&key);
+        if let Some(entity) = self.entities.get_mut(&key.1) {
+            entity.queued = false;
+        }
+        Some(key.1)
+    }
+
+    /// Charges `ns` of run time to `id` after a poll, throttling it until
+    /// its next period if that uses up its budget.
+    pub fn charge(&mut self, id: TaskId, ns: u64) {
+        let Some(entity) = self.entities.get_mut(&id) else {
+            return;
+        };
+        entity.running = false;
+        if entity.release.is_some() {
+            return;
+        }
+        entity.budget.remaining = entity.budget.remaining.saturating_sub(ns);
+        if entity.budget.remaining > 0 {
+            return;
+        }
+        let release = entity.next_period();
+        entity.release = Some(release);
+        if entity.queued {
+            self.timeline.remove(&(entity.budget.deadline, id));
+        }
+        self.throttled.insert((release, id));
+    }
+
+    /// Replenishes every throttled task whose next period started by `now`.
+    pub fn release(&mut self, now: u64) {
+        while let Some(&(release, id)) = self.throttled.first() {
+            if release > now {
+                break;
+            }
+            self.throttled.pop_first();
+            let Some(entity) = self.entities.get_mut(&id) else {
+                continue;
+            };
+            entity.replenish(release);
+            // A task released late must not start with a deadline that has
+            // already passed.
+            if entity.budget.deadline <= now {
+                entity.replenish(now);
+            }
+            if entity.queued {
+                self.timeline.insert((entity.budget.deadline, id));
+            }
+        }
+    }
+
+    /// Stops tracking `id` and returns its budget, or `None` if it was not
+    /// tracked.
+    pub fn remove(&mut self, id: TaskId) -> Option<DeadlineBudget> {
+        let entity = self.entities.remove(&id)?;
+        if let Some(release) = entity.release {
+            self.throttled.remove(&(release, id));
+        } else if entity.queued {
+            self.timeline.remove(&(entity.budget.deadline, id));
+        }
+        Some(entity.budget)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: u64 = 1_000_000;
+
+    fn params(runtime_ms: u64, deadline_ms: u64, period_ms: u64) -> DeadlineParams {
+        DeadlineParams {
+            runtime_ns: runtime_ms * MS,
+            deadline_ns: deadline_ms * MS,
+            period_ns: period_ms * MS,
+        }
+    }
+
+    #[test]
+    fn policy_validation() {
+        assert!(SchedPolicy::Normal.is_valid());
+        assert!(SchedPolicy::Fifo(1).is_valid());
+        assert!(SchedPolicy::RoundRobin(99).is_valid());
+        assert!(!SchedPolicy::Fifo(0).is_valid());
+        assert!(!SchedPolicy::RoundRobin(100).is_valid());
+        assert!(SchedPolicy::Deadline(params(1, 5, 10)).is_valid());
+        assert!(!SchedPolicy::Deadline(params(6, 5, 10)).is_valid());
+        assert!(!SchedPolicy::Deadline(params(1, 11, 10)).is_valid());
+        assert!(!SchedPolicy::Deadline(params(0, 5, 10)).is_valid());
+        assert!(!SchedPolicy::Deadline(params(1, 5, 5_000)).is_valid());
+    }
+
+    #[test]
+    fn bandwidth_is_runtime_over_period() {
+        assert_eq!(params(1, 2, 2).bandwidth(), 1 << (BW_SHIFT - 1));
+        assert_eq!(params(10, 10, 10).bandwidth(), 1 << BW_SHIFT);
+    }
+
+    #[test]
+    fn admission_limits_total_bandwidth() {
+        let mut admission = DeadlineAdmission::new();
+        let half = Some(params(50, 100, 100));
+        let tenth = Some(params(10, 100, 100));
+        assert!(admission.change(None, half, 1));
+        // 50% + 50% > 95% of one CPU.
+        assert!(!admission.change(None, half, 1));
+        // Two CPUs have room for three halves, but not four.
+        assert!(admission.change(None, half, 2));
+        assert!(admission.change(None, half, 2));
+        assert!(!admission.change(None, half, 2));
+        // Shrinking a reservation always fits, and makes room.
+        assert!(admission.change(half, tenth, 2));
+        assert!(admission.change(None, tenth, 2));
+        // Releasing gives the bandwidth back.
+        for old in [half, half, tenth, tenth] {
+            assert!(admission.change(old, None, 2));
+        }
+        assert_eq!(admission.allocated(), 0);
+    }
+
+    #[test]
+    fn rt_bandwidth_throttles_and_resets() {
+        let mut bw = RtBandwidth::new();
+        bw.update(0);
+        bw.charge(RT_RUNTIME_NS - 1);
+        assert!(!bw.is_throttled());
+        bw.charge(1);
+        assert!(bw.is_throttled());
+        bw.update(RT_PERIOD_NS - 1);
+        assert!(bw.is_throttled());
+        bw.update(RT_PERIOD_NS);
+        assert!(!bw.is_throttled());
+    }
+
+    #[test]
+    fn rt_pops_highest_priority_fifo() {
+        let mut rt = RtQueue::new();
+        rt.set(TaskId(1), 10, false);
+        rt.set(TaskId(2), 50, false);
+        rt.set(TaskId(3), 10, false);
+        for id in [1, 2, 3] {
+            rt.enqueue(TaskId(id));
+        }
+        assert_eq!(rt.len(), 3);
+        assert_eq!(rt.pop(), Some(TaskId(2)));
+        assert_eq!(rt.pop(), Some(TaskId(1)));
+        assert_eq!(rt.pop(), Some(TaskId(3)));
+        assert_eq!(rt.pop(), None);
+        assert!(rt.is_empty());
+    }
+
+    #[test]
+    fn rt_enqueue_ignores_untracked_and_queued() {
+        let mut rt = RtQueue::new();
+        rt.enqueue(TaskId(1));
+        assert!(rt.is_empty());
+        rt.set(TaskId(1), 10, false);
+        rt.enqueue(TaskId(1));
+        rt.enqueue(TaskId(1));
+        assert_eq!(rt.len(), 1);
+    }
+
+    #[test]
+    fn rt_preempted_fifo_keeps_head() {
+        let mut rt = RtQueue::new();
+        rt.set(TaskId(1), 10, false);
+        rt.set(TaskId(2), 10, false);
+        rt.enqueue(TaskId(1));
+        rt.enqueue(TaskId(2));
+        assert_eq!(rt.pop(), Some(TaskId(1)));
+        // Preempted: requeued during its poll, then charged.
+        rt.enqueue(TaskId(1));
+        rt.charge(TaskId(1), RR_TIMESLICE_NS * 10);
+        assert_eq!(rt.pop(), Some(TaskId(1)));
+    }
+
+    #[test]
+    fn rt_round_robin_rotates_after_slice() {
+        let mut rt = RtQueue::new();
+        rt.set(TaskId(1), 10, true);
+        rt.set(TaskId(2), 10, true);
+        rt.enqueue(TaskId(1));
+        rt.enqueue(TaskId(2));
+
+        assert_eq!(rt.pop(), Some(TaskId(1)));
+        rt.enqueue(TaskId(1));
+        rt.charge(TaskId(1), RR_TIMESLICE_NS / 2);
+        // Half a slice left: still first.
+        assert_eq!(rt.pop(), Some(TaskId(1)));
+        rt.enqueue(TaskId(1));
+        rt.charge(TaskId(1), RR_TIMESLICE_NS / 2);
+        // Slice used up: the other task's turn.
+        assert_eq!(rt.pop(), Some(TaskId(2)));
+        assert_eq!(rt.pop(), Some(TaskId(1)));
+    }
+
+    #[test]
+    fn rt_priority_change_moves_task() {
+        let mut rt = RtQueue::new();
+        rt.set(TaskId(1), 10, false);
+        rt.set(TaskId(2), 20, false);
+        rt.enqueue(TaskId(1));
+        rt.enqueue(TaskId(2));
+        rt.set(TaskId(1), 30, false);
+        assert_eq!(rt.len(), 2);
+        assert_eq!(rt.pop(), Some(TaskId(1)));
+        assert_eq!(rt.pop(), Some(TaskId(2)));
+    }
+
+    #[test]
+    fn rt_steal_takes_lowest_priority() {
+        let mut rt = RtQueue::new();
+        rt.set(TaskId(1), 50, false);
+        rt.set(TaskId(2), 10, false);
+        rt.set(TaskId(3), 10, false);
+        for id in [1, 2, 3] {
+            rt.enqueue(TaskId(id));
+        }
+        assert_eq!(rt.steal_where(|id| id != TaskId(3)), Some(TaskId(2)));
+        assert_eq!(rt.len(), 2);
+        assert!(rt.contains(TaskId(2)));
+        assert!(!rt.is_queued(TaskId(2)));
+    }
+
+    #[test]
+    fn rt_remove_untracks() {
+        let mut rt = RtQueue::new();
+        rt.set(TaskId(1), 10, false);
+        rt.enqueue(TaskId(1));
+        assert!(rt.remove(TaskId(1)));
+        assert!(!rt.remove(TaskId(1)));
+        assert!(rt.is_empty());
+        assert_eq!(rt.pop(), None);
+    }
+
+    #[test]
+    fn dl_pops_earliest_deadline() {
+        let mut dl = DeadlineQueue::new();
+        dl.set(TaskId(1), params(1, 50, 100), 0);
+        dl.set(TaskId(2), params(1, 10, 100), 0);
+        dl.enqueue(TaskId(1), 0);
+        dl.enqueue(TaskId(2), 0);
+        assert_eq!(dl.pop(), Some(TaskId(2)));
+        assert_eq!(dl.pop(), Some(TaskId(1)));
+        assert_eq!(dl.pop(), None);
+    }
+
+    #[test]
+    fn dl_throttles_until_next_period() {
+        let mut dl = DeadlineQueue::new();
+        dl.set(TaskId(1), params(10, 50, 100), 0);
+        dl.enqueue(TaskId(1), 0);
+        assert_eq!(dl.pop(), Some(TaskId(1)));
+        dl.enqueue(TaskId(1), 5 * MS);
+        dl.charge(TaskId(1), 10 * MS);
+
+        // Out of budget: runnable but throttled.
+        assert!(dl.is_queued(TaskId(1)));
+        assert!(dl.is_empty());
+        assert_eq!(dl.next_release(), Some(100 * MS));
+
+        dl.release(99 * MS);
+        assert!(dl.is_empty());
+        dl.release(100 * MS);
+        assert_eq!(
+            dl.budget(TaskId(1)),
+            Some(DeadlineBudget {
+                deadline: 150 * MS,
+                remaining: 10 * MS,
+            })
+        );
+        assert_eq!(dl.pop(), Some(TaskId(1)));
+    }
+
+    #[test]
+    fn dl_preempted_task_keeps_deadline() {
+        let mut dl = DeadlineQueue::new();
+        dl.set(TaskId(1), params(10, 20, 100), 0);
+        dl.enqueue(TaskId(1), 0);
+        assert_eq!(dl.pop(), Some(TaskId(1)));
+        // Requeued by its own preemption 4ms in, then charged.
+        dl.enqueue(TaskId(1), 4 * MS);
+        dl.charge(TaskId(1), 4 * MS);
+        assert_eq!(
+            dl.budget(TaskId(1)),
+            Some(DeadlineBudget {
+                deadline: 20 * MS,
+                remaining: 6 * MS,
+            })
+        );
+        assert_eq!(dl.pop(), Some(TaskId(1)));
+    }
+
+    #[test]
+    fn dl_throttled_sleeper_released_without_queueing() {
+        let mut dl = DeadlineQueue::new();
+        dl.set(TaskId(1), params(10, 100, 100), 0);
+        dl.enqueue(TaskId(1), 0);
+        dl.pop();
+        dl.charge(TaskId(1), 10 * MS);
+        dl.release(100 * MS);
+        assert!(dl.is_empty());
+        assert_eq!(dl.next_release(), None);
+        dl.enqueue(TaskId(1), 100 * MS);
+        assert_eq!(dl.pop(), Some(TaskId(1)));
+    }
+
+    #[test]
+    fn dl_wakeup_keeps_budget_within_bandwidth() {
+        let mut dl = DeadlineQueue::new();
+        dl.set(TaskId(1), params(10, 100, 100), 0);
+        dl.enqueue(TaskId(1), 0);
+        dl.pop();
+        dl.charge(TaskId(1), 2 * MS);
+
+        // 8ms left over 90ms is within 10%: keep the deadline.
+        dl.enqueue(TaskId(1), 10 * MS);
+        assert_eq!(dl.budget(TaskId(1)).unwrap().deadline, 100 * MS);
+        dl.pop();
+        // Blocks at once.
+        dl.charge(TaskId(1), 0);
+
+        // 8ms left over 20ms exceeds 10%: start a new period.
+        dl.enqueue(TaskId(1), 80 * MS);
+        assert_eq!(
+            dl.budget(TaskId(1)),
+            Some(DeadlineBudget {
+                deadline: 180 * MS,
+                remaining: 10 * MS,
+            })
+        );
+    }
+
+    #[test]
+    fn dl_migration_keeps_budget() {
+        let mut a = DeadlineQueue::new();
+        let p = params(10, 100, 100);
+        a.set(TaskId(1), p, 0);
+        a.enqueue(TaskId(1), 0);
+        a.pop();
+        a.charge(TaskId(1), 4 * MS);
+        let budget = a.remove(TaskId(1)).unwrap();
+        assert_eq!(budget.remaining, 6 * MS);
+
+        let mut b = DeadlineQueue::new();
+        b.set(TaskId(1), p, 5 * MS);
+        b.enqueue_migrated(TaskId(1), budget, 5 * MS);
+        assert_eq!(b.budget(TaskId(1)), Some(budget));
+        assert_eq!(b.pop(), Some(TaskId(1)));
+
+        // A throttled task stays throttled on its new CPU.
+        let spent = DeadlineBudget {
+            deadline: 100 * MS,
+            remaining: 0,
+        };
+        let mut c = DeadlineQueue::new();
+        c.set(TaskId(1), p, 5 * MS);
+        c.enqueue_migrated(TaskId(1), spent, 5 * MS);
+        assert!(c.is_empty());
+        assert_eq!(c.next_release(), Some(100 * MS));
+    }
+
+    #[test]
+    fn dl_remove_clears_throttle() {
+        let mut dl = DeadlineQueue::new();
+        dl.set(TaskId(1), params(1, 10, 10), 0);
+        dl.enqueue(TaskId(1), 0);
+        dl.charge(TaskId(1), MS);
+        assert!(dl.next_release().is_some());
+        assert!(dl.remove(TaskId(1)).is_some());
+        assert_eq!(dl.next_release(), None);
+        assert!(!dl.contains(TaskId(1)));
+    }
+}
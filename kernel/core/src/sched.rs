@@ -2,24 +2,262 @@
 //!
 //! Contains priority-aware ready queues and related scheduling logic.
 //! These types are host-testable and used by the kernel's async executor.
+//!
+//! The Critical, Normal and Background tiers are FIFO queues. The User tier
+//! is a [`FairQueue`]: user processes are picked by smallest virtual runtime,
+//! which advances more slowly for heavier (lower nice) tasks, so CPU time is
+//! shared in proportion to [`nice_to_weight`]. User tasks with a real-time
+//! or deadline [`SchedPolicy`] are queued in the classes of [`crate::rt`]
+//! instead and run before the fair ones.
 
 extern crate alloc;
 
-use alloc::collections::VecDeque;
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
 
+use crate::rt::{DeadlineBudget, DeadlineQueue, RtBandwidth, RtQueue, SchedPolicy};
 use crate::task::{Priority, TaskId};
 
-/// How many consecutive Normal polls before forcing one Background poll.
+/// How many consecutive Normal or User polls before forcing one Background
+/// poll.
 const BACKGROUND_STARVATION_LIMIT: u64 = 100;
 
+/// Highest priority nice value.
+pub const NICE_MIN: i32 = -20;
+
+/// Lowest priority nice value.
+pub const NICE_MAX: i32 = 19;
+
+/// Weight of a nice-0 task. Virtual runtime advances at wall-clock speed
+/// for a task of this weight.
+pub const NICE_0_WEIGHT: u32 = 1024;
+
+/// Task weight for each nice value from [`NICE_MIN`] to [`NICE_MAX`].
+///
+/// Each step is about 1.25x, so one nice level moves roughly 10% of the CPU
+/// between two competing tasks (the same table as Linux CFS).
+const NICE_TO_WEIGHT: [u32; 40] = [
+    88761, 71755, 56483, 46273, 36291, // -20 .. -16
+    29154, 23254, 18705, 14949, 11916, // -15 .. -11
+    9548, 7620, 6100, 4904, 3906, // -10 .. -6
+    3121, 2501, 1991, 1586, 1277, // -5 .. -1
+    1024, 820, 655, 526, 423, // 0 .. 4
+    335, 272, 215, 172, 137, // 5 .. 9
+    110, 87, 70, 56, 45, // 10 .. 14
+    36, 29, 23, 18, 15, // 15 .. 19
+];
+
+/// Returns the scheduling weight for `nice`, clamped to
+/// [`NICE_MIN`]..=[`NICE_MAX`].
+pub const fn nice_to_weight(nice: i32) -> u32 {
+    let nice = if nice < NICE_MIN {
+        NICE_MIN
+    } else if nice > NICE_MAX {
+        NICE_MAX
+    } else {
+        nice
+    };
+    NICE_TO_WEIGHT[(nice - NICE_MIN) as usize]
+}
+
+/// How far behind `min_vruntime` a waking task may be placed, in
+/// nanoseconds of virtual runtime.
+///
+/// A task that slept gets a small head start over the tasks that kept
+/// running, but cannot bank its sleep time to monopolise the CPU later.
+const SLEEPER_CREDIT_NS: u64 = 3_000_000;
+
+/// Fair-scheduling state of one task.
+#[derive(Debug, Clone, Copy)]
+struct FairEntity {
+    /// Weighted nanoseconds this task has run.
+    vruntime: u64,
+    /// Whether the task is in the timeline.
+    queued: bool,
+}
+
+/// Ready queue ordered by virtual runtime.
+///
+/// Tracks every task of the tier that this executor owns, not just the
+/// runnable ones, so a task keeps its virtual runtime while it sleeps.
+/// The executor calls [`charge`](Self::charge) after each poll and
+/// [`remove`](Self::remove) when the task finishes or moves to another CPU.
+pub struct FairQueue {
+    entities: BTreeMap<TaskId, FairEntity>,
+    /// Runnable tasks, smallest virtual runtime first.
+    timeline: BTreeSet<(u64, TaskId)>,
+    /// Monotonic lower bound of the virtual runtimes in the queue. New and
+    /// migrated tasks are placed relative to it.
+    min_vruntime: u64,
+}
+
+impl FairQueue {
+    /// Creates an empty queue.
+    pub const fn new() -> Self {
+        Self {
+            entities: BTreeMap::new(),
+            timeline: BTreeSet::new(),
+            min_vruntime: 0,
+        }
+    }
+
+    /// Returns the number of runnable tasks.
+    pub fn len(&self) -> usize {
+        self.timeline.len()
+    }
+
+    /// Returns `true` if no task is runnable.
+    pub fn is_empty(&self) -> bool {
+        self.timeline.is_empty()
+    }
+
+    /// Returns the queue's minimum virtual runtime.
+    pub fn min_vruntime(&self) -> u64 {
+        self.min_vruntime
+    }
+
+    /// Returns `true` if `id` is runnable.
+    pub fn is_queued(&self, id: TaskId) -> bool {
+        self.entities.get(&id).is_some_and(|e| e.queued)
+    }
+
+    /// Returns the virtual runtime of `id`, if this queue tracks it.
+    pub fn vruntime(&self, id: TaskId) -> Option<u64> {
+        self.entities.get(&id).map(|e| e.vruntime)
+    }
+
+    /// Makes `id` runnable.
+    ///
+    /// A new task starts at `min_vruntime`; a waking task is moved up to at
+    /// most [`SLEEPER_CREDIT_NS`] behind it. Does nothing if the task is
+    /// already runnable.
+    pub fn enqueue(&mut self, id: TaskId) {
+        let floor = self.min_vruntime.saturating_sub(SLEEPER_CREDIT_NS);
+        let entity = self.entities.entry(id).or_insert(FairEntity {
+            vruntime: self.min_vruntime,
+            queued: false,
+        });
+        if entity.queued {
+            return;
+        }
+        entity.vruntime = entity.vruntime.max(floor);
+        entity.queued = true;
+        self.timeline.insert((entity.vruntime, id));
+    }
+
+    /// Makes `id` runnable with `lag` nanoseconds of virtual runtime ahead
+    /// of this queue's `min_vruntime`, as returned by [`remove`](Self::remove)
+    /// on the queue it came from.
+    pub fn enqueue_migrated(&mut self, id: TaskId, lag: u64) {
+        if let Some(old) = self.entities.remove(&id)
+            && old.queued
+        {
+            self.timeline.remove(&(old.vruntime, id));
+        }
+        let vruntime = self.min_vruntime.saturating_add(lag);
+        self.entities.insert(
+            id,
+            FairEntity {
+                vruntime,
+                queued: true,
+            },
+        );
+        self.timeline.insert((vruntime, id));
+    }
+
+    /// Takes the runnable task with the smallest virtual runtime.
+    pub fn pop(&mut self) -> Option<TaskId> {
+        let (vruntime, id) = self.timeline.pop_first()?;
+        if let Some(entity) = self.entities.get_mut(&id) {
+            entity.queued = false;
+        }
+        self.min_vruntime = self.min_vruntime.max(vruntime);
+        Some(id)
+    }
+
+    /// Takes the runnable task with the largest virtual runtime for which
+    /// `allowed` holds, for work stealing.
+    ///
+    /// The task stays tracked; the caller either puts it back with
+    /// [`enqueue`](Self::enqueue) or drops it with [`remove`](Self::remove).
+    pub fn steal_where(&mut self, mut allowed: impl FnMut(TaskId) -> bool) -> Option<TaskId> {
+        let key = *self.timeline.iter().rev().find(|(_, id)| allowed(*id))?;
+        self.timeline.remove(&key);
+        if let Some(entity) = self.entities.get_mut(&key.1) {
+            entity.queued = false;
+        }
+        Some(key.1)
+    }
+
+    /// Charges `ns` nanoseconds of run time to `id`, scaled by
+    /// [`NICE_0_WEIGHT`]` / weight`.
+    pub fn charge(&mut self, id: TaskId, ns: u64, weight: u32) {
+        let Some(entity) = self.entities.get_mut(&id) else {
+            return;
+        };
+        let delta = u128::from(ns) * u128::from(NICE_0_WEIGHT) / u128::from(weight.max(1));
+        let old = entity.vruntime;
+        entity.vruntime = old.saturating_add(u64::try_from(delta).unwrap_or(u64::MAX));
+        if entity.queued {
+            self.timeline.remove(&(old, id));
+            self.timeline.insert((entity.vruntime, id));
+        }
+        let mut floor = entity.vruntime;
+        if let Some(&(leftmost, _)) = self.timeline.first() {
+            floor = floor.min(leftmost);
+        }
+        self.min_vruntime = self.min_vruntime.max(floor);
+    }
+
+    /// Stops tracking `id` and returns how far its virtual runtime was
+    /// ahead of `min_vruntime`, or `None` if it was not tracked.
+    pub fn remove(&mut self, id: TaskId) -> Option<u64> {
+        let entity = self.entities.remove(&id)?;
+        if entity.queued {
+            self.timeline.remove(&(entity.vruntime, id));
+        }
+        Some(entity.vruntime.saturating_sub(self.min_vruntime))
+    }
+}
+
+impl Default for FairQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Scheduling state a User task takes along when it moves to another CPU.
+///
+/// Returned by [`ReadyQueues::forget`] on the old CPU and passed to
+/// [`ReadyQueues::push_migrated`] on the new one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Migration {
+    /// Virtual runtime ahead of the old queue's `min_vruntime`.
+    pub lag: u64,
+    /// Remaining budget, for a deadline task.
+    pub budget: Option<DeadlineBudget>,
+}
+
 /// Priority-aware ready queues.
 ///
-/// Maintains one FIFO queue per priority tier. Pops from the highest
-/// priority (lowest ordinal) non-empty queue first.
+/// Maintains one FIFO queue per kernel priority tier and, for the User
+/// tier, a [`DeadlineQueue`], an [`RtQueue`] and a [`FairQueue`]. Pops
+/// Critical, Normal, User, then Background; within User, deadline tasks
+/// come first, then real-time, then fair ones.
 pub struct ReadyQueues {
     queues: [VecDeque<TaskId>; Priority::COUNT],
+    /// Fair class of [`Priority::User`]; its slot in `queues` is unused.
+    fair: FairQueue,
+    /// Real-time class of [`Priority::User`].
+    rt: RtQueue,
+    /// Deadline class of [`Priority::User`].
+    deadline: DeadlineQueue,
+    /// Real-time run time used in the current throttling period.
+    rt_bandwidth: RtBandwidth,
+    /// Latest time passed to [`update_clock`](Self::update_clock).
+    now: u64,
     /// Counter for background starvation prevention.
-    /// Incremented each time a Normal task is popped while Background tasks wait.
+    /// Incremented each time a Normal or User task is popped while
+    /// Background tasks wait.
     normal_streak: u64,
 }
 
@@ -27,20 +265,132 @@ impl ReadyQueues {
     /// Creates empty ready queues.
     pub fn new() -> Self {
         Self {
-            queues: [VecDeque::new(), VecDeque::new(), VecDeque::new()],
+            queues: [const { VecDeque::new() }; Priority::COUNT],
+            fair: FairQueue::new(),
+            rt: RtQueue::new(),
+            deadline: DeadlineQueue::new(),
+            rt_bandwidth: RtBandwidth::new(),
+            now: 0,
             normal_streak: 0,
         }
     }
 
     /// Pushes a task into the queue for the given priority.
+    ///
+    /// A User task goes to the class of its policy; see
+    /// [`set_policy`](Self::set_policy).
     pub fn push(&mut self, priority: Priority, id: TaskId) {
-        self.queues[priority as usize].push_back(id);
+        match priority {
+            Priority::User if self.deadline.contains(id) => self.deadline.enqueue(id, self.now),
+            Priority::User if self.rt.contains(id) => self.rt.enqueue(id),
+            Priority::User => self.fair.enqueue(id),
+            _ => self.queues[priority as usize].push_back(id),
+        }
+    }
+
+    /// Pushes a task stolen from, or handed over by, another CPU.
+    ///
+    /// `migration` is the value [`forget`](Self::forget) returned on the old
+    /// CPU; it is ignored outside the User tier. A User task's policy must
+    /// be set first.
+    pub fn push_migrated(&mut self, priority: Priority, id: TaskId, migration: Migration) {
+        match (priority, migration.budget) {
+            (Priority::User, Some(budget)) if self.deadline.contains(id) => {
+                self.deadline.enqueue_migrated(id, budget, self.now);
+            }
+            (Priority::User, _) if self.deadline.contains(id) || self.rt.contains(id) => {
+                self.push(priority, id);
+            }
+            (Priority::User, _) => self.fair.enqueue_migrated(id, migration.lag),
+            _ => self.queues[priority as usize].push_back(id),
+        }
+    }
+
+    /// Moves User task `id` to the class of `policy`.
+    ///
+    /// A runnable task stays runnable. Leaving the fair class drops the
+    /// task's virtual runtime, and a new deadline reservation starts with a
+    /// fresh budget.
+    pub fn set_policy(&mut self, id: TaskId, policy: SchedPolicy) {
+        let queued =
+            self.fair.is_queued(id) || self.rt.is_queued(id) || self.deadline.is_queued(id);
+        match policy {
+            SchedPolicy::Normal => {
+                self.rt.remove(id);
+                self.deadline.remove(id);
+                if queued {
+                    self.fair.enqueue(id);
+                }
+            }
+            SchedPolicy::Fifo(priority) | SchedPolicy::RoundRobin(priority) => {
+                self.fair.remove(id);
+                self.deadline.remove(id);
+                let round_robin = matches!(policy, SchedPolicy::RoundRobin(_));
+                self.rt.set(id, priority, round_robin);
+                if queued {
+                    self.rt.enqueue(id);
+                }
+            }
+            SchedPolicy::Deadline(params) => {
+                self.fair.remove(id);
+                self.rt.remove(id);
+                self.deadline.set(id, params, self.now);
+                if queued {
+                    self.deadline.enqueue(id, self.now);
+                }
+            }
+        }
+    }
+
+    /// Charges `ns` of run time to User task `id`; `weight` applies if it
+    /// is a fair task.
+    pub fn charge(&mut self, id: TaskId, ns: u64, weight: u32) {
+        if self.deadline.contains(id) {
+            self.deadline.charge(id, ns);
+        } else if self.rt.contains(id) {
+            self.rt.charge(id, ns);
+            self.rt_bandwidth.charge(ns);
+        } else {
+            self.fair.charge(id, ns, weight);
+        }
+    }
+
+    /// Drops the class state of User task `id`, which finished or is
+    /// leaving this CPU, and returns what it takes along.
+    pub fn forget(&mut self, id: TaskId) -> Migration {
+        self.rt.remove(id);
+        Migration {
+            lag: self.fair.remove(id).unwrap_or(0),
+            budget: self.deadline.remove(id),
+        }
+    }
+
+    /// Advances the queues' clock to `now` nanoseconds, replenishing
+    /// throttled deadline tasks and starting new real-time periods.
+    ///
+    /// The executor calls this before popping; until it does, deadline
+    /// tasks are placed at time 0.
+    pub fn update_clock(&mut self, now: u64) {
+        self.now = self.now.max(now);
+        self.deadline.release(self.now);
+        self.rt_bandwidth.update(self.now);
+    }
+
+    /// Returns when the next throttled deadline task gets a new budget, so
+    /// an idle CPU knows when to look again.
+    pub fn next_release(&self) -> Option<u64> {
+        self.deadline.next_release()
+    }
+
+    /// Returns the User tier's fair queue.
+    pub fn fair(&self) -> &FairQueue {
+        &self.fair
     }
 
     /// Pops the highest-priority ready task.
     ///
-    /// Always drains Critical first. Between Normal and Background,
-    /// applies starvation prevention: if Normal has run for
+    /// Always drains Critical first. Between Normal/User and Background,
+    /// applies starvation prevention: if Normal and User have run for
     /// `BACKGROUND_STARVATION_LIMIT` consecutive pops and Background
     /// has tasks, pop one Background task instead.
     pub fn pop(&mut self) -> Option<(Priority, TaskId)> {
@@ -50,10 +400,10 @@ impl ReadyQueues {
             return Some((Priority::Critical, id));
         }
 
-        // Starvation prevention: if Normal has been running too long
-        // and Background has work, give Background a turn.
+        // Starvation prevention: if Normal and User have been running too
+        // long and Background has work, give Background a turn.
         let has_background = !self.queues[Priority::Background as usize].is_empty();
-        let has_normal = !self.queues[Priority::Normal as usize].is_empty();
+        let has_normal = !self.queues[Priority::Normal as usize].is_empty() || self.has_user();
 
         if has_normal && has_background && self.normal_streak >= BACKGROUND_STARVATION_LIMIT {
             self.normal_streak = 0;
@@ -62,14 +412,18 @@ impl ReadyQueues {
             }
         }
 
-        // Normal next.
-        if let Some(id) = self.queues[Priority::Normal as usize].pop_front() {
+        // Normal next, then User.
+        let next = match self.queues[Priority::Normal as usize].pop_front() {
+            Some(id) => Some((Priority::Normal, id)),
+            None => self.pop_user().map(|id| (Priority::User, id)),
+        };
+        if let Some(next) = next {
             if has_background {
                 self.normal_streak += 1;
             } else {
                 self.normal_streak = 0;
             }
-            return Some((Priority::Normal, id));
+            return Some(next);
         }
 
         // Background last.
@@ -79,42 +433,102 @@ impl ReadyQueues {
             .map(|id| (Priority::Background, id))
     }
 
+    /// Pops the next User task: deadline, then real-time, then fair.
+    ///
+    /// Once real-time tasks have used up their run time for the period,
+    /// waiting fair tasks go first.
+    fn pop_user(&mut self) -> Option<TaskId> {
+        if let Some(id) = self.deadline.pop() {
+            return Some(id);
+        }
+        if (!self.rt_bandwidth.is_throttled() || self.fair.is_empty())
+            && let Some(id) = self.rt.pop()
+        {
+            return Some(id);
+        }
+        self.fair.pop()
+    }
+
+    /// Returns `true` if any User class has a runnable task.
+    fn has_user(&self) -> bool {
+        !self.deadline.is_empty() || !self.rt.is_empty() || !self.fair.is_empty()
+    }
+
     /// Returns `true` if any priority queue has tasks.
     pub fn has_ready(&self) -> bool {
-        self.queues.iter().any(|q| !q.is_empty())
+        self.queues.iter().any(|q| !q.is_empty()) || self.has_user()
     }
 
     /// Steals one task from the back of the queue for work stealing.
     ///
-    /// Returns a Normal or Background task (never Critical). Steals from
-    /// the back to preserve locality — the victim keeps its hot (front)
-    /// tasks while the thief gets the coldest (most recently enqueued) one.
+    /// Equivalent to [`steal_one_where`](Self::steal_one_where) with no
+    /// filter.
+    pub fn steal_one(&mut self) -> Option<(Priority, TaskId)> {
+        self.steal_one_where(|_| true)
+    }
+
+    /// Steals one task for which `allowed` holds.
+    ///
+    /// Returns a Normal, User or Background task (never Critical). Steals
+    /// from the back to preserve locality — the victim keeps its hot
+    /// (front) tasks while the thief gets the coldest (most recently
+    /// enqueued) one. From the User tier, waiting deadline and real-time
+    /// tasks are taken before fair ones, since an idle CPU helps them
+    /// most; among fair tasks, the one furthest ahead in virtual runtime is
+    /// taken. `allowed` lets the caller skip tasks that may not run on the
+    /// thief's CPU.
     ///
     /// **One-task rule**: refuses to steal if the victim has only 1 stealable
-    /// task (Normal + Background combined). This prevents the bouncing
-    /// livelock where a sole task is stolen back and forth between CPUs
-    /// without making forward progress.
-    pub fn steal_one(&mut self) -> Option<(Priority, TaskId)> {
+    /// task (Normal + User + Background combined). This prevents the
+    /// bouncing livelock where a sole task is stolen back and forth between
+    /// CPUs without making forward progress.
+    pub fn steal_one_where(
+        &mut self,
+        mut allowed: impl FnMut(TaskId) -> bool,
+    ) -> Option<(Priority, TaskId)> {
         // One-task rule: never steal the victim's only runnable task.
         // This prevents the bouncing livelock where idle CPUs endlessly
         // steal a single task from each other, each polling it once before
         // the next steal. The victim needs at least 1 task to guarantee
         // local forward progress.
         let stealable = self.queues[Priority::Normal as usize].len()
+            + self.deadline.len()
+            + self.rt.len()
+            + self.fair.len()
             + self.queues[Priority::Background as usize].len();
         if stealable <= 1 {
             return None;
         }
 
-        // Prefer stealing Normal over Background.
-        if let Some(id) = self.queues[Priority::Normal as usize].pop_back() {
-            return Some((Priority::Normal, id));
-        }
-        if let Some(id) = self.queues[Priority::Background as usize].pop_back() {
-            return Some((Priority::Background, id));
+        // Prefer stealing Normal, then User, over Background.
+        for priority in [Priority::Normal, Priority::User, Priority::Background] {
+            let stolen = match priority {
+                Priority::User => self.steal_user(&mut allowed),
+                _ => {
+                    let queue = &mut self.queues[priority as usize];
+                    queue
+                        .iter()
+                        .rposition(|id| allowed(*id))
+                        .and_then(|pos| queue.remove(pos))
+                }
+            };
+            if let Some(id) = stolen {
+                return Some((priority, id));
+            }
         }
         None
     }
+
+    /// Steals a User task: deadline, then real-time, then fair.
+    fn steal_user(&mut self, allowed: &mut impl FnMut(TaskId) -> bool) -> Option<TaskId> {
+        if let Some(id) = self.deadline.steal_where(&mut *allowed) {
+            return Some(id);
+        }
+        if let Some(id) = self.rt.steal_where(&mut *allowed) {
+            return Some(id);
+        }
+        self.fair.steal_where(allowed)
+    }
 }
 
 impl Default for ReadyQueues {
@@ -126,6 +540,7 @@ impl Default for ReadyQueues {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rt::DeadlineParams;
 
     // -----------------------------------------------------------------------
     // ReadyQueues basic behavior
@@ -199,6 +614,300 @@ mod tests {
         assert_eq!(id, TaskId(999));
     }
 
+    #[test]
+    fn user_tier_between_normal_and_background() {
+        let mut rq = ReadyQueues::new();
+        rq.push(Priority::Background, TaskId(1));
+        rq.push(Priority::User, TaskId(2));
+        rq.push(Priority::Normal, TaskId(3));
+
+        assert_eq!(rq.pop(), Some((Priority::Normal, TaskId(3))));
+        assert_eq!(rq.pop(), Some((Priority::User, TaskId(2))));
+        assert_eq!(rq.pop(), Some((Priority::Background, TaskId(1))));
+    }
+
+    #[test]
+    fn starvation_prevention_counts_user() {
+        let mut rq = ReadyQueues::new();
+        rq.push(Priority::Background, TaskId(999));
+
+        // One user task that is always runnable again after its poll.
+        for _ in 0..BACKGROUND_STARVATION_LIMIT {
+            rq.push(Priority::User, TaskId(1));
+            assert_eq!(rq.pop(), Some((Priority::User, TaskId(1))));
+        }
+
+        rq.push(Priority::User, TaskId(1));
+        assert_eq!(rq.pop(), Some((Priority::Background, TaskId(999))));
+    }
+
+    // -----------------------------------------------------------------------
+    // Weighted-fair User tier
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn nice_weights() {
+        assert_eq!(nice_to_weight(0), NICE_0_WEIGHT);
+        assert_eq!(nice_to_weight(NICE_MIN), 88761);
+        assert_eq!(nice_to_weight(NICE_MAX), 15);
+        assert_eq!(nice_to_weight(-100), nice_to_weight(NICE_MIN));
+        assert_eq!(nice_to_weight(100), nice_to_weight(NICE_MAX));
+        for nice in NICE_MIN..NICE_MAX {
+            assert!(nice_to_weight(nice) > nice_to_weight(nice + 1));
+        }
+    }
+
+    #[test]
+    fn fair_picks_smallest_vruntime() {
+        let mut q = FairQueue::new();
+        q.enqueue(TaskId(1));
+        q.enqueue(TaskId(2));
+
+        assert_eq!(q.pop(), Some(TaskId(1)));
+        q.charge(TaskId(1), 1_000_000, NICE_0_WEIGHT);
+        q.enqueue(TaskId(1));
+
+        assert_eq!(q.pop(), Some(TaskId(2)));
+        assert_eq!(q.pop(), Some(TaskId(1)));
+        assert_eq!(q.pop(), None);
+    }
+
+    #[test]
+    fn fair_enqueue_is_idempotent() {
+        let mut q = FairQueue::new();
+        q.enqueue(TaskId(1));
+        q.enqueue(TaskId(1));
+        assert_eq!(q.len(), 1);
+    }
+
+    #[test]
+    fn fair_charge_repositions_queued_task() {
+        let mut q = FairQueue::new();
+        q.enqueue(TaskId(1));
+        q.enqueue(TaskId(2));
+        // Task 1 re-queued itself while it was running (a yield), then
+        // its run time is charged.
+        q.charge(TaskId(1), 5_000_000, NICE_0_WEIGHT);
+        assert_eq!(q.pop(), Some(TaskId(2)));
+        assert_eq!(q.pop(), Some(TaskId(1)));
+    }
+
+    #[test]
+    fn fair_share_follows_weight() {
+        let mut q = FairQueue::new();
+        let heavy = TaskId(1);
+        let light = TaskId(2);
+        q.enqueue(heavy);
+        q.enqueue(light);
+
+        let mut heavy_runs = 0u32;
+        for _ in 0..1000 {
+            let id = q.pop().unwrap();
+            let weight = if id == heavy {
+                heavy_runs += 1;
+                nice_to_weight(0)
+            } else {
+                nice_to_weight(10)
+            };
+            q.charge(id, 1_000_000, weight);
+            q.enqueue(id);
+        }
+        // 1024 / (1024 + 110) is about 90%.
+        assert!((880..=920).contains(&heavy_runs), "heavy ran {heavy_runs}");
+    }
+
+    #[test]
+    fn fair_sleeper_credit_is_bounded() {
+        let mut q = FairQueue::new();
+        let sleeper = TaskId(1);
+        let runner = TaskId(2);
+        q.enqueue(sleeper);
+        q.enqueue(runner);
+        assert_eq!(q.pop(), Some(sleeper));
+        assert_eq!(q.pop(), Some(runner));
+
+        // The runner keeps running while the sleeper is blocked.
+        for _ in 0..100 {
+            q.charge(runner, 1_000_000, NICE_0_WEIGHT);
+        }
+        assert_eq!(q.min_vruntime(), 100_000_000);
+
+        q.enqueue(sleeper);
+        assert_eq!(
+            q.vruntime(sleeper),
+            Some(100_000_000 - SLEEPER_CREDIT_NS),
+            "sleeper should not bank its sleep time"
+        );
+    }
+
+    #[test]
+    fn fair_migration_keeps_lag() {
+        let mut a = FairQueue::new();
+        a.enqueue(TaskId(1));
+        a.enqueue(TaskId(2));
+        a.pop();
+        a.charge(TaskId(1), 10_000_000, NICE_0_WEIGHT);
+        a.enqueue(TaskId(1));
+
+        let lag = a.remove(TaskId(1)).unwrap();
+        assert_eq!(lag, 10_000_000);
+        assert_eq!(a.vruntime(TaskId(1)), None);
+        assert_eq!(a.len(), 1);
+
+        let mut b = FairQueue::new();
+        b.enqueue(TaskId(3));
+        b.pop();
+        b.charge(TaskId(3), 50_000_000, NICE_0_WEIGHT);
+        b.enqueue_migrated(TaskId(1), lag);
+        assert_eq!(b.vruntime(TaskId(1)), Some(b.min_vruntime() + lag));
+    }
+
+    #[test]
+    fn fair_steal_takes_furthest_ahead() {
+        let mut rq = ReadyQueues::new();
+        rq.push(Priority::User, TaskId(1));
+        rq.push(Priority::User, TaskId(2));
+        rq.pop();
+        rq.charge(TaskId(1), 1_000_000, NICE_0_WEIGHT);
+        rq.push(Priority::User, TaskId(1));
+        rq.push(Priority::User, TaskId(3));
+
+        assert_eq!(rq.steal_one(), Some((Priority::User, TaskId(1))));
+        assert_eq!(rq.forget(TaskId(1)).lag, 1_000_000);
+    }
+
+    // -----------------------------------------------------------------------
+    // Real-time and deadline classes
+    // -----------------------------------------------------------------------
+
+    const MS: u64 = 1_000_000;
+
+    fn dl_params(runtime_ms: u64, period_ms: u64) -> DeadlineParams {
+        DeadlineParams {
+            runtime_ns: runtime_ms * MS,
+            deadline_ns: period_ms * MS,
+            period_ns: period_ms * MS,
+        }
+    }
+
+    #[test]
+    fn user_classes_run_deadline_rt_fair() {
+        let mut rq = ReadyQueues::new();
+        rq.set_policy(TaskId(2), SchedPolicy::Fifo(10));
+        rq.set_policy(TaskId(3), SchedPolicy::Deadline(dl_params(1, 10)));
+        rq.push(Priority::User, TaskId(1));
+        rq.push(Priority::User, TaskId(2));
+        rq.push(Priority::User, TaskId(3));
+        rq.push(Priority::Normal, TaskId(4));
+
+        assert_eq!(rq.pop(), Some((Priority::Normal, TaskId(4))));
+        assert_eq!(rq.pop(), Some((Priority::User, TaskId(3))));
+        assert_eq!(rq.pop(), Some((Priority::User, TaskId(2))));
+        assert_eq!(rq.pop(), Some((Priority::User, TaskId(1))));
+        assert!(!rq.has_ready());
+    }
+
+    #[test]
+    fn set_policy_keeps_task_runnable() {
+        let mut rq = ReadyQueues::new();
+        rq.push(Priority::User, TaskId(1));
+        rq.push(Priority::User, TaskId(2));
+        rq.set_policy(TaskId(2), SchedPolicy::RoundRobin(5));
+        assert_eq!(rq.pop(), Some((Priority::User, TaskId(2))));
+
+        rq.push(Priority::User, TaskId(2));
+        rq.set_policy(TaskId(2), SchedPolicy::Normal);
+        assert!(rq.fair().is_queued(TaskId(2)));
+        assert_eq!(rq.fair().len(), 2);
+    }
+
+    #[test]
+    fn rt_throttle_lets_fair_tasks_run() {
+        let mut rq = ReadyQueues::new();
+        rq.update_clock(0);
+        rq.set_policy(TaskId(1), SchedPolicy::Fifo(50));
+        rq.push(Priority::User, TaskId(1));
+        rq.push(Priority::User, TaskId(2));
+
+        // A runaway real-time task, preempted over and over.
+        let mut now = 0;
+        while now < crate::rt::RT_RUNTIME_NS {
+            assert_eq!(rq.pop(), Some((Priority::User, TaskId(1))));
+            rq.push(Priority::User, TaskId(1));
+            rq.charge(TaskId(1), 10 * MS, NICE_0_WEIGHT);
+            now += 10 * MS;
+            rq.update_clock(now);
+        }
+
+        // Throttled: the fair task gets its turn.
+        assert_eq!(rq.pop(), Some((Priority::User, TaskId(2))));
+        // With no fair task waiting, the real-time task may still run.
+        assert_eq!(rq.pop(), Some((Priority::User, TaskId(1))));
+        rq.push(Priority::User, TaskId(1));
+        rq.push(Priority::User, TaskId(2));
+
+        // A new period lifts the throttle.
+        rq.update_clock(crate::rt::RT_PERIOD_NS);
+        assert_eq!(rq.pop(), Some((Priority::User, TaskId(1))));
+    }
+
+    #[test]
+    fn deadline_throttle_and_release() {
+        let mut rq = ReadyQueues::new();
+        rq.update_clock(0);
+        rq.set_policy(TaskId(1), SchedPolicy::Deadline(dl_params(2, 10)));
+        rq.push(Priority::User, TaskId(1));
+        rq.push(Priority::User, TaskId(2));
+
+        assert_eq!(rq.pop(), Some((Priority::User, TaskId(1))));
+        rq.push(Priority::User, TaskId(1));
+        rq.charge(TaskId(1), 2 * MS, NICE_0_WEIGHT);
+        rq.update_clock(2 * MS);
+
+        // Out of budget until the next period at 10ms.
+        assert_eq!(rq.next_release(), Some(10 * MS));
+        assert_eq!(rq.pop(), Some((Priority::User, TaskId(2))));
+        assert!(!rq.has_ready());
+
+        rq.update_clock(10 * MS);
+        assert_eq!(rq.next_release(), None);
+        assert_eq!(rq.pop(), Some((Priority::User, TaskId(1))));
+    }
+
+    #[test]
+    fn deadline_budget_migrates() {
+        let policy = SchedPolicy::Deadline(dl_params(5, 100));
+        let mut a = ReadyQueues::new();
+        a.update_clock(0);
+        a.set_policy(TaskId(1), policy);
+        a.push(Priority::User, TaskId(1));
+        a.pop();
+        a.charge(TaskId(1), 3 * MS, NICE_0_WEIGHT);
+        let migration = a.forget(TaskId(1));
+        assert_eq!(migration.budget.map(|b| b.remaining), Some(2 * MS));
+
+        let mut b = ReadyQueues::new();
+        b.update_clock(MS);
+        b.set_policy(TaskId(1), policy);
+        b.push_migrated(Priority::User, TaskId(1), migration);
+        assert_eq!(b.pop(), Some((Priority::User, TaskId(1))));
+        b.charge(TaskId(1), 2 * MS, NICE_0_WEIGHT);
+        assert_eq!(b.next_release(), Some(100 * MS));
+    }
+
+    #[test]
+    fn steal_prefers_rt_over_fair() {
+        let mut rq = ReadyQueues::new();
+        rq.set_policy(TaskId(2), SchedPolicy::Fifo(10));
+        rq.set_policy(TaskId(3), SchedPolicy::Fifo(10));
+        rq.push(Priority::User, TaskId(1));
+        rq.push(Priority::User, TaskId(2));
+        rq.push(Priority::User, TaskId(3));
+
+        assert_eq!(rq.steal_one(), Some((Priority::User, TaskId(3))));
+        assert!(rq.forget(TaskId(3)).budget.is_none());
+    }
+
     // -----------------------------------------------------------------------
     // Work stealing
     // -----------------------------------------------------------------------
@@ -217,6 +926,18 @@ mod tests {
         assert_eq!(popped, Some((Priority::Normal, TaskId(1))));
     }
 
+    #[test]
+    fn steal_skips_disallowed_tasks() {
+        let mut rq = ReadyQueues::new();
+        rq.push(Priority::Normal, TaskId(1));
+        rq.push(Priority::Normal, TaskId(2));
+        rq.push(Priority::User, TaskId(3));
+
+        let stolen = rq.steal_one_where(|id| id != TaskId(2));
+        assert_eq!(stolen, Some((Priority::Normal, TaskId(1))));
+        assert_eq!(rq.steal_one_where(|id| id == TaskId(7)), None);
+    }
+
     #[test]
     fn steal_never_takes_critical() {
         let mut rq = ReadyQueues::new();
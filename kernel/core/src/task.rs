@@ -21,6 +21,11 @@ mod tests {
         assert_eq!(Priority::from_u8(2), Priority::Background);
     }
 
+    #[test]
+    fn priority_from_u8_user() {
+        assert_eq!(Priority::from_u8(3), Priority::User);
+    }
+
     #[test]
     fn priority_from_u8_unknown_defaults_normal() {
         assert_eq!(Priority::from_u8(255), Priority::Normal);
@@ -29,7 +34,7 @@ mod tests {
 
     #[test]
     fn priority_count() {
-        assert_eq!(Priority::COUNT, 3);
+        assert_eq!(Priority::COUNT, 4);
     }
 
     #[test]
@@ -68,6 +73,9 @@ mod tests {
 pub struct TaskId(pub u64);
 
 /// Task priority tier for the kernel executor.
+///
+/// The discriminants index the ready queues and are not the scheduling
+/// order: the executor runs Critical, Normal, User, then Background.
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Priority {
@@ -77,17 +85,20 @@ pub enum Priority {
     Normal = 1,
     /// Housekeeping: memory compaction, log flushing, statistics.
     Background = 2,
+    /// User processes, shared by weighted-fair scheduling on nice values.
+    User = 3,
 }
 
 impl Priority {
     /// Number of priority tiers.
-    pub const COUNT: usize = 3;
+    pub const COUNT: usize = 4;
 
     /// Converts a raw u8 to a priority, defaulting to Normal.
     pub const fn from_u8(val: u8) -> Self {
         match val {
             0 => Self::Critical,
             2 => Self::Background,
+            3 => Self::User,
             _ => Self::Normal,
         }
     }
@@ -0,0 +1,609 @@
+//! Timer queues: a hierarchical timing wheel and a high-resolution queue.
+//!
+//! [`TimerWheel`] holds coarse timeouts in whole ticks. It has [`LEVELS`]
+//! levels of [`SLOTS`] slots; a slot on level `n` spans `SLOTS^n` ticks. A
+//! timer goes into the lowest level that reaches its deadline and moves
+//! down a level ("cascades") when its slot comes up, so inserting is O(1)
+//! and advancing only visits the slots that hold timers.
+//!
+//! [`HrTimerQueue`] holds nanosecond deadlines in a binary heap, for
+//! timeouts that need to fire between ticks.
+//!
+//! Both hand out a [`TimerKey`] that cancels the timer in O(1): the wheel
+//! unlinks it from its slot, and the high-resolution queue frees it and
+//! skips its heap entry later.
+
+extern crate alloc;
+
+use alloc::collections::BinaryHeap;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+/// Number of slots per wheel level.
+pub const SLOTS: usize = 64;
+
+/// Number of wheel levels. Timers further out than `SLOTS^LEVELS` ticks
+/// wait in the last slot of the top level and cascade again when it comes
+/// up.
+pub const LEVELS: usize = 4;
+
+/// `log2(SLOTS)`.
+const SLOT_BITS: u32 = SLOTS.trailing_zeros();
+
+/// End-of-list marker for slab indices.
+const NIL: u32 = u32::MAX;
+
+/// Identifies a timer in a [`TimerWheel`] or [`HrTimerQueue`].
+///
+/// A key goes stale once its timer fires or is cancelled; using it
+/// afterwards finds nothing, even if the slot was reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerKey {
+    index: u32,
+    generation: u32,
+}
+
+/// A timer in the slab, or a free slab entry.
+struct Node<T> {
+    deadline: u64,
+    generation: u32,
+    /// Wheel slot the node is linked into, `level * SLOTS + slot`.
+    slot: u32,
+    prev: u32,
+    /// Next node in the same slot, or in the free list.
+    next: u32,
+    value: Option<T>,
+}
+
+/// Timer storage shared by both queues; freed entries are reused.
+struct Slab<T> {
+    nodes: Vec<Node<T>>,
+    free: u32,
+    len: usize,
+}
+
+impl<T> Slab<T> {
+    const fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            free: NIL,
+            len: 0,
+        }
+    }
+
+    fn insert(&mut self, deadline: u64, value: T) -> TimerKey {
+        self.len += 1;
+        if self.free != NIL {
+            let index = self.free;
+            let node = &mut self.nodes[index as usize];
+            self.free = node.next;
+            node.deadline = deadline;
+            node.slot = NIL;
+            node.prev = NIL;
+            node.next = NIL;
+            node.value = Some(value);
+            return TimerKey {
+                index,
+                generation: node.generation,
+            };
+        }
+        let index = u32::try_from(self.nodes.len()).expect("too many timers");
+        self.nodes.push(Node {
+            deadline,
+            generation: 0,
+            slot: NIL,
+            prev: NIL,
+            next: NIL,
+            value: Some(value),
+        });
+        TimerKey {
+            index,
+            generation: 0,
+        }
+    }
+
+    /// Frees the node at `index` and returns its value.
+    fn remove(&mut self, index: u32) -> T {
+        self.len -= 1;
+        let node = &mut self.nodes[index as usize];
+        node.generation = node.generation.wrapping_add(1);
+        node.next = self.free;
+        self.free = index;
+        node.value.take().expect("timer slab entry is free")
+    }
+
+    /// Returns `true` if `key` names a live timer.
+    fn contains(&self, key: TimerKey) -> bool {
+        self.nodes
+            .get(key.index as usize)
+            .is_some_and(|node| node.generation == key.generation && node.value.is_some())
+    }
+
+    fn get_mut(&mut self, key: TimerKey) -> Option<&mut T> {
+        if !self.contains(key) {
+            return None;
+        }
+        self.nodes[key.index as usize].value.as_mut()
+    }
+}
+
+/// A hierarchical timing wheel of timers with tick deadlines.
+pub struct TimerWheel<T> {
+    slab: Slab<T>,
+    /// First node of each slot, `level * SLOTS + slot`.
+    heads: [u32; LEVELS * SLOTS],
+    /// Non-empty slots of each level (bit `n` = slot `n`).
+    occupied: [u64; LEVELS],
+    /// Tick the wheel has advanced to.
+    now: u64,
+}
+
+impl<T> TimerWheel<T> {
+    /// Creates an empty wheel at tick 0.
+    pub const fn new() -> Self {
+        Self {
+            slab: Slab::new(),
+            heads: [NIL; LEVELS * SLOTS],
+            occupied: [0; LEVELS],
+            now: 0,
+        }
+    }
+
+    /// Returns the number of pending timers.
+    pub fn len(&self) -> usize {
+        self.slab.len
+    }
+
+    /// Returns `true` if no timer is pending.
+    pub fn is_empty(&self) -> bool {
+        self.slab.len == 0
+    }
+
+    /// Adds a timer that fires at tick `deadline`.
+    ///
+    /// A deadline that has already passed fires at the next
+    /// [`advance`](Self::advance).
+    pub fn insert(&mut self, deadline: u64, value: T) -> TimerKey {
+        let key = self.slab.insert(deadline, value);
+        self.link(key.index);
+        key
+    }
+
+    /// Cancels a pending timer and returns its value, or `None` if it
+    /// already fired or was cancelled.
+    pub fn cancel(&mut self, key: TimerKey) -> Option<T> {
+        if !self.slab.contains(key) {
+            return None;
+        }
+        self.unlink(key.index);
+        Some(self.slab.remove(key.index))
+    }
+
+    /// Returns the value of a pending timer.
+    pub fn get_mut(&mut self, key: TimerKey) -> Option<&mut T> {
+        self.slab.get_mut(key)
+    }
+
+    /// Returns the first tick at which [`advance`](Self::advance) has work
+    /// to do, or `None` if no timer is pending.
+    ///
+    /// This is at most the earliest deadline: it may instead be the tick
+    /// at which a far-off timer cascades to a lower level.
+    pub fn next_expiry(&self) -> Option<u64> {
+        (0..LEVELS)
+            .filter(|&level| self.occupied[level] != 0)
+            .map(|level| {
+                let shift = level as u32 * SLOT_BITS;
+                let position = self.now >> shift;
+                let distance = self.occupied[level]
+                    .rotate_right((position % SLOTS as u64) as u32)
+                    .trailing_zeros();
+                (position + u64::from(distance)) << shift
+            })
+            .min()
+    }
+
+    /// Advances the wheel to tick `now` and hands each expired timer's
+    /// value to `expire`.
+    ///
+    /// Stops early once `expire` returns `false`; the remaining expired
+    /// timers are handed out by the next call.
+    pub fn advance(&mut self, now: u64, mut expire: impl FnMut(T) -> bool) {
+        loop {
+            // The current level-0 slot only ever holds due timers.
+            let slot = (self.now % SLOTS as u64) as usize;
+            while self.heads[slot] != NIL {
+                let index = self.heads[slot];
+                self.unlink(index);
+                if !expire(self.slab.remove(index)) {
+                    return;
+                }
+            }
+            let Some(next) = self.next_expiry().filter(|&next| next <= now) else {
+                self.now = self.now.max(now);
+                return;
+            };
+            self.now = next;
+            // Higher levels first, so their timers can land in the slots
+            // below that start at the same tick.
+            for level in (1..LEVELS).rev() {
+                let shift = level as u32 * SLOT_BITS;
+                if next & ((1 << shift) - 1) == 0 {
+                    self.cascade(level * SLOTS + ((next >> shift) % SLOTS as u64) as usize);
+                }
+            }
+        }
+    }
+
+    /// Re-inserts every timer of `slot` relative to the current tick.
+    fn cascade(&mut self, slot: usize) {
+        let mut index = self.heads[slot];
+        self.heads[slot] = NIL;
+        self.occupied[slot / SLOTS] &= !(1 << (slot % SLOTS));
+        while index != NIL {
+            let next = self.slab.nodes[index as usize].next;
+            self.link(index);
+            index = next;
+        }
+    }
+
+    /// Links node `index` into the slot its deadline falls in.
+    fn link(&mut self, index: u32) {
+        let deadline = self.slab.nodes[index as usize].deadline.max(self.now);
+        let mut level = 0;
+        while level < LEVELS - 1 {
+            let shift = level as u32 * SLOT_BITS;
+            if (deadline >> shift) - (self.now >> shift) < SLOTS as u64 {
+                break;
+            }
+            level += 1;
+        }
+        let shift = level as u32 * SLOT_BITS;
+        let position = (deadline >> shift).min((self.now >> shift) + SLOTS as u64 - 1);
+        let slot = level * SLOTS + (position % SLOTS as u64) as usize;
+
+        let head = self.heads[slot];
+        let node = &mut self.slab.nodes[index as usize];
+        node.slot = slot as u32;
+        node.prev = NIL;
+        node.next = head;
+        if head != NIL {
+            self.slab.nodes[head as usize].prev = index;
+        }
+        self.heads[slot] = index;
+        self.occupied[level] |= 1 << (slot % SLOTS);
+    }
+
+    /// Unlinks node `index` from its slot.
+    fn unlink(&mut self, index: u32) {
+        let node = &self.slab.nodes[index as usize];
+        let (slot, prev, next) = (node.slot as usize, node.prev, node.next);
+        if prev == NIL {
+            self.heads[slot] = next;
+            if next == NIL {
+                self.occupied[slot / SLOTS] &= !(1 << (slot % SLOTS));
+            }
+        } else {
+            self.slab.nodes[prev as usize].next = next;
+        }
+        if next != NIL {
+            self.slab.nodes[next as usize].prev = prev;
+        }
+    }
+}
+
+impl<T> Default for TimerWheel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A queue of timers with nanosecond deadlines.
+pub struct HrTimerQueue<T> {
+    slab: Slab<T>,
+    /// `(deadline, index, generation)` of every timer not yet popped,
+    /// including cancelled ones.
+    heap: BinaryHeap<Reverse<(u64, u32, u32)>>,
+}
+
+impl<T> HrTimerQueue<T> {
+    /// Creates an empty queue.
+    pub const fn new() -> Self {
+        Self {
+            slab: Slab::new(),
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    /// Returns the number of pending timers.
+    pub fn len(&self) -> usize {
+        self.slab.len
+    }
+
+    /// Returns `true` if no timer is pending.
+    pub fn is_empty(&self) -> bool {
+        self.slab.len == 0
+    }
+
+    /// Adds a timer that fires at `deadline` nanoseconds.
+    pub fn insert(&mut self, deadline: u64, value: T) -> TimerKey {
+        let key = self.slab.insert(deadline, value);
+        self.heap
+            .push(Reverse((deadline, key.index, key.generation)));
+        key
+    }
+
+    /// Cancels a pending timer and returns its value, or `None` if it
+    /// already fired or was cancelled.
+    pub fn cancel(&mut self, key: TimerKey) -> Option<T> {
+        if !self.slab.contains(key) {
+            return None;
+        }
+        let value = self.slab.remove(key.index);
+        // Cancelled entries stay in the heap until they reach the top;
+        // rebuild it once they make up most of it.
+        if self.heap.len() > 2 * self.slab.len + SLOTS {
+            let slab = &self.slab;
+            self.heap.retain(|Reverse((_, index, generation))| {
+                slab.contains(TimerKey {
+                    index: *index,
+                    generation: *generation,
+                })
+            });
+        }
+        Some(value)
+    }
+
+    /// Returns the value of a pending timer.
+    pub fn get_mut(&mut self, key: TimerKey) -> Option<&mut T> {
+        self.slab.get_mut(key)
+    }
+
+    /// Returns the earliest pending deadline.
+    pub fn next_expiry(&mut self) -> Option<u64> {
+        self.discard_cancelled();
+        self.heap.peek().map(|Reverse((deadline, ..))| *deadline)
+    }
+
+    /// Hands the value of each timer due at `now` to `expire`, earliest
+    /// first.
+    ///
+    /// Stops early once `expire` returns `false`; the remaining expired
+    /// timers are handed out by the next call.
+    pub fn expire(&mut self, now: u64, mut expire: impl FnMut(T) -> bool) {
+        while self.next_expiry().is_some_and(|deadline| deadline <= now) {
+            let Some(Reverse((_, index, _))) = self.heap.pop() else {
+                break;
+            };
+            if !expire(self.slab.remove(index)) {
+                return;
+            }
+        }
+    }
+
+    /// Pops cancelled entries off the top of the heap.
+    fn discard_cancelled(&mut self) {
+        while let Some(&Reverse((_, index, generation))) = self.heap.peek() {
+            if self.slab.contains(TimerKey { index, generation }) {
+                break;
+            }
+            self.heap.pop();
+        }
+    }
+}
+
+impl<T> Default for HrTimerQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Moves the expiry of a periodic timer past `now` in whole `interval`s.
+///
+/// Returns the new expiry and the number of periods that were skipped
+/// entirely (the overrun). An expiry still in the future is returned as is.
+pub fn forward(expiry: u64, interval: u64, now: u64) -> (u64, u64) {
+    debug_assert!(interval > 0, "forward of a one-shot timer");
+    if now < expiry {
+        return (expiry, 0);
+    }
+    let missed = (now - expiry) / interval;
+    let next = expiry.saturating_add(missed.saturating_add(1).saturating_mul(interval));
+    (next, missed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Advances `wheel` to `now` and returns the expired values in order.
+    fn advance(wheel: &mut TimerWheel<u32>, now: u64) -> Vec<u32> {
+        let mut fired = Vec::new();
+        wheel.advance(now, |value| {
+            fired.push(value);
+            true
+        });
+        fired
+    }
+
+    #[test]
+    fn wheel_fires_at_deadline() {
+        let mut wheel = TimerWheel::new();
+        wheel.insert(5, 1);
+        assert!(advance(&mut wheel, 4).is_empty());
+        assert_eq!(advance(&mut wheel, 5), [1]);
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn wheel_fires_past_deadline_immediately() {
+        let mut wheel = TimerWheel::new();
+        advance(&mut wheel, 100);
+        wheel.insert(50, 1);
+        assert_eq!(advance(&mut wheel, 100), [1]);
+    }
+
+    #[test]
+    fn wheel_cascades_far_timers_on_time() {
+        let mut wheel = TimerWheel::new();
+        let deadlines = [63, 64, 65, 4095, 4096, 4097, 300_000, 20_000_000];
+        for (value, &deadline) in deadlines.iter().enumerate() {
+            wheel.insert(deadline, value as u32);
+        }
+        for (value, &deadline) in deadlines.iter().enumerate() {
+            assert!(
+                advance(&mut wheel, deadline - 1).is_empty(),
+                "early at {deadline}"
+            );
+            assert_eq!(advance(&mut wheel, deadline), [value as u32]);
+        }
+        assert!(wheel.is_empty());
+    }
+
+    #[test]
+    fn wheel_next_expiry_bounds_deadline() {
+        let mut wheel = TimerWheel::new();
+        assert_eq!(wheel.next_expiry(), None);
+        wheel.insert(10, 1);
+        assert_eq!(wheel.next_expiry(), Some(10));
+        wheel.insert(5_000, 2);
+        assert_eq!(wheel.next_expiry(), Some(10));
+        advance(&mut wheel, 10);
+        let next = wheel.next_expiry().unwrap();
+        assert!(next > 10 && next <= 5_000);
+    }
+
+    #[test]
+    fn wheel_cancel() {
+        let mut wheel = TimerWheel::new();
+        let a = wheel.insert(10, 1);
+        let b = wheel.insert(10, 2);
+        let c = wheel.insert(10, 3);
+        assert_eq!(wheel.cancel(b), Some(2));
+        assert_eq!(wheel.cancel(b), None);
+        assert_eq!(wheel.len(), 2);
+        let mut fired = advance(&mut wheel, 10);
+        fired.sort_unstable();
+        assert_eq!(fired, [1, 3]);
+        assert_eq!(wheel.cancel(a), None);
+        assert_eq!(wheel.cancel(c), None);
+    }
+
+    #[test]
+    fn wheel_stale_key_ignores_reused_entry() {
+        let mut wheel = TimerWheel::new();
+        let old = wheel.insert(10, 1);
+        wheel.cancel(old);
+        let new = wheel.insert(20, 2);
+        assert_eq!(wheel.cancel(old), None);
+        assert_eq!(wheel.get_mut(new), Some(&mut 2));
+    }
+
+    #[test]
+    fn wheel_resumes_after_early_stop() {
+        let mut wheel = TimerWheel::new();
+        for value in 0..5 {
+            wheel.insert(3, value);
+        }
+        wheel.insert(4, 5);
+        let mut fired = Vec::new();
+        wheel.advance(10, |value| {
+            fired.push(value);
+            fired.len() < 2
+        });
+        assert_eq!(fired.len(), 2);
+        assert_eq!(wheel.next_expiry(), Some(3));
+        fired.extend(advance(&mut wheel, 10));
+        fired.sort_unstable();
+        assert_eq!(fired, [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn wheel_matches_sorted_order() {
+        let mut wheel = TimerWheel::new();
+        let mut seed = 0x2545_f491_u64;
+        let mut deadlines = Vec::new();
+        for value in 0..500 {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            let deadline = seed % 1_000_000;
+            deadlines.push((deadline, value));
+            wheel.insert(deadline, value);
+        }
+        deadlines.sort_unstable();
+        let mut now = 0;
+        let mut fired = Vec::new();
+        while !wheel.is_empty() {
+            now += 997;
+            for value in advance(&mut wheel, now) {
+                let &(deadline, _) = deadlines.iter().find(|(_, v)| *v == value).unwrap();
+                assert!(deadline <= now && deadline + 997 > now);
+                fired.push(value);
+            }
+        }
+        assert_eq!(fired.len(), deadlines.len());
+    }
+
+    #[test]
+    fn hr_fires_in_deadline_order() {
+        let mut queue = HrTimerQueue::new();
+        queue.insert(300, 3);
+        queue.insert(100, 1);
+        queue.insert(200, 2);
+        assert_eq!(queue.next_expiry(), Some(100));
+        let mut fired = Vec::new();
+        queue.expire(250, |value| {
+            fired.push(value);
+            true
+        });
+        assert_eq!(fired, [1, 2]);
+        assert_eq!(queue.next_expiry(), Some(300));
+    }
+
+    #[test]
+    fn hr_cancel_skips_entry() {
+        let mut queue = HrTimerQueue::new();
+        let a = queue.insert(100, 1);
+        queue.insert(200, 2);
+        assert_eq!(queue.cancel(a), Some(1));
+        assert_eq!(queue.cancel(a), None);
+        assert_eq!(queue.next_expiry(), Some(200));
+        let mut fired = Vec::new();
+        queue.expire(1_000, |value| {
+            fired.push(value);
+            true
+        });
+        assert_eq!(fired, [2]);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn hr_cancelled_entries_are_compacted() {
+        let mut queue = HrTimerQueue::new();
+        queue.insert(u64::MAX, 0);
+        for value in 0..1_000 {
+            let key = queue.insert(1_000 + value, value);
+            queue.cancel(key);
+        }
+        assert_eq!(queue.len(), 1);
+        assert!(queue.heap.len() <= 2 + SLOTS);
+    }
+
+    #[test]
+    fn forward_on_time() {
+        assert_eq!(forward(100, 10, 100), (110, 0));
+        assert_eq!(forward(100, 10, 109), (110, 0));
+    }
+
+    #[test]
+    fn forward_counts_skipped_periods() {
+        assert_eq!(forward(100, 10, 110), (120, 1));
+        assert_eq!(forward(100, 10, 135), (140, 3));
+    }
+
+    #[test]
+    fn forward_keeps_future_expiry() {
+        assert_eq!(forward(100, 10, 50), (100, 0));
+    }
+}
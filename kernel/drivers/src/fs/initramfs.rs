@@ -12,12 +12,14 @@ use hadris_cpio::CpioReader;
 use hadris_cpio::mode::FileType;
 use hadris_io::Cursor;
 
-use hadron_kernel::fs::{FsError, Inode, InodeType, Permissions, poll_immediate};
+use hadron_kernel::fs::{FsError, Inode, InodeType, Ownership, Permissions, poll_immediate};
+use hadron_kernel::id::{Gid, Uid};
 
 /// Unpack a CPIO newc archive into the given root inode.
 ///
 /// Returns the number of files unpacked. Directories are created as needed;
-/// the CPIO root `.` entry is skipped.
+/// the CPIO root `.` entry only sets the root's ownership. Each entry keeps
+/// the owner, group and mode bits recorded in the archive.
 ///
 /// # Panics
 ///
@@ -39,8 +41,18 @@ pub fn unpack_cpio(initrd: &[u8], root: &Arc<dyn Inode>) -> usize {
         let name = entry.name_str().unwrap_or("");
         let name = name.strip_prefix('/').unwrap_or(name);
 
-        // Skip the root directory entry and empty names.
+        let header = entry.header();
+        let ownership = Ownership::new(
+            Uid::new(header.uid),
+            Gid::new(header.gid),
+            header.permissions() as u16,
+        );
+
+        // The root directory entry only carries the root's ownership.
         if name.is_empty() || name == "." {
+            if name == "." {
+                set_ownership(root, ownership);
+            }
             reader
                 .skip_entry_data(&entry)
                 .expect("failed to skip CPIO entry data");
@@ -52,6 +64,7 @@ pub fn unpack_cpio(initrd: &[u8], root: &Arc<dyn Inode>) -> usize {
         match file_type {
             FileType::Directory => {
                 ensure_directory(root, name);
+                set_ownership(&resolve_path(root, name), ownership);
                 reader
                     .skip_entry_data(&entry)
                     .expect("failed to skip CPIO directory data");
@@ -77,6 +90,7 @@ pub fn unpack_cpio(initrd: &[u8], root: &Arc<dyn Inode>) -> usize {
                         .unwrap_or_else(|e| {
                             panic!("initramfs: failed to create file '{}': {:?}", name, e)
                         });
+                set_ownership(&file_inode, ownership);
 
                 // Read data from CPIO and write to the inode.
                 if file_size > 0 {
@@ -119,7 +133,7 @@ pub fn unpack_cpio(initrd: &[u8], root: &Arc<dyn Inode>) -> usize {
                     (root.clone(), name)
                 };
 
-                parent
+                let link = parent
                     .create_symlink(link_name, target, Permissions::all())
                     .unwrap_or_else(|e| {
                         panic!(
@@ -127,6 +141,7 @@ pub fn unpack_cpio(initrd: &[u8], root: &Arc<dyn Inode>) -> usize {
                             name, target, e
                         )
                     });
+                set_ownership(&link, ownership);
 
                 file_count += 1;
             }
@@ -142,6 +157,15 @@ pub fn unpack_cpio(initrd: &[u8], root: &Arc<dyn Inode>) -> usize {
     file_count
 }
 
+/// Apply archived ownership to an unpacked inode. Filesystems that do not
+/// track ownership keep their defaults.
+fn set_ownership(inode: &Arc<dyn Inode>, ownership: Ownership) {
+    match inode.set_ownership(ownership) {
+        Ok(()) | Err(FsError::NotSupported) => {}
+        Err(e) => panic!("initramfs: failed to set ownership: {:?}", e),
+    }
+}
+
 /// Ensure that a directory path exists, creating intermediate directories as needed.
 fn ensure_directory(root: &Arc<dyn Inode>, path: &str) {
     let mut current = root.clone();
@@ -16,7 +16,13 @@ use core::pin::Pin;
 
 use hadron_kernel::sync::SpinLock;
 
-use hadron_kernel::fs::{DirEntry, FileSystem, FsError, Inode, InodeType, Permissions};
+use hadron_kernel::fs::{DirEntry, FileSystem, FsError, Inode, InodeType, Ownership, Permissions};
+use hadron_kernel::id::{Gid, Uid};
+use hadron_kernel::mm::slab::{self, SlabCache};
+
+/// Slab cache serving `Arc<RamInode>` allocations.
+static RAM_INODE_CACHE: SlabCache =
+    SlabCache::new("ram_inode", slab::arc_layout::<RamInode>(), None);
 
 /// A ramfs filesystem instance.
 pub struct RamFs {
@@ -31,15 +37,20 @@ impl Default for RamFs {
 }
 
 impl RamFs {
-    /// Creates a new ramfs with an empty root directory.
+    /// Creates a new ramfs with an empty root directory, owned by root and
+    /// writable only by root.
     #[must_use]
     pub fn new() -> Self {
+        slab::register(&RAM_INODE_CACHE);
         Self {
             root: Arc::new(RamInode {
                 itype: InodeType::Directory,
                 data: SpinLock::named("RamInode.data", Vec::new()),
                 children: SpinLock::named("RamInode.children", BTreeMap::new()),
-                permissions: Permissions::all(),
+                ownership: SpinLock::named(
+                    "RamInode.ownership",
+                    Ownership::new(Uid::ROOT, Gid::ROOT, 0o755),
+                ),
             }),
         }
     }
@@ -63,8 +74,8 @@ pub struct RamInode {
     data: SpinLock<Vec<u8>>,
     /// Child entries (only meaningful for directories).
     children: SpinLock<BTreeMap<String, Arc<RamInode>>>,
-    /// Permissions.
-    permissions: Permissions,
+    /// Owner, group and mode bits.
+    ownership: SpinLock<Ownership>,
 }
 
 impl Inode for RamInode {
@@ -80,7 +91,16 @@ impl Inode for RamInode {
     }
 
     fn permissions(&self) -> Permissions {
-        self.permissions
+        Permissions::from_mode(self.ownership.lock().mode)
+    }
+
+    fn ownership(&self) -> Ownership {
+        *self.ownership.lock()
+    }
+
+    fn set_ownership(&self, ownership: Ownership) -> Result<(), FsError> {
+        *self.ownership.lock() = ownership;
+        Ok(())
     }
 
     fn read<'a>(
@@ -189,7 +209,10 @@ impl Inode for RamInode {
                 itype,
                 data: SpinLock::named("RamInode.data", Vec::new()),
                 children: SpinLock::named("RamInode.children", BTreeMap::new()),
-                permissions: perms,
+                ownership: SpinLock::named(
+                    "RamInode.ownership",
+                    Ownership::from_permissions(perms),
+                ),
             });
             children.insert(name.to_string(), new_inode.clone());
             Ok(new_inode as Arc<dyn Inode>)
@@ -237,7 +260,7 @@ impl Inode for RamInode {
             itype: InodeType::Symlink,
             data: SpinLock::named("RamInode.data", target.as_bytes().to_vec()),
             children: SpinLock::named("RamInode.children", BTreeMap::new()),
-            permissions: perms,
+            ownership: SpinLock::named("RamInode.ownership", Ownership::from_permissions(perms)),
         });
         children.insert(name.to_string(), new_inode.clone());
         Ok(new_inode)
@@ -22,6 +22,7 @@ use core::future::Future;
 use core::pin::Pin;
 
 use hadron_core::addr::PhysAddr;
+use hadron_core::id::{Gid, Uid};
 
 pub use devfs::DevNumber;
 
@@ -83,6 +84,56 @@ impl Permissions {
             execute: false,
         }
     }
+
+    /// Permissions granted by the owner class of a POSIX `mode`.
+    #[must_use]
+    pub const fn from_mode(mode: u16) -> Self {
+        Self {
+            read: mode & 0o400 != 0,
+            write: mode & 0o200 != 0,
+            execute: mode & 0o100 != 0,
+        }
+    }
+
+    /// POSIX permission bits granting these permissions to owner, group
+    /// and others alike.
+    #[must_use]
+    pub const fn mode(self) -> u16 {
+        let class = (self.read as u16) << 2 | (self.write as u16) << 1 | self.execute as u16;
+        class << 6 | class << 3 | class
+    }
+}
+
+/// Owner, group and POSIX mode bits of an inode.
+///
+/// `mode` holds the permission bits only (`0o7777`: set-user-ID,
+/// set-group-ID, sticky and the three `rwx` classes), not the file type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ownership {
+    /// Owning user.
+    pub uid: Uid,
+    /// Owning group.
+    pub gid: Gid,
+    /// Permission bits.
+    pub mode: u16,
+}
+
+impl Ownership {
+    /// Creates an ownership record.
+    #[must_use]
+    pub const fn new(uid: Uid, gid: Gid, mode: u16) -> Self {
+        Self {
+            uid,
+            gid,
+            mode: mode & 0o7777,
+        }
+    }
+
+    /// Owned by root, with `perms` granted to every class.
+    #[must_use]
+    pub const fn from_permissions(perms: Permissions) -> Self {
+        Self::new(Uid::ROOT, Gid::ROOT, perms.mode())
+    }
 }
 
 /// A directory entry returned by [`Inode::readdir`].
@@ -162,6 +213,21 @@ pub trait Inode: Send + Sync {
     /// Returns the permissions of this inode.
     fn permissions(&self) -> Permissions;
 
+    /// Returns the owner, group and mode bits of this inode.
+    ///
+    /// Default: owned by root, with [`permissions`](Self::permissions)
+    /// granted to every class.
+    fn ownership(&self) -> Ownership {
+        Ownership::from_permissions(self.permissions())
+    }
+
+    /// Changes the owner, group and mode bits of this inode.
+    ///
+    /// Default implementation returns [`FsError::NotSupported`].
+    fn set_ownership(&self, _ownership: Ownership) -> Result<(), FsError> {
+        Err(FsError::NotSupported)
+    }
+
     /// Read data from this inode at the given offset.
     ///
     /// Returns the number of bytes read. For in-memory filesystems the
@@ -314,6 +380,14 @@ pub trait Inode: Send + Sync {
         Box::pin(core::future::ready(Err(FsError::NotSupported)))
     }
 
+    /// Flush writes to this inode through to its backing store.
+    ///
+    /// Default succeeds: inodes that write through, or have no backing
+    /// store, have nothing to flush.
+    fn sync(&self) -> Pin<Box<dyn Future<Output = Result<(), FsError>> + Send + '_>> {
+        Box::pin(core::future::ready(Ok(())))
+    }
+
     /// Returns the physical frame addresses for shared memory mapping.
     ///
     /// Only meaningful for shared memory objects. Returns a vector of
@@ -357,6 +431,14 @@ pub trait Inode: Send + Sync {
         None
     }
 
+    /// Downcast to a concrete inode type defined outside this crate.
+    ///
+    /// Default: `None`. Overridden by inodes that syscalls other than
+    /// read and write operate on, such as timerfds and signalfds.
+    fn as_any(&self) -> Option<&dyn core::any::Any> {
+        None
+    }
+
     /// Accept an incoming connection on a listening socket.
     ///
     /// Returns a future that resolves to the accepted socket's inode, or an
@@ -26,6 +26,9 @@ const MAX_MESSAGE_SIZE: usize = 4096;
 const MAX_BUFFERED_MESSAGES: usize = 16;
 
 /// A channel message: data bytes plus an optional attached inode (for fd passing).
+///
+/// Queued messages are boxed; the kernel serves the boxes from a dedicated
+/// slab cache.
 pub struct ChannelMessage {
     /// Message payload.
     pub data: Vec<u8>,
@@ -56,9 +59,9 @@ pub fn channel() -> (Arc<dyn Inode>, Arc<dyn Inode>) {
 /// Shared channel state between both endpoints.
 struct ChannelInner {
     /// Messages sent by A, received by B.
-    a_to_b: SpinLock<alloc::collections::VecDeque<ChannelMessage>>,
+    a_to_b: SpinLock<alloc::collections::VecDeque<Box<ChannelMessage>>>,
     /// Messages sent by B, received by A.
-    b_to_a: SpinLock<alloc::collections::VecDeque<ChannelMessage>>,
+    b_to_a: SpinLock<alloc::collections::VecDeque<Box<ChannelMessage>>>,
     /// Woken when A can send (space in `a_to_b`).
     a_send_wq: HeapWaitQueue,
     /// Woken when A can recv (data in `b_to_a`).
@@ -79,7 +82,7 @@ impl ChannelInner {
         &self,
         is_a: bool,
     ) -> (
-        &SpinLock<alloc::collections::VecDeque<ChannelMessage>>,
+        &SpinLock<alloc::collections::VecDeque<Box<ChannelMessage>>>,
         &HeapWaitQueue,
         &HeapWaitQueue,
     ) {
@@ -95,7 +98,7 @@ impl ChannelInner {
         &self,
         is_a: bool,
     ) -> (
-        &SpinLock<alloc::collections::VecDeque<ChannelMessage>>,
+        &SpinLock<alloc::collections::VecDeque<Box<ChannelMessage>>>,
         &HeapWaitQueue,
         &HeapWaitQueue,
     ) {
@@ -167,10 +170,10 @@ impl ChannelEndpoint {
         let mut queue = send_q.lock();
         if queue.len() < MAX_BUFFERED_MESSAGES {
             let len = data.len();
-            queue.push_back(ChannelMessage {
+            queue.push_back(Box::new(ChannelMessage {
                 data: data.to_vec(),
                 attached,
-            });
+            }));
             drop(queue);
             peer_recv_wq.wake_one();
             Some(Ok(len))
@@ -258,10 +261,10 @@ impl Inode for ChannelEndpoint {
                 let mut queue = send_q.lock();
                 if queue.len() < MAX_BUFFERED_MESSAGES {
                     let len = buf.len();
-                    queue.push_back(ChannelMessage {
+                    queue.push_back(Box::new(ChannelMessage {
                         data: buf.to_vec(),
                         attached: None,
-                    });
+                    }));
                     drop(queue);
                     // Wake peer's recv side.
                     peer_recv_wq.wake_one();
@@ -0,0 +1,326 @@
+//! Interest lists for scalable readiness notification (`epoll`).
+//!
+//! An [`Epoll`] holds one [`Interest`] per watched fd. Each interest
+//! registers its own waker with the watched inode through
+//! [`Inode::poll_readiness`]; when the inode wakes it, the interest puts
+//! itself on the ready list. Waiting only re-checks the interests on that
+//! list, so its cost follows the number of ready fds rather than the number
+//! watched.
+//!
+//! Level-triggered interests go back on the ready list after being reported
+//! and are dropped from it once they stop being ready. Edge-triggered ones
+//! ([`EPOLLET`]) are reported once per wakeup, and one-shot ones
+//! ([`EPOLLONESHOT`]) are disabled after one event until modified.
+//!
+//! Interests hold their inode weakly: once every fd for it is closed, the
+//! interest is dropped the next time it is seen.
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::sync::{Arc, Weak};
+use alloc::task::Wake;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use core::task::Waker;
+
+use hadron_core::sync::{HeapWaitQueue, IrqSpinLock, SpinLock};
+use hadron_fs::{DirEntry, FsError, Inode, InodeType, Permissions};
+use hadron_syscall::{EPOLLET, EPOLLONESHOT, EpollEvent, POLLERR, POLLHUP, POLLIN};
+
+/// Interests whose inodes have woken them since they were last checked.
+struct ReadyList {
+    /// The interests, each at most once (see [`Interest::queued`]).
+    ///
+    /// Its capacity is kept at least the number of interests that could be
+    /// queued, so that waking an interest never allocates: inodes may wake
+    /// from interrupt context.
+    queue: IrqSpinLock<VecDeque<Arc<Interest>>>,
+    /// Woken when an interest is queued.
+    wq: HeapWaitQueue,
+}
+
+/// One watched fd.
+struct Interest {
+    /// The fd the inode was added as.
+    fd: u32,
+    /// The watched inode.
+    inode: Weak<dyn Inode>,
+    /// Requested events and [`EPOLLET`] / [`EPOLLONESHOT`]; 0 while a
+    /// one-shot interest is disabled.
+    events: AtomicU32,
+    /// Caller data returned with each event.
+    data: AtomicU64,
+    /// Whether this interest is on the ready list.
+    queued: AtomicBool,
+    /// Set when the interest is deleted, so that a queued copy is skipped.
+    removed: AtomicBool,
+    /// The list to put this interest on when it is woken.
+    ready: Weak<ReadyList>,
+}
+
+impl Interest {
+    /// Puts this interest on the ready list unless it already is or has
+    /// been deleted. A deleted interest no longer counts toward the list's
+    /// reserved capacity, so queueing it could allocate.
+    fn enqueue(self: &Arc<Self>) {
+        if self.removed.load(Ordering::Acquire) || self.queued.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        if let Some(ready) = self.ready.upgrade() {
+            ready.queue.lock().push_back(Arc::clone(self));
+            ready.wq.wake_all();
+        }
+    }
+
+    /// Checks the inode's readiness against the requested events,
+    /// registering this interest's waker first. Returns the events to
+    /// report, or `None` if the inode is gone.
+    fn check(self: &Arc<Self>) -> Option<u32> {
+        let inode = self.inode.upgrade()?;
+        let requested = self.events.load(Ordering::Acquire);
+        if requested == 0 {
+            return Some(0);
+        }
+        let waker = Waker::from(Arc::clone(self));
+        let readiness = u32::from(inode.poll_readiness(Some(&waker)));
+        let mask = (requested & !(EPOLLET | EPOLLONESHOT)) | u32::from(POLLERR | POLLHUP);
+        Some(readiness & mask)
+    }
+}
+
+impl Wake for Interest {
+    fn wake(self: Arc<Self>) {
+        self.enqueue();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.enqueue();
+    }
+}
+
+/// An epoll fd.
+pub struct Epoll {
+    /// Interests by fd number.
+    interests: SpinLock<BTreeMap<u32, Arc<Interest>>>,
+    /// Interests to check on the next wait.
+    ready: Arc<ReadyList>,
+}
+
+impl Epoll {
+    /// Creates an empty interest list.
+    pub fn new() -> Self {
+        Self {
+            interests: SpinLock::named("epoll_interests", BTreeMap::new()),
+            ready: Arc::new(ReadyList {
+                queue: IrqSpinLock::named("epoll_ready", VecDeque::new()),
+                wq: HeapWaitQueue::new(),
+            }),
+        }
+    }
+
+    /// Returns the live interest for `fd`, dropping it if its inode is gone.
+    fn live(interests: &mut BTreeMap<u32, Arc<Interest>>, fd: u32) -> Option<Arc<Interest>> {
+        let interest = interests.get(&fd)?;
+        if interest.inode.strong_count() == 0 {
+            interests.remove(&fd);
+            return None;
+        }
+        Some(Arc::clone(interest))
+    }
+
+    /// Starts watching `inode`, open as `fd`, for `event.events`.
+    ///
+    /// Returns [`FsError::AlreadyExists`] if `fd` is already watched.
+    pub fn add(&self, fd: u32, inode: &Arc<dyn Inode>, event: EpollEvent) -> Result<(), FsError> {
+        let interest = Arc::new(Interest {
+            fd,
+            inode: Arc::downgrade(inode),
+            events: AtomicU32::new(event.events),
+            data: AtomicU64::new(event.data),
+            queued: AtomicBool::new(false),
+            removed: AtomicBool::new(false),
+            ready: Arc::downgrade(&self.ready),
+        });
+        {
+            let mut interests = self.interests.lock();
+            if Self::live(&mut interests, fd).is_some() {
+                return Err(FsError::AlreadyExists);
+            }
+            interests.insert(fd, Arc::clone(&interest));
+            // Each interest is queued at most once, and stale entries only
+            // leave the queue, so this keeps `enqueue` from allocating.
+            let count = interests.len();
+            self.ready.queue.lock().reserve(count);
+        }
+        // Report an inode that is already ready on the next wait.
+        if interest.check().unwrap_or(0) != 0 {
+            interest.enqueue();
+        }
+        Ok(())
+    }
+
+    /// Replaces the events and data of the interest in `fd`, re-enabling a
+    /// disabled one-shot interest.
+    ///
+    /// Returns [`FsError::NotFound`] if `fd` is not watched.
+    pub fn modify(&self, fd: u32, event: EpollEvent) -> Result<(), FsError> {
+        let interest = Self::live(&mut self.interests.lock(), fd).ok_or(FsError::NotFound)?;
+        interest.data.store(event.data, Ordering::Release);
+        interest.events.store(event.events, Ordering::Release);
+        if interest.check().unwrap_or(0) != 0 {
+            interest.enqueue();
+        }
+        Ok(())
+    }
+
+    /// Stops watching `fd`.
+    ///
+    /// Returns [`FsError::NotFound`] if `fd` is not watched.
+    pub fn remove(&self, fd: u32) -> Result<(), FsError> {
+        let mut interests = self.interests.lock();
+        let interest = Self::live(&mut interests, fd).ok_or(FsError::NotFound)?;
+        interests.remove(&fd);
+        interest.removed.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// Takes up to `max` ready events without blocking.
+    pub fn collect(&self, max: usize) -> Vec<EpollEvent> {
+        let mut events = Vec::new();
+        let mut again = Vec::new();
+        while events.len() < max {
+            let Some(interest) = self.ready.queue.lock().pop_front() else {
+                break;
+            };
+            // Cleared before checking, so a wakeup from here on queues the
+            // interest again.
+            interest.queued.store(false, Ordering::Release);
+            if interest.removed.load(Ordering::Acquire) {
+                continue;
+            }
+            let Some(revents) = interest.check() else {
+                let mut interests = self.interests.lock();
+                if interests
+                    .get(&interest.fd)
+                    .is_some_and(|other| Arc::ptr_eq(other, &interest))
+                {
+                    interests.remove(&interest.fd);
+                }
+                continue;
+            };
+            if revents == 0 {
+                continue;
+            }
+            events.push(EpollEvent {
+                events: revents,
+                data: interest.data.load(Ordering::Acquire),
+            });
+            let requested = interest.events.load(Ordering::Acquire);
+            if requested & EPOLLONESHOT != 0 {
+                // A concurrent modify re-enables it; don't undo that.
+                let _ = interest.events.compare_exchange(
+                    requested,
+                    0,
+                    Ordering::AcqRel,
+                    Ordering::Acquire,
+                );
+            } else if requested & EPOLLET == 0 {
+                // Level-triggered: check again on the next wait.
+                again.push(interest);
+            }
+        }
+        for interest in again {
+            interest.enqueue();
+        }
+        events
+    }
+
+    /// Registers `waker` to be woken when an interest is queued.
+    pub fn register_waker(&self, waker: &Waker) {
+        self.ready.wq.register_waker(waker);
+    }
+}
+
+impl Default for Epoll {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inode for Epoll {
+    fn inode_type(&self) -> InodeType {
+        InodeType::CharDevice
+    }
+
+    fn size(&self) -> usize {
+        0
+    }
+
+    fn permissions(&self) -> Permissions {
+        Permissions::read_only()
+    }
+
+    fn read<'a>(
+        &'a self,
+        _offset: usize,
+        _buf: &'a mut [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::InvalidArgument) })
+    }
+
+    fn write<'a>(
+        &'a self,
+        _offset: usize,
+        _buf: &'a [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::InvalidArgument) })
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        _name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Arc<dyn Inode>, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn readdir(&self) -> Pin<Box<dyn Future<Output = Result<Vec<DirEntry>, FsError>> + Send + '_>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn create<'a>(
+        &'a self,
+        _name: &'a str,
+        _itype: InodeType,
+        _perms: Permissions,
+    ) -> Pin<Box<dyn Future<Output = Result<Arc<dyn Inode>, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn unlink<'a>(
+        &'a self,
+        _name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(), FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    /// Readable while any interest is queued. A queued interest may turn
+    /// out not to be ready, so a wait can still return no events.
+    fn poll_readiness(&self, waker: Option<&Waker>) -> u16 {
+        if let Some(w) = waker {
+            self.register_waker(w);
+        }
+        if self.ready.queue.lock().is_empty() {
+            0
+        } else {
+            POLLIN
+        }
+    }
+
+    fn as_any(&self) -> Option<&dyn core::any::Any> {
+        Some(self)
+    }
+}
@@ -0,0 +1,178 @@
+//! Counter-based event file descriptor (`eventfd`).
+//!
+//! An eventfd holds a 64-bit counter. Writing an 8-byte value adds it to
+//! the counter, blocking while the sum would exceed [`COUNTER_MAX`]. Reading
+//! blocks while the counter is zero, then returns the whole counter and
+//! resets it, or in semaphore mode returns 1 and decrements it.
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+
+use hadron_core::sync::{HeapWaitQueue, SpinLock};
+use hadron_fs::{DirEntry, FsError, Inode, InodeType, Permissions};
+
+/// Largest counter value; a write that would pass it blocks.
+pub const COUNTER_MAX: u64 = u64::MAX - 1;
+
+/// An eventfd.
+pub struct EventFd {
+    /// The counter.
+    counter: SpinLock<u64>,
+    /// Read returns 1 and decrements instead of returning the whole counter.
+    semaphore: bool,
+    /// Woken when the counter becomes non-zero.
+    read_wq: HeapWaitQueue,
+    /// Woken when the counter decreases.
+    write_wq: HeapWaitQueue,
+}
+
+impl EventFd {
+    /// Creates an eventfd whose counter starts at `initval`.
+    pub fn new(initval: u64, semaphore: bool) -> Self {
+        Self {
+            counter: SpinLock::named("eventfd_counter", initval),
+            semaphore,
+            read_wq: HeapWaitQueue::new(),
+            write_wq: HeapWaitQueue::new(),
+        }
+    }
+
+    /// Takes the value a read returns, or `None` if the counter is zero.
+    fn try_take(&self) -> Option<u64> {
+        let mut counter = self.counter.lock();
+        let value = match *counter {
+            0 => return None,
+            _ if self.semaphore => 1,
+            n => n,
+        };
+        *counter -= value;
+        drop(counter);
+        self.write_wq.wake_all();
+        Some(value)
+    }
+
+    /// Adds `value` to the counter. Returns `false` if it would pass
+    /// [`COUNTER_MAX`].
+    fn try_add(&self, value: u64) -> bool {
+        let mut counter = self.counter.lock();
+        if COUNTER_MAX - *counter < value {
+            return false;
+        }
+        *counter += value;
+        drop(counter);
+        if value != 0 {
+            self.read_wq.wake_all();
+        }
+        true
+    }
+}
+
+impl Inode for EventFd {
+    fn inode_type(&self) -> InodeType {
+        InodeType::CharDevice
+    }
+
+    fn size(&self) -> usize {
+        0
+    }
+
+    fn permissions(&self) -> Permissions {
+        Permissions::read_write()
+    }
+
+    fn read<'a>(
+        &'a self,
+        _offset: usize,
+        buf: &'a mut [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
+        Box::pin(async move {
+            let Some(out) = buf.get_mut(..8) else {
+                return Err(FsError::InvalidArgument);
+            };
+            // Register before checking to avoid a lost wakeup.
+            let value = core::future::poll_fn(|cx| {
+                self.read_wq.register_waker(cx.waker());
+                self.try_take().map_or(Poll::Pending, Poll::Ready)
+            })
+            .await;
+            out.copy_from_slice(&value.to_ne_bytes());
+            Ok(8)
+        })
+    }
+
+    fn write<'a>(
+        &'a self,
+        _offset: usize,
+        buf: &'a [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
+        Box::pin(async move {
+            let value = buf
+                .get(..8)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(u64::from_ne_bytes)
+                .filter(|&value| value != u64::MAX)
+                .ok_or(FsError::InvalidArgument)?;
+            core::future::poll_fn(|cx| {
+                self.write_wq.register_waker(cx.waker());
+                if self.try_add(value) {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await;
+            Ok(8)
+        })
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        _name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Arc<dyn Inode>, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn readdir(&self) -> Pin<Box<dyn Future<Output = Result<Vec<DirEntry>, FsError>> + Send + '_>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn create<'a>(
+        &'a self,
+        _name: &'a str,
+        _itype: InodeType,
+        _perms: Permissions,
+    ) -> Pin<Box<dyn Future<Output = Result<Arc<dyn Inode>, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn unlink<'a>(
+        &'a self,
+        _name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(), FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn poll_readiness(&self, waker: Option<&core::task::Waker>) -> u16 {
+        use hadron_syscall::{POLLIN, POLLOUT};
+
+        if let Some(w) = waker {
+            self.read_wq.register_waker(w);
+            self.write_wq.register_waker(w);
+        }
+        let counter = *self.counter.lock();
+        let mut events = 0u16;
+        if counter > 0 {
+            events |= POLLIN;
+        }
+        if counter < COUNTER_MAX {
+            events |= POLLOUT;
+        }
+        events
+    }
+}
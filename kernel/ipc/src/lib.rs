@@ -1,7 +1,8 @@
 //! Inter-process communication primitives for Hadron OS.
 //!
 //! Provides channels for message-oriented IPC, pipes for byte-oriented IPC,
-//! and service endpoints for dynamic client connections.
+//! eventfd counters, epoll interest lists, and service endpoints for dynamic
+//! client connections.
 //!
 //! This crate contains the pure IPC logic with no direct kernel dependencies.
 //! Kernel-specific IPC (futex, shared memory) remains in `hadron-kernel`.
@@ -13,12 +14,22 @@ extern crate alloc;
 
 pub mod channel;
 pub mod circular_buffer;
+pub mod epoll;
+pub mod eventfd;
 pub mod pipe;
 pub mod service;
 
 #[cfg(test)]
 mod tests {
+    use alloc::sync::Arc;
+    use core::task::{Context, Poll, Waker};
+
+    use hadron_fs::{FsError, Inode};
+    use hadron_syscall::{EPOLLET, EPOLLONESHOT, EpollEvent, POLLIN, POLLOUT};
+
     use super::circular_buffer::CircularBuffer;
+    use super::epoll::Epoll;
+    use super::eventfd::{COUNTER_MAX, EventFd};
 
     // -- CircularBuffer tests -------------------------------------------------
 
@@ -88,4 +99,175 @@ mod tests {
         assert_eq!(written, 4);
         assert!(buf.is_full());
     }
+
+    // -- EventFd tests --------------------------------------------------------
+
+    /// Polls an inode read or write once.
+    fn poll_once<T>(
+        fut: core::pin::Pin<alloc::boxed::Box<dyn Future<Output = T> + Send + '_>>,
+    ) -> Poll<T> {
+        let mut fut = fut;
+        fut.as_mut().poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    fn read_u64(efd: &EventFd) -> Poll<u64> {
+        let mut buf = [0u8; 8];
+        match poll_once(efd.read(0, &mut buf)) {
+            Poll::Ready(Ok(8)) => Poll::Ready(u64::from_ne_bytes(buf)),
+            Poll::Ready(other) => panic!("unexpected read result {other:?}"),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn write_u64(efd: &EventFd, value: u64) -> Poll<Result<usize, FsError>> {
+        poll_once(efd.write(0, &value.to_ne_bytes()))
+    }
+
+    #[test]
+    fn eventfd_read_takes_whole_counter() {
+        let efd = EventFd::new(2, false);
+        assert!(write_u64(&efd, 3).is_ready());
+        assert_eq!(read_u64(&efd), Poll::Ready(5));
+        assert_eq!(read_u64(&efd), Poll::Pending);
+    }
+
+    #[test]
+    fn eventfd_semaphore_reads_one_at_a_time() {
+        let efd = EventFd::new(2, true);
+        assert_eq!(read_u64(&efd), Poll::Ready(1));
+        assert_eq!(read_u64(&efd), Poll::Ready(1));
+        assert_eq!(read_u64(&efd), Poll::Pending);
+    }
+
+    #[test]
+    fn eventfd_readiness_follows_counter() {
+        let efd = EventFd::new(0, false);
+        assert_eq!(efd.poll_readiness(None), POLLOUT);
+        assert!(write_u64(&efd, COUNTER_MAX).is_ready());
+        assert_eq!(efd.poll_readiness(None), POLLIN);
+        assert!(
+            write_u64(&efd, 1).is_pending(),
+            "write past the maximum should block"
+        );
+    }
+
+    #[test]
+    fn eventfd_rejects_bad_writes() {
+        let efd = EventFd::new(0, false);
+        assert!(matches!(
+            write_u64(&efd, u64::MAX),
+            Poll::Ready(Err(FsError::InvalidArgument))
+        ));
+        assert!(matches!(
+            poll_once(efd.write(0, &[1, 2, 3])),
+            Poll::Ready(Err(FsError::InvalidArgument))
+        ));
+    }
+
+    // -- Epoll tests ----------------------------------------------------------
+
+    fn interest(events: u32, data: u64) -> EpollEvent {
+        EpollEvent { events, data }
+    }
+
+    /// Returns the events and data of each ready event, unpacked.
+    fn ready(epoll: &Epoll) -> alloc::vec::Vec<(u32, u64)> {
+        epoll
+            .collect(16)
+            .into_iter()
+            .map(|e| (e.events, e.data))
+            .collect()
+    }
+
+    fn watched_eventfd(epoll: &Epoll, flags: u32) -> Arc<EventFd> {
+        let efd = Arc::new(EventFd::new(0, false));
+        let inode: Arc<dyn Inode> = efd.clone();
+        epoll
+            .add(3, &inode, interest(u32::from(POLLIN) | flags, 7))
+            .unwrap();
+        efd
+    }
+
+    #[test]
+    fn epoll_level_triggered_reports_while_ready() {
+        let epoll = Epoll::new();
+        let efd = watched_eventfd(&epoll, 0);
+        assert!(ready(&epoll).is_empty());
+        assert_eq!(epoll.poll_readiness(None), 0);
+
+        assert!(write_u64(&efd, 1).is_ready());
+        assert_eq!(epoll.poll_readiness(None), POLLIN);
+        assert_eq!(ready(&epoll), [(u32::from(POLLIN), 7)]);
+        assert_eq!(ready(&epoll), [(u32::from(POLLIN), 7)]);
+
+        assert_eq!(read_u64(&efd), Poll::Ready(1));
+        assert!(ready(&epoll).is_empty());
+    }
+
+    #[test]
+    fn epoll_edge_triggered_reports_each_change_once() {
+        let epoll = Epoll::new();
+        let efd = watched_eventfd(&epoll, EPOLLET);
+        assert!(write_u64(&efd, 1).is_ready());
+        assert_eq!(ready(&epoll), [(u32::from(POLLIN), 7)]);
+        assert!(ready(&epoll).is_empty());
+
+        assert!(write_u64(&efd, 1).is_ready());
+        assert_eq!(ready(&epoll).len(), 1);
+    }
+
+    #[test]
+    fn epoll_oneshot_disables_until_modified() {
+        let epoll = Epoll::new();
+        let efd = watched_eventfd(&epoll, EPOLLONESHOT);
+        assert!(write_u64(&efd, 1).is_ready());
+        assert_eq!(ready(&epoll).len(), 1);
+        assert!(write_u64(&efd, 1).is_ready());
+        assert!(ready(&epoll).is_empty());
+
+        epoll
+            .modify(3, interest(u32::from(POLLIN) | EPOLLONESHOT, 9))
+            .unwrap();
+        assert_eq!(ready(&epoll), [(u32::from(POLLIN), 9)]);
+    }
+
+    #[test]
+    fn epoll_add_modify_remove_errors() {
+        let epoll = Epoll::new();
+        let efd = watched_eventfd(&epoll, 0);
+        let inode: Arc<dyn Inode> = efd.clone();
+        assert_eq!(
+            epoll.add(3, &inode, interest(u32::from(POLLIN), 0)),
+            Err(FsError::AlreadyExists)
+        );
+
+        assert!(write_u64(&efd, 1).is_ready());
+        assert_eq!(epoll.remove(3), Ok(()));
+        assert!(ready(&epoll).is_empty(), "removed fds are not reported");
+        assert_eq!(epoll.remove(3), Err(FsError::NotFound));
+        assert_eq!(
+            epoll.modify(3, interest(u32::from(POLLIN), 0)),
+            Err(FsError::NotFound)
+        );
+    }
+
+    #[test]
+    fn epoll_drops_closed_inodes() {
+        let epoll = Epoll::new();
+        let efd = watched_eventfd(&epoll, 0);
+        assert!(write_u64(&efd, 1).is_ready());
+        drop(efd);
+        assert!(ready(&epoll).is_empty());
+
+        // The fd number can be added again once its inode is gone.
+        let _efd = watched_eventfd(&epoll, 0);
+    }
+
+    #[test]
+    fn epoll_reports_only_requested_events() {
+        let epoll = Epoll::new();
+        let efd: Arc<dyn Inode> = Arc::new(EventFd::new(1, false));
+        epoll.add(4, &efd, interest(u32::from(POLLOUT), 1)).unwrap();
+        assert_eq!(ready(&epoll), [(u32::from(POLLOUT), 1)]);
+    }
 }
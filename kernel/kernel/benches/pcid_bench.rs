@@ -0,0 +1,190 @@
+//! Address-space switch microbenchmarks.
+//!
+//! Compares loading a user address space the way the kernel did before
+//! PCIDs (an untagged CR3 write, which flushes the TLB) against the PCID
+//! allocator's tagged switch, on the two paths that pay for it:
+//!
+//! - **Syscall copy**: the kernel → user → kernel CR3 round trip a
+//!   blocking syscall makes to copy its arguments or results.
+//! - **Context switch**: alternating between two processes, each touching
+//!   a small working set after the switch, as a pipeline of small
+//!   processes does.
+//!
+//! On a CPU without PCIDs both variants take the same path.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(hadron_bench::bench_runner)]
+#![reexport_test_harness_main = "bench_main"]
+
+extern crate alloc;
+
+hadron_bench::bench_entry_point_with_init!();
+
+use hadron_bench::{Bencher, black_box};
+use hadron_kernel::addr::{PhysAddr, VirtAddr};
+use hadron_kernel::arch::x86_64::paging::PageTableMapper;
+use hadron_kernel::arch::x86_64::pcid;
+use hadron_kernel::arch::x86_64::registers::control::{Cr3, Cr4, Cr4Flags};
+use hadron_kernel::mm::address_space::AddressSpace;
+use hadron_kernel::mm::mapper::MapFlags;
+use hadron_kernel::mm::pmm::{self, BuddyFrameAllocRef};
+use hadron_kernel::mm::{FrameAllocator, hhdm};
+use hadron_kernel::paging::{Page, PhysFrame, Size4KiB};
+
+/// Base of each process's working set.
+const WORKING_SET_BASE: u64 = 0x4000_0000;
+
+fn dealloc_frame(frame: PhysFrame<Size4KiB>) {
+    // SAFETY: The frame belonged to a dropped address space.
+    unsafe { pmm::free_frame(frame) };
+}
+
+/// Creates a user address space with `pages` writable pages mapped at
+/// [`WORKING_SET_BASE`]. The working-set frames are not reclaimed.
+fn process(pages: usize) -> AddressSpace<PageTableMapper> {
+    let hhdm = hhdm::offset();
+    pmm::with(|pmm| {
+        let mut alloc = BuddyFrameAllocRef(pmm);
+        // SAFETY: CR3 holds the live kernel PML4.
+        let space = unsafe {
+            AddressSpace::new_user(
+                Cr3::read(),
+                PageTableMapper::new(hhdm),
+                hhdm,
+                &mut alloc,
+                dealloc_frame,
+            )
+            .expect("create address space")
+        };
+        for i in 0..pages as u64 {
+            let frame = FrameAllocator::<Size4KiB>::allocate_frame(&mut alloc).expect("alloc");
+            let page = Page::containing_address(VirtAddr::new(WORKING_SET_BASE + i * 4096));
+            space
+                .map_user_page(page, frame, MapFlags::WRITABLE, &mut alloc)
+                .expect("map")
+                .ignore();
+        }
+        space
+    })
+}
+
+/// Clears CR4.SMAP so that [`touch`] can read user pages directly: the
+/// benchmark harness does not patch in the `stac`/`clac` pairs of the
+/// kernel's user-copy routines.
+fn allow_user_access() {
+    // SAFETY: Only the benchmarks run on this CPU.
+    unsafe { Cr4::write(Cr4::read() - Cr4Flags::SMAP) };
+}
+
+/// Reads one byte from each of the first `pages` pages of the working set.
+fn touch(pages: usize) {
+    for i in 0..pages as u64 {
+        let addr = (WORKING_SET_BASE + i * 4096) as *const u8;
+        // SAFETY: The working set is mapped in the address space currently
+        // loaded, and SMAP is off.
+        black_box(unsafe { core::ptr::read_volatile(addr) });
+    }
+}
+
+/// Loads `space` with an untagged CR3 write, as before PCIDs.
+fn load_flush(space: &AddressSpace<PageTableMapper>) {
+    // SAFETY: The address space copies the kernel upper half.
+    unsafe { Cr3::write(space.root_phys()) };
+}
+
+/// Loads `space` through the PCID allocator.
+fn load_pcid(space: &AddressSpace<PageTableMapper>) {
+    // SAFETY: The address space copies the kernel upper half.
+    black_box(unsafe { pcid::switch_to(space) });
+}
+
+fn restore(kernel_cr3: PhysAddr) {
+    // SAFETY: Restoring the kernel page tables.
+    unsafe { Cr3::write(kernel_cr3) };
+}
+
+// ── Syscall copy ───────────────────────────────────────────────────────
+
+#[test_case]
+fn bench_syscall_copy_flush(b: &mut Bencher) {
+    allow_user_access();
+    let kernel_cr3 = Cr3::read();
+    let p = process(1);
+    b.iter(|| {
+        load_flush(&p);
+        touch(1);
+        restore(kernel_cr3);
+    });
+}
+
+#[test_case]
+fn bench_syscall_copy_pcid(b: &mut Bencher) {
+    allow_user_access();
+    let kernel_cr3 = Cr3::read();
+    let p = process(1);
+    b.iter(|| {
+        load_pcid(&p);
+        touch(1);
+        restore(kernel_cr3);
+    });
+}
+
+// ── Context switch ─────────────────────────────────────────────────────
+
+#[test_case]
+fn bench_context_switch_flush_16(b: &mut Bencher) {
+    allow_user_access();
+    let kernel_cr3 = Cr3::read();
+    let (p, q) = (process(16), process(16));
+    b.iter(|| {
+        load_flush(&p);
+        touch(16);
+        load_flush(&q);
+        touch(16);
+    });
+    restore(kernel_cr3);
+}
+
+#[test_case]
+fn bench_context_switch_pcid_16(b: &mut Bencher) {
+    allow_user_access();
+    let kernel_cr3 = Cr3::read();
+    let (p, q) = (process(16), process(16));
+    b.iter(|| {
+        load_pcid(&p);
+        touch(16);
+        load_pcid(&q);
+        touch(16);
+    });
+    restore(kernel_cr3);
+}
+
+#[test_case]
+fn bench_context_switch_flush_64(b: &mut Bencher) {
+    allow_user_access();
+    let kernel_cr3 = Cr3::read();
+    let (p, q) = (process(64), process(64));
+    b.iter(|| {
+        load_flush(&p);
+        touch(64);
+        load_flush(&q);
+        touch(64);
+    });
+    restore(kernel_cr3);
+}
+
+#[test_case]
+fn bench_context_switch_pcid_64(b: &mut Bencher) {
+    allow_user_access();
+    let kernel_cr3 = Cr3::read();
+    let (p, q) = (process(64), process(64));
+    b.iter(|| {
+        load_pcid(&p);
+        touch(64);
+        load_pcid(&q);
+        touch(64);
+    });
+    restore(kernel_cr3);
+}
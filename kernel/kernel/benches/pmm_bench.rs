@@ -0,0 +1,157 @@
+//! Physical frame allocator microbenchmarks.
+//!
+//! Compares the bitmap allocator against the buddy allocator on the same
+//! physical memory, and measures the per-CPU hot cache fast path of the
+//! global PMM. Each allocator under test is built over its own 4 MiB arena
+//! carved out of the global PMM.
+
+#![no_std]
+#![no_main]
+#![feature(custom_test_frameworks)]
+#![test_runner(hadron_bench::bench_runner)]
+#![reexport_test_harness_main = "bench_main"]
+
+extern crate alloc;
+
+hadron_bench::bench_entry_point_with_init!();
+
+use alloc::vec::Vec;
+use hadron_bench::{Bencher, black_box};
+use hadron_kernel::mm::PhysMemoryRegion;
+use hadron_kernel::mm::hhdm;
+use hadron_kernel::mm::pmm::{self, BitmapAllocator, BuddyAllocator, MAX_ORDER};
+
+/// Arena size in frames (4 MiB, the largest buddy block).
+const ARENA_FRAMES: usize = 1 << MAX_ORDER;
+
+/// Carves a 4 MiB arena out of the global PMM and describes it as a
+/// single usable region.
+fn arena() -> [PhysMemoryRegion; 1] {
+    let base = pmm::with(|pmm| pmm.allocate_frames(ARENA_FRAMES))
+        .expect("failed to allocate benchmark arena");
+    [PhysMemoryRegion {
+        start: base.start_address(),
+        size: (ARENA_FRAMES * 4096) as u64,
+        usable: true,
+    }]
+}
+
+fn bitmap() -> BitmapAllocator {
+    // SAFETY: The arena is exclusively owned by the benchmark.
+    unsafe { BitmapAllocator::new(&arena(), hhdm::offset()).expect("bitmap init") }
+}
+
+fn buddy() -> BuddyAllocator {
+    // SAFETY: The arena is exclusively owned by the benchmark.
+    unsafe { BuddyAllocator::new(&arena(), hhdm::offset()).expect("buddy init") }
+}
+
+/// Allocates the first quarter of the arena one frame at a time, then frees
+/// every other frame, leaving a checkerboard of isolated free frames below
+/// the remaining contiguous space.
+macro_rules! fragment {
+    ($alloc:expr) => {{
+        let frames: Vec<_> = (0..ARENA_FRAMES / 4)
+            .map(|_| $alloc.allocate_frame().expect("alloc"))
+            .collect();
+        for frame in frames.into_iter().step_by(2) {
+            // SAFETY: The frame was allocated above and is unused.
+            let _ = unsafe { $alloc.deallocate_frame(frame) };
+        }
+    }};
+}
+
+// ── Single frames ──────────────────────────────────────────────────────
+
+#[test_case]
+fn bench_bitmap_alloc_free_1(b: &mut Bencher) {
+    let mut a = bitmap();
+    b.iter(|| {
+        let frame = a.allocate_frame().expect("alloc");
+        // SAFETY: The frame was just allocated and is unused.
+        let _ = unsafe { a.deallocate_frame(black_box(frame)) };
+    });
+}
+
+#[test_case]
+fn bench_buddy_alloc_free_1(b: &mut Bencher) {
+    let mut a = buddy();
+    b.iter(|| {
+        let frame = a.allocate_frame().expect("alloc");
+        // SAFETY: The frame was just allocated and is unused.
+        let _ = unsafe { a.deallocate_frame(black_box(frame)) };
+    });
+}
+
+#[test_case]
+fn bench_pmm_hot_cache_alloc_free_1(b: &mut Bencher) {
+    b.iter(|| {
+        let frame = pmm::alloc_frame().expect("alloc");
+        // SAFETY: The frame was just allocated and is unused.
+        unsafe { pmm::free_frame(black_box(frame)) };
+    });
+}
+
+// ── Contiguous runs ────────────────────────────────────────────────────
+
+#[test_case]
+fn bench_bitmap_alloc_free_64(b: &mut Bencher) {
+    let mut a = bitmap();
+    b.iter(|| {
+        let frame = a.allocate_frames(64).expect("alloc");
+        // SAFETY: The run was just allocated and is unused.
+        let _ = unsafe { a.deallocate_frames(black_box(frame), 64) };
+    });
+}
+
+#[test_case]
+fn bench_buddy_alloc_free_64(b: &mut Bencher) {
+    let mut a = buddy();
+    b.iter(|| {
+        let frame = a.allocate_frames(64).expect("alloc");
+        // SAFETY: The run was just allocated and is unused.
+        let _ = unsafe { a.deallocate_frames(black_box(frame), 64) };
+    });
+}
+
+#[test_case]
+fn bench_bitmap_alloc_free_64_fragmented(b: &mut Bencher) {
+    let mut a = bitmap();
+    fragment!(a);
+    b.iter(|| {
+        let frame = a.allocate_frames(64).expect("alloc");
+        // SAFETY: The run was just allocated and is unused.
+        let _ = unsafe { a.deallocate_frames(black_box(frame), 64) };
+    });
+}
+
+#[test_case]
+fn bench_buddy_alloc_free_64_fragmented(b: &mut Bencher) {
+    let mut a = buddy();
+    fragment!(a);
+    b.iter(|| {
+        let frame = a.allocate_frames(64).expect("alloc");
+        // SAFETY: The run was just allocated and is unused.
+        let _ = unsafe { a.deallocate_frames(black_box(frame), 64) };
+    });
+}
+
+#[test_case]
+fn bench_bitmap_alloc_free_512(b: &mut Bencher) {
+    let mut a = bitmap();
+    b.iter(|| {
+        let frame = a.allocate_frames(512).expect("alloc");
+        // SAFETY: The run was just allocated and is unused.
+        let _ = unsafe { a.deallocate_frames(black_box(frame), 512) };
+    });
+}
+
+#[test_case]
+fn bench_buddy_alloc_free_512(b: &mut Bencher) {
+    let mut a = buddy();
+    b.iter(|| {
+        let frame = a.allocate_frames(512).expect("alloc");
+        // SAFETY: The run was just allocated and is unused.
+        let _ = unsafe { a.deallocate_frames(black_box(frame), 512) };
+    });
+}
@@ -18,3 +18,23 @@ pub fn platform_init(_boot_info: &impl crate::boot::BootInfo) {
 pub fn spawn_platform_tasks() {
     todo!("aarch64 spawn_platform_tasks")
 }
+
+/// Returns a boot-time randomization seed (RNDR / CNTVCT).
+pub fn boot_entropy() -> u64 {
+    todo!("aarch64 boot_entropy")
+}
+
+/// Returns a runtime random value (RNDR / CNTVCT).
+pub fn random_u64() -> u64 {
+    todo!("aarch64 random_u64")
+}
+
+/// Copies from user memory with fault recovery.
+pub fn copy_from_user(_dst: &mut [u8], _src: usize) -> Result<(), usize> {
+    todo!("aarch64 copy_from_user")
+}
+
+/// Copies to user memory with fault recovery.
+pub fn copy_to_user(_dst: usize, _src: &[u8]) -> Result<(), usize> {
+    todo!("aarch64 copy_to_user")
+}
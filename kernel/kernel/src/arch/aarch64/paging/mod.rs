@@ -2,7 +2,7 @@
 
 use crate::addr::{PhysAddr, VirtAddr};
 use crate::mm::mapper::{self, MapFlags, MapFlush, UnmapError};
-use crate::paging::{Page, PhysFrame, Size4KiB};
+use crate::paging::{Page, PhysFrame, Size2MiB, Size4KiB};
 
 /// AArch64 page table mapper (stub).
 pub struct AArch64PageMapper {
@@ -48,6 +48,49 @@ unsafe impl mapper::PageMapper<Size4KiB> for AArch64PageMapper {
     }
 }
 
+// SAFETY: stub — all methods `todo!()`.
+unsafe impl mapper::PageMapper<Size2MiB> for AArch64PageMapper {
+    unsafe fn map(
+        &self,
+        _root: PhysAddr,
+        _page: Page<Size2MiB>,
+        _frame: PhysFrame<Size2MiB>,
+        _flags: MapFlags,
+        _alloc: &mut dyn FnMut() -> PhysFrame<Size4KiB>,
+    ) -> MapFlush {
+        todo!("aarch64 map 2MiB")
+    }
+
+    unsafe fn unmap(
+        &self,
+        _root: PhysAddr,
+        _page: Page<Size2MiB>,
+    ) -> Result<(PhysFrame<Size2MiB>, MapFlush), UnmapError> {
+        todo!("aarch64 unmap 2MiB")
+    }
+
+    unsafe fn update_flags(
+        &self,
+        _root: PhysAddr,
+        _page: Page<Size2MiB>,
+        _flags: MapFlags,
+    ) -> Result<MapFlush, UnmapError> {
+        todo!("aarch64 update_flags 2MiB")
+    }
+}
+
+// SAFETY: stub — `todo!()`.
+unsafe impl mapper::PageSplitter<Size2MiB> for AArch64PageMapper {
+    unsafe fn split(
+        &self,
+        _root: PhysAddr,
+        _page: Page<Size2MiB>,
+        _alloc: &mut dyn FnMut() -> PhysFrame<Size4KiB>,
+    ) -> Result<MapFlush, UnmapError> {
+        todo!("aarch64 split 2MiB")
+    }
+}
+
 // SAFETY: stub — `todo!()`.
 unsafe impl mapper::PageTranslator for AArch64PageMapper {
     unsafe fn translate_addr(&self, _root: PhysAddr, _virt: VirtAddr) -> Option<PhysAddr> {
@@ -20,6 +20,8 @@ pub fn cpu_init() {
         unsafe {
             x86_64::fpu::enable_fpu_support();
         }
+        unsafe { x86_64::uaccess::enable_protections() };
+        unsafe { x86_64::pcid::init_cpu() };
     }
     #[cfg(target_arch = "aarch64")]
     {
@@ -110,6 +112,66 @@ fn acpi_platform_devices() -> alloc::vec::Vec<crate::driver_api::acpi_device::Ac
     devices
 }
 
+/// Returns a best-effort 64-bit seed for boot-time randomization (KASLR).
+pub fn boot_entropy() -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        x86_64::instructions::random::boot_entropy()
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        aarch64::boot_entropy()
+    }
+}
+
+/// Returns a 64-bit random value for runtime randomization (user ASLR).
+pub fn random_u64() -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        x86_64::instructions::random::random_u64()
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        aarch64::random_u64()
+    }
+}
+
+/// Copies `dst.len()` bytes from user address `src` into `dst`, returning
+/// `Err(n)` with the number of bytes left uncopied on a fault.
+///
+/// # Safety
+///
+/// `src..src + dst.len()` must lie entirely in the user half of the
+/// address space.
+pub unsafe fn copy_from_user(dst: &mut [u8], src: usize) -> Result<(), usize> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        unsafe { x86_64::uaccess::copy_from_user(dst, src) }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        aarch64::copy_from_user(dst, src)
+    }
+}
+
+/// Copies `src` to user address `dst`, returning `Err(n)` with the number
+/// of bytes left uncopied on a fault.
+///
+/// # Safety
+///
+/// `dst..dst + src.len()` must lie entirely in the user half of the
+/// address space.
+pub unsafe fn copy_to_user(dst: usize, src: &[u8]) -> Result<(), usize> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        unsafe { x86_64::uaccess::copy_to_user(dst, src) }
+    }
+    #[cfg(target_arch = "aarch64")]
+    {
+        aarch64::copy_to_user(dst, src)
+    }
+}
+
 /// Spawn arch-specific async tasks.
 ///
 /// The serial echo task is now spawned by the serial driver during probe
@@ -202,8 +202,9 @@ pub(crate) extern "C" fn timer_tick_and_eoi() {
 /// LAPIC timer interrupt handler.
 #[cfg(hadron_apic)]
 fn timer_handler(_vector: IrqVector) {
-    // Wake tasks whose sleep deadline has expired.
-    crate::sched::timer::wake_expired(crate::time::Time::timer_ticks());
+    // Wake tasks whose timers have expired.
+    crate::sched::timer::wake_expired(crate::time::Time::boot_nanos());
+    crate::sched::tick::timer_tick();
 
     // Signal the executor to rotate to the next task.
     crate::sched::set_preempt_pending();
@@ -767,9 +768,11 @@ fn calibrate_and_start_timer(lapic: &LocalApic, hpet: Option<&Hpet>) {
     crate::arch::x86_64::interrupts::dispatch::register_handler(vectors::TIMER, timer_handler)
         .expect("Failed to register timer handler");
 
-    // Calibration: measure how many LAPIC timer ticks occur in 10ms.
+    // Calibration: measure how many LAPIC timer ticks and TSC cycles occur
+    // in 10ms.
     let divide = 16u8;
     lapic.start_timer_oneshot(vectors::TIMER.as_irq_vector(), u32::MAX, divide);
+    let tsc_start = crate::arch::x86_64::hw::tsc::read_tsc();
 
     // Wait 10ms using HPET or PIT.
     if let Some(hpet) = hpet {
@@ -780,8 +783,13 @@ fn calibrate_and_start_timer(lapic: &LocalApic, hpet: Option<&Hpet>) {
     }
 
     let elapsed = u32::MAX - lapic.timer_current_count();
+    let tsc_elapsed = crate::arch::x86_64::hw::tsc::read_tsc().wrapping_sub(tsc_start);
     lapic.stop_timer();
 
+    let tsc_hz = tsc_elapsed * 100;
+    crate::time::Time::set_tsc_frequency(tsc_hz);
+    crate::kinfo!("Timer: TSC calibrated at {} MHz", tsc_hz / 1_000_000);
+
     // Calculate ticks per second: elapsed in 10ms, so * 100.
     let ticks_per_second = u64::from(elapsed) * 100;
     let ticks_per_ms = ticks_per_second / 1000;
@@ -123,6 +123,9 @@ fn detect() -> CpuFeatures {
         if leaf1.ecx & (1 << 9) != 0 {
             features |= CpuFeatures::SSSE3;
         }
+        if leaf1.ecx & (1 << 17) != 0 {
+            features |= CpuFeatures::PCID;
+        }
         if leaf1.ecx & (1 << 19) != 0 {
             features |= CpuFeatures::SSE4_1;
         }
@@ -132,12 +135,18 @@ fn detect() -> CpuFeatures {
         if leaf1.ecx & (1 << 23) != 0 {
             features |= CpuFeatures::POPCNT;
         }
+        if leaf1.ecx & (1 << 24) != 0 {
+            features |= CpuFeatures::TSC_DEADLINE;
+        }
         if leaf1.ecx & (1 << 26) != 0 {
             features |= CpuFeatures::XSAVE;
         }
         if leaf1.ecx & (1 << 28) != 0 {
             features |= CpuFeatures::AVX;
         }
+        if leaf1.ecx & (1 << 30) != 0 {
+            features |= CpuFeatures::RDRAND;
+        }
 
         // EDX bits
         if leaf1.edx & (1 << 26) != 0 {
@@ -158,9 +167,21 @@ fn detect() -> CpuFeatures {
         if leaf7.ebx & (1 << 8) != 0 {
             features |= CpuFeatures::BMI2;
         }
+        if leaf7.ebx & (1 << 7) != 0 {
+            features |= CpuFeatures::SMEP;
+        }
         if leaf7.ebx & (1 << 9) != 0 {
             features |= CpuFeatures::ERMS;
         }
+        if leaf7.ebx & (1 << 10) != 0 {
+            features |= CpuFeatures::INVPCID;
+        }
+        if leaf7.ebx & (1 << 20) != 0 {
+            features |= CpuFeatures::SMAP;
+        }
+        if leaf7.ecx & (1 << 2) != 0 {
+            features |= CpuFeatures::UMIP;
+        }
     }
 
     // Extended leaf 0x8000_0001: AMD-style extended features.
@@ -9,10 +9,15 @@
 //! 2. **`KernelFpuGuard`** — RAII guard that saves/restores FPU state and
 //!    disables preemption (interrupts) so the kernel can safely use XMM/YMM
 //!    registers for bulk operations.
+//!
+//! 3. **`save_user_state()` / `sanitize_mxcsr()`** — Helpers for the user
+//!    FXSAVE area, which signal frames expose to handlers.
 
 #[cfg(hadron_kernel_fpu)]
 use core::cell::UnsafeCell;
 
+use hadron_core::sync::atomic::{AtomicU32, Ordering};
+
 use super::cpuid::{self, CpuFeatures};
 use super::registers::control::{Cr4, Cr4Flags};
 
@@ -109,6 +114,46 @@ pub unsafe fn enable_fpu_support() {
     } else {
         unsafe { Cr4::write(cr4) };
     }
+
+    // Record which MXCSR bits FXRSTOR accepts.
+    let mut area = FxSaveArea([0; 512]);
+    unsafe { save_user_state(area.0.as_mut_ptr()) };
+    let mask = u32::from_le_bytes([area.0[28], area.0[29], area.0[30], area.0[31]]);
+    if mask != 0 {
+        MXCSR_MASK.store(mask, Ordering::Relaxed);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// User FPU state
+// ---------------------------------------------------------------------------
+
+/// MXCSR bits supported by the CPU. FXSAVE reports 0 when the CPU predates
+/// the field, meaning the architectural default of `0xFFBF`.
+static MXCSR_MASK: AtomicU32 = AtomicU32::new(0xFFBF);
+
+/// 16-byte aligned scratch area for FXSAVE64.
+#[repr(C, align(16))]
+struct FxSaveArea([u8; 512]);
+
+/// Saves the live x87/SSE state into a 512-byte FXSAVE area.
+///
+/// The kernel is built without SSE, so between a user trap and the next
+/// user entry these registers still hold the user's state.
+///
+/// # Safety
+///
+/// `area` must be valid for 512 bytes of writes and 16-byte aligned.
+pub unsafe fn save_user_state(area: *mut u8) {
+    unsafe {
+        core::arch::asm!("fxsave64 [{}]", in(reg) area, options(nostack));
+    }
+}
+
+/// Clears the MXCSR bits the CPU does not support, so that FXRSTOR of a
+/// user-supplied area cannot raise #GP.
+pub fn sanitize_mxcsr(mxcsr: u32) -> u32 {
+    mxcsr & MXCSR_MASK.load(Ordering::Relaxed)
 }
 
 // ---------------------------------------------------------------------------
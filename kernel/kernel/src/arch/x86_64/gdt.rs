@@ -10,7 +10,9 @@
 //!   <https://wiki.osdev.org/Task_State_Segment>
 
 use core::cell::UnsafeCell;
+use core::ops::Range;
 
+use crate::arch::x86_64::kpti::PageAligned;
 use crate::arch::x86_64::structures::gdt::{
     Descriptor, GlobalDescriptorTable, SegmentSelector, TaskStateSegment,
 };
@@ -24,7 +26,9 @@ const DOUBLE_FAULT_STACK_SIZE: usize = 16384;
 pub const DOUBLE_FAULT_IST_INDEX: u8 = 1;
 
 /// Dedicated stack for the double-fault handler.
-#[repr(align(16))]
+///
+/// Page-aligned so KPTI can map it into the shadow page tables on its own.
+#[repr(align(4096))]
 #[allow(dead_code, reason = "backing storage for double-fault stack")]
 struct AlignedStack([u8; DOUBLE_FAULT_STACK_SIZE]);
 
@@ -55,8 +59,8 @@ impl<T> SyncUnsafeCell<T> {
 
 /// Static Task State Segment, wrapped in `SyncUnsafeCell` to allow mutation of
 /// RSP0 during context switches without UB (the CPU reads the TSS directly
-/// from memory).
-static TSS: LazyLock<SyncUnsafeCell<TaskStateSegment>> = LazyLock::new(|| {
+/// from memory). Page-aligned, like the GDT, for KPTI.
+static TSS: LazyLock<PageAligned<SyncUnsafeCell<TaskStateSegment>>> = LazyLock::new(|| {
     let mut tss = TaskStateSegment::new();
     // IST entries are 1-indexed in the IDT but 0-indexed in the TSS array.
     tss.interrupt_stack_table[(DOUBLE_FAULT_IST_INDEX - 1) as usize] = {
@@ -65,7 +69,7 @@ static TSS: LazyLock<SyncUnsafeCell<TaskStateSegment>> = LazyLock::new(|| {
     };
     // Set RSP0 to early BSS stack (same as percpu.kernel_rsp during early boot).
     tss.privilege_stack_table[0] = crate::percpu::early_kernel_rsp();
-    SyncUnsafeCell::new(tss)
+    PageAligned(SyncUnsafeCell::new(tss))
 });
 
 /// Cached segment selectors from GDT initialization.
@@ -82,15 +86,57 @@ pub struct Selectors {
     pub tss: SegmentSelector,
 }
 
-/// Static GDT and its selectors.
-static GDT: LazyLock<(GlobalDescriptorTable, Selectors)> = LazyLock::new(|| {
+/// A CPU's descriptor tables and double-fault stack.
+///
+/// Returned by [`bsp_tables`] and [`init_ap`] for code that needs their
+/// addresses, such as KPTI, which maps them into the shadow page tables.
+pub struct CpuTables {
+    /// The CPU's TSS.
+    tss: *mut TaskStateSegment,
+    /// Address range of the GDT.
+    pub gdt: Range<u64>,
+    /// Address range of the double-fault IST stack.
+    pub double_fault_stack: Range<u64>,
+}
+
+impl CpuTables {
+    /// Address range of the TSS.
+    pub fn tss(&self) -> Range<u64> {
+        let start = self.tss as u64;
+        start..start + size_of::<TaskStateSegment>() as u64
+    }
+
+    /// Updates RSP0 in this CPU's TSS. See [`set_tss_rsp0`].
+    ///
+    /// # Safety
+    ///
+    /// Must be called on the CPU that owns these tables, with interrupts
+    /// disabled. `rsp` must point to the top of a valid, mapped stack.
+    pub unsafe fn set_rsp0(&self, rsp: u64) {
+        // SAFETY: The TSS lives forever; the caller guarantees exclusive
+        // access from software.
+        unsafe { (*self.tss).privilege_stack_table[0] = rsp };
+    }
+}
+
+hadron_core::ro_after_init! {
+    /// Static GDT and its selectors, read-only after boot.
+    ///
+    /// The CPU writes a descriptor only to set its accessed bit on a segment
+    /// load, which the descriptors preset, and the TSS busy bit on `ltr`,
+    /// which [`init`] executes before the section is write-protected.
+    static GDT: LazyLock<(PageAligned<GlobalDescriptorTable>, Selectors)> = LazyLock::new(build);
+}
+
+/// Builds the BSP's GDT.
+fn build() -> (PageAligned<GlobalDescriptorTable>, Selectors) {
     let mut gdt = GlobalDescriptorTable::new();
     let kernel_code = gdt.append(Descriptor::kernel_code_segment());
     let kernel_data = gdt.append(Descriptor::kernel_data_segment());
     // user_data before user_code: SYSRET requires SS at STAR[63:48]+8, CS at STAR[63:48]+16
     let user_data = gdt.append(Descriptor::user_data_segment());
     let user_code = gdt.append(Descriptor::user_code_segment());
-    // SAFETY: The TSS is fully initialized by the LazyLock closure above.
+    // SAFETY: The TSS is fully initialized by its LazyLock closure.
     // We take a shared reference for the descriptor, which only reads the address.
     let tss = gdt.append(Descriptor::tss_segment(unsafe { &*TSS.get() }));
     let selectors = Selectors {
@@ -100,8 +146,8 @@ static GDT: LazyLock<(GlobalDescriptorTable, Selectors)> = LazyLock::new(|| {
         user_data,
         tss,
     };
-    (gdt, selectors)
-});
+    (PageAligned(gdt), selectors)
+}
 
 /// Initializes the GDT, reloads all segment registers, and loads the TSS.
 ///
@@ -156,49 +202,67 @@ pub fn selectors() -> &'static Selectors {
     &GDT.1
 }
 
+/// Returns the BSP's descriptor tables.
+pub fn bsp_tables() -> CpuTables {
+    let gdt = &*GDT.0 as *const GlobalDescriptorTable as u64;
+    let df_stack = &DOUBLE_FAULT_STACK as *const _ as u64;
+    CpuTables {
+        tss: TSS.get(),
+        gdt: gdt..gdt + size_of::<GlobalDescriptorTable>() as u64,
+        double_fault_stack: df_stack..df_stack + DOUBLE_FAULT_STACK_SIZE as u64,
+    }
+}
+
 /// Initializes a per-CPU GDT and TSS for an Application Processor.
 ///
 /// Allocates a new TSS (with double-fault IST stack) and GDT on the heap,
 /// leaks both (they must live forever), loads the GDT, reloads segment
 /// registers, and loads the TSS.
 ///
-/// Returns the kernel stack top address (for `PerCpu.kernel_rsp`).
+/// Returns the kernel stack top address (for `PerCpu.kernel_rsp`) and the
+/// AP's descriptor tables.
 ///
 /// # Safety
 ///
 /// Must be called exactly once per AP, after the heap is available and
 /// VMM is initialized. The caller must ensure no interrupts are processed
 /// before the GDT and TSS are fully loaded.
-pub unsafe fn init_ap(cpu_id: crate::id::CpuId) -> u64 {
+pub unsafe fn init_ap(cpu_id: crate::id::CpuId) -> (u64, CpuTables) {
     extern crate alloc;
     use alloc::boxed::Box;
 
     use crate::arch::x86_64::instructions::segmentation::{
         load_ds, load_es, load_fs, load_gs, load_ss, load_tss, set_cs,
     };
-    use crate::mm::pmm::BitmapFrameAllocRef;
+    use crate::mm::pmm::BuddyFrameAllocRef;
 
     // Allocate and set up a new TSS.
     let mut tss = TaskStateSegment::new();
 
     // Allocate double-fault IST stack and kernel stack via VMM.
-    let (df_stack_top, kernel_stack_top) = crate::mm::vmm::with(|vmm| {
+    let (df_stack, kernel_stack_top) = crate::mm::vmm::with(|vmm| {
         crate::mm::pmm::with(|pmm| {
-            let mut alloc = BitmapFrameAllocRef(pmm);
+            let mut alloc = BuddyFrameAllocRef(pmm);
             let df_stack = vmm
                 .alloc_kernel_stack(&mut alloc, None)
                 .expect("init_ap: failed to allocate double-fault stack");
             let kern_stack = vmm
                 .alloc_kernel_stack(&mut alloc, None)
                 .expect("init_ap: failed to allocate kernel stack");
-            (df_stack.top().as_u64(), kern_stack.top().as_u64())
+            (
+                df_stack.bottom().as_u64()..df_stack.top().as_u64(),
+                kern_stack.top().as_u64(),
+            )
         })
     });
-    tss.interrupt_stack_table[(DOUBLE_FAULT_IST_INDEX - 1) as usize] = df_stack_top;
+    tss.interrupt_stack_table[(DOUBLE_FAULT_IST_INDEX - 1) as usize] = df_stack.end;
     tss.privilege_stack_table[0] = kernel_stack_top;
 
     // Leak the TSS so it lives forever.
-    let tss_ref: &'static TaskStateSegment = Box::leak(Box::new(tss));
+    let tss_cell: &'static PageAligned<SyncUnsafeCell<TaskStateSegment>> =
+        Box::leak(Box::new(PageAligned(SyncUnsafeCell::new(tss))));
+    // SAFETY: The TSS was just initialized and is not yet loaded.
+    let tss_ref: &'static TaskStateSegment = unsafe { &*tss_cell.get() };
 
     // Build a new GDT with the same segment layout as BSP.
     let mut gdt = GlobalDescriptorTable::new();
@@ -209,7 +273,7 @@ pub unsafe fn init_ap(cpu_id: crate::id::CpuId) -> u64 {
     let tss_sel = gdt.append(Descriptor::tss_segment(tss_ref));
 
     // Leak the GDT so it lives forever.
-    let gdt_ref: &'static GlobalDescriptorTable = Box::leak(Box::new(gdt));
+    let gdt_ref: &'static GlobalDescriptorTable = &Box::leak(Box::new(PageAligned(gdt))).0;
 
     // SAFETY: The GDT and TSS are fully initialized and leaked (static lifetime).
     // Segment selectors match the BSP layout.
@@ -226,5 +290,11 @@ pub unsafe fn init_ap(cpu_id: crate::id::CpuId) -> u64 {
 
     crate::kdebug!("AP {} GDT/TSS initialized", cpu_id);
 
-    kernel_stack_top
+    let gdt_addr = gdt_ref as *const GlobalDescriptorTable as u64;
+    let tables = CpuTables {
+        tss: tss_cell.get(),
+        gdt: gdt_addr..gdt_addr + size_of::<GlobalDescriptorTable>() as u64,
+        double_fault_stack: df_stack,
+    };
+    (kernel_stack_top, tables)
 }
@@ -4,6 +4,7 @@
 //! timer configuration, and inter-processor interrupts.
 
 use crate::addr::VirtAddr;
+use crate::arch::x86_64::registers::model_specific::Msr;
 use crate::id::IrqVector;
 
 // Register offsets from LAPIC base.
@@ -24,8 +25,12 @@ const SVR_ENABLE: u32 = 1 << 8;
 
 /// LVT timer mode bits.
 const TIMER_PERIODIC: u32 = 1 << 17;
+const TIMER_TSC_DEADLINE: u32 = 0b10 << 17;
 const TIMER_MASKED: u32 = 1 << 16;
 
+/// MSR holding the TSC value at which a TSC-deadline timer fires.
+const IA32_TSC_DEADLINE: Msr = Msr::new(0x6E0);
+
 /// MSR address for APIC base.
 pub const IA32_APIC_BASE_MSR: u32 = 0x1B;
 
@@ -84,6 +89,27 @@ impl LocalApic {
         self.write_reg(REG_TIMER_INITIAL, initial_count);
     }
 
+    /// Arms the LAPIC timer in TSC-deadline mode to fire once when the TSC
+    /// reaches `deadline`.
+    ///
+    /// A deadline in the past fires immediately.
+    ///
+    /// # Safety
+    ///
+    /// The CPU must support TSC-deadline mode
+    /// ([`CpuFeatures::TSC_DEADLINE`](hadron_core::cpu_features::CpuFeatures::TSC_DEADLINE)).
+    pub unsafe fn start_timer_tsc_deadline(&self, vector: IrqVector, deadline: u64) {
+        self.write_reg(
+            REG_LVT_TIMER,
+            TIMER_TSC_DEADLINE | u32::from(vector.as_u8()),
+        );
+        // The mode switch must be visible before the MSR write arms it.
+        core::sync::atomic::fence(core::sync::atomic::Ordering::SeqCst);
+        // SAFETY: The caller guarantees IA32_TSC_DEADLINE exists. A zero
+        // write would disarm the timer, so the deadline is at least 1.
+        unsafe { IA32_TSC_DEADLINE.write(deadline.max(1)) };
+    }
+
     /// Stops the LAPIC timer by masking it.
     pub fn stop_timer(&self) {
         self.write_reg(REG_LVT_TIMER, TIMER_MASKED);
@@ -18,9 +18,15 @@ use super::gdt::DOUBLE_FAULT_IST_INDEX;
 use super::interrupts::timer_stub;
 use super::interrupts::{dispatch, exception_table::exception_table, handlers};
 
-/// Static Interrupt Descriptor Table with all exception and hardware interrupt
-/// handlers wired.
-static IDT: LazyLock<InterruptDescriptorTable> = LazyLock::new(|| {
+hadron_core::ro_after_init! {
+    /// Static Interrupt Descriptor Table with all exception and hardware
+    /// interrupt handlers wired. Built when the BSP first loads it and
+    /// read-only after boot.
+    static IDT: LazyLock<InterruptDescriptorTable> = LazyLock::new(build);
+}
+
+/// Builds the IDT.
+fn build() -> InterruptDescriptorTable {
     let mut idt = InterruptDescriptorTable::new();
 
     // --- CPU Exception Handlers (vectors 0-31) ---
@@ -78,8 +84,18 @@ static IDT: LazyLock<InterruptDescriptorTable> = LazyLock::new(|| {
             .set_naked_stub(timer_stub::timer_preempt_stub);
     }
 
+    // With KPTI, exceptions enter through trampolines that switch to the
+    // full page tables before the handlers above run.
+    #[cfg(hadron_kpti)]
+    super::kpti::install_trampolines(&mut idt, DOUBLE_FAULT_IST_INDEX);
+
     idt
-});
+}
+
+/// Returns the address of the IDT.
+pub fn table_address() -> crate::addr::VirtAddr {
+    crate::addr::VirtAddr::new(&*IDT as *const InterruptDescriptorTable as u64)
+}
 
 /// Loads the IDT into the CPU.
 ///
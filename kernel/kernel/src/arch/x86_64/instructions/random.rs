@@ -0,0 +1,81 @@
+//! Hardware random number sources.
+//!
+//! Wraps `RDRAND` and provides [`boot_entropy`], a best-effort seed for
+//! boot-time randomization (KASLR) that works before CPUID detection has
+//! run and on CPUs without `RDRAND`, and [`random_u64`] for runtime
+//! randomization such as per-exec user ASLR.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::arch::x86_64::cpuid::cpuid;
+use crate::arch::x86_64::hw::tsc::read_tsc;
+
+/// Number of times `RDRAND` is retried before giving up, as recommended by
+/// the Intel DRNG software implementation guide.
+const RDRAND_RETRIES: usize = 10;
+
+/// Returns `true` if the CPU implements `RDRAND` (CPUID.01H:ECX[30]).
+///
+/// Queries CPUID directly so that it can be used before
+/// [`cpuid::init`](crate::arch::x86_64::cpuid::init).
+pub fn has_rdrand() -> bool {
+    cpuid(1).ecx & (1 << 30) != 0
+}
+
+/// Reads a 64-bit random value with `RDRAND`.
+///
+/// Returns `None` if the instruction is unsupported or the DRNG did not
+/// deliver a value within [`RDRAND_RETRIES`] attempts.
+pub fn rdrand64() -> Option<u64> {
+    if !has_rdrand() {
+        return None;
+    }
+    for _ in 0..RDRAND_RETRIES {
+        let value: u64;
+        let ok: u8;
+        // SAFETY: RDRAND is supported (checked above) and only writes the
+        // output register and flags.
+        unsafe {
+            core::arch::asm!(
+                "rdrand {value}",
+                "setc {ok}",
+                value = out(reg) value,
+                ok = out(reg_byte) ok,
+                options(nomem, nostack),
+            );
+        }
+        if ok != 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Returns a 64-bit seed for boot-time randomization.
+///
+/// Mixes `RDRAND` output (when available) with the TSC through a
+/// SplitMix64 finalizer. Without `RDRAND` the result only carries the
+/// jitter of the TSC at boot, which is weak but still defeats fixed
+/// addresses.
+pub fn boot_entropy() -> u64 {
+    mix(read_tsc() ^ rdrand64().unwrap_or(0))
+}
+
+/// Returns a 64-bit random value for runtime randomization.
+///
+/// Like [`boot_entropy`], but also folds in a global call counter so that
+/// back-to-back calls on CPUs without `RDRAND` never return the same value
+/// even if the TSC has not advanced.
+pub fn random_u64() -> u64 {
+    static CALLS: AtomicU64 = AtomicU64::new(0);
+    let count = CALLS.fetch_add(1, Ordering::Relaxed);
+    mix(read_tsc() ^ rdrand64().unwrap_or(0) ^ count.rotate_left(32))
+}
+
+/// SplitMix64 finalizer: spreads every input bit over the whole output.
+fn mix(mut seed: u64) -> u64 {
+    seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    seed = (seed ^ (seed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    seed = (seed ^ (seed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    seed ^ (seed >> 31)
+}
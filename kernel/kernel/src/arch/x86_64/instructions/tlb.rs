@@ -17,10 +17,48 @@ pub fn flush(addr: VirtAddr) {
     }
 }
 
-/// Flushes the entire TLB by reloading CR3.
+/// Flushes the non-global TLB entries of the current PCID by reloading CR3.
 #[inline]
 pub fn flush_all() {
-    // SAFETY: Writing back the same CR3 value only flushes non-global TLB
-    // entries. The page table root remains unchanged.
-    unsafe { Cr3::write(Cr3::read()) };
+    // SAFETY: Writing back the same CR3 value (whose bit 63 always reads as
+    // zero) only flushes non-global TLB entries of the current PCID. The
+    // page table root remains unchanged.
+    unsafe { Cr3::write_raw(Cr3::read_raw()) };
+}
+
+/// INVPCID invalidation types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub enum InvpcidKind {
+    /// One linear address in one PCID.
+    Address = 0,
+    /// Every non-global entry of one PCID.
+    SingleContext = 1,
+    /// Every entry of every PCID, including global ones.
+    AllIncludingGlobal = 2,
+    /// Every non-global entry of every PCID.
+    AllContexts = 3,
+}
+
+/// Invalidates TLB entries by PCID (INVPCID).
+///
+/// `addr` is only used by [`InvpcidKind::Address`].
+///
+/// # Safety
+///
+/// The CPU must support INVPCID (`CpuFeatures::INVPCID`), and `pcid` must
+/// fit in 12 bits.
+#[inline]
+pub unsafe fn invpcid(kind: InvpcidKind, pcid: u64, addr: VirtAddr) {
+    let descriptor: [u64; 2] = [pcid, addr.as_u64()];
+    // SAFETY: The caller guarantees INVPCID is supported; the descriptor is
+    // a valid 16-byte memory operand.
+    unsafe {
+        core::arch::asm!(
+            "invpcid {}, [{}]",
+            in(reg) kind as u64,
+            in(reg) &descriptor,
+            options(nostack, preserves_flags),
+        );
+    }
 }
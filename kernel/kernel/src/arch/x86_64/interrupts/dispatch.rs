@@ -12,6 +12,7 @@
 use hadron_core::static_assert;
 use hadron_core::sync::AtomicFn;
 
+use crate::arch::x86_64::kpti::{kpti_enter, kpti_exit};
 use crate::id::{HwIrqVector, IrqVector};
 
 /// Number of hardware interrupt vectors (32-255).
@@ -99,6 +100,9 @@ extern "C" fn dispatch_interrupt(vector: u8) {
         crate::time::Time::pit_tick();
     }
 
+    // Restart a stopped tick if the handler queued a task on this CPU.
+    crate::sched::tick::irq_exit();
+
     // Send EOI to the interrupt controller.
     #[cfg(hadron_apic)]
     crate::arch::x86_64::acpi::Acpi::send_lapic_eoi();
@@ -120,6 +124,10 @@ extern "C" fn dispatch_interrupt(vector: u8) {
 //   3. Saves/restores scratch registers around the call to dispatch_interrupt
 //   4. Conditionally swapgs on exit (ring 3 → user GS)
 //   5. Returns via iretq
+//
+// With `hadron_kpti`, the ring 3 paths also switch between the shadow and
+// full page tables right after the entry swapgs and right before the exit
+// swapgs (see `kpti_enter!`/`kpti_exit!`).
 
 /// Stub handler type: raw function address for IDT entries.
 pub type StubFn = unsafe extern "C" fn();
@@ -132,6 +140,7 @@ pub type StubFn = unsafe extern "C" fn();
 macro_rules! make_stub {
     ($offset:expr) => {{
         #[unsafe(naked)]
+        #[unsafe(link_section = ".text.hadron_entry")]
         unsafe extern "C" fn stub() {
             core::arch::naked_asm!(
                 // ── Check privilege level of interrupted code ──
@@ -140,6 +149,7 @@ macro_rules! make_stub {
                 "test qword ptr [rsp + 8], 3",
                 "jz 1f",
                 "swapgs",                       // Ring 3 → swap to kernel GS
+                kpti_enter!(frame = 5),         // full page tables, kernel stack
                 "1:",
 
                 // ── Save scratch registers ──
@@ -178,6 +188,7 @@ macro_rules! make_stub {
                 // ── Check privilege level again before return ──
                 "test qword ptr [rsp + 8], 3",
                 "jz 2f",
+                kpti_exit!(),                   // entry stack, shadow page tables
                 "swapgs",                       // Returning to ring 3 → swap back
                 "2:",
                 "iretq",
@@ -5,6 +5,10 @@
 //! Faults originating from ring 3 gracefully terminate the user process via
 //! [`crate::proc::terminate_current_process_from_fault`] instead of panicking
 //! the kernel.
+//!
+//! With `hadron_kpti`, the exceptions that can return to ring 3 (#DB, #BP,
+//! #PF) enter through trampolines that call the `extern "C"` `*_from_user`
+//! variants below on the kernel stack; see [`super::super::kpti`].
 
 // Handler names are self-documenting; suppress missing_docs for this module.
 #![allow(missing_docs)]
@@ -14,6 +18,9 @@ use crate::arch::x86_64::structures::idt::InterruptStackFrame;
 /// RPL mask for the code segment selector — bits [0:1] hold the privilege level.
 const CS_RPL_MASK: u64 = 0x3;
 
+/// First address above the user half of the address space.
+const USER_ADDR_LIMIT: u64 = 0x0000_8000_0000_0000;
+
 /// Returns `true` if the interrupt originated from user mode (ring 3).
 fn is_user_mode(frame: &InterruptStackFrame) -> bool {
     frame.code_segment & CS_RPL_MASK != 0
@@ -52,6 +59,11 @@ pub extern "x86-interrupt" fn debug(frame: InterruptStackFrame) {
     crate::kwarn!("EXCEPTION: DEBUG\n{:#?}", frame);
 }
 
+#[cfg(hadron_kpti)]
+pub extern "C" fn debug_from_user(frame: &mut InterruptStackFrame) {
+    crate::kwarn!("EXCEPTION: DEBUG\n{:#?}", frame);
+}
+
 pub extern "x86-interrupt" fn nmi(_frame: InterruptStackFrame) {
     // If a panic is in progress, this NMI was sent by `panic_halt_other_cpus`.
     // Halt this CPU permanently.
@@ -69,6 +81,11 @@ pub extern "x86-interrupt" fn breakpoint(frame: InterruptStackFrame) {
     crate::kwarn!("EXCEPTION: BREAKPOINT\n{:#?}", frame);
 }
 
+#[cfg(hadron_kpti)]
+pub extern "C" fn breakpoint_from_user(frame: &mut InterruptStackFrame) {
+    crate::kwarn!("EXCEPTION: BREAKPOINT\n{:#?}", frame);
+}
+
 pub extern "x86-interrupt" fn overflow(frame: InterruptStackFrame) {
     if is_user_mode(&frame) {
         terminate_user_fault("#OF", &frame);
@@ -138,7 +155,18 @@ pub extern "x86-interrupt" fn general_protection(frame: InterruptStackFrame, err
     );
 }
 
-pub extern "x86-interrupt" fn page_fault(frame: InterruptStackFrame, error_code: u64) {
+pub extern "x86-interrupt" fn page_fault(mut frame: InterruptStackFrame, error_code: u64) {
+    handle_page_fault(&mut frame, error_code);
+}
+
+#[cfg(hadron_kpti)]
+pub extern "C" fn page_fault_from_user(frame: &mut InterruptStackFrame, error_code: u64) {
+    handle_page_fault(frame, error_code);
+}
+
+/// Page fault body shared by the `x86-interrupt` handler and the KPTI
+/// trampoline. `frame` is the hardware frame that `iretq` reloads.
+fn handle_page_fault(frame: &mut InterruptStackFrame, error_code: u64) {
     use crate::arch::x86_64::structures::paging::PageFaultErrorCode;
     use crate::mm::layout::FaultRegion;
 
@@ -174,6 +202,22 @@ pub extern "x86-interrupt" fn page_fault(frame: InterruptStackFrame, error_code:
     let is_user = error.contains(PageFaultErrorCode::USER);
     let mode = if is_user { "user" } else { "kernel" };
 
+    // User-mode fault below the main stack: grow the stack and retry.
+    if is_user && !error.contains(PageFaultErrorCode::PRESENT) {
+        // Exception entry does not swapgs, so GS still holds the user base.
+        // Switch to the kernel per-CPU base for the process lookup and swap
+        // back before returning to (or terminating) the process.
+        // SAFETY: The fault came from ring 3, so KERNEL_GS_BASE holds the
+        // per-CPU pointer; the two swapgs are balanced.
+        unsafe { core::arch::asm!("swapgs", options(nostack, preserves_flags)) };
+        let grown = crate::proc::exec::grow_user_stack(cr2);
+        // SAFETY: Restores the user GS base swapped in above.
+        unsafe { core::arch::asm!("swapgs", options(nostack, preserves_flags)) };
+        if grown {
+            return;
+        }
+    }
+
     // User-mode fault: log and terminate the process instead of panicking.
     if is_user {
         crate::kerr!(
@@ -187,6 +231,37 @@ pub extern "x86-interrupt" fn page_fault(frame: InterruptStackFrame, error_code:
         }
     }
 
+    // Kernel-mode fault inside a user-access copy: grow the user stack if
+    // the address is in its growth range, otherwise resume at the copy's
+    // fixup so the syscall fails with EFAULT.
+    if let Some(fixup) =
+        crate::arch::x86_64::uaccess::search_exception_table(frame.instruction_pointer.as_u64())
+    {
+        if !error.contains(PageFaultErrorCode::PRESENT) && crate::proc::exec::grow_user_stack(cr2) {
+            return;
+        }
+        // SAFETY: `frame` refers to the hardware-pushed frame, which iretq
+        // reloads; the volatile write keeps the store from being optimized
+        // away.
+        unsafe {
+            core::ptr::write_volatile(
+                &mut frame.instruction_pointer,
+                crate::addr::VirtAddr::new(fixup),
+            );
+        }
+        return;
+    }
+
+    // Kernel access to a user page outside a user-access window.
+    if cr2 < USER_ADDR_LIMIT && error.contains(PageFaultErrorCode::PRESENT) {
+        let violation = if error.contains(PageFaultErrorCode::INSTRUCTION_FETCH) {
+            "SMEP violation: kernel executed a user page"
+        } else {
+            "SMAP violation: kernel accessed user memory outside a user-access copy"
+        };
+        panic!("PAGE FAULT: {violation}\n  Address: {cr2:#x}\n  Error: {error:?}\n{frame:#?}");
+    }
+
     // Kernel-mode fault: diagnose and panic.
     // Try to identify the faulting region (non-blocking to avoid deadlock
     // if we faulted inside the VMM itself).
@@ -5,7 +5,9 @@
 //! via `iretq`. When the timer fires from ring 3, it saves the full user
 //! register state into [`USER_CONTEXT`], performs the tick-and-EOI, restores
 //! the kernel address space and GS bases, then longjmps back to
-//! [`process_task`](crate::proc) via `restore_kernel_context`.
+//! [`process_task`](crate::proc) via `restore_kernel_context`. With
+//! `hadron_kpti`, the ring-3 path first switches to the full page tables and
+//! the kernel stack; it never returns to user mode, so it has no exit switch.
 //!
 //! When `hadron_profile_sample` is enabled, the ring-0 path additionally
 //! captures the interrupted RIP, RSP, and RBP and calls into the sampling
@@ -14,6 +16,7 @@
 //! [`USER_CONTEXT`]: crate::proc::USER_CONTEXT
 
 use crate::arch::x86_64::acpi::timer_tick_and_eoi;
+use crate::arch::x86_64::kpti::kpti_enter;
 use crate::proc::{KERNEL_CR3, TrapReason};
 
 /// MSR address for `IA32_GS_BASE`.
@@ -29,6 +32,7 @@ const IA32_KERNEL_GS_BASE_MSR: u32 = 0xC000_0102;
 /// Naked timer interrupt handler (standard, non-profiling variant).
 #[cfg(not(hadron_profile_sample))]
 #[unsafe(naked)]
+#[unsafe(link_section = ".text.hadron_entry")]
 pub(crate) unsafe extern "C" fn timer_preempt_stub() {
     core::arch::naked_asm!(
         // ── Check privilege level of interrupted code ──
@@ -67,6 +71,7 @@ pub(crate) unsafe extern "C" fn timer_preempt_stub() {
         // ── Ring 3: preempt userspace ──
         "2:",
         "swapgs",
+        kpti_enter!(frame = 5),
         "push rax",
         "mov rax, gs:[32]",
         "mov [rax + 8],   rbx",
@@ -139,6 +144,7 @@ use crate::profiling::sample::sample_capture;
 /// is inactive, `sample_capture` returns after a single atomic load.
 #[cfg(hadron_profile_sample)]
 #[unsafe(naked)]
+#[unsafe(link_section = ".text.hadron_entry")]
 pub(crate) unsafe extern "C" fn timer_preempt_stub() {
     core::arch::naked_asm!(
         // ── Check privilege level of interrupted code ──
@@ -185,6 +191,7 @@ pub(crate) unsafe extern "C" fn timer_preempt_stub() {
         // ── Ring 3: preempt userspace (identical to non-profiling variant) ──
         "2:",
         "swapgs",
+        kpti_enter!(frame = 5),
         "push rax",
         "mov rax, gs:[32]",
         "mov [rax + 8],   rbx",
@@ -0,0 +1,760 @@
+//! Kernel page-table isolation (KPTI).
+//!
+//! With `hadron_kpti`, user code runs on a per-process *shadow* PML4 whose
+//! kernel half maps only what the CPU touches on the way into the kernel:
+//! the entry text (`.text.hadron_entry`), the IDT, and each CPU's GDT, TSS,
+//! [`PerCpu`] page, entry stack and double-fault stack. Kernel code and
+//! data stay unmapped while user code runs, so speculative loads from ring 3
+//! have nothing to read.
+//!
+//! Every ring 3 → ring 0 path switches CR3 to the process's full page
+//! tables before touching anything else, and every ring 0 → ring 3 path
+//! switches back to the shadow tables as its last step:
+//!
+//! - **Interrupts and exceptions** arrive on the per-CPU *entry stack*
+//!   (TSS.RSP0), which is mapped in both tables. The entry sequence
+//!   ([`kpti_enter!`]) loads the full CR3 and moves the hardware frame to
+//!   the real kernel stack (`PerCpu.kernel_rsp`). The exit sequence
+//!   ([`kpti_exit!`]) moves the frame back to the entry stack and loads
+//!   the shadow CR3 right before `iretq`.
+//! - **SYSCALL** switches CR3 in [`kpti_syscall_enter!`] right after
+//!   `swapgs`, and back in [`kpti_syscall_exit!`] right before `sysretq`.
+//! - **NMI, #MC and #DF** may interrupt the kernel inside an entry or exit
+//!   sequence, when neither GS nor CR3 can be trusted. None of them return,
+//!   so from ring 0 they simply load the kernel CR3 from a static that is
+//!   mapped in the shadow tables.
+//!
+//! The CR3 values for the current process live in `PerCpu.kpti_kernel_cr3`
+//! and `PerCpu.kpti_user_cr3`, set by [`set_user_cr3`] before every entry
+//! to user mode.
+//!
+//! Global pages are disabled (CR4.PGE cleared) so that kernel translations
+//! do not survive the switch to the shadow tables. When the CPU supports
+//! PCIDs, a process's full tables run as its own PCID and its shadow tables
+//! as the same PCID with bit 11 set (see [`pcid`](super::pcid)). Kernel
+//! entry sets the no-flush bit, so the kernel's TLB entries survive a round
+//! trip through user mode. Exits set it too when INVPCID lets the kernel
+//! invalidate user translations in the shadow PCID directly; otherwise
+//! every exit flushes the shadow PCID so that those translations never go
+//! stale.
+//!
+//! The asm sequences in this module expand to nothing without
+//! `hadron_kpti`, so the entry stubs can use them unconditionally.
+//!
+//! [`PerCpu`]: crate::percpu::PerCpu
+
+/// Page-aligned wrapper, so that a structure mapped into the shadow page
+/// tables does not share its pages with unrelated kernel data.
+#[repr(C, align(4096))]
+pub struct PageAligned<T>(pub T);
+
+impl<T> core::ops::Deref for PageAligned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Entry/exit sequences
+// ---------------------------------------------------------------------------
+//
+// PerCpu offsets used below: GS:[8] = kernel_rsp, GS:[72] = kpti_kernel_cr3,
+// GS:[80] = kpti_user_cr3, GS:[88] = kpti_scratch, GS:[96] = entry_stack_top.
+
+/// Switches to the full page tables and moves the hardware interrupt frame
+/// from the entry stack to the kernel stack.
+///
+/// `frame = 5` for a plain frame, `frame = 6` when the CPU pushed an error
+/// code. Requires the kernel GS base; preserves every register.
+#[cfg(hadron_kpti)]
+macro_rules! kpti_enter {
+    (frame = 5) => {
+        concat!(
+            "mov gs:[88], rax\n",
+            "mov rax, gs:[72]\n",
+            "mov cr3, rax\n",
+            "mov rax, gs:[8]\n",
+            "sub rax, 40\n",
+            "pop qword ptr [rax]\n",
+            "pop qword ptr [rax + 8]\n",
+            "pop qword ptr [rax + 16]\n",
+            "pop qword ptr [rax + 24]\n",
+            "pop qword ptr [rax + 32]\n",
+            "mov rsp, rax\n",
+            "mov rax, gs:[88]\n",
+        )
+    };
+    (frame = 6) => {
+        concat!(
+            "mov gs:[88], rax\n",
+            "mov rax, gs:[72]\n",
+            "mov cr3, rax\n",
+            "mov rax, gs:[8]\n",
+            "sub rax, 48\n",
+            "pop qword ptr [rax]\n",
+            "pop qword ptr [rax + 8]\n",
+            "pop qword ptr [rax + 16]\n",
+            "pop qword ptr [rax + 24]\n",
+            "pop qword ptr [rax + 32]\n",
+            "pop qword ptr [rax + 40]\n",
+            "mov rsp, rax\n",
+            "mov rax, gs:[88]\n",
+        )
+    };
+}
+
+/// Moves the `iretq` frame at RSP to the entry stack and switches to the
+/// shadow page tables. Must be followed by `swapgs; iretq`.
+///
+/// Requires the kernel GS base; preserves every register.
+#[cfg(hadron_kpti)]
+macro_rules! kpti_exit {
+    () => {
+        concat!(
+            "mov gs:[88], rax\n",
+            "mov rax, gs:[96]\n",
+            "sub rax, 40\n",
+            "pop qword ptr [rax]\n",
+            "pop qword ptr [rax + 8]\n",
+            "pop qword ptr [rax + 16]\n",
+            "pop qword ptr [rax + 24]\n",
+            "pop qword ptr [rax + 32]\n",
+            "mov rsp, rax\n",
+            "mov rax, gs:[80]\n",
+            "mov cr3, rax\n",
+            "mov rax, gs:[88]\n",
+        )
+    };
+}
+
+/// [`kpti_exit!`] for `enter_userspace_save`/`enter_userspace_resume`,
+/// which run with the user GS base already loaded.
+#[cfg(hadron_kpti)]
+macro_rules! kpti_enter_user {
+    () => {
+        concat!(
+            "swapgs\n",
+            $crate::arch::x86_64::kpti::kpti_exit!(),
+            "swapgs\n"
+        )
+    };
+}
+
+/// Switches to the full page tables on SYSCALL entry, after the user RSP
+/// has been saved. Skipped for SYSCALL from ring 0 (negative RCX), which
+/// already runs on the kernel tables. Clobbers RSP and flags.
+#[cfg(hadron_kpti)]
+macro_rules! kpti_syscall_enter {
+    () => {
+        concat!(
+            "test rcx, rcx\n",
+            "js 9f\n",
+            "mov rsp, gs:[72]\n",
+            "mov cr3, rsp\n",
+            "9:\n",
+        )
+    };
+}
+
+/// Switches to the shadow page tables right before the `sysretq` path
+/// reloads the user RSP. Clobbers RSP.
+#[cfg(hadron_kpti)]
+macro_rules! kpti_syscall_exit {
+    () => {
+        concat!("mov rsp, gs:[80]\n", "mov cr3, rsp\n")
+    };
+}
+
+#[cfg(not(hadron_kpti))]
+macro_rules! kpti_enter {
+    (frame = $n:literal) => {
+        ""
+    };
+}
+
+#[cfg(not(hadron_kpti))]
+macro_rules! kpti_exit {
+    () => {
+        ""
+    };
+}
+
+#[cfg(not(hadron_kpti))]
+macro_rules! kpti_enter_user {
+    () => {
+        ""
+    };
+}
+
+#[cfg(not(hadron_kpti))]
+macro_rules! kpti_syscall_enter {
+    () => {
+        ""
+    };
+}
+
+#[cfg(not(hadron_kpti))]
+macro_rules! kpti_syscall_exit {
+    () => {
+        ""
+    };
+}
+
+pub(crate) use {kpti_enter, kpti_enter_user, kpti_exit, kpti_syscall_enter, kpti_syscall_exit};
+
+#[cfg(hadron_kpti)]
+pub use imp::*;
+
+#[cfg(hadron_kpti)]
+mod imp {
+    use core::sync::atomic::{AtomicU64, Ordering};
+
+    use super::PageAligned;
+    use crate::addr::{PhysAddr, VirtAddr};
+    use crate::arch::x86_64::gdt::CpuTables;
+    use crate::arch::x86_64::interrupts::handlers;
+    use crate::arch::x86_64::paging::PageTableMapper;
+    use crate::arch::x86_64::pcid::{self, CR3_NOFLUSH, SHADOW_PCID_BIT};
+    use crate::arch::x86_64::registers::control::{Cr3, Cr4, Cr4Flags};
+    use crate::arch::x86_64::registers::model_specific::IA32_GS_BASE;
+    use crate::arch::x86_64::structures::idt::InterruptDescriptorTable;
+    use crate::arch::x86_64::structures::paging::PageTableFlags;
+    use crate::mm::PAGE_SIZE;
+    use crate::percpu::PerCpu;
+    use crate::sync::SpinLock;
+
+    /// Size of each CPU's entry stack (8 KiB).
+    ///
+    /// Only the hardware frame passes through it on the way in and out,
+    /// but an NMI or machine check taken from ring 3 runs its (diverging)
+    /// handler on it.
+    const ENTRY_STACK_SIZE: usize = 8192;
+
+    /// A CPU's entry stack, the TSS.RSP0 stack under isolation.
+    #[repr(C, align(4096))]
+    struct EntryStack([u8; ENTRY_STACK_SIZE]);
+
+    impl EntryStack {
+        fn range(&self) -> (u64, u64) {
+            let bottom = self as *const Self as u64;
+            (bottom, bottom + ENTRY_STACK_SIZE as u64)
+        }
+    }
+
+    /// BSP entry stack. `static mut` so it is placed in `.bss`.
+    static mut BSP_ENTRY_STACK: EntryStack = EntryStack([0; ENTRY_STACK_SIZE]);
+
+    /// Kernel CR3 for the NMI, #MC and #DF trampolines, which cannot rely on
+    /// GS. Page-aligned because the shadow page tables map it.
+    static PARANOID_CR3: PageAligned<AtomicU64> = PageAligned(AtomicU64::new(0));
+
+    /// Root of the shadow template: an empty user half and a kernel half
+    /// that maps only the entry text and per-CPU entry state. Every
+    /// process's shadow PML4 copies its kernel half.
+    static SHADOW_TEMPLATE: SpinLock<PhysAddr> =
+        SpinLock::leveled("kpti_template", 2, PhysAddr::zero());
+
+    /// Cached copy of the template root, readable without the lock once
+    /// the BSP has built it.
+    static SHADOW_TEMPLATE_ROOT: AtomicU64 = AtomicU64::new(0);
+
+    unsafe extern "C" {
+        static __hadron_entry_text_start: u8;
+        static __hadron_entry_text_end: u8;
+    }
+
+    /// Returns the start and end of the entry text section.
+    pub fn entry_text() -> (VirtAddr, VirtAddr) {
+        // SAFETY: Linker-defined symbols; only their addresses are taken.
+        unsafe {
+            (
+                VirtAddr::new(core::ptr::addr_of!(__hadron_entry_text_start) as u64),
+                VirtAddr::new(core::ptr::addr_of!(__hadron_entry_text_end) as u64),
+            )
+        }
+    }
+
+    /// Returns the physical address of the shadow template PML4, or `None`
+    /// before [`init`] has run.
+    pub fn shadow_template() -> Option<PhysAddr> {
+        match SHADOW_TEMPLATE_ROOT.load(Ordering::Acquire) {
+            0 => None,
+            root => Some(PhysAddr::new(root)),
+        }
+    }
+
+    /// Records the page tables the current CPU switches between for the
+    /// process it is about to enter.
+    ///
+    /// `full` is the CR3 value of the process's PML4 as returned by
+    /// [`pcid::switch_to`], and `shadow` its shadow PML4. Must be called
+    /// while GS still holds the kernel per-CPU base.
+    pub fn set_user_cr3(full: u64, shadow: PhysAddr) {
+        let (kernel_cr3, user_cr3) = if pcid::enabled() {
+            let shadow_pcid = (full & 0xfff) | SHADOW_PCID_BIT;
+            let keep = if pcid::shadow_keeps_tlb() {
+                CR3_NOFLUSH
+            } else {
+                0
+            };
+            (full | CR3_NOFLUSH, shadow.as_u64() | shadow_pcid | keep)
+        } else {
+            (full, shadow.as_u64())
+        };
+        // SAFETY: GS_BASE holds this CPU's PerCpu pointer (caller contract).
+        let percpu = unsafe { IA32_GS_BASE.read() } as *mut PerCpu;
+        // SAFETY: Only this CPU touches its PerCpu CR3 fields, and the entry
+        // stubs read them only while this CPU is in user mode.
+        unsafe {
+            (*percpu).kpti_kernel_cr3 = kernel_cr3;
+            (*percpu).kpti_user_cr3 = user_cr3;
+        }
+    }
+
+    /// Builds the shadow template and enables isolation on the BSP.
+    ///
+    /// Maps the entry text, the IDT and the paranoid CR3 slot, then the
+    /// BSP's own entry state (see [`init_ap`] for the per-CPU part).
+    ///
+    /// # Safety
+    ///
+    /// Must be called once on the BSP after the heap is initialized, before
+    /// APs are booted and before any user address space is created.
+    pub unsafe fn init(tables: &CpuTables) {
+        let hhdm = crate::mm::hhdm::offset();
+        let root = crate::mm::pmm::with(|pmm| {
+            pmm.allocate_frame()
+                .expect("kpti: out of memory allocating shadow template")
+                .start_address()
+        });
+        // SAFETY: The frame was just allocated and is mapped through the HHDM.
+        unsafe { crate::mm::zero_frame((hhdm + root.as_u64()).as_mut_ptr::<u8>()) };
+        *SHADOW_TEMPLATE.lock() = root;
+
+        PARANOID_CR3
+            .0
+            .store(Cr3::read().as_u64(), Ordering::Release);
+
+        let (text_start, text_end) = entry_text();
+        expose(
+            text_start.as_u64(),
+            text_end.as_u64(),
+            PageTableFlags::PRESENT,
+        );
+        let idt = super::super::idt::table_address().as_u64();
+        expose(
+            idt,
+            idt + core::mem::size_of::<InterruptDescriptorTable>() as u64,
+            PageTableFlags::PRESENT | PageTableFlags::NO_EXECUTE,
+        );
+        let paranoid = &PARANOID_CR3 as *const _ as u64;
+        expose(
+            paranoid,
+            paranoid + 8,
+            PageTableFlags::PRESENT | PageTableFlags::NO_EXECUTE,
+        );
+
+        // SAFETY: BSP_ENTRY_STACK is only referenced here, once.
+        let entry_stack = unsafe { &*core::ptr::addr_of!(BSP_ENTRY_STACK) };
+        // SAFETY: Forwarded from the caller.
+        unsafe { init_cpu(tables, entry_stack) };
+
+        SHADOW_TEMPLATE_ROOT.store(root.as_u64(), Ordering::Release);
+        crate::kinfo!(
+            "KPTI: enabled (PCID {})",
+            if pcid::enabled() { "on" } else { "off" }
+        );
+    }
+
+    /// Enables isolation on an AP: allocates its entry stack and maps its
+    /// entry state into the shadow template.
+    ///
+    /// # Safety
+    ///
+    /// Must be called once per AP after its GDT, TSS and GS base are set up,
+    /// with interrupts disabled, before any user address space is created.
+    pub unsafe fn init_ap(tables: &CpuTables) {
+        extern crate alloc;
+        let entry_stack: &'static EntryStack =
+            alloc::boxed::Box::leak(alloc::boxed::Box::new(EntryStack([0; ENTRY_STACK_SIZE])));
+        // SAFETY: Forwarded from the caller.
+        unsafe { init_cpu(tables, entry_stack) };
+    }
+
+    /// Maps the current CPU's entry state into the shadow template, points
+    /// TSS.RSP0 at its entry stack, and configures CR4.
+    unsafe fn init_cpu(tables: &CpuTables, entry_stack: &'static EntryStack) {
+        let rw = PageTableFlags::PRESENT | PageTableFlags::WRITABLE | PageTableFlags::NO_EXECUTE;
+        // SAFETY: GS_BASE holds this CPU's PerCpu pointer.
+        let percpu_addr = unsafe { IA32_GS_BASE.read() };
+        let percpu = percpu_addr as *mut PerCpu;
+        expose(
+            percpu_addr,
+            percpu_addr + core::mem::size_of::<PerCpu>() as u64,
+            rw,
+        );
+        expose(tables.gdt.start, tables.gdt.end, rw);
+        let tss = tables.tss();
+        expose(tss.start, tss.end, rw);
+        expose(
+            tables.double_fault_stack.start,
+            tables.double_fault_stack.end,
+            rw,
+        );
+        let (stack_bottom, stack_top) = entry_stack.range();
+        expose(stack_bottom, stack_top, rw);
+
+        // SAFETY: This CPU owns its PerCpu and TSS; interrupts are disabled
+        // and no user process has run yet, so nothing uses RSP0.
+        unsafe {
+            (*percpu).entry_stack_top = stack_top;
+            tables.set_rsp0(stack_top);
+        }
+
+        // SAFETY: Clearing PGE flushes all global translations.
+        unsafe { Cr4::write(Cr4::read() - Cr4Flags::PGE) };
+    }
+
+    /// Maps the pages covering `[start, end)` into the shadow template,
+    /// backed by the same frames as in the kernel page tables.
+    fn expose(start: u64, end: u64, flags: PageTableFlags) {
+        let template = SHADOW_TEMPLATE.lock();
+        let kernel_root = PhysAddr::new(PARANOID_CR3.0.load(Ordering::Acquire));
+        let mapper = PageTableMapper::new(crate::mm::hhdm::offset());
+        let page_size = PAGE_SIZE as u64;
+
+        crate::mm::pmm::with(|pmm| {
+            let mut page = start & !(page_size - 1);
+            while page < end {
+                let virt = VirtAddr::new(page);
+                // SAFETY: PARANOID_CR3 holds the kernel page tables; the
+                // template root is a valid PML4 owned by this module.
+                unsafe {
+                    let phys = mapper
+                        .translate_addr(kernel_root, virt)
+                        .expect("kpti: exposed kernel page is not mapped");
+                    mapper.map_4k(*template, virt, phys, flags, &mut || {
+                        pmm.allocate_frame()
+                            .expect("kpti: out of memory building shadow page tables")
+                    });
+                }
+                page += page_size;
+            }
+        });
+    }
+
+    // -----------------------------------------------------------------------
+    // Exception trampolines
+    // -----------------------------------------------------------------------
+
+    /// Generates a trampoline for an exception whose handler never returns
+    /// to ring 3. From ring 3 it switches to the full page tables and the
+    /// kernel stack, then jumps to the `x86-interrupt` handler with the user
+    /// GS base still loaded, exactly as the handler expects.
+    macro_rules! terminating_trampoline {
+        ($name:ident, $handler:path, frame = $n:tt, cs = $cs:literal) => {
+            #[unsafe(naked)]
+            #[unsafe(link_section = ".text.hadron_entry")]
+            unsafe extern "C" fn $name() {
+                core::arch::naked_asm!(
+                    concat!("test qword ptr [rsp + ", $cs, "], 3"),
+                    "jz 1f",
+                    "swapgs",
+                    kpti_enter!(frame = $n),
+                    "swapgs",
+                    "1:",
+                    "jmp {handler}",
+                    handler = sym $handler,
+                );
+            }
+        };
+    }
+
+    terminating_trampoline!(divide_error, handlers::divide_error, frame = 5, cs = "8");
+    terminating_trampoline!(overflow, handlers::overflow, frame = 5, cs = "8");
+    terminating_trampoline!(bound_range, handlers::bound_range, frame = 5, cs = "8");
+    terminating_trampoline!(
+        invalid_opcode,
+        handlers::invalid_opcode,
+        frame = 5,
+        cs = "8"
+    );
+    terminating_trampoline!(
+        device_not_available,
+        handlers::device_not_available,
+        frame = 5,
+        cs = "8"
+    );
+    terminating_trampoline!(invalid_tss, handlers::invalid_tss, frame = 6, cs = "16");
+    terminating_trampoline!(
+        segment_not_present,
+        handlers::segment_not_present,
+        frame = 6,
+        cs = "16"
+    );
+    terminating_trampoline!(
+        stack_segment_fault,
+        handlers::stack_segment_fault,
+        frame = 6,
+        cs = "16"
+    );
+    terminating_trampoline!(
+        general_protection,
+        handlers::general_protection,
+        frame = 6,
+        cs = "16"
+    );
+    terminating_trampoline!(
+        x87_floating_point,
+        handlers::x87_floating_point,
+        frame = 5,
+        cs = "8"
+    );
+    terminating_trampoline!(
+        alignment_check,
+        handlers::alignment_check,
+        frame = 6,
+        cs = "16"
+    );
+    terminating_trampoline!(
+        simd_floating_point,
+        handlers::simd_floating_point,
+        frame = 5,
+        cs = "8"
+    );
+    terminating_trampoline!(
+        virtualization,
+        handlers::virtualization,
+        frame = 5,
+        cs = "8"
+    );
+    terminating_trampoline!(
+        control_protection,
+        handlers::control_protection,
+        frame = 6,
+        cs = "16"
+    );
+    terminating_trampoline!(
+        hypervisor_injection,
+        handlers::hypervisor_injection,
+        frame = 5,
+        cs = "8"
+    );
+    terminating_trampoline!(
+        vmm_communication,
+        handlers::vmm_communication,
+        frame = 6,
+        cs = "16"
+    );
+    terminating_trampoline!(
+        security_exception,
+        handlers::security_exception,
+        frame = 6,
+        cs = "16"
+    );
+
+    /// Trampoline for exceptions that may interrupt the entry/exit sequences
+    /// themselves (NMI, #MC). From ring 3 it behaves like the terminating
+    /// trampolines; from ring 0 it loads the kernel CR3 from
+    /// [`PARANOID_CR3`], since neither GS nor CR3 can be trusted there.
+    macro_rules! paranoid_trampoline {
+        ($name:ident, $handler:path) => {
+            #[unsafe(naked)]
+            #[unsafe(link_section = ".text.hadron_entry")]
+            unsafe extern "C" fn $name() {
+                core::arch::naked_asm!(
+                    "test qword ptr [rsp + 8], 3",
+                    "jz 1f",
+                    "swapgs",
+                    kpti_enter!(frame = 5),
+                    "swapgs",
+                    "jmp {handler}",
+                    "1:",
+                    "push rax",
+                    "mov rax, [rip + {cr3}]",
+                    "test rax, rax",
+                    "jz 2f",
+                    "mov cr3, rax",
+                    "2:",
+                    "pop rax",
+                    "jmp {handler}",
+                    handler = sym $handler,
+                    cr3 = sym PARANOID_CR3,
+                );
+            }
+        };
+    }
+
+    paranoid_trampoline!(nmi, handlers::nmi);
+    paranoid_trampoline!(machine_check, handlers::machine_check);
+
+    /// Double fault trampoline. Runs on the IST stack, which is mapped in
+    /// the shadow tables; always loads the kernel CR3 before the handler.
+    #[unsafe(naked)]
+    #[unsafe(link_section = ".text.hadron_entry")]
+    unsafe extern "C" fn double_fault() {
+        core::arch::naked_asm!(
+            "push rax",
+            "mov rax, [rip + {cr3}]",
+            "test rax, rax",
+            "jz 1f",
+            "mov cr3, rax",
+            "1:",
+            "pop rax",
+            "jmp {handler}",
+            handler = sym handlers::double_fault,
+            cr3 = sym PARANOID_CR3,
+        );
+    }
+
+    /// Trampoline for #DB and #BP, which return to ring 3. The ring-3 path
+    /// calls the handler body on the kernel stack and leaves through
+    /// [`kpti_exit!`]; the ring-0 path jumps to the `x86-interrupt` handler.
+    macro_rules! returning_trampoline {
+        ($name:ident, $handler:path, $body:path) => {
+            #[unsafe(naked)]
+            #[unsafe(link_section = ".text.hadron_entry")]
+            unsafe extern "C" fn $name() {
+                core::arch::naked_asm!(
+                    "test qword ptr [rsp + 8], 3",
+                    "jnz 2f",
+                    "jmp {handler}",
+                    "2:",
+                    "swapgs",
+                    kpti_enter!(frame = 5),
+                    "swapgs",
+                    // 9 pushes + the 40-byte frame keep RSP 16-byte aligned
+                    // below the (aligned) kernel stack top.
+                    "push rax",
+                    "push rcx",
+                    "push rdx",
+                    "push rsi",
+                    "push rdi",
+                    "push r8",
+                    "push r9",
+                    "push r10",
+                    "push r11",
+                    "cld",
+                    "lea rdi, [rsp + 72]",
+                    "call {body}",
+                    "pop r11",
+                    "pop r10",
+                    "pop r9",
+                    "pop r8",
+                    "pop rdi",
+                    "pop rsi",
+                    "pop rdx",
+                    "pop rcx",
+                    "pop rax",
+                    "swapgs",
+                    kpti_exit!(),
+                    "swapgs",
+                    "iretq",
+                    handler = sym $handler,
+                    body = sym $body,
+                );
+            }
+        };
+    }
+
+    returning_trampoline!(debug, handlers::debug, handlers::debug_from_user);
+    returning_trampoline!(
+        breakpoint,
+        handlers::breakpoint,
+        handlers::breakpoint_from_user
+    );
+
+    /// Page fault trampoline. Like the #DB/#BP trampolines, but passes the
+    /// error code and drops it before `iretq`.
+    #[unsafe(naked)]
+    #[unsafe(link_section = ".text.hadron_entry")]
+    unsafe extern "C" fn page_fault() {
+        core::arch::naked_asm!(
+            "test qword ptr [rsp + 16], 3",
+            "jnz 2f",
+            "jmp {handler}",
+            "2:",
+            "swapgs",
+            kpti_enter!(frame = 6),
+            "swapgs",
+            // 9 pushes + 8 bytes of padding + the 48-byte frame keep RSP
+            // 16-byte aligned below the kernel stack top.
+            "push rax",
+            "push rcx",
+            "push rdx",
+            "push rsi",
+            "push rdi",
+            "push r8",
+            "push r9",
+            "push r10",
+            "push r11",
+            "sub rsp, 8",
+            "cld",
+            "mov rsi, [rsp + 80]",
+            "lea rdi, [rsp + 88]",
+            "call {body}",
+            "add rsp, 8",
+            "pop r11",
+            "pop r10",
+            "pop r9",
+            "pop r8",
+            "pop rdi",
+            "pop rsi",
+            "pop rdx",
+            "pop rcx",
+            "pop rax",
+            "add rsp, 8",
+            "swapgs",
+            kpti_exit!(),
+            "swapgs",
+            "iretq",
+            handler = sym handlers::page_fault,
+            body = sym handlers::page_fault_from_user,
+        );
+    }
+
+    /// Points the exception vectors at the isolation trampolines.
+    ///
+    /// Called after the `exception_table!` registration; the IST and DPL
+    /// settings are reapplied because replacing the address resets them.
+    pub fn install_trampolines(idt: &mut InterruptDescriptorTable, double_fault_ist: u8) {
+        macro_rules! install {
+            ($($field:ident),* $(,)?) => {
+                $(idt.$field.set_raw_handler_addr($field as *const () as u64);)*
+            };
+        }
+        install!(
+            divide_error,
+            debug,
+            nmi,
+            breakpoint,
+            overflow,
+            bound_range,
+            invalid_opcode,
+            device_not_available,
+            double_fault,
+            invalid_tss,
+            segment_not_present,
+            stack_segment_fault,
+            general_protection,
+            page_fault,
+            x87_floating_point,
+            alignment_check,
+            machine_check,
+            simd_floating_point,
+            virtualization,
+            control_protection,
+            hypervisor_injection,
+            vmm_communication,
+            security_exception,
+        );
+        idt.breakpoint
+            .set_raw_handler_addr(breakpoint as *const () as u64)
+            .set_dpl(3);
+        idt.double_fault
+            .set_raw_handler_addr(double_fault as *const () as u64)
+            .set_ist_index(double_fault_ist);
+    }
+}
@@ -12,15 +12,18 @@ pub mod hw;
 pub mod idt;
 pub mod instructions;
 pub mod interrupts;
+pub mod kpti;
 #[cfg(not(hadron_acpi))]
 pub mod legacy;
 pub mod mem;
 pub mod paging;
+pub mod pcid;
 pub mod registers;
 #[cfg(hadron_smp)]
 pub mod smp;
 pub mod structures;
 pub mod syscall;
+pub mod uaccess;
 pub mod userspace;
 
 // Re-export commonly used types for ergonomic imports.
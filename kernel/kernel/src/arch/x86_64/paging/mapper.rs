@@ -7,6 +7,17 @@ use crate::mm::mapper::{self, MapFlags, MapFlush};
 use crate::paging::{Page, PhysFrame, Size1GiB, Size2MiB, Size4KiB};
 use hadron_core::assert_unsafe_precondition;
 
+/// Size of a 2 MiB huge page.
+const SIZE_2MIB: u64 = 0x20_0000;
+
+/// Returns the 2 MiB-aligned physical base of a huge page entry.
+///
+/// Bit 12 of a huge page entry is `PAT_HUGE`, not an address bit, but
+/// [`PageTableEntry::address`] includes it.
+fn huge_2mib_base(entry: PageTableEntry) -> PhysAddr {
+    entry.address().align_down(SIZE_2MIB)
+}
+
 /// Result of translating a virtual address.
 #[derive(Debug, Clone, Copy)]
 pub enum TranslateResult {
@@ -313,7 +324,7 @@ impl PageTableMapper {
         }
         if pde.flags().contains(PageTableFlags::HUGE_PAGE) {
             return TranslateResult::Page2MiB {
-                phys_start: pde.address(),
+                phys_start: huge_2mib_base(pde),
                 flags: pde.flags(),
             };
         }
@@ -453,6 +464,68 @@ impl PageTableMapper {
         Ok(frame)
     }
 
+    /// Splits a 2 MiB huge page into a page table of 512 4 KiB pages.
+    ///
+    /// The new PTEs map the same physical memory with the same flags; a
+    /// `PAT_HUGE` memory type is carried over as `PAT_4K`. `alloc` is only
+    /// called once the huge page has been found.
+    ///
+    /// Does NOT flush the TLB -- the caller must do that.
+    ///
+    /// # Safety
+    /// - `pml4_phys` must point to a valid PML4 table.
+    /// - The caller must flush the TLB for `virt_addr` after splitting.
+    pub unsafe fn split_2mib(
+        &self,
+        pml4_phys: PhysAddr,
+        virt_addr: VirtAddr,
+        alloc: &mut (impl FnMut() -> PhysFrame<Size4KiB> + ?Sized),
+    ) -> Result<(), UnmapError> {
+        let pml4_idx = virt_addr.pml4_index().as_usize();
+        let pdpt_idx = virt_addr.pdpt_index().as_usize();
+        let pd_idx = virt_addr.pd_index().as_usize();
+
+        let pml4 = unsafe { self.table_at(pml4_phys) };
+        let pml4e = pml4.entries[pml4_idx];
+        if !pml4e.is_present() {
+            return Err(UnmapError::NotMapped);
+        }
+
+        let pdpt = unsafe { self.table_at(pml4e.address()) };
+        let pdpte = pdpt.entries[pdpt_idx];
+        if !pdpte.is_present() {
+            return Err(UnmapError::NotMapped);
+        }
+        if pdpte.flags().contains(PageTableFlags::HUGE_PAGE) {
+            return Err(UnmapError::HugePage); // 1 GiB page, not 2 MiB
+        }
+
+        let pd = unsafe { self.table_at(pdpte.address()) };
+        let pde = pd.entries[pd_idx];
+        if !pde.is_present() {
+            return Err(UnmapError::NotMapped);
+        }
+        if !pde.flags().contains(PageTableFlags::HUGE_PAGE) {
+            return Err(UnmapError::HugePage); // 4 KiB pages, not 2 MiB
+        }
+
+        let phys_start = huge_2mib_base(pde);
+        let mut leaf = pde.flags() & !PageTableFlags::HUGE_PAGE;
+        if pde.address().as_u64() & PageTableFlags::PAT_HUGE.bits() != 0 {
+            leaf |= PageTableFlags::PAT_4K;
+        }
+
+        let pt_phys = alloc().start_address();
+        // SAFETY: The frame was just allocated and is accessible through the
+        // HHDM. Every entry is written below, so it need not be zeroed.
+        let pt = unsafe { self.table_at(pt_phys) };
+        for (i, entry) in pt.entries.iter_mut().enumerate() {
+            *entry = PageTableEntry::new(phys_start + (i * PAGE_SIZE) as u64, leaf);
+        }
+        pd.entries[pd_idx] = PageTableEntry::new(pt_phys, Self::intermediate_flags_for(leaf));
+        Ok(())
+    }
+
     /// Unmaps a 1 GiB huge page and returns the physical frame that was mapped.
     ///
     /// Does NOT flush the TLB -- the caller must do that.
@@ -530,7 +603,7 @@ impl PageTableMapper {
         }
 
         pd.entries[pd_idx] =
-            PageTableEntry::new(pde.address(), new_flags | PageTableFlags::HUGE_PAGE);
+            PageTableEntry::new(huge_2mib_base(pde), new_flags | PageTableFlags::HUGE_PAGE);
         Ok(())
     }
 
@@ -605,8 +678,9 @@ impl PageTableMapper {
             // PAT entry 4 = WC (programmed at boot). PAT index 4 = {PAT=1, PCD=0, PWT=0}.
             // For 4 KiB pages: set PAT_4K (bit 7), clear PCD and PWT.
             // For 2 MiB pages: set PAT_HUGE (bit 12), clear PCD and PWT.
-            // We set both PAT bits here; the mapper uses PAT_4K for 4 KiB PTEs
-            // and PAT_HUGE for 2 MiB PD entries (they never appear in the same entry).
+            // We set both PAT bits here; the 4 KiB trait impl strips PAT_HUGE
+            // (bit 12 is an address bit in a PTE), while in a 2 MiB PD entry
+            // bit 7 is HUGE_PAGE, which is set anyway.
             native |= PageTableFlags::PAT_4K | PageTableFlags::PAT_HUGE;
             native &= !(PageTableFlags::CACHE_DISABLE | PageTableFlags::WRITE_THROUGH);
         }
@@ -625,7 +699,7 @@ unsafe impl mapper::PageMapper<Size4KiB> for PageTableMapper {
         flags: MapFlags,
         alloc: &mut dyn FnMut() -> PhysFrame<Size4KiB>,
     ) -> MapFlush {
-        let native = Self::map_flags_to_native(flags);
+        let native = Self::map_flags_to_native(flags) & !PageTableFlags::PAT_HUGE;
         let virt = page.start_address();
         // SAFETY: Caller guarantees root is valid.
         unsafe { self.map_4k(root, virt, frame.start_address(), native, alloc) }
@@ -655,7 +729,7 @@ unsafe impl mapper::PageMapper<Size4KiB> for PageTableMapper {
         flags: MapFlags,
     ) -> Result<MapFlush, mapper::UnmapError> {
         let virt = page.start_address();
-        let native = Self::map_flags_to_native(flags);
+        let native = Self::map_flags_to_native(flags) & !PageTableFlags::PAT_HUGE;
         // SAFETY: Caller guarantees root is valid.
         unsafe {
             self.update_flags_4k(root, virt, native)
@@ -776,6 +850,27 @@ unsafe impl mapper::PageMapper<Size1GiB> for PageTableMapper {
     }
 }
 
+// SAFETY: `split_2mib` maps the same physical range with the same flags and
+// memory type through a new page table.
+unsafe impl mapper::PageSplitter<Size2MiB> for PageTableMapper {
+    unsafe fn split(
+        &self,
+        root: PhysAddr,
+        page: Page<Size2MiB>,
+        alloc: &mut dyn FnMut() -> PhysFrame<Size4KiB>,
+    ) -> Result<MapFlush, mapper::UnmapError> {
+        let virt = page.start_address();
+        // SAFETY: Caller guarantees root is valid.
+        unsafe {
+            self.split_2mib(root, virt, alloc).map_err(|e| match e {
+                UnmapError::NotMapped => mapper::UnmapError::NotMapped,
+                UnmapError::HugePage => mapper::UnmapError::SizeMismatch,
+            })?;
+        }
+        Ok(MapFlush::new(virt))
+    }
+}
+
 // SAFETY: `PageTableMapper` correctly walks x86_64 4-level page tables
 // for address translation via the HHDM.
 unsafe impl mapper::PageTranslator for PageTableMapper {
@@ -0,0 +1,267 @@
+//! Process-context identifiers (PCIDs).
+//!
+//! With CR4.PCIDE set, every TLB entry is tagged with the PCID in CR3[11:0]
+//! at the time it was filled, and a CR3 write with bit 63 set keeps the
+//! entries of the PCID being loaded. Each user [`AddressSpace`] gets its
+//! own PCID on every CPU it runs on, so switching back to a process whose
+//! translations are still cached does not refill the TLB from scratch.
+//!
+//! PCIDs are allocated per CPU with generation-based recycling, like ASIDs
+//! on other architectures:
+//!
+//! - Each CPU hands out PCIDs `1..NR_PCIDS` in order. When they run out,
+//!   it starts a new *generation* and hands them out again from 1.
+//! - An address space remembers, per CPU, the generation and PCID it was
+//!   last given there (its ASID tag). A tag from an older generation is
+//!   stale: the PCID may have been given to someone else since.
+//! - A PCID is flushed whenever it is (re)assigned, so recycling needs no
+//!   global flush.
+//! - Each CPU also remembers, per PCID, the address space's TLB generation
+//!   at the last flush. If the address space has removed or downgraded a
+//!   translation since (possibly on another CPU), the PCID is flushed
+//!   before it is reused.
+//!
+//! PCID 0 belongs to the kernel page tables, which are always loaded with
+//! a flush. Kernel mappings that are not global are cached under every
+//! PCID, so unmapping one bumps a global kernel TLB generation, and each
+//! CPU starts a new generation when it next sees it changed.
+//!
+//! Under KPTI, the shadow page tables of an address space run as its PCID
+//! with bit 11 ([`SHADOW_PCID_BIT`]) set. When the CPU supports INVPCID,
+//! user invalidations are mirrored into the shadow PCID so that exits to
+//! user mode can keep its entries; otherwise every exit flushes it.
+//!
+//! Without PCID support, [`switch_to`] simply loads the root and flushes
+//! the TLB as before.
+
+use core::cell::UnsafeCell;
+
+use hadron_core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use super::cpuid::{self, CpuFeatures};
+use super::instructions::interrupts::without_interrupts;
+use super::instructions::tlb::{self, InvpcidKind};
+use super::paging::PageTableMapper;
+use super::registers::control::{Cr3, Cr4, Cr4Flags};
+use crate::addr::VirtAddr;
+use crate::mm::address_space::AddressSpace;
+use crate::percpu::{CpuLocal, MAX_CPUS, PerCpuState};
+
+/// CR3 bit 63: keep the TLB entries of the PCID being loaded.
+pub const CR3_NOFLUSH: u64 = 1 << 63;
+
+/// Set in the PCID of KPTI shadow page tables.
+pub const SHADOW_PCID_BIT: u64 = 1 << 11;
+
+/// Number of PCIDs per CPU, including the kernel's PCID 0. Must stay below
+/// [`SHADOW_PCID_BIT`].
+const NR_PCIDS: usize = 128;
+
+/// Mask of the PCID in a CR3 value or an ASID tag.
+const PCID_MASK: u64 = 0xfff;
+
+/// Whether CR4.PCIDE is set on every CPU.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Whether INVPCID is available.
+static INVPCID: AtomicBool = AtomicBool::new(false);
+
+/// Bumped after every invalidation of a kernel translation.
+static KERNEL_TLB_GEN: AtomicU64 = AtomicU64::new(0);
+
+/// A CPU's PCID allocator.
+struct PcidState {
+    /// Current generation. Starts at 1 so that a zero ASID tag is never
+    /// current.
+    generation: u64,
+    /// Next PCID to hand out in this generation.
+    next: u64,
+    /// Kernel TLB generation when this CPU's current generation started.
+    kernel_gen: u64,
+    /// TLB generation of the owning address space when each PCID was last
+    /// flushed.
+    flushed_gen: [u64; NR_PCIDS],
+}
+
+impl PcidState {
+    const fn new() -> Self {
+        Self {
+            generation: 1,
+            next: 1,
+            kernel_gen: 0,
+            flushed_gen: [0; NR_PCIDS],
+        }
+    }
+
+    /// Hands out the next PCID, starting a new generation if none are left.
+    fn allocate(&mut self) -> u64 {
+        if self.next as usize == NR_PCIDS {
+            self.new_generation();
+        }
+        let pcid = self.next;
+        self.next += 1;
+        pcid
+    }
+
+    /// Invalidates every ASID tag handed out by this CPU so far.
+    fn new_generation(&mut self) {
+        self.generation += 1;
+        self.next = 1;
+    }
+}
+
+/// Per-CPU PCID allocators. Only touched by the owning CPU with interrupts
+/// disabled.
+static STATE: CpuLocal<UnsafeCell<PcidState>> =
+    CpuLocal::new([const { UnsafeCell::new(PcidState::new()) }; MAX_CPUS]);
+
+/// Runs `f` on the current CPU's allocator with interrupts disabled.
+fn with_state<R>(f: impl FnOnce(&mut PcidState, usize) -> R) -> R {
+    without_interrupts(|| {
+        let cpu = PerCpuState::current().get_cpu_id().as_u32() as usize;
+        // SAFETY: Only this CPU accesses its slot, and interrupts are
+        // disabled, so nothing else on this CPU can re-enter.
+        let state = unsafe { &mut *STATE.get().get() };
+        f(state, cpu)
+    })
+}
+
+/// Returns `true` if user address spaces run with their own PCIDs.
+#[inline]
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Sets CR4.PCIDE on the calling CPU if PCIDs are supported.
+///
+/// # Safety
+///
+/// Must be called after [`cpuid::init()`] on the BSP, or after
+/// [`cpuid::verify_ap()`] on APs, while CR3[11:0] is zero.
+pub unsafe fn init_cpu() {
+    let features = cpuid::cpu_features();
+    if !features.contains(CpuFeatures::PCID) {
+        return;
+    }
+    INVPCID.store(features.contains(CpuFeatures::INVPCID), Ordering::Relaxed);
+    // SAFETY: PCIDE may be set in long mode with CR3[11:0] = 0 (caller
+    // contract). Until a tagged CR3 is loaded, everything runs as PCID 0.
+    unsafe { Cr4::write(Cr4::read() | Cr4Flags::PCIDE) };
+    if !ENABLED.swap(true, Ordering::Release) {
+        crate::kinfo!(
+            "PCID: enabled ({} per CPU, INVPCID {})",
+            NR_PCIDS - 1,
+            if INVPCID.load(Ordering::Relaxed) {
+                "on"
+            } else {
+                "off"
+            }
+        );
+    }
+}
+
+/// Loads `space` into CR3 on the current CPU, tagged with its PCID.
+///
+/// Returns the CR3 value loaded, without the no-flush bit: the root with
+/// the PCID in bits 11:0 (zero without PCIDs).
+///
+/// # Safety
+///
+/// `space` must share the kernel upper half, so that the caller keeps
+/// running after the switch, and must stay alive while it is loaded.
+pub unsafe fn switch_to(space: &AddressSpace<PageTableMapper>) -> u64 {
+    let root = space.root_phys().as_u64();
+    if !enabled() {
+        // SAFETY: Forwarded from the caller.
+        unsafe { Cr3::write_raw(root) };
+        return root;
+    }
+
+    with_state(|state, cpu| {
+        let kernel_gen = KERNEL_TLB_GEN.load(Ordering::Acquire);
+        if state.kernel_gen != kernel_gen {
+            state.kernel_gen = kernel_gen;
+            state.new_generation();
+        }
+
+        let slot = space.asid_tag(cpu);
+        let tag = slot.load(Ordering::Relaxed);
+        let tlb_gen = space.tlb_generation();
+
+        let pcid = if tag >> 12 == state.generation {
+            let pcid = tag & PCID_MASK;
+            if state.flushed_gen[pcid as usize] == tlb_gen {
+                // SAFETY: Forwarded from the caller; this PCID has held
+                // only `space`'s translations since it was last flushed,
+                // and none of them have changed since.
+                unsafe { Cr3::write_raw(root | pcid | CR3_NOFLUSH) };
+                return root | pcid;
+            }
+            pcid
+        } else {
+            let pcid = state.allocate();
+            slot.store((state.generation << 12) | pcid, Ordering::Relaxed);
+            pcid
+        };
+
+        state.flushed_gen[pcid as usize] = tlb_gen;
+        // SAFETY: Forwarded from the caller. Loading without the no-flush
+        // bit drops whatever the PCID held before.
+        unsafe {
+            flush_shadow(pcid);
+            Cr3::write_raw(root | pcid);
+        }
+        root | pcid
+    })
+}
+
+/// Flushes the KPTI shadow PCID belonging to `pcid`.
+///
+/// Without INVPCID, exits to user mode flush the shadow PCID themselves.
+///
+/// # Safety
+///
+/// PCIDs must be enabled.
+unsafe fn flush_shadow(pcid: u64) {
+    if cfg!(hadron_kpti) && INVPCID.load(Ordering::Relaxed) {
+        // SAFETY: INVPCID is supported; the PCID fits in 12 bits.
+        unsafe {
+            tlb::invpcid(
+                InvpcidKind::SingleContext,
+                pcid | SHADOW_PCID_BIT,
+                VirtAddr::zero(),
+            );
+        }
+    }
+}
+
+/// Returns `true` if exits to user mode may keep the TLB entries of the
+/// KPTI shadow PCID, because every user invalidation reaches it directly.
+pub fn shadow_keeps_tlb() -> bool {
+    enabled() && INVPCID.load(Ordering::Relaxed)
+}
+
+/// Invalidates the TLB entry for `addr` in every PCID that may hold it on
+/// the current CPU.
+///
+/// Registered as the `hadron-mm` TLB flush hook. User translations only
+/// need flushing in the current PCID (and its shadow): other address
+/// spaces do not map them, and the address space's TLB generation covers
+/// its other PCIDs. A kernel translation may be cached under any PCID on
+/// any CPU, so unmapping one also bumps the kernel TLB generation, which
+/// makes every CPU flush each PCID before its next use.
+pub fn flush_page(addr: VirtAddr) {
+    tlb::flush(addr);
+    if !enabled() {
+        return;
+    }
+    if addr.as_u64() >= 0xffff_8000_0000_0000 {
+        KERNEL_TLB_GEN.fetch_add(1, Ordering::Release);
+    } else if cfg!(hadron_kpti) && INVPCID.load(Ordering::Relaxed) {
+        let pcid = Cr3::read_raw() & PCID_MASK;
+        if pcid != 0 {
+            // SAFETY: INVPCID is supported; the PCID fits in 12 bits.
+            unsafe { tlb::invpcid(InvpcidKind::Address, pcid | SHADOW_PCID_BIT, addr) };
+        }
+    }
+}
@@ -36,10 +36,18 @@ bitflags::bitflags! {
         const OSFXSR     = 1 << 9;
         /// OS handles SIMD floating-point exceptions (#XM, vector 19).
         const OSXMMEXCPT = 1 << 10;
+        /// User-Mode Instruction Prevention (SGDT/SIDT/SLDT/SMSW/STR fault in ring 3).
+        const UMIP       = 1 << 11;
         /// 57-bit linear addresses (5-level paging).
         const LA57       = 1 << 12;
+        /// Process-context identifiers (CR3 bits 11:0 tag TLB entries).
+        const PCIDE      = 1 << 17;
         /// XSAVE/XRSTOR and XGETBV/XSETBV support.
         const OSXSAVE    = 1 << 18;
+        /// Supervisor Mode Execution Prevention.
+        const SMEP       = 1 << 20;
+        /// Supervisor Mode Access Prevention.
+        const SMAP       = 1 << 21;
     }
 }
 
@@ -90,13 +98,21 @@ pub struct Cr3;
 
 impl Cr3 {
     /// Reads the current page table root physical address from CR3.
+    ///
+    /// The PCID in bits 11:0 is masked off; see [`read_raw`](Self::read_raw).
     #[inline]
     pub fn read() -> PhysAddr {
+        PhysAddr::new_truncate(Self::read_raw() & !0xfff)
+    }
+
+    /// Reads the raw CR3 value, including the PCID in bits 11:0.
+    #[inline]
+    pub fn read_raw() -> u64 {
         let val: u64;
         unsafe {
             core::arch::asm!("mov {}, cr3", out(reg) val, options(nomem, nostack, preserves_flags));
         }
-        PhysAddr::new_truncate(val)
+        val
     }
 
     /// Writes a new page table root physical address to CR3.
@@ -107,8 +123,21 @@ impl Cr3 {
     /// PML4 page table.
     #[inline]
     pub unsafe fn write(addr: PhysAddr) {
+        unsafe { Self::write_raw(addr.as_u64()) };
+    }
+
+    /// Writes a raw value to CR3: a page table root tagged with a PCID in
+    /// bits 11:0 and, in bit 63, the flag that keeps that PCID's TLB
+    /// entries.
+    ///
+    /// # Safety
+    ///
+    /// As for [`write`](Self::write). The PCID and no-flush bits must be
+    /// zero unless CR4.PCIDE is set.
+    #[inline]
+    pub unsafe fn write_raw(val: u64) {
         unsafe {
-            core::arch::asm!("mov cr3, {}", in(reg) addr.as_u64(), options(nostack, preserves_flags));
+            core::arch::asm!("mov cr3, {}", in(reg) val, options(nostack, preserves_flags));
         }
     }
 }
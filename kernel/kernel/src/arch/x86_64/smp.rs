@@ -232,7 +232,7 @@ fn ap_entry(_mp_info: u64, percpu_addr: u64) -> ! {
     // Must be done BEFORE setting GS base because `load_gs(null)` in GDT
     // init clears the GS base MSR on Intel CPUs.
     // SAFETY: Heap and VMM are initialized by BSP. Called once per AP.
-    let kernel_stack_top = unsafe { super::gdt::init_ap(cpu_id) };
+    let (kernel_stack_top, _tables) = unsafe { super::gdt::init_ap(cpu_id) };
 
     // 2. Set GS base to our PerCpu struct.
     // Done AFTER GDT init because `load_gs(null_selector)` clears GS base.
@@ -254,12 +254,24 @@ fn ap_entry(_mp_info: u64, percpu_addr: u64) -> ! {
     // SAFETY: IDT is initialized by BSP and is a shared immutable static.
     unsafe { super::idt::init() };
 
-    // 3b. Verify CPUID features match BSP and enable FPU support.
+    // 3b. Verify CPUID features match BSP, enable FPU support,
+    // SMEP/SMAP/UMIP and PCIDs.
     super::cpuid::verify_ap();
     #[cfg(hadron_kernel_fpu)]
     unsafe {
         super::fpu::enable_fpu_support();
     }
+    // SAFETY: CPUID features were verified above.
+    unsafe { super::uaccess::enable_protections() };
+    // SAFETY: As above; CR3 still holds the untagged kernel root.
+    unsafe { super::pcid::init_cpu() };
+
+    // 3c. Map this AP's entry state into the KPTI shadow page tables.
+    // SAFETY: GDT, TSS and GS base are set up; interrupts are disabled.
+    #[cfg(hadron_kpti)]
+    unsafe {
+        super::kpti::init_ap(&_tables);
+    }
 
     // 4. Initialize SYSCALL/SYSRET MSRs.
     // SAFETY: GDT is loaded, GS base is set.
@@ -81,34 +81,34 @@ impl Descriptor {
 
     /// Creates a 64-bit kernel code segment descriptor.
     ///
-    /// L=1, D=0, P=1, DPL=0, type=execute/read.
+    /// L=1, D=0, P=1, DPL=0, type=execute/read, accessed.
     #[inline]
     pub const fn kernel_code_segment() -> Self {
-        Self::UserSegment(0x00AF_9A00_0000_FFFF)
+        Self::UserSegment(0x00AF_9B00_0000_FFFF)
     }
 
     /// Creates a kernel data segment descriptor.
     ///
-    /// P=1, DPL=0, type=read/write.
+    /// P=1, DPL=0, type=read/write, accessed.
     #[inline]
     pub const fn kernel_data_segment() -> Self {
-        Self::UserSegment(0x00CF_9200_0000_FFFF)
+        Self::UserSegment(0x00CF_9300_0000_FFFF)
     }
 
     /// Creates a 64-bit user code segment descriptor.
     ///
-    /// L=1, D=0, P=1, DPL=3, type=execute/read.
+    /// L=1, D=0, P=1, DPL=3, type=execute/read, accessed.
     #[inline]
     pub const fn user_code_segment() -> Self {
-        Self::UserSegment(0x00AF_FA00_0000_FFFF)
+        Self::UserSegment(0x00AF_FB00_0000_FFFF)
     }
 
     /// Creates a user data segment descriptor.
     ///
-    /// P=1, DPL=3, type=read/write.
+    /// P=1, DPL=3, type=read/write, accessed.
     #[inline]
     pub const fn user_data_segment() -> Self {
-        Self::UserSegment(0x00CF_F200_0000_FFFF)
+        Self::UserSegment(0x00CF_F300_0000_FFFF)
     }
 
     /// TSS type: 64-bit TSS (available).
@@ -348,6 +348,22 @@ mod tests {
         assert_eq!((bits >> 45) & 0b11, 3, "DPL should be 3 for user code");
     }
 
+    #[test]
+    fn segments_preset_accessed_bit() {
+        for desc in [
+            Descriptor::kernel_code_segment(),
+            Descriptor::kernel_data_segment(),
+            Descriptor::user_code_segment(),
+            Descriptor::user_data_segment(),
+        ] {
+            let Descriptor::UserSegment(bits) = desc else {
+                panic!("expected UserSegment");
+            };
+            // Accessed (bit 40) — the CPU never writes it back.
+            assert_ne!(bits & (1 << 40), 0, "accessed bit not set");
+        }
+    }
+
     #[test]
     #[should_panic(expected = "GDT full")]
     fn gdt_overflow_panics() {
@@ -187,7 +187,10 @@ impl IdtEntry {
 }
 
 /// The Interrupt Descriptor Table with named fields for all 32 CPU exceptions.
-#[repr(C, align(16))]
+///
+/// Exactly one page (256 × 16 bytes) and page-aligned, so KPTI can map it
+/// into the shadow page tables without exposing neighbouring data.
+#[repr(C, align(4096))]
 pub struct InterruptDescriptorTable {
     /// Vector 0: Divide Error (#DE).
     pub divide_error: IdtEntry,
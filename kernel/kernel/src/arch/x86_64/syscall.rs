@@ -12,10 +12,12 @@
 
 use core::cell::UnsafeCell;
 
+use super::kpti::{kpti_syscall_enter, kpti_syscall_exit};
 use super::registers::model_specific::{EferFlags, IA32_EFER, MSR_LSTAR, MSR_SFMASK, MSR_STAR};
 
-/// RFLAGS bits to mask on SYSCALL entry: IF (bit 9) + DF (bit 10).
-const SFMASK_VALUE: u64 = 0x600;
+/// RFLAGS bits to mask on SYSCALL entry: IF (bit 9) + DF (bit 10) + AC
+/// (bit 18). Clearing AC keeps SMAP armed even if userspace set it.
+const SFMASK_VALUE: u64 = 0x4_0600;
 
 /// User registers saved at every SYSCALL entry.
 ///
@@ -94,7 +96,7 @@ pub static SYSCALL_SAVED_REGS: crate::percpu::CpuLocal<SyncSavedRegs> =
 ///   - SYSRET loads:  SS = 0x10+8 = 0x18 (user data), CS = 0x10+16 = 0x20 (user code)
 ///     (both OR'd with RPL=3)
 /// - **LSTAR**: Address of [`syscall_entry`]
-/// - **SFMASK**: Masks IF and DF in RFLAGS on entry
+/// - **SFMASK**: Masks IF, DF, and AC in RFLAGS on entry
 ///
 /// # Safety
 ///
@@ -114,7 +116,7 @@ pub unsafe fn init() {
         // LSTAR: syscall entry point
         MSR_LSTAR.write(syscall_entry as *const () as usize as u64);
 
-        // SFMASK: mask IF and DF on entry
+        // SFMASK: mask IF, DF, and AC on entry
         MSR_SFMASK.write(SFMASK_VALUE);
     }
 
@@ -154,12 +156,18 @@ unsafe extern "C" {
 /// The exit path checks if the return RIP is in kernel space (bit 63 set)
 /// and uses `iretq` instead of `sysretq` for ring 0 callers, since `sysretq`
 /// unconditionally loads ring 3 CS/SS.
+///
+/// With `hadron_kpti`, the entry switches from the shadow page tables to the
+/// full ones right after saving the user RSP, and the `sysretq` path switches
+/// back just before restoring it (see [`super::kpti`]).
 #[unsafe(naked)]
+#[unsafe(link_section = ".text.hadron_entry")]
 unsafe extern "C" fn syscall_entry() {
     core::arch::naked_asm!(
         // Switch to kernel GS and stack
         "swapgs",
         "mov gs:[16], rsp",         // save caller RSP to percpu.user_rsp (offset 16)
+        kpti_syscall_enter!(),      // switch to the full page tables (KPTI only)
         "mov rsp, gs:[8]",          // load kernel RSP from percpu.kernel_rsp (offset 8)
 
         // Save caller return state and callee-saved registers
@@ -236,6 +244,7 @@ unsafe extern "C" fn syscall_entry() {
         "js 2f",
 
         // --- User return path (sysretq) ---
+        kpti_syscall_exit!(),       // switch to the shadow page tables (KPTI only)
         "mov rsp, gs:[16]",         // restore caller RSP from percpu.user_rsp (offset 16)
         "swapgs",
         "sysretq",
@@ -0,0 +1,163 @@
+//! User memory access under SMAP, with page fault recovery.
+//!
+//! With CR4.SMAP set, any supervisor-mode access to a user page faults
+//! unless RFLAGS.AC is set. The copy routines here are the only code that
+//! opens such a window: they bracket a single `rep movsb` with `stac` /
+//! `clac` and register the `rep movsb` in the kernel exception table
+//! (`.hadron_extable`). A page fault at a registered instruction resumes at
+//! its fixup label instead of panicking, so a bad user pointer turns into
+//! `EFAULT` rather than a kernel crash.
+//!
+//! `stac` / `clac` are emitted as 3-byte NOPs and patched in by the
+//! alternative-instruction pass on CPUs with SMAP, which is why SMAP is
+//! only armed when alternative-instruction patching is compiled in.
+//!
+//! # References
+//!
+//! - Intel SDM Vol. 3A, §4.6: Access Rights (SMEP, SMAP)
+//! - Intel SDM Vol. 3A, §2.5: Control Registers (CR4.UMIP)
+
+use hadron_core::cpu_features::CpuFeatures;
+
+use super::cpuid;
+use super::registers::control::{Cr4, Cr4Flags};
+
+/// One exception table entry: a faulting instruction and where to resume.
+///
+/// Emitted into `.hadron_extable` by the assembly in this module. Both
+/// addresses are absolute, so they are slid along with the kernel image.
+#[repr(C)]
+pub struct ExceptionTableEntry {
+    /// Address of the instruction that may fault.
+    pub fault_ip: u64,
+    /// Address execution resumes at after a fault.
+    pub fixup_ip: u64,
+}
+
+hadron_linkset::declare_linkset! {
+    /// Returns all exception table entries from the `.hadron_extable` linker section.
+    pub fn exception_table_entries() -> [ExceptionTableEntry],
+    section = "hadron_extable"
+}
+
+/// Returns the fixup address for a fault at `ip`, if `ip` is a registered
+/// user-access instruction.
+pub fn search_exception_table(ip: u64) -> Option<u64> {
+    exception_table_entries()
+        .iter()
+        .find(|entry| entry.fault_ip == ip)
+        .map(|entry| entry.fixup_ip)
+}
+
+/// Copies `len` bytes from `src` to `dst` with user access enabled.
+///
+/// Returns the number of bytes **not** copied: 0 on success, non-zero if a
+/// page fault interrupted the copy.
+///
+/// # Safety
+///
+/// One side must be user memory and the other a valid kernel buffer of at
+/// least `len` bytes. The user range must lie below the user/kernel split.
+#[unsafe(naked)]
+unsafe extern "C" fn copy_user_raw(dst: *mut u8, src: *const u8, len: usize) -> usize {
+    core::arch::naked_asm!(
+        "mov rcx, rdx",
+        hadron_core::alt_instr!(
+            uaccess_copy_stac,
+            default = ".byte 0x0f, 0x1f, 0x00",
+            alternative = "stac",
+            feature_bits = hadron_core::alt_instr_feature_bits!(SMAP),
+            priority = 1,
+        ),
+        "2:",
+        "rep movsb",
+        hadron_core::alt_instr!(
+            uaccess_copy_clac,
+            default = ".byte 0x0f, 0x1f, 0x00",
+            alternative = "clac",
+            feature_bits = hadron_core::alt_instr_feature_bits!(SMAP),
+            priority = 1,
+        ),
+        "xor eax, eax",
+        "ret",
+        // Fixup: the page fault handler resumes here with RCX holding the
+        // number of bytes rep movsb had left to copy.
+        "3:",
+        hadron_core::alt_instr!(
+            uaccess_copy_fixup_clac,
+            default = ".byte 0x0f, 0x1f, 0x00",
+            alternative = "clac",
+            feature_bits = hadron_core::alt_instr_feature_bits!(SMAP),
+            priority = 1,
+        ),
+        "mov rax, rcx",
+        "ret",
+        ".pushsection .hadron_extable, \"a\"",
+        ".balign 8",
+        ".quad 2b",
+        ".quad 3b",
+        ".popsection",
+    );
+}
+
+/// Copies `dst.len()` bytes from user address `src` into `dst`.
+///
+/// Returns `Err(n)` with the number of bytes left uncopied if part of the
+/// source is not mapped readable.
+///
+/// # Safety
+///
+/// `src..src + dst.len()` must lie entirely below the user/kernel split
+/// (see [`UserSlice`](crate::syscall::userptr::UserSlice)).
+pub unsafe fn copy_from_user(dst: &mut [u8], src: usize) -> Result<(), usize> {
+    // SAFETY: dst is a valid kernel buffer; the caller validated the range.
+    let left = unsafe { copy_user_raw(dst.as_mut_ptr(), src as *const u8, dst.len()) };
+    if left == 0 { Ok(()) } else { Err(left) }
+}
+
+/// Copies `src` to user address `dst`.
+///
+/// Returns `Err(n)` with the number of bytes left uncopied if part of the
+/// destination is not mapped writable.
+///
+/// # Safety
+///
+/// `dst..dst + src.len()` must lie entirely below the user/kernel split
+/// (see [`UserSlice`](crate::syscall::userptr::UserSlice)).
+pub unsafe fn copy_to_user(dst: usize, src: &[u8]) -> Result<(), usize> {
+    // SAFETY: src is a valid kernel buffer; the caller validated the range.
+    let left = unsafe { copy_user_raw(dst as *mut u8, src.as_ptr(), src.len()) };
+    if left == 0 { Ok(()) } else { Err(left) }
+}
+
+/// Sets CR4.SMEP, CR4.SMAP, and CR4.UMIP on the calling CPU, each only if
+/// the CPU supports it.
+///
+/// SMAP is left off unless alternative-instruction patching is compiled in,
+/// since the `stac` / `clac` in [`copy_user_raw`] would otherwise stay NOPs.
+/// It may be armed before `alt_instr::apply` runs: no user memory is
+/// touched until the first process is spawned, which happens after
+/// patching.
+///
+/// # Safety
+///
+/// Must be called after [`cpuid::init()`](super::cpuid::init) on the BSP,
+/// or after [`cpuid::verify_ap()`](super::cpuid::verify_ap) on APs.
+pub unsafe fn enable_protections() {
+    let features = cpuid::cpu_features();
+    let mut cr4 = Cr4::read();
+
+    if features.contains(CpuFeatures::SMEP) {
+        cr4 |= Cr4Flags::SMEP;
+    }
+    if cfg!(hadron_alt_instructions) && features.contains(CpuFeatures::SMAP) {
+        cr4 |= Cr4Flags::SMAP;
+    }
+    if features.contains(CpuFeatures::UMIP) {
+        cr4 |= Cr4Flags::UMIP;
+    }
+
+    // SAFETY: The kernel never executes user pages and only accesses them
+    // through copy_user_raw, so these bits do not affect correct code.
+    unsafe { Cr4::write(cr4) };
+}
@@ -5,6 +5,8 @@
 //! "returned from" via [`restore_kernel_context`], and [`UserRegisters`] for
 //! saving/restoring user-mode state.
 
+use super::kpti::kpti_enter_user;
+
 /// Saved user-mode register state.
 ///
 /// Stored when entering the kernel via SYSCALL or interrupt, and restored
@@ -137,6 +139,7 @@ pub unsafe extern "C" fn jump_to_userspace(entry: u64, user_rsp: u64) -> ! {
 /// - CR3 must already be loaded with the user address space.
 /// - Interrupts must be disabled.
 #[unsafe(naked)]
+#[unsafe(link_section = ".text.hadron_entry")]
 pub unsafe extern "C" fn enter_userspace_save(entry: u64, user_rsp: u64, saved_rsp_ptr: *mut u64) {
     core::arch::naked_asm!(
         // Save callee-saved registers so restore_kernel_context can pop them.
@@ -174,7 +177,8 @@ pub unsafe extern "C" fn enter_userspace_save(entry: u64, user_rsp: u64, saved_r
         "xor r14, r14",
         "xor r15, r15",
 
-        // Transition to ring 3.
+        // Switch to the shadow page tables (KPTI only) and enter ring 3.
+        kpti_enter_user!(),
         "iretq",
 
         user_ds = const USER_DATA_SELECTOR,
@@ -201,6 +205,7 @@ pub unsafe extern "C" fn enter_userspace_save(entry: u64, user_rsp: u64, saved_r
 /// - CR3 must already be loaded with the user address space.
 /// - Interrupts must be disabled.
 #[unsafe(naked)]
+#[unsafe(link_section = ".text.hadron_entry")]
 pub unsafe extern "C" fn enter_userspace_resume(
     ctx: *const UserRegisters,
     saved_rsp_ptr: *mut u64,
@@ -245,7 +250,8 @@ pub unsafe extern "C" fn enter_userspace_resume(
         "mov r15, [rdi + 112]",
         "mov rdi, [rdi + 40]",           // rdi last (was the pointer)
 
-        // Transition to ring 3.
+        // Switch to the shadow page tables (KPTI only) and enter ring 3.
+        kpti_enter_user!(),
         "iretq",
 
         user_ds = const USER_DATA_SELECTOR,
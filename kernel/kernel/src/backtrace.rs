@@ -69,6 +69,9 @@ struct HkifSections {
 static HKIF_STATE: SpinLock<Option<HkifState>> = SpinLock::leveled("HKIF_STATE", 4, None);
 
 /// Kernel virtual base address for offset-to-address conversion.
+///
+/// This is the load address (link base plus the KASLR slide), so HKIF
+/// offsets stay valid wherever the boot stub placed the image.
 static KERNEL_VIRT_BASE: AtomicU64 = AtomicU64::new(0);
 
 /// Combined state for the HKIF backtrace system.
@@ -101,7 +104,8 @@ impl Backtrace {
     /// and parses the section directory to locate symbol/line/string data.
     ///
     /// Must be called once during boot. The `kernel_virt_base` is the lowest
-    /// PT_LOAD virtual address of the kernel image.
+    /// PT_LOAD virtual address of the kernel image as loaded, i.e. after the
+    /// boot stub applied the KASLR slide.
     pub fn init_from_embedded(kernel_virt_base: u64) {
         let data = hkif_data();
 
@@ -350,6 +354,13 @@ fn print_frame_symbolicated(
 
     let _ = write!(writer, "  #{index}: {addr:#018x}");
 
+    // With KASLR, also print the link-time address so frames can be fed
+    // straight to addr2line/objdump on the kernel ELF.
+    if kernel_base != crate::mm::layout::KERNEL_IMAGE_BASE {
+        let link_addr = crate::mm::layout::KERNEL_IMAGE_BASE.wrapping_add(offset);
+        let _ = write!(writer, " [{link_addr:#018x}]");
+    }
+
     if let Some((name, func_offset)) = sym {
         let _ = write!(writer, " - {name}+{func_offset:#x}");
     }
@@ -328,7 +328,7 @@ pub fn kernel_init(boot_info: &impl BootInfo) -> ! {
 
     // 2a. Register architecture-specific TLB flush for hadron-mm.
     #[cfg(target_arch = "x86_64")]
-    crate::mm::mapper::register_tlb_flush(crate::arch::x86_64::instructions::tlb::flush);
+    crate::mm::mapper::register_tlb_flush(crate::arch::x86_64::pcid::flush_page);
 
     // 2b. Alt-fn/alt-instr patching deferred until after SMP boot (step 8c)
     // so that APs complete init (FPU, GS base) before SSE2 alternatives
@@ -339,7 +339,7 @@ pub fn kernel_init(boot_info: &impl BootInfo) -> ! {
         boot_info.kernel_address().virtual_base.as_u64(),
     );
 
-    // 3. Initialize PMM (bitmap from memory map).
+    // 3. Initialize PMM (buddy allocator from memory map).
     crate::mm::pmm::init(boot_info);
     let (free, total) = crate::mm::pmm::with(|pmm| (pmm.free_frames(), pmm.total_frames()));
     crate::kinfo!(
@@ -354,10 +354,10 @@ pub fn kernel_init(boot_info: &impl BootInfo) -> ! {
 
     // 4b. Allocate a guarded kernel syscall stack (replaces the early BSS stack).
     {
-        use crate::mm::pmm::BitmapFrameAllocRef;
+        use crate::mm::pmm::BuddyFrameAllocRef;
         let (bottom, top, guard) = crate::mm::vmm::with(|vmm| {
             crate::mm::pmm::with(|pmm| {
-                let mut alloc = BitmapFrameAllocRef(pmm);
+                let mut alloc = BuddyFrameAllocRef(pmm);
                 let stack = vmm
                     .alloc_kernel_stack(&mut alloc, None)
                     .expect("failed to allocate guarded kernel stack");
@@ -386,10 +386,23 @@ pub fn kernel_init(boot_info: &impl BootInfo) -> ! {
         );
     }
 
+    // 4c. Map the KASAN shadow of the HHDM and enable checking. The heap
+    // maps its own shadow as it grows.
+    #[cfg(hadron_kasan)]
+    crate::mm::kasan::init(boot_info);
+
     // 5. Map initial heap and initialize the heap allocator.
     crate::mm::heap::init();
     crate::kinfo!("Heap allocator initialized");
 
+    // 5a. Build the KPTI shadow page tables and move the BSP onto its entry
+    // stack. Must precede AP bring-up (platform_init) and the first process.
+    // SAFETY: Called once on the BSP with the heap available.
+    #[cfg(hadron_kpti)]
+    unsafe {
+        crate::arch::x86_64::kpti::init(&crate::arch::x86_64::gdt::bsp_tables());
+    }
+
     // [KTEST] Run early_boot stage tests (CPU, HHDM, PMM, VMM, heap available).
     #[cfg(ktest)]
     crate::ktest::run_sync_stage(hadron_ktest::TestStage::EarlyBoot);
@@ -412,6 +425,8 @@ pub fn kernel_init(boot_info: &impl BootInfo) -> ! {
 
     // 8. Arch-specific platform init (ACPI, PCI, drivers, etc.).
     crate::arch::platform_init(boot_info);
+    #[cfg(target_arch = "x86_64")]
+    crate::sched::init();
 
     // 8a. Initialize lock stress delays (after HPET is available).
     #[cfg(hadron_lock_stress)]
@@ -695,6 +710,10 @@ pub fn kernel_init(boot_info: &impl BootInfo) -> ! {
         }
     }
 
+    // 10c. Boot-time data is final: the GDT and IDT are built and alt-fn dispatch
+    // pointers are patched (8c). Write-protect `.data.ro_after_init`.
+    crate::mm::vmm::protect_ro_after_init();
+
     // [KTEST] Initialize watchdog, run before_executor stage, and spawn async test runner.
     #[cfg(ktest)]
     {
@@ -713,6 +732,14 @@ pub fn kernel_init(boot_info: &impl BootInfo) -> ! {
         crate::proc::enable_utest_mode();
     }
 
+    // `norandmaps` pins every user image to the fixed layout (no user ASLR).
+    if boot_info
+        .command_line()
+        .is_some_and(|c| c.split_whitespace().any(|t| t == "norandmaps"))
+    {
+        crate::proc::exec::disable_user_aslr();
+    }
+
     #[cfg(not(ktest))]
     crate::proc::spawn_init();
 
@@ -15,6 +15,7 @@ use core::pin::Pin;
 use crate::addr::PhysAddr;
 use crate::driver_api::framebuffer::{Framebuffer, PixelFormat};
 use crate::fs::{DirEntry, FsError, Inode, InodeType, Permissions};
+use crate::syscall::userptr::UserPtr;
 
 /// Framebuffer device inode wrapping an `Arc<dyn Framebuffer>`.
 pub struct DevFramebuffer {
@@ -104,25 +105,21 @@ impl Inode for DevFramebuffer {
                     },
                 };
 
-                let ptr = arg as *mut hadron_syscall::FbInfo;
-                if ptr.is_null() {
+                if arg == 0 {
                     return Err(FsError::InvalidArgument);
                 }
-                // SAFETY: The caller (ioctl syscall handler) has validated the
-                // user pointer and switched to the user's address space (CR3).
-                unsafe {
-                    core::ptr::write_volatile(ptr, fb_info);
-                }
+                UserPtr::<hadron_syscall::FbInfo>::new(arg)
+                    .and_then(|ptr| ptr.write(fb_info))
+                    .map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             hadron_syscall::FBIODIRTY => {
-                let ptr = arg as *const hadron_syscall::FbDirtyRect;
-                if ptr.is_null() {
+                if arg == 0 {
                     return Err(FsError::InvalidArgument);
                 }
-                // SAFETY: The caller (ioctl syscall handler) has validated the
-                // user pointer and switched to the user's address space (CR3).
-                let rect = unsafe { core::ptr::read_volatile(ptr) };
+                let rect = UserPtr::<hadron_syscall::FbDirtyRect>::new(arg)
+                    .and_then(|ptr| ptr.read())
+                    .map_err(|_| FsError::Fault)?;
                 self.fb.flush_rect(rect.x, rect.y, rect.width, rect.height);
                 Ok(0)
             }
@@ -106,36 +106,31 @@ impl Inode for DevConsole {
             TCGETS => {
                 let t = tty.get_termios();
                 let ptr = UserPtr::<Termios>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(t) };
+                ptr.write(t).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             TCSETS | TCSETSW | TCSETSF => {
                 let ptr = UserPtr::<Termios>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address; reading a POD struct.
-                let t = unsafe { *ptr.as_ref() };
+                let t = ptr.read().map_err(|_| FsError::Fault)?;
                 tty.set_termios(&t);
                 Ok(0)
             }
             TIOCGPGRP => {
                 let pgid = tty.foreground_pgid().unwrap_or(0);
                 let ptr = UserPtr::<u32>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(pgid) };
+                ptr.write(pgid).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             TIOCSPGRP => {
                 let ptr = UserPtr::<u32>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address; reading a u32.
-                let pgid = unsafe { *ptr.as_ref() };
+                let pgid = ptr.read().map_err(|_| FsError::Fault)?;
                 tty.set_foreground_pgid(pgid);
                 Ok(0)
             }
             TIOCGWINSZ => {
                 let ws = tty.get_winsize();
                 let ptr = UserPtr::<Winsize>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(ws) };
+                ptr.write(ws).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             TIOCSWINSZ => Ok(0),
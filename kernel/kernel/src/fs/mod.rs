@@ -6,7 +6,7 @@
 
 // Re-export everything from hadron-fs root.
 pub use hadron_fs::{
-    DevNumber, DirEntry, FileSystem, FsError, Inode, InodeType, Permissions, noop_waker,
+    DevNumber, DirEntry, FileSystem, FsError, Inode, InodeType, Ownership, Permissions, noop_waker,
     poll_immediate, try_poll_immediate,
 };
 
@@ -4,9 +4,14 @@
 //! - `/proc/self` — magic symlink to `/proc/<current_pid>`
 //! - `/proc/meminfo` — PMM statistics in Linux format
 //! - `/proc/cpuinfo` — CPU vendor + feature flags in Linux format
+//! - `/proc/slabinfo` — slab cache statistics in Linux `slabinfo` 2.1 format
+//! - `/proc/uptime` — seconds since boot and seconds all CPUs spent idle
 //! - `/proc/<pid>/maps` — VMA dump for address space layout
 //! - `/proc/<pid>/exe` — symlink to the process executable path
-//! - `/proc/<pid>/status` — name, pid, ppid in Linux format
+//! - `/proc/<pid>/status` — name, pid, ppid and memory usage in Linux format
+//! - `/proc/<pid>/stat` — one-line process summary with CPU times in Linux format
+//! - `/proc/<pid>/oom_score` — current OOM killer badness (0..=1000)
+//! - `/proc/<pid>/oom_score_adj` — writable OOM killer bias (-1000..=1000)
 //!
 //! All file contents are generated fresh on every `read()` call; there is no
 //! snapshot caching. `size()` returns 0 (matching Linux procfs convention).
@@ -21,8 +26,11 @@ use alloc::vec::Vec;
 use core::future::Future;
 use core::pin::Pin;
 
+use hadron_core::sync::atomic::Ordering;
+
 use crate::fs::{DirEntry, FileSystem, FsError, Inode, InodeType, Permissions};
 use crate::id::Pid;
+use crate::mm::oom;
 use crate::proc::{MappingKind, ProcessTable};
 
 // ── ProcFs ──────────────────────────────────────────────────────────────
@@ -105,6 +113,12 @@ impl Inode for ProcRootDir {
                 "cpuinfo" => Ok(Arc::new(ProcGlobalFile {
                     generator: gen_cpuinfo,
                 }) as Arc<dyn Inode>),
+                "slabinfo" => Ok(Arc::new(ProcGlobalFile {
+                    generator: gen_slabinfo,
+                }) as Arc<dyn Inode>),
+                "uptime" => Ok(Arc::new(ProcGlobalFile {
+                    generator: gen_uptime,
+                }) as Arc<dyn Inode>),
                 other => {
                     // Try to parse as a PID.
                     let pid: u32 = other.parse().map_err(|_| FsError::NotFound)?;
@@ -134,6 +148,14 @@ impl Inode for ProcRootDir {
                     name: "cpuinfo".into(),
                     inode_type: InodeType::File,
                 },
+                DirEntry {
+                    name: "slabinfo".into(),
+                    inode_type: InodeType::File,
+                },
+                DirEntry {
+                    name: "uptime".into(),
+                    inode_type: InodeType::File,
+                },
             ];
             for pid in ProcessTable::all_pids() {
                 entries.push(DirEntry {
@@ -357,11 +379,28 @@ impl Inode for ProcPidDir {
                 "maps" => Ok(Arc::new(ProcPidFile {
                     pid,
                     generator: gen_maps,
+                    writer: None,
                 }) as Arc<dyn Inode>),
                 "exe" => Ok(Arc::new(ProcExeLink { pid }) as Arc<dyn Inode>),
                 "status" => Ok(Arc::new(ProcPidFile {
                     pid,
                     generator: gen_status,
+                    writer: None,
+                }) as Arc<dyn Inode>),
+                "stat" => Ok(Arc::new(ProcPidFile {
+                    pid,
+                    generator: gen_stat,
+                    writer: None,
+                }) as Arc<dyn Inode>),
+                "oom_score" => Ok(Arc::new(ProcPidFile {
+                    pid,
+                    generator: gen_oom_score,
+                    writer: None,
+                }) as Arc<dyn Inode>),
+                "oom_score_adj" => Ok(Arc::new(ProcPidFile {
+                    pid,
+                    generator: gen_oom_score_adj,
+                    writer: Some(write_oom_score_adj),
                 }) as Arc<dyn Inode>),
                 _ => Err(FsError::NotFound),
             }
@@ -383,6 +422,18 @@ impl Inode for ProcPidDir {
                     name: "status".into(),
                     inode_type: InodeType::File,
                 },
+                DirEntry {
+                    name: "stat".into(),
+                    inode_type: InodeType::File,
+                },
+                DirEntry {
+                    name: "oom_score".into(),
+                    inode_type: InodeType::File,
+                },
+                DirEntry {
+                    name: "oom_score_adj".into(),
+                    inode_type: InodeType::File,
+                },
             ])
         })
     }
@@ -407,9 +458,13 @@ impl Inode for ProcPidDir {
 // ── ProcPidFile ─────────────────────────────────────────────────────────
 
 /// A per-process procfs file generated from the live process state.
+///
+/// Files with a `writer` accept writes, which are parsed and applied to the
+/// process; all others are read-only.
 struct ProcPidFile {
     pid: Pid,
     generator: fn(Pid) -> Vec<u8>,
+    writer: Option<fn(Pid, &[u8]) -> Result<(), FsError>>,
 }
 
 impl Inode for ProcPidFile {
@@ -422,7 +477,11 @@ impl Inode for ProcPidFile {
     }
 
     fn permissions(&self) -> Permissions {
-        Permissions::read_only()
+        if self.writer.is_some() {
+            Permissions::read_write()
+        } else {
+            Permissions::read_only()
+        }
     }
 
     fn read<'a>(
@@ -445,9 +504,13 @@ impl Inode for ProcPidFile {
     fn write<'a>(
         &'a self,
         _offset: usize,
-        _buf: &'a [u8],
+        buf: &'a [u8],
     ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
-        Box::pin(async { Err(FsError::NotSupported) })
+        let result = match self.writer {
+            Some(writer) => writer(self.pid, buf).map(|()| buf.len()),
+            None => Err(FsError::NotSupported),
+        };
+        Box::pin(async move { result })
     }
 
     fn lookup<'a>(
@@ -553,6 +616,7 @@ impl Inode for ProcExeLink {
 /// Generate `/proc/meminfo` content.
 fn gen_meminfo() -> Vec<u8> {
     let (total, free) = crate::mm::pmm::with(|pmm| (pmm.total_frames(), pmm.free_frames()));
+    let free = free + crate::mm::pmm::hot_cached_frames();
     // 4 KiB per frame, convert to kB.
     let total_kb = total * 4;
     let free_kb = free * 4;
@@ -563,6 +627,57 @@ fn gen_meminfo() -> Vec<u8> {
     .into_bytes()
 }
 
+/// Generate `/proc/uptime` content: uptime and summed idle time of all
+/// CPUs, in seconds with two decimals.
+fn gen_uptime() -> Vec<u8> {
+    let centis = |ns: u64| ns / 10_000_000;
+    let up = centis(crate::time::Time::boot_nanos());
+    let idle = centis(crate::sched::tick::total_idle_nanos());
+    format!(
+        "{}.{:02} {}.{:02}\n",
+        up / 100,
+        up % 100,
+        idle / 100,
+        idle % 100
+    )
+    .into_bytes()
+}
+
+/// Generate `/proc/slabinfo` content.
+///
+/// The tunables columns report the per-CPU magazine size and the
+/// magazine/depot batch size. Slabs are never freed, so every slab counts
+/// as active.
+fn gen_slabinfo() -> Vec<u8> {
+    use crate::mm::slab::{self, MAGAZINE_BATCH, MAGAZINE_ROUNDS};
+
+    let mut out = String::from(
+        "slabinfo - version: 2.1\n\
+         # name            <active_objs> <num_objs> <objsize> <objperslab> <pagesperslab> \
+         : tunables <limit> <batchcount> <sharedfactor> \
+         : slabdata <active_slabs> <num_slabs> <sharedavail>\n",
+    );
+    slab::for_each_stats(|s| {
+        out.push_str(&format!(
+            "{:<17} {:>6} {:>6} {:>6} {:>4} {:>4} : tunables {:>4} {:>4} {:>4} \
+             : slabdata {:>6} {:>6} {:>6}\n",
+            s.name,
+            s.active_objects,
+            s.total_objects,
+            s.object_size,
+            s.objects_per_slab,
+            s.pages_per_slab,
+            MAGAZINE_ROUNDS,
+            MAGAZINE_BATCH,
+            0,
+            s.slabs,
+            s.slabs,
+            0,
+        ));
+    });
+    out.into_bytes()
+}
+
 /// Generate `/proc/cpuinfo` content.
 fn gen_cpuinfo() -> Vec<u8> {
     #[cfg(target_arch = "x86_64")]
@@ -667,13 +782,103 @@ fn gen_status(pid: Pid) -> Vec<u8> {
     let exe = process.exe_path.lock().clone();
     // Extract the basename from the exe path.
     let name = exe.rsplit('/').next().unwrap_or(&exe);
-    let ppid = process.parent_pid.map_or(0, |p| p.as_u32());
+    let ppid = process.parent_pid().map_or(0, |p| p.as_u32());
 
     format!(
-        "Name:\t{}\nPid:\t{}\nPPid:\t{}\nVmRSS:\t0 kB\n",
+        "Name:\t{}\nPid:\t{}\nPPid:\t{}\nVmRSS:\t{} kB\nVmPTE:\t{} kB\n",
         name,
         pid.as_u32(),
         ppid,
+        process.resident_pages() * 4,
+        process.page_table_pages() * 4,
     )
     .into_bytes()
 }
+
+/// Clock ticks per second used for the times in `/proc/<pid>/stat`
+/// (Linux `USER_HZ`).
+const USER_HZ: u64 = 100;
+
+/// Converts nanoseconds to [`USER_HZ`] clock ticks.
+fn nanos_to_ticks(ns: u64) -> u64 {
+    ns / (1_000_000_000 / USER_HZ)
+}
+
+/// Generate `/proc/<pid>/stat` content.
+///
+/// Emits the first 24 fields of the Linux format, through `rss`. Fields
+/// Hadron does not track (fault counts, start time, virtual size) are 0;
+/// `utime`/`stime` cover all threads of the process and `cutime`/`cstime`
+/// its reaped children.
+fn gen_stat(pid: Pid) -> Vec<u8> {
+    let process = match ProcessTable::lookup(pid) {
+        Some(p) => p,
+        None => return alloc::vec![],
+    };
+
+    let exe = process.exe_path.lock().clone();
+    let name = exe.rsplit('/').next().unwrap_or(&exe);
+    let state = if process.exit_status.lock().is_some() {
+        'Z'
+    } else {
+        'R'
+    };
+    let ppid = process.parent_pid().map_or(0, |p| p.as_u32());
+    let threads = ProcessTable::all_pids()
+        .into_iter()
+        .filter_map(ProcessTable::lookup)
+        .filter(|p| p.shares_address_space(&process))
+        .count();
+    let own = process.process_cpu_times();
+    let children = process.children_cpu_times();
+
+    format!(
+        "{} ({}) {} {} {} {} 0 -1 0 0 0 0 0 {} {} {} {} 20 0 {} 0 0 0 {}\n",
+        pid.as_u32(),
+        name,
+        state,
+        ppid,
+        process.pgid.load(Ordering::Relaxed),
+        process.session_id.load(Ordering::Relaxed),
+        nanos_to_ticks(own.user_ns),
+        nanos_to_ticks(own.system_ns),
+        nanos_to_ticks(children.user_ns),
+        nanos_to_ticks(children.system_ns),
+        threads,
+        process.resident_pages(),
+    )
+    .into_bytes()
+}
+
+/// Generate `/proc/<pid>/oom_score` content.
+fn gen_oom_score(pid: Pid) -> Vec<u8> {
+    match ProcessTable::lookup(pid) {
+        Some(process) => format!("{}\n", oom::score_of(&process)).into_bytes(),
+        None => alloc::vec![],
+    }
+}
+
+/// Generate `/proc/<pid>/oom_score_adj` content.
+fn gen_oom_score_adj(pid: Pid) -> Vec<u8> {
+    match ProcessTable::lookup(pid) {
+        Some(process) => {
+            format!("{}\n", process.oom_score_adj.load(Ordering::Relaxed)).into_bytes()
+        }
+        None => alloc::vec![],
+    }
+}
+
+/// Apply a write to `/proc/<pid>/oom_score_adj`.
+///
+/// Accepts a decimal integer in `OOM_SCORE_ADJ_MIN..=OOM_SCORE_ADJ_MAX`,
+/// optionally surrounded by whitespace.
+fn write_oom_score_adj(pid: Pid, buf: &[u8]) -> Result<(), FsError> {
+    let process = ProcessTable::lookup(pid).ok_or(FsError::NotFound)?;
+    let text = core::str::from_utf8(buf).map_err(|_| FsError::InvalidArgument)?;
+    let adj: i32 = text.trim().parse().map_err(|_| FsError::InvalidArgument)?;
+    if !(oom::OOM_SCORE_ADJ_MIN..=oom::OOM_SCORE_ADJ_MAX).contains(&adj) {
+        return Err(FsError::InvalidArgument);
+    }
+    process.oom_score_adj.store(adj, Ordering::Relaxed);
+    Ok(())
+}
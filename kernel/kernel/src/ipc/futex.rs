@@ -65,27 +65,45 @@ impl FutexTable {
         }
         to_wake
     }
+
+    /// Remove the waiters on `addr` that would wake the same task as `waker`.
+    fn unregister(&mut self, addr: usize, waker: &Waker) {
+        let Some(waiters) = self.waiters.get_mut(&addr) else {
+            return;
+        };
+        waiters.retain(|w| !w.will_wake(waker));
+        if waiters.is_empty() {
+            self.waiters.remove(&addr);
+        }
+    }
 }
 
 /// FUTEX_WAIT: if `*addr == expected`, register waker and return Pending.
 ///
 /// Called from the trap handler's async context. Returns `true` if the
-/// condition was met and we should sleep, `false` if the value changed.
+/// condition was met and we should sleep, `false` if the value changed or
+/// the futex word is not readable.
 ///
 /// The table lock is acquired **before** reading the futex word so that a
 /// concurrent `futex_wake` cannot slip between the value check and the
 /// waker registration (TOCTOU).
 pub fn futex_wait_check(addr: usize, expected: u32, waker: &Waker) -> bool {
     let mut table = FUTEX_TABLE.lock();
-    // SAFETY: addr points to user memory, caller has switched to user CR3.
-    let current = unsafe { core::ptr::read_volatile(addr as *const u32) };
-    if current != expected {
+    // The caller has switched to user CR3.
+    let current = crate::syscall::userptr::UserPtr::<u32>::new(addr).and_then(|p| p.read());
+    if current != Ok(expected) {
         return false; // Value changed, don't sleep.
     }
     table.register(addr, waker.clone());
     true
 }
 
+/// Withdraws a waiter registered by [`futex_wait_check`] that timed out, so
+/// a later `futex_wake` does not count it.
+pub fn futex_wait_cancel(addr: usize, waker: &Waker) {
+    FUTEX_TABLE.lock().unregister(addr, waker);
+}
+
 /// FUTEX_WAKE: wake up to `count` threads sleeping on `addr`.
 ///
 /// Returns the number of threads actually woken.
@@ -0,0 +1,376 @@
+//! Shared-memory I/O rings.
+//!
+//! An [`IoRing`] is a submission queue of [`IoOp`]s and a completion queue
+//! of [`IoCompletion`]s in one [`ShmObject`] that the owning process maps.
+//! `io_ring_enter` takes operations off the submission queue and runs them
+//! on the executor, and each finished operation posts a completion that
+//! userspace reaps straight from shared memory, without a syscall.
+//!
+//! The kernel reaches the queues through the HHDM, so it needs no user
+//! address space to do so. At most `cq_entries` operations are in flight,
+//! counting completions that are waiting in an overflow list because the
+//! completion queue was full, so that list stays bounded.
+//!
+//! Closing the last fd for a ring cancels its in-flight operations, and
+//! `execve` cancels those its process submitted (see [`CancelToken`]).
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::mem::{offset_of, size_of};
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use core::task::{Poll, Waker};
+
+use crate::addr::PhysAddr;
+use crate::fs::{DirEntry, FsError, Inode, InodeType, Permissions};
+use crate::ipc::shm::ShmObject;
+use crate::sync::{HeapWaitQueue, SpinLock};
+use crate::syscall::{IO_RING_MAX_ENTRIES, IoCompletion, IoOp, IoRingHeader, IoRingParams, POLLIN};
+
+/// Offset of the submission queue entries. With 64-byte entries after a
+/// 64-byte header, and 16-byte completions after them, no entry crosses a
+/// page, so each can be reached through [`ShmObject::hhdm_ptr`].
+const OPS_OFFSET: usize = 64;
+
+const _: () = assert!(size_of::<IoRingHeader>() == OPS_OFFSET);
+const _: () = assert!(size_of::<IoOp>() == 64);
+const _: () = assert!(size_of::<IoCompletion>() == 16);
+
+/// A flag that cancels the operations holding it once set.
+pub struct CancelToken {
+    /// Whether the token has been cancelled.
+    cancelled: AtomicBool,
+    /// Woken when `cancelled` is set.
+    wq: HeapWaitQueue,
+}
+
+impl CancelToken {
+    /// Creates a token that is not cancelled.
+    pub fn new() -> Self {
+        Self {
+            cancelled: AtomicBool::new(false),
+            wq: HeapWaitQueue::new(),
+        }
+    }
+
+    /// Cancels the token, waking the operations waiting on it.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+        self.wq.wake_all();
+    }
+
+    /// Returns `true` once the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ring state shared by the fd and the operations in flight.
+pub struct RingState {
+    /// The shared region.
+    mem: Arc<ShmObject>,
+    /// Submission queue length, a power of two.
+    sq_entries: u32,
+    /// Completion queue length, twice `sq_entries`.
+    cq_entries: u32,
+    /// Serializes submission, which advances `sq_head`.
+    submit: SpinLock<()>,
+    /// Completions waiting for room in the completion queue. Its lock also
+    /// serializes posting, which advances `cq_tail`.
+    overflow: SpinLock<VecDeque<IoCompletion>>,
+    /// Operations submitted whose completions are not yet in the queue.
+    in_flight: AtomicU32,
+    /// Woken when a completion is posted.
+    cq_wq: HeapWaitQueue,
+    /// Cancelled when the last fd for the ring is closed.
+    cancel: CancelToken,
+}
+
+impl RingState {
+    /// Returns the header index at byte offset `field`.
+    fn index(&self, field: usize) -> &AtomicU32 {
+        // SAFETY: `field` is the offset of a `u32` in the header, which is
+        // 4-byte aligned in the first page. The memory lives as long as
+        // `self`, and the ABI has userspace access it atomically too.
+        unsafe { AtomicU32::from_ptr(self.mem.hhdm_ptr(field).cast()) }
+    }
+
+    fn completions_offset(&self) -> usize {
+        OPS_OFFSET + self.sq_entries as usize * size_of::<IoOp>()
+    }
+
+    /// Takes up to `max` operations off the submission queue, as many as
+    /// there is room in flight for.
+    ///
+    /// Returns `None` if operations are queued but none fit.
+    pub fn take_ops(&self, max: usize) -> Option<Vec<IoOp>> {
+        let _guard = self.submit.lock();
+        let head = self
+            .index(offset_of!(IoRingHeader, sq_head))
+            .load(Ordering::Relaxed);
+        let tail = self
+            .index(offset_of!(IoRingHeader, sq_tail))
+            .load(Ordering::Acquire);
+        // A bad tail from userspace only limits how many are taken.
+        let queued = tail.wrapping_sub(head).min(self.sq_entries) as usize;
+        let room = (self.cq_entries - self.in_flight.load(Ordering::Acquire)) as usize;
+        if queued > 0 && max > 0 && room == 0 {
+            return None;
+        }
+
+        let count = queued.min(max).min(room);
+        let mut ops = Vec::with_capacity(count);
+        for i in 0..count {
+            let slot = (head as usize + i) & (self.sq_entries as usize - 1);
+            let ptr = self.mem.hhdm_ptr(OPS_OFFSET + slot * size_of::<IoOp>());
+            // SAFETY: The entry lies within one page of the ring. Userspace
+            // may write it concurrently, hence the volatile copy.
+            ops.push(unsafe { ptr.cast::<IoOp>().read_volatile() });
+        }
+        #[expect(clippy::cast_possible_truncation, reason = "count <= cq_entries")]
+        let count = count as u32;
+        self.in_flight.fetch_add(count, Ordering::AcqRel);
+        self.index(offset_of!(IoRingHeader, sq_head))
+            .store(head.wrapping_add(count), Ordering::Release);
+        Some(ops)
+    }
+
+    /// Posts `completion`, queueing it in the overflow list if the
+    /// completion queue is full.
+    pub fn complete(&self, completion: IoCompletion) {
+        let mut overflow = self.overflow.lock();
+        overflow.push_back(completion);
+        self.flush_locked(&mut overflow);
+        drop(overflow);
+        self.cq_wq.wake_all();
+    }
+
+    /// Moves overflowed completions into the room userspace has made.
+    fn flush_locked(&self, overflow: &mut VecDeque<IoCompletion>) {
+        if overflow.is_empty() {
+            return;
+        }
+        let head = self
+            .index(offset_of!(IoRingHeader, cq_head))
+            .load(Ordering::Acquire);
+        let start = self
+            .index(offset_of!(IoRingHeader, cq_tail))
+            .load(Ordering::Relaxed);
+        let mut tail = start;
+        while tail.wrapping_sub(head) < self.cq_entries {
+            let Some(completion) = overflow.pop_front() else {
+                break;
+            };
+            let slot = (tail & (self.cq_entries - 1)) as usize;
+            let offset = self.completions_offset() + slot * size_of::<IoCompletion>();
+            // SAFETY: The entry lies within one page of the ring.
+            unsafe {
+                self.mem
+                    .hhdm_ptr(offset)
+                    .cast::<IoCompletion>()
+                    .write_volatile(completion);
+            }
+            tail = tail.wrapping_add(1);
+        }
+        self.index(offset_of!(IoRingHeader, cq_tail))
+            .store(tail, Ordering::Release);
+        self.in_flight
+            .fetch_sub(tail.wrapping_sub(start), Ordering::AcqRel);
+    }
+
+    /// Returns the number of completions userspace has not taken yet,
+    /// first moving overflowed ones into any room it has made.
+    pub fn completions(&self) -> usize {
+        let mut overflow = self.overflow.lock();
+        self.flush_locked(&mut overflow);
+        let head = self
+            .index(offset_of!(IoRingHeader, cq_head))
+            .load(Ordering::Acquire);
+        let tail = self
+            .index(offset_of!(IoRingHeader, cq_tail))
+            .load(Ordering::Relaxed);
+        tail.wrapping_sub(head).min(self.cq_entries) as usize + overflow.len()
+    }
+
+    /// Returns the completion queue length.
+    pub fn cq_entries(&self) -> usize {
+        self.cq_entries as usize
+    }
+
+    /// Registers `waker` to be woken when a completion is posted.
+    pub fn register_waker(&self, waker: &Waker) {
+        self.cq_wq.register_waker(waker);
+    }
+
+    /// Runs `op` to completion, or returns `None` once the ring is closed
+    /// or `image`, the token of the image that submitted `op`, is
+    /// cancelled.
+    pub async fn cancellable<F: Future>(&self, image: &CancelToken, op: F) -> Option<F::Output> {
+        let mut op = core::pin::pin!(op);
+        core::future::poll_fn(|cx| {
+            self.cancel.wq.register_waker(cx.waker());
+            image.wq.register_waker(cx.waker());
+            if self.cancel.is_cancelled() || image.is_cancelled() {
+                return Poll::Ready(None);
+            }
+            op.as_mut().poll(cx).map(Some)
+        })
+        .await
+    }
+}
+
+/// An I/O ring fd.
+pub struct IoRing {
+    /// The state, also held by the operations in flight.
+    state: Arc<RingState>,
+}
+
+impl IoRing {
+    /// Creates a ring with at least `entries` submission queue entries.
+    ///
+    /// Returns `None` if `entries` is 0 or above [`IO_RING_MAX_ENTRIES`],
+    /// or if the ring memory cannot be allocated.
+    pub fn new(entries: usize) -> Option<Self> {
+        if entries == 0 || entries > IO_RING_MAX_ENTRIES {
+            return None;
+        }
+        let sq_entries = entries.next_power_of_two();
+        let cq_entries = sq_entries * 2;
+        let size =
+            OPS_OFFSET + sq_entries * size_of::<IoOp>() + cq_entries * size_of::<IoCompletion>();
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "at most twice IO_RING_MAX_ENTRIES"
+        )]
+        let state = RingState {
+            mem: ShmObject::new(size, false)?,
+            sq_entries: sq_entries as u32,
+            cq_entries: cq_entries as u32,
+            submit: SpinLock::named("io_ring_submit", ()),
+            overflow: SpinLock::named("io_ring_overflow", VecDeque::new()),
+            in_flight: AtomicU32::new(0),
+            cq_wq: HeapWaitQueue::new(),
+            cancel: CancelToken::new(),
+        };
+        state
+            .index(offset_of!(IoRingHeader, sq_mask))
+            .store(state.sq_entries - 1, Ordering::Release);
+        state
+            .index(offset_of!(IoRingHeader, cq_mask))
+            .store(state.cq_entries - 1, Ordering::Release);
+        Some(Self {
+            state: Arc::new(state),
+        })
+    }
+
+    /// Returns the layout to report to userspace.
+    pub fn params(&self) -> IoRingParams {
+        let state = &self.state;
+        IoRingParams {
+            sq_entries: state.sq_entries,
+            cq_entries: state.cq_entries,
+            ops_offset: OPS_OFFSET as u64,
+            completions_offset: state.completions_offset() as u64,
+            size: state.mem.len() as u64,
+        }
+    }
+
+    /// Returns the state shared with operations in flight.
+    pub fn state(&self) -> &Arc<RingState> {
+        &self.state
+    }
+}
+
+impl Drop for IoRing {
+    fn drop(&mut self) {
+        self.state.cancel.cancel();
+    }
+}
+
+impl Inode for IoRing {
+    fn inode_type(&self) -> InodeType {
+        InodeType::CharDevice
+    }
+
+    fn size(&self) -> usize {
+        self.state.mem.len()
+    }
+
+    fn permissions(&self) -> Permissions {
+        Permissions::read_write()
+    }
+
+    fn read<'a>(
+        &'a self,
+        _offset: usize,
+        _buf: &'a mut [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::InvalidArgument) })
+    }
+
+    fn write<'a>(
+        &'a self,
+        _offset: usize,
+        _buf: &'a [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::InvalidArgument) })
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        _name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Arc<dyn Inode>, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn readdir(&self) -> Pin<Box<dyn Future<Output = Result<Vec<DirEntry>, FsError>> + Send + '_>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn create<'a>(
+        &'a self,
+        _name: &'a str,
+        _itype: InodeType,
+        _perms: Permissions,
+    ) -> Pin<Box<dyn Future<Output = Result<Arc<dyn Inode>, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn unlink<'a>(
+        &'a self,
+        _name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(), FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn shared_phys_frames(&self) -> Result<Vec<PhysAddr>, FsError> {
+        self.state.mem.shared_phys_frames()
+    }
+
+    /// Readable while completions are waiting to be taken.
+    fn poll_readiness(&self, waker: Option<&Waker>) -> u16 {
+        if let Some(w) = waker {
+            self.state.register_waker(w);
+        }
+        if self.state.completions() > 0 {
+            POLLIN
+        } else {
+            0
+        }
+    }
+
+    fn as_any(&self) -> Option<&dyn core::any::Any> {
+        Some(self)
+    }
+}
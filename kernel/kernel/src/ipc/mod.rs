@@ -1,13 +1,19 @@
 //! Inter-process communication primitives.
 //!
-//! Pure IPC logic (pipes, channels, services) lives in the `hadron-ipc` crate.
-//! This module re-exports those types and provides kernel-specific IPC
-//! (futex, shared memory) that depends on kernel internals.
+//! Pure IPC logic (pipes, channels, eventfds, epoll, services) lives in the
+//! `hadron-ipc` crate. This module re-exports those types and provides
+//! kernel-specific IPC (futex, shared memory, I/O rings, timerfds,
+//! signalfds) that depends on kernel internals.
 
 pub use hadron_ipc::channel;
 pub use hadron_ipc::circular_buffer;
+pub use hadron_ipc::epoll;
+pub use hadron_ipc::eventfd;
 pub use hadron_ipc::pipe;
 pub use hadron_ipc::service;
 
 pub mod futex;
+pub mod io_ring;
 pub mod shm;
+pub mod signalfd;
+pub mod timerfd;
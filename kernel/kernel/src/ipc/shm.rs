@@ -3,6 +3,9 @@
 //! A [`ShmObject`] owns a set of physical frames that can be mapped into
 //! multiple process address spaces simultaneously. The compositor uses this
 //! to share pixel buffers with client processes.
+//!
+//! A huge object is allocated in naturally aligned 2 MiB blocks, so every
+//! process can map it with 2 MiB pages.
 
 extern crate alloc;
 
@@ -15,7 +18,7 @@ use core::pin::Pin;
 use crate::addr::PhysAddr;
 use crate::fs::{DirEntry, FsError, Inode, InodeType, Permissions};
 use crate::mm::PAGE_SIZE;
-use crate::mm::pmm;
+use crate::mm::pmm::{self, HUGE_PAGE_ORDER};
 use crate::paging::{PhysFrame, Size4KiB};
 
 /// A shared memory object backed by physical frames.
@@ -34,21 +37,25 @@ pub struct ShmObject {
 impl ShmObject {
     /// Allocate a new shared memory object of the given size.
     ///
-    /// `size` is rounded up to page alignment. All pages are zeroed.
-    /// Returns `None` if the PMM cannot satisfy the allocation.
-    pub fn new(size: usize) -> Option<Arc<Self>> {
+    /// `size` is rounded up to page alignment, or to 2 MiB if `huge` is set,
+    /// in which case the frames come in naturally aligned 2 MiB blocks. All
+    /// pages are zeroed. Returns `None` if the PMM cannot satisfy the
+    /// allocation.
+    pub fn new(size: usize, huge: bool) -> Option<Arc<Self>> {
         if size == 0 {
             return None;
         }
 
-        let aligned = (size + PAGE_SIZE - 1) & !(PAGE_SIZE - 1);
+        let block_pages = if huge { 1 << HUGE_PAGE_ORDER } else { 1 };
+        let block_size = block_pages * PAGE_SIZE;
+        let aligned = size.next_multiple_of(block_size);
         let page_count = aligned / PAGE_SIZE;
         let hhdm_offset = crate::mm::hhdm::offset();
 
         let frames = pmm::with(|pmm| {
             let mut allocated = Vec::with_capacity(page_count);
-            for _ in 0..page_count {
-                let Some(frame) = pmm.allocate_frame() else {
+            for _ in 0..page_count / block_pages {
+                let Some(block) = pmm.allocate_frames(block_pages) else {
                     // Free all frames allocated so far to avoid leaking them.
                     for f in &allocated {
                         // SAFETY: Each frame was allocated by PMM above and has
@@ -58,14 +65,18 @@ impl ShmObject {
                     return None;
                 };
 
-                // Zero the frame via HHDM.
-                let ptr = (hhdm_offset + frame.start_address().as_u64()).as_mut_ptr::<u8>();
-                // SAFETY: Frame was just allocated; zeroing via HHDM is safe.
+                // Zero the block via HHDM.
+                let ptr = (hhdm_offset + block.start_address().as_u64()).as_mut_ptr::<u8>();
+                // SAFETY: Block was just allocated; zeroing via HHDM is safe.
                 unsafe {
-                    core::ptr::write_bytes(ptr, 0, PAGE_SIZE);
+                    core::ptr::write_bytes(ptr, 0, block_size);
                 }
 
-                allocated.push(frame);
+                for i in 0..block_pages {
+                    allocated.push(PhysFrame::containing_address(
+                        block.start_address() + (i * PAGE_SIZE) as u64,
+                    ));
+                }
             }
             Some(allocated)
         })?;
@@ -78,6 +89,21 @@ impl ShmObject {
     pub fn len(&self) -> usize {
         self.size
     }
+
+    /// Returns a kernel pointer to byte `offset` of the object, through the
+    /// HHDM.
+    ///
+    /// The frames need not be contiguous, so the pointer is only valid up
+    /// to the end of the page containing `offset`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is past the end of the object's frames.
+    pub fn hhdm_ptr(&self, offset: usize) -> *mut u8 {
+        let frame = self.frames[offset / PAGE_SIZE];
+        let phys = frame.start_address().as_u64() + (offset % PAGE_SIZE) as u64;
+        (crate::mm::hhdm::offset() + phys).as_mut_ptr::<u8>()
+    }
 }
 
 impl Drop for ShmObject {
@@ -0,0 +1,168 @@
+//! Signal file descriptors (`signalfd`).
+//!
+//! A signalfd accepts the signals in its mask that are pending for the
+//! thread that created it. Reading dequeues them as [`SigInfo`] records,
+//! as many as fit in the buffer, and blocks while none is pending. The fd
+//! is readable while one is, so signals can be waited for with
+//! `event_wait_many` alongside other fds. The signals should be blocked so
+//! that they are not delivered to a handler first.
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+use core::future::Future;
+use core::mem::size_of;
+use core::pin::Pin;
+use core::task::Poll;
+
+use hadron_core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::fs::{DirEntry, FsError, Inode, InodeType, Permissions};
+use crate::proc::Process;
+use crate::proc::signal::sig_bit;
+use crate::syscall::{SIGKILL, SIGSTOP, SigInfo};
+
+/// A signalfd.
+pub struct SignalFd {
+    /// Thread whose signals are read. Weak, since the thread's fd table
+    /// may hold this signalfd.
+    thread: Weak<Process>,
+    /// Signals accepted, as a signal bitmask.
+    mask: AtomicU64,
+}
+
+impl SignalFd {
+    /// Creates a signalfd for the signals in `mask` pending for `thread`.
+    pub fn new(thread: &Arc<Process>, mask: u64) -> Self {
+        Self {
+            thread: Arc::downgrade(thread),
+            mask: AtomicU64::new(Self::accepted(mask)),
+        }
+    }
+
+    /// Replaces the mask.
+    pub fn set_mask(&self, mask: u64) {
+        self.mask.store(Self::accepted(mask), Ordering::Release);
+    }
+
+    /// Returns `mask` without `SIGKILL` and `SIGSTOP`, which are never
+    /// accepted.
+    fn accepted(mask: u64) -> u64 {
+        mask & !(sig_bit(SIGKILL) | sig_bit(SIGSTOP))
+    }
+}
+
+impl Inode for SignalFd {
+    fn inode_type(&self) -> InodeType {
+        InodeType::CharDevice
+    }
+
+    fn size(&self) -> usize {
+        0
+    }
+
+    fn permissions(&self) -> Permissions {
+        Permissions::read_only()
+    }
+
+    fn read<'a>(
+        &'a self,
+        _offset: usize,
+        buf: &'a mut [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
+        Box::pin(async move {
+            let capacity = buf.len() / size_of::<SigInfo>();
+            if capacity == 0 {
+                return Err(FsError::InvalidArgument);
+            }
+            let Some(thread) = self.thread.upgrade() else {
+                return Ok(0);
+            };
+            // Register before checking to avoid a lost wakeup.
+            let first = core::future::poll_fn(|cx| {
+                thread.signals.register_waker(cx.waker());
+                let mask = self.mask.load(Ordering::Acquire);
+                thread
+                    .signals
+                    .dequeue_from(mask)
+                    .map_or(Poll::Pending, Poll::Ready)
+            })
+            .await;
+
+            let mut next = Some(first);
+            let mut count = 0;
+            while let Some(info) = next {
+                let record = buf[count * size_of::<SigInfo>()..].as_mut_ptr();
+                // SAFETY: `count < capacity`, so `record` has room for one
+                // `SigInfo`, which is plain old data.
+                unsafe { core::ptr::write_unaligned(record.cast(), info.to_user()) };
+                count += 1;
+                if count == capacity {
+                    break;
+                }
+                next = thread
+                    .signals
+                    .dequeue_from(self.mask.load(Ordering::Acquire));
+            }
+            Ok(count * size_of::<SigInfo>())
+        })
+    }
+
+    fn write<'a>(
+        &'a self,
+        _offset: usize,
+        _buf: &'a [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotSupported) })
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        _name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Arc<dyn Inode>, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn readdir(&self) -> Pin<Box<dyn Future<Output = Result<Vec<DirEntry>, FsError>> + Send + '_>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn create<'a>(
+        &'a self,
+        _name: &'a str,
+        _itype: InodeType,
+        _perms: Permissions,
+    ) -> Pin<Box<dyn Future<Output = Result<Arc<dyn Inode>, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn unlink<'a>(
+        &'a self,
+        _name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(), FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn poll_readiness(&self, waker: Option<&core::task::Waker>) -> u16 {
+        use crate::syscall::{POLLHUP, POLLIN};
+
+        let Some(thread) = self.thread.upgrade() else {
+            // The thread is gone; reads return 0.
+            return POLLIN | POLLHUP;
+        };
+        if let Some(w) = waker {
+            thread.signals.register_waker(w);
+        }
+        if thread.signals.pending() & self.mask.load(Ordering::Acquire) != 0 {
+            POLLIN
+        } else {
+            0
+        }
+    }
+
+    fn as_any(&self) -> Option<&dyn core::any::Any> {
+        Some(self)
+    }
+}
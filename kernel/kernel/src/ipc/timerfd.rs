@@ -0,0 +1,155 @@
+//! Timer file descriptors (`timerfd`).
+//!
+//! A timerfd wraps an [`IntervalTimer`] that counts its expiries instead of
+//! sending a signal. Reading an 8-byte buffer blocks until the timer has
+//! expired, then returns the number of expiries since the last read and
+//! resets it. The fd is readable while that number is non-zero, so timers
+//! can be waited for with `event_wait_many` alongside other fds.
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+
+use crate::fs::{DirEntry, FsError, Inode, InodeType, Permissions};
+use crate::proc::timers::{IntervalTimer, TimerClock, TimerNotify};
+use crate::time::Time;
+
+/// A timerfd.
+pub struct TimerFd {
+    /// The timer, on the monotonic or real-time clock.
+    timer: Arc<IntervalTimer>,
+}
+
+impl TimerFd {
+    /// Creates a disarmed timerfd on `clock`, which must be
+    /// [`TimerClock::Monotonic`] or [`TimerClock::Realtime`].
+    pub fn new(clock: TimerClock) -> Self {
+        debug_assert!(matches!(
+            clock,
+            TimerClock::Monotonic | TimerClock::Realtime
+        ));
+        Self {
+            timer: Arc::new(IntervalTimer::new(clock, TimerNotify::Count)),
+        }
+    }
+
+    /// Returns the current time on the timer's clock, in nanoseconds.
+    pub fn now(&self) -> u64 {
+        match self.timer.clock() {
+            TimerClock::Realtime => Time::realtime_nanos(),
+            _ => Time::boot_nanos(),
+        }
+    }
+
+    /// Arms the timer to expire at clock time `expiry` and then every
+    /// `interval`, or disarms it if `expiry` is 0. Resets the expiry count.
+    ///
+    /// Returns the previous time left and interval.
+    pub fn set(&self, expiry: u64, interval: u64) -> (u64, u64) {
+        self.timer.set(expiry, interval, self.now())
+    }
+
+    /// Returns the time left until the next expiry and the interval.
+    pub fn get(&self) -> (u64, u64) {
+        self.timer.get(self.now())
+    }
+}
+
+impl Drop for TimerFd {
+    fn drop(&mut self) {
+        // Retires the expiry task, which holds its own reference.
+        self.timer.disarm();
+    }
+}
+
+impl Inode for TimerFd {
+    fn inode_type(&self) -> InodeType {
+        InodeType::CharDevice
+    }
+
+    fn size(&self) -> usize {
+        0
+    }
+
+    fn permissions(&self) -> Permissions {
+        Permissions::read_only()
+    }
+
+    fn read<'a>(
+        &'a self,
+        _offset: usize,
+        buf: &'a mut [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
+        Box::pin(async move {
+            let Some(out) = buf.get_mut(..8) else {
+                return Err(FsError::InvalidArgument);
+            };
+            // Register before checking to avoid a lost wakeup.
+            let ticks = core::future::poll_fn(|cx| {
+                self.timer.register_expiry_waker(cx.waker());
+                match self.timer.take_ticks() {
+                    0 => Poll::Pending,
+                    ticks => Poll::Ready(ticks),
+                }
+            })
+            .await;
+            out.copy_from_slice(&ticks.to_ne_bytes());
+            Ok(8)
+        })
+    }
+
+    fn write<'a>(
+        &'a self,
+        _offset: usize,
+        _buf: &'a [u8],
+    ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotSupported) })
+    }
+
+    fn lookup<'a>(
+        &'a self,
+        _name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<Arc<dyn Inode>, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn readdir(&self) -> Pin<Box<dyn Future<Output = Result<Vec<DirEntry>, FsError>> + Send + '_>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn create<'a>(
+        &'a self,
+        _name: &'a str,
+        _itype: InodeType,
+        _perms: Permissions,
+    ) -> Pin<Box<dyn Future<Output = Result<Arc<dyn Inode>, FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn unlink<'a>(
+        &'a self,
+        _name: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<(), FsError>> + Send + 'a>> {
+        Box::pin(async { Err(FsError::NotADirectory) })
+    }
+
+    fn poll_readiness(&self, waker: Option<&core::task::Waker>) -> u16 {
+        if let Some(w) = waker {
+            self.timer.register_expiry_waker(w);
+        }
+        if self.timer.has_ticks() {
+            crate::syscall::POLLIN
+        } else {
+            0
+        }
+    }
+
+    fn as_any(&self) -> Option<&dyn core::any::Any> {
+        Some(self)
+    }
+}
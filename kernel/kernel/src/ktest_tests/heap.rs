@@ -95,3 +95,30 @@ fn test_alloc_stats_smoke() {
     drop(b1);
     drop(b2);
 }
+
+// ── Slab front-end (bypassed when heap poisoning or tracking is on) ─────
+
+#[cfg(not(any(hadron_debug_heap_poison, hadron_debug_alloc_track)))]
+#[kernel_test(stage = "early_boot", timeout = 5)]
+fn test_small_allocations_come_from_slab() {
+    use crate::mm::layout::HEAP_MAX_SIZE;
+
+    let heap_base = crate::mm::vmm::with(|vmm| vmm.layout().heap.base().as_u64());
+    let b = Box::new([0u8; 48]);
+    let addr = core::ptr::from_ref(&*b) as u64;
+    // Slab objects live in the HHDM, outside the linked-list heap.
+    assert!(addr.wrapping_sub(heap_base) >= HEAP_MAX_SIZE);
+    assert_eq!(addr % 64, 0, "kmalloc-64 objects are naturally aligned");
+}
+
+#[cfg(not(any(hadron_debug_heap_poison, hadron_debug_alloc_track)))]
+#[kernel_test(stage = "early_boot", timeout = 5)]
+fn test_slab_stats_track_allocations() {
+    let layout = core::alloc::Layout::new::<[u8; 200]>();
+    let cache = crate::mm::slab::cache_for(layout).expect("slab enabled");
+    let before = cache.stats().active_objects;
+    let boxes: Vec<_> = (0..64).map(|_| Box::new([0u8; 200])).collect();
+    assert!(cache.stats().active_objects >= before + 64);
+    drop(boxes);
+    assert!(cache.stats().total_objects >= cache.stats().active_objects);
+}
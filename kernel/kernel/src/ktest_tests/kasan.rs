@@ -0,0 +1,179 @@
+//! KASAN tests — provoke each bug class on purpose and check the report.
+//!
+//! Every bad access is a read, or a write into slack nobody uses, so a
+//! captured report leaves no corruption behind.
+
+extern crate alloc;
+
+use alloc::alloc::{alloc, dealloc};
+use alloc::boxed::Box;
+use core::alloc::Layout;
+use hadron_ktest::kernel_test;
+
+use crate::mm::kasan::{self, Report};
+
+/// Large enough to bypass the slab caches and come from the heap.
+const HEAP_OBJECT: usize = 2000;
+
+/// Reads `ptr` with an instrumented load and returns the captured report.
+fn read_report(ptr: *const u8) -> Option<Report> {
+    // SAFETY: Callers pass mapped kernel memory; reading it is harmless
+    // even when the sanitizer objects.
+    kasan::expect_report(|| unsafe {
+        core::ptr::read_volatile(ptr);
+    })
+}
+
+/// Asserts that `report` flags a read at `addr` with shadow `code`.
+fn assert_read(report: Option<Report>, addr: *const u8, code: u8) {
+    let report = report.expect("bad access not reported");
+    assert_eq!(report.addr, addr as usize);
+    assert_eq!(report.size, 1);
+    assert!(!report.write);
+    assert_eq!(report.bad.addr, addr as usize);
+    assert_eq!(
+        report.bad.code,
+        code,
+        "expected {:#04x}, got {}",
+        code,
+        report.bad.kind()
+    );
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kasan_enabled() {
+    assert!(kasan::is_enabled());
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kasan_in_bounds_accesses_pass() {
+    let layout = Layout::from_size_align(HEAP_OBJECT, 8).unwrap();
+    // SAFETY: `layout` has a nonzero size.
+    let heap = unsafe { alloc(layout) };
+    assert!(!heap.is_null());
+    let slab = Box::new([7u8; 24]);
+
+    let report = kasan::expect_report(|| {
+        // SAFETY: Both accesses are within live allocations.
+        unsafe {
+            heap.add(HEAP_OBJECT - 1).write_volatile(1);
+            assert_eq!(heap.add(HEAP_OBJECT - 1).read_volatile(), 1);
+        }
+        assert_eq!(core::hint::black_box(&slab)[23], 7);
+    });
+    assert_eq!(report, None);
+
+    // SAFETY: Allocated above with the same layout.
+    unsafe { dealloc(heap, layout) };
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kasan_heap_out_of_bounds() {
+    let layout = Layout::from_size_align(HEAP_OBJECT, 8).unwrap();
+    // SAFETY: `layout` has a nonzero size.
+    let ptr = unsafe { alloc(layout) };
+    assert!(!ptr.is_null());
+
+    // SAFETY: The byte past the end lies in the block's tail red zone.
+    let past_end = unsafe { ptr.add(HEAP_OBJECT) };
+    assert_read(read_report(past_end), past_end, kasan::HEAP_REDZONE);
+
+    // SAFETY: Allocated above with the same layout.
+    unsafe { dealloc(ptr, layout) };
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kasan_heap_use_after_free() {
+    let layout = Layout::from_size_align(HEAP_OBJECT, 8).unwrap();
+    // SAFETY: `layout` has a nonzero size.
+    let ptr = unsafe { alloc(layout) };
+    assert!(!ptr.is_null());
+    // SAFETY: Allocated above with the same layout.
+    unsafe { dealloc(ptr, layout) };
+
+    // SAFETY: The freed block is still mapped heap memory.
+    let inside = unsafe { ptr.add(64) };
+    assert_read(read_report(inside), inside, kasan::HEAP_FREE);
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kasan_slab_out_of_bounds() {
+    // 13 bytes come from `kmalloc-16`: the last three bytes of the object
+    // and everything after them are out of bounds.
+    let layout = Layout::from_size_align(13, 1).unwrap();
+    // SAFETY: `layout` has a nonzero size.
+    let ptr = unsafe { alloc(layout) };
+    assert!(!ptr.is_null());
+
+    // SAFETY: The last requested byte is in bounds.
+    assert_eq!(read_report(unsafe { ptr.add(12) }), None);
+    // SAFETY: Byte 13 is slack within the 16-byte slab object.
+    let past_end = unsafe { ptr.add(13) };
+    let report = read_report(past_end).expect("slab overflow not reported");
+    assert_eq!(report.bad.addr, past_end as usize);
+    assert_eq!(report.bad.kind(), "out-of-bounds");
+
+    // A store into the slack is reported as a write.
+    let report = kasan::expect_report(|| {
+        // SAFETY: Slack within the slab object; nothing else uses it.
+        unsafe { past_end.cast_mut().write_volatile(0) };
+    })
+    .expect("slab overflow write not reported");
+    assert!(report.write);
+
+    // SAFETY: Allocated above with the same layout.
+    unsafe { dealloc(ptr, layout) };
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kasan_slab_redzone() {
+    // 24 bytes come from `kmalloc-32`: bytes 24..32 are a whole red-zone
+    // granule.
+    let layout = Layout::from_size_align(24, 8).unwrap();
+    // SAFETY: `layout` has a nonzero size.
+    let ptr = unsafe { alloc(layout) };
+    assert!(!ptr.is_null());
+
+    // SAFETY: Byte 24 is slack within the 32-byte slab object.
+    let past_end = unsafe { ptr.add(24) };
+    assert_read(read_report(past_end), past_end, kasan::SLAB_REDZONE);
+
+    // SAFETY: Allocated above with the same layout.
+    unsafe { dealloc(ptr, layout) };
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kasan_slab_use_after_free() {
+    let ptr = Box::into_raw(Box::new([0u64; 4])).cast::<u8>();
+    // SAFETY: `ptr` came from `Box::into_raw` above.
+    drop(unsafe { Box::from_raw(ptr.cast::<[u64; 4]>()) });
+
+    // SAFETY: The freed object is still mapped slab memory.
+    let inside = unsafe { ptr.add(8) };
+    assert_read(read_report(inside), inside, kasan::SLAB_FREE);
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kasan_page_use_after_free() {
+    let frame = crate::mm::pmm::alloc_frame().expect("out of memory");
+    let page = crate::mm::hhdm::phys_to_virt(frame.start_address()).as_mut_ptr::<u8>();
+
+    let report = kasan::expect_report(|| {
+        // SAFETY: The frame is allocated and mapped through the HHDM.
+        unsafe { page.add(100).write_volatile(1) };
+    });
+    assert_eq!(report, None);
+
+    // SAFETY: The frame was allocated above and is no longer used.
+    unsafe { crate::mm::pmm::free_frame(frame) };
+    // SAFETY: The freed frame is still mapped through the HHDM.
+    let inside = unsafe { page.add(100) };
+    assert_read(read_report(inside), inside, kasan::PAGE_FREE);
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kasan_unshadowed_memory_passes() {
+    // Kernel image data has no shadow and is never reported.
+    static DATA: [u8; 4] = [1, 2, 3, 4];
+    assert_eq!(read_report(DATA.as_ptr().wrapping_add(3)), None);
+}
@@ -0,0 +1,125 @@
+//! Kernel page-table isolation tests — shadow template contents and
+//! isolated address spaces.
+
+use hadron_ktest::kernel_test;
+
+use crate::addr::VirtAddr;
+use crate::arch::x86_64::kpti;
+use crate::arch::x86_64::paging::PageTableMapper;
+
+/// Translates `addr` through the page tables rooted at `root`.
+fn translate(root: crate::addr::PhysAddr, addr: u64) -> Option<crate::addr::PhysAddr> {
+    let mapper = PageTableMapper::new(crate::mm::hhdm::offset());
+    // SAFETY: `root` is a live PML4 owned by the kernel or the test.
+    unsafe { mapper.translate_addr(root, VirtAddr::new(addr)) }
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kpti_template_maps_entry_state() {
+    let template = kpti::shadow_template().expect("shadow template should be built");
+
+    let (text_start, text_end) = kpti::entry_text();
+    assert!(text_start < text_end, "entry text section is empty");
+    assert!(
+        translate(template, text_start.as_u64()).is_some(),
+        "entry text must be mapped in the shadow template"
+    );
+
+    let percpu = crate::percpu::PerCpuState::current() as *const _ as u64;
+    assert!(
+        translate(template, percpu).is_some(),
+        "current PerCpu must be mapped in the shadow template"
+    );
+    assert!(
+        translate(template, crate::arch::x86_64::idt::table_address().as_u64()).is_some(),
+        "IDT must be mapped in the shadow template"
+    );
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kpti_template_hides_kernel() {
+    extern crate alloc;
+
+    let template = kpti::shadow_template().expect("shadow template should be built");
+
+    // Ordinary kernel text lives outside the entry section.
+    let func = translate as *const () as u64;
+    let (text_start, text_end) = kpti::entry_text();
+    assert!(func < text_start.as_u64() || func >= text_end.as_u64());
+    assert!(
+        translate(template, func).is_none(),
+        "kernel text outside the entry section must not be mapped"
+    );
+
+    let heap = alloc::boxed::Box::new(0u64);
+    assert!(
+        translate(template, &*heap as *const u64 as u64).is_none(),
+        "kernel heap must not be mapped in the shadow template"
+    );
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kpti_isolated_address_space_shares_user_half() {
+    use crate::mm::address_space::AddressSpace;
+    use crate::mm::mapper::MapFlags;
+    use crate::paging::{Page, PhysFrame, Size4KiB};
+
+    fn dealloc_frame(frame: PhysFrame<Size4KiB>) {
+        crate::mm::pmm::with(|pmm| unsafe {
+            let _ = pmm.deallocate_frame(frame);
+        });
+    }
+
+    let template = kpti::shadow_template().expect("shadow template should be built");
+    let kernel_cr3 = crate::proc::TrapContext::kernel_cr3();
+    let hhdm = crate::mm::hhdm::offset();
+    let user_addr = 0x4000_0000u64;
+
+    let (space, frame) = crate::mm::pmm::with(|pmm| {
+        let mut alloc = crate::mm::pmm::BuddyFrameAllocRef(pmm);
+        let space = unsafe {
+            AddressSpace::new_user_isolated(
+                kernel_cr3,
+                template,
+                PageTableMapper::new(hhdm),
+                hhdm,
+                &mut alloc,
+                dealloc_frame,
+            )
+            .expect("create isolated address space")
+        };
+        let frame = crate::mm::FrameAllocator::<Size4KiB>::allocate_frame(&mut alloc)
+            .expect("allocate user frame");
+        space
+            .map_user_page(
+                Page::containing_address(VirtAddr::new(user_addr)),
+                frame,
+                MapFlags::WRITABLE,
+                &mut alloc,
+            )
+            .expect("map user page")
+            .ignore();
+        (space, frame)
+    });
+
+    let shadow = space
+        .shadow_root_phys()
+        .expect("isolated space has a shadow");
+    assert_ne!(shadow, space.root_phys());
+    assert_eq!(space.page_table_pages(), 2 + 3);
+    assert_eq!(
+        translate(shadow, user_addr),
+        Some(frame.start_address()),
+        "user pages must be visible through the shadow PML4"
+    );
+
+    let func = translate as *const () as u64;
+    assert!(translate(space.root_phys(), func).is_some());
+    assert!(translate(shadow, func).is_none());
+
+    space
+        .unmap_user_page(Page::containing_address(VirtAddr::new(user_addr)))
+        .expect("unmap user page");
+    dealloc_frame(frame);
+    drop(space);
+}
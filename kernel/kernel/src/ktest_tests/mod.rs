@@ -11,7 +11,12 @@ mod alt_instr;
 mod backtrace;
 mod boot;
 mod heap;
+#[cfg(hadron_kasan)]
+mod kasan;
+#[cfg(hadron_kpti)]
+mod kpti;
 mod pci;
+mod pcid;
 mod pmm;
 mod proc;
 mod profiling;
@@ -20,3 +25,4 @@ mod syscall;
 mod trace;
 mod vfs;
 mod vmm;
+mod wx;
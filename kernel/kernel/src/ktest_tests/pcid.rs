@@ -0,0 +1,124 @@
+//! PCID allocator tests — tag reuse, TLB generations, and kernel unmaps.
+
+use hadron_ktest::kernel_test;
+
+use crate::addr::VirtAddr;
+use crate::arch::x86_64::paging::PageTableMapper;
+use crate::arch::x86_64::pcid;
+use crate::arch::x86_64::registers::control::Cr3;
+use crate::mm::address_space::AddressSpace;
+use crate::paging::{Page, PhysFrame, Size4KiB};
+
+fn dealloc_frame(frame: PhysFrame<Size4KiB>) {
+    crate::mm::pmm::with(|pmm| unsafe {
+        let _ = pmm.deallocate_frame(frame);
+    });
+}
+
+/// Creates an empty user address space.
+fn new_space() -> AddressSpace<PageTableMapper> {
+    let hhdm = crate::mm::hhdm::offset();
+    crate::mm::pmm::with(|pmm| {
+        let mut alloc = crate::mm::pmm::BuddyFrameAllocRef(pmm);
+        // SAFETY: The kernel CR3 is the live kernel PML4.
+        unsafe {
+            AddressSpace::new_user(
+                crate::proc::TrapContext::kernel_cr3(),
+                PageTableMapper::new(hhdm),
+                hhdm,
+                &mut alloc,
+                dealloc_frame,
+            )
+            .expect("create address space")
+        }
+    })
+}
+
+/// Loads `space`, returns the CR3 value `switch_to` reported, and switches
+/// back to the kernel page tables.
+fn switch_and_back(space: &AddressSpace<PageTableMapper>) -> u64 {
+    // SAFETY: `space` copies the kernel upper half and outlives the switch.
+    let cr3 = unsafe { pcid::switch_to(space) };
+    assert_eq!(Cr3::read_raw(), cr3, "switch_to must report the loaded CR3");
+    // SAFETY: Restoring the kernel page tables.
+    unsafe { Cr3::write(crate::proc::TrapContext::kernel_cr3()) };
+    cr3
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_pcid_tags_address_spaces() {
+    let a = new_space();
+    let b = new_space();
+
+    let a1 = switch_and_back(&a);
+    let b1 = switch_and_back(&b);
+    let a2 = switch_and_back(&a);
+
+    assert_eq!(a1 & !0xfff, a.root_phys().as_u64());
+    if pcid::enabled() {
+        assert_ne!(a1 & 0xfff, 0, "user address spaces must not use PCID 0");
+        assert_ne!(a1 & 0xfff, b1 & 0xfff, "live address spaces share a PCID");
+        assert_eq!(a1, a2, "address space lost its PCID between switches");
+    } else {
+        assert_eq!(a1 & 0xfff, 0);
+        assert_eq!(b1 & 0xfff, 0);
+    }
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_pcid_unmap_bumps_tlb_generation() {
+    let space = new_space();
+    let page = Page::<Size4KiB>::containing_address(VirtAddr::new(0x4000_0000));
+    let frame = crate::mm::pmm::with(|pmm| {
+        let mut alloc = crate::mm::pmm::BuddyFrameAllocRef(pmm);
+        let frame = crate::mm::FrameAllocator::<Size4KiB>::allocate_frame(&mut alloc)
+            .expect("allocate user frame");
+        space
+            .map_user_page(
+                page,
+                frame,
+                crate::mm::mapper::MapFlags::WRITABLE,
+                &mut alloc,
+            )
+            .expect("map user page")
+            .ignore();
+        frame
+    });
+
+    let before = space.tlb_generation();
+    let cr3 = switch_and_back(&space);
+    space.unmap_user_page(page).expect("unmap user page");
+    assert!(
+        space.tlb_generation() > before,
+        "unmapping a user page must bump the TLB generation"
+    );
+    // The address space keeps its PCID; the stale generation only forces
+    // a flush.
+    assert_eq!(switch_and_back(&space), cr3);
+    dealloc_frame(frame);
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_pcid_kernel_unmap_retires_tags() {
+    if !pcid::enabled() {
+        return;
+    }
+    let space = new_space();
+    switch_and_back(&space);
+    let cpu = crate::percpu::PerCpuState::current().get_cpu_id().as_u32() as usize;
+    let tag = space
+        .asid_tag(cpu)
+        .load(core::sync::atomic::Ordering::Relaxed);
+    assert_ne!(tag, 0);
+
+    // Invalidating any kernel address starts a new generation everywhere.
+    pcid::flush_page(VirtAddr::new(dealloc_frame as *const () as u64));
+    switch_and_back(&space);
+    let retagged = space
+        .asid_tag(cpu)
+        .load(core::sync::atomic::Ordering::Relaxed);
+    assert!(
+        retagged >> 12 > tag >> 12,
+        "kernel unmap must retire PCIDs handed out before it"
+    );
+}
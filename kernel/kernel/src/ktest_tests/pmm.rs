@@ -86,3 +86,47 @@ fn test_pmm_multi_frame_cycle() {
             .expect("failed to re-allocate 8 frames");
     });
 }
+
+// ── Buddy allocator and hot caches ─────────────────────────────────────
+
+#[kernel_test(stage = "early_boot", timeout = 5)]
+fn test_buddy_block_alignment() {
+    crate::mm::pmm::with(|pmm| {
+        let free_before = pmm.free_frames();
+        // Order 9 = 512 frames = 2 MiB.
+        let block = pmm
+            .allocate_block(9)
+            .expect("failed to allocate 2 MiB block");
+        assert_eq!(
+            block.start_address().as_u64() % (2 * 1024 * 1024),
+            0,
+            "order-9 block should be 2 MiB aligned"
+        );
+        assert_eq!(pmm.free_frames(), free_before - 512);
+        unsafe {
+            pmm.deallocate_block(block, 9)
+                .expect("failed to deallocate block");
+        }
+        assert_eq!(pmm.free_frames(), free_before);
+    });
+}
+
+#[kernel_test(stage = "early_boot", timeout = 5)]
+fn test_hot_cache_round_trip() {
+    let frame = crate::mm::pmm::alloc_frame().expect("failed to allocate frame");
+    let cached = crate::mm::pmm::hot_cached_frames();
+    unsafe { crate::mm::pmm::free_frame(frame) };
+    assert_eq!(crate::mm::pmm::hot_cached_frames(), cached + 1);
+    // LIFO: the frame just freed is handed out again.
+    let again = crate::mm::pmm::alloc_frame().expect("failed to re-allocate frame");
+    assert_eq!(again, frame);
+    unsafe { crate::mm::pmm::free_frame(again) };
+
+    let free_before = crate::mm::pmm::with(|pmm| pmm.free_frames());
+    let drained = crate::mm::pmm::drain_hot_caches();
+    assert_eq!(crate::mm::pmm::hot_cached_frames(), 0);
+    assert_eq!(
+        crate::mm::pmm::with(|pmm| pmm.free_frames()),
+        free_before + drained
+    );
+}
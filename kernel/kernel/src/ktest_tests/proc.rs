@@ -5,8 +5,11 @@ extern crate alloc;
 use alloc::sync::Arc;
 use hadron_ktest::kernel_test;
 
-use crate::proc::signal::{Signal, SignalState};
-use crate::syscall::{SIGKILL, SIGTERM};
+use crate::proc::signal::{PostError, RTSIG_QUEUE_MAX, Signal, SignalInfo, SignalState, sig_bit};
+use crate::syscall::{
+    SA_SIGINFO, SI_QUEUE, SIG_BLOCK, SIG_IGN, SIGCHLD, SIGCONT, SIGKILL, SIGRTMIN, SIGSTOP,
+    SIGTERM, SIGTSTP, SIGUSR1, SigAction,
+};
 
 // ── Before executor stage — signal tests ────────────────────────────────
 
@@ -59,6 +62,177 @@ fn test_signal_has_pending() {
     assert!(!state.has_pending(), "should have no pending after dequeue");
 }
 
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_signal_set_layout() {
+    let state = SignalState::new();
+    state.set_mask(SIG_BLOCK, sig_bit(SIGTERM) | sig_bit(SIGRTMIN + 30));
+    state.post(SIGTERM);
+    state.post(SIGRTMIN + 30);
+    assert_eq!(
+        state.pending_blocked(),
+        (1 << 14) | (1 << 63),
+        "signal n should use bit n - 1, up to signal 64"
+    );
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_signal_standard_coalesces() {
+    let state = SignalState::new();
+    state
+        .post_info(SignalInfo::user(SIGUSR1, 7, 0))
+        .expect("post should succeed");
+    state
+        .post_info(SignalInfo::user(SIGUSR1, 8, 0))
+        .expect("post should succeed");
+    let info = state.dequeue_info().expect("SIGUSR1 should be pending");
+    assert_eq!(info.pid, 7, "the first sender's info should be kept");
+    assert_eq!(state.dequeue_info(), None, "SIGUSR1 should be pending once");
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_signal_realtime_queue_order() {
+    let state = SignalState::new();
+    let rt = SIGRTMIN + 1;
+    state
+        .post_info(SignalInfo::queued(rt, 1, 0, 10))
+        .expect("post should succeed");
+    state
+        .post_info(SignalInfo::queued(SIGRTMIN, 1, 0, 20))
+        .expect("post should succeed");
+    state
+        .post_info(SignalInfo::queued(rt, 1, 0, 30))
+        .expect("post should succeed");
+
+    let order: [(usize, u64); 3] = core::array::from_fn(|_| {
+        let info = state.dequeue_info().expect("signal should be queued");
+        assert_eq!(info.code, SI_QUEUE);
+        (info.signo, info.value)
+    });
+    assert_eq!(
+        order,
+        [(SIGRTMIN, 20), (rt, 10), (rt, 30)],
+        "lowest signal first, then FIFO per signal"
+    );
+    assert!(!state.has_pending());
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_signal_realtime_queue_full() {
+    let state = SignalState::new();
+    for _ in 0..RTSIG_QUEUE_MAX {
+        state
+            .post_info(SignalInfo::kernel(SIGRTMIN))
+            .expect("post should succeed");
+    }
+    assert_eq!(
+        state.post_info(SignalInfo::kernel(SIGRTMIN)),
+        Err(PostError::QueueFull)
+    );
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_signal_kill_stop_unblockable() {
+    let state = SignalState::new();
+    state.set_mask(SIG_BLOCK, u64::MAX);
+    assert_eq!(
+        state.get_mask() & (sig_bit(SIGKILL) | sig_bit(SIGSTOP)),
+        0,
+        "SIGKILL and SIGSTOP cannot be blocked"
+    );
+    let catch = SigAction {
+        handler: 0x1000,
+        ..SigAction::default()
+    };
+    assert!(state.set_action(SIGKILL, &catch).is_none());
+    assert!(state.set_action(SIGSTOP, &catch).is_none());
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_signal_ignored_discarded() {
+    let state = SignalState::new();
+    state.post(SIGCHLD);
+    assert!(
+        !state.is_pending(SIGCHLD),
+        "default-ignored signals are discarded"
+    );
+
+    state.set_mask(SIG_BLOCK, sig_bit(SIGUSR1));
+    state.post(SIGUSR1);
+    let ignore = SigAction {
+        handler: SIG_IGN,
+        ..SigAction::default()
+    };
+    state.set_action(SIGUSR1, &ignore);
+    assert!(
+        !state.is_pending(SIGUSR1),
+        "ignoring a signal discards it even while blocked"
+    );
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_signal_exec_resets_caught() {
+    let state = SignalState::new();
+    let caught = SigAction {
+        handler: 0x1000,
+        flags: SA_SIGINFO,
+        ..SigAction::default()
+    };
+    let ignored = SigAction {
+        handler: SIG_IGN,
+        ..SigAction::default()
+    };
+    state.set_action(SIGUSR1, &caught);
+    state.set_action(SIGTERM, &ignored);
+    assert_eq!(state.action(SIGUSR1).flags, SA_SIGINFO);
+
+    state.reset_for_exec();
+    assert_eq!(state.action(SIGUSR1).handler, 0, "caught → SIG_DFL");
+    assert_eq!(
+        state.action(SIGTERM).handler,
+        SIG_IGN,
+        "ignored stays ignored"
+    );
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_signal_stop_and_continue() {
+    let state = SignalState::new();
+    state.post(SIGTSTP);
+    assert_eq!(state.dequeue(), Some(Signal(SIGTSTP)));
+    assert!(state.stop(SIGTSTP), "dequeued stop signal should stop");
+    assert!(state.is_stopped());
+    assert_eq!(state.stop_report(false), Some(SIGTSTP), "peeking keeps it");
+    assert_eq!(state.stop_report(true), Some(SIGTSTP));
+    assert_eq!(state.stop_report(true), None, "stop is reported once");
+
+    state.post(SIGCONT);
+    assert!(!state.is_stopped(), "SIGCONT resumes the thread");
+    assert!(state.continue_report(true));
+    assert!(!state.continue_report(true), "resume is reported once");
+    assert!(state.take_continue_notify());
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_signal_continue_cancels_stop() {
+    let state = SignalState::new();
+    state.post(SIGSTOP);
+    state.post(SIGCONT);
+    assert!(!state.is_pending(SIGSTOP), "SIGCONT discards pending stops");
+
+    state.post(SIGTSTP);
+    assert_eq!(state.dequeue(), Some(Signal(SIGTSTP)));
+    state.post(SIGCONT);
+    assert!(
+        !state.stop(SIGTSTP),
+        "SIGCONT after dequeue should cancel the stop"
+    );
+    assert!(!state.is_stopped());
+    assert!(
+        !state.continue_report(true),
+        "never stopped, nothing to report"
+    );
+}
+
 // ── Before executor stage — PID allocation ──────────────────────────────
 
 #[kernel_test(stage = "before_executor", timeout = 5)]
@@ -80,7 +254,7 @@ fn test_pid_allocation_sequential() {
     // Create two processes inside the PMM lock, but return them so they
     // drop *outside* the lock (Drop -> dealloc_frame -> pmm::with).
     let (p1, p2) = crate::mm::pmm::with(|pmm| {
-        let mut alloc = crate::mm::pmm::BitmapFrameAllocRef(pmm);
+        let mut alloc = crate::mm::pmm::BuddyFrameAllocRef(pmm);
 
         let as1 = unsafe {
             AddressSpace::new_user(
@@ -119,6 +293,51 @@ fn test_pid_allocation_sequential() {
     // p1, p2 drop here — outside the PMM lock.
 }
 
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_replace_address_space_cancels_image() {
+    use crate::mm::address_space::AddressSpace;
+
+    let kernel_cr3 = crate::proc::TrapContext::kernel_cr3();
+    let hhdm = crate::mm::hhdm::offset();
+
+    #[cfg(target_arch = "x86_64")]
+    type KernelMapper = crate::arch::x86_64::paging::PageTableMapper;
+
+    fn dealloc_frame(frame: crate::paging::PhysFrame<crate::paging::Size4KiB>) {
+        crate::mm::pmm::with(|pmm| unsafe {
+            let _ = pmm.deallocate_frame(frame);
+        });
+    }
+
+    let new_space = || {
+        crate::mm::pmm::with(|pmm| {
+            let mut alloc = crate::mm::pmm::BuddyFrameAllocRef(pmm);
+            unsafe {
+                AddressSpace::new_user(
+                    kernel_cr3,
+                    KernelMapper::new(hhdm),
+                    hhdm,
+                    &mut alloc,
+                    dealloc_frame,
+                )
+                .expect("create address space")
+            }
+        })
+    };
+
+    let process = crate::proc::Process::new(new_space(), None);
+    let old = Arc::clone(&process.user_image.read());
+    assert!(!old.is_cancelled(), "fresh image should not be cancelled");
+
+    // Frees the old address space, so this must run outside the PMM lock.
+    process.replace_address_space(new_space());
+
+    let new = Arc::clone(&process.user_image.read());
+    assert!(old.is_cancelled(), "exec should cancel the old image");
+    assert!(!new.is_cancelled(), "exec should install a live image");
+    assert!(!Arc::ptr_eq(&old, &new));
+}
+
 // ── With executor stage — process table ─────────────────────────────────
 
 #[kernel_test(stage = "with_executor", timeout = 10)]
@@ -139,7 +358,7 @@ async fn test_process_table_register_lookup() {
 
     // Create process inside PMM lock, return it so it drops outside.
     let process = crate::mm::pmm::with(|pmm| {
-        let mut alloc = crate::mm::pmm::BitmapFrameAllocRef(pmm);
+        let mut alloc = crate::mm::pmm::BuddyFrameAllocRef(pmm);
         let addr_space = unsafe {
             AddressSpace::new_user(
                 kernel_cr3,
@@ -170,6 +389,71 @@ async fn test_process_table_register_lookup() {
     drop(process);
 }
 
+#[kernel_test(stage = "with_executor", timeout = 10)]
+async fn test_process_table_reparent_children() {
+    use crate::id::Pid;
+    use crate::mm::address_space::AddressSpace;
+    use crate::proc::{Process, ProcessTable};
+
+    #[cfg(target_arch = "x86_64")]
+    type KernelMapper = crate::arch::x86_64::paging::PageTableMapper;
+
+    fn dealloc_frame(frame: crate::paging::PhysFrame<crate::paging::Size4KiB>) {
+        crate::mm::pmm::with(|pmm| unsafe {
+            let _ = pmm.deallocate_frame(frame);
+        });
+    }
+
+    // Create the process inside the PMM lock, return it so it drops outside.
+    fn new_process(parent: Option<Pid>) -> Arc<Process> {
+        let kernel_cr3 = crate::proc::TrapContext::kernel_cr3();
+        let hhdm = crate::mm::hhdm::offset();
+        let process = crate::mm::pmm::with(|pmm| {
+            let mut alloc = crate::mm::pmm::BuddyFrameAllocRef(pmm);
+            let addr_space = unsafe {
+                AddressSpace::new_user(
+                    kernel_cr3,
+                    KernelMapper::new(hhdm),
+                    hhdm,
+                    &mut alloc,
+                    dealloc_frame,
+                )
+                .expect("create address space")
+            };
+            Arc::new(Process::new(addr_space, parent))
+        });
+        ProcessTable::register(&process);
+        process
+    }
+
+    let reaper = new_process(None);
+    let parent = new_process(Some(reaper.pid));
+    let live = new_process(Some(parent.pid));
+    let exited = new_process(Some(parent.pid));
+    *exited.exit_status.lock() = Some(0);
+
+    let reported = ProcessTable::reparent_children(&parent, Some(&reaper));
+    assert_eq!(reported, [exited.pid], "only exited orphans are reported");
+    assert!(ProcessTable::children_of(parent.pid).is_empty());
+    assert_eq!(live.parent_pid(), Some(reaper.pid));
+    assert_eq!(exited.parent_pid(), Some(reaper.pid));
+    let adopted = ProcessTable::children_of(reaper.pid);
+    assert!(adopted.contains(&live.pid) && adopted.contains(&exited.pid));
+
+    // Without a reaper the orphans are left parentless.
+    let reported = ProcessTable::reparent_children(&reaper, None);
+    assert_eq!(reported, [exited.pid]);
+    assert_eq!(live.parent_pid(), None);
+    assert_eq!(parent.parent_pid(), None);
+
+    // Unregister first, then drop the Arcs (which may trigger
+    // Process::Drop -> dealloc_frame -> pmm::with).
+    for process in [&live, &exited, &parent, &reaper] {
+        ProcessTable::unregister(process.pid);
+    }
+    drop((live, exited, parent, reaper));
+}
+
 // ── With executor stage — FD table ──────────────────────────────────────
 
 #[kernel_test(stage = "with_executor", timeout = 10)]
@@ -128,6 +128,51 @@ async fn test_sleep_ticks() {
     );
 }
 
+// ── High-resolution sleep ───────────────────────────────────────────────
+
+#[kernel_test(stage = "with_executor", timeout = 10)]
+async fn test_sleep_nanos() {
+    let before = crate::time::Time::boot_nanos();
+    crate::sched::primitives::sleep_nanos(250_000).await;
+    let elapsed = crate::time::Time::boot_nanos() - before;
+    assert!(
+        elapsed >= 250_000,
+        "woke after {elapsed} ns, expected at least 250000 ns"
+    );
+}
+
+#[kernel_test(stage = "with_executor", timeout = 10)]
+async fn test_dropped_timer_is_cancelled() {
+    use crate::sched::timer::Timer;
+
+    // Arm a far-future timer, then drop it before it fires.
+    let deadline = crate::sched::primitives::timeout_deadline(60_000_000_000);
+    core::future::poll_fn(|cx| {
+        let mut timer = Timer::at_nanos(deadline);
+        timer.register(cx.waker());
+        core::task::Poll::Ready(())
+    })
+    .await;
+    let next = crate::sched::timer::next_hr_expiry();
+    assert!(
+        next.is_none_or(|next| next < deadline),
+        "dropped timer still queued at {next:?}"
+    );
+}
+
+// ── Idle accounting ─────────────────────────────────────────────────
+
+#[kernel_test(stage = "with_executor", timeout = 10)]
+async fn test_sleep_accounts_idle_time() {
+    let before = crate::sched::tick::total_idle_nanos();
+    crate::sched::primitives::sleep_ticks(20).await;
+    let after = crate::sched::tick::total_idle_nanos();
+    assert!(
+        after > before,
+        "no idle time accounted while sleeping ({before} -> {after} ns)"
+    );
+}
+
 // ── Instanced barrier (4 instances) ────────────────────────────────────
 
 #[kernel_test(stage = "with_executor", instances = 0..=3, timeout = 10)]
@@ -181,7 +226,7 @@ async fn test_steal_from_executor() {
     victim.spawn_with_meta(async {}, TaskMeta::new("a").with_priority(Priority::Normal));
     victim.spawn_with_meta(async {}, TaskMeta::new("b").with_priority(Priority::Normal));
 
-    let stolen = victim.steal_task();
+    let stolen = victim.steal_task(crate::id::CpuId::new(0));
     assert!(
         stolen.is_some(),
         "should be able to steal a task from executor with 2 tasks"
@@ -203,7 +248,7 @@ async fn test_steal_respects_critical() {
         TaskMeta::new("crit2").with_priority(Priority::Critical),
     );
 
-    let stolen = victim.steal_task();
+    let stolen = victim.steal_task(crate::id::CpuId::new(0));
     assert!(stolen.is_none(), "Critical tasks should not be stealable");
 }
 
@@ -218,7 +263,7 @@ async fn test_steal_one_task_rule() {
         TaskMeta::new("only").with_priority(Priority::Normal),
     );
 
-    let stolen = victim.steal_task();
+    let stolen = victim.steal_task(crate::id::CpuId::new(0));
     assert!(
         stolen.is_none(),
         "one-task rule should prevent stealing the only runnable task"
@@ -239,9 +284,35 @@ async fn test_steal_allowed_with_two() {
         TaskMeta::new("t2").with_priority(Priority::Normal),
     );
 
-    let stolen = victim.steal_task();
+    let stolen = victim.steal_task(crate::id::CpuId::new(0));
     assert!(
         stolen.is_some(),
         "should be able to steal when there are 2 Normal tasks"
     );
 }
+
+#[kernel_test(stage = "with_executor", timeout = 10)]
+async fn test_steal_respects_affinity() {
+    use crate::id::CpuId;
+    use crate::sched::{Executor, Priority, TaskMeta};
+
+    let victim = Executor::new();
+    for name in ["p1", "p2"] {
+        victim.spawn_with_meta(
+            async {},
+            TaskMeta::new(name)
+                .with_priority(Priority::User)
+                .with_affinity(CpuId::new(1)),
+        );
+    }
+
+    assert!(
+        victim.steal_task(CpuId::new(0)).is_none(),
+        "tasks pinned to CPU 1 must not be stolen by CPU 0"
+    );
+    let stolen = victim.steal_task(CpuId::new(1));
+    assert!(
+        matches!(stolen, Some((_, Priority::User, _))),
+        "CPU 1 should be able to steal a User task pinned to it"
+    );
+}
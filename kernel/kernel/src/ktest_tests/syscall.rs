@@ -109,3 +109,101 @@ fn test_syscall_clock_gettime_invalid_clock() {
         "invalid clock should return -EINVAL"
     );
 }
+
+/// Dispatches syscall `nr` as if it had been issued from ring 3.
+///
+/// The mode is taken from the return RIP that the entry stub saved, so
+/// that is pointed at a user address for the duration of the call.
+fn dispatch_as_user(nr: usize, a0: usize, a1: usize, a2: usize, a3: usize) -> isize {
+    let regs = crate::arch::x86_64::syscall::SYSCALL_SAVED_REGS
+        .get()
+        .get()
+        .cast_mut();
+    // SAFETY: Nothing else touches this CPU's saved registers until the
+    // next syscall; the test restores the previous value before returning.
+    let saved = unsafe { core::mem::replace(&mut (*regs).user_rip, 0x40_0000) };
+    let result = crate::syscall::syscall_dispatch(nr, a0, a1, a2, a3, 0);
+    // SAFETY: As above.
+    unsafe { (*regs).user_rip = saved };
+    result
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_syscall_user_mode_kernel_pointer_faults() {
+    let mut ts = crate::syscall::Timespec {
+        tv_sec: u64::MAX,
+        tv_nsec: u64::MAX,
+    };
+    let result = dispatch_as_user(
+        crate::syscall::SYS_CLOCK_GETTIME,
+        crate::syscall::CLOCK_MONOTONIC,
+        &raw mut ts as usize,
+        0,
+        0,
+    );
+    assert_eq!(
+        result,
+        -(crate::syscall::EFAULT),
+        "an upper-half tp from user mode must fault"
+    );
+    assert_eq!(ts.tv_sec, u64::MAX, "kernel memory must not be written");
+
+    let mut uptime = [0u8; 64];
+    let result = dispatch_as_user(
+        crate::syscall::SYS_QUERY,
+        crate::syscall::QUERY_UPTIME as usize,
+        0,
+        uptime.as_mut_ptr() as usize,
+        uptime.len(),
+    );
+    assert_eq!(result, -(crate::syscall::EFAULT));
+    assert!(uptime.iter().all(|&b| b == 0));
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_uaccess_exception_table_populated() {
+    let entries = crate::arch::x86_64::uaccess::exception_table_entries();
+    assert!(
+        !entries.is_empty(),
+        "user copy routine must register a fixup"
+    );
+    for entry in entries {
+        assert_eq!(
+            crate::arch::x86_64::uaccess::search_exception_table(entry.fault_ip),
+            Some(entry.fixup_ip)
+        );
+    }
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_uaccess_unmapped_user_address_faults() {
+    // No user address space is active, so user addresses outside the boot
+    // identity map are unmapped and the copy must fault, resume at the
+    // fixup, and report the failure.
+    let mut buf = [0u8; 16];
+    let result = crate::syscall::userptr::UserSlice::new(0x1000_0000_0000, buf.len())
+        .and_then(|slice| slice.read_into(&mut buf));
+    assert_eq!(result, Err(-crate::syscall::EFAULT));
+
+    let result =
+        crate::syscall::userptr::UserPtr::<u64>::new(0x1000_0000_1000).and_then(|p| p.write(7));
+    assert_eq!(result, Err(-crate::syscall::EFAULT));
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_uaccess_protections_enabled() {
+    use crate::arch::x86_64::cpuid::CpuFeatures;
+    use crate::arch::x86_64::registers::control::{Cr4, Cr4Flags};
+
+    let features = crate::arch::x86_64::cpuid::cpu_features();
+    let cr4 = Cr4::read();
+    if features.contains(CpuFeatures::SMEP) {
+        assert!(cr4.contains(Cr4Flags::SMEP), "CR4.SMEP must be set");
+    }
+    if cfg!(hadron_alt_instructions) && features.contains(CpuFeatures::SMAP) {
+        assert!(cr4.contains(Cr4Flags::SMAP), "CR4.SMAP must be set");
+    }
+    if features.contains(CpuFeatures::UMIP) {
+        assert!(cr4.contains(Cr4Flags::UMIP), "CR4.UMIP must be set");
+    }
+}
@@ -5,7 +5,8 @@ extern crate alloc;
 use alloc::vec;
 use hadron_ktest::kernel_test;
 
-use crate::fs::{FsError, InodeType, Permissions, poll_immediate};
+use crate::fs::{FsError, InodeType, Ownership, Permissions, poll_immediate};
+use crate::id::{Gid, Uid};
 
 // ── Before executor stage — sync tests ──────────────────────────────────
 
@@ -139,6 +140,25 @@ fn test_vfs_unlink_file() {
     }
 }
 
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_vfs_ramfs_ownership() {
+    let root = crate::fs::vfs::with_vfs(|vfs| vfs.resolve("/")).expect("resolve /");
+    let file = poll_immediate(root.create("ktest_owned", InodeType::File, Permissions::all()))
+        .expect("create file");
+    // New inodes are root-owned with their permissions in every class.
+    let own = file.ownership();
+    assert_eq!((own.uid, own.gid, own.mode), (Uid::ROOT, Gid::ROOT, 0o777));
+
+    let owned = Ownership::new(Uid::new(1000), Gid::new(100), 0o4750);
+    file.set_ownership(owned).expect("set ownership");
+    assert_eq!(file.ownership(), owned);
+    // `permissions()` reflects the owner class.
+    let perms = file.permissions();
+    assert!(perms.read && perms.write && perms.execute);
+
+    poll_immediate(root.unlink("ktest_owned")).expect("unlink");
+}
+
 // ── With executor stage — async test ────────────────────────────────────
 
 #[kernel_test(stage = "with_executor", timeout = 10)]
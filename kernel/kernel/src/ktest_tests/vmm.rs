@@ -79,3 +79,140 @@ fn test_mmio_map_unmap() {
     // Drop unmaps — no crash means success.
     drop(mapping);
 }
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_user_huge_page_split_and_unmap() {
+    use crate::addr::VirtAddr;
+    use crate::mm::address_space::{AddressSpace, HUGE_PAGE_PAGES};
+    use crate::mm::mapper::MapFlags;
+    use crate::paging::{Page, Size2MiB};
+
+    #[cfg(target_arch = "x86_64")]
+    type KernelMapper = crate::arch::x86_64::paging::PageTableMapper;
+
+    fn dealloc_frame(frame: crate::paging::PhysFrame<crate::paging::Size4KiB>) {
+        crate::mm::pmm::with(|pmm| unsafe {
+            let _ = pmm.deallocate_frame(frame);
+        });
+    }
+
+    let kernel_cr3 = crate::proc::TrapContext::kernel_cr3();
+    let hhdm = crate::mm::hhdm::offset();
+    let base = VirtAddr::new(0x4000_0000);
+
+    // Build and tear down inside the PMM lock; the address space drops
+    // outside it (Drop -> dealloc_frame -> pmm::with).
+    let space = crate::mm::pmm::with(|pmm| {
+        let free_before = pmm.free_frames();
+        let mut alloc = crate::mm::pmm::BuddyFrameAllocRef(pmm);
+        let space = unsafe {
+            AddressSpace::new_user(
+                kernel_cr3,
+                KernelMapper::new(hhdm),
+                hhdm,
+                &mut alloc,
+                dealloc_frame,
+            )
+            .expect("create address space")
+        };
+
+        let frame = crate::mm::FrameAllocator::<Size2MiB>::allocate_frame(&mut alloc)
+            .expect("allocate 2 MiB frame");
+        space
+            .map_user_huge_page(
+                Page::containing_address(base),
+                frame,
+                MapFlags::WRITABLE,
+                &mut alloc,
+            )
+            .expect("map huge page");
+        assert_eq!(space.resident_pages(), HUGE_PAGE_PAGES);
+        assert_eq!(
+            space.translate(base + 0x1234),
+            Some(frame.start_address() + 0x1234)
+        );
+
+        // Protecting one page splits the huge page; translation is unchanged.
+        space
+            .protect_range(base + 0x1000, 1, MapFlags::empty(), &mut alloc)
+            .expect("protect inside huge page");
+        assert_eq!(
+            space.translate(base + 0x1234),
+            Some(frame.start_address() + 0x1234)
+        );
+        assert_eq!(space.resident_pages(), HUGE_PAGE_PAGES);
+
+        space
+            .unmap_user_range(base, HUGE_PAGE_PAGES, &mut alloc, true)
+            .expect("unmap range");
+        assert_eq!(space.resident_pages(), 0);
+        assert_eq!(space.translate(base), None);
+
+        // Everything but the address space's page tables was returned.
+        assert_eq!(
+            alloc.0.free_frames() + space.page_table_pages(),
+            free_before
+        );
+        space
+    });
+    drop(space);
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_user_foreign_page_not_resident() {
+    use crate::addr::VirtAddr;
+    use crate::mm::address_space::AddressSpace;
+    use crate::mm::mapper::MapFlags;
+    use crate::paging::Page;
+
+    #[cfg(target_arch = "x86_64")]
+    type KernelMapper = crate::arch::x86_64::paging::PageTableMapper;
+
+    fn dealloc_frame(frame: crate::paging::PhysFrame<crate::paging::Size4KiB>) {
+        crate::mm::pmm::with(|pmm| unsafe {
+            let _ = pmm.deallocate_frame(frame);
+        });
+    }
+
+    let kernel_cr3 = crate::proc::TrapContext::kernel_cr3();
+    let hhdm = crate::mm::hhdm::offset();
+    let base = VirtAddr::new(0x4000_0000);
+
+    let space = crate::mm::pmm::with(|pmm| {
+        let mut alloc = crate::mm::pmm::BuddyFrameAllocRef(pmm);
+        let space = unsafe {
+            AddressSpace::new_user(
+                kernel_cr3,
+                KernelMapper::new(hhdm),
+                hhdm,
+                &mut alloc,
+                dealloc_frame,
+            )
+            .expect("create address space")
+        };
+
+        // A device or shared frame is mapped but never counted as resident.
+        let frame = alloc.0.allocate_frame().expect("allocate frame");
+        space
+            .map_user_foreign_page(
+                Page::containing_address(base),
+                frame,
+                MapFlags::WRITABLE,
+                &mut alloc,
+            )
+            .expect("map foreign page");
+        assert_eq!(space.resident_pages(), 0);
+        assert_eq!(space.translate(base), Some(frame.start_address()));
+
+        space
+            .unmap_user_range(base, 1, &mut alloc, false)
+            .expect("unmap range");
+        assert_eq!(space.resident_pages(), 0);
+        assert_eq!(space.translate(base), None);
+
+        // The frame was not owned by the address space, so free it here.
+        unsafe { alloc.0.deallocate_frame(frame).expect("free frame") };
+        space
+    });
+    drop(space);
+}
@@ -0,0 +1,152 @@
+//! Kernel mapping permission tests — W^X and read-only-after-init, checked
+//! by walking the live page tables.
+
+use hadron_ktest::kernel_test;
+
+use crate::addr::{PhysAddr, VirtAddr};
+use crate::arch::x86_64::structures::paging::{PageTable, PageTableFlags};
+
+/// Effective permissions of a leaf mapping: writable only if every level
+/// allows writes, executable only if no level sets NX.
+#[derive(Clone, Copy)]
+struct Access {
+    writable: bool,
+    executable: bool,
+}
+
+impl Access {
+    const ALL: Self = Self {
+        writable: true,
+        executable: true,
+    };
+
+    fn restrict(self, flags: PageTableFlags) -> Self {
+        Self {
+            writable: self.writable && flags.contains(PageTableFlags::WRITABLE),
+            executable: self.executable && !flags.contains(PageTableFlags::NO_EXECUTE),
+        }
+    }
+}
+
+/// Returns the page table at `phys`.
+fn table(phys: PhysAddr) -> &'static PageTable {
+    let virt = crate::mm::hhdm::offset() + phys.as_u64();
+    // SAFETY: `phys` comes from a present non-leaf entry of a live page
+    // table, and the HHDM maps all physical memory.
+    unsafe { &*(virt.as_u64() as *const PageTable) }
+}
+
+/// Calls `f` with the address, size and effective permissions of every
+/// present leaf mapping reachable from `root`.
+fn walk(root: PhysAddr, f: &mut impl FnMut(VirtAddr, u64, Access)) {
+    fn level(
+        phys: PhysAddr,
+        depth: u32,
+        base: u64,
+        access: Access,
+        f: &mut impl FnMut(VirtAddr, u64, Access),
+    ) {
+        let shift = 39 - 9 * depth;
+        for (i, entry) in table(phys).entries.iter().enumerate() {
+            if !entry.is_present() {
+                continue;
+            }
+            let virt = base | ((i as u64) << shift);
+            let access = access.restrict(entry.flags());
+            if depth == 3 || (depth > 0 && entry.flags().contains(PageTableFlags::HUGE_PAGE)) {
+                f(VirtAddr::new_truncate(virt), 1 << shift, access);
+            } else {
+                level(entry.address(), depth + 1, virt, access, f);
+            }
+        }
+    }
+    level(root, 0, 0, Access::ALL, f);
+}
+
+/// Looks up the effective permissions of `addr` under `root`.
+fn access_at(root: PhysAddr, addr: VirtAddr) -> Option<Access> {
+    let mut found = None;
+    walk(root, &mut |virt, size, access| {
+        if (virt.as_u64()..virt.as_u64() + size).contains(&addr.as_u64()) {
+            found = Some(access);
+        }
+    });
+    found
+}
+
+/// Asserts that no mapping under `root` is both writable and executable.
+fn assert_w_xor_x(root: PhysAddr, what: &str) {
+    let mut leaves = 0usize;
+    walk(root, &mut |virt, size, access| {
+        leaves += 1;
+        assert!(
+            !(access.writable && access.executable),
+            "{} maps {:#x} (+{:#x}) writable and executable",
+            what,
+            virt.as_u64(),
+            size
+        );
+    });
+    assert!(leaves > 0, "{} has no mappings", what);
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kernel_mappings_w_xor_x() {
+    assert_w_xor_x(crate::proc::TrapContext::kernel_cr3(), "kernel page tables");
+
+    #[cfg(hadron_kpti)]
+    if let Some(template) = crate::arch::x86_64::kpti::shadow_template() {
+        assert_w_xor_x(template, "KPTI shadow template");
+    }
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_kernel_text_and_rodata_read_only() {
+    let root = crate::proc::TrapContext::kernel_cr3();
+
+    let text = VirtAddr::new(access_at as *const () as u64);
+    let access = access_at(root, text).expect("kernel text not mapped");
+    assert!(access.executable, "kernel text must be executable");
+    assert!(!access.writable, "kernel text must be read-only");
+
+    static RODATA: [u8; 4] = *b"wx!\0";
+    let rodata = VirtAddr::new(RODATA.as_ptr() as u64);
+    let access = access_at(root, rodata).expect("kernel rodata not mapped");
+    assert!(!access.executable, "kernel rodata must not be executable");
+    assert!(!access.writable, "kernel rodata must be read-only");
+}
+
+#[kernel_test(stage = "before_executor", timeout = 5)]
+fn test_ro_after_init_read_only() {
+    let root = crate::proc::TrapContext::kernel_cr3();
+    let (start, end) = crate::mm::vmm::ro_after_init_range();
+    assert!(start < end, "ro_after_init section is empty");
+    assert!(start.is_aligned(4096u64) && end.is_aligned(4096u64));
+
+    let mut virt = start;
+    while virt < end {
+        let access = access_at(root, virt).expect("ro_after_init page not mapped");
+        assert!(
+            !access.writable,
+            "ro_after_init page {:#x} is still writable",
+            virt.as_u64()
+        );
+        assert!(!access.executable);
+        virt = virt + 4096;
+    }
+
+    // The statics the kernel declares read-only-after-init live there.
+    let section = start.as_u64()..end.as_u64();
+    let idt = crate::arch::x86_64::idt::table_address().as_u64();
+    assert!(section.contains(&idt), "IDT is not in .data.ro_after_init");
+    let gdt = crate::arch::x86_64::gdt::bsp_tables().gdt.start;
+    assert!(
+        section.contains(&gdt),
+        "BSP GDT is not in .data.ro_after_init"
+    );
+    let dispatch = &hadron_core::mem::dispatch::kernel_memcpy::DISPATCH as *const _ as u64;
+    assert!(
+        section.contains(&dispatch),
+        "alt-fn dispatch pointers are not in .data.ro_after_init"
+    );
+}
@@ -11,6 +11,7 @@
 )]
 #![cfg_attr(target_arch = "x86_64", feature(abi_x86_interrupt))]
 #![feature(allocator_api, negative_impls, never_type)]
+#![cfg_attr(hadron_kasan, feature(sanitize))]
 #![warn(missing_docs)]
 
 extern crate alloc;
@@ -22,6 +23,8 @@ extern crate alloc;
 pub use hadron_core::addr;
 pub use hadron_core::cell;
 pub use hadron_core::cpu_local;
+pub use hadron_core::cputime;
+pub use hadron_core::cred;
 pub use hadron_core::id;
 pub use hadron_core::paging;
 pub use hadron_core::static_assert;
@@ -91,7 +94,7 @@ pub fn test_init(boot_info: &impl boot::BootInfo) {
     crate::arch::cpu_init();
     crate::mm::hhdm::init(addr::VirtAddr::new(boot_info.hhdm_offset()));
     #[cfg(target_arch = "x86_64")]
-    crate::mm::mapper::register_tlb_flush(crate::arch::x86_64::instructions::tlb::flush);
+    crate::mm::mapper::register_tlb_flush(crate::arch::x86_64::pcid::flush_page);
     crate::backtrace::Backtrace::init_from_embedded(
         boot_info.kernel_address().virtual_base.as_u64(),
     );
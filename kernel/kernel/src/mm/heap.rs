@@ -11,6 +11,7 @@ pub use hadron_mm::heap::*;
 /// 1. Maps initial heap pages via VMM/PMM.
 /// 2. Initializes the global linked-list allocator.
 /// 3. Registers the growth callback.
+/// 4. Enables the slab caches in front of the linked list.
 pub fn init() {
     let (heap_start, heap_size) = super::vmm::map_initial_heap();
 
@@ -19,6 +20,7 @@ pub fn init() {
     }
 
     register_grow_fn(grow_callback);
+    super::slab::init();
 
     crate::ktrace_subsys!(
         mm,
@@ -0,0 +1,259 @@
+//! Kernel address sanitizer (KASAN) — kernel glue and runtime.
+//!
+//! Re-exports the shadow memory core from `hadron-mm`. Adds the boot-time
+//! shadow mapping for the HHDM, the heap shadow mapping used by the VMM as
+//! the heap grows, and the `__asan_*` callbacks the instrumented kernel
+//! crates call before every load and store.
+//!
+//! A bad access is reported on COM1 with an HKIF-symbolized backtrace, then
+//! the kernel panics. Kernel tests provoke bad accesses on purpose through
+//! [`expect_report`], which records the report instead of panicking.
+
+// The runtime checks accesses made by instrumented code; it must not be
+// instrumented itself.
+#![sanitize(address = "off")]
+
+pub use hadron_mm::kasan::*;
+
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+
+use crate::addr::VirtAddr;
+use crate::boot::{BootInfo, MemoryRegionKind};
+use crate::drivers::early_console::{COM1, EarlySerial};
+use crate::log::SerialWriter;
+use crate::mm::pmm::BuddyFrameAllocRef;
+use crate::mm::vmm::KernelVmm;
+
+/// Maps the shadow of the HHDM and turns checking on.
+///
+/// The HHDM window ends with the last usable RAM region; memory above it
+/// (firmware tables, MMIO holes) is not checked. Its shadow is mapped for
+/// the whole window, holes included, so every HHDM address below the end
+/// can be checked. Must run after the VMM is initialized and before the
+/// heap, whose shadow is mapped as it grows (see [`map_heap_shadow`]).
+pub fn init(boot_info: &impl BootInfo) {
+    let ram_end = boot_info
+        .memory_map()
+        .iter()
+        .filter(|r| r.kind == MemoryRegionKind::Usable)
+        .map(|r| r.start.as_u64() + r.size)
+        .max()
+        .unwrap_or(0);
+
+    let (heap, hhdm) = crate::mm::vmm::with(|vmm| {
+        let layout = *vmm.layout();
+        let hhdm = Window::hhdm(&layout, ram_end);
+        let (start, end) = hhdm
+            .shadow_range(hhdm.base, hhdm.size)
+            .expect("KASAN: no memory to shadow");
+        crate::mm::pmm::with(|pmm| {
+            vmm.map_zeroed(
+                VirtAddr::new(start as u64),
+                VirtAddr::new(end as u64),
+                &mut BuddyFrameAllocRef(pmm),
+            )
+            .expect("KASAN: failed to map HHDM shadow");
+        });
+        (Window::heap(&layout, 0), hhdm)
+    });
+
+    // SAFETY: The HHDM shadow was just mapped and zeroed; the heap window
+    // is empty until the heap maps its shadow.
+    unsafe { enable(heap, hhdm) };
+    crate::kinfo!(
+        "KASAN: enabled, shadowing {} MiB of HHDM ({} KiB shadow)",
+        hhdm.size >> 20,
+        hhdm.size.div_ceil(GRANULE_SIZE) >> 10
+    );
+}
+
+/// Maps the shadow of newly mapped heap pages `[base, base + size)` and
+/// extends the heap window over them.
+///
+/// Called by the VMM with the VMM and PMM locks held.
+pub(super) fn map_heap_shadow(
+    vmm: &mut KernelVmm,
+    alloc: &mut BuddyFrameAllocRef<'_>,
+    base: VirtAddr,
+    size: u64,
+) -> Result<(), crate::mm::VmmError> {
+    let layout = vmm.layout();
+    let heap_size = (base + size - layout.heap.base()) as usize;
+    let window = Window::heap(layout, heap_size);
+    if let Some((start, end)) = window.shadow_range(base.as_u64() as usize, size as usize) {
+        vmm.map_zeroed(
+            VirtAddr::new(start as u64),
+            VirtAddr::new(end as u64),
+            alloc,
+        )?;
+    }
+    // SAFETY: The shadow of the whole extended window is now mapped; pages
+    // mapped by `map_zeroed` are zeroed.
+    unsafe { extend_heap(heap_size) };
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Reports
+// ---------------------------------------------------------------------------
+
+/// A bad access caught by the sanitizer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Report {
+    /// Start of the access.
+    pub addr: usize,
+    /// Size of the access in bytes.
+    pub size: usize,
+    /// Whether the access was a store.
+    pub write: bool,
+    /// What the shadow said about it.
+    pub bad: BadAccess,
+}
+
+/// Set while a report is printed, so the reporter's own accesses are not
+/// checked, and left set once the kernel panics.
+static REPORTING: AtomicBool = AtomicBool::new(false);
+
+/// Set while [`expect_report`] runs.
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+
+/// First report captured by [`expect_report`]. `CAPTURED_SIZE` is written
+/// last and is nonzero once a report has been captured.
+static CAPTURED_ADDR: AtomicUsize = AtomicUsize::new(0);
+static CAPTURED_SIZE: AtomicUsize = AtomicUsize::new(0);
+static CAPTURED_WRITE: AtomicBool = AtomicBool::new(false);
+static CAPTURED_BAD_ADDR: AtomicUsize = AtomicUsize::new(0);
+static CAPTURED_CODE: AtomicU8 = AtomicU8::new(0);
+
+/// Runs `f` with reports captured instead of panicking, and returns the
+/// first report `f` triggered, if any.
+///
+/// Reports are still printed. Meant for kernel tests that provoke bad
+/// accesses on purpose; `f` must not leave anything corrupted behind.
+pub fn expect_report(f: impl FnOnce()) -> Option<Report> {
+    CAPTURED_SIZE.store(0, Ordering::Relaxed);
+    CAPTURING.store(true, Ordering::SeqCst);
+    f();
+    CAPTURING.store(false, Ordering::SeqCst);
+    let size = CAPTURED_SIZE.load(Ordering::Acquire);
+    (size != 0).then(|| Report {
+        addr: CAPTURED_ADDR.load(Ordering::Relaxed),
+        size,
+        write: CAPTURED_WRITE.load(Ordering::Relaxed),
+        bad: BadAccess {
+            addr: CAPTURED_BAD_ADDR.load(Ordering::Relaxed),
+            code: CAPTURED_CODE.load(Ordering::Relaxed),
+        },
+    })
+}
+
+/// Prints a report for `access` and panics, unless [`expect_report`] is
+/// capturing.
+#[cold]
+#[inline(never)]
+fn report(access: Report) {
+    if REPORTING.swap(true, Ordering::Acquire) {
+        return;
+    }
+
+    let mut w = SerialWriter(EarlySerial::new(COM1));
+    let _ = write!(
+        w,
+        "\n==================================================================\n\
+         BUG: KASAN: {} at {:#x}\n\
+         {} of size {} at addr {:#x}, shadow byte {:#04x}\n",
+        access.bad.kind(),
+        access.bad.addr,
+        if access.write { "Write" } else { "Read" },
+        access.size,
+        access.addr,
+        access.bad.code,
+    );
+    crate::backtrace::Backtrace::panic_backtrace(&mut w);
+    let _ = w.write_str("==================================================================\n");
+
+    if CAPTURING.load(Ordering::SeqCst) {
+        if CAPTURED_SIZE.load(Ordering::Relaxed) == 0 {
+            CAPTURED_ADDR.store(access.addr, Ordering::Relaxed);
+            CAPTURED_WRITE.store(access.write, Ordering::Relaxed);
+            CAPTURED_BAD_ADDR.store(access.bad.addr, Ordering::Relaxed);
+            CAPTURED_CODE.store(access.bad.code, Ordering::Relaxed);
+            CAPTURED_SIZE.store(access.size, Ordering::Release);
+        }
+        REPORTING.store(false, Ordering::Release);
+        return;
+    }
+
+    panic!(
+        "KASAN: {} of size {} at {:#x}",
+        access.bad.kind(),
+        access.size,
+        access.addr
+    );
+}
+
+/// Checks an access and reports it if it is bad.
+#[inline(always)]
+fn access(addr: usize, size: usize, write: bool) {
+    if let Err(bad) = check(addr, size)
+        && !REPORTING.load(Ordering::Relaxed)
+    {
+        report(Report {
+            addr,
+            size,
+            write,
+            bad,
+        });
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Compiler callbacks
+// ---------------------------------------------------------------------------
+
+/// Defines the fixed-size `__asan_load<N>` / `__asan_store<N>` callbacks.
+macro_rules! sized_callbacks {
+    ($($size:literal => $load:ident, $store:ident;)*) => {
+        $(
+            #[doc = concat!("Called before every ", stringify!($size), "-byte load.")]
+            #[unsafe(no_mangle)]
+            pub extern "C" fn $load(addr: usize) {
+                access(addr, $size, false);
+            }
+
+            #[doc = concat!("Called before every ", stringify!($size), "-byte store.")]
+            #[unsafe(no_mangle)]
+            pub extern "C" fn $store(addr: usize) {
+                access(addr, $size, true);
+            }
+        )*
+    };
+}
+
+sized_callbacks! {
+    1 => __asan_load1, __asan_store1;
+    2 => __asan_load2, __asan_store2;
+    4 => __asan_load4, __asan_store4;
+    8 => __asan_load8, __asan_store8;
+    16 => __asan_load16, __asan_store16;
+}
+
+/// Called before a load of any other size.
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "C" fn __asan_loadN(addr: usize, size: usize) {
+    access(addr, size, false);
+}
+
+/// Called before a store of any other size.
+#[allow(non_snake_case)]
+#[unsafe(no_mangle)]
+pub extern "C" fn __asan_storeN(addr: usize, size: usize) {
+    access(addr, size, true);
+}
+
+/// Called before calls to functions that never return. Only matters with
+/// stack instrumentation, which the kernel does not use.
+#[unsafe(no_mangle)]
+pub extern "C" fn __asan_handle_no_return() {}
@@ -12,14 +12,20 @@ pub use hadron_mm::{
 
 // Re-export submodules that don't need kernel extension.
 pub use hadron_mm::address_space;
+pub use hadron_mm::buddy;
 pub use hadron_mm::hhdm;
 pub use hadron_mm::layout;
 pub use hadron_mm::mapper;
 pub use hadron_mm::region;
+pub use hadron_mm::user_layout;
 pub use hadron_mm::zone;
 
 // Kernel-extended modules (re-export hadron-mm contents + add glue).
 pub mod heap;
+#[cfg(hadron_kasan)]
+pub mod kasan;
+pub mod oom;
 pub mod pmm;
 pub mod scope;
+pub mod slab;
 pub mod vmm;
@@ -0,0 +1,169 @@
+//! Out-of-memory handling — kernel glue.
+//!
+//! Re-exports the victim scoring from `hadron-mm` and adds the kernel-side
+//! OOM killer: when a user allocation cannot be satisfied, [`out_of_memory`]
+//! logs a memory report, selects the process with the highest badness, and
+//! delivers `SIGKILL` to it (and to every thread sharing its address space).
+//! The failing allocation itself returns `ENOMEM`; the memory is reclaimed
+//! once the victim exits.
+
+pub use hadron_mm::oom::*;
+
+extern crate alloc;
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use hadron_core::sync::atomic::Ordering;
+
+use crate::mm::address_space::{HUGE_PAGE_PAGES, USER_MAP_MAX_TABLE_FRAMES};
+use crate::mm::pmm::{BuddyAllocator, HUGE_PAGE_ORDER};
+use crate::paging::{PhysFrame, Size2MiB, Size4KiB};
+use crate::proc::{Process, ProcessTable};
+use crate::syscall::SIGKILL;
+use crate::{kerr, kwarn};
+
+/// Returns `true` if enough frames are free for [`map_user_page`] to
+/// allocate its intermediate page tables without panicking.
+///
+/// [`map_user_page`]: crate::mm::address_space::AddressSpace::map_user_page
+pub fn has_table_reserve(pmm: &BuddyAllocator) -> bool {
+    pmm.free_frames() >= USER_MAP_MAX_TABLE_FRAMES
+}
+
+/// Allocates a frame to back a user page.
+///
+/// Fails while fewer than [`USER_MAP_MAX_TABLE_FRAMES`] frames would remain,
+/// so that the subsequent [`map_user_page`] call can always allocate its
+/// intermediate page tables instead of panicking.
+///
+/// [`map_user_page`]: crate::mm::address_space::AddressSpace::map_user_page
+pub fn alloc_user_frame(pmm: &mut BuddyAllocator) -> Option<PhysFrame<Size4KiB>> {
+    if pmm.free_frames() <= USER_MAP_MAX_TABLE_FRAMES {
+        return None;
+    }
+    pmm.allocate_frame()
+}
+
+/// Allocates a 2 MiB frame to back a user huge page.
+///
+/// Keeps the same [`USER_MAP_MAX_TABLE_FRAMES`] reserve as
+/// [`alloc_user_frame`] for the subsequent [`map_user_huge_page`] call.
+///
+/// [`map_user_huge_page`]: crate::mm::address_space::AddressSpace::map_user_huge_page
+pub fn alloc_user_huge_frame(pmm: &mut BuddyAllocator) -> Option<PhysFrame<Size2MiB>> {
+    if pmm.free_frames() < HUGE_PAGE_PAGES + USER_MAP_MAX_TABLE_FRAMES {
+        return None;
+    }
+    let frame = pmm.allocate_block(HUGE_PAGE_ORDER)?;
+    Some(PhysFrame::containing_address(frame.start_address()))
+}
+
+/// Builds the OOM candidate describing `process`.
+fn candidate(process: &Process) -> OomCandidate {
+    OomCandidate {
+        id: process.pid.as_u32(),
+        resident_pages: process.resident_pages(),
+        page_table_pages: process.page_table_pages(),
+        score_adj: process.oom_score_adj.load(Ordering::Relaxed),
+    }
+}
+
+/// Returns the normalized OOM score (0..=1000) of `process`.
+pub fn score_of(process: &Process) -> u32 {
+    let total = crate::mm::pmm::with(|pmm| pmm.total_frames());
+    oom_score(&candidate(process), total)
+}
+
+/// Handles a failed user allocation of `requested_pages` pages.
+///
+/// Must be called without the PMM or any address-space lock held. Drains
+/// the per-CPU frame caches first; if that does not free enough memory,
+/// logs a memory report and kills the selected victim. If a previous victim is
+/// still exiting, no new process is killed. Init (PID 1) is never chosen.
+pub fn out_of_memory(requested_pages: usize) {
+    // Frames parked in the per-CPU hot caches are invisible to the buddy
+    // allocator; give them back before resorting to a kill.
+    let reclaimed = crate::mm::pmm::drain_hot_caches();
+    if reclaimed >= requested_pages + USER_MAP_MAX_TABLE_FRAMES {
+        kwarn!(
+            "OOM: reclaimed {} frames from per-CPU caches, not killing",
+            reclaimed
+        );
+        return;
+    }
+
+    let (total, free) = crate::mm::pmm::with(|pmm| (pmm.total_frames(), pmm.free_frames()));
+
+    // Snapshot live processes outside the process table lock. Threads that
+    // share an address space are charged once, to the lowest PID.
+    let mut processes: Vec<Arc<Process>> = Vec::new();
+    for pid in ProcessTable::all_pids() {
+        let Some(process) = ProcessTable::lookup(pid) else {
+            continue;
+        };
+        if process.exit_status.lock().is_some() {
+            continue;
+        }
+        if process.signals.is_pending(SIGKILL) {
+            kwarn!(
+                "OOM: process {} is already being killed, not selecting another victim",
+                pid
+            );
+            return;
+        }
+        if processes.iter().any(|p| p.shares_address_space(&process)) {
+            continue;
+        }
+        processes.push(process);
+    }
+
+    let candidates: Vec<OomCandidate> = processes
+        .iter()
+        .filter(|p| p.pid.as_u32() != 1)
+        .map(|p| candidate(p))
+        .collect();
+
+    kerr!(
+        "OOM: failed to allocate {} pages ({} of {} frames free)",
+        requested_pages,
+        free,
+        total
+    );
+    kerr!("OOM: [  pid] rss_pages pt_pages score_adj oom_score name");
+    for process in &processes {
+        let c = candidate(process);
+        let exe = process.exe_path.lock().clone();
+        kerr!(
+            "OOM: [{:>5}] {:>9} {:>8} {:>9} {:>9} {}",
+            c.id,
+            c.resident_pages,
+            c.page_table_pages,
+            c.score_adj,
+            oom_score(&c, total),
+            exe
+        );
+    }
+
+    let Some(victim) = select_victim(&candidates, total) else {
+        kerr!("OOM: no killable process found");
+        return;
+    };
+
+    kerr!(
+        "OOM: killing process {} (rss {} KiB, page tables {} KiB)",
+        victim.id,
+        victim.resident_pages * 4,
+        victim.page_table_pages * 4
+    );
+    let Some(victim) = processes.iter().find(|p| p.pid.as_u32() == victim.id) else {
+        return;
+    };
+    for pid in ProcessTable::all_pids() {
+        if let Some(process) = ProcessTable::lookup(pid) {
+            if process.shares_address_space(victim) {
+                process.signals.post(SIGKILL);
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! Physical memory manager — kernel glue.
 //!
-//! Re-exports the bitmap allocator and PMM global from `hadron-mm`.
+//! Re-exports the buddy allocator, hot caches and PMM global from `hadron-mm`.
 //! Adds `init(boot_info)` which converts bootloader memory map into
 //! `PhysMemoryRegion` descriptors before delegating to `hadron_mm::pmm::init`.
 
@@ -14,7 +14,7 @@ use hadron_mm::PhysMemoryRegion;
 /// Initializes the PMM from boot info.
 ///
 /// Converts the bootloader memory map into `PhysMemoryRegion` descriptors
-/// and creates the bitmap allocator.
+/// and creates the buddy allocator.
 pub fn init(boot_info: &impl BootInfo) {
     let hhdm_offset = VirtAddr::new(boot_info.hhdm_offset());
     let memory_map = boot_info.memory_map();
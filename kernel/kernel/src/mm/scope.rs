@@ -23,7 +23,7 @@
 //!   6    AHCI_DISK_INDEX, VIRTIO_DISK_INDEX                   drivers
 //!  10    SCANCODE_BUF, CONSOLE_INPUT_STATE                    input
 //!  11    PLATFORM (ACPI)                                      arch
-//!  12    SLEEP_QUEUE                                          sched
+//!  12    TIMER_QUEUES                                         sched
 //!  13    Executor.ready_queues                                sched
 //!  14    Executor.tasks                                       sched
 //!
@@ -40,17 +40,17 @@
 //! - MMIO mapping: `VMM(2) → PMM(3)`
 //! - Work stealing: `Executor.ready_queues(13) → Executor.tasks(14)`
 
-use super::pmm::BitmapAllocator;
+use super::pmm::BuddyAllocator;
 use super::vmm::KernelVmm;
 
 /// Proof that the PMM lock is held. Only created by [`with_pmm_scope`].
 pub struct PmmScope<'a> {
-    pmm: &'a mut BitmapAllocator,
+    pmm: &'a mut BuddyAllocator,
 }
 
 impl<'a> PmmScope<'a> {
     /// Returns a mutable reference to the physical frame allocator.
-    pub fn allocator(&mut self) -> &mut BitmapAllocator {
+    pub fn allocator(&mut self) -> &mut BuddyAllocator {
         self.pmm
     }
 }
@@ -0,0 +1,35 @@
+//! Slab allocator — kernel glue.
+//!
+//! Re-exports the slab caches from `hadron-mm` and declares the typed caches
+//! for kernel objects whose types live in crates above `hadron-mm`.
+
+pub use hadron_mm::slab::*;
+
+use core::alloc::Layout;
+
+use crate::ipc::channel::ChannelMessage;
+use crate::proc::Process;
+use hadron_sched::executor::TaskEntry;
+
+/// Cache for `Arc<Process>` allocations.
+static PROCESS_CACHE: SlabCache = SlabCache::new("process", arc_layout::<Process>(), None);
+
+/// Cache for boxed executor task entries.
+static TASK_ENTRY_CACHE: SlabCache = SlabCache::new("task_entry", Layout::new::<TaskEntry>(), None);
+
+/// Cache for boxed channel messages.
+static CHANNEL_MESSAGE_CACHE: SlabCache =
+    SlabCache::new("channel_msg", Layout::new::<ChannelMessage>(), None);
+
+/// Registers the kernel's typed caches and enables the slab layer in the
+/// global allocator.
+///
+/// Called from [`super::heap::init`] once the PMM and HHDM are available.
+/// Filesystem drivers register their own inode caches when they are
+/// instantiated.
+pub fn init() {
+    for cache in [&PROCESS_CACHE, &TASK_ENTRY_CACHE, &CHANNEL_MESSAGE_CACHE] {
+        register(cache);
+    }
+    enable();
+}
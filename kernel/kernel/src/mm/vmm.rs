@@ -8,7 +8,9 @@ pub use hadron_mm::vmm::*;
 
 use crate::addr::{PhysAddr, VirtAddr};
 use crate::boot::BootInfo;
-use crate::mm::pmm::BitmapFrameAllocRef;
+use crate::mm::layout;
+use crate::mm::mapper::MapFlags;
+use crate::mm::pmm::BuddyFrameAllocRef;
 use crate::paging::Page;
 use crate::sync::SpinLock;
 
@@ -25,7 +27,9 @@ static VMM: SpinLock<Option<KernelVmm>> = SpinLock::leveled("VMM", 2, None);
 
 /// Initializes the VMM from boot info and the PMM.
 ///
-/// Creates the `Vmm`, maps the initial heap, and stores globally.
+/// Creates the `Vmm` and stores it globally. The heap/stacks/MMIO regions
+/// base is randomized unless the command line contains `nokaslr`, and the
+/// kernel image region follows the base the boot stub slid the image to.
 pub fn init(boot_info: &impl BootInfo) {
     let hhdm_offset = VirtAddr::new(boot_info.hhdm_offset());
     let root_phys = boot_info.page_table_root();
@@ -38,15 +42,48 @@ pub fn init(boot_info: &impl BootInfo) {
         .max()
         .unwrap_or(0);
 
+    let kaslr = !boot_info
+        .command_line()
+        .is_some_and(|c| c.split_whitespace().any(|t| t == "nokaslr"));
+    let regions_base = if kaslr {
+        layout::randomize_regions_base(hhdm_offset, max_phys, crate::arch::boot_entropy())
+    } else {
+        layout::DEFAULT_REGIONS_BASE
+    };
+    let image_base = boot_info.kernel_address().virtual_base;
+    let memory_layout =
+        layout::MemoryLayout::with_regions_base(hhdm_offset, max_phys, regions_base)
+            .with_kernel_image_base(image_base);
+
+    // Report what was actually randomized: with no entropy, or an image
+    // too large to slide, `kaslr` can be set while nothing moved.
+    let slide = image_base.as_u64().wrapping_sub(layout::KERNEL_IMAGE_BASE);
+    if slide == 0 && regions_base == layout::DEFAULT_REGIONS_BASE {
+        let reason = if kaslr {
+            "nothing to randomize"
+        } else {
+            "nokaslr"
+        };
+        crate::kinfo!("KASLR: disabled ({})", reason);
+    } else {
+        crate::kinfo!(
+            "KASLR: regions base {:#x}, kernel image {:#x} (slide {:#x})",
+            regions_base,
+            image_base.as_u64(),
+            slide
+        );
+    }
+
     let mapper = KernelMapper::new(hhdm_offset);
-    let vmm = KernelVmm::new(root_phys, mapper, hhdm_offset, max_phys);
+    let vmm = KernelVmm::with_layout(root_phys, mapper, memory_layout);
 
     let mut global = VMM.lock();
     assert!(global.is_none(), "VMM already initialized");
     *global = Some(vmm);
 }
 
-/// Maps the initial heap pages via the VMM and PMM.
+/// Maps the initial heap pages via the VMM and PMM, using 2 MiB pages
+/// where possible.
 ///
 /// Returns `(heap_start, heap_size)`.
 pub fn map_initial_heap() -> (usize, usize) {
@@ -54,10 +91,13 @@ pub fn map_initial_heap() -> (usize, usize) {
     let vmm = vmm.as_mut().expect("VMM not initialized");
 
     let result = super::pmm::with(|pmm| {
-        let mut alloc = BitmapFrameAllocRef(pmm);
+        let mut alloc = BuddyFrameAllocRef(pmm);
         let (base, size) = vmm
-            .map_initial_heap(&mut alloc)
+            .grow_heap_huge(layout::INITIAL_HEAP_SIZE, &mut alloc)
             .expect("failed to map initial heap");
+        #[cfg(hadron_kasan)]
+        super::kasan::map_heap_shadow(vmm, &mut alloc, base, size)
+            .expect("failed to map initial heap shadow");
         (base.as_u64() as usize, size as usize)
     });
     // Log after releasing PMM lock to avoid PMM → LOGGER ordering violation.
@@ -78,8 +118,10 @@ pub fn grow_heap(min_bytes: usize) -> Option<(*mut u8, usize)> {
     let vmm = vmm.as_mut()?;
 
     let result = super::pmm::with(|pmm| {
-        let mut alloc = BitmapFrameAllocRef(pmm);
-        let (base, size) = vmm.grow_heap(min_bytes as u64, &mut alloc).ok()?;
+        let mut alloc = BuddyFrameAllocRef(pmm);
+        let (base, size) = vmm.grow_heap_huge(min_bytes as u64, &mut alloc).ok()?;
+        #[cfg(hadron_kasan)]
+        super::kasan::map_heap_shadow(vmm, &mut alloc, base, size).ok()?;
         Some((base.as_mut_ptr::<u8>(), size as usize))
     });
     // Log after releasing PMM lock to avoid PMM → LOGGER ordering violation.
@@ -89,6 +131,46 @@ pub fn grow_heap(min_bytes: usize) -> Option<(*mut u8, usize)> {
     result
 }
 
+unsafe extern "C" {
+    static __ro_after_init_start: u8;
+    static __ro_after_init_end: u8;
+}
+
+/// Returns the page-aligned bounds of the `.data.ro_after_init` section
+/// (see [`hadron_core::ro_after_init!`]).
+pub fn ro_after_init_range() -> (VirtAddr, VirtAddr) {
+    // SAFETY: Linker-defined symbols; only their addresses are taken.
+    unsafe {
+        (
+            VirtAddr::new(core::ptr::addr_of!(__ro_after_init_start) as u64),
+            VirtAddr::new(core::ptr::addr_of!(__ro_after_init_end) as u64),
+        )
+    }
+}
+
+/// Remaps the `.data.ro_after_init` section read-only.
+///
+/// Called once at the end of boot init, after the IDT is built and the
+/// alt-fn dispatch pointers are patched. The HHDM alias of these pages
+/// stays writable. Other CPUs only drop their cached writable translations
+/// as they are evicted from the TLB: there are no cross-CPU shootdowns yet.
+pub fn protect_ro_after_init() {
+    let (start, end) = ro_after_init_range();
+    let page_size = super::PAGE_SIZE as u64;
+    with(|vmm| {
+        let mut virt = start;
+        while virt < end {
+            vmm.protect_page(Page::containing_address(virt), MapFlags::GLOBAL)
+                .expect("ro_after_init page not mapped with 4 KiB pages");
+            virt = virt + page_size;
+        }
+    });
+    crate::kinfo!(
+        "VMM: {} KiB of ro_after_init data now read-only",
+        (end - start) / 1024
+    );
+}
+
 /// Executes a closure with a mutable reference to the global VMM.
 pub fn with<R>(f: impl FnOnce(&mut KernelVmm) -> R) -> R {
     let mut vmm = VMM.lock();
@@ -111,7 +193,7 @@ pub fn map_mmio_region(phys: PhysAddr, size: u64) -> MmioMapping {
 pub fn map_mmio_region_with_cache(phys: PhysAddr, size: u64, cache: CacheMode) -> MmioMapping {
     let mapping = with(|vmm| {
         super::pmm::with(|pmm| {
-            let mut alloc = BitmapFrameAllocRef(pmm);
+            let mut alloc = BuddyFrameAllocRef(pmm);
             vmm.map_mmio_with_cache(phys, size, cache, &mut alloc, Some(default_mmio_cleanup))
                 .expect("failed to map MMIO region")
         })
@@ -52,11 +52,18 @@ static mut SYSCALL_STACK: AlignedStack = AlignedStack([0; EARLY_SYSCALL_STACK_SI
 /// - offset 48: `trap_reason_ptr`
 /// - offset 56: `saved_regs_ptr`
 /// - offset 64: `user_fpu_context_ptr`
+/// - offset 72: `kpti_kernel_cr3`
+/// - offset 80: `kpti_user_cr3`
+/// - offset 88: `kpti_scratch`
+/// - offset 96: `entry_stack_top`
+///
+/// The struct is page-aligned so that kernel page-table isolation can map
+/// it into the shadow page tables without exposing neighbouring data.
 ///
 /// Each CPU's GS base points to its own `PerCpu` instance.
 /// `PerCpuState::current()` reads `GS:[0]` to get the self-pointer,
 /// avoiding global statics.
-#[repr(C)]
+#[repr(C, align(4096))]
 pub struct PerCpu {
     /// Self-pointer for `GS:[0]` access pattern (offset 0).
     ///
@@ -98,6 +105,21 @@ pub struct PerCpu {
     /// Used by the timer preemption stub via `GS:[64]` to save/restore
     /// user FPU state with `fxsave64`/`fxrstor64`.
     pub user_fpu_context_ptr: u64,
+    /// CR3 value loaded on kernel entry from ring 3 (offset 72).
+    ///
+    /// Only used with kernel page-table isolation: the current process's
+    /// full page table root, with the PCID no-flush bit when PCIDs are on.
+    pub kpti_kernel_cr3: u64,
+    /// CR3 value loaded on return to ring 3 (offset 80).
+    ///
+    /// Only used with kernel page-table isolation: the current process's
+    /// shadow page table root, tagged with the user PCID when PCIDs are on.
+    pub kpti_user_cr3: u64,
+    /// Scratch slot for the isolation entry/exit sequences (offset 88).
+    pub kpti_scratch: u64,
+    /// Top of this CPU's entry stack (offset 96), the TSS RSP0 under
+    /// kernel page-table isolation.
+    pub entry_stack_top: u64,
 }
 
 impl PerCpu {
@@ -115,6 +137,10 @@ impl PerCpu {
             trap_reason_ptr: 0,
             saved_regs_ptr: 0,
             user_fpu_context_ptr: 0,
+            kpti_kernel_cr3: 0,
+            kpti_user_cr3: 0,
+            kpti_scratch: 0,
+            entry_stack_top: 0,
         }
     }
 
@@ -0,0 +1,112 @@
+//! Per-thread user and system time accounting.
+//!
+//! Each CPU keeps a TSC stamp of the last accounting point and whether the
+//! thread it runs is inside a syscall. Time between two points is charged
+//! to the running thread as user time or system time accordingly:
+//!
+//! - [`enter_user`] stamps just before a process task enters userspace.
+//! - [`syscall_entry`] charges user time and switches to system time.
+//! - [`syscall_exit`] charges system time and switches back to user time.
+//! - [`trap_return`] charges the interval that ended with a return to the
+//!   process task: user time after a preemption or fault, system time when
+//!   a syscall blocked, exec'd or exited.
+//!
+//! Time a blocked thread spends waiting in its process task is not charged
+//! to anyone.
+
+use hadron_core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use super::Process;
+use crate::arch::x86_64::hw::tsc::read_tsc;
+use crate::cputime::CpuTimes;
+use crate::percpu::{CpuLocal, MAX_CPUS};
+use crate::time::Time;
+
+/// TSC value at the last accounting point on this CPU.
+static STAMP: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
+
+/// Whether the thread on this CPU is inside a syscall.
+static IN_SYSCALL: CpuLocal<AtomicBool> =
+    CpuLocal::new([const { AtomicBool::new(false) }; MAX_CPUS]);
+
+/// Returns the nanoseconds since the last accounting point and moves the
+/// stamp to now.
+fn advance() -> u64 {
+    let now = read_tsc();
+    let then = STAMP.get().swap(now, Ordering::Relaxed);
+    Time::tsc_cycles_to_nanos(now.saturating_sub(then))
+}
+
+/// Charges `ns` to `process` as user or system time and fires the CPU-time
+/// timers that expire with it.
+fn charge(process: &Process, ns: u64, system: bool) {
+    if system {
+        process.cpu_time.add_system(ns);
+        process.group_cpu_time.threads.add_system(ns);
+    } else {
+        process.cpu_time.add_user(ns);
+        process.group_cpu_time.threads.add_user(ns);
+    }
+    process.timers.check_cpu(process);
+}
+
+/// Starts a user-mode interval. Called right before entering userspace.
+pub(super) fn enter_user() {
+    STAMP.get().store(read_tsc(), Ordering::Relaxed);
+    IN_SYSCALL.get().store(false, Ordering::Relaxed);
+}
+
+/// Ends a user-mode interval at the start of a syscall.
+pub(crate) fn syscall_entry(process: &Process) {
+    charge(process, advance(), false);
+    IN_SYSCALL.get().store(true, Ordering::Relaxed);
+}
+
+/// Ends a syscall that returns to userspace normally.
+pub(crate) fn syscall_exit(process: &Process) {
+    charge(process, advance(), true);
+    IN_SYSCALL.get().store(false, Ordering::Relaxed);
+}
+
+/// Ends the interval that was running when control returned to the
+/// process task.
+pub(super) fn trap_return(process: &Process) {
+    let system = IN_SYSCALL.get().swap(false, Ordering::Relaxed);
+    charge(process, advance(), system);
+}
+
+impl Process {
+    /// Returns the CPU time of this thread alone.
+    pub fn thread_cpu_times(&self) -> CpuTimes {
+        self.cpu_time.load()
+    }
+
+    /// Returns the CPU time of every thread of this process.
+    pub fn process_cpu_times(&self) -> CpuTimes {
+        self.group_cpu_time.threads.load()
+    }
+
+    /// Returns the CPU time of this process's reaped children.
+    pub fn children_cpu_times(&self) -> CpuTimes {
+        self.group_cpu_time.children.load()
+    }
+
+    /// Returns the CPU time `wait4` and `waitid` report for this process:
+    /// its own time plus that of the children it reaped.
+    pub(crate) fn wait_cpu_times(&self) -> CpuTimes {
+        self.group_cpu_time.reaped_total()
+    }
+
+    /// Folds a reaped child's time into this process's children time.
+    ///
+    /// Threads share their creator's time already, so reaping one adds
+    /// nothing.
+    pub(crate) fn add_reaped_child(&self, child: &Process) {
+        if self.same_thread_group(child) {
+            return;
+        }
+        self.group_cpu_time
+            .children
+            .add(child.group_cpu_time.reaped_total());
+    }
+}
@@ -1,8 +1,10 @@
 //! ELF binary format handler.
 //!
-//! Supports `ET_EXEC` (fixed-address) and `ET_DYN` (static-PIE, relocated to
-//! [`USER_PIE_BASE`]). `ET_REL` is rejected here — relocatable objects are
-//! loaded via a separate module loader path.
+//! Supports `ET_EXEC` (fixed-address) and `ET_DYN` (PIE, placed at the load
+//! base chosen by the caller). Static-PIE images are relocated by the kernel;
+//! images with a `PT_INTERP` leave relocation to their interpreter. `ET_REL`
+//! is rejected here — relocatable objects are loaded via a separate module
+//! loader path.
 
 use hadron_elf::ElfType;
 use planck_noalloc::vec::ArrayVec;
@@ -14,9 +16,6 @@ const PF_X: u32 = 1;
 /// ELF segment permission flags.
 const PF_W: u32 = 2;
 
-/// Default base address for PIE (`ET_DYN`) user binaries.
-const USER_PIE_BASE: u64 = 0x40_0000;
-
 /// Singleton handler for ELF64 binaries.
 pub struct ElfHandler;
 
@@ -60,33 +59,43 @@ fn collect_segments<'a>(
     Ok(segments)
 }
 
-/// Load an `ET_EXEC` binary. Segments map at their stated vaddrs.
-fn load_exec<'a>(elf: &hadron_elf::ElfFile<'a>) -> Result<ExecImage<'a>, BinaryError> {
-    let segments = collect_segments(elf, 0)?;
+/// Build an [`ExecImage`] whose segments and program headers are offset by
+/// `base_addr`.
+fn exec_image<'a>(
+    elf: &hadron_elf::ElfFile<'a>,
+    base_addr: u64,
+    elf_data: Option<&'a [u8]>,
+) -> Result<ExecImage<'a>, BinaryError> {
+    let segments = collect_segments(elf, base_addr)?;
+    let header = elf.header();
     Ok(ExecImage {
-        entry_point: elf.entry_point(),
-        base_addr: 0,
-        needs_relocation: false,
-        elf_data: None,
+        entry_point: base_addr + elf.entry_point(),
+        base_addr,
+        needs_relocation: elf_data.is_some(),
+        elf_data,
+        interp: elf.interpreter(),
+        phdr_addr: elf.phdr_vaddr().map_or(0, |vaddr| base_addr + vaddr),
+        phent: header.e_phentsize,
+        phnum: header.e_phnum,
         segments,
     })
 }
 
-/// Load an `ET_DYN` (static-PIE) binary. Segments are relocated to
-/// [`USER_PIE_BASE`] and the image is marked for relocation application.
+/// Load an `ET_EXEC` binary. Segments map at their stated vaddrs.
+fn load_exec<'a>(elf: &hadron_elf::ElfFile<'a>) -> Result<ExecImage<'a>, BinaryError> {
+    exec_image(elf, 0, None)
+}
+
+/// Load an `ET_DYN` binary. Segments are placed at `base`; a static-PIE
+/// image is marked for relocation application, while a dynamically linked
+/// one is left to its interpreter.
 fn load_dyn<'a>(
     elf: &hadron_elf::ElfFile<'a>,
     data: &'a [u8],
+    base: u64,
 ) -> Result<ExecImage<'a>, BinaryError> {
-    let base = USER_PIE_BASE;
-    let segments = collect_segments(elf, base)?;
-    Ok(ExecImage {
-        entry_point: base + elf.entry_point(),
-        base_addr: base,
-        needs_relocation: true,
-        elf_data: Some(data),
-        segments,
-    })
+    let elf_data = elf.interpreter().is_none().then_some(data);
+    exec_image(elf, base, elf_data)
 }
 
 impl BinaryFormat for ElfHandler {
@@ -98,12 +107,12 @@ impl BinaryFormat for ElfHandler {
         data.len() >= 4 && data[..4] == [0x7f, b'E', b'L', b'F']
     }
 
-    fn load<'a>(&self, data: &'a [u8]) -> Result<ExecImage<'a>, BinaryError> {
+    fn load<'a>(&self, data: &'a [u8], load_base: u64) -> Result<ExecImage<'a>, BinaryError> {
         let elf = hadron_elf::ElfFile::parse(data).map_err(map_elf_error)?;
 
         match elf.elf_type() {
             ElfType::Exec => load_exec(&elf),
-            ElfType::Dyn => load_dyn(&elf, data),
+            ElfType::Dyn => load_dyn(&elf, data, load_base),
             ElfType::Rel => Err(BinaryError::Unimplemented(
                 "ET_REL via BinaryFormat; use ModuleLoader",
             )),
@@ -47,6 +47,17 @@ pub struct ExecImage<'a> {
     pub needs_relocation: bool,
     /// Raw ELF data for the relocation pass (only set when `needs_relocation` is true).
     pub elf_data: Option<&'a [u8]>,
+    /// Path of the program interpreter (`PT_INTERP`) that must be loaded
+    /// alongside the image, if any. Such images are relocated by the
+    /// interpreter, not the kernel.
+    pub interp: Option<&'a [u8]>,
+    /// Address of the program header table in the loaded image, or 0 if it
+    /// is not mapped. Passed to the interpreter as `AT_PHDR`.
+    pub phdr_addr: u64,
+    /// Size of one program header entry (`AT_PHENT`).
+    pub phent: u16,
+    /// Number of program header entries (`AT_PHNUM`).
+    pub phnum: u16,
     /// Loadable segments.
     segments: ArrayVec<ExecSegment<'a>, MAX_SEGMENTS>,
 }
@@ -57,6 +68,17 @@ impl<'a> ExecImage<'a> {
     pub fn segments(&self) -> &[ExecSegment<'a>] {
         self.segments.as_slice()
     }
+
+    /// Returns the end address (exclusive) of the highest loadable segment,
+    /// or 0 if the image has no segments.
+    #[must_use]
+    pub fn end(&self) -> u64 {
+        self.segments()
+            .iter()
+            .map(|seg| seg.vaddr + seg.memsz)
+            .max()
+            .unwrap_or(0)
+    }
 }
 
 /// Errors that can occur while loading a binary.
@@ -72,6 +94,12 @@ pub enum BinaryError {
     Unimplemented(&'static str),
     /// A relocation failed to apply.
     RelocError(hadron_elf::RelocError),
+    /// Physical memory was exhausted while mapping the image.
+    OutOfMemory,
+    /// The caller may not execute the file.
+    PermissionDenied,
+    /// The caller may not take on the requested identity.
+    NotPermitted,
 }
 
 impl fmt::Display for BinaryError {
@@ -82,6 +110,9 @@ impl fmt::Display for BinaryError {
             BinaryError::TooManySegments => write!(f, "too many loadable segments"),
             BinaryError::Unimplemented(what) => write!(f, "unimplemented format: {what}"),
             BinaryError::RelocError(e) => write!(f, "relocation error: {e}"),
+            BinaryError::OutOfMemory => write!(f, "out of memory"),
+            BinaryError::PermissionDenied => write!(f, "permission denied"),
+            BinaryError::NotPermitted => write!(f, "operation not permitted"),
         }
     }
 }
@@ -100,25 +131,29 @@ pub trait BinaryFormat: Sync {
     /// Parses `data` and returns an [`ExecImage`] with zero-copy segment
     /// references into the input slice.
     ///
+    /// Position-independent images are placed at `load_base`; fixed-address
+    /// images ignore it.
+    ///
     /// # Errors
     ///
     /// Returns [`BinaryError`] if parsing fails.
-    fn load<'a>(&self, data: &'a [u8]) -> Result<ExecImage<'a>, BinaryError>;
+    fn load<'a>(&self, data: &'a [u8], load_base: u64) -> Result<ExecImage<'a>, BinaryError>;
 }
 
 /// Registered binary format handlers, tried in order.
 static BINARY_FORMATS: &[&dyn BinaryFormat] = &[&elf::ElfHandler, &script::ScriptHandler];
 
-/// Probes `data` against all registered formats and loads the first match.
+/// Probes `data` against all registered formats and loads the first match,
+/// placing position-independent images at `load_base`.
 ///
 /// # Errors
 ///
 /// Returns [`BinaryError::UnrecognizedFormat`] if no handler matches, or
 /// a handler-specific error if parsing fails.
-pub fn load_binary(data: &[u8]) -> Result<ExecImage<'_>, BinaryError> {
+pub fn load_binary(data: &[u8], load_base: u64) -> Result<ExecImage<'_>, BinaryError> {
     for handler in BINARY_FORMATS {
         if handler.probe(data) {
-            return handler.load(data);
+            return handler.load(data, load_base);
         }
     }
     Err(BinaryError::UnrecognizedFormat)
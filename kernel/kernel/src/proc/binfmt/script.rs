@@ -14,7 +14,7 @@ impl BinaryFormat for ScriptHandler {
         data.len() >= 2 && data[..2] == *b"#!"
     }
 
-    fn load<'a>(&self, _data: &'a [u8]) -> Result<ExecImage<'a>, BinaryError> {
+    fn load<'a>(&self, _data: &'a [u8], _load_base: u64) -> Result<ExecImage<'a>, BinaryError> {
         Err(BinaryError::Unimplemented("script/shebang (#!)"))
     }
 }
@@ -3,20 +3,38 @@
 //! Parses a binary via the [`binfmt`](super::binfmt) registry, maps its
 //! segments into a fresh user address space, sets up a user stack, and
 //! returns a [`Process`] ready to run.
+//!
+//! Dynamically linked images name a program interpreter (`PT_INTERP`, the
+//! userspace dynamic linker). It is loaded next to the image and entered
+//! first; the auxiliary vector on the initial stack tells it where the
+//! image's program headers and entry point are.
+//!
+//! Every exec draws a fresh [`UserLayout`] (user ASLR): the stack top, the
+//! `ET_DYN` and interpreter load bases, the mmap region base, and the
+//! initial program break are randomized unless `norandmaps` is on the
+//! kernel command line. Only the top
+//! [`USER_STACK_INITIAL_SIZE`](crate::mm::user_layout::USER_STACK_INITIAL_SIZE)
+//! of the main stack is mapped up front; [`grow_user_stack`] maps the rest
+//! on demand from the page fault handler.
 
 use crate::addr::VirtAddr;
-use crate::id::Pid;
+use crate::cred::{Access, Credentials};
+use crate::fs::{Inode, InodeType, Ownership};
+use crate::id::{Gid, Pid, Uid};
 use crate::mm::PAGE_SIZE;
 use crate::mm::address_space::AddressSpace;
 use crate::mm::mapper::{MapFlags, PageMapper, PageTranslator};
-use crate::mm::pmm::BitmapFrameAllocRef;
+use crate::mm::pmm::BuddyFrameAllocRef;
+use crate::mm::user_layout::{UserLayout, UserStack};
 use crate::paging::{Page, PhysFrame, Size4KiB};
+use crate::syscall::userptr::{UserPtr, UserSlice, read_user_array};
 use crate::{kdebug, kinfo};
 
 extern crate alloc;
 
 use alloc::string::String;
 use alloc::sync::Arc;
+use hadron_core::sync::atomic::{AtomicBool, Ordering};
 
 use super::Process;
 
@@ -28,15 +46,66 @@ pub struct SpawnOptions<'a> {
     /// Working directory for the child process.
     /// If `None`, the child inherits the parent's CWD.
     pub cwd: Option<String>,
+    /// User ID the child runs as. If `None`, the child inherits the
+    /// parent's user IDs; otherwise the parent must be privileged.
+    pub uid: Option<Uid>,
+    /// Group ID the child runs as, with no supplementary groups. If
+    /// `None`, the child inherits the parent's group IDs and groups;
+    /// otherwise the parent must be privileged.
+    pub gid: Option<Gid>,
+}
+use super::binfmt::{self, BinaryError, ExecImage, ExecSegment};
+
+#[cfg(target_arch = "x86_64")]
+type KernelMapper = crate::arch::x86_64::paging::PageTableMapper;
+
+/// Whether exec randomizes the user layout. Cleared by `norandmaps`.
+static USER_ASLR: AtomicBool = AtomicBool::new(true);
+
+/// Disable user ASLR — called from boot.rs when `norandmaps` is on the
+/// cmdline. Every subsequent exec uses [`UserLayout::FIXED`].
+pub fn disable_user_aslr() {
+    USER_ASLR.store(false, Ordering::Release);
+}
+
+/// Picks the user layout for a new image.
+fn choose_layout() -> UserLayout {
+    if USER_ASLR.load(Ordering::Acquire) {
+        UserLayout::randomized(crate::arch::random_u64())
+    } else {
+        UserLayout::FIXED
+    }
 }
-use super::binfmt::{self, BinaryError, ExecSegment};
 
-/// User stack top address. Placed just below the non-canonical hole.
-/// Stack grows downward from here.
-const USER_STACK_TOP: u64 = 0x7FFF_FFFF_F000;
+/// A binary mapped into a fresh address space by [`load_image`].
+struct LoadedImage {
+    /// User address space holding the image, stack, and trampoline.
+    address_space: AddressSpace<KernelMapper>,
+    /// Address user code starts at: the interpreter's entry point if the
+    /// image has one, otherwise the image's.
+    entry: u64,
+    /// Layout the image was loaded with.
+    layout: UserLayout,
+    /// Initial program break, just above the image.
+    brk_start: u64,
+    /// Values for the auxiliary vector.
+    aux: AuxInfo,
+}
 
-/// User stack size: 64 KiB (16 pages) for MVP.
-const USER_STACK_SIZE: u64 = 64 * 1024;
+/// Facts about a loaded image that the new program receives in its
+/// auxiliary vector.
+struct AuxInfo {
+    /// Address of the image's program headers (`AT_PHDR`), or 0.
+    phdr: u64,
+    /// Size of one program header (`AT_PHENT`).
+    phent: u16,
+    /// Number of program headers (`AT_PHNUM`).
+    phnum: u16,
+    /// Entry point of the image itself (`AT_ENTRY`).
+    entry: u64,
+    /// Load base of the interpreter (`AT_BASE`), or 0 without one.
+    interp_base: u64,
+}
 
 /// Syscall number for `task_sigreturn`, used in the trampoline stub.
 const SYS_TASK_SIGRETURN_NR: u64 = {
@@ -49,8 +118,8 @@ const SYS_TASK_SIGRETURN_NR: u64 = {
 /// Called by `AddressSpace::Drop` to free the PML4 frame.
 fn dealloc_frame(frame: PhysFrame<Size4KiB>) {
     crate::mm::pmm::with(|pmm| {
-        let mut dealloc = BitmapFrameAllocRef(pmm);
-        // SAFETY: The frame was allocated by BitmapFrameAllocRef and is no
+        let mut dealloc = BuddyFrameAllocRef(pmm);
+        // SAFETY: The frame was allocated by BuddyFrameAllocRef and is no
         // longer referenced by any page table (the address space is being dropped).
         unsafe {
             crate::mm::FrameDeallocator::deallocate_frame(&mut dealloc, frame);
@@ -58,29 +127,67 @@ fn dealloc_frame(frame: PhysFrame<Size4KiB>) {
     });
 }
 
-/// Loads a binary into a new user address space and returns the
-/// process, entry point, and user stack top.
+/// Loads a binary into a new user address space, writes `args`, `envs`,
+/// and the auxiliary vector onto its stack, and returns the process, entry
+/// point, and initial user stack pointer.
 ///
-/// The caller is responsible for entering userspace via the executor.
+/// `cred` is the identity the process will run with; it is checked against
+/// the program interpreter, if any, and reported in the auxiliary vector.
+/// The caller is responsible for installing it and for entering userspace
+/// via the executor.
 ///
 /// # Errors
 ///
-/// Returns [`BinaryError`] if format detection, parsing, or relocation fails.
-///
-/// # Panics
-///
-/// Panics if the user address space cannot be created or if physical memory
-/// is exhausted while mapping segments or stack.
+/// Returns [`BinaryError`] if format detection, parsing, or relocation fails,
+/// or [`BinaryError::OutOfMemory`] if physical memory is exhausted while
+/// mapping segments or stack.
 pub fn create_process_from_binary(
     data: &[u8],
     parent_pid: Option<Pid>,
+    args: &[&str],
+    envs: &[&str],
+    cred: &Credentials,
 ) -> Result<(Process, u64, u64), BinaryError> {
-    #[cfg(target_arch = "x86_64")]
-    type KernelMapper = crate::arch::x86_64::paging::PageTableMapper;
+    let loaded = load_image(data, cred)?;
+    kinfo!("Loading process (entry={:#x})...", loaded.entry);
 
-    let image = binfmt::load_binary(data)?;
-    let entry = image.entry_point;
-    kinfo!("Loading process (entry={:#x})...", entry);
+    let hhdm_offset = crate::mm::hhdm::offset();
+    let stack_ptr = write_startup_data(
+        &loaded.address_space,
+        loaded.layout.stack_top,
+        args,
+        envs,
+        &loaded.aux,
+        cred,
+        hhdm_offset,
+    )?;
+
+    // Wrap in Process (takes ownership of address space).
+    let process = Process::new(loaded.address_space, parent_pid);
+    process.set_user_layout(&loaded.layout, loaded.brk_start);
+
+    Ok((process, loaded.entry, stack_ptr))
+}
+
+/// Picks a user layout, parses `data`, and maps the image, its program
+/// interpreter (if any), the stack, and the sigreturn trampoline into a new
+/// user address space.
+fn load_image(data: &[u8], cred: &Credentials) -> Result<LoadedImage, BinaryError> {
+    let layout = choose_layout();
+    let image = binfmt::load_binary(data, layout.pie_base)?;
+
+    let interp_data = match image.interp {
+        Some(path) => Some(read_interpreter(path, cred)?),
+        None => None,
+    };
+    let interp = match &interp_data {
+        Some(interp_data) => Some(load_interpreter(interp_data, layout.interp_base)?),
+        None => None,
+    };
+    let images: &[&ExecImage<'_>] = match &interp {
+        Some(interp) => &[&image, interp],
+        None => &[&image],
+    };
 
     // Use the saved kernel CR3 — not Cr3::read() — because this function may
     // be called from a syscall handler where CR3 is the calling process's
@@ -89,49 +196,186 @@ pub fn create_process_from_binary(
     let hhdm_offset = crate::mm::hhdm::offset();
     let mapper = KernelMapper::new(hhdm_offset);
 
-    // Create address space and map segments + stack inside PMM lock scope.
-    let process = crate::mm::pmm::with(|pmm| -> Result<Process, BinaryError> {
-        let mut alloc = BitmapFrameAllocRef(pmm);
+    let address_space = create_user_address_space(kernel_cr3, mapper, hhdm_offset)?;
+    crate::mm::pmm::with(|pmm| map_image(&address_space, images, &layout, hhdm_offset, pmm))?;
 
-        // Create a new user address space (copies kernel upper half).
-        // SAFETY: kernel_cr3 is the current (valid) PML4 read from CR3.
-        // The mapper and allocator are correctly configured for the current
-        // architecture. The allocator returns zeroed 4 KiB frames.
-        let address_space = unsafe {
-            AddressSpace::new_user(kernel_cr3, mapper, hhdm_offset, &mut alloc, dealloc_frame)
-                .expect("failed to create user address space")
-        };
+    kdebug!(
+        "  User layout: stack top {:#x}, mmap base {:#x}, image base {:#x}, interpreter base {:#x}",
+        layout.stack_top,
+        layout.mmap_base,
+        image.base_addr,
+        interp.as_ref().map_or(0, |interp| interp.base_addr)
+    );
 
-        // Map binary segments.
-        for seg in image.segments() {
-            map_segment(&address_space, seg, hhdm_offset, &mut alloc);
-        }
+    Ok(LoadedImage {
+        address_space,
+        entry: interp
+            .as_ref()
+            .map_or(image.entry_point, |interp| interp.entry_point),
+        brk_start: layout.brk_start(image.end()),
+        layout,
+        aux: AuxInfo {
+            phdr: image.phdr_addr,
+            phent: image.phent,
+            phnum: image.phnum,
+            entry: image.entry_point,
+            interp_base: interp.as_ref().map_or(0, |interp| interp.base_addr),
+        },
+    })
+}
 
-        // Apply relocations for PIE binaries (ET_DYN).
-        if image.needs_relocation {
-            if let Some(elf_data) = image.elf_data {
-                let elf = hadron_elf::ElfFile::parse(elf_data)
-                    .expect("ELF already validated during load");
-                binfmt::reloc::apply_dyn_relocations(
-                    &address_space,
-                    &elf,
-                    image.base_addr,
+/// Reads the program interpreter named by an image's `PT_INTERP` from the
+/// VFS, checking that `cred` may execute it.
+fn read_interpreter(path: &[u8], cred: &Credentials) -> Result<alloc::vec::Vec<u8>, BinaryError> {
+    let path = core::str::from_utf8(path)
+        .map_err(|_| BinaryError::ParseError("interpreter path is not UTF-8"))?;
+    let inode = crate::fs::vfs::with_vfs(|vfs| vfs.resolve(path)).map_err(|e| {
+        crate::kwarn!("exec: interpreter '{}' not found: {:?}", path, e);
+        BinaryError::ParseError("interpreter not found")
+    })?;
+    check_exec_access(cred, &*inode)?;
+
+    let mut buf = alloc::vec![0u8; inode.size()];
+    let bytes_read = crate::fs::poll_immediate(inode.read(0, &mut buf))
+        .map_err(|_| BinaryError::ParseError("failed to read interpreter"))?;
+    buf.truncate(bytes_read);
+    Ok(buf)
+}
+
+/// Parses a program interpreter and places it at `base`.
+///
+/// The interpreter must be a self-contained static-PIE image: the kernel
+/// relocates it, and it may not request an interpreter of its own.
+fn load_interpreter(data: &[u8], base: u64) -> Result<ExecImage<'_>, BinaryError> {
+    let interp = binfmt::load_binary(data, base)?;
+    if !interp.needs_relocation || interp.interp.is_some() {
+        return Err(BinaryError::ParseError(
+            "interpreter must be a static-PIE ELF image",
+        ));
+    }
+    Ok(interp)
+}
+
+/// Creates an empty user address space, with a KPTI shadow PML4 when
+/// isolation is enabled.
+///
+/// The PMM lock is only held for the allocation itself, so that the
+/// address space can later be dropped (which re-enters the PMM) if
+/// mapping the image fails.
+fn create_user_address_space<M: PageMapper<Size4KiB> + PageTranslator>(
+    kernel_cr3: crate::addr::PhysAddr,
+    mapper: M,
+    hhdm_offset: VirtAddr,
+) -> Result<AddressSpace<M>, BinaryError> {
+    crate::mm::pmm::with(|pmm| {
+        let mut alloc = BuddyFrameAllocRef(pmm);
+        #[cfg(hadron_kpti)]
+        if let Some(template) = crate::arch::x86_64::kpti::shadow_template() {
+            // SAFETY: As for `new_user` below; the shadow template's kernel
+            // half is never torn down.
+            return unsafe {
+                AddressSpace::new_user_isolated(
+                    kernel_cr3,
+                    template,
+                    mapper,
                     hhdm_offset,
-                )?;
+                    &mut alloc,
+                    dealloc_frame,
+                )
+            };
+        }
+        // SAFETY: kernel_cr3 is the saved kernel PML4. The mapper and
+        // allocator are correctly configured for the current architecture.
+        // The allocator returns zeroed 4 KiB frames.
+        unsafe {
+            AddressSpace::new_user(kernel_cr3, mapper, hhdm_offset, &mut alloc, dealloc_frame)
+        }
+    })
+    .map_err(|_| BinaryError::OutOfMemory)
+}
+
+/// Maps the segments of `images` (the program and its interpreter), user
+/// stack, and sigreturn trampoline into `address_space` and applies
+/// relocations.
+///
+/// On failure, every frame mapped so far is unmapped and returned to the
+/// PMM before the error is propagated.
+fn map_image<M: PageMapper<Size4KiB> + PageTranslator>(
+    address_space: &AddressSpace<M>,
+    images: &[&ExecImage<'_>],
+    layout: &UserLayout,
+    hhdm_offset: VirtAddr,
+    pmm: &mut crate::mm::pmm::BuddyAllocator,
+) -> Result<(), BinaryError> {
+    let result = (|| {
+        let mut alloc = BuddyFrameAllocRef(&mut *pmm);
+
+        for image in images {
+            // Map binary segments.
+            for seg in image.segments() {
+                map_segment(address_space, seg, hhdm_offset, &mut alloc)?;
+            }
+
+            // Apply relocations for static-PIE binaries (ET_DYN).
+            if image.needs_relocation {
+                if let Some(elf_data) = image.elf_data {
+                    let elf = hadron_elf::ElfFile::parse(elf_data)
+                        .expect("ELF already validated during load");
+                    binfmt::reloc::apply_dyn_relocations(
+                        address_space,
+                        &elf,
+                        image.base_addr,
+                        hhdm_offset,
+                    )?;
+                }
             }
         }
 
         // Map user stack.
-        map_user_stack(&address_space, &mut alloc);
+        map_user_stack(address_space, &layout.initial_stack(), &mut alloc)?;
 
         // Map signal return trampoline page.
-        map_sigreturn_trampoline(&address_space, hhdm_offset, &mut alloc);
+        map_sigreturn_trampoline(address_space, hhdm_offset, &mut alloc)
+    })();
 
-        // Wrap in Process (takes ownership of address space).
-        Ok(Process::new(address_space, parent_pid))
-    })?;
+    if result.is_err() {
+        unmap_image(address_space, images, layout, pmm);
+    }
+    result
+}
 
-    Ok((process, entry, USER_STACK_TOP))
+/// Unmaps and frees every page that [`map_image`] may have mapped.
+///
+/// Pages that were never mapped are skipped.
+fn unmap_image<M: PageMapper<Size4KiB> + PageTranslator>(
+    address_space: &AddressSpace<M>,
+    images: &[&ExecImage<'_>],
+    layout: &UserLayout,
+    pmm: &mut crate::mm::pmm::BuddyAllocator,
+) {
+    let page_mask = PAGE_SIZE as u64 - 1;
+    let mut release = |start: u64, end: u64| {
+        let mut vaddr = start & !page_mask;
+        while vaddr < end {
+            let page = Page::containing_address(VirtAddr::new(vaddr));
+            if let Ok(frame) = address_space.unmap_user_page(page) {
+                // SAFETY: The frame was allocated by map_image and is no
+                // longer referenced by any page table entry.
+                let _ = unsafe { pmm.deallocate_frame(frame) };
+            }
+            vaddr += PAGE_SIZE as u64;
+        }
+    };
+
+    for seg in images.iter().flat_map(|image| image.segments()) {
+        release(seg.vaddr, seg.vaddr + seg.memsz);
+    }
+    let stack = layout.initial_stack();
+    release(stack.bottom, stack.top);
+    release(
+        super::SIGRETURN_TRAMPOLINE_ADDR,
+        super::SIGRETURN_TRAMPOLINE_ADDR + PAGE_SIZE as u64,
+    );
 }
 
 /// Maps a single loadable segment into the user address space.
@@ -143,8 +387,8 @@ fn map_segment<M: crate::mm::mapper::PageMapper<Size4KiB> + crate::mm::mapper::P
     address_space: &AddressSpace<M>,
     seg: &ExecSegment<'_>,
     hhdm_offset: crate::addr::VirtAddr,
-    alloc: &mut BitmapFrameAllocRef<'_>,
-) {
+    alloc: &mut BuddyFrameAllocRef<'_>,
+) -> Result<(), BinaryError> {
     let mut flags = MapFlags::USER;
     if seg.flags.writable {
         flags |= MapFlags::WRITABLE;
@@ -168,17 +412,14 @@ fn map_segment<M: crate::mm::mapper::PageMapper<Size4KiB> + crate::mm::mapper::P
 
     for i in 0..page_count {
         let page_vaddr = seg_start + i * PAGE_SIZE as u64;
-        let frame = alloc
-            .0
-            .allocate_frame()
-            .expect("PMM: out of memory mapping segment");
+        let frame = crate::mm::oom::alloc_user_frame(alloc.0).ok_or(BinaryError::OutOfMemory)?;
 
         let page = Page::containing_address(VirtAddr::new(page_vaddr));
 
         // Map the page. Address space not yet in CR3, so ignore flush.
         address_space
             .map_user_page(page, frame, flags, alloc)
-            .expect("failed to map segment page")
+            .map_err(|_| BinaryError::OutOfMemory)?
             .ignore();
 
         // Write the segment data into the frame via HHDM.
@@ -211,22 +452,27 @@ fn map_segment<M: crate::mm::mapper::PageMapper<Size4KiB> + crate::mm::mapper::P
             }
         }
     }
+    Ok(())
 }
 
-/// Maps a user stack (guard page + usable pages) and returns the stack top.
+/// Maps the initially populated part of the main stack, `[bottom, top)`.
+///
+/// Pages below `bottom` are left unmapped and faulted in by
+/// [`grow_user_stack`].
 fn map_user_stack<
     M: crate::mm::mapper::PageMapper<Size4KiB> + crate::mm::mapper::PageTranslator,
 >(
     address_space: &AddressSpace<M>,
-    alloc: &mut BitmapFrameAllocRef<'_>,
-) {
-    let stack_bottom = USER_STACK_TOP - USER_STACK_SIZE;
-    let page_count = USER_STACK_SIZE / PAGE_SIZE as u64;
+    stack: &UserStack,
+    alloc: &mut BuddyFrameAllocRef<'_>,
+) -> Result<(), BinaryError> {
+    let stack_bottom = stack.bottom;
+    let page_count = (stack.top - stack.bottom) / PAGE_SIZE as u64;
 
     kdebug!(
         "  Mapping user stack: {:#x}..{:#x} ({} pages)",
         stack_bottom,
-        USER_STACK_TOP,
+        stack.top,
         page_count
     );
 
@@ -234,17 +480,14 @@ fn map_user_stack<
 
     for i in 0..page_count {
         let page_vaddr = stack_bottom + i * PAGE_SIZE as u64;
-        let frame = alloc
-            .0
-            .allocate_frame()
-            .expect("PMM: out of memory mapping user stack");
+        let frame = crate::mm::oom::alloc_user_frame(alloc.0).ok_or(BinaryError::OutOfMemory)?;
 
         let page = Page::containing_address(VirtAddr::new(page_vaddr));
 
         // Address space not yet in CR3, so ignore flush.
         address_space
             .map_user_page(page, frame, flags, alloc)
-            .expect("failed to map user stack page")
+            .map_err(|_| BinaryError::OutOfMemory)?
             .ignore();
 
         // SAFETY: The frame was just allocated and mapped; zeroing via HHDM is safe.
@@ -254,6 +497,64 @@ fn map_user_stack<
             core::ptr::write_bytes(frame_ptr, 0, PAGE_SIZE);
         }
     }
+    Ok(())
+}
+
+/// Grows the current process's main stack down to cover `addr`.
+///
+/// Called from the page fault handler for not-present faults on user
+/// addresses, from user mode or from a kernel user-access copy. Maps
+/// every page between the faulting one and the current stack bottom,
+/// top-down, so that the stack stays contiguous if memory runs out
+/// part-way.
+///
+/// Returns `true` if `addr` is now mapped (including when another thread
+/// sharing the stack grew it first), or `false` if `addr` is outside the
+/// stack's growth range or no memory is left.
+pub fn grow_user_stack(addr: u64) -> bool {
+    super::ProcessTable::with_user_space(|process| {
+        let mut stack = process.user_stack.lock();
+        if stack.contains(addr) {
+            return true;
+        }
+        let Some(new_bottom) = stack.grow_to(addr) else {
+            return false;
+        };
+
+        let hhdm_offset = crate::mm::hhdm::offset();
+        let flags = MapFlags::WRITABLE | MapFlags::USER;
+        let reached = crate::mm::pmm::with(|pmm| {
+            let mut alloc = BuddyFrameAllocRef(pmm);
+            let address_space = process.address_space();
+            let mut bottom = stack.bottom;
+            while bottom > new_bottom {
+                let Some(frame) = crate::mm::oom::alloc_user_frame(alloc.0) else {
+                    break;
+                };
+                let frame_ptr = (hhdm_offset + frame.start_address().as_u64()).as_mut_ptr::<u8>();
+                // SAFETY: The frame was just allocated and is not mapped
+                // anywhere yet; zeroing it via HHDM is safe.
+                unsafe { core::ptr::write_bytes(frame_ptr, 0, PAGE_SIZE) };
+
+                let page = Page::containing_address(VirtAddr::new(bottom - PAGE_SIZE as u64));
+                match address_space.map_user_page(page, frame, flags, &mut alloc) {
+                    // The address space is live in CR3 on this CPU.
+                    Ok(flush) => flush.flush(),
+                    Err(_) => {
+                        // SAFETY: The frame was never mapped.
+                        let _ = unsafe { alloc.0.deallocate_frame(frame) };
+                        break;
+                    }
+                }
+                bottom -= PAGE_SIZE as u64;
+            }
+            bottom
+        });
+
+        stack.bottom = reached;
+        stack.contains(addr)
+    })
+    .unwrap_or(false)
 }
 
 /// Maps a single read-only executable page at [`super::SIGRETURN_TRAMPOLINE_ADDR`]
@@ -269,21 +570,18 @@ fn map_sigreturn_trampoline<
 >(
     address_space: &AddressSpace<M>,
     hhdm_offset: crate::addr::VirtAddr,
-    alloc: &mut BitmapFrameAllocRef<'_>,
-) {
+    alloc: &mut BuddyFrameAllocRef<'_>,
+) -> Result<(), BinaryError> {
     let trampoline_addr = super::SIGRETURN_TRAMPOLINE_ADDR;
     let page = Page::containing_address(VirtAddr::new(trampoline_addr));
-    let frame = alloc
-        .0
-        .allocate_frame()
-        .expect("PMM: out of memory mapping sigreturn trampoline");
+    let frame = crate::mm::oom::alloc_user_frame(alloc.0).ok_or(BinaryError::OutOfMemory)?;
 
     // Read + execute, no write — user can execute but not modify.
     let flags = MapFlags::USER | MapFlags::EXECUTABLE;
 
     address_space
         .map_user_page(page, frame, flags, alloc)
-        .expect("failed to map sigreturn trampoline page")
+        .map_err(|_| BinaryError::OutOfMemory)?
         .ignore();
 
     // Write the trampoline stub into the frame via HHDM.
@@ -313,19 +611,25 @@ fn map_sigreturn_trampoline<
     }
 
     kdebug!("  Mapped sigreturn trampoline at {:#x}", trampoline_addr);
+    Ok(())
 }
 
-/// Writes argv and envp data onto the child's user stack via HHDM translation.
+/// Writes argv, envp, and the auxiliary vector onto the child's user stack
+/// via HHDM translation.
 ///
-/// Stack layout (standard C ABI with null-terminated strings):
+/// Stack layout (System V x86-64 ABI process entry):
 /// ```text
-/// HIGH ADDRESS (USER_STACK_TOP = 0x7FFF_FFFF_F000)
+/// HIGH ADDRESS (stack_top)
 ///   ┌────────────────────────────────┐
+///   │ 16 random bytes                │  ← AT_RANDOM
 ///   │ env string bytes (NUL-term)    │
 ///   │ arg string bytes (NUL-term)    │  ← packed, each NUL-terminated
 ///   ├────────────────────────────────┤
 ///   │ padding (align to 16 bytes)    │
 ///   ├────────────────────────────────┤
+///   │ AT_NULL, 0                     │  ← auxv terminator
+///   │ ...                            │
+///   │ auxv[0]: (type, value)         │
 ///   │ NULL                           │  ← envp terminator
 ///   │ envp[envc-1]: *const c_char    │
 ///   │ ...                            │
@@ -338,6 +642,9 @@ fn map_sigreturn_trampoline<
 ///   └────────────────────────────────┘
 /// ```
 ///
+/// `cred` supplies the IDs reported in the auxiliary vector; `AT_SECURE`
+/// is set when the real and effective IDs differ.
+///
 /// Returns the adjusted RSP value, or `BinaryError` if translation fails.
 #[expect(
     clippy::cast_possible_truncation,
@@ -345,11 +652,19 @@ fn map_sigreturn_trampoline<
 )]
 fn write_startup_data<M: PageMapper<Size4KiB> + PageTranslator>(
     address_space: &AddressSpace<M>,
+    stack_top: u64,
     args: &[&str],
     envs: &[&str],
+    aux: &AuxInfo,
+    cred: &Credentials,
     hhdm_offset: crate::addr::VirtAddr,
 ) -> Result<u64, BinaryError> {
-    let mut cursor = USER_STACK_TOP;
+    use hadron_syscall::{
+        AT_BASE, AT_EGID, AT_ENTRY, AT_EUID, AT_GID, AT_HADRON_DL, AT_NULL, AT_PAGESZ, AT_PHDR,
+        AT_PHENT, AT_PHNUM, AT_RANDOM, AT_SECURE, AT_UID,
+    };
+
+    let mut cursor = stack_top;
 
     const MAX_STRINGS: usize = 96; // 32 args + 64 envs
     if args.len() + envs.len() > MAX_STRINGS {
@@ -357,41 +672,76 @@ fn write_startup_data<M: PageMapper<Size4KiB> + PageTranslator>(
     }
     let mut string_addrs = [0u64; MAX_STRINGS];
 
-    // 1. Write env string bytes first (higher addresses), NUL-terminated.
+    // 1. Write the AT_RANDOM bytes (seed material for stack protectors and
+    //    pointer guards).
+    cursor -= 16;
+    let random = [crate::arch::random_u64(), crate::arch::random_u64()];
+    for (i, word) in random.iter().enumerate() {
+        write_bytes_to_user(
+            address_space,
+            cursor + 8 * i as u64,
+            &word.to_le_bytes(),
+            hhdm_offset,
+        )?;
+    }
+    let random_addr = cursor;
+
+    // 2. Write env string bytes, NUL-terminated.
     //    Pages are zeroed by map_user_stack, so reserving len+1 bytes per
     //    string ensures a NUL terminator after each string's content.
     for (i, env) in envs.iter().enumerate().rev() {
         let idx = args.len() + i;
         cursor -= (env.len() as u64) + 1; // +1 for NUL terminator
-        write_string_bytes(address_space, cursor, env, hhdm_offset)?;
+        write_bytes_to_user(address_space, cursor, env.as_bytes(), hhdm_offset)?;
         string_addrs[idx] = cursor;
     }
 
-    // 2. Write arg string bytes, NUL-terminated.
+    // 3. Write arg string bytes, NUL-terminated.
     for (i, arg) in args.iter().enumerate().rev() {
         cursor -= (arg.len() as u64) + 1; // +1 for NUL terminator
-        write_string_bytes(address_space, cursor, arg, hhdm_offset)?;
+        write_bytes_to_user(address_space, cursor, arg.as_bytes(), hhdm_offset)?;
         string_addrs[i] = cursor;
     }
 
-    // 3. Align cursor to 16 bytes.
+    // 4. Align cursor to 16 bytes.
     cursor &= !0xF;
 
-    // 4. Compute layout: argc, argv[0..argc], NULL, envp[0..envc], NULL
+    // 5. Build the auxiliary vector. AT_HADRON_DL is left for the
+    //    interpreter to fill in.
+    let secure = cred.euid() != cred.uid() || cred.egid() != cred.gid();
+    let auxv: [(usize, u64); 14] = [
+        (AT_PHDR, aux.phdr),
+        (AT_PHENT, u64::from(aux.phent)),
+        (AT_PHNUM, u64::from(aux.phnum)),
+        (AT_PAGESZ, PAGE_SIZE as u64),
+        (AT_BASE, aux.interp_base),
+        (AT_ENTRY, aux.entry),
+        (AT_UID, u64::from(cred.uid().as_u32())),
+        (AT_EUID, u64::from(cred.euid().as_u32())),
+        (AT_GID, u64::from(cred.gid().as_u32())),
+        (AT_EGID, u64::from(cred.egid().as_u32())),
+        (AT_SECURE, u64::from(secure)),
+        (AT_RANDOM, random_addr),
+        (AT_HADRON_DL, 0),
+        (AT_NULL, 0),
+    ];
+
+    // 6. Compute layout: argc, argv[0..argc], NULL, envp[0..envc], NULL, auxv
     let ptr_size = core::mem::size_of::<usize>() as u64;
     let total_below = ptr_size                      // argc
         + ptr_size * (args.len() as u64)            // argv pointers
         + ptr_size                                  // argv NULL terminator
         + ptr_size * (envs.len() as u64)            // envp pointers
-        + ptr_size; // envp NULL terminator
+        + ptr_size                                  // envp NULL terminator
+        + 2 * ptr_size * (auxv.len() as u64); // auxv pairs
     let rsp = (cursor - total_below) & !0xF; // 16-byte aligned
 
-    // 5. Write argc.
+    // 7. Write argc.
     let mut pos = rsp;
     write_usize_to_user(address_space, pos, args.len(), hhdm_offset)?;
     pos += ptr_size;
 
-    // 6. Write argv pointers, then NULL terminator.
+    // 8. Write argv pointers, then NULL terminator.
     for &addr in &string_addrs[..args.len()] {
         write_usize_to_user(address_space, pos, addr as usize, hhdm_offset)?;
         pos += ptr_size;
@@ -399,24 +749,32 @@ fn write_startup_data<M: PageMapper<Size4KiB> + PageTranslator>(
     write_usize_to_user(address_space, pos, 0, hhdm_offset)?;
     pos += ptr_size;
 
-    // 7. Write envp pointers, then NULL terminator.
+    // 9. Write envp pointers, then NULL terminator.
     for &addr in &string_addrs[args.len()..args.len() + envs.len()] {
         write_usize_to_user(address_space, pos, addr as usize, hhdm_offset)?;
         pos += ptr_size;
     }
     write_usize_to_user(address_space, pos, 0, hhdm_offset)?;
+    pos += ptr_size;
+
+    // 10. Write the auxiliary vector, ending with AT_NULL.
+    for &(tag, value) in &auxv {
+        write_usize_to_user(address_space, pos, tag, hhdm_offset)?;
+        write_usize_to_user(address_space, pos + ptr_size, value as usize, hhdm_offset)?;
+        pos += 2 * ptr_size;
+    }
 
     Ok(rsp)
 }
 
-/// Write a UTF-8 string's bytes to the user address space via HHDM.
-fn write_string_bytes<M: PageMapper<Size4KiB> + PageTranslator>(
+/// Write raw bytes to the user address space via HHDM.
+fn write_bytes_to_user<M: PageMapper<Size4KiB> + PageTranslator>(
     address_space: &AddressSpace<M>,
     vaddr: u64,
-    s: &str,
+    bytes: &[u8],
     hhdm_offset: crate::addr::VirtAddr,
 ) -> Result<(), BinaryError> {
-    for (j, &byte) in s.as_bytes().iter().enumerate() {
+    for (j, &byte) in bytes.iter().enumerate() {
         let addr = vaddr + j as u64;
         let phys = address_space
             .translate(VirtAddr::new(addr))
@@ -450,33 +808,24 @@ fn write_usize_to_user<M: PageMapper<Size4KiB> + PageTranslator>(
     Ok(())
 }
 
-/// Writes startup data for the init process: argv=`["/bin/init"]`, no envp.
-///
-/// This is a separate entry point for `spawn_init` which doesn't go through
-/// the full `spawn_process` flow.
-///
-/// # Errors
-///
-/// Returns [`BinaryError`] if address translation fails.
-pub fn write_argv_to_init_stack<M: PageMapper<Size4KiB> + PageTranslator>(
-    address_space: &AddressSpace<M>,
-    hhdm_offset: crate::addr::VirtAddr,
-) -> Result<u64, BinaryError> {
-    write_startup_data(address_space, &["/bin/init"], &[], hhdm_offset)
-}
-
 /// Spawns a new process from an ELF binary at the given VFS path.
 ///
 /// Reads the binary from the VFS, creates a process with inherited fd 0/1/2
 /// from the parent, writes argv and envp onto the child stack, registers it
 /// in the global process table, and spawns its async task on the executor.
 ///
+/// The child starts with the parent's credentials, or the identity in
+/// `opts`, adjusted by the binary's set-user-ID and set-group-ID bits.
+///
 /// Returns the child process `Arc` on success.
 ///
 /// # Errors
 ///
 /// Returns [`BinaryError`] if the path cannot be resolved, the file cannot
-/// be read, or the binary cannot be loaded.
+/// be read, or the binary cannot be loaded;
+/// [`BinaryError::PermissionDenied`] if the child's credentials do not
+/// allow executing it; [`BinaryError::NotPermitted`] if an unprivileged
+/// parent requests another identity.
 pub fn spawn_process(
     path: &str,
     parent_pid: Pid,
@@ -493,6 +842,10 @@ pub fn spawn_process(
         BinaryError::ParseError("path not found")
     })?;
 
+    let parent = super::ProcessTable::lookup(parent_pid)
+        .ok_or(BinaryError::ParseError("parent process not found"))?;
+    let cred = spawn_cred(&parent, opts.as_ref(), &*inode)?;
+
     let file_size = inode.size();
     let mut buf = alloc::vec![0u8; file_size];
     let bytes_read = poll_immediate(inode.read(0, &mut buf)).map_err(|e| {
@@ -506,22 +859,19 @@ pub fn spawn_process(
     })?;
     assert_eq!(bytes_read, file_size, "short read of binary");
 
-    let (process, entry, _stack_top) =
-        create_process_from_binary(&buf, Some(parent_pid)).map_err(|e| {
+    let (process, entry, stack_top) =
+        create_process_from_binary(&buf, Some(parent_pid), args, envs, &cred).map_err(|e| {
             crate::kwarn!("spawn_process: binary load '{}' failed: {:?}", path, e);
+            if matches!(e, BinaryError::OutOfMemory) {
+                crate::mm::oom::out_of_memory(file_size.div_ceil(PAGE_SIZE));
+            }
             e
         })?;
 
-    // Write argv and envp onto the child's user stack.
-    let hhdm_offset = crate::mm::hhdm::offset();
-    let stack_top = write_startup_data(&*process.address_space(), args, envs, hhdm_offset)?;
-
     let fd_map = opts.as_ref().and_then(|o| o.fd_map);
     let child_cwd = opts.as_ref().and_then(|o| o.cwd.clone());
 
-    // Look up the parent process once for both fd inheritance and CWD.
-    let parent = super::ProcessTable::lookup(parent_pid)
-        .ok_or(BinaryError::ParseError("parent process not found"))?;
+    process.set_cred(cred);
 
     // Inherit file descriptors into the child process.
     // Resolve /dev/console BEFORE locking any fd_table to maintain the
@@ -581,24 +931,63 @@ pub fn spawn_process(
         stack_top
     );
 
-    crate::sched::spawn(super::process_task(process.clone(), entry, stack_top));
+    crate::sched::spawn_user(
+        "process",
+        super::process_task(process.clone(), entry, stack_top),
+    );
 
     Ok(process)
 }
 
-// ── Execve ─────────────────────────────────────────────────────────
+/// Works out the credentials a child spawned by `parent` runs `binary` with.
+fn spawn_cred(
+    parent: &Process,
+    opts: Option<&SpawnOptions<'_>>,
+    binary: &dyn Inode,
+) -> Result<Credentials, BinaryError> {
+    let mut cred = Credentials::clone(&parent.cred());
+    let (uid, gid) = opts.map_or((None, None), |o| (o.uid, o.gid));
+    if (uid.is_some() || gid.is_some()) && !cred.is_privileged() {
+        return Err(BinaryError::NotPermitted);
+    }
+    // Group first: dropping the user ID gives up the privilege to do so.
+    if let Some(gid) = gid {
+        cred.set_gid(gid).map_err(|_| BinaryError::NotPermitted)?;
+        cred.set_groups(&[])
+            .map_err(|_| BinaryError::NotPermitted)?;
+    }
+    if let Some(uid) = uid {
+        cred.set_uid(uid).map_err(|_| BinaryError::NotPermitted)?;
+    }
+    let own = check_exec_access(&cred, binary)?;
+    cred.exec(own.uid, own.gid, own.mode);
+    Ok(cred)
+}
 
-/// Public constants for use by the Exec trap handler.
-pub const USER_MMAP_BASE: u64 = 0x0000_4000_0000_0000;
-/// Maximum mmap region size.
-pub const USER_MMAP_MAX_SIZE: u64 = 256 * 1024 * 1024 * 1024 * 1024;
+/// Checks that `cred` may execute `binary` and returns its ownership.
+fn check_exec_access(cred: &Credentials, binary: &dyn Inode) -> Result<Ownership, BinaryError> {
+    let own = binary.ownership();
+    if binary.inode_type() != InodeType::File
+        || !cred.may_access(own.uid, own.gid, own.mode, Access::EXECUTE)
+    {
+        return Err(BinaryError::PermissionDenied);
+    }
+    Ok(own)
+}
+
+// ── Execve ─────────────────────────────────────────────────────────
 
 /// Handle `task_execve`: load a new binary and replace the process's address space.
 ///
 /// Called from `process_task` under user CR3 (to read SpawnInfo from user memory).
 /// Returns `(entry_point, stack_top)` on success, or negated errno on failure.
 ///
-/// On success, the process's address space has been replaced (old one dropped).
+/// On success, the process's address space has been replaced (old one dropped,
+/// and the I/O ring operations submitted under it cancelled) and its mmap
+/// region, program break, and main stack follow the new image's freshly
+/// randomized layout. The thread pointer (FS base) is cleared. The
+/// binary's set-user-ID and set-group-ID bits have been applied to the
+/// credentials.
 #[expect(
     clippy::cast_possible_wrap,
     reason = "returning negated errno as isize"
@@ -608,24 +997,25 @@ pub fn handle_execve(
     info_ptr: usize,
     info_len: usize,
 ) -> Result<(u64, u64), isize> {
-    use crate::syscall::{EINVAL, ENOENT};
+    use crate::syscall::{EACCES, EINVAL, ENOENT};
     use hadron_syscall::SpawnInfo;
 
     if info_len < core::mem::size_of::<SpawnInfo>() {
         return Err(EINVAL);
     }
 
-    // SAFETY: Caller switched to user CR3. info_ptr is in user memory.
-    let info = unsafe { core::ptr::read(info_ptr as *const SpawnInfo) };
-
-    // Read path from user memory.
-    let path_slice =
-        unsafe { core::slice::from_raw_parts(info.path_ptr as *const u8, info.path_len) };
-    let path = core::str::from_utf8(path_slice).map_err(|_| EINVAL)?;
-
-    // Read argv and envp from user memory.
-    let args = read_user_string_array(info.argv_ptr, info.argv_count);
-    let envs = read_user_string_array(info.envp_ptr, info.envp_count);
+    // Copy SpawnInfo, path, argv and envp in from user memory (user CR3
+    // is active). Copy errors are negated errnos; this function returns
+    // positive ones.
+    let info = UserPtr::<SpawnInfo>::new(info_ptr)
+        .and_then(|p| p.read())
+        .map_err(|e| -e)?;
+    let path_bytes = UserSlice::new(info.path_ptr, info.path_len)
+        .and_then(|s| s.read_to_vec())
+        .map_err(|e| -e)?;
+    let path = core::str::from_utf8(&path_bytes).map_err(|_| EINVAL)?;
+    let args = read_user_string_array(info.argv_ptr, info.argv_count).map_err(|e| -e)?;
+    let envs = read_user_string_array(info.envp_ptr, info.envp_count).map_err(|e| -e)?;
 
     // Switch back to kernel CR3 for VFS operations.
     unsafe {
@@ -634,35 +1024,61 @@ pub fn handle_execve(
 
     // Resolve and read the binary from VFS.
     let inode = crate::fs::vfs::with_vfs(|vfs| vfs.resolve(path)).map_err(|_| ENOENT)?;
+    let mut cred = Credentials::clone(&process.cred());
+    let own = check_exec_access(&cred, &*inode).map_err(|_| EACCES)?;
     let file_size = inode.size();
     let mut buf = alloc::vec![0u8; file_size];
     crate::fs::poll_immediate(inode.read(0, &mut buf)).map_err(|_| ENOENT)?;
     let binary_data = buf;
+    // The identity the new image runs with; installed past the point of
+    // no return below.
+    cred.exec(own.uid, own.gid, own.mode);
 
     // Load the binary and create a new address space.
-    let (new_space, entry, _stack_top) = match create_address_space_from_binary(&binary_data) {
+    let loaded = match load_image(&binary_data, &cred) {
         Ok(result) => result,
+        Err(BinaryError::OutOfMemory) => {
+            crate::mm::oom::out_of_memory(file_size.div_ceil(PAGE_SIZE));
+            return Err(crate::syscall::ENOMEM);
+        }
+        Err(BinaryError::PermissionDenied) => return Err(EACCES),
         Err(_e) => return Err(EINVAL),
     };
 
-    // Write argv/envp onto the new stack.
+    // Write argv/envp and the auxiliary vector onto the new stack.
     let hhdm_offset = crate::mm::hhdm::offset();
     let args_refs: alloc::vec::Vec<&str> = args.iter().map(alloc::string::String::as_str).collect();
     let envs_refs: alloc::vec::Vec<&str> = envs.iter().map(alloc::string::String::as_str).collect();
-    let stack_top = match write_startup_data(&new_space, &args_refs, &envs_refs, hhdm_offset) {
+    let stack_top = match write_startup_data(
+        &loaded.address_space,
+        loaded.layout.stack_top,
+        &args_refs,
+        &envs_refs,
+        &loaded.aux,
+        &cred,
+        hhdm_offset,
+    ) {
         Ok(st) => st,
         Err(_e) => return Err(EINVAL),
     };
+    let entry = loaded.entry;
 
-    // Replace the process's address space (drops the old one).
-    let _old_space = process.replace_address_space(new_space);
+    // Replace the process's address space (freeing the old image) and adopt
+    // the new layout.
+    process.replace_address_space(loaded.address_space);
+    process.set_user_layout(&loaded.layout, loaded.brk_start);
+    // The old image's thread pointer means nothing to the new one.
+    process.fs_base.store(0, Ordering::Relaxed);
+
+    // Past the point of no return: take on the new image's identity.
+    process.set_cred(cred);
 
     // Update the executable path for /proc/<pid>/exe.
     *process.exe_path.lock() = String::from(path);
 
     // Switch to the new user CR3 for subsequent operations.
     unsafe {
-        crate::arch::x86_64::registers::control::Cr3::write(process.user_cr3());
+        process.load_user_cr3();
     }
 
     kinfo!(
@@ -676,82 +1092,22 @@ pub fn handle_execve(
     Ok((entry, stack_top))
 }
 
-/// Read an array of C strings from user memory.
-fn read_user_string_array(ptr: usize, count: usize) -> alloc::vec::Vec<String> {
+/// Read an array of `(ptr, len)` string descriptors from user memory.
+///
+/// Returns a negated errno if any descriptor or string cannot be copied.
+fn read_user_string_array(ptr: usize, count: usize) -> Result<alloc::vec::Vec<String>, isize> {
     if ptr == 0 || count == 0 {
-        return alloc::vec::Vec::new();
+        return Ok(alloc::vec::Vec::new());
     }
+    let pairs = read_user_array::<[usize; 2]>(ptr, count)?;
     let mut result = alloc::vec::Vec::with_capacity(count);
-    // SAFETY: ptr points to an array of (ptr, len) pairs in user memory,
-    // and caller has switched to user CR3.
-    let pairs = unsafe { core::slice::from_raw_parts(ptr as *const [usize; 2], count) };
-    for pair in pairs {
-        let s_ptr = pair[0];
-        let s_len = pair[1];
+    for [s_ptr, s_len] in pairs {
         if s_ptr == 0 || s_len == 0 {
             result.push(String::new());
             continue;
         }
-        let bytes = unsafe { core::slice::from_raw_parts(s_ptr as *const u8, s_len) };
-        result.push(String::from_utf8_lossy(bytes).into_owned());
+        let bytes = UserSlice::new(s_ptr, s_len)?.read_to_vec()?;
+        result.push(String::from_utf8_lossy(&bytes).into_owned());
     }
-    result
-}
-
-/// Create a new address space from binary data without creating a Process.
-///
-/// Returns `(AddressSpace, entry_point, stack_top)`.
-fn create_address_space_from_binary(
-    data: &[u8],
-) -> Result<
-    (
-        AddressSpace<crate::arch::x86_64::paging::PageTableMapper>,
-        u64,
-        u64,
-    ),
-    BinaryError,
-> {
-    #[cfg(target_arch = "x86_64")]
-    type KernelMapper = crate::arch::x86_64::paging::PageTableMapper;
-
-    let image = binfmt::load_binary(data)?;
-    let entry = image.entry_point;
-
-    let kernel_cr3 = super::TrapContext::kernel_cr3();
-    let hhdm_offset = crate::mm::hhdm::offset();
-    let mapper = KernelMapper::new(hhdm_offset);
-
-    let address_space = crate::mm::pmm::with(|pmm| -> Result<_, BinaryError> {
-        let mut alloc = BitmapFrameAllocRef(pmm);
-
-        // SAFETY: Same as create_process_from_binary.
-        let address_space = unsafe {
-            AddressSpace::new_user(kernel_cr3, mapper, hhdm_offset, &mut alloc, dealloc_frame)
-                .expect("failed to create user address space")
-        };
-
-        for seg in image.segments() {
-            map_segment(&address_space, seg, hhdm_offset, &mut alloc);
-        }
-
-        if image.needs_relocation {
-            if let Some(elf_data) = image.elf_data {
-                let elf = hadron_elf::ElfFile::parse(elf_data)
-                    .expect("ELF already validated during load");
-                binfmt::reloc::apply_dyn_relocations(
-                    &address_space,
-                    &elf,
-                    image.base_addr,
-                    hhdm_offset,
-                )?;
-            }
-        }
-
-        map_user_stack(&address_space, &mut alloc);
-        map_sigreturn_trampoline(&address_space, hhdm_offset, &mut alloc);
-
-        Ok(address_space)
-    })?;
-
-    Ok((address_space, entry, USER_STACK_TOP))
+    Ok(result)
 }
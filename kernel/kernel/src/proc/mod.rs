@@ -7,9 +7,11 @@
 //! re-entered via [`enter_userspace_resume_wrapper`] using saved register
 //! state.
 
+pub(crate) mod acct;
 pub mod binfmt;
 pub mod exec;
 pub mod signal;
+pub mod timers;
 
 extern crate alloc;
 
@@ -18,7 +20,10 @@ use alloc::string::String;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 use core::cell::UnsafeCell;
-use hadron_core::sync::atomic::{AtomicBool, AtomicU8, AtomicU32, AtomicU64, Ordering};
+use hadron_core::rt::SchedPolicy;
+use hadron_core::sync::atomic::{
+    AtomicBool, AtomicI32, AtomicPtr, AtomicU8, AtomicU32, AtomicU64, Ordering,
+};
 
 /// When set, PID 1's exit code is forwarded to the `isa-debug-exit` device.
 ///
@@ -40,21 +45,29 @@ fn utest_mode() -> bool {
 use crate::addr::{PhysAddr, VirtAddr};
 use crate::arch::x86_64::paging::PageTableMapper;
 use crate::arch::x86_64::registers::control::Cr3;
-use crate::arch::x86_64::registers::model_specific::{IA32_GS_BASE, IA32_KERNEL_GS_BASE};
+use crate::arch::x86_64::registers::model_specific::{
+    IA32_FS_BASE, IA32_GS_BASE, IA32_KERNEL_GS_BASE,
+};
 use crate::arch::x86_64::userspace::{
     UserRegisters, enter_userspace_resume, enter_userspace_save, restore_kernel_context,
 };
+use crate::cputime::{CpuTimeCounter, GroupCpuTime};
+use crate::cred::Credentials;
 use crate::id::Pid;
 use crate::mm::address_space::AddressSpace;
 use crate::mm::layout::VirtRegion;
 use crate::mm::region::FreeRegionAllocator;
+use crate::mm::user_layout::{UserLayout, UserStack};
 use crate::percpu::{CpuLocal, MAX_CPUS};
-use crate::sync::SpinLock;
+use crate::sched::primitives::timer_expired;
+use crate::sched::timer::Timer;
+use crate::sync::{RwLock, SpinLock};
 use crate::{kdebug, kinfo, kwarn};
 
 use crate::fs::Inode;
 use crate::fs::file::{FileDescriptorTable, OpenFlags};
 use crate::id::Fd;
+use crate::ipc::io_ring::CancelToken;
 use crate::sync::HeapWaitQueue;
 
 // ── Signal trampoline ──────────────────────────────────────────────
@@ -69,36 +82,30 @@ pub const SIGRETURN_TRAMPOLINE_ADDR: u64 = 0x7FFF_FFFE_0000;
 /// Layout saved on the user stack before invoking a signal handler.
 ///
 /// The signal handler's return address points to [`SIGRETURN_TRAMPOLINE_ADDR`],
-/// which calls `task_sigreturn()`. The kernel then restores these registers
-/// and resumes execution at the interrupted instruction.
+/// which calls `task_sigreturn()`. The kernel then restores the registers and
+/// signal mask from `uc`, and the FPU state that `uc.mcontext.fpregs` points
+/// to, and resumes execution at the interrupted instruction. `SA_SIGINFO`
+/// handlers receive pointers to `info` and `uc`.
 #[repr(C)]
 #[derive(Debug, Clone, Copy)]
 pub struct SignalFrame {
     /// Return address (trampoline addr, at top of frame).
     pub ret_addr: u64,
-    /// Signal number that was delivered.
-    pub signum: u64,
-    // Saved user registers:
-    pub rax: u64,
-    pub rbx: u64,
-    pub rcx: u64,
-    pub rdx: u64,
-    pub rsi: u64,
-    pub rdi: u64,
-    pub rbp: u64,
-    pub r8: u64,
-    pub r9: u64,
-    pub r10: u64,
-    pub r11: u64,
-    pub r12: u64,
-    pub r13: u64,
-    pub r14: u64,
-    pub r15: u64,
-    pub rip: u64,
-    pub rsp: u64,
-    pub rflags: u64,
+    /// Information about the delivered signal.
+    pub info: hadron_syscall::SigInfo,
+    /// Saved user context.
+    pub uc: hadron_syscall::UContext,
+    /// Saved x87/SSE state.
+    pub fpstate: hadron_syscall::FpState,
 }
 
+// `FpState` is copied to and from `UserFpuSaveArea` byte for byte.
+const _: () = assert!(core::mem::size_of::<hadron_syscall::FpState>() == 512);
+
+/// Bytes below the user stack pointer that leaf functions may use without
+/// adjusting it (System V AMD64 red zone); signal frames skip over them.
+const RED_ZONE_SIZE: u64 = 128;
+
 // ── Trap reason ─────────────────────────────────────────────────────
 
 /// Why userspace returned to the kernel.
@@ -128,6 +135,8 @@ pub enum TrapReason {
     Accept = 8,
     /// Syscall requested blocking poll (`event_wait_many` with timeout).
     Poll = 9,
+    /// Syscall requested a signal wait (`sig_suspend`, `sig_timedwait`).
+    SigWait = 10,
 }
 
 impl TrapReason {
@@ -143,6 +152,7 @@ impl TrapReason {
             7 => Self::Futex,
             8 => Self::Accept,
             9 => Self::Poll,
+            10 => Self::SigWait,
             _ => Self::Exit,
         }
     }
@@ -172,6 +182,17 @@ static PROCESS_EXIT_STATUS: CpuLocal<AtomicU64> =
 static CURRENT_PROCESS: CpuLocal<SpinLock<Option<Arc<Process>>>> =
     CpuLocal::new([const { SpinLock::named("CURRENT_PROCESS", None) }; MAX_CPUS]);
 
+/// Per-CPU process whose page tables [`Process::load_user_cr3`] last loaded.
+/// Read without locking by the page fault handler, which must find the
+/// faulting address space even outside `CURRENT_PROCESS` and while its lock
+/// is held.
+static USER_SPACE_PROCESS: CpuLocal<AtomicPtr<Process>> =
+    CpuLocal::new([const { AtomicPtr::new(core::ptr::null_mut()) }; MAX_CPUS]);
+
+/// Per-CPU page table root loaded together with [`USER_SPACE_PROCESS`].
+static USER_SPACE_ROOT: CpuLocal<AtomicU64> =
+    CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
+
 /// Wrapper to make `UnsafeCell<UserRegisters>` usable in a `static`.
 ///
 /// # Safety
@@ -269,15 +290,29 @@ pub(crate) static USER_FPU_CONTEXT: CpuLocal<SyncUserFpuContext> =
 pub(crate) static TRAP_REASON: CpuLocal<AtomicU8> =
     CpuLocal::new([const { AtomicU8::new(TrapReason::Exit as u8) }; MAX_CPUS]);
 
-/// Per-CPU target PID for `sys_task_wait`. Set by syscall handler, read by `process_task`.
-static WAIT_TARGET_PID: CpuLocal<AtomicU32> =
-    CpuLocal::new([const { AtomicU32::new(0) }; MAX_CPUS]);
+/// Per-CPU kind of wait target for TRAP_WAIT: 0 = any child, 1 = PID,
+/// 2 = process group. Set by syscall handler, read by `process_task`.
+static WAIT_TARGET_KIND: CpuLocal<AtomicU8> = CpuLocal::new([const { AtomicU8::new(0) }; MAX_CPUS]);
+
+/// Per-CPU target PID or PGID for TRAP_WAIT.
+static WAIT_TARGET_ID: CpuLocal<AtomicU32> = CpuLocal::new([const { AtomicU32::new(0) }; MAX_CPUS]);
 
-/// Per-CPU user-space pointer where `sys_task_wait` should write the exit status.
+/// Per-CPU user-space pointer where TRAP_WAIT should write the wait status.
 static WAIT_STATUS_PTR: CpuLocal<AtomicU64> =
     CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
 
-/// Per-CPU wait flags (WNOHANG, WUNTRACED) for `sys_task_wait`.
+/// Per-CPU user-space pointer where TRAP_WAIT should write a `SigInfo`.
+static WAIT_INFO_PTR: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
+
+/// Per-CPU user-space pointer where TRAP_WAIT should write an `RusageInfo`.
+static WAIT_RUSAGE_PTR: CpuLocal<AtomicU64> =
+    CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
+
+/// Per-CPU TRAP_WAIT return convention: 1 = `waitid` (return 0), 0 = the
+/// child PID.
+static WAIT_IS_WAITID: CpuLocal<AtomicU8> = CpuLocal::new([const { AtomicU8::new(0) }; MAX_CPUS]);
+
+/// Per-CPU wait flags (WNOHANG, WUNTRACED, WEXITED, ...) for TRAP_WAIT.
 static WAIT_FLAGS: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
 
 /// Per-CPU file descriptor for TRAP_IO.
@@ -301,8 +336,8 @@ static IO_CMSG_LEN: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(
 /// Per-CPU user pointer to the `struct msghdr` for `recvmsg` (to update `msg_controllen`).
 static IO_MSG_PTR: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
 
-/// Per-CPU sleep duration in milliseconds for TRAP_SLEEP.
-static SLEEP_MS: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
+/// Per-CPU sleep duration in nanoseconds for TRAP_SLEEP.
+static SLEEP_NS: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
 
 /// Per-CPU SpawnInfo pointer for TRAP_EXEC.
 static EXEC_INFO_PTR: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
@@ -313,6 +348,9 @@ static EXEC_INFO_LEN: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::ne
 static FUTEX_ADDR: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
 /// Per-CPU futex expected value for TRAP_FUTEX.
 static FUTEX_VAL: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
+/// Per-CPU timeout in nanoseconds for TRAP_FUTEX (`u64::MAX` = forever).
+static FUTEX_TIMEOUT_NS: CpuLocal<AtomicU64> =
+    CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
 
 /// Per-CPU listening socket fd for TRAP_ACCEPT.
 static ACCEPT_FD: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
@@ -321,8 +359,30 @@ static ACCEPT_FD: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0)
 static POLL_FDS_PTR: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
 /// Per-CPU number of fds for TRAP_POLL.
 static POLL_NFDS: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
-/// Per-CPU timeout in milliseconds for TRAP_POLL.
-static POLL_TIMEOUT_MS: CpuLocal<AtomicU64> =
+/// Per-CPU timeout in nanoseconds for TRAP_POLL.
+static POLL_TIMEOUT_NS: CpuLocal<AtomicU64> =
+    CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
+/// Per-CPU epoll fd for an `epoll_wait` TRAP_POLL, or `u64::MAX` for
+/// `event_wait_many`. The fds pointer and count are then the event buffer
+/// and its capacity.
+static POLL_EPOLL_FD: CpuLocal<AtomicU64> =
+    CpuLocal::new([const { AtomicU64::new(u64::MAX) }; MAX_CPUS]);
+/// Per-CPU I/O ring fd for an `io_ring_enter` TRAP_POLL, or `u64::MAX`.
+/// The fds pointer and count are then the number of operations submitted
+/// and the completions to wait for.
+static POLL_IO_RING_FD: CpuLocal<AtomicU64> =
+    CpuLocal::new([const { AtomicU64::new(u64::MAX) }; MAX_CPUS]);
+
+/// Per-CPU TRAP_SIGWAIT kind: `true` for `sig_suspend`, `false` for `sig_timedwait`.
+static SIGWAIT_SUSPEND: CpuLocal<AtomicBool> =
+    CpuLocal::new([const { AtomicBool::new(false) }; MAX_CPUS]);
+/// Per-CPU signal set accepted by a `sig_timedwait` TRAP_SIGWAIT.
+static SIGWAIT_SET: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
+/// Per-CPU user pointer for the `SigInfo` of TRAP_SIGWAIT (0 = none).
+static SIGWAIT_INFO_PTR: CpuLocal<AtomicU64> =
+    CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
+/// Per-CPU timeout in nanoseconds for TRAP_SIGWAIT (`u64::MAX` = forever).
+static SIGWAIT_TIMEOUT_NS: CpuLocal<AtomicU64> =
     CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
 
 /// Send a signal to all processes in a process group.
@@ -359,16 +419,73 @@ impl WaitState {
         if raw == 0 { None } else { Some(Pid::new(raw)) }
     }
 
-    /// Sets the target PID, status pointer, and flags for a `TRAP_WAIT` syscall.
-    pub fn set_params(target_pid: Pid, status_ptr: u64, flags: u64) {
-        WAIT_TARGET_PID
-            .get()
-            .store(target_pid.as_u32(), Ordering::Release);
-        WAIT_STATUS_PTR.get().store(status_ptr, Ordering::Release);
+    /// Sets the target, flags and output pointers for a `TRAP_WAIT` syscall.
+    pub fn set_params(target: WaitTarget, flags: u64, output: WaitOutput) {
+        let (kind, id) = match target {
+            WaitTarget::Any => (0, 0),
+            WaitTarget::Pid(pid) => (1, pid.as_u32()),
+            WaitTarget::Group(pgid) => (2, pgid),
+        };
+        WAIT_TARGET_KIND.get().store(kind, Ordering::Release);
+        WAIT_TARGET_ID.get().store(id, Ordering::Release);
         WAIT_FLAGS.get().store(flags, Ordering::Release);
+        WAIT_STATUS_PTR
+            .get()
+            .store(output.status_ptr, Ordering::Release);
+        WAIT_INFO_PTR
+            .get()
+            .store(output.info_ptr, Ordering::Release);
+        WAIT_RUSAGE_PTR
+            .get()
+            .store(output.rusage_ptr, Ordering::Release);
+        WAIT_IS_WAITID
+            .get()
+            .store(u8::from(output.waitid), Ordering::Release);
+    }
+
+    /// Reads back the parameters stored by [`set_params`](Self::set_params).
+    fn params() -> (WaitTarget, u64, WaitOutput) {
+        let id = WAIT_TARGET_ID.get().load(Ordering::Acquire);
+        let target = match WAIT_TARGET_KIND.get().load(Ordering::Acquire) {
+            1 => WaitTarget::Pid(Pid::new(id)),
+            2 => WaitTarget::Group(id),
+            _ => WaitTarget::Any,
+        };
+        let output = WaitOutput {
+            status_ptr: WAIT_STATUS_PTR.get().load(Ordering::Acquire),
+            info_ptr: WAIT_INFO_PTR.get().load(Ordering::Acquire),
+            rusage_ptr: WAIT_RUSAGE_PTR.get().load(Ordering::Acquire),
+            waitid: WAIT_IS_WAITID.get().load(Ordering::Acquire) != 0,
+        };
+        (target, WAIT_FLAGS.get().load(Ordering::Acquire), output)
     }
 }
 
+/// Children a `TRAP_WAIT` selects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitTarget {
+    /// Any child.
+    Any,
+    /// The child with this PID.
+    Pid(Pid),
+    /// Any child in this process group.
+    Group(u32),
+}
+
+/// Where a `TRAP_WAIT` writes its result. Zero pointers are skipped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WaitOutput {
+    /// User pointer to a `u64` wait status.
+    pub status_ptr: u64,
+    /// User pointer to a `SigInfo` describing the event.
+    pub info_ptr: u64,
+    /// User pointer to an `RusageInfo` with the child's CPU time.
+    pub rusage_ptr: u64,
+    /// `waitid` semantics: the syscall returns 0 rather than the child
+    /// PID, and a `WNOHANG` miss zeroes the `SigInfo`.
+    pub waitid: bool,
+}
+
 // ── Global process table ────────────────────────────────────────────
 
 /// Global process table mapping PID → `Arc<Process>`.
@@ -387,9 +504,10 @@ impl ProcessTable {
     /// child list.
     pub fn register(process: &Arc<Process>) {
         let mut table = PROCESS_TABLE.lock();
-        if let Some(ppid) = process.parent_pid {
+        if let Some(ppid) = process.parent_pid() {
             if let Some(parent) = table.get(&ppid) {
                 parent.children.lock().push(process.pid);
+                parent.child_notify.wake_all();
             }
         }
         table.insert(process.pid, process.clone());
@@ -406,7 +524,7 @@ impl ProcessTable {
     pub fn unregister(pid: Pid) {
         let mut table = PROCESS_TABLE.lock();
         if let Some(process) = table.get(&pid) {
-            if let Some(ppid) = process.parent_pid {
+            if let Some(ppid) = process.parent_pid() {
                 if let Some(parent) = table.get(&ppid) {
                     parent.children.lock().retain(|&c| c != pid);
                 }
@@ -415,6 +533,31 @@ impl ProcessTable {
         table.remove(&pid);
     }
 
+    /// Hands the children of the exiting `process` to `reaper`, or leaves
+    /// them without a parent if there is none.
+    ///
+    /// Returns the PIDs of the children that have already exited, which
+    /// the reaper must be told about.
+    pub fn reparent_children(process: &Process, reaper: Option<&Arc<Process>>) -> Vec<Pid> {
+        let table = PROCESS_TABLE.lock();
+        let orphans = core::mem::take(&mut *process.children.lock());
+        let reaper_pid = reaper.map_or(0, |r| r.pid.as_u32());
+        let mut exited = Vec::new();
+        for pid in orphans {
+            let Some(child) = table.get(&pid) else {
+                continue;
+            };
+            child.parent.store(reaper_pid, Ordering::Release);
+            if let Some(reaper) = reaper {
+                reaper.children.lock().push(pid);
+            }
+            if child.exit_status.lock().is_some() {
+                exited.push(pid);
+            }
+        }
+        exited
+    }
+
     /// Returns the PIDs of all children of the given parent.
     pub fn children_of(parent_pid: Pid) -> Vec<Pid> {
         let table = PROCESS_TABLE.lock();
@@ -447,6 +590,26 @@ impl ProcessTable {
         guard.as_ref().map(f)
     }
 
+    /// Executes a closure with the process whose address space is in CR3
+    /// on this CPU.
+    ///
+    /// Unlike [`try_current`](Self::try_current) this takes no lock and
+    /// also finds the process while it is not `CURRENT_PROCESS`, e.g. while
+    /// the kernel writes a signal frame or completes blocked I/O. Returns
+    /// `None` under the kernel page tables.
+    pub fn with_user_space<R>(f: impl FnOnce(&Process) -> R) -> Option<R> {
+        let process = USER_SPACE_PROCESS.get().load(Ordering::Acquire);
+        let root = USER_SPACE_ROOT.get().load(Ordering::Acquire);
+        if process.is_null() || Cr3::read().as_u64() != root {
+            return None;
+        }
+        // SAFETY: `load_user_cr3` stored `process` when it loaded `root`,
+        // and every load of user page tables goes through it, so `process`
+        // is the one whose page tables are still live. Its caller switches
+        // back to the kernel CR3 before the process can be dropped.
+        Some(f(unsafe { &*process }))
+    }
+
     /// Returns the number of processes in the global table.
     pub fn count() -> usize {
         PROCESS_TABLE.lock().len()
@@ -460,12 +623,6 @@ impl ProcessTable {
 
 // ── User mmap region ────────────────────────────────────────────────
 
-/// Base address for user mmap allocations (middle of lower-half address space).
-const USER_MMAP_BASE: u64 = 0x0000_4000_0000_0000;
-
-/// Maximum size of the user mmap region: 256 TiB.
-const USER_MMAP_MAX_SIZE: u64 = 256 * 1024 * 1024 * 1024 * 1024;
-
 /// Maximum free-list entries for the per-process mmap allocator.
 const MMAP_FREE_LIST_CAPACITY: usize = 64;
 
@@ -487,24 +644,37 @@ pub enum MappingKind {
 ///
 /// Fields that may be shared between threads via `task_clone` are wrapped
 /// in `Arc<SpinLock<T>>`. When `CLONE_VM` is used, the address space,
-/// mmap state, and program break are shared; when `CLONE_FILES` is used,
+/// mmap state, program break, and main stack are shared; when `CLONE_FILES` is used,
 /// the fd table is shared. Unshared fields (pid, signals, exit status)
 /// are per-thread.
 pub struct Process {
     /// Process ID.
     pub pid: Pid,
-    /// Parent process ID (`None` for init).
-    pub parent_pid: Option<Pid>,
+    /// Parent process ID, or 0 for init and for orphans nothing adopted.
+    /// Changes when an exiting parent hands its children to a reaper; see
+    /// [`parent_pid`](Self::parent_pid).
+    parent: AtomicU32,
+    /// Set by `child_set_subreaper`: orphaned descendants are adopted by
+    /// this process rather than by init. Not inherited.
+    pub child_subreaper: AtomicBool,
     /// Process group ID. Initialized to own PID on spawn.
     pub pgid: AtomicU32,
     /// Session ID. Initialized to parent's session, or own PID for session leaders.
     pub session_id: AtomicU32,
-    /// Physical address of the user PML4 (cached for fast CR3 switch).
-    /// Stored as `AtomicU64` to allow safe updates during `execve`.
-    user_cr3: AtomicU64,
+    /// Physical address of the shadow PML4 that user code runs on under
+    /// KPTI (the full PML4 if the address space has no shadow). Stored as
+    /// `AtomicU64` to allow safe updates during `execve`.
+    #[cfg(hadron_kpti)]
+    user_shadow_cr3: AtomicU64,
     /// User address space (owns the PML4, freed when last reference is dropped).
     /// Shared between threads created with `CLONE_VM`.
     address_space: Arc<SpinLock<AddressSpace<PageTableMapper>>>,
+    /// Cancel token of the image the address space holds. I/O ring
+    /// operations copy through the address space with it held for reading;
+    /// `execve` and exit hold it for writing while they tear the image
+    /// down, and `execve` cancels the token. Shared with address space
+    /// (`CLONE_VM`).
+    pub(crate) user_image: Arc<RwLock<Arc<CancelToken>>>,
     /// Per-process file descriptor table.
     /// Shared between threads created with `CLONE_FILES`.
     pub fd_table: Arc<SpinLock<FileDescriptorTable>>,
@@ -516,23 +686,77 @@ pub struct Process {
     pub mmap_mappings: Arc<SpinLock<BTreeMap<u64, MappingKind>>>,
     /// Pending signals for this process (per-thread).
     pub signals: signal::SignalState,
-    /// Exit status, set when the process terminates.
+    /// Wait status (Linux encoding), set when the process terminates.
     pub exit_status: SpinLock<Option<u64>>,
-    /// Wait queue notified when this process exits.
+    /// Wait queue notified when this process exits, stops or continues.
     pub exit_notify: HeapWaitQueue,
+    /// Wait queue notified when this process gains a child, by spawn or by
+    /// adopting an orphan, so a pending wait starts watching it.
+    pub child_notify: HeapWaitQueue,
     /// Current working directory (absolute path).
     pub cwd: SpinLock<String>,
     /// Program break address (heap boundary) for `brk()`.
     /// Shared with address space (`CLONE_VM`).
     pub program_break: Arc<SpinLock<u64>>,
+    /// Mapped extent of the main user stack, grown on demand by the page
+    /// fault handler. Shared with address space (`CLONE_VM`).
+    pub(crate) user_stack: Arc<SpinLock<UserStack>>,
     /// PIDs of child processes spawned by this process.
     /// Updated on spawn (push) and reap (remove).
     pub(crate) children: SpinLock<Vec<Pid>>,
     /// Path to the executable image (set after exec/spawn; `"<unknown>"` initially).
     pub exe_path: SpinLock<String>,
+    /// OOM killer bias (`oom_score_adj`), in
+    /// [`OOM_SCORE_ADJ_MIN`](crate::mm::oom::OOM_SCORE_ADJ_MIN)..=
+    /// [`OOM_SCORE_ADJ_MAX`](crate::mm::oom::OOM_SCORE_ADJ_MAX).
+    /// Inherited from the parent on spawn and clone.
+    pub oom_score_adj: AtomicI32,
+    /// Nice value, in [`NICE_MIN`](hadron_core::sched::NICE_MIN)..=
+    /// [`NICE_MAX`](hadron_core::sched::NICE_MAX). Sets the thread's weight
+    /// in the User scheduling tier. Inherited on spawn and clone.
+    pub nice: AtomicI32,
+    /// CPUs this thread may run on (bit `n` = CPU `n`). Inherited on spawn
+    /// and clone.
+    pub affinity: AtomicU64,
+    /// Scheduling class (`sched_setattr`). Inherited on spawn and clone,
+    /// except that a deadline reservation is not: the child falls back to
+    /// [`SchedPolicy::Normal`].
+    pub sched_policy: SpinLock<SchedPolicy>,
+    /// User and group credentials. Shared by all threads, since POSIX makes
+    /// them process-wide; spawned children start with a copy. Replaced
+    /// wholesale on change, so readers can hold a snapshot without the lock.
+    cred: Arc<SpinLock<Arc<Credentials>>>,
+    /// Thread pointer (FS base), loaded into `IA32_FS_BASE` on every entry
+    /// to userspace. Per-thread: inherited on clone unless `CLONE_SETTLS`
+    /// overrides it, and cleared by execve.
+    pub(crate) fs_base: AtomicU64,
+    /// User and system time of this thread.
+    cpu_time: CpuTimeCounter,
+    /// User and system time of the whole process and its reaped children.
+    /// Shared with threads created by `task_clone`; spawned children start
+    /// a fresh group.
+    group_cpu_time: Arc<GroupCpuTime>,
+    /// `setitimer` and `timer_create` timers. Shared with threads created
+    /// by `task_clone`; spawned children start with none.
+    pub timers: Arc<timers::ProcessTimers>,
 }
 
 impl Process {
+    /// Returns the parent's PID, or `None` for init and for orphans that
+    /// nothing adopted.
+    pub fn parent_pid(&self) -> Option<Pid> {
+        match self.parent.load(Ordering::Acquire) {
+            0 => None,
+            pid => Some(Pid::new(pid)),
+        }
+    }
+
+    /// Returns `true` if `other` is a thread of the same process, created
+    /// by `task_clone`.
+    pub(crate) fn same_thread_group(&self, other: &Process) -> bool {
+        Arc::ptr_eq(&self.group_cpu_time, &other.group_cpu_time)
+    }
+
     /// Returns a reference to the process's address space (borrows the lock).
     pub(crate) fn address_space(
         &self,
@@ -540,47 +764,210 @@ impl Process {
         self.address_space.lock()
     }
 
-    /// Returns the cached user CR3 physical address.
-    pub fn user_cr3(&self) -> PhysAddr {
-        PhysAddr::new(self.user_cr3.load(Ordering::Acquire))
+    /// Returns the number of resident user pages in the address space.
+    pub fn resident_pages(&self) -> usize {
+        self.address_space.lock().resident_pages()
+    }
+
+    /// Returns the number of page table frames owned by the address space.
+    pub fn page_table_pages(&self) -> usize {
+        self.address_space.lock().page_table_pages()
+    }
+
+    /// Tears down every `mem_map` mapping if this process is the last user
+    /// of its address space.
+    ///
+    /// Anonymous frames are returned to the PMM; device and shared frames
+    /// are only unmapped. Called when the process exits so that memory is
+    /// reclaimed immediately rather than when the zombie is reaped, which
+    /// is what makes killing an OOM victim effective.
+    pub(crate) fn release_user_mappings(&self) {
+        let _image = self.user_image.write();
+        self.unmap_user_mappings();
+    }
+
+    /// Does the work of [`release_user_mappings`](Self::release_user_mappings)
+    /// with [`user_image`](Self::user_image) held for writing.
+    fn unmap_user_mappings(&self) {
+        if Arc::strong_count(&self.address_space) != 1 {
+            return;
+        }
+        let mappings = core::mem::take(&mut *self.mmap_mappings.lock());
+        if mappings.is_empty() {
+            return;
+        }
+        crate::mm::pmm::with(|pmm| {
+            let mut alloc = crate::mm::pmm::BuddyFrameAllocRef(pmm);
+            let address_space = self.address_space.lock();
+            for (base, kind) in mappings {
+                let (page_count, owns_frames) = match kind {
+                    MappingKind::Anonymous { page_count } => (page_count, true),
+                    MappingKind::Device { page_count } | MappingKind::Shared { page_count } => {
+                        (page_count, false)
+                    }
+                };
+                // Mappings are released whole, so huge pages never need a
+                // split here and the unmap cannot fail. Anonymous frames were
+                // allocated from the PMM by mem_map.
+                let _ = address_space.unmap_user_range(
+                    VirtAddr::new(base),
+                    page_count,
+                    &mut alloc,
+                    owns_frames,
+                );
+            }
+        });
+    }
+
+    /// Returns a snapshot of the process's credentials.
+    pub fn cred(&self) -> Arc<Credentials> {
+        self.cred.lock().clone()
     }
 
-    /// Replace the address space (for `execve`). Returns the old address space
-    /// which will be dropped by the caller, freeing its PML4 and page tables.
-    /// Also updates the cached `user_cr3`.
-    pub(crate) fn replace_address_space(
+    /// Applies `f` to a copy of the credentials and installs the copy if
+    /// `f` succeeds. All threads of the process see the change.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error from `f`, leaving the credentials unchanged.
+    pub fn update_cred<T, E>(
         &self,
-        new_space: AddressSpace<PageTableMapper>,
-    ) -> AddressSpace<PageTableMapper> {
-        let new_cr3 = new_space.root_phys();
-        let mut guard = self.address_space.lock();
-        let old = core::mem::replace(&mut *guard, new_space);
-        self.user_cr3.store(new_cr3.as_u64(), Ordering::Release);
-        old
+        f: impl FnOnce(&mut Credentials) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let mut cred = self.cred.lock();
+        let mut new = Credentials::clone(&cred);
+        let ret = f(&mut new)?;
+        *cred = Arc::new(new);
+        Ok(ret)
+    }
+
+    /// Replaces the credentials outright, bypassing the transition rules.
+    ///
+    /// For exec and spawn, which have already applied them.
+    pub(crate) fn set_cred(&self, cred: Credentials) {
+        *self.cred.lock() = Arc::new(cred);
+    }
+
+    /// Returns `true` if `other` shares this process's address space
+    /// (i.e. they are threads created with `CLONE_VM`).
+    pub fn shares_address_space(&self, other: &Process) -> bool {
+        Arc::ptr_eq(&self.address_space, &other.address_space)
+    }
+
+    /// Returns the cached shadow PML4 physical address used under KPTI.
+    #[cfg(hadron_kpti)]
+    pub fn user_shadow_cr3(&self) -> PhysAddr {
+        PhysAddr::new(self.user_shadow_cr3.load(Ordering::Acquire))
+    }
+
+    /// Loads this process's page tables into CR3 on the current CPU,
+    /// tagged with its address space's PCID, and returns the CR3 value
+    /// loaded.
+    ///
+    /// # Safety
+    ///
+    /// GS must hold the kernel per-CPU base. The caller must switch back to
+    /// the kernel CR3 before the process can exit.
+    pub(crate) unsafe fn load_user_cr3(&self) -> u64 {
+        let space = self.address_space.lock();
+        USER_SPACE_PROCESS
+            .get()
+            .store(core::ptr::from_ref(self).cast_mut(), Ordering::Release);
+        USER_SPACE_ROOT
+            .get()
+            .store(space.root_phys().as_u64(), Ordering::Release);
+        // SAFETY: The address space copies the kernel upper half and stays
+        // alive while the process does (caller contract).
+        unsafe { crate::arch::x86_64::pcid::switch_to(&space) }
+    }
+
+    /// Replace the address space (for `execve`), freeing the old image's
+    /// `mem_map` mappings, PML4 and page tables. Also updates the cached
+    /// shadow PML4 address.
+    ///
+    /// I/O ring operations submitted under the old image are cancelled, and
+    /// any that are copying through it finish before it is torn down.
+    pub(crate) fn replace_address_space(&self, new_space: AddressSpace<PageTableMapper>) {
+        #[cfg(hadron_kpti)]
+        let new_shadow_cr3 = new_space
+            .shadow_root_phys()
+            .unwrap_or(new_space.root_phys());
+        let mut image = self.user_image.write();
+        image.cancel();
+        *image = Arc::new(CancelToken::new());
+        self.unmap_user_mappings();
+        self.mmap_mappings.lock().clear();
+        let old = core::mem::replace(&mut *self.address_space.lock(), new_space);
+        #[cfg(hadron_kpti)]
+        self.user_shadow_cr3
+            .store(new_shadow_cr3.as_u64(), Ordering::Release);
+        drop(old);
+    }
+
+    /// Adopts the user layout of a freshly loaded image: resets the mmap
+    /// allocator to the layout's mmap region, the program break to
+    /// `brk_start`, and the main stack to its initial extent.
+    ///
+    /// Called after exec; the state is shared with `CLONE_VM` threads.
+    pub(crate) fn set_user_layout(&self, layout: &UserLayout, brk_start: u64) {
+        let mmap_region = VirtRegion::new(VirtAddr::new(layout.mmap_base), layout.mmap_size());
+        *self.mmap_alloc.lock() = FreeRegionAllocator::new(mmap_region);
+        *self.program_break.lock() = brk_start;
+        *self.user_stack.lock() = layout.initial_stack();
     }
 
     /// Creates a new process with the given address space and parent PID.
     ///
     /// The process group ID is initialized to the process's own PID.
     /// The session ID is inherited from the parent, or set to own PID if init.
+    /// Credentials are copied from the parent; init runs as root.
+    /// User regions start out at [`UserLayout::FIXED`]; the exec path
+    /// replaces them via [`set_user_layout`](Self::set_user_layout).
     pub fn new(address_space: AddressSpace<PageTableMapper>, parent_pid: Option<Pid>) -> Self {
-        let user_cr3 = address_space.root_phys();
-        let mmap_region = VirtRegion::new(VirtAddr::new(USER_MMAP_BASE), USER_MMAP_MAX_SIZE);
+        #[cfg(hadron_kpti)]
+        let user_shadow_cr3 = address_space
+            .shadow_root_phys()
+            .unwrap_or(address_space.root_phys());
+        let layout = UserLayout::FIXED;
+        let mmap_region = VirtRegion::new(VirtAddr::new(layout.mmap_base), layout.mmap_size());
         let pid = Pid::new(NEXT_PID.fetch_add(1, Ordering::Relaxed));
 
         // Inherit session ID from parent, or use own PID for session leaders.
-        let session = parent_pid
-            .and_then(|ppid| ProcessTable::lookup(ppid))
+        let parent = parent_pid.and_then(|ppid| ProcessTable::lookup(ppid));
+        let session = parent
+            .as_ref()
             .map(|p| p.session_id.load(Ordering::Acquire))
             .unwrap_or(pid.as_u32());
+        let oom_score_adj = parent
+            .as_ref()
+            .map_or(0, |p| p.oom_score_adj.load(Ordering::Relaxed));
+        let nice = parent
+            .as_ref()
+            .map_or(0, |p| p.nice.load(Ordering::Relaxed));
+        let affinity = parent
+            .as_ref()
+            .map_or(u64::MAX, |p| p.affinity.load(Ordering::Relaxed));
+        let sched_policy = parent.as_ref().map_or(SchedPolicy::Normal, |p| {
+            inherited_policy(&p.sched_policy.lock())
+        });
+        let cred = parent
+            .as_ref()
+            .map_or_else(|| Arc::new(Credentials::root()), |p| p.cred());
 
         Self {
             pid,
-            parent_pid,
+            parent: AtomicU32::new(parent_pid.map_or(0, Pid::as_u32)),
+            child_subreaper: AtomicBool::new(false),
             pgid: AtomicU32::new(pid.as_u32()),
             session_id: AtomicU32::new(session),
-            user_cr3: AtomicU64::new(user_cr3.as_u64()),
+            #[cfg(hadron_kpti)]
+            user_shadow_cr3: AtomicU64::new(user_shadow_cr3.as_u64()),
             address_space: Arc::new(SpinLock::leveled("address_space", 3, address_space)),
+            user_image: Arc::new(RwLock::leveled(
+                "user_image",
+                2,
+                Arc::new(CancelToken::new()),
+            )),
             fd_table: Arc::new(SpinLock::leveled("fd_table", 4, FileDescriptorTable::new())),
             mmap_alloc: Arc::new(SpinLock::leveled(
                 "mmap_alloc",
@@ -591,19 +978,33 @@ impl Process {
             signals: signal::SignalState::new(),
             exit_status: SpinLock::leveled("exit_status", 4, None),
             exit_notify: HeapWaitQueue::new(),
+            child_notify: HeapWaitQueue::new(),
             cwd: SpinLock::leveled("cwd", 4, String::from("/")),
             program_break: Arc::new(SpinLock::leveled("program_break", 4, 0)),
+            user_stack: Arc::new(SpinLock::leveled("user_stack", 4, layout.initial_stack())),
             children: SpinLock::leveled("children", 4, Vec::new()),
             exe_path: SpinLock::leveled("exe_path", 4, String::from("<unknown>")),
+            oom_score_adj: AtomicI32::new(oom_score_adj),
+            nice: AtomicI32::new(nice),
+            affinity: AtomicU64::new(affinity),
+            sched_policy: SpinLock::leveled("sched_policy", 4, sched_policy),
+            cred: Arc::new(SpinLock::leveled("cred", 4, cred)),
+            fs_base: AtomicU64::new(0),
+            cpu_time: CpuTimeCounter::new(),
+            group_cpu_time: Arc::new(GroupCpuTime::new()),
+            timers: Arc::new(timers::ProcessTimers::new(pid)),
         }
     }
 
     /// Creates a new thread that shares state with `parent` based on `flags`.
     ///
-    /// `CLONE_VM`: shares address space, mmap state, and program break.
+    /// `CLONE_VM`: shares address space, mmap state, program break, and
+    /// main stack.
     /// `CLONE_FILES`: shares file descriptor table.
     ///
-    /// The new thread gets its own PID, signal state, and exit status.
+    /// The new thread gets its own PID, signal state, exit status, and
+    /// thread CPU time, and always shares the parent's credentials, process
+    /// CPU time and interval timers.
     /// Returns the new Process (not yet registered or spawned).
     pub(crate) fn clone_thread(parent: &Process, flags: usize) -> Self {
         use hadron_syscall::{CLONE_FILES, CLONE_VM};
@@ -611,21 +1012,23 @@ impl Process {
         let pid = Pid::new(NEXT_PID.fetch_add(1, Ordering::Relaxed));
         let pgid = parent.pgid.load(Ordering::Acquire);
         let session = parent.session_id.load(Ordering::Acquire);
-        let user_cr3_val = parent.user_cr3.load(Ordering::Acquire);
-
-        // CLONE_VM: share address space, mmap state, and program break.
-        let (address_space, mmap_alloc, mmap_mappings, program_break) = if flags & CLONE_VM != 0 {
-            (
-                Arc::clone(&parent.address_space),
-                Arc::clone(&parent.mmap_alloc),
-                Arc::clone(&parent.mmap_mappings),
-                Arc::clone(&parent.program_break),
-            )
-        } else {
-            // Non-VM-sharing clone is not supported (would require CoW page tables).
-            // Caller must validate flags before calling this.
-            panic!("clone_thread requires CLONE_VM");
-        };
+
+        // CLONE_VM: share address space, mmap state, program break, and stack.
+        let (address_space, user_image, mmap_alloc, mmap_mappings, program_break, user_stack) =
+            if flags & CLONE_VM != 0 {
+                (
+                    Arc::clone(&parent.address_space),
+                    Arc::clone(&parent.user_image),
+                    Arc::clone(&parent.mmap_alloc),
+                    Arc::clone(&parent.mmap_mappings),
+                    Arc::clone(&parent.program_break),
+                    Arc::clone(&parent.user_stack),
+                )
+            } else {
+                // Non-VM-sharing clone is not supported (would require CoW page tables).
+                // Caller must validate flags before calling this.
+                panic!("clone_thread requires CLONE_VM");
+            };
 
         // CLONE_FILES: share fd table.
         let fd_table = if flags & CLONE_FILES != 0 {
@@ -638,31 +1041,62 @@ impl Process {
 
         Self {
             pid,
-            parent_pid: Some(parent.pid),
+            parent: AtomicU32::new(parent.pid.as_u32()),
+            child_subreaper: AtomicBool::new(false),
             pgid: AtomicU32::new(pgid),
             session_id: AtomicU32::new(session),
-            user_cr3: AtomicU64::new(user_cr3_val),
+            #[cfg(hadron_kpti)]
+            user_shadow_cr3: AtomicU64::new(parent.user_shadow_cr3.load(Ordering::Acquire)),
             address_space,
+            user_image,
             fd_table,
             mmap_alloc,
             mmap_mappings,
             signals: signal::SignalState::new(),
             exit_status: SpinLock::leveled("exit_status", 4, None),
             exit_notify: HeapWaitQueue::new(),
+            child_notify: HeapWaitQueue::new(),
             cwd: SpinLock::leveled("cwd", 4, parent.cwd.lock().clone()),
             program_break,
+            user_stack,
             children: SpinLock::leveled("children", 4, Vec::new()),
             exe_path: SpinLock::leveled("exe_path", 4, parent.exe_path.lock().clone()),
+            oom_score_adj: AtomicI32::new(parent.oom_score_adj.load(Ordering::Relaxed)),
+            nice: AtomicI32::new(parent.nice.load(Ordering::Relaxed)),
+            affinity: AtomicU64::new(parent.affinity.load(Ordering::Relaxed)),
+            sched_policy: SpinLock::leveled(
+                "sched_policy",
+                4,
+                inherited_policy(&parent.sched_policy.lock()),
+            ),
+            cred: Arc::clone(&parent.cred),
+            fs_base: AtomicU64::new(parent.fs_base.load(Ordering::Relaxed)),
+            cpu_time: CpuTimeCounter::new(),
+            group_cpu_time: Arc::clone(&parent.group_cpu_time),
+            timers: Arc::clone(&parent.timers),
         }
     }
 }
 
+/// Returns the scheduling class a new thread inherits from `parent`.
+///
+/// Deadline reservations are admitted per thread, so they do not carry over.
+fn inherited_policy(parent: &SchedPolicy) -> SchedPolicy {
+    match parent {
+        SchedPolicy::Deadline(_) => SchedPolicy::Normal,
+        policy => *policy,
+    }
+}
+
 impl Drop for Process {
     fn drop(&mut self) {
         kdebug!(
             "Process {}: dropping (address space will be freed)",
             self.pid
         );
+        // Reclaim any mappings not already released at exit (e.g. the last
+        // thread sharing this address space was a zombie until now).
+        self.release_user_mappings();
         // AddressSpace::Drop fires automatically, freeing the PML4 frame
         // via the dealloc_fn stored at construction time.
     }
@@ -788,8 +1222,8 @@ pub unsafe fn terminate_current_process_from_fault() -> ! {
 
 /// Enters userspace for the first time (initial entry).
 ///
-/// Disables interrupts, sets up GS bases for user/kernel transition,
-/// switches CR3, and calls `enter_userspace_save`.
+/// Disables interrupts, sets up GS bases for user/kernel transition and the
+/// thread's FS base, switches CR3, and calls `enter_userspace_save`.
 fn enter_userspace_first(process: &Process, entry: u64, stack_top: u64) {
     // Compute per-CPU pointer BEFORE clearing GS base (CpuLocal::get()
     // needs current_cpu() which reads GS:[0]).
@@ -804,21 +1238,24 @@ fn enter_userspace_first(process: &Process, entry: u64, stack_top: u64) {
     // SAFETY: Reading IA32_GS_BASE is safe; the MSR contains the current
     // per-CPU data pointer set during boot.
     let percpu_addr = unsafe { IA32_GS_BASE.read() };
+    // Switch to the user address space and record the page tables the entry
+    // stubs switch between (GS must still hold the kernel base).
+    // SAFETY: The kernel upper half is identity-mapped in both address
+    // spaces, and the process returns to the kernel CR3 before it exits.
+    let _user_cr3 = unsafe { process.load_user_cr3() };
+    #[cfg(hadron_kpti)]
+    crate::arch::x86_64::kpti::set_user_cr3(_user_cr3, process.user_shadow_cr3());
     // SAFETY: We are preparing for iretq to userspace. Setting KERNEL_GS_BASE
     // to percpu_addr means swapgs in the syscall entry stub will restore it.
-    // Setting GS_BASE to 0 gives user code a zeroed GS. Switching CR3 to the
-    // user page table is safe because the kernel upper half is identity-mapped
-    // in both address spaces.
+    // Setting GS_BASE to 0 gives user code a zeroed GS.
     unsafe {
         IA32_KERNEL_GS_BASE.write(percpu_addr);
         IA32_GS_BASE.write(0);
+        IA32_FS_BASE.write(process.fs_base.load(Ordering::Relaxed));
 
         // Initialize FPU to clean state for the new process.
         core::arch::asm!("fninit", options(nostack));
 
-        // Switch to user address space.
-        Cr3::write(process.user_cr3());
-
         enter_userspace_save(entry, stack_top, saved_rsp_ptr);
     }
     // Returns here when restore_kernel_context is called.
@@ -826,7 +1263,7 @@ fn enter_userspace_first(process: &Process, entry: u64, stack_top: u64) {
 
 /// Re-enters userspace from saved register state (after preemption).
 ///
-/// Disables interrupts, sets up GS bases, switches CR3, and calls
+/// Disables interrupts, sets up GS and FS bases, switches CR3, and calls
 /// `enter_userspace_resume` with the saved `USER_CONTEXT`.
 fn enter_userspace_resume_wrapper(process: &Process) {
     // Compute per-CPU pointers BEFORE clearing GS base (CpuLocal::get()
@@ -841,10 +1278,14 @@ fn enter_userspace_resume_wrapper(process: &Process) {
     }
 
     let percpu_addr = unsafe { IA32_GS_BASE.read() };
+    // SAFETY: As in `enter_userspace_first`.
+    let _user_cr3 = unsafe { process.load_user_cr3() };
+    #[cfg(hadron_kpti)]
+    crate::arch::x86_64::kpti::set_user_cr3(_user_cr3, process.user_shadow_cr3());
     unsafe {
         IA32_KERNEL_GS_BASE.write(percpu_addr);
         IA32_GS_BASE.write(0);
-        Cr3::write(process.user_cr3());
+        IA32_FS_BASE.write(process.fs_base.load(Ordering::Relaxed));
 
         // Restore user FPU state before entering userspace.
         core::arch::asm!("fxrstor64 [{}]", in(reg) fpu_ctx, options(nostack));
@@ -871,13 +1312,10 @@ fn enter_userspace_resume_wrapper(process: &Process) {
 pub fn spawn_init() {
     let init_elf = read_init_from_vfs();
 
-    let (process, entry, _stack_top) =
-        exec::create_process_from_binary(init_elf, None).expect("failed to load init binary");
-
-    // Write argv onto the init process's stack: ["/bin/init"].
-    let hhdm_offset = crate::mm::hhdm::offset();
-    let stack_top = exec::write_argv_to_init_stack(&*process.address_space(), hhdm_offset)
-        .expect("failed to write argv for init");
+    // argv = ["/bin/init"], no envp; init runs as root.
+    let (process, entry, stack_top) =
+        exec::create_process_from_binary(init_elf, None, &["/bin/init"], &[], &Credentials::root())
+            .expect("failed to load init binary");
 
     // Set up stdin/stdout/stderr pointing to /dev/console.
     {
@@ -901,7 +1339,7 @@ pub fn spawn_init() {
         stack_top
     );
 
-    crate::sched::spawn(process_task(process, entry, stack_top));
+    crate::sched::spawn_user("process", process_task(process, entry, stack_top));
 }
 
 /// Reads the `/bin/init` binary from the VFS.
@@ -982,9 +1420,9 @@ impl AcceptState {
 pub struct SleepState;
 
 impl SleepState {
-    /// Sets the sleep duration for a `TRAP_SLEEP`.
-    pub fn set_ms(ms: u64) {
-        SLEEP_MS.get().store(ms, Ordering::Release);
+    /// Sets the sleep duration for a `TRAP_SLEEP`, in nanoseconds.
+    pub fn set_nanos(nanos: u64) {
+        SLEEP_NS.get().store(nanos, Ordering::Release);
     }
 }
 
@@ -1005,10 +1443,12 @@ impl ExecState {
 pub struct FutexState;
 
 impl FutexState {
-    /// Sets the futex address and expected value for a `TRAP_FUTEX`.
-    pub fn set_params(addr: u64, val: u64) {
+    /// Sets the futex address, expected value and timeout in nanoseconds
+    /// (`u64::MAX` = forever) for a `TRAP_FUTEX`.
+    pub fn set_params(addr: u64, val: u64, timeout_ns: u64) {
         FUTEX_ADDR.get().store(addr, Ordering::Release);
         FUTEX_VAL.get().store(val, Ordering::Release);
+        FUTEX_TIMEOUT_NS.get().store(timeout_ns, Ordering::Release);
     }
 }
 
@@ -1018,11 +1458,64 @@ impl FutexState {
 pub struct PollState;
 
 impl PollState {
-    /// Sets the poll parameters for a `TRAP_POLL`.
-    pub fn set_params(fds_ptr: u64, nfds: u64, timeout_ms: u64) {
+    /// Sets the poll parameters for a `TRAP_POLL`, with the timeout in
+    /// nanoseconds.
+    pub fn set_params(fds_ptr: u64, nfds: u64, timeout_ns: u64) {
         POLL_FDS_PTR.get().store(fds_ptr, Ordering::Release);
         POLL_NFDS.get().store(nfds, Ordering::Release);
-        POLL_TIMEOUT_MS.get().store(timeout_ms, Ordering::Release);
+        POLL_TIMEOUT_NS.get().store(timeout_ns, Ordering::Release);
+        POLL_EPOLL_FD.get().store(u64::MAX, Ordering::Release);
+        POLL_IO_RING_FD.get().store(u64::MAX, Ordering::Release);
+    }
+
+    /// Sets the parameters for an `epoll_wait` `TRAP_POLL`, which waits on
+    /// the interest list of `epfd` and writes up to `max_events` events to
+    /// `events_ptr`. The timeout is in nanoseconds.
+    pub fn set_epoll_params(epfd: u64, events_ptr: u64, max_events: u64, timeout_ns: u64) {
+        POLL_FDS_PTR.get().store(events_ptr, Ordering::Release);
+        POLL_NFDS.get().store(max_events, Ordering::Release);
+        POLL_TIMEOUT_NS.get().store(timeout_ns, Ordering::Release);
+        POLL_EPOLL_FD.get().store(epfd, Ordering::Release);
+        POLL_IO_RING_FD.get().store(u64::MAX, Ordering::Release);
+    }
+
+    /// Sets the parameters for an `io_ring_enter` `TRAP_POLL`, which waits
+    /// for `min_complete` completions on ring `fd` and then returns
+    /// `submitted`. The timeout is in nanoseconds.
+    pub fn set_io_ring_params(fd: u64, submitted: u64, min_complete: u64, timeout_ns: u64) {
+        POLL_FDS_PTR.get().store(submitted, Ordering::Release);
+        POLL_NFDS.get().store(min_complete, Ordering::Release);
+        POLL_TIMEOUT_NS.get().store(timeout_ns, Ordering::Release);
+        POLL_EPOLL_FD.get().store(u64::MAX, Ordering::Release);
+        POLL_IO_RING_FD.get().store(fd, Ordering::Release);
+    }
+}
+
+// ── SigWaitState facade ──────────────────────────────────────────
+
+/// Zero-sized facade for signal wait syscall parameters.
+pub struct SigWaitState;
+
+impl SigWaitState {
+    /// Sets up a `sig_suspend` `TRAP_SIGWAIT`, which ends only when a
+    /// handler runs or the process is killed.
+    pub fn set_suspend() {
+        SIGWAIT_SUSPEND.get().store(true, Ordering::Release);
+        SIGWAIT_SET.get().store(0, Ordering::Release);
+        SIGWAIT_INFO_PTR.get().store(0, Ordering::Release);
+        SIGWAIT_TIMEOUT_NS.get().store(u64::MAX, Ordering::Release);
+    }
+
+    /// Sets up a `sig_timedwait` `TRAP_SIGWAIT` for the signals in `set`,
+    /// writing the accepted signal's info to `info_ptr` if non-zero. The
+    /// timeout is in nanoseconds.
+    pub fn set_timedwait(set: u64, info_ptr: u64, timeout_ns: u64) {
+        SIGWAIT_SUSPEND.get().store(false, Ordering::Release);
+        SIGWAIT_SET.get().store(set, Ordering::Release);
+        SIGWAIT_INFO_PTR.get().store(info_ptr, Ordering::Release);
+        SIGWAIT_TIMEOUT_NS
+            .get()
+            .store(timeout_ns, Ordering::Release);
     }
 }
 
@@ -1030,7 +1523,7 @@ impl PollState {
 enum SignalCheckResult {
     /// No actionable signal; continue normally.
     None,
-    /// Process should be terminated with this exit code.
+    /// Process should be terminated with this wait status.
     Terminate(u64),
     /// A signal was delivered to a userspace handler; USER_CONTEXT was modified
     /// to enter the handler. The caller should `continue` the loop to re-enter
@@ -1049,29 +1542,45 @@ enum SignalCheckResult {
 /// will be handled on the next kernel re-entry after the handler returns
 /// via `sigreturn`.
 fn check_signals(process: &Process) -> SignalCheckResult {
-    while let Some(sig) = process.signals.dequeue() {
-        match process.signals.disposition(sig.0) {
-            signal::SignalDisposition::Default(signal::SignalAction::Terminate) => {
-                // Exit status = 128 + signal number (Unix convention).
-                return SignalCheckResult::Terminate(128 + sig.0 as u64);
+    while let Some(info) = process.signals.dequeue_info() {
+        let signum = info.signo;
+        match process.signals.disposition(signum) {
+            signal::SignalDisposition::Default(
+                signal::SignalAction::Terminate | signal::SignalAction::CoreDump,
+            ) => {
+                return SignalCheckResult::Terminate(wait_status_signaled(signum));
             }
             signal::SignalDisposition::Default(signal::SignalAction::Ignore)
             | signal::SignalDisposition::Ignore => {
                 // Discard the signal, check next.
                 continue;
             }
-            signal::SignalDisposition::Handler(handler_addr) => {
+            signal::SignalDisposition::Default(signal::SignalAction::Stop) => {
+                // The process loop parks a stopped thread before it
+                // re-enters userspace. Remaining signals wait until it is
+                // resumed.
+                if process.signals.stop(signum) {
+                    notify_parent_job_change(process);
+                    return SignalCheckResult::None;
+                }
+                continue;
+            }
+            signal::SignalDisposition::Default(signal::SignalAction::Continue) => {
+                // Posting SIGCONT already resumed the thread.
+                continue;
+            }
+            signal::SignalDisposition::Handler { addr, flags, mask } => {
                 // Deliver to userspace handler by modifying USER_CONTEXT.
-                if deliver_signal_to_handler(process, sig.0, handler_addr) {
+                if deliver_signal_to_handler(process, &info, addr, flags, mask) {
                     return SignalCheckResult::Delivered;
                 }
                 // If delivery failed (e.g. stack overflow), fall through to terminate.
                 kwarn!(
                     "Process {}: signal {} handler delivery failed, terminating",
                     process.pid,
-                    sig.0
+                    signum
                 );
-                return SignalCheckResult::Terminate(128 + sig.0 as u64);
+                return SignalCheckResult::Terminate(wait_status_signaled(signum));
             }
         }
     }
@@ -1081,75 +1590,123 @@ fn check_signals(process: &Process) -> SignalCheckResult {
 /// Push a [`SignalFrame`] onto the user stack and redirect `USER_CONTEXT` to
 /// the signal handler.
 ///
-/// Returns `true` on success, `false` if the user stack is too small for the frame.
-fn deliver_signal_to_handler(process: &Process, signum: usize, handler_addr: u64) -> bool {
+/// The frame goes below the red zone of the interrupted stack, or at the top
+/// of the alternate signal stack for `SA_ONSTACK` handlers. While the handler
+/// runs, `mask` and (unless `SA_NODEFER`) the signal itself are blocked; the
+/// mask in the frame is restored by `sigreturn`.
+///
+/// Returns `true` on success, `false` if the user stack is too small for the
+/// frame or not mapped writable.
+fn deliver_signal_to_handler(
+    process: &Process,
+    info: &signal::SignalInfo,
+    handler_addr: u64,
+    flags: u32,
+    mask: u64,
+) -> bool {
+    use hadron_syscall::{FpState, MContext, UContext};
+
     // SAFETY: USER_CONTEXT is per-CPU, only accessed from this task and the
     // preemption stub (mutually exclusive). We are in the process loop between
     // userspace entries.
     let ctx = unsafe { &mut *USER_CONTEXT.get().get() };
+    let signals = &process.signals;
 
-    // Build the signal frame from the current (about-to-be-resumed) context.
-    let frame = SignalFrame {
-        ret_addr: SIGRETURN_TRAMPOLINE_ADDR,
-        signum: signum as u64,
-        rax: ctx.rax,
-        rbx: ctx.rbx,
-        rcx: ctx.rcx,
-        rdx: ctx.rdx,
-        rsi: ctx.rsi,
-        rdi: ctx.rdi,
-        rbp: ctx.rbp,
-        r8: ctx.r8,
-        r9: ctx.r9,
-        r10: ctx.r10,
-        r11: ctx.r11,
-        r12: ctx.r12,
-        r13: ctx.r13,
-        r14: ctx.r14,
-        r15: ctx.r15,
-        rip: ctx.rip,
-        rsp: ctx.rsp,
-        rflags: ctx.rflags,
-    };
+    // A handler ending `sig_suspend` returns to the mask from before it.
+    let current_mask = signals.get_mask();
+    let restore_mask = signals.take_saved_mask().unwrap_or(current_mask);
+
+    let top = if flags & crate::syscall::SA_ONSTACK != 0 {
+        signals.alt_stack_top(ctx.rsp)
+    } else {
+        None
+    }
+    .unwrap_or_else(|| ctx.rsp.wrapping_sub(RED_ZONE_SIZE));
 
     let frame_size = core::mem::size_of::<SignalFrame>() as u64;
 
-    // Compute new stack pointer: below the frame, 16-byte aligned.
-    // The frame starts at (rsp - frame_size), aligned down to 16 bytes.
-    let new_rsp = (ctx.rsp - frame_size) & !0xF;
+    // The frame starts 16-byte aligned minus 8, so the handler sees the
+    // stack as if `ret_addr` had been pushed by a `call`.
+    let new_rsp = (top.wrapping_sub(frame_size) & !0xF).wrapping_sub(8);
 
     // Basic sanity check: don't let the stack pointer wrap or go too low.
-    if new_rsp < 0x1000 || new_rsp > ctx.rsp {
+    if new_rsp < 0x1000 || new_rsp > top {
         return false;
     }
 
+    // SAFETY: USER_FPU_CONTEXT is per-CPU and holds the state about to be
+    // resumed; `FpState` is a plain 512-byte view of the FXSAVE area.
+    let fpstate = unsafe { (USER_FPU_CONTEXT.get().get() as *const FpState).read() };
+
+    // Build the signal frame from the current (about-to-be-resumed) context.
+    let frame = SignalFrame {
+        ret_addr: SIGRETURN_TRAMPOLINE_ADDR,
+        info: info.to_user(),
+        uc: UContext {
+            stack: signals.alt_stack(ctx.rsp),
+            mcontext: MContext {
+                r8: ctx.r8,
+                r9: ctx.r9,
+                r10: ctx.r10,
+                r11: ctx.r11,
+                r12: ctx.r12,
+                r13: ctx.r13,
+                r14: ctx.r14,
+                r15: ctx.r15,
+                rdi: ctx.rdi,
+                rsi: ctx.rsi,
+                rbp: ctx.rbp,
+                rbx: ctx.rbx,
+                rdx: ctx.rdx,
+                rax: ctx.rax,
+                rcx: ctx.rcx,
+                rsp: ctx.rsp,
+                rip: ctx.rip,
+                rflags: ctx.rflags,
+                fpregs: new_rsp + core::mem::offset_of!(SignalFrame, fpstate) as u64,
+                ..MContext::default()
+            },
+            sigmask: restore_mask,
+            ..UContext::default()
+        },
+        fpstate,
+    };
+
     // Write the SignalFrame to user memory via the process's address space.
     // SAFETY: Switching to user CR3 to access user memory. The kernel upper
     // half is identity-mapped in both address spaces.
     unsafe {
-        use crate::arch::x86_64::registers::control::Cr3;
-        Cr3::write(process.user_cr3());
+        process.load_user_cr3();
     }
 
-    // SAFETY: new_rsp is in user address space and we switched to user CR3.
-    // The frame is repr(C) and we write it atomically via ptr::write.
-    unsafe {
-        core::ptr::write(new_rsp as *mut SignalFrame, frame);
-    }
+    // The copy grows the main stack if the frame lands below its bottom,
+    // and fails if the stack pointer is bogus.
+    let written = crate::syscall::userptr::UserPtr::<SignalFrame>::new(new_rsp as usize)
+        .and_then(|p| p.write(frame));
 
     // Restore kernel CR3.
     unsafe {
-        use crate::arch::x86_64::registers::control::Cr3;
         Cr3::write(TrapContext::kernel_cr3());
     }
 
-    // Redirect userspace execution to the signal handler.
+    if written.is_err() {
+        return false;
+    }
+
+    let mut handler_mask = mask;
+    if flags & crate::syscall::SA_NODEFER == 0 {
+        handler_mask |= signal::sig_bit(info.signo);
+    }
+    signals.set_mask(crate::syscall::SIG_BLOCK, handler_mask);
+
+    // Redirect userspace execution to the signal handler with the
+    // `(signo, *info, *ucontext)` arguments of an `SA_SIGINFO` handler.
+    let frame_addr = new_rsp;
     ctx.rip = handler_addr;
-    ctx.rdi = signum as u64; // First argument: signal number.
+    ctx.rdi = info.signo as u64;
+    ctx.rsi = frame_addr + core::mem::offset_of!(SignalFrame, info) as u64;
+    ctx.rdx = frame_addr + core::mem::offset_of!(SignalFrame, uc) as u64;
     ctx.rsp = new_rsp; // Stack points to the SignalFrame (ret_addr at top).
-    // Clear other argument registers for cleanliness.
-    ctx.rsi = 0;
-    ctx.rdx = 0;
 
     true
 }
@@ -1172,12 +1729,75 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
     let mut first_entry = first_entry_args;
 
     loop {
+        // A stopped thread stays parked here, before re-entering
+        // userspace, until SIGCONT resumes it or SIGKILL kills it.
+        let mut killed = None;
+        while killed.is_none() && process.signals.is_stopped() {
+            // SAFETY: USER_CONTEXT holds the state to resume and is not
+            // touched again until the restore below (see the Preempted
+            // branch).
+            let saved_ctx = unsafe { (*USER_CONTEXT.get().get()).clone() };
+            let saved_fpu = unsafe { (*USER_FPU_CONTEXT.get().get()).clone() };
+
+            core::future::poll_fn(|cx| {
+                let signals = &process.signals;
+                if !signals.is_stopped() || signals.is_pending(crate::syscall::SIGKILL) {
+                    return core::task::Poll::Ready(());
+                }
+                signals.register_waker(cx.waker());
+                if !signals.is_stopped() || signals.is_pending(crate::syscall::SIGKILL) {
+                    return core::task::Poll::Ready(());
+                }
+                core::task::Poll::Pending
+            })
+            .await;
+
+            // SAFETY: No other task accesses USER_CONTEXT between here and
+            // enter_userspace_resume_wrapper (no .await).
+            unsafe {
+                *USER_CONTEXT.get().get() = saved_ctx;
+                *USER_FPU_CONTEXT.get().get() = saved_fpu;
+            }
+
+            // Signals that arrived while stopped are handled now, and may
+            // stop the thread again.
+            if let SignalCheckResult::Terminate(exit_code) = check_signals(&process) {
+                killed = Some(exit_code);
+            }
+        }
+        if let Some(exit_code) = killed {
+            kinfo!("Process {} killed by signal {}", pid, exit_code);
+            *process.exit_status.lock() = Some(exit_code);
+            process.exit_notify.wake_all();
+            break;
+        }
+        if process.signals.take_continue_notify() {
+            notify_parent_job_change(&process);
+        }
+
+        // Report this thread's nice value and affinity to the executor. A
+        // changed mask takes effect when this poll returns, which for a
+        // running thread is the next timer preemption.
+        crate::sched::set_task_weight(hadron_core::sched::nice_to_weight(
+            process.nice.load(Ordering::Relaxed),
+        ));
+        crate::sched::set_task_affinity(process.affinity.load(Ordering::Relaxed));
+        let policy = *process.sched_policy.lock();
+        crate::sched::set_task_policy(policy);
+
         // Set the current process so syscall handlers can access it.
         {
             let mut current = CURRENT_PROCESS.get().lock();
             *current = Some(process.clone());
         }
 
+        // Nothing to preempt for if this thread is alone on the CPU, but a
+        // deadline budget still has to be enforced, and CPU-time timers
+        // only expire when the tick charges the thread's time.
+        crate::sched::tick::user_enter(
+            policy.deadline().is_some() || process.timers.cpu_timer_armed(),
+        );
+        acct::enter_user();
         if let Some((entry, stack_top)) = first_entry.take() {
             enter_userspace_first(&process, entry, stack_top);
         } else {
@@ -1186,6 +1806,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
 
         // We're back from userspace. CR3 and GS were already restored
         // by the syscall/fault/preemption handler.
+        acct::trap_return(&process);
         {
             let mut current = CURRENT_PROCESS.get().lock();
             *current = None;
@@ -1194,13 +1815,15 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
         match TrapReason::from_u8(TRAP_REASON.get().load(Ordering::Acquire)) {
             TrapReason::Exit => {
                 let status = PROCESS_EXIT_STATUS.get().load(Ordering::Acquire);
-                if status == usize::MAX as u64 {
+                let wait_status = if status == usize::MAX as u64 {
                     kinfo!("Process {} killed by fault", pid);
+                    wait_status_signaled(crate::syscall::SIGSEGV)
                 } else {
                     kinfo!("Process {} exited with status {}", pid, status);
-                }
+                    wait_status_exited(status)
+                };
                 // Store exit status and notify waiters.
-                *process.exit_status.lock() = Some(status);
+                *process.exit_status.lock() = Some(wait_status);
                 process.exit_notify.wake_all();
 
                 // In utest mode, translate PID 1's exit into a QEMU signal.
@@ -1240,7 +1863,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 // Check for pending signals before re-entering userspace.
                 match check_signals(&process) {
                     SignalCheckResult::Terminate(exit_code) => {
-                        kinfo!("Process {} killed by signal (exit {})", pid, exit_code);
+                        kinfo!("Process {} killed by signal {}", pid, exit_code);
                         *process.exit_status.lock() = Some(exit_code);
                         process.exit_notify.wake_all();
                         break;
@@ -1254,17 +1877,16 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
             }
             TrapReason::Fault => {
                 kinfo!("Process {} killed by fault", pid);
-                // Faults report as exit status usize::MAX.
-                *process.exit_status.lock() = Some(usize::MAX as u64);
+                // Faults report as a death by SIGSEGV.
+                *process.exit_status.lock() = Some(wait_status_signaled(crate::syscall::SIGSEGV));
                 process.exit_notify.wake_all();
                 break;
             }
             TrapReason::Wait => {
-                // The syscall handler set WAIT_TARGET_PID and WAIT_STATUS_PTR.
-                // We await the child's exit here (async context).
-                let target = Pid::new(WAIT_TARGET_PID.get().load(Ordering::Acquire));
-                let status_ptr = WAIT_STATUS_PTR.get().load(Ordering::Acquire);
-                let wait_flags = WAIT_FLAGS.get().load(Ordering::Acquire);
+                // The syscall handler set the wait target, flags and output
+                // pointers. We await the child's state change here (async
+                // context).
+                let (target, wait_flags, output) = WaitState::params();
 
                 // Snapshot the saved user registers and RSP BEFORE yielding.
                 // SYSCALL_SAVED_REGS and percpu.user_rsp are global statics
@@ -1307,33 +1929,32 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 let saved_fpu = unsafe { (*USER_FPU_CONTEXT.get().get()).clone() };
 
                 // Set foreground PID so Ctrl+C delivers SIGINT to the child.
-                if target.as_u32() != 0 {
+                if let WaitTarget::Pid(target) = target {
                     WaitState::set_foreground(target);
                 }
 
-                let (result, exit_code) = handle_wait(pid, target, wait_flags).await;
+                let waited = handle_wait(pid, target, wait_flags).await;
 
                 // Clear foreground PID.
                 WaitState::set_foreground(Pid::new(0));
 
-                // Write exit status to user memory under user CR3.
-                if status_ptr != 0 && result >= 0 {
+                #[expect(clippy::cast_possible_wrap, reason = "PIDs fit in isize")]
+                let result = match &waited {
+                    Err(errno) => -errno,
+                    Ok(_) if output.waitid => 0,
+                    Ok(event) => event.as_ref().map_or(0, |e| e.pid.as_u32() as isize),
+                };
+
+                // Write the results to user memory under user CR3.
+                if let Ok(event) = waited {
                     // SAFETY: Switching to user CR3 is safe because the kernel
                     // upper half is identity-mapped in both address spaces.
                     unsafe {
-                        Cr3::write(process.user_cr3());
-                    }
-                    let uslice = crate::syscall::userptr::UserSlice::new(
-                        status_ptr as usize,
-                        core::mem::size_of::<u64>(),
-                    );
-                    if let Ok(slice) = uslice {
-                        // SAFETY: The pointer was validated by UserSlice and the
-                        // user address space is mapped (we switched CR3).
-                        unsafe {
-                            core::ptr::write(slice.addr() as *mut u64, exit_code);
-                        }
+                        process.load_user_cr3();
                     }
+                    // Faults here are ignored: the child has been reaped
+                    // either way.
+                    write_wait_output(&output, event.as_ref());
                     // SAFETY: Restore kernel CR3.
                     unsafe {
                         Cr3::write(TrapContext::kernel_cr3());
@@ -1376,7 +1997,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 // Check for pending signals after wait completes.
                 match check_signals(&process) {
                     SignalCheckResult::Terminate(exit_code) => {
-                        kinfo!("Process {} killed by signal (exit {})", pid, exit_code);
+                        kinfo!("Process {} killed by signal {}", pid, exit_code);
                         *process.exit_status.lock() = Some(exit_code);
                         process.exit_notify.wake_all();
                         break;
@@ -1442,43 +2063,41 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                             -crate::syscall::EBADF
                         } else {
                             // Copy user data to kernel buffer under user CR3.
-                            let mut kbuf = alloc::vec![0u8; io_buf_len];
                             // SAFETY: Switching to user CR3 to copy data.
                             unsafe {
-                                Cr3::write(process.user_cr3());
-                            }
-                            let uslice =
-                                crate::syscall::userptr::UserSlice::new(io_buf_ptr, io_buf_len);
-                            if let Ok(slice) = uslice {
-                                // SAFETY: UserSlice validated pointer range under user CR3.
-                                let src = unsafe { slice.as_slice() };
-                                kbuf.copy_from_slice(src);
+                                process.load_user_cr3();
                             }
+                            let copied =
+                                crate::syscall::userptr::UserSlice::new(io_buf_ptr, io_buf_len)
+                                    .and_then(|slice| slice.read_to_vec());
                             // SAFETY: Restore kernel CR3.
                             unsafe {
                                 Cr3::write(TrapContext::kernel_cr3());
                             }
 
-                            match inode.write(offset, &kbuf).await {
-                                Ok(n) => {
-                                    let mut fd_table = process.fd_table.lock();
-                                    if let Some(f) = fd_table.get_mut(io_fd) {
-                                        f.offset += n;
-                                    }
-                                    #[expect(
-                                        clippy::cast_possible_wrap,
-                                        reason = "byte counts are small"
-                                    )]
-                                    {
-                                        n as isize
+                            match copied {
+                                Err(e) => e,
+                                Ok(kbuf) => match inode.write(offset, &kbuf).await {
+                                    Ok(n) => {
+                                        let mut fd_table = process.fd_table.lock();
+                                        if let Some(f) = fd_table.get_mut(io_fd) {
+                                            f.offset += n;
+                                        }
+                                        #[expect(
+                                            clippy::cast_possible_wrap,
+                                            reason = "byte counts are small"
+                                        )]
+                                        {
+                                            n as isize
+                                        }
                                     }
-                                }
-                                Err(e) => {
-                                    if matches!(e, crate::fs::FsError::BrokenPipe) {
-                                        process.signals.post(crate::syscall::SIGPIPE);
+                                    Err(e) => {
+                                        if matches!(e, crate::fs::FsError::BrokenPipe) {
+                                            process.signals.post(crate::syscall::SIGPIPE);
+                                        }
+                                        -e.to_errno()
                                     }
-                                    -e.to_errno()
-                                }
+                                },
                             }
                         }
                     } else {
@@ -1491,96 +2110,103 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                                     // Copy kernel buffer to user memory under user CR3.
                                     // SAFETY: Switching to user CR3.
                                     unsafe {
-                                        Cr3::write(process.user_cr3());
-                                    }
-                                    let uslice =
-                                        crate::syscall::userptr::UserSlice::new(io_buf_ptr, n);
-                                    if let Ok(slice) = uslice {
-                                        // SAFETY: UserSlice validated pointer range under user CR3.
-                                        let dst = unsafe { slice.as_mut_slice() };
-                                        dst.copy_from_slice(&kbuf[..n]);
+                                        process.load_user_cr3();
                                     }
+                                    let copied =
+                                        crate::syscall::userptr::UserSlice::new(io_buf_ptr, n)
+                                            .and_then(|slice| slice.write_from(&kbuf[..n]));
                                     // SAFETY: Restore kernel CR3.
                                     unsafe {
                                         Cr3::write(TrapContext::kernel_cr3());
                                     }
-                                    let mut fd_table = process.fd_table.lock();
-                                    if let Some(f) = fd_table.get_mut(io_fd) {
-                                        f.offset += n;
-                                    }
-                                    drop(fd_table);
-
-                                    // Dequeue SCM_RIGHTS fds if this is a recvmsg call.
-                                    let cmsg_ptr =
-                                        IO_CMSG_PTR.get().load(Ordering::Acquire) as usize;
-                                    let cmsg_len =
-                                        IO_CMSG_LEN.get().load(Ordering::Acquire) as usize;
-                                    let msg_ptr = IO_MSG_PTR.get().load(Ordering::Acquire) as usize;
-                                    if cmsg_ptr != 0 && cmsg_len >= 24 {
-                                        // CMSG_SPACE(sizeof(int)) = 24 bytes per fd.
-                                        let mut written: usize = 0;
-                                        while written + 24 <= cmsg_len {
-                                            let Some(recv_inode) = inode.dequeue_recv_fd() else {
-                                                break;
-                                            };
-                                            let new_fd = process.fd_table.lock().open(
-                                                recv_inode,
-                                                crate::fs::file::OpenFlags::READ
-                                                    | crate::fs::file::OpenFlags::WRITE,
-                                            );
-                                            // Write cmsg header + fd under user CR3.
-                                            // SAFETY: Switching to user CR3.
-                                            unsafe {
-                                                Cr3::write(process.user_cr3());
-                                            }
-                                            let cmsg_offset = cmsg_ptr + written;
-                                            // Each cmsg is: [cmsg_len:u64=20][level:i32=1][type:i32=1][fd:i32]
-                                            if let Ok(sl) = crate::syscall::userptr::UserSlice::new(
-                                                cmsg_offset,
-                                                20,
-                                            ) {
-                                                let buf = unsafe { sl.as_mut_slice() };
-                                                buf[0..8].copy_from_slice(&20u64.to_le_bytes());
-                                                buf[8..12].copy_from_slice(&1i32.to_le_bytes());
-                                                buf[12..16].copy_from_slice(&1i32.to_le_bytes());
-                                                buf[16..20].copy_from_slice(
-                                                    &(new_fd.as_u32() as i32).to_le_bytes(),
-                                                );
-                                            }
-                                            // SAFETY: Restore kernel CR3.
-                                            unsafe {
-                                                Cr3::write(TrapContext::kernel_cr3());
-                                            }
-                                            written += 24; // CMSG_SPACE(4)
+                                    if let Err(e) = copied {
+                                        e
+                                    } else {
+                                        let mut fd_table = process.fd_table.lock();
+                                        if let Some(f) = fd_table.get_mut(io_fd) {
+                                            f.offset += n;
                                         }
-                                        // Update msg_controllen at byte offset 40 of the msghdr.
-                                        if msg_ptr != 0 {
-                                            // SAFETY: Switching to user CR3.
-                                            unsafe {
-                                                Cr3::write(process.user_cr3());
-                                            }
-                                            if let Ok(sl) = crate::syscall::userptr::UserSlice::new(
-                                                msg_ptr + 40,
-                                                8,
-                                            ) {
-                                                let buf = unsafe { sl.as_mut_slice() };
-                                                buf.copy_from_slice(
-                                                    &(written as u64).to_le_bytes(),
+                                        drop(fd_table);
+
+                                        // Dequeue SCM_RIGHTS fds if this is a recvmsg call.
+                                        let cmsg_ptr =
+                                            IO_CMSG_PTR.get().load(Ordering::Acquire) as usize;
+                                        let cmsg_len =
+                                            IO_CMSG_LEN.get().load(Ordering::Acquire) as usize;
+                                        let msg_ptr =
+                                            IO_MSG_PTR.get().load(Ordering::Acquire) as usize;
+                                        if cmsg_ptr != 0 && cmsg_len >= 24 {
+                                            // CMSG_SPACE(sizeof(int)) = 24 bytes per fd.
+                                            let mut written: usize = 0;
+                                            while written + 24 <= cmsg_len {
+                                                let Some(recv_inode) = inode.dequeue_recv_fd()
+                                                else {
+                                                    break;
+                                                };
+                                                let new_fd = process.fd_table.lock().open(
+                                                    recv_inode,
+                                                    crate::fs::file::OpenFlags::READ
+                                                        | crate::fs::file::OpenFlags::WRITE,
                                                 );
+                                                // Write cmsg header + fd under user CR3.
+                                                // SAFETY: Switching to user CR3.
+                                                unsafe {
+                                                    process.load_user_cr3();
+                                                }
+                                                let cmsg_offset = cmsg_ptr + written;
+                                                // Each cmsg is: [cmsg_len:u64=20][level:i32=1][type:i32=1][fd:i32]
+                                                if let Ok(sl) =
+                                                    crate::syscall::userptr::UserSlice::new(
+                                                        cmsg_offset,
+                                                        20,
+                                                    )
+                                                {
+                                                    let mut buf = [0u8; 20];
+                                                    buf[0..8].copy_from_slice(&20u64.to_le_bytes());
+                                                    buf[8..12].copy_from_slice(&1i32.to_le_bytes());
+                                                    buf[12..16]
+                                                        .copy_from_slice(&1i32.to_le_bytes());
+                                                    buf[16..20].copy_from_slice(
+                                                        &(new_fd.as_u32() as i32).to_le_bytes(),
+                                                    );
+                                                    let _ = sl.write_from(&buf);
+                                                }
+                                                // SAFETY: Restore kernel CR3.
+                                                unsafe {
+                                                    Cr3::write(TrapContext::kernel_cr3());
+                                                }
+                                                written += 24; // CMSG_SPACE(4)
                                             }
-                                            // SAFETY: Restore kernel CR3.
-                                            unsafe {
-                                                Cr3::write(TrapContext::kernel_cr3());
+                                            // Update msg_controllen at byte offset 40 of the msghdr.
+                                            if msg_ptr != 0 {
+                                                // SAFETY: Switching to user CR3.
+                                                unsafe {
+                                                    process.load_user_cr3();
+                                                }
+                                                if let Ok(sl) =
+                                                    crate::syscall::userptr::UserSlice::new(
+                                                        msg_ptr + 40,
+                                                        8,
+                                                    )
+                                                {
+                                                    let _ = sl.write_from(
+                                                        &(written as u64).to_le_bytes(),
+                                                    );
+                                                }
+                                                // SAFETY: Restore kernel CR3.
+                                                unsafe {
+                                                    Cr3::write(TrapContext::kernel_cr3());
+                                                }
                                             }
                                         }
-                                    }
 
-                                    #[expect(
-                                        clippy::cast_possible_wrap,
-                                        reason = "byte counts are small"
-                                    )]
-                                    {
-                                        n as isize
+                                        #[expect(
+                                            clippy::cast_possible_wrap,
+                                            reason = "byte counts are small"
+                                        )]
+                                        {
+                                            n as isize
+                                        }
                                     }
                                 }
                                 Err(e) => {
@@ -1629,7 +2255,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 // Check for pending signals after I/O completes.
                 match check_signals(&process) {
                     SignalCheckResult::Terminate(exit_code) => {
-                        kinfo!("Process {} killed by signal (exit {})", pid, exit_code);
+                        kinfo!("Process {} killed by signal {}", pid, exit_code);
                         *process.exit_status.lock() = Some(exit_code);
                         process.exit_notify.wake_all();
                         break;
@@ -1733,7 +2359,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 // Check for pending signals.
                 match check_signals(&process) {
                     SignalCheckResult::Terminate(exit_code) => {
-                        kinfo!("Process {} killed by signal (exit {})", pid, exit_code);
+                        kinfo!("Process {} killed by signal {}", pid, exit_code);
                         *process.exit_status.lock() = Some(exit_code);
                         process.exit_notify.wake_all();
                         break;
@@ -1743,7 +2369,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 continue;
             }
             TrapReason::Sleep => {
-                let sleep_ms = SLEEP_MS.get().load(Ordering::Acquire);
+                let sleep_ns = SLEEP_NS.get().load(Ordering::Acquire);
 
                 // Snapshot saved user registers (same pattern as TRAP_IO).
                 // SAFETY: SYSCALL_SAVED_REGS is only written by syscall entry
@@ -1781,7 +2407,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 let saved_fpu = unsafe { (*USER_FPU_CONTEXT.get().get()).clone() };
 
                 // Sleep for the requested duration.
-                crate::sched::primitives::sleep_ms(sleep_ms).await;
+                crate::sched::primitives::sleep_nanos(sleep_ns).await;
 
                 // Restore FPU state after sleep.
                 unsafe {
@@ -1814,7 +2440,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 // Check for pending signals after sleep completes.
                 match check_signals(&process) {
                     SignalCheckResult::Terminate(exit_code) => {
-                        kinfo!("Process {} killed by signal (exit {})", pid, exit_code);
+                        kinfo!("Process {} killed by signal {}", pid, exit_code);
                         *process.exit_status.lock() = Some(exit_code);
                         process.exit_notify.wake_all();
                         break;
@@ -1832,7 +2458,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
 
                 // Read SpawnInfo from user memory under user CR3.
                 unsafe {
-                    Cr3::write(process.user_cr3());
+                    process.load_user_cr3();
                 }
                 let exec_result = exec::handle_execve(&process, exec_info_ptr, exec_info_len);
                 // Restore kernel CR3.
@@ -1843,7 +2469,11 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 match exec_result {
                     Ok((new_entry, new_stack_top)) => {
                         // Reset signal handlers to SIG_DFL.
-                        process.signals.reset_handlers();
+                        process.signals.reset_for_exec();
+
+                        // timer_create timers do not survive exec;
+                        // setitimer timers do.
+                        process.timers.delete_posix();
 
                         // Close CLOEXEC file descriptors.
                         {
@@ -1851,18 +2481,8 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                             fd_table.close_cloexec();
                         }
 
-                        // Reset mmap state.
-                        {
-                            let mut mmap = process.mmap_alloc.lock();
-                            let mmap_region =
-                                VirtRegion::new(VirtAddr::new(USER_MMAP_BASE), USER_MMAP_MAX_SIZE);
-                            *mmap = FreeRegionAllocator::new(mmap_region);
-                        }
-                        {
-                            let mut mappings = process.mmap_mappings.lock();
-                            mappings.clear();
-                        }
-                        *process.program_break.lock() = 0;
+                        // mmap state, program break, and stack were reset to
+                        // the new image's layout by handle_execve.
 
                         // Set up USER_CONTEXT for the new entry point.
                         unsafe {
@@ -1948,6 +2568,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
             TrapReason::Futex => {
                 let futex_addr = FUTEX_ADDR.get().load(Ordering::Acquire) as usize;
                 let futex_val = FUTEX_VAL.get().load(Ordering::Acquire) as u32;
+                let timeout_ns = FUTEX_TIMEOUT_NS.get().load(Ordering::Acquire);
 
                 // Snapshot saved user registers (same pattern as TRAP_SLEEP).
                 // SAFETY: SYSCALL_SAVED_REGS is only written by syscall entry
@@ -1984,14 +2605,27 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 }
                 let saved_fpu = unsafe { (*USER_FPU_CONTEXT.get().get()).clone() };
 
-                // Wait until woken by futex_wake.
+                // Arm a high-resolution timeout, unless waiting forever.
+                let mut timeout = (timeout_ns != u64::MAX).then(|| {
+                    Timer::at_nanos(crate::sched::primitives::timeout_deadline(timeout_ns))
+                });
+
+                // Wait until woken by futex_wake or the timeout.
                 // The poll_fn checks the futex condition under user CR3 and
                 // registers a waker. If the value has changed, we return
                 // immediately (spurious wakeup is fine per POSIX).
-                core::future::poll_fn(|cx| {
+                let timed_out = core::future::poll_fn(|cx| {
+                    if let Some(timer) = &mut timeout {
+                        if timer_expired(timer) {
+                            crate::ipc::futex::futex_wait_cancel(futex_addr, cx.waker());
+                            return core::task::Poll::Ready(true);
+                        }
+                        timer.register(cx.waker());
+                    }
+
                     // Switch to user CR3 to read the user futex word.
                     unsafe {
-                        Cr3::write(process.user_cr3());
+                        process.load_user_cr3();
                     }
                     let should_sleep =
                         crate::ipc::futex::futex_wait_check(futex_addr, futex_val, cx.waker());
@@ -2002,7 +2636,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                     if should_sleep {
                         core::task::Poll::Pending
                     } else {
-                        core::task::Poll::Ready(())
+                        core::task::Poll::Ready(false)
                     }
                 })
                 .await;
@@ -2012,7 +2646,8 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                     *USER_FPU_CONTEXT.get().get() = saved_fpu;
                 }
 
-                // Restore user registers, returning 0 (success) in rax.
+                // Restore user registers, returning 0 (success) or
+                // -ETIMEDOUT in rax.
                 unsafe {
                     let ctx = &mut *USER_CONTEXT.get().get();
                     ctx.rip = saved_rip;
@@ -2024,7 +2659,11 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                     ctx.r13 = saved_r13;
                     ctx.r14 = saved_r14;
                     ctx.r15 = saved_r15;
-                    ctx.rax = 0; // Futex wait returned successfully
+                    ctx.rax = if timed_out {
+                        (-crate::syscall::ETIMEDOUT) as u64
+                    } else {
+                        0
+                    };
                     ctx.rcx = 0;
                     ctx.rdx = 0;
                     ctx.rsi = 0;
@@ -2038,7 +2677,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 // Check for pending signals after futex wait completes.
                 match check_signals(&process) {
                     SignalCheckResult::Terminate(exit_code) => {
-                        kinfo!("Process {} killed by signal (exit {})", pid, exit_code);
+                        kinfo!("Process {} killed by signal {}", pid, exit_code);
                         *process.exit_status.lock() = Some(exit_code);
                         process.exit_notify.wake_all();
                         break;
@@ -2050,7 +2689,9 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
             TrapReason::Poll => {
                 let poll_fds_ptr = POLL_FDS_PTR.get().load(Ordering::Acquire) as usize;
                 let poll_nfds = POLL_NFDS.get().load(Ordering::Acquire) as usize;
-                let poll_timeout = POLL_TIMEOUT_MS.get().load(Ordering::Acquire);
+                let poll_timeout = POLL_TIMEOUT_NS.get().load(Ordering::Acquire);
+                let poll_epoll_fd = POLL_EPOLL_FD.get().load(Ordering::Acquire);
+                let poll_io_ring_fd = POLL_IO_RING_FD.get().load(Ordering::Acquire);
 
                 // Snapshot saved user registers (same pattern as TRAP_FUTEX).
                 // SAFETY: SYSCALL_SAVED_REGS is only written by syscall entry
@@ -2087,111 +2728,192 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 }
                 let saved_fpu = unsafe { (*USER_FPU_CONTEXT.get().get()).clone() };
 
-                // Compute deadline: current ticks + timeout_ms (1 tick = 1ms).
-                let deadline = if poll_timeout == u64::MAX {
-                    u64::MAX
-                } else {
-                    crate::time::Time::timer_ticks() + poll_timeout
-                };
-
-                // Phase 1: clone inodes while holding fd_table.
-                //
-                // poll_readiness() on unix sockets acquires unix_socket (level 3).
-                // fd_table is level 4, so calling poll_readiness() while fd_table
-                // is locked would violate lock ordering.  We collect Arc clones
-                // here — cheap and safe inside the lock — then call
-                // poll_readiness() after releasing it.
-                let inodes: Vec<Option<Arc<dyn Inode>>> = {
-                    // Switch to user CR3 to read the PollFd array.
+                // Arm a high-resolution timeout, unless waiting forever.
+                let mut timeout = (poll_timeout != u64::MAX).then(|| {
+                    Timer::at_nanos(crate::sched::primitives::timeout_deadline(poll_timeout))
+                });
+
+                let ready_count: isize = if poll_io_ring_fd != u64::MAX {
+                    // An `io_ring_enter`: wait for completions, then return
+                    // the number of operations submitted.
+                    #[expect(clippy::cast_possible_truncation, reason = "fd fits in u32")]
+                    let fd = crate::id::Fd::new(poll_io_ring_fd as u32);
+                    let inode = process.fd_table.lock().get(fd).map(|f| f.inode.clone());
+                    let ring = inode
+                        .as_ref()
+                        .and_then(|inode| inode.as_any())
+                        .and_then(|any| any.downcast_ref::<crate::ipc::io_ring::IoRing>());
+                    match ring {
+                        // Closed while the syscall was trapping.
+                        None => -crate::syscall::EBADF,
+                        Some(ring) => {
+                            let ring = ring.state();
+                            core::future::poll_fn(|cx| {
+                                if let Some(timer) = &mut timeout {
+                                    timer.register(cx.waker());
+                                }
+                                // Register before counting (prevents lost wakeups).
+                                ring.register_waker(cx.waker());
+                                if ring.completions() >= poll_nfds
+                                    || timeout.as_ref().is_some_and(timer_expired)
+                                {
+                                    core::task::Poll::Ready(())
+                                } else {
+                                    core::task::Poll::Pending
+                                }
+                            })
+                            .await;
+                            // At most IO_RING_MAX_ENTRIES, so the cast never wraps.
+                            poll_fds_ptr.cast_signed()
+                        }
+                    }
+                } else if poll_epoll_fd == u64::MAX {
+                    // Copy the PollFd array in under user CR3.
                     // SAFETY: user CR3 is valid; kernel upper-half is identity-mapped.
                     unsafe {
-                        Cr3::write(process.user_cr3());
+                        process.load_user_cr3();
                     }
-                    // SAFETY: poll_fds_ptr was validated by UserSlice in the syscall handler.
-                    let poll_fds = unsafe {
-                        core::slice::from_raw_parts(
-                            poll_fds_ptr as *const hadron_syscall::PollFd,
-                            poll_nfds,
-                        )
-                    };
-                    let fd_table = process.fd_table.lock();
-                    let result = poll_fds
-                        .iter()
-                        .map(|pfd| {
-                            fd_table
-                                .get(crate::id::Fd::new(pfd.fd))
-                                .map(|f| f.inode.clone())
-                        })
-                        .collect();
-                    drop(fd_table);
+                    let copied = crate::syscall::userptr::read_user_array::<hadron_syscall::PollFd>(
+                        poll_fds_ptr,
+                        poll_nfds,
+                    );
                     unsafe {
                         Cr3::write(TrapContext::kernel_cr3());
                     }
-                    result
-                };
 
-                // Phase 2: poll readiness outside all locks.
-                #[expect(
-                    clippy::cast_possible_wrap,
-                    reason = "ready_count fits in isize for syscall return"
-                )]
-                let ready_count: isize = core::future::poll_fn(|cx| {
-                    // Register timeout waker so we get woken at deadline.
-                    if deadline != u64::MAX {
-                        crate::sched::timer::register_sleep_waker(deadline, cx.waker().clone());
-                    }
+                    match copied {
+                        Err(e) => e,
+                        Ok(mut poll_fds) => {
+                            // Phase 1: clone inodes while holding fd_table.
+                            //
+                            // poll_readiness() on unix sockets acquires unix_socket (level 3).
+                            // fd_table is level 4, so calling poll_readiness() while fd_table
+                            // is locked would violate lock ordering.  We collect Arc clones
+                            // here — cheap and safe inside the lock — then call
+                            // poll_readiness() after releasing it.
+                            let inodes: Vec<Option<Arc<dyn Inode>>> = {
+                                let fd_table = process.fd_table.lock();
+                                poll_fds
+                                    .iter()
+                                    .map(|pfd| {
+                                        fd_table
+                                            .get(crate::id::Fd::new(pfd.fd))
+                                            .map(|f| f.inode.clone())
+                                    })
+                                    .collect()
+                            };
+
+                            // Phase 2: poll readiness outside all locks.
+                            #[expect(
+                                clippy::cast_possible_wrap,
+                                reason = "ready_count fits in isize for syscall return"
+                            )]
+                            let count: isize = core::future::poll_fn(|cx| {
+                                // Register timeout waker so we get woken at deadline.
+                                if let Some(timer) = &mut timeout {
+                                    timer.register(cx.waker());
+                                }
 
-                    // Switch to user CR3 to write revents back into user memory.
-                    // SAFETY: user CR3 is valid; kernel upper-half is identity-mapped.
-                    unsafe {
-                        Cr3::write(process.user_cr3());
-                    }
+                                let mut count: isize = 0;
 
-                    // SAFETY: poll_fds_ptr was validated by UserSlice in the syscall handler.
-                    let poll_fds = unsafe {
-                        core::slice::from_raw_parts_mut(
-                            poll_fds_ptr as *mut hadron_syscall::PollFd,
-                            poll_nfds,
-                        )
-                    };
+                                // No fd_table lock needed — inodes already cloned above.
+                                for (pfd, inode_opt) in poll_fds.iter_mut().zip(inodes.iter()) {
+                                    pfd.revents = 0;
+                                    match inode_opt {
+                                        None => {
+                                            pfd.revents = crate::syscall::POLLNVAL;
+                                            count += 1;
+                                        }
+                                        Some(inode) => {
+                                            // Register waker BEFORE checking readiness (prevents lost wakeups).
+                                            let readiness = inode.poll_readiness(Some(cx.waker()));
+                                            pfd.revents = readiness
+                                                & (pfd.events
+                                                    | hadron_syscall::POLLERR
+                                                    | hadron_syscall::POLLHUP
+                                                    | hadron_syscall::POLLNVAL);
+                                            if pfd.revents != 0 {
+                                                count += 1;
+                                            }
+                                        }
+                                    }
+                                }
 
-                    let mut count: isize = 0;
+                                if count > 0 || timeout.as_ref().is_some_and(timer_expired) {
+                                    core::task::Poll::Ready(count)
+                                } else {
+                                    core::task::Poll::Pending
+                                }
+                            })
+                            .await;
 
-                    // No fd_table lock needed — inodes already cloned above.
-                    for (pfd, inode_opt) in poll_fds.iter_mut().zip(inodes.iter()) {
-                        pfd.revents = 0;
-                        match inode_opt {
-                            None => {
-                                pfd.revents = crate::syscall::POLLNVAL;
-                                count += 1;
+                            // Write revents back into user memory under user CR3.
+                            // SAFETY: user CR3 is valid; kernel upper-half is identity-mapped.
+                            unsafe {
+                                process.load_user_cr3();
                             }
-                            Some(inode) => {
-                                // Register waker BEFORE checking readiness (prevents lost wakeups).
-                                let readiness = inode.poll_readiness(Some(cx.waker()));
-                                pfd.revents = readiness
-                                    & (pfd.events
-                                        | hadron_syscall::POLLERR
-                                        | hadron_syscall::POLLHUP
-                                        | hadron_syscall::POLLNVAL);
-                                if pfd.revents != 0 {
-                                    count += 1;
-                                }
+                            let written =
+                                crate::syscall::userptr::write_user_array(poll_fds_ptr, &poll_fds);
+                            unsafe {
+                                Cr3::write(TrapContext::kernel_cr3());
+                            }
+                            match written {
+                                Ok(()) => count,
+                                Err(e) => e,
                             }
                         }
                     }
+                } else {
+                    // An `epoll_wait`: wait on the interest list, whose
+                    // wakers are already registered with the watched inodes.
+                    #[expect(clippy::cast_possible_truncation, reason = "fd fits in u32")]
+                    let epfd = crate::id::Fd::new(poll_epoll_fd as u32);
+                    let inode = process.fd_table.lock().get(epfd).map(|f| f.inode.clone());
+                    let epoll = inode
+                        .as_ref()
+                        .and_then(|inode| inode.as_any())
+                        .and_then(|any| any.downcast_ref::<crate::ipc::epoll::Epoll>());
+                    match epoll {
+                        // Closed while the syscall was trapping.
+                        None => -crate::syscall::EBADF,
+                        Some(epoll) => {
+                            let events = core::future::poll_fn(|cx| {
+                                if let Some(timer) = &mut timeout {
+                                    timer.register(cx.waker());
+                                }
+                                // Register before collecting (prevents lost wakeups).
+                                epoll.register_waker(cx.waker());
+                                let events = epoll.collect(poll_nfds);
+                                if !events.is_empty() || timeout.as_ref().is_some_and(timer_expired)
+                                {
+                                    core::task::Poll::Ready(events)
+                                } else {
+                                    core::task::Poll::Pending
+                                }
+                            })
+                            .await;
 
-                    // Switch back to kernel CR3.
-                    unsafe {
-                        Cr3::write(TrapContext::kernel_cr3());
-                    }
-
-                    if count > 0 || crate::time::Time::timer_ticks() >= deadline {
-                        core::task::Poll::Ready(count)
-                    } else {
-                        core::task::Poll::Pending
+                            // Write the events into user memory under user CR3.
+                            // SAFETY: user CR3 is valid; kernel upper-half is identity-mapped.
+                            unsafe {
+                                process.load_user_cr3();
+                            }
+                            let written =
+                                crate::syscall::userptr::write_user_array(poll_fds_ptr, &events);
+                            unsafe {
+                                Cr3::write(TrapContext::kernel_cr3());
+                            }
+                            #[expect(
+                                clippy::cast_possible_wrap,
+                                reason = "event count fits in isize for syscall return"
+                            )]
+                            match written {
+                                Ok(()) => events.len() as isize,
+                                Err(e) => e,
+                            }
+                        }
                     }
-                })
-                .await;
+                };
 
                 // Restore FPU state after poll wait.
                 unsafe {
@@ -2224,7 +2946,7 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 // Check for pending signals after poll completes.
                 match check_signals(&process) {
                     SignalCheckResult::Terminate(exit_code) => {
-                        kinfo!("Process {} killed by signal (exit {})", pid, exit_code);
+                        kinfo!("Process {} killed by signal {}", pid, exit_code);
                         *process.exit_status.lock() = Some(exit_code);
                         process.exit_notify.wake_all();
                         break;
@@ -2233,9 +2955,167 @@ async fn process_task_inner(process: Arc<Process>, first_entry_args: Option<(u64
                 }
                 continue;
             }
+            TrapReason::SigWait => {
+                let wait_set = SIGWAIT_SET.get().load(Ordering::Acquire);
+                let info_ptr = SIGWAIT_INFO_PTR.get().load(Ordering::Acquire) as usize;
+                let timeout_ns = SIGWAIT_TIMEOUT_NS.get().load(Ordering::Acquire);
+                let is_suspend = SIGWAIT_SUSPEND.get().load(Ordering::Acquire);
+
+                // Snapshot saved user registers (same pattern as TRAP_POLL).
+                // SAFETY: SYSCALL_SAVED_REGS is only written by syscall entry
+                // assembly with interrupts masked, and we haven't yielded yet.
+                let (
+                    saved_rip,
+                    saved_rflags,
+                    saved_rbx,
+                    saved_rbp,
+                    saved_r12,
+                    saved_r13,
+                    saved_r14,
+                    saved_r15,
+                    saved_user_rsp,
+                ) = unsafe {
+                    let saved = &*crate::arch::x86_64::syscall::SYSCALL_SAVED_REGS.get().get();
+                    (
+                        saved.user_rip,
+                        saved.user_rflags,
+                        saved.rbx,
+                        saved.rbp,
+                        saved.r12,
+                        saved.r13,
+                        saved.r14,
+                        saved.r15,
+                        crate::percpu::PerCpuState::current().user_rsp,
+                    )
+                };
+
+                // Snapshot user FPU state before the .await.
+                unsafe {
+                    let fpu_ptr = USER_FPU_CONTEXT.get().get() as *mut u8;
+                    core::arch::asm!("fxsave64 [{}]", in(reg) fpu_ptr, options(nostack));
+                }
+                let saved_fpu = unsafe { (*USER_FPU_CONTEXT.get().get()).clone() };
+
+                // Arm a high-resolution timeout, unless waiting forever.
+                let mut timeout = (timeout_ns != u64::MAX).then(|| {
+                    Timer::at_nanos(crate::sched::primitives::timeout_deadline(timeout_ns))
+                });
+
+                // `sig_suspend` only returns once a handler runs, so signals
+                // consumed without one (e.g. default-ignored) restart the wait.
+                let killed = loop {
+                    let accepted = core::future::poll_fn(|cx| {
+                        // Register wakers BEFORE checking (prevents lost wakeups).
+                        process.signals.register_waker(cx.waker());
+                        if let Some(timer) = &mut timeout {
+                            timer.register(cx.waker());
+                        }
+
+                        if let Some(info) = process.signals.dequeue_from(wait_set) {
+                            core::task::Poll::Ready(Some(info))
+                        } else if process.signals.has_pending()
+                            || timeout.as_ref().is_some_and(timer_expired)
+                        {
+                            core::task::Poll::Ready(None)
+                        } else {
+                            core::task::Poll::Pending
+                        }
+                    })
+                    .await;
+
+                    let result: isize = match accepted {
+                        Some(info) => {
+                            // Copy the info out under user CR3.
+                            // SAFETY: user CR3 is valid; kernel upper-half is identity-mapped.
+                            unsafe {
+                                process.load_user_cr3();
+                            }
+                            let written = if info_ptr == 0 {
+                                Ok(())
+                            } else {
+                                crate::syscall::userptr::UserPtr::<hadron_syscall::SigInfo>::new(
+                                    info_ptr,
+                                )
+                                .and_then(|p| p.write(info.to_user()))
+                            };
+                            unsafe {
+                                Cr3::write(TrapContext::kernel_cr3());
+                            }
+                            #[expect(
+                                clippy::cast_possible_wrap,
+                                reason = "signal numbers are at most 64"
+                            )]
+                            match written {
+                                Ok(()) => info.signo as isize,
+                                Err(e) => e,
+                            }
+                        }
+                        None if process.signals.has_pending() => -crate::syscall::EINTR,
+                        None => -crate::syscall::EAGAIN,
+                    };
+
+                    // Restore FPU state after the wait.
+                    unsafe {
+                        *USER_FPU_CONTEXT.get().get() = saved_fpu.clone();
+                    }
+
+                    // Restore user registers, returning the result in rax.
+                    unsafe {
+                        let ctx = &mut *USER_CONTEXT.get().get();
+                        ctx.rip = saved_rip;
+                        ctx.rflags = saved_rflags;
+                        ctx.rsp = saved_user_rsp;
+                        ctx.rbx = saved_rbx;
+                        ctx.rbp = saved_rbp;
+                        ctx.r12 = saved_r12;
+                        ctx.r13 = saved_r13;
+                        ctx.r14 = saved_r14;
+                        ctx.r15 = saved_r15;
+                        ctx.rax = result as u64;
+                        ctx.rcx = 0;
+                        ctx.rdx = 0;
+                        ctx.rsi = 0;
+                        ctx.rdi = 0;
+                        ctx.r8 = 0;
+                        ctx.r9 = 0;
+                        ctx.r10 = 0;
+                        ctx.r11 = 0;
+                    }
+
+                    match check_signals(&process) {
+                        SignalCheckResult::Terminate(exit_code) => break Some(exit_code),
+                        SignalCheckResult::None if is_suspend => continue,
+                        SignalCheckResult::Delivered | SignalCheckResult::None => break None,
+                    }
+                };
+
+                if let Some(exit_code) = killed {
+                    kinfo!("Process {} killed by signal {}", pid, exit_code);
+                    *process.exit_status.lock() = Some(exit_code);
+                    process.exit_notify.wake_all();
+                    break;
+                }
+                continue;
+            }
         }
     }
 
+    // Give back this thread's deadline bandwidth, if it reserved any.
+    let policy = core::mem::take(&mut *process.sched_policy.lock());
+    crate::sched::change_deadline_reservation(policy.deadline(), None);
+
+    // Return mapped memory to the PMM now rather than at reap time.
+    process.release_user_mappings();
+
+    // Stop the process's timers if this was its last thread.
+    if Arc::strong_count(&process.timers) == 1 {
+        process.timers.disarm_all();
+    }
+
+    // Hand our children to a reaper, then tell our own parent.
+    reparent_orphans(&process);
+    notify_parent_exit(&process);
+
     // Process remains in the table as a zombie until reaped by waitpid.
     // The Arc in PROCESS_TABLE keeps the Process alive so handle_wait
     // can still look it up and read exit_status.
@@ -2306,12 +3186,10 @@ pub(crate) async fn process_task_clone(
         fpu.data[25] = 0x1F;
     }
 
-    // If CLONE_SETTLS, set the FS base for the child thread.
+    // If CLONE_SETTLS, set the FS base for the child thread. It is loaded
+    // into the MSR on every entry to userspace.
     if let Some(tls_addr) = tls {
-        // SAFETY: Writing FS_BASE MSR is the standard way to set TLS.
-        unsafe {
-            crate::arch::x86_64::registers::model_specific::IA32_FS_BASE.write(tls_addr);
-        }
+        process.fs_base.store(tls_addr, Ordering::Relaxed);
     }
 
     kinfo!(
@@ -2326,142 +3204,269 @@ pub(crate) async fn process_task_clone(
     process_task_inner(process, None).await;
 }
 
-/// Handles a `TRAP_WAIT` by awaiting the target child's exit.
-///
-/// Returns `(child_pid, exit_code)` on success, or `(-errno, 0)` on failure.
-/// The caller is responsible for writing the exit status to user memory
-/// (requires switching to user CR3).
-#[expect(
-    clippy::cast_possible_wrap,
-    reason = "returning negated errno as isize"
-)]
-async fn handle_wait(parent_pid: Pid, target_pid: Pid, flags: u64) -> (isize, u64) {
-    use hadron_syscall::WNOHANG;
+/// Wait status of a child that exited with `code` (`WIFEXITED`).
+const fn wait_status_exited(code: u64) -> u64 {
+    (code & 0xFF) << 8
+}
 
-    let wnohang = flags as usize & WNOHANG != 0;
+/// Wait status of a child killed by signal `signum` (`WIFSIGNALED`).
+const fn wait_status_signaled(signum: usize) -> u64 {
+    signum as u64
+}
 
-    if target_pid.as_u32() == 0 {
-        // Wait for any child — scan for the first zombie.
-        let children = ProcessTable::children_of(parent_pid);
-        if children.is_empty() {
-            return (-(crate::syscall::ECHILD), 0);
-        }
+/// Wait status of a child stopped by signal `signum` (`WIFSTOPPED`).
+const fn wait_status_stopped(signum: usize) -> u64 {
+    ((signum as u64) << 8) | 0x7F
+}
 
-        // Check once for any zombie child.
-        for &child_pid in &children {
-            if let Some(child) = ProcessTable::lookup(child_pid) {
-                let status = child.exit_status.lock();
-                if let Some(exit_code) = *status {
-                    drop(status);
-                    ProcessTable::unregister(child_pid);
-                    #[expect(clippy::cast_possible_wrap, reason = "PID fits in isize")]
-                    {
-                        return (child_pid.as_u32() as isize, exit_code);
-                    }
-                }
+/// Wait status of a stopped child resumed by `SIGCONT` (`WIFCONTINUED`).
+const WAIT_STATUS_CONTINUED: u64 = 0xFFFF;
+
+/// Tells the parent of `process` that it stopped or continued: wakes its
+/// `task_wait` and sends it `SIGCHLD` unless it set `SA_NOCLDSTOP`.
+fn notify_parent_job_change(process: &Process) {
+    process.exit_notify.wake_all();
+    let Some(parent) = process.parent_pid().and_then(ProcessTable::lookup) else {
+        return;
+    };
+    let signals = &parent.signals;
+    if signals.action(crate::syscall::SIGCHLD).flags & crate::syscall::SA_NOCLDSTOP == 0 {
+        signals.post(crate::syscall::SIGCHLD);
+    }
+}
+
+/// PID of init, which adopts orphans when no subreaper does.
+const INIT_PID: Pid = Pid::new(1);
+
+/// Tells the parent of the exited `process` with `SIGCHLD`.
+///
+/// A process left without a parent, because it was orphaned and nothing
+/// could adopt it, is released at once since nobody will reap it.
+fn notify_parent_exit(process: &Process) {
+    match process.parent_pid().and_then(ProcessTable::lookup) {
+        // Threads are not reported to the thread that created them.
+        Some(parent) if parent.same_thread_group(process) => {}
+        Some(parent) => parent.signals.post(crate::syscall::SIGCHLD),
+        None if process.pid != INIT_PID => ProcessTable::unregister(process.pid),
+        None => {}
+    }
+}
+
+/// Reparents the children of the exited `process` to the nearest living
+/// ancestor that is a child subreaper, or to init.
+///
+/// Children that have already exited are announced to the reaper with
+/// `SIGCHLD`; with no reaper at all they are released.
+fn reparent_orphans(process: &Process) {
+    if process.children.lock().is_empty() {
+        return;
+    }
+    let reaper = find_reaper(process);
+    let exited = ProcessTable::reparent_children(process, reaper.as_ref());
+    match reaper {
+        Some(reaper) => {
+            reaper.child_notify.wake_all();
+            if !exited.is_empty() {
+                reaper.signals.post(crate::syscall::SIGCHLD);
+            }
+        }
+        None => {
+            for pid in exited {
+                ProcessTable::unregister(pid);
             }
         }
+    }
+}
 
-        // WNOHANG: return 0 immediately if no child has exited.
-        if wnohang {
-            return (0, 0);
+/// Returns the process that adopts the children of the exited `process`.
+fn find_reaper(process: &Process) -> Option<Arc<Process>> {
+    let alive = |p: &Arc<Process>| p.pid != process.pid && p.exit_status.lock().is_none();
+    let mut ancestor = process.parent_pid().and_then(ProcessTable::lookup);
+    while let Some(candidate) = ancestor {
+        if candidate.child_subreaper.load(Ordering::Acquire) && alive(&candidate) {
+            return Some(candidate);
         }
+        ancestor = candidate.parent_pid().and_then(ProcessTable::lookup);
+    }
+    ProcessTable::lookup(INIT_PID).filter(alive)
+}
 
-        let (zombie_pid, exit_code) = core::future::poll_fn(|cx| {
-            // Check all children for an already-exited zombie.
-            let children = ProcessTable::children_of(parent_pid);
-            for &child_pid in &children {
-                if let Some(child) = ProcessTable::lookup(child_pid) {
-                    let status = child.exit_status.lock();
-                    if let Some(exit_code) = *status {
-                        return core::task::Poll::Ready((child_pid, exit_code));
-                    }
-                }
-            }
+/// A child state change collected by [`handle_wait`].
+struct WaitEvent {
+    /// PID of the child.
+    pid: Pid,
+    /// Wait status in the Linux encoding.
+    status: u64,
+    /// Real user ID of the child.
+    uid: u32,
+    /// CPU time of the child and of the children it reaped.
+    cpu_times: crate::cputime::CpuTimes,
+}
 
-            // No zombie found — register waker on every child's exit_notify.
-            for &child_pid in &children {
-                if let Some(child) = ProcessTable::lookup(child_pid) {
-                    child.exit_notify.register_waker(cx.waker());
-                }
-            }
+impl WaitEvent {
+    /// Describes the event as a `SIGCHLD` [`SigInfo`](crate::syscall::SigInfo),
+    /// as returned by `waitid`.
+    fn siginfo(&self) -> crate::syscall::SigInfo {
+        use crate::syscall::{CLD_CONTINUED, CLD_EXITED, CLD_KILLED, CLD_STOPPED};
+
+        #[expect(clippy::cast_possible_truncation, reason = "masked to 8 bits")]
+        let (code, status) = match self.status {
+            WAIT_STATUS_CONTINUED => (CLD_CONTINUED, crate::syscall::SIGCONT as i32),
+            s if s & 0xFF == 0x7F => (CLD_STOPPED, ((s >> 8) & 0xFF) as i32),
+            s if s & 0x7F == 0 => (CLD_EXITED, ((s >> 8) & 0xFF) as i32),
+            s => (CLD_KILLED, (s & 0x7F) as i32),
+        };
+        #[expect(clippy::cast_possible_wrap, reason = "SIGCHLD is 17")]
+        crate::syscall::SigInfo {
+            signo: crate::syscall::SIGCHLD as i32,
+            code,
+            pid: self.pid.as_u32(),
+            uid: self.uid,
+            status,
+            ..crate::syscall::SigInfo::default()
+        }
+    }
+}
+
+/// Handles a `TRAP_WAIT` by awaiting a state change of a child selected
+/// by `target`: with `WEXITED` an exit, with `WUNTRACED`/`WCONTINUED` a
+/// stop or continue.
+///
+/// Returns `Ok(None)` if `WNOHANG` is set and nothing has changed, or
+/// `Err(errno)`. An exited child is reaped unless `WNOWAIT` is set. The
+/// caller is responsible for writing the results to user memory (requires
+/// switching to user CR3).
+async fn handle_wait(
+    parent_pid: Pid,
+    target: WaitTarget,
+    flags: u64,
+) -> Result<Option<WaitEvent>, isize> {
+    use hadron_syscall::{WNOHANG, WNOWAIT};
+
+    let flags = flags as usize;
+    let Some(parent) = ProcessTable::lookup(parent_pid) else {
+        return Err(crate::syscall::ECHILD);
+    };
+
+    let children = waitable_children(parent_pid, target);
+    if children.is_empty() {
+        return Err(crate::syscall::ECHILD);
+    }
 
-            // Double-check: a child may have exited between scan and registration.
-            for &child_pid in &children {
-                if let Some(child) = ProcessTable::lookup(child_pid) {
-                    let status = child.exit_status.lock();
-                    if let Some(exit_code) = *status {
-                        return core::task::Poll::Ready((child_pid, exit_code));
+    let event = match poll_children(&children, flags) {
+        Some(event) => event,
+        // WNOHANG: return immediately if no child has changed state.
+        None if flags & WNOHANG != 0 => return Ok(None),
+        None => {
+            core::future::poll_fn(|cx| {
+                // Register on our own child_notify and every child's
+                // exit_notify BEFORE re-checking, so a concurrent
+                // wake_all() is never missed. Children spawned or adopted
+                // while we wait are picked up on the next poll.
+                parent.child_notify.register_waker(cx.waker());
+                let children = waitable_children(parent_pid, target);
+                if children.is_empty() {
+                    return core::task::Poll::Ready(Err(crate::syscall::ECHILD));
+                }
+                for &child_pid in &children {
+                    if let Some(child) = ProcessTable::lookup(child_pid) {
+                        child.exit_notify.register_waker(cx.waker());
                     }
                 }
-            }
+                match poll_children(&children, flags) {
+                    Some(event) => core::task::Poll::Ready(Ok(event)),
+                    None => core::task::Poll::Pending,
+                }
+            })
+            .await?
+        }
+    };
+    let (event, exited) = event;
+    if exited && flags & WNOWAIT == 0 {
+        // Reap: remove child from the process table now that the parent
+        // has collected the exit status.
+        reap_child(&parent, event.pid);
+    }
+    Ok(Some(event))
+}
 
-            core::task::Poll::Pending
-        })
-        .await;
+/// Returns the children of `parent_pid` that `target` selects.
+fn waitable_children(parent_pid: Pid, target: WaitTarget) -> Vec<Pid> {
+    let mut children = ProcessTable::children_of(parent_pid);
+    match target {
+        WaitTarget::Any => {}
+        WaitTarget::Pid(pid) => children.retain(|&c| c == pid),
+        WaitTarget::Group(pgid) => children.retain(|&c| {
+            ProcessTable::lookup(c).is_some_and(|p| p.pgid.load(Ordering::Acquire) == pgid)
+        }),
+    }
+    children
+}
 
-        // Reap the zombie.
-        ProcessTable::unregister(zombie_pid);
-        #[expect(clippy::cast_possible_wrap, reason = "PID fits in isize")]
-        {
-            (zombie_pid.as_u32() as isize, exit_code)
-        }
-    } else {
-        // Wait for a specific child.
-        let child = match ProcessTable::lookup(target_pid) {
-            Some(c) => c,
-            None => return (-(crate::syscall::ECHILD), 0),
+/// Returns the first of `children` with a state change that `flags` asks
+/// to report, and whether it is an exit.
+///
+/// Stop and continue reports are consumed unless `WNOWAIT` is set, so each
+/// is returned once.
+fn poll_children(children: &[Pid], flags: usize) -> Option<(WaitEvent, bool)> {
+    use hadron_syscall::{WCONTINUED, WEXITED, WNOWAIT, WUNTRACED};
+
+    let take = flags & WNOWAIT == 0;
+    children.iter().find_map(|&child_pid| {
+        let child = ProcessTable::lookup(child_pid)?;
+        let event = |status| WaitEvent {
+            pid: child_pid,
+            status,
+            uid: child.cred().uid().as_u32(),
+            cpu_times: child.wait_cpu_times(),
         };
-
-        // Verify it's actually our child.
-        if child.parent_pid != Some(parent_pid) {
-            return (-(crate::syscall::ECHILD), 0);
+        let exit_status = *child.exit_status.lock();
+        if let Some(status) = exit_status {
+            return (flags & WEXITED != 0).then(|| (event(status), true));
         }
-
-        // Check once for already-exited.
+        if flags & WUNTRACED != 0
+            && let Some(signum) = child.signals.stop_report(take)
         {
-            let status = child.exit_status.lock();
-            if let Some(exit_code) = *status {
-                drop(status);
-                let pid = child.pid.as_u32() as isize;
-                ProcessTable::unregister(child.pid);
-                return (pid, exit_code);
-            }
+            return Some((event(wait_status_stopped(signum)), false));
         }
-
-        // WNOHANG: return 0 immediately if child hasn't exited.
-        if wnohang {
-            return (0, 0);
+        if flags & WCONTINUED != 0 && child.signals.continue_report(take) {
+            return Some((event(WAIT_STATUS_CONTINUED), false));
         }
+        None
+    })
+}
 
-        // Wait for the child to exit using the double-check pattern to
-        // avoid a lost wakeup race: register the waker *before* re-checking
-        // exit_status so a concurrent wake_all() is never missed.
-        let (pid, exit_code) = core::future::poll_fn(|cx| {
-            // Fast path: child already exited.
-            let status = child.exit_status.lock();
-            if let Some(exit_code) = *status {
-                return core::task::Poll::Ready((child.pid.as_u32() as isize, exit_code));
-            }
-            drop(status);
-
-            // Register waker BEFORE re-checking — eliminates the race window.
-            child.exit_notify.register_waker(cx.waker());
-
-            // Re-check: child may have exited between our check and registration.
-            let status = child.exit_status.lock();
-            if let Some(exit_code) = *status {
-                return core::task::Poll::Ready((child.pid.as_u32() as isize, exit_code));
-            }
-            drop(status);
+/// Writes the outputs of a `TRAP_WAIT` that `output` asks for. `event` is
+/// `None` for a `WNOHANG` wait that found nothing. Runs under user CR3.
+fn write_wait_output(output: &WaitOutput, event: Option<&WaitEvent>) {
+    use crate::syscall::userptr::UserPtr;
 
-            core::task::Poll::Pending
-        })
-        .await;
+    if let Some(event) = event {
+        if output.status_ptr != 0 {
+            let _ =
+                UserPtr::<u64>::new(output.status_ptr as usize).and_then(|p| p.write(event.status));
+        }
+        if output.rusage_ptr != 0 {
+            let rusage = crate::syscall::RusageInfo {
+                user_ns: event.cpu_times.user_ns,
+                system_ns: event.cpu_times.system_ns,
+            };
+            let _ = UserPtr::<crate::syscall::RusageInfo>::new(output.rusage_ptr as usize)
+                .and_then(|p| p.write(rusage));
+        }
+    }
+    if output.info_ptr != 0 && (event.is_some() || output.waitid) {
+        let info = event.map(WaitEvent::siginfo).unwrap_or_default();
+        let _ = UserPtr::<crate::syscall::SigInfo>::new(output.info_ptr as usize)
+            .and_then(|p| p.write(info));
+    }
+}
 
-        // Reap: remove child from the process table now that the parent
-        // has collected the exit status.
-        ProcessTable::unregister(child.pid);
-        (pid, exit_code)
+/// Removes an exited child from the process table, adding its CPU time
+/// to the parent's children time.
+fn reap_child(parent: &Process, child_pid: Pid) {
+    if let Some(child) = ProcessTable::lookup(child_pid) {
+        parent.add_reaped_child(&child);
     }
+    ProcessTable::unregister(child_pid);
 }
@@ -1,22 +1,72 @@
 //! Process signal infrastructure.
 //!
-//! Provides a bitmask-based pending signal set, per-signal handler registration,
-//! and signal-to-action mapping. Each process has a [`SignalState`] that stores
-//! pending signals as bits in an `AtomicU64` and a handler table mapping signal
-//! numbers to dispositions (`SIG_DFL`, `SIG_IGN`, or a userspace function pointer).
-//! Signal delivery is checked at kernel re-entry points (after preemption, after
-//! blocking I/O, after waitpid).
+//! Each process (thread) has a [`SignalState`] holding its pending signals,
+//! blocked mask, per-signal actions and alternate signal stack. Pending
+//! signals are tracked twice: as bits in an `AtomicU64` for lock-free checks
+//! on every kernel exit, and as [`SignalInfo`] records under an IRQ-safe lock
+//! so handlers and `sig_timedwait` see who sent each signal.
+//!
+//! Standard signals (below `SIGRTMIN`) are pending at most once; a second
+//! post while one is pending is merged into it. Real-time signals are queued
+//! in order, each post delivered once, up to [`RTSIG_QUEUE_MAX`] per thread.
+//!
+//! Signal delivery is checked at kernel re-entry points (after preemption,
+//! after blocking I/O, after waitpid, on syscall return).
+
+use hadron_core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+
+use crate::sync::{HeapWaitQueue, IrqSpinLock, SpinLock};
+
+use crate::syscall::{
+    SA_NOCLDSTOP, SA_NOCLDWAIT, SA_NODEFER, SA_ONSTACK, SA_RESETHAND, SA_RESTART, SA_SIGINFO,
+    SI_KERNEL, SI_QUEUE, SI_TIMER, SI_USER, SIG_DFL, SIG_IGN, SIG_SETMASK, SS_DISABLE, SS_ONSTACK,
+};
+use crate::syscall::{
+    SIGABRT, SIGALRM, SIGBUS, SIGCHLD, SIGCONT, SIGFPE, SIGHUP, SIGILL, SIGINT, SIGIO, SIGKILL,
+    SIGPIPE, SIGPROF, SIGPWR, SIGQUIT, SIGRTMIN, SIGSEGV, SIGSTKFLT, SIGSTOP, SIGSYS, SIGTERM,
+    SIGTRAP, SIGTSTP, SIGTTIN, SIGTTOU, SIGURG, SIGUSR1, SIGUSR2, SIGVTALRM, SIGWINCH, SIGXCPU,
+    SIGXFSZ,
+};
+use hadron_syscall::{SigAction, SigAltStack, SigInfo};
 
-use hadron_core::sync::atomic::{AtomicU64, Ordering};
+/// Maximum signal number supported (`SIGRTMAX`).
+const MAX_SIGNAL: usize = 64;
 
-use crate::syscall::{SA_RESETHAND, SA_RESTART, SIG_DFL, SIG_IGN};
-use crate::syscall::{SIGCHLD, SIGINT, SIGKILL, SIGPIPE, SIGQUIT, SIGSEGV, SIGSTOP, SIGTERM};
+/// Number of entries in the handler table (indexed 0..=64, slot 0 unused).
+const HANDLER_TABLE_SIZE: usize = MAX_SIGNAL + 1;
 
-/// Maximum signal number supported (bits 1..63).
-const MAX_SIGNAL: usize = 63;
+/// Maximum number of real-time signals queued per thread.
+pub const RTSIG_QUEUE_MAX: usize = 32;
 
-/// Number of entries in the handler table (indexed 0..63, slot 0 unused).
-const HANDLER_TABLE_SIZE: usize = 64;
+/// Signals that can be neither blocked, caught nor ignored.
+const UNBLOCKABLE: u64 = sig_bit(SIGKILL) | sig_bit(SIGSTOP);
+
+/// Signals whose default action stops the thread.
+const STOP_SIGNALS: u64 = sig_bit(SIGSTOP) | sig_bit(SIGTSTP) | sig_bit(SIGTTIN) | sig_bit(SIGTTOU);
+
+/// [`SignalState::job_events`] bit: a stop signal was dequeued and no
+/// `SIGCONT` has been posted since, so the thread may still stop.
+const STOP_DEQUEUED: u32 = 1 << 0;
+/// [`SignalState::job_events`] bit: a stop not yet reported to `task_wait`.
+const REPORT_STOPPED: u32 = 1 << 1;
+/// [`SignalState::job_events`] bit: a resume not yet reported to
+/// `task_wait`.
+const REPORT_CONTINUED: u32 = 1 << 2;
+/// [`SignalState::job_events`] bit: a resume the parent has not yet been
+/// notified of.
+const NOTIFY_CONTINUED: u32 = 1 << 3;
+
+/// Sentinel for "no mask saved by `sig_suspend`". Never a valid mask, since
+/// SIGKILL and SIGSTOP can't be blocked.
+const NO_SAVED_MASK: u64 = u64::MAX;
+
+/// Returns the bit for `signum` in a signal set.
+///
+/// Sets use the Linux layout, bit `n - 1` for signal `n`, so a `u64` holds
+/// all 64 signals and matches the C `sigset_t`.
+pub const fn sig_bit(signum: usize) -> u64 {
+    1 << (signum - 1)
+}
 
 /// A Unix-style signal number.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -27,6 +77,12 @@ impl Signal {
     pub const fn is_valid(signum: usize) -> bool {
         signum >= 1 && signum <= MAX_SIGNAL
     }
+
+    /// Returns `true` for real-time signals, which are queued rather than
+    /// merged.
+    pub const fn is_realtime(signum: usize) -> bool {
+        signum >= SIGRTMIN && signum <= MAX_SIGNAL
+    }
 }
 
 /// Default action for a signal.
@@ -34,16 +90,28 @@ impl Signal {
 pub enum SignalAction {
     /// Terminate the process.
     Terminate,
+    /// Terminate the process; a core dump would be written if supported.
+    CoreDump,
     /// Ignore the signal.
     Ignore,
+    /// Stop the process until `SIGCONT`.
+    Stop,
+    /// Continue the process if stopped.
+    Continue,
 }
 
 /// Returns the default action for a signal number.
 pub fn default_action(signum: usize) -> SignalAction {
     match signum {
-        SIGINT | SIGKILL | SIGQUIT | SIGSEGV | SIGPIPE | SIGTERM => SignalAction::Terminate,
-        SIGCHLD => SignalAction::Ignore,
-        _ => SignalAction::Terminate, // Unknown signals terminate by default.
+        SIGHUP | SIGINT | SIGKILL | SIGUSR1 | SIGUSR2 | SIGPIPE | SIGALRM | SIGTERM | SIGSTKFLT
+        | SIGVTALRM | SIGPROF | SIGIO | SIGPWR => SignalAction::Terminate,
+        SIGQUIT | SIGILL | SIGTRAP | SIGABRT | SIGBUS | SIGFPE | SIGSEGV | SIGXCPU | SIGXFSZ
+        | SIGSYS => SignalAction::CoreDump,
+        SIGCHLD | SIGURG | SIGWINCH => SignalAction::Ignore,
+        SIGSTOP | SIGTSTP | SIGTTIN | SIGTTOU => SignalAction::Stop,
+        SIGCONT => SignalAction::Continue,
+        // Real-time and unassigned signals terminate by default.
+        _ => SignalAction::Terminate,
     }
 }
 
@@ -54,116 +122,452 @@ pub enum SignalDisposition {
     Default(SignalAction),
     /// Ignore the signal entirely.
     Ignore,
-    /// Deliver to a userspace handler at the given address.
-    Handler(u64),
+    /// Deliver to a userspace handler.
+    Handler {
+        /// Handler address.
+        addr: u64,
+        /// `SA_*` flags of the action.
+        flags: u32,
+        /// Signals to block while the handler runs.
+        mask: u64,
+    },
+}
+
+/// Why and by whom a signal was sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalInfo {
+    /// Signal number.
+    pub signo: usize,
+    /// `SI_*` origin code.
+    pub code: i32,
+    /// Sender PID (0 for the kernel).
+    pub pid: u32,
+    /// Sender real user ID (0 for the kernel).
+    pub uid: u32,
+    /// Value passed to `sig_queue`.
+    pub value: u64,
+}
+
+impl SignalInfo {
+    /// A signal generated by the kernel itself.
+    pub const fn kernel(signo: usize) -> Self {
+        Self {
+            signo,
+            code: SI_KERNEL,
+            pid: 0,
+            uid: 0,
+            value: 0,
+        }
+    }
+
+    /// A signal sent by `task_kill`.
+    pub const fn user(signo: usize, pid: u32, uid: u32) -> Self {
+        Self {
+            signo,
+            code: SI_USER,
+            pid,
+            uid,
+            value: 0,
+        }
+    }
+
+    /// A signal sent by `sig_queue` with `value`.
+    pub const fn queued(signo: usize, pid: u32, uid: u32, value: u64) -> Self {
+        Self {
+            signo,
+            code: SI_QUEUE,
+            pid,
+            uid,
+            value,
+        }
+    }
+
+    /// A signal sent by the expiry of a `timer_create` timer.
+    pub const fn timer(signo: usize, value: u64) -> Self {
+        Self {
+            signo,
+            code: SI_TIMER,
+            pid: 0,
+            uid: 0,
+            value,
+        }
+    }
+
+    /// Converts to the `siginfo_t` layout handed to userspace.
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_possible_wrap,
+        reason = "signal numbers are at most 64"
+    )]
+    pub fn to_user(&self) -> SigInfo {
+        SigInfo {
+            signo: self.signo as i32,
+            code: self.code,
+            pid: self.pid,
+            uid: self.uid,
+            value: self.value,
+            ..SigInfo::default()
+        }
+    }
+}
+
+/// Error from [`SignalState::post_info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostError {
+    /// The signal number is out of range.
+    Invalid,
+    /// The real-time signal queue is full.
+    QueueFull,
+}
+
+/// Pending signal records, guarded by [`SignalState::queue`].
+struct PendingQueue {
+    /// Info of each pending standard signal, indexed by signal number.
+    /// Only meaningful while the signal's pending bit is set.
+    standard: [SignalInfo; SIGRTMIN],
+    /// Queued real-time signals in the order they were posted.
+    realtime: [SignalInfo; RTSIG_QUEUE_MAX],
+    /// Number of entries used in `realtime`.
+    realtime_len: usize,
+}
+
+impl PendingQueue {
+    const fn new() -> Self {
+        Self {
+            standard: [SignalInfo::kernel(0); SIGRTMIN],
+            realtime: [SignalInfo::kernel(0); RTSIG_QUEUE_MAX],
+            realtime_len: 0,
+        }
+    }
+
+    /// Removes the oldest queued entry for real-time signal `signo`.
+    /// Returns it and whether more entries for `signo` remain.
+    fn take_realtime(&mut self, signo: usize) -> Option<(SignalInfo, bool)> {
+        let queued = &self.realtime[..self.realtime_len];
+        let index = queued.iter().position(|info| info.signo == signo)?;
+        let info = queued[index];
+        self.realtime
+            .copy_within(index + 1..self.realtime_len, index);
+        self.realtime_len -= 1;
+        let more = self.realtime[..self.realtime_len]
+            .iter()
+            .any(|info| info.signo == signo);
+        Some((info, more))
+    }
+}
+
+/// A thread's alternate signal stack.
+#[derive(Debug, Clone, Copy)]
+struct AltStack {
+    /// Lowest address of the stack.
+    sp: u64,
+    /// Size in bytes.
+    size: u64,
+    /// Whether the stack is installed.
+    enabled: bool,
+}
+
+impl AltStack {
+    /// Returns `true` if `rsp` lies within the stack.
+    fn contains(&self, rsp: u64) -> bool {
+        self.enabled && rsp > self.sp && rsp - self.sp <= self.size
+    }
 }
 
 /// Compact flag bits used in the packed handler+flags representation.
 /// External `SA_*` constants are converted to/from these on the API boundary.
-const PACKED_FLAG_RESTART: u64 = 1 << 48;
 const PACKED_FLAG_RESETHAND: u64 = 1 << 49;
 const PACKED_HANDLER_MASK: u64 = (1 << 48) - 1;
 
-/// Convert external `sa_flags` (u32, using `SA_RESTART`/`SA_RESETHAND` bit positions)
-/// into the compact packed representation stored in the upper bits of handler entries.
+/// `SA_*` flags and their packed bits.
+const PACKED_FLAGS: [(u32, u64); 7] = [
+    (SA_RESTART, 1 << 48),
+    (SA_RESETHAND, PACKED_FLAG_RESETHAND),
+    (SA_SIGINFO, 1 << 50),
+    (SA_ONSTACK, 1 << 51),
+    (SA_NODEFER, 1 << 52),
+    (SA_NOCLDSTOP, 1 << 53),
+    (SA_NOCLDWAIT, 1 << 54),
+];
+
+/// Convert external `sa_flags` into the compact packed representation stored
+/// in the upper bits of handler entries. Unknown flags are dropped.
 fn pack_flags(sa_flags: u32) -> u64 {
-    let mut packed = 0u64;
-    if sa_flags & SA_RESTART as u32 != 0 {
-        packed |= PACKED_FLAG_RESTART;
-    }
-    if sa_flags & SA_RESETHAND as u32 != 0 {
-        packed |= PACKED_FLAG_RESETHAND;
-    }
-    packed
+    PACKED_FLAGS
+        .iter()
+        .filter(|(flag, _)| sa_flags & flag != 0)
+        .fold(0, |packed, (_, bit)| packed | bit)
 }
 
 /// Convert packed flags back to external `sa_flags` format.
 fn unpack_flags(packed: u64) -> u32 {
-    let mut flags = 0u32;
-    if packed & PACKED_FLAG_RESTART != 0 {
-        flags |= SA_RESTART as u32;
-    }
-    if packed & PACKED_FLAG_RESETHAND != 0 {
-        flags |= SA_RESETHAND as u32;
-    }
-    flags
+    PACKED_FLAGS
+        .iter()
+        .filter(|(_, bit)| packed & bit != 0)
+        .fold(0, |flags, (flag, _)| flags | flag)
 }
 
-/// Per-process signal state using an atomic bitmask and handler table.
+/// Per-process signal state.
 ///
-/// Bit N of `pending` represents signal number N (1-indexed, so bit 0 is unused).
-/// Each handler entry packs both the handler address (bits 0..48) and compact flags
-/// (bits 48+) into a single `AtomicU64`, ensuring atomicity for `SA_RESETHAND`.
+/// Bit `n - 1` of `pending` and `blocked` represents signal `n`. Each handler
+/// entry packs both the handler address (bits 0..48) and compact flags
+/// (bits 48+) into a single `AtomicU64`, ensuring atomicity for
+/// `SA_RESETHAND`.
 ///
-/// Both pending and handlers are atomic for lock-free access from interrupt context.
+/// `pending` is only modified under the `queue` lock, so it always agrees
+/// with the queued records, but it can be read without the lock, including
+/// from interrupt context.
 pub struct SignalState {
-    /// Pending signal bitmask. Bit N = signal N is pending.
+    /// Pending signal bitmask.
     pending: AtomicU64,
-    /// Blocked signal bitmask. Bit N = signal N is blocked (masked).
-    /// SIGKILL and SIGSTOP cannot be blocked.
+    /// Blocked signal bitmask. SIGKILL and SIGSTOP cannot be blocked.
     blocked: AtomicU64,
+    /// Mask to restore after the handler that ends a `sig_suspend`, or
+    /// [`NO_SAVED_MASK`].
+    saved_mask: AtomicU64,
     /// Per-signal packed handler+flags table.
     /// Bits 0..48: handler address (`SIG_DFL`=0, `SIG_IGN`=1, else user fn pointer).
-    /// Bits 48+: compact flag bits (`PACKED_FLAG_RESTART`, `PACKED_FLAG_RESETHAND`).
+    /// Bits 48+: compact flag bits (see [`PACKED_FLAGS`]).
     handlers: [AtomicU64; HANDLER_TABLE_SIZE],
+    /// Per-signal `sa_mask`.
+    handler_masks: [AtomicU64; HANDLER_TABLE_SIZE],
+    /// Info for each pending signal.
+    queue: IrqSpinLock<PendingQueue>,
+    /// Alternate signal stack.
+    alt_stack: SpinLock<AltStack>,
+    /// Tasks waiting for a signal to become pending.
+    waiters: HeapWaitQueue,
+    /// Signal that stopped this thread, or 0 while it runs.
+    stop_signal: AtomicU32,
+    /// Job-control state changes not yet consumed ([`STOP_DEQUEUED`],
+    /// `REPORT_*`, [`NOTIFY_CONTINUED`]). Only modified under the `queue`
+    /// lock.
+    job_events: AtomicU32,
 }
 
 impl SignalState {
-    /// Creates a new signal state with no pending signals, no mask, and all handlers `SIG_DFL`.
+    /// Creates a new signal state with no pending signals, no mask, all
+    /// handlers `SIG_DFL`, and no alternate stack.
     pub const fn new() -> Self {
         Self {
             pending: AtomicU64::new(0),
             blocked: AtomicU64::new(0),
+            saved_mask: AtomicU64::new(NO_SAVED_MASK),
             handlers: [const { AtomicU64::new(SIG_DFL as u64) }; HANDLER_TABLE_SIZE],
+            handler_masks: [const { AtomicU64::new(0) }; HANDLER_TABLE_SIZE],
+            queue: IrqSpinLock::named("signal_queue", PendingQueue::new()),
+            alt_stack: SpinLock::named(
+                "signal_alt_stack",
+                AltStack {
+                    sp: 0,
+                    size: 0,
+                    enabled: false,
+                },
+            ),
+            waiters: HeapWaitQueue::new(),
+            stop_signal: AtomicU32::new(0),
+            job_events: AtomicU32::new(0),
         }
     }
 
-    /// Post a signal (set its pending bit).
+    /// Post a kernel-generated signal.
     ///
-    /// Can be called from any context (interrupt-safe).
+    /// Can be called from any context (interrupt-safe). A full real-time
+    /// queue drops the signal.
     pub fn post(&self, signum: usize) {
-        if Signal::is_valid(signum) {
-            self.pending.fetch_or(1 << signum, Ordering::Release);
+        let _ = self.post_info(SignalInfo::kernel(signum));
+    }
+
+    /// Post a signal with its sender information.
+    ///
+    /// Signals whose action is to be ignored are discarded unless blocked,
+    /// since a blocked signal may still be accepted by `sig_timedwait`.
+    /// Can be called from any context (interrupt-safe).
+    pub fn post_info(&self, info: SignalInfo) -> Result<(), PostError> {
+        let signum = info.signo;
+        if !Signal::is_valid(signum) {
+            return Err(PostError::Invalid);
         }
+        let bit = sig_bit(signum);
+        // SIGCONT resumes the thread and cancels pending stops even when it
+        // is blocked or ignored; a stop signal cancels a pending SIGCONT.
+        if signum == SIGCONT {
+            self.resume();
+        } else if STOP_SIGNALS & bit != 0 {
+            let _queue = self.queue.lock();
+            self.pending.fetch_and(!sig_bit(SIGCONT), Ordering::Release);
+        }
+        if self.blocked.load(Ordering::Acquire) & bit == 0 && self.is_ignored(signum) {
+            return Ok(());
+        }
+
+        {
+            let mut queue = self.queue.lock();
+            if Signal::is_realtime(signum) {
+                if queue.realtime_len == RTSIG_QUEUE_MAX {
+                    return Err(PostError::QueueFull);
+                }
+                let len = queue.realtime_len;
+                queue.realtime[len] = info;
+                queue.realtime_len += 1;
+            } else if self.pending.load(Ordering::Acquire) & bit == 0 {
+                queue.standard[signum] = info;
+            }
+            self.pending.fetch_or(bit, Ordering::Release);
+        }
+        self.waiters.wake_all();
+        crate::sched::tick::kick();
+        Ok(())
+    }
+
+    /// Returns `true` if the current action for `signum` discards it.
+    fn is_ignored(&self, signum: usize) -> bool {
+        if UNBLOCKABLE & sig_bit(signum) != 0 {
+            return false;
+        }
+        match self.handlers[signum].load(Ordering::Acquire) & PACKED_HANDLER_MASK {
+            h if h == SIG_IGN as u64 => true,
+            h if h == SIG_DFL as u64 => default_action(signum) == SignalAction::Ignore,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if `signum` is pending, regardless of the mask.
+    pub fn is_pending(&self, signum: usize) -> bool {
+        Signal::is_valid(signum) && self.pending.load(Ordering::Acquire) & sig_bit(signum) != 0
+    }
+
+    /// Returns the set of pending signals.
+    pub fn pending(&self) -> u64 {
+        self.pending.load(Ordering::Acquire)
+    }
+
+    /// Returns the set of pending signals that are blocked.
+    pub fn pending_blocked(&self) -> u64 {
+        self.pending.load(Ordering::Acquire) & self.blocked.load(Ordering::Acquire)
     }
 
     /// Dequeue the highest-priority deliverable signal.
     ///
-    /// Returns `Some(Signal)` and clears its pending bit, or `None` if
-    /// no unblocked signals are pending. SIGKILL is always deliverable
-    /// (cannot be blocked) and has highest priority.
+    /// Returns `Some(Signal)` and removes it, or `None` if no unblocked
+    /// signals are pending. SIGKILL is always deliverable (cannot be
+    /// blocked) and has highest priority.
     pub fn dequeue(&self) -> Option<Signal> {
-        loop {
-            let pending = self.pending.load(Ordering::Acquire);
-            let blocked = self.blocked.load(Ordering::Acquire);
-            // SIGKILL/SIGSTOP can never be blocked.
-            let unblockable = (1u64 << SIGKILL) | (1u64 << SIGSTOP);
-            let deliverable = pending & (!blocked | unblockable);
-
-            if deliverable == 0 {
-                return None;
-            }
+        self.dequeue_info().map(|info| Signal(info.signo))
+    }
 
-            // SIGKILL (bit 9) has highest priority.
-            let signum = if deliverable & (1 << SIGKILL) != 0 {
-                SIGKILL
-            } else {
-                // Find lowest set bit (lowest signal number).
-                deliverable.trailing_zeros() as usize
-            };
+    /// Like [`dequeue`](Self::dequeue), but returns the signal's info.
+    pub fn dequeue_info(&self) -> Option<SignalInfo> {
+        self.take(!self.blocked.load(Ordering::Acquire) | UNBLOCKABLE)
+    }
 
-            let mask = 1u64 << signum;
-            // Atomically clear the bit. If another thread modified pending
-            // between load and CAS, retry.
-            match self.pending.compare_exchange_weak(
-                pending,
-                pending & !mask,
-                Ordering::AcqRel,
-                Ordering::Acquire,
-            ) {
-                Ok(_) => return Some(Signal(signum)),
-                Err(_) => continue,
+    /// Dequeues the lowest pending signal in `set`, blocked or not.
+    ///
+    /// Used by `sig_timedwait`, which accepts signals the caller has blocked.
+    pub fn dequeue_from(&self, set: u64) -> Option<SignalInfo> {
+        self.take(set)
+    }
+
+    /// Removes and returns the highest-priority pending signal among
+    /// `candidates`.
+    fn take(&self, candidates: u64) -> Option<SignalInfo> {
+        let mut queue = self.queue.lock();
+        let pending = self.pending.load(Ordering::Acquire);
+        let available = pending & candidates;
+        if available == 0 {
+            return None;
+        }
+
+        // SIGKILL has highest priority; otherwise lowest signal number first.
+        let signum = if available & sig_bit(SIGKILL) != 0 {
+            SIGKILL
+        } else {
+            available.trailing_zeros() as usize + 1
+        };
+
+        let (info, more) = if Signal::is_realtime(signum) {
+            queue
+                .take_realtime(signum)
+                .unwrap_or((SignalInfo::kernel(signum), false))
+        } else {
+            (queue.standard[signum], false)
+        };
+        if !more {
+            self.pending.fetch_and(!sig_bit(signum), Ordering::Release);
+        }
+        if STOP_SIGNALS & sig_bit(signum) != 0 {
+            self.job_events.fetch_or(STOP_DEQUEUED, Ordering::Release);
+        }
+        Some(info)
+    }
+
+    /// Stops the thread for the stop signal `signum` it just dequeued.
+    ///
+    /// Returns `false`, leaving the thread running, if a `SIGCONT` was
+    /// posted since the stop signal was dequeued.
+    pub fn stop(&self, signum: usize) -> bool {
+        let _queue = self.queue.lock();
+        if self.job_events.load(Ordering::Acquire) & STOP_DEQUEUED == 0 {
+            return false;
+        }
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "signal numbers are at most 64"
+        )]
+        let signum = signum as u32;
+        self.stop_signal.store(signum, Ordering::Release);
+        self.job_events.store(REPORT_STOPPED, Ordering::Release);
+        true
+    }
+
+    /// Resumes a stopped thread and discards its pending stop signals.
+    fn resume(&self) {
+        {
+            let _queue = self.queue.lock();
+            self.pending.fetch_and(!STOP_SIGNALS, Ordering::Release);
+            if self.stop_signal.swap(0, Ordering::AcqRel) == 0 {
+                self.job_events.fetch_and(!STOP_DEQUEUED, Ordering::Release);
+                return;
             }
+            self.job_events
+                .store(REPORT_CONTINUED | NOTIFY_CONTINUED, Ordering::Release);
+        }
+        self.waiters.wake_all();
+    }
+
+    /// Returns `true` if the thread is stopped.
+    pub fn is_stopped(&self) -> bool {
+        self.stop_signal.load(Ordering::Acquire) != 0
+    }
+
+    /// Returns the signal of a stop not yet reported to a wait, consuming
+    /// the report if `take` is set.
+    pub fn stop_report(&self, take: bool) -> Option<usize> {
+        let _queue = self.queue.lock();
+        let signum = self.stop_signal.load(Ordering::Acquire) as usize;
+        (signum != 0 && self.job_event(REPORT_STOPPED, take)).then_some(signum)
+    }
+
+    /// Returns `true` if a resume has not yet been reported to a wait,
+    /// consuming the report if `take` is set.
+    pub fn continue_report(&self, take: bool) -> bool {
+        let _queue = self.queue.lock();
+        self.job_event(REPORT_CONTINUED, take)
+    }
+
+    /// Takes the resume the parent has not yet been notified of.
+    pub fn take_continue_notify(&self) -> bool {
+        let _queue = self.queue.lock();
+        self.job_event(NOTIFY_CONTINUED, true)
+    }
+
+    /// Returns whether `event` is set in `job_events`, clearing it if
+    /// `take` is set. The caller holds the `queue` lock.
+    fn job_event(&self, event: u32, take: bool) -> bool {
+        if take {
+            self.job_events.fetch_and(!event, Ordering::AcqRel) & event != 0
+        } else {
+            self.job_events.load(Ordering::Acquire) & event != 0
         }
     }
 
@@ -171,8 +575,12 @@ impl SignalState {
     pub fn has_pending(&self) -> bool {
         let pending = self.pending.load(Ordering::Acquire);
         let blocked = self.blocked.load(Ordering::Acquire);
-        let unblockable = (1u64 << SIGKILL) | (1u64 << SIGSTOP);
-        (pending & (!blocked | unblockable)) != 0
+        (pending & (!blocked | UNBLOCKABLE)) != 0
+    }
+
+    /// Registers `waker` to be woken when the next signal is posted.
+    pub fn register_waker(&self, waker: &core::task::Waker) {
+        self.waiters.register_waker(waker);
     }
 
     /// Get the current signal mask (blocked signals bitmask).
@@ -189,7 +597,6 @@ impl SignalState {
     ///
     /// SIGKILL and SIGSTOP bits are always cleared (cannot be blocked).
     pub fn set_mask(&self, how: usize, set: u64) -> u64 {
-        let unblockable = (1u64 << SIGKILL) | (1u64 << SIGSTOP);
         loop {
             let old = self.blocked.load(Ordering::Acquire);
             let new = match how {
@@ -197,7 +604,7 @@ impl SignalState {
                 1 => old & !set, // SIG_UNBLOCK
                 2 => set,        // SIG_SETMASK
                 _ => return old,
-            } & !unblockable; // SIGKILL/SIGSTOP never blocked
+            } & !UNBLOCKABLE; // SIGKILL/SIGSTOP never blocked
 
             match self
                 .blocked
@@ -209,50 +616,144 @@ impl SignalState {
         }
     }
 
-    /// Set the handler and flags for a signal number. Returns the previous handler value.
+    /// Installs `mask` for the duration of a `sig_suspend`, remembering the
+    /// current mask to restore once a handler has run.
+    pub fn suspend_with_mask(&self, mask: u64) {
+        let old = self.set_mask(SIG_SETMASK, mask);
+        self.saved_mask.store(old, Ordering::Release);
+    }
+
+    /// Returns the mask saved by [`suspend_with_mask`](Self::suspend_with_mask),
+    /// if any, and forgets it.
+    pub fn take_saved_mask(&self) -> Option<u64> {
+        match self.saved_mask.swap(NO_SAVED_MASK, Ordering::AcqRel) {
+            NO_SAVED_MASK => None,
+            mask => Some(mask),
+        }
+    }
+
+    /// Set the action for a signal number. Returns the previous action.
     ///
-    /// SIGKILL and SIGSTOP cannot be caught or ignored — returns `None` for those.
-    pub fn set_handler(&self, signum: usize, handler: u64, sa_flags: u32) -> Option<u64> {
+    /// SIGKILL and SIGSTOP cannot be caught or ignored — returns `None` for
+    /// those. Setting a signal to be ignored discards it if pending.
+    pub fn set_action(&self, signum: usize, action: &SigAction) -> Option<SigAction> {
         if !Signal::is_valid(signum) || signum == SIGKILL || signum == SIGSTOP {
             return None;
         }
-        let packed = (handler & PACKED_HANDLER_MASK) | pack_flags(sa_flags);
-        let old = self.handlers[signum].swap(packed, Ordering::AcqRel);
-        Some(old & PACKED_HANDLER_MASK)
+        let old = self.action(signum);
+        let packed = (action.handler as u64 & PACKED_HANDLER_MASK) | pack_flags(action.flags);
+        self.handler_masks[signum].store(action.mask & !UNBLOCKABLE, Ordering::Release);
+        self.handlers[signum].store(packed, Ordering::Release);
+
+        if self.is_ignored(signum) {
+            self.discard(signum);
+        }
+        Some(old)
     }
 
-    /// Get the current handler for a signal number.
-    pub fn get_handler(&self, signum: usize) -> u64 {
-        if !Signal::is_valid(signum) {
-            return SIG_DFL as u64;
+    /// Removes every pending instance of `signum`.
+    fn discard(&self, signum: usize) {
+        let mut queue = self.queue.lock();
+        if Signal::is_realtime(signum) {
+            while queue.take_realtime(signum).is_some() {}
         }
-        self.handlers[signum].load(Ordering::Acquire) & PACKED_HANDLER_MASK
+        self.pending.fetch_and(!sig_bit(signum), Ordering::Release);
     }
 
-    /// Get the flags for a signal number.
-    pub fn get_flags(&self, signum: usize) -> u32 {
+    /// Returns the current action for a signal number.
+    pub fn action(&self, signum: usize) -> SigAction {
         if !Signal::is_valid(signum) {
-            return 0;
+            return SigAction::default();
+        }
+        let packed = self.handlers[signum].load(Ordering::Acquire);
+        SigAction {
+            handler: (packed & PACKED_HANDLER_MASK) as usize,
+            mask: self.handler_masks[signum].load(Ordering::Acquire),
+            flags: unpack_flags(packed),
+            ..SigAction::default()
         }
-        unpack_flags(self.handlers[signum].load(Ordering::Acquire))
     }
 
     /// Returns `true` if `SA_RESTART` is set for the given signal.
     pub fn has_restart(&self, signum: usize) -> bool {
-        self.get_flags(signum) & SA_RESTART as u32 != 0
+        self.action(signum).flags & SA_RESTART != 0
     }
 
-    /// Reset all signal handlers to `SIG_DFL` (used by execve).
-    pub fn reset_handlers(&self) {
+    /// Applies the `execve` rules: caught signals revert to `SIG_DFL`,
+    /// ignored signals stay ignored, and the alternate stack is removed.
+    /// The mask and pending signals are kept.
+    pub fn reset_for_exec(&self) {
         for i in 1..HANDLER_TABLE_SIZE {
-            self.handlers[i].store(SIG_DFL as u64, Ordering::Release);
+            let handler = self.handlers[i].load(Ordering::Acquire) & PACKED_HANDLER_MASK;
+            let reset = if handler == SIG_IGN as u64 {
+                SIG_IGN
+            } else {
+                SIG_DFL
+            };
+            self.handlers[i].store(reset as u64, Ordering::Release);
+            self.handler_masks[i].store(0, Ordering::Release);
+        }
+        self.alt_stack.lock().enabled = false;
+    }
+
+    /// Returns the alternate stack as seen from a thread whose stack pointer
+    /// is `rsp`.
+    pub fn alt_stack(&self, rsp: u64) -> SigAltStack {
+        let stack = *self.alt_stack.lock();
+        let flags = if !stack.enabled {
+            SS_DISABLE
+        } else if stack.contains(rsp) {
+            SS_ONSTACK
+        } else {
+            0
+        };
+        SigAltStack {
+            sp: stack.sp as usize,
+            flags,
+            size: stack.size as usize,
+            ..SigAltStack::default()
         }
     }
 
+    /// Installs or disables the alternate stack.
+    ///
+    /// Fails with `EPERM` while `rsp` is on the current alternate stack,
+    /// `EINVAL` for unknown flags, and `ENOMEM` for a stack smaller than
+    /// `MINSIGSTKSZ`.
+    pub fn set_alt_stack(&self, new: &SigAltStack, rsp: u64) -> Result<(), isize> {
+        let mut stack = self.alt_stack.lock();
+        if stack.contains(rsp) {
+            return Err(crate::syscall::EPERM);
+        }
+        match new.flags {
+            SS_DISABLE => stack.enabled = false,
+            0 => {
+                if new.size < crate::syscall::MINSIGSTKSZ {
+                    return Err(crate::syscall::ENOMEM);
+                }
+                *stack = AltStack {
+                    sp: new.sp as u64,
+                    size: new.size as u64,
+                    enabled: true,
+                };
+            }
+            _ => return Err(crate::syscall::EINVAL),
+        }
+        Ok(())
+    }
+
+    /// Returns the top of the alternate stack if a handler for a signal
+    /// with `SA_ONSTACK` should switch to it, i.e. if one is installed and
+    /// `rsp` is not already on it.
+    pub fn alt_stack_top(&self, rsp: u64) -> Option<u64> {
+        let stack = *self.alt_stack.lock();
+        (stack.enabled && !stack.contains(rsp)).then(|| stack.sp + stack.size)
+    }
+
     /// Resolve how a signal should be handled based on the handler table.
     ///
     /// When `SA_RESETHAND` is set, the handler is atomically swapped to `SIG_DFL`
-    /// using a CAS loop, preventing TOCTOU races with concurrent `set_handler` calls.
+    /// using a CAS loop, preventing TOCTOU races with concurrent `set_action` calls.
     pub fn disposition(&self, signum: usize) -> SignalDisposition {
         // SIGKILL and SIGSTOP always use default action, regardless of handler table.
         if signum == SIGKILL || signum == SIGSTOP {
@@ -288,7 +789,11 @@ impl SignalState {
         match handler as usize {
             SIG_DFL => SignalDisposition::Default(default_action(signum)),
             SIG_IGN => SignalDisposition::Ignore,
-            _ => SignalDisposition::Handler(handler),
+            _ => SignalDisposition::Handler {
+                addr: handler,
+                flags: unpack_flags(packed),
+                mask: self.handler_masks[signum].load(Ordering::Acquire),
+            },
         }
     }
 }
@@ -0,0 +1,529 @@
+//! Interval timers: `setitimer` timers and POSIX `timer_create` timers.
+//!
+//! The threads of a process share one [`ProcessTimers`]. It holds the three
+//! `setitimer` timers and the timers made by `timer_create`, all
+//! [`IntervalTimer`]s that differ only in their clock and in how they
+//! report an expiry. Timerfds (see [`crate::ipc::timerfd`]) are
+//! `IntervalTimer`s too, which count their expiries instead of sending a
+//! signal.
+//!
+//! While a timer on the monotonic or real-time clock is armed, a kernel
+//! task sleeps on a high-resolution [`Timer`] until its next expiry, sends
+//! the signal and sleeps again. Re-arming or deleting the timer bumps its
+//! generation and wakes the task, which then exits.
+//!
+//! CPU-time timers have no task. The CPU time accounting in
+//! [`acct`](super::acct) calls [`ProcessTimers::check_cpu`] each time it
+//! charges time, so they fire at the first accounting point after their
+//! expiry: the next syscall, preemption or block of a thread. A thread
+//! whose process has one armed keeps the tick running, so a loop that
+//! never enters the kernel is still preempted, and charged, every tick.
+
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use hadron_core::sync::atomic::{AtomicBool, Ordering};
+use hadron_core::timer::forward;
+
+use super::signal::SignalInfo;
+use super::{Process, ProcessTable};
+use crate::id::Pid;
+use crate::sched::TaskMeta;
+use crate::sched::primitives::timer_expired;
+use crate::sched::timer::Timer;
+use crate::sync::{HeapWaitQueue, SpinLock};
+use crate::syscall::{SIGALRM, SIGPROF, SIGVTALRM};
+use crate::time::Time;
+
+/// Maximum number of `timer_create` timers per process.
+pub const TIMER_MAX: usize = 32;
+
+/// The clock an [`IntervalTimer`] measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerClock {
+    /// `CLOCK_MONOTONIC`.
+    Monotonic,
+    /// `CLOCK_REALTIME`.
+    Realtime,
+    /// User CPU time of the process (`ITIMER_VIRTUAL`).
+    UserCpu,
+    /// User and system CPU time of the process (`ITIMER_PROF`,
+    /// `CLOCK_PROCESS_CPUTIME_ID`).
+    ProcessCpu,
+    /// User and system CPU time of one thread (`CLOCK_THREAD_CPUTIME_ID`).
+    ThreadCpu(Pid),
+}
+
+impl TimerClock {
+    /// Returns `true` for clocks that only advance while the process runs.
+    const fn is_cpu(self) -> bool {
+        matches!(self, Self::UserCpu | Self::ProcessCpu | Self::ThreadCpu(_))
+    }
+
+    /// Returns the clock's current value, in nanoseconds. `thread` is a
+    /// thread of the process that owns the timer.
+    pub fn now(self, thread: &Process) -> u64 {
+        match self {
+            Self::Monotonic => Time::boot_nanos(),
+            Self::Realtime => Time::realtime_nanos(),
+            Self::UserCpu => thread.process_cpu_times().user_ns,
+            Self::ProcessCpu => thread.process_cpu_times().total_ns(),
+            Self::ThreadCpu(pid) if pid == thread.pid => thread.thread_cpu_times().total_ns(),
+            Self::ThreadCpu(pid) => {
+                ProcessTable::lookup(pid).map_or(0, |t| t.thread_cpu_times().total_ns())
+            }
+        }
+    }
+
+    /// Converts `expiry` on a monotonic or real-time clock to monotonic
+    /// time.
+    fn to_monotonic(self, expiry: u64) -> u64 {
+        match self {
+            Self::Realtime => {
+                let offset = Time::realtime_nanos().saturating_sub(Time::boot_nanos());
+                expiry.saturating_sub(offset)
+            }
+            _ => expiry,
+        }
+    }
+}
+
+/// How an [`IntervalTimer`] reports an expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimerNotify {
+    /// Not at all (`SIGEV_NONE`).
+    None,
+    /// By sending `info` to thread `target`.
+    Signal {
+        /// Thread that receives the signal.
+        target: Pid,
+        /// The signal and its info.
+        info: SignalInfo,
+    },
+    /// By counting expiries for a timerfd to read.
+    Count,
+}
+
+/// Arming state of an [`IntervalTimer`].
+#[derive(Debug, Clone, Copy)]
+struct TimerState {
+    /// Next expiry on the timer's clock, or 0 if disarmed.
+    expiry: u64,
+    /// Period after each expiry, or 0 for a one-shot timer.
+    interval: u64,
+    /// Bumped each time the timer is set, to retire the previous setting's
+    /// expiry task.
+    generation: u64,
+    /// Expiries since the timer last sent a signal that did not send one.
+    overrun: u32,
+    /// Expiries not yet read, for [`TimerNotify::Count`].
+    ticks: u64,
+}
+
+/// A one-shot or periodic timer that sends a signal when it expires.
+pub struct IntervalTimer {
+    /// Clock the expiry is measured on.
+    clock: TimerClock,
+    /// How an expiry is reported.
+    notify: TimerNotify,
+    /// Arming state.
+    state: SpinLock<TimerState>,
+    /// Woken when the timer is set or deleted, so that a sleeping expiry
+    /// task notices.
+    reset: HeapWaitQueue,
+    /// Woken when a [`TimerNotify::Count`] timer expires.
+    expired: HeapWaitQueue,
+}
+
+impl IntervalTimer {
+    /// Creates a disarmed timer.
+    pub fn new(clock: TimerClock, notify: TimerNotify) -> Self {
+        Self {
+            clock,
+            notify,
+            state: SpinLock::leveled(
+                "interval_timer",
+                4,
+                TimerState {
+                    expiry: 0,
+                    interval: 0,
+                    generation: 0,
+                    overrun: 0,
+                    ticks: 0,
+                },
+            ),
+            reset: HeapWaitQueue::new(),
+            expired: HeapWaitQueue::new(),
+        }
+    }
+
+    /// Returns the timer's clock.
+    pub fn clock(&self) -> TimerClock {
+        self.clock
+    }
+
+    /// Returns the time left until the next expiry and the interval, at
+    /// clock time `now`. Both are zero for a disarmed timer.
+    pub fn get(&self, now: u64) -> (u64, u64) {
+        let state = self.state.lock();
+        if state.expiry == 0 {
+            return (0, state.interval);
+        }
+        // An armed timer never reports zero, which would read as disarmed.
+        (state.expiry.saturating_sub(now).max(1), state.interval)
+    }
+
+    /// Arms the timer to expire at clock time `expiry` and then every
+    /// `interval`, or disarms it if `expiry` is 0. `now` is the current
+    /// clock time.
+    ///
+    /// Returns the previous setting as [`get`](Self::get) would have.
+    ///
+    /// Timers on a CPU-time clock must be set through
+    /// [`ProcessTimers::set`], which makes sure they are checked.
+    pub fn set(self: &Arc<Self>, expiry: u64, interval: u64, now: u64) -> (u64, u64) {
+        let (old, generation) = {
+            let mut state = self.state.lock();
+            let old = if state.expiry == 0 {
+                (0, state.interval)
+            } else {
+                (state.expiry.saturating_sub(now).max(1), state.interval)
+            };
+            state.generation += 1;
+            state.expiry = expiry;
+            state.interval = if expiry == 0 { 0 } else { interval };
+            state.overrun = 0;
+            state.ticks = 0;
+            (old, state.generation)
+        };
+        // Retire the expiry task of the previous setting.
+        self.reset.wake_all();
+
+        if expiry != 0 && !self.clock.is_cpu() {
+            crate::sched::spawn_with(
+                run_expiry(Arc::clone(self), generation),
+                TaskMeta::new("interval-timer"),
+            );
+        }
+        old
+    }
+
+    /// Disarms the timer.
+    pub fn disarm(self: &Arc<Self>) {
+        self.set(0, 0, 0);
+    }
+
+    /// Returns the number of expiries since the timer last sent a signal
+    /// that did not send one.
+    pub fn overrun(&self) -> u32 {
+        self.state.lock().overrun
+    }
+
+    /// Returns and resets the number of expiries of a
+    /// [`TimerNotify::Count`] timer since it was last read or set.
+    pub fn take_ticks(&self) -> u64 {
+        core::mem::take(&mut self.state.lock().ticks)
+    }
+
+    /// Returns `true` if a [`TimerNotify::Count`] timer has expired since
+    /// it was last read or set.
+    pub fn has_ticks(&self) -> bool {
+        self.state.lock().ticks != 0
+    }
+
+    /// Registers `waker` to be woken when a [`TimerNotify::Count`] timer
+    /// next expires.
+    pub fn register_expiry_waker(&self, waker: &core::task::Waker) {
+        self.expired.register_waker(waker);
+    }
+
+    /// Returns `true` if the timer is armed.
+    fn is_armed(&self) -> bool {
+        self.state.lock().expiry != 0
+    }
+
+    /// Returns the current generation.
+    fn generation(&self) -> u64 {
+        self.state.lock().generation
+    }
+
+    /// Handles the expiry of the setting with `generation` at clock time
+    /// `now`: moves a periodic timer to its next expiry, disarms a one-shot
+    /// timer and sends the signal or counts the expiry.
+    ///
+    /// While the signal from a previous expiry is still pending, the
+    /// expiry is counted as an overrun instead of sending another.
+    ///
+    /// Returns the next expiry if the setting is still armed.
+    fn expire(&self, generation: u64, now: u64) -> Option<u64> {
+        let target = match self.notify {
+            TimerNotify::Signal { target, info } => {
+                ProcessTable::lookup(target).map(|process| (process, info))
+            }
+            TimerNotify::None | TimerNotify::Count => None,
+        };
+
+        let (send, next) = {
+            let mut state = self.state.lock();
+            if state.generation != generation || state.expiry == 0 {
+                return None;
+            }
+            if now < state.expiry {
+                return Some(state.expiry);
+            }
+            let missed = if state.interval == 0 {
+                state.expiry = 0;
+                0
+            } else {
+                let (next, missed) = forward(state.expiry, state.interval, now);
+                state.expiry = next;
+                missed
+            };
+            if self.notify == TimerNotify::Count {
+                state.ticks = state.ticks.saturating_add(missed).saturating_add(1);
+            }
+            let missed = u32::try_from(missed).unwrap_or(u32::MAX);
+            let pending = target
+                .as_ref()
+                .is_some_and(|(process, info)| process.signals.is_pending(info.signo));
+            state.overrun = if pending {
+                state.overrun.saturating_add(missed).saturating_add(1)
+            } else {
+                missed
+            };
+            let send = if pending { None } else { target };
+            (send, (state.expiry != 0).then_some(state.expiry))
+        };
+
+        if let Some((process, info)) = send {
+            // A full real-time queue drops the signal, as for any sender.
+            let _ = process.signals.post_info(info);
+        }
+        if self.notify == TimerNotify::Count {
+            self.expired.wake_all();
+        }
+        next
+    }
+}
+
+/// Expiry task of a monotonic or real-time timer armed with `generation`.
+async fn run_expiry(timer: Arc<IntervalTimer>, generation: u64) {
+    let mut expiry = {
+        let state = timer.state.lock();
+        if state.generation != generation {
+            return;
+        }
+        state.expiry
+    };
+    while expiry != 0 {
+        let mut sleep = Timer::at_nanos(timer.clock.to_monotonic(expiry));
+        let current = core::future::poll_fn(|cx| {
+            if timer.generation() != generation {
+                return core::task::Poll::Ready(false);
+            }
+            if timer_expired(&sleep) {
+                return core::task::Poll::Ready(true);
+            }
+            timer.reset.register_waker(cx.waker());
+            sleep.register(cx.waker());
+            // Re-check after registering so a concurrent reset is not lost.
+            if timer.generation() == generation {
+                core::task::Poll::Pending
+            } else {
+                core::task::Poll::Ready(false)
+            }
+        })
+        .await;
+        if !current {
+            return;
+        }
+        let now = match timer.clock {
+            TimerClock::Realtime => Time::realtime_nanos(),
+            _ => Time::boot_nanos(),
+        };
+        expiry = timer.expire(generation, now).unwrap_or(0);
+    }
+}
+
+/// `timer_create` timers of a process.
+struct PosixTimers {
+    /// Timers by ID.
+    timers: BTreeMap<u32, Arc<IntervalTimer>>,
+    /// Next ID to try.
+    next_id: u32,
+}
+
+/// The interval timers of a process, shared by its threads.
+pub struct ProcessTimers {
+    /// First thread of the process, which receives process-directed timer
+    /// signals.
+    leader: Pid,
+    /// `ITIMER_REAL`, `ITIMER_VIRTUAL` and `ITIMER_PROF`, by `which`.
+    itimers: [Arc<IntervalTimer>; 3],
+    /// Timers made by `timer_create`.
+    posix: SpinLock<PosixTimers>,
+    /// Set once a CPU-time timer has been armed, so processes that never
+    /// use one skip [`check_cpu`](Self::check_cpu).
+    cpu_timers_used: AtomicBool,
+}
+
+impl ProcessTimers {
+    /// Creates the timers of a new process whose first thread is `leader`.
+    /// Process-directed signals go to that thread.
+    pub fn new(leader: Pid) -> Self {
+        let itimer = |clock, signo| {
+            Arc::new(IntervalTimer::new(
+                clock,
+                TimerNotify::Signal {
+                    target: leader,
+                    info: SignalInfo::kernel(signo),
+                },
+            ))
+        };
+        Self {
+            leader,
+            itimers: [
+                itimer(TimerClock::Monotonic, SIGALRM),
+                itimer(TimerClock::UserCpu, SIGVTALRM),
+                itimer(TimerClock::ProcessCpu, SIGPROF),
+            ],
+            posix: SpinLock::leveled(
+                "posix_timers",
+                4,
+                PosixTimers {
+                    timers: BTreeMap::new(),
+                    next_id: 0,
+                },
+            ),
+            cpu_timers_used: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns the thread that receives process-directed timer signals.
+    pub fn leader(&self) -> Pid {
+        self.leader
+    }
+
+    /// Returns `setitimer` timer `which`, if valid.
+    pub fn itimer(&self, which: usize) -> Option<&Arc<IntervalTimer>> {
+        self.itimers.get(which)
+    }
+
+    /// Adds a `timer_create` timer and returns its ID.
+    ///
+    /// `notify` builds the notification from the new ID, which is the
+    /// default signal value. Returns `None` if the process already has
+    /// [`TIMER_MAX`] timers.
+    pub fn create(
+        &self,
+        clock: TimerClock,
+        notify: impl FnOnce(u32) -> TimerNotify,
+    ) -> Option<u32> {
+        let mut posix = self.posix.lock();
+        if posix.timers.len() >= TIMER_MAX {
+            return None;
+        }
+        let mut id = posix.next_id;
+        while posix.timers.contains_key(&id) {
+            id = id.wrapping_add(1);
+        }
+        posix.next_id = id.wrapping_add(1);
+        posix
+            .timers
+            .insert(id, Arc::new(IntervalTimer::new(clock, notify(id))));
+        Some(id)
+    }
+
+    /// Returns `timer_create` timer `id`.
+    pub fn get(&self, id: u32) -> Option<Arc<IntervalTimer>> {
+        self.posix.lock().timers.get(&id).cloned()
+    }
+
+    /// Removes and disarms `timer_create` timer `id`. Returns `false` if
+    /// there is no such timer.
+    pub fn delete(&self, id: u32) -> bool {
+        let timer = self.posix.lock().timers.remove(&id);
+        timer.inspect(|timer| timer.disarm()).is_some()
+    }
+
+    /// Arms `timer`, one of this process's timers, as
+    /// [`IntervalTimer::set`] does.
+    pub fn set(
+        &self,
+        timer: &Arc<IntervalTimer>,
+        expiry: u64,
+        interval: u64,
+        now: u64,
+    ) -> (u64, u64) {
+        if expiry != 0 && timer.clock.is_cpu() {
+            self.cpu_timers_used.store(true, Ordering::Release);
+        }
+        timer.set(expiry, interval, now)
+    }
+
+    /// Removes and disarms every `timer_create` timer, as `execve` does.
+    /// The `setitimer` timers survive.
+    pub fn delete_posix(&self) {
+        let timers = core::mem::take(&mut self.posix.lock().timers);
+        for timer in timers.values() {
+            timer.disarm();
+        }
+    }
+
+    /// Disarms every timer. Called when the last thread exits.
+    pub fn disarm_all(&self) {
+        self.delete_posix();
+        for timer in &self.itimers {
+            if timer.is_armed() {
+                timer.disarm();
+            }
+        }
+    }
+
+    /// Returns `true` if a CPU-time timer is armed.
+    pub fn cpu_timer_armed(&self) -> bool {
+        if !self.cpu_timers_used.load(Ordering::Acquire) {
+            return false;
+        }
+        self.itimers[1..].iter().any(|timer| timer.is_armed())
+            || self
+                .posix
+                .lock()
+                .timers
+                .values()
+                .any(|timer| timer.clock.is_cpu() && timer.is_armed())
+    }
+
+    /// Fires the CPU-time timers that `thread`'s latest CPU time charge
+    /// made expire.
+    pub(super) fn check_cpu(&self, thread: &Process) {
+        if !self.cpu_timers_used.load(Ordering::Acquire) {
+            return;
+        }
+        let posix: Vec<Arc<IntervalTimer>> = self
+            .posix
+            .lock()
+            .timers
+            .values()
+            .filter(|timer| timer.clock.is_cpu())
+            .cloned()
+            .collect();
+        for timer in self.itimers[1..].iter().chain(posix.iter()) {
+            if let TimerClock::ThreadCpu(pid) = timer.clock
+                && pid != thread.pid
+            {
+                continue;
+            }
+            timer.expire(timer.generation(), timer.clock.now(thread));
+        }
+    }
+}
+
+impl Drop for ProcessTimers {
+    fn drop(&mut self) {
+        self.disarm_all();
+    }
+}
@@ -132,7 +132,12 @@ pub fn stop() {
 
     let cpus = cpu_count();
 
-    format::emit_header(format::FLAG_FTRACE, 0, 0, cpus);
+    format::emit_header(
+        format::FLAG_FTRACE,
+        crate::time::Time::tsc_frequency(),
+        0,
+        cpus,
+    );
 
     for cpu in 0..cpus {
         let cpu_id = CpuId::new(cpu);
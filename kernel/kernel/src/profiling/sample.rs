@@ -67,7 +67,12 @@ pub fn stop() {
     let cpus = cpu_count();
 
     // Emit HPRF header.
-    format::emit_header(format::FLAG_SAMPLES, 0, 0, cpus);
+    format::emit_header(
+        format::FLAG_SAMPLES,
+        crate::time::Time::tsc_frequency(),
+        0,
+        cpus,
+    );
 
     // Drain each CPU's buffer.
     for cpu in 0..cpus {
@@ -4,10 +4,13 @@
 //! `hadron-sched` crate for host testability. This module re-exports them
 //! and adds kernel-specific code (SMP/IPI, block_on, sleep primitives).
 
+use hadron_core::rt::{DeadlineAdmission, DeadlineParams};
+
 // Re-export everything from hadron-sched root.
 pub use hadron_sched::{
     Executor, Priority, TaskMeta, clear_preempt_pending, preempt_pending, set_preempt_pending,
-    spawn, spawn_background, spawn_critical, spawn_with,
+    set_task_affinity, set_task_policy, set_task_weight, spawn, spawn_background, spawn_critical,
+    spawn_user, spawn_with,
 };
 
 // Re-export submodules that don't need kernel extension.
@@ -22,28 +25,63 @@ pub fn executor() -> &'static Executor {
     hadron_sched::executor::global()
 }
 
+/// Registers the TSC as the clock that charges run time to User tasks, and
+/// lets high-resolution timers program the timer interrupt.
+///
+/// Called once the TSC is calibrated; polls before that are charged a
+/// nominal slice.
+#[cfg(target_arch = "x86_64")]
+pub fn init() {
+    hadron_sched::executor::set_clock_fn(|| {
+        crate::time::Time::tsc_cycles_to_nanos(crate::arch::x86_64::hw::tsc::read_tsc())
+    });
+    tick::init();
+}
+
+/// Deadline bandwidth reserved by all threads, across all CPUs.
+static DEADLINE_ADMISSION: crate::sync::SpinLock<DeadlineAdmission> =
+    crate::sync::SpinLock::leveled("DEADLINE_ADMISSION", 5, DeadlineAdmission::new());
+
+/// Replaces a thread's deadline reservation `old` by `new`; either may be
+/// `None`.
+///
+/// Returns `false` and keeps `old` reserved if `new` would overcommit the
+/// online CPUs.
+pub fn change_deadline_reservation(
+    old: Option<DeadlineParams>,
+    new: Option<DeadlineParams>,
+) -> bool {
+    DEADLINE_ADMISSION
+        .lock()
+        .change(old, new, crate::percpu::PerCpuState::cpu_count())
+}
+
 // Kernel-extended modules.
 pub mod block_on;
 pub mod primitives;
 pub mod smp;
+pub mod tick;
 
 // ── ArchHalt implementation ─────────────────────────────────────────
 
 /// x86_64 implementation of [`hadron_sched::executor::ArchHalt`].
 ///
 /// Enables interrupts and halts (`sti; hlt`), then disables interrupts
-/// after waking from the halt.
+/// after waking from the halt. The halted time counts as idle, and the
+/// periodic tick is stopped meanwhile (see [`tick`]).
 #[cfg(target_arch = "x86_64")]
 pub struct X86ArchHalt;
 
 #[cfg(target_arch = "x86_64")]
 impl hadron_sched::executor::ArchHalt for X86ArchHalt {
     fn enable_interrupts_and_halt(&self) {
+        tick::idle_enter();
         // SAFETY: IDT and LAPIC are fully configured before executor starts.
         unsafe {
             crate::arch::x86_64::instructions::interrupts::enable_and_hlt();
         }
         // Interrupt fired — disable interrupts and check for ready tasks.
         crate::arch::x86_64::instructions::interrupts::disable();
+        tick::idle_exit();
     }
 }
@@ -9,10 +9,15 @@ use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll};
 
+use crate::sched::timer::Timer;
+
 /// Sleeps for at least `ticks` timer ticks (1 tick = 1ms at 1kHz).
 pub async fn sleep_ticks(ticks: u64) {
     let deadline = crate::time::Time::timer_ticks() + ticks;
-    SleepFuture { deadline }.await;
+    SleepFuture {
+        timer: Timer::at_tick(deadline),
+    }
+    .await;
 }
 
 /// Sleeps for at least `ms` milliseconds.
@@ -20,18 +25,42 @@ pub async fn sleep_ms(ms: u64) {
     sleep_ticks(ms).await;
 }
 
+/// Sleeps for at least `nanos` nanoseconds, on a high-resolution timer.
+pub async fn sleep_nanos(nanos: u64) {
+    sleep_until(timeout_deadline(nanos)).await;
+}
+
+/// Sleeps until `deadline` nanoseconds on the monotonic clock.
+pub async fn sleep_until(deadline: u64) {
+    SleepFuture {
+        timer: Timer::at_nanos(deadline),
+    }
+    .await;
+}
+
+/// Returns the monotonic time `nanos` from now, saturating.
+pub fn timeout_deadline(nanos: u64) -> u64 {
+    crate::time::Time::boot_nanos().saturating_add(nanos)
+}
+
+/// Returns `true` if `timer` has expired on the monotonic clock.
+pub fn timer_expired(timer: &Timer) -> bool {
+    timer.is_expired(crate::time::Time::boot_nanos())
+}
+
 struct SleepFuture {
-    deadline: u64,
+    timer: Timer,
 }
 
 impl Future for SleepFuture {
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
-        if crate::time::Time::timer_ticks() >= self.deadline {
+        let this = self.get_mut();
+        if timer_expired(&this.timer) {
             Poll::Ready(())
         } else {
-            crate::sched::timer::register_sleep_waker(self.deadline, cx.waker().clone());
+            this.timer.register(cx.waker());
             Poll::Pending
         }
     }
@@ -86,14 +86,15 @@ pub fn send_wake_ipi(target_cpu: CpuId) {
 type StealResult = (
     crate::task::TaskId,
     Priority,
-    hadron_sched::executor::TaskEntry,
+    alloc::boxed::Box<hadron_sched::executor::TaskEntry>,
 );
 
 /// Attempts to steal one task from another CPU's executor.
 ///
 /// Iterates over other CPUs starting from a pseudo-random offset (to avoid
 /// thundering herd). Uses `try_lock` to avoid blocking victims. Returns the
-/// stolen task's ID, priority, and full entry (future + metadata).
+/// stolen task's ID, priority, and full entry (future + metadata). Tasks
+/// whose affinity mask excludes this CPU are never stolen.
 ///
 /// The caller must insert the stolen entry into their local executor's task
 /// map and ready queue.
@@ -118,7 +119,7 @@ pub(crate) fn try_steal() -> Option<StealResult> {
                 continue;
             }
 
-            if let Some(stolen) = hadron_sched::executor::for_cpu(target).steal_task() {
+            if let Some(stolen) = hadron_sched::executor::for_cpu(target).steal_task(local_cpu) {
                 return Some(stolen);
             }
         }
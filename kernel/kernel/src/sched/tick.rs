@@ -0,0 +1,327 @@
+//! Dynamic tick, high-resolution timer interrupts and idle time accounting.
+//!
+//! Every CPU normally runs the 1ms periodic LAPIC timer, which drives the
+//! timer wheel and preempts user threads. When a high-resolution timer is
+//! due before the next tick, the CPU switches its LAPIC timer to a single
+//! interrupt at that deadline and resumes the tick afterwards.
+//!
+//! With `hadron_tickless`, a CPU also stops the tick whenever nothing
+//! needs it:
+//!
+//! - **Idle**: [`idle_enter`] runs just before the CPU halts and programs a
+//!   single timer interrupt for the next event, the earliest timer or
+//!   deadline-task release. With no event pending the timer stays off.
+//!   [`idle_exit`] restarts the tick.
+//! - **Single task**: [`user_enter`] does the same before a thread enters
+//!   userspace with no other task waiting on this CPU, since there is
+//!   nothing to preempt it for.
+//!
+//! A stopped tick restarts as soon as a task is waiting on this CPU: from
+//! [`irq_exit`] after an interrupt that queued one (a wake IPI or a local
+//! device) and from the timer handler after it woke one. A posted signal
+//! [`kick`]s busy tickless CPUs, so a thread running alone still notices it
+//! within a tick. Single timer interrupts use TSC-deadline mode when the
+//! CPU has it, and the LAPIC one-shot mode otherwise.
+//!
+//! Idle CPUs no longer look for work to steal every tick, so a ticking CPU
+//! with tasks waiting wakes one idle CPU from its timer handler instead.
+//!
+//! Idle time is accounted with or without the dynamic tick: each CPU adds
+//! the time between [`idle_enter`] and [`idle_exit`] to its total.
+
+use hadron_core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use crate::arch::x86_64::acpi::Acpi;
+use crate::arch::x86_64::cpuid::{CpuFeatures, has_feature};
+use crate::arch::x86_64::hw::local_apic::LocalApic;
+use crate::arch::x86_64::hw::tsc::read_tsc;
+use crate::arch::x86_64::instructions::interrupts::without_interrupts;
+use crate::arch::x86_64::interrupts::dispatch::vectors;
+use crate::id::CpuId;
+use crate::percpu::{CpuLocal, MAX_CPUS, PerCpuState};
+use crate::sched::timer::TICK_NS;
+use crate::time::Time;
+
+/// TSC value when this CPU halted, or 0 while it is running.
+static IDLE_SINCE: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
+
+/// Time this CPU has spent halted, in nanoseconds.
+static IDLE_NS: CpuLocal<AtomicU64> = CpuLocal::new([const { AtomicU64::new(0) }; MAX_CPUS]);
+
+/// Whether this CPU's LAPIC timer runs the periodic tick, rather than a
+/// single interrupt or nothing.
+static PERIODIC: CpuLocal<AtomicBool> = CpuLocal::new([const { AtomicBool::new(true) }; MAX_CPUS]);
+
+/// Lets high-resolution timers reprogram the timer interrupt.
+pub fn init() {
+    crate::sched::timer::set_program_fn(hrtimer_armed);
+}
+
+/// Called with interrupts disabled right before the CPU halts.
+pub fn idle_enter() {
+    IDLE_SINCE.get().store(read_tsc(), Ordering::Relaxed);
+    #[cfg(hadron_tickless)]
+    dynamic::idle_enter();
+}
+
+/// Called with interrupts disabled right after the CPU wakes from a halt.
+pub fn idle_exit() {
+    let since = IDLE_SINCE.get().swap(0, Ordering::Relaxed);
+    if since != 0 {
+        let ns = Time::tsc_cycles_to_nanos(read_tsc().saturating_sub(since));
+        IDLE_NS.get().fetch_add(ns, Ordering::Relaxed);
+    }
+    #[cfg(hadron_tickless)]
+    dynamic::idle_exit();
+}
+
+/// Returns the time `cpu` has spent idle, in nanoseconds.
+pub fn idle_nanos(cpu: CpuId) -> u64 {
+    IDLE_NS.get_for(cpu).load(Ordering::Relaxed)
+}
+
+/// Returns the time all CPUs together have spent idle, in nanoseconds.
+pub fn total_idle_nanos() -> u64 {
+    (0..PerCpuState::cpu_count())
+        .map(|cpu| idle_nanos(CpuId::new(cpu)))
+        .sum()
+}
+
+/// Called right before a thread enters userspace.
+///
+/// Stops the tick if no other task is waiting on this CPU, unless
+/// `needs_tick` (the thread has a deadline budget to enforce or a CPU-time
+/// timer armed); otherwise makes sure it runs.
+pub fn user_enter(needs_tick: bool) {
+    #[cfg(hadron_tickless)]
+    dynamic::user_enter(needs_tick);
+    #[cfg(not(hadron_tickless))]
+    let _ = needs_tick;
+}
+
+/// Called from the timer interrupt handler after it woke expired timers.
+pub fn timer_tick() {
+    #[cfg(hadron_tickless)]
+    dynamic::timer_tick();
+    reprogram();
+}
+
+/// Called at the end of every other hardware interrupt.
+pub fn irq_exit() {
+    #[cfg(hadron_tickless)]
+    dynamic::irq_exit();
+}
+
+/// Restarts the tick on every CPU that runs a thread with its tick stopped.
+///
+/// Called after posting a signal: the thread it is for may be running
+/// alone in userspace and would not trap into the kernel otherwise.
+pub fn kick() {
+    #[cfg(hadron_tickless)]
+    dynamic::kick();
+}
+
+/// Returns `true` if this CPU is halted (inside an interrupt that woke it).
+#[cfg(hadron_tickless)]
+fn is_idle() -> bool {
+    IDLE_SINCE.get().load(Ordering::Relaxed) != 0
+}
+
+/// Returns `true` if this CPU's tick is stopped.
+fn tick_stopped() -> bool {
+    #[cfg(hadron_tickless)]
+    return dynamic::stopped();
+    #[cfg(not(hadron_tickless))]
+    false
+}
+
+/// Called when a high-resolution timer at `deadline` became the earliest
+/// on this CPU.
+fn hrtimer_armed(deadline: u64) {
+    without_interrupts(|| {
+        // A running tick switches to a single interrupt once the deadline
+        // is less than a tick away.
+        let periodic = PERIODIC.get().load(Ordering::Relaxed);
+        if !periodic || deadline < Time::boot_nanos().saturating_add(TICK_NS) {
+            reprogram();
+        }
+    });
+}
+
+/// Returns this CPU's LAPIC once its timer is calibrated.
+fn lapic() -> Option<LocalApic> {
+    let (initial_count, _) = Acpi::lapic_timer_config();
+    let base = Acpi::lapic_virt().filter(|_| initial_count > 0)?;
+    // SAFETY: The LAPIC was mapped during ACPI init and the mapping is
+    // permanent.
+    Some(unsafe { LocalApic::new(base) })
+}
+
+/// Programs this CPU's LAPIC timer for its next event.
+///
+/// A running tick stays periodic unless a high-resolution timer is due
+/// first. A stopped tick gets a single interrupt for the next timer or
+/// deadline-task release, or none.
+fn reprogram() {
+    let Some(lapic) = lapic() else {
+        return;
+    };
+    let now = Time::boot_nanos();
+    let delay = if tick_stopped() {
+        next_event_delay(now)
+    } else {
+        crate::sched::timer::next_hr_expiry()
+            .map(|at| at.saturating_sub(now))
+            .filter(|&delay| delay < TICK_NS)
+    };
+
+    let vector = vectors::TIMER.as_irq_vector();
+    match delay {
+        Some(delay) => {
+            PERIODIC.get().store(false, Ordering::Relaxed);
+            if has_feature(CpuFeatures::TSC_DEADLINE) && Time::tsc_frequency() != 0 {
+                let deadline = read_tsc().saturating_add(Time::nanos_to_tsc_cycles(delay));
+                // SAFETY: The CPU supports TSC-deadline mode.
+                unsafe { lapic.start_timer_tsc_deadline(vector, deadline) };
+            } else {
+                let (per_ms, divide) = Acpi::lapic_timer_config();
+                let count = u128::from(delay) * u128::from(per_ms) / u128::from(TICK_NS);
+                let count = u32::try_from(count).unwrap_or(u32::MAX).max(1);
+                lapic.start_timer_oneshot(vector, count, divide);
+            }
+        }
+        None if tick_stopped() => {
+            PERIODIC.get().store(false, Ordering::Relaxed);
+            lapic.stop_timer();
+        }
+        None => {
+            if !PERIODIC.get().swap(true, Ordering::Relaxed) {
+                let (initial_count, divide) = Acpi::lapic_timer_config();
+                lapic.start_timer_periodic(vector, initial_count, divide);
+            }
+        }
+    }
+}
+
+/// Returns the nanoseconds from `now` until the next timer or
+/// deadline-task release, or `None` if there is neither.
+fn next_event_delay(now: u64) -> Option<u64> {
+    let timer = crate::sched::timer::next_expiry().map(|at| at.saturating_sub(now));
+    let release = crate::sched::executor()
+        .next_release()
+        .map(|at| at.saturating_sub(Time::tsc_cycles_to_nanos(read_tsc())));
+    match (timer, release) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(hadron_tickless)]
+mod dynamic {
+    use hadron_core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+    use super::reprogram;
+    use crate::id::CpuId;
+    use crate::percpu::{CpuLocal, MAX_CPUS, PerCpuState};
+
+    /// Whether this CPU's periodic tick is stopped.
+    static STOPPED: CpuLocal<AtomicBool> =
+        CpuLocal::new([const { AtomicBool::new(false) }; MAX_CPUS]);
+
+    /// Set by [`kick`] to make this CPU restart its tick at the next
+    /// interrupt.
+    static KICKED: CpuLocal<AtomicBool> =
+        CpuLocal::new([const { AtomicBool::new(false) }; MAX_CPUS]);
+
+    /// Halted CPUs with a stopped tick (bit `n` = CPU `n`). CPUs from 64
+    /// up are never woken for work stealing.
+    static IDLE_CPUS: AtomicU64 = AtomicU64::new(0);
+
+    /// Returns this CPU's bit in [`IDLE_CPUS`], or 0 if it has none.
+    fn cpu_bit() -> u64 {
+        let cpu = PerCpuState::current().get_cpu_id().as_u32();
+        1u64.checked_shl(cpu).unwrap_or(0)
+    }
+
+    pub(super) fn stopped() -> bool {
+        STOPPED.get().load(Ordering::Relaxed)
+    }
+
+    pub(super) fn idle_enter() {
+        IDLE_CPUS.fetch_or(cpu_bit(), Ordering::Relaxed);
+        stop();
+    }
+
+    pub(super) fn idle_exit() {
+        IDLE_CPUS.fetch_and(!cpu_bit(), Ordering::Relaxed);
+        restart();
+    }
+
+    pub(super) fn user_enter(needs_tick: bool) {
+        if needs_tick || crate::sched::executor().has_ready() {
+            restart();
+        } else {
+            stop();
+        }
+    }
+
+    /// Updates the tick state from the timer handler, which reprograms the
+    /// timer afterwards.
+    pub(super) fn timer_tick() {
+        let waiting = crate::sched::executor().has_ready();
+        if !stopped() {
+            if waiting {
+                wake_idle_cpu();
+            }
+        } else if !super::is_idle() && (waiting || KICKED.get().load(Ordering::Relaxed)) {
+            // An idle CPU restarts its tick in idle_exit once the halt
+            // returns.
+            KICKED.get().store(false, Ordering::Relaxed);
+            STOPPED.get().store(false, Ordering::Relaxed);
+        }
+    }
+
+    pub(super) fn irq_exit() {
+        if stopped()
+            && !super::is_idle()
+            && (KICKED.get().load(Ordering::Relaxed) || crate::sched::executor().has_ready())
+        {
+            restart();
+        }
+    }
+
+    pub(super) fn kick() {
+        let current = PerCpuState::current().get_cpu_id();
+        for cpu in (0..PerCpuState::cpu_count()).map(CpuId::new) {
+            let busy = super::IDLE_SINCE.get_for(cpu).load(Ordering::Relaxed) == 0;
+            if STOPPED.get_for(cpu).load(Ordering::Relaxed) && busy {
+                KICKED.get_for(cpu).store(true, Ordering::Relaxed);
+                if cpu != current {
+                    crate::sched::smp::send_wake_ipi(cpu);
+                }
+            }
+        }
+    }
+
+    /// Wakes one other halted CPU so it can steal a waiting task.
+    fn wake_idle_cpu() {
+        let idle = IDLE_CPUS.load(Ordering::Relaxed) & !cpu_bit();
+        if idle != 0 {
+            crate::sched::smp::send_wake_ipi(CpuId::new(idle.trailing_zeros()));
+        }
+    }
+
+    /// Stops the periodic tick and programs the next event instead.
+    fn stop() {
+        STOPPED.get().store(true, Ordering::Relaxed);
+        reprogram();
+    }
+
+    /// Restarts the periodic tick if it is stopped.
+    fn restart() {
+        KICKED.get().store(false, Ordering::Relaxed);
+        if STOPPED.get().swap(false, Ordering::Relaxed) {
+            reprogram();
+        }
+    }
+}
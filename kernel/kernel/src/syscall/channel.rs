@@ -12,7 +12,7 @@ use crate::fs::try_poll_immediate;
 use crate::id::Fd;
 use crate::ipc::channel::ChannelEndpoint;
 use crate::ipc::service::ServiceListener;
-use crate::syscall::userptr::UserSlice;
+use crate::syscall::userptr::{UserPtr, UserSlice};
 use crate::syscall::{EBADF, EFAULT, EINVAL};
 
 /// `sys_channel_create` — create a channel pair and write `[fd_a, fd_b]` to user space.
@@ -23,7 +23,7 @@ use crate::syscall::{EBADF, EFAULT, EINVAL};
     reason = "fd numbers are small, wrap is impossible"
 )]
 pub(super) fn sys_channel_create(fds_ptr: usize) -> isize {
-    let Ok(user_slice) = UserSlice::new(fds_ptr, 2 * core::mem::size_of::<usize>()) else {
+    let Ok(user_fds) = UserPtr::<[usize; 2]>::new(fds_ptr) else {
         return -EFAULT;
     };
 
@@ -36,14 +36,10 @@ pub(super) fn sys_channel_create(fds_ptr: usize) -> isize {
         (a, b)
     });
 
-    // SAFETY: UserSlice validated the pointer range is in user space.
-    unsafe {
-        let dst = user_slice.addr() as *mut usize;
-        core::ptr::write(dst, fd_a.as_usize());
-        core::ptr::write(dst.add(1), fd_b.as_usize());
+    match user_fds.write([fd_a.as_usize(), fd_b.as_usize()]) {
+        Ok(()) => 0,
+        Err(e) => e,
     }
-
-    0
 }
 
 /// `sys_channel_send` — send a message on a channel endpoint.
@@ -60,8 +56,10 @@ pub(super) fn sys_channel_send(handle: usize, buf_ptr: usize, buf_len: usize) ->
         return -EFAULT;
     };
 
-    // SAFETY: UserSlice validated that [buf_ptr, buf_ptr+buf_len) is in user space.
-    let buf = unsafe { user_slice.as_slice() };
+    let buf = match user_slice.read_to_vec() {
+        Ok(buf) => buf,
+        Err(e) => return e,
+    };
 
     // Extract inode from fd table, then release the process lock before I/O.
     let inode = match crate::proc::ProcessTable::with_current(|process| {
@@ -79,7 +77,7 @@ pub(super) fn sys_channel_send(handle: usize, buf_ptr: usize, buf_len: usize) ->
     };
 
     // Channel write enqueues a discrete message; offset is unused.
-    match try_poll_immediate(inode.write(0, buf)) {
+    match try_poll_immediate(inode.write(0, &buf)) {
         Some(Ok(n)) => n as isize,
         Some(Err(e)) => {
             if matches!(e, crate::fs::FsError::BrokenPipe) {
@@ -110,8 +108,10 @@ pub(super) fn sys_channel_recv(handle: usize, buf_ptr: usize, buf_len: usize) ->
         return -EFAULT;
     };
 
-    // SAFETY: UserSlice validated that [buf_ptr, buf_ptr+buf_len) is in user space.
-    let buf = unsafe { user_slice.as_mut_slice() };
+    let mut buf = match user_slice.kernel_buffer() {
+        Ok(buf) => buf,
+        Err(e) => return e,
+    };
 
     // Extract inode from fd table, then release the process lock before I/O.
     let inode = match crate::proc::ProcessTable::with_current(|process| {
@@ -129,8 +129,11 @@ pub(super) fn sys_channel_recv(handle: usize, buf_ptr: usize, buf_len: usize) ->
     };
 
     // Channel read dequeues a discrete message; offset is unused.
-    match try_poll_immediate(inode.read(0, buf)) {
-        Some(Ok(n)) => n as isize,
+    match try_poll_immediate(inode.read(0, &mut buf)) {
+        Some(Ok(n)) => match user_slice.write_from(&buf[..n]) {
+            Ok(()) => n as isize,
+            Err(e) => e,
+        },
         Some(Err(e)) => -e.to_errno(),
         None => {
             drop(inode);
@@ -200,8 +203,10 @@ pub(super) fn sys_channel_send_fd(
         return -EFAULT;
     };
 
-    // SAFETY: UserSlice validated that [buf_ptr, buf_ptr+buf_len) is in user space.
-    let buf = unsafe { user_slice.as_slice() };
+    let buf = match user_slice.read_to_vec() {
+        Ok(buf) => buf,
+        Err(e) => return e,
+    };
 
     // Extract both the channel inode and the inode to attach.
     let (channel_inode, attached_inode) = match crate::proc::ProcessTable::with_current(|process| {
@@ -223,7 +228,7 @@ pub(super) fn sys_channel_send_fd(
     // for passing a channel fd.
     let endpoint: &ChannelEndpoint = unsafe { &*endpoint_ptr };
 
-    match endpoint.send_with_attachment(buf, Some(attached_inode)) {
+    match endpoint.send_with_attachment(&buf, Some(attached_inode)) {
         Some(Ok(n)) => n as isize,
         Some(Err(e)) => {
             if matches!(e, crate::fs::FsError::BrokenPipe) {
@@ -259,12 +264,14 @@ pub(super) fn sys_channel_recv_fd(
     let Ok(user_slice) = UserSlice::new(buf_ptr, buf_len) else {
         return -EFAULT;
     };
-    let Ok(fd_out_slice) = UserSlice::new(fd_out_ptr, core::mem::size_of::<usize>()) else {
+    let Ok(fd_out) = UserPtr::<usize>::new(fd_out_ptr) else {
         return -EFAULT;
     };
 
-    // SAFETY: UserSlice validated the range.
-    let buf = unsafe { user_slice.as_mut_slice() };
+    let mut buf = match user_slice.kernel_buffer() {
+        Ok(buf) => buf,
+        Err(e) => return e,
+    };
 
     let channel_inode = match crate::proc::ProcessTable::with_current(|process| {
         let fd_table = process.fd_table.lock();
@@ -279,7 +286,7 @@ pub(super) fn sys_channel_recv_fd(
     // SAFETY: The fd was opened as a channel endpoint.
     let endpoint: &ChannelEndpoint = unsafe { &*endpoint_ptr };
 
-    match endpoint.recv_with_attachment(buf) {
+    match endpoint.recv_with_attachment(&mut buf) {
         Some(Ok((data_len, attached))) => {
             let received_fd = match attached {
                 Some(inode) => {
@@ -291,11 +298,13 @@ pub(super) fn sys_channel_recv_fd(
                 }
                 None => usize::MAX,
             };
-            // SAFETY: fd_out_slice validated the pointer range.
-            unsafe {
-                core::ptr::write(fd_out_slice.addr() as *mut usize, received_fd);
+            if let Err(e) = user_slice.write_from(&buf[..data_len]) {
+                return e;
+            }
+            match fd_out.write(received_fd) {
+                Ok(()) => data_len as isize,
+                Err(e) => e,
             }
-            data_len as isize
         }
         Some(Err(e)) => -e.to_errno(),
         None => {
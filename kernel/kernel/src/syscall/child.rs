@@ -0,0 +1,93 @@
+//! Child syscall handlers: child_wait4, child_waitid and the subreaper
+//! flag.
+//!
+//! Both waits block through the same TRAP_WAIT path as `task_wait`; see
+//! [`trap_wait`](super::process::trap_wait).
+
+use crate::id::Pid;
+use crate::proc::{ProcessTable, WaitOutput, WaitTarget};
+use crate::syscall::EINVAL;
+use hadron_core::sync::atomic::Ordering;
+use hadron_syscall::{
+    P_ALL, P_PGID, P_PID, WCONTINUED, WEXITED, WNOHANG, WNOWAIT, WSTOPPED, WUNTRACED,
+};
+
+/// Returns the process group ID of the calling process.
+fn current_pgid() -> u32 {
+    ProcessTable::with_current(|p| p.pgid.load(Ordering::Acquire))
+}
+
+/// `sys_child_wait4(pid, status_ptr, options, rusage_ptr)` — waits for a
+/// child selected by a `waitpid`-style `pid`.
+///
+/// Returns `-EINVAL` for unknown options; otherwise blocks in TRAP_WAIT.
+#[expect(clippy::cast_possible_truncation, reason = "PIDs fit in u32")]
+pub(super) fn sys_child_wait4(
+    pid: usize,
+    status_ptr: usize,
+    options: usize,
+    rusage_ptr: usize,
+) -> isize {
+    if options & !(WNOHANG | WUNTRACED | WCONTINUED) != 0 {
+        return -EINVAL;
+    }
+    let target = match pid.cast_signed() {
+        -1 => WaitTarget::Any,
+        0 => WaitTarget::Group(current_pgid()),
+        pid if pid > 0 => WaitTarget::Pid(Pid::new(pid as u32)),
+        pgid => WaitTarget::Group(pgid.unsigned_abs() as u32),
+    };
+    let output = WaitOutput {
+        status_ptr: status_ptr as u64,
+        rusage_ptr: rusage_ptr as u64,
+        ..WaitOutput::default()
+    };
+    super::process::trap_wait(target, options | WEXITED, output)
+}
+
+/// `sys_child_waitid(idtype, id, info_ptr, options, rusage_ptr)` — waits
+/// for a child and reports it as a `SigInfo`.
+///
+/// Returns `-EINVAL` for an unknown `idtype`, a zero `P_PID` id, or
+/// options that select no event; otherwise blocks in TRAP_WAIT.
+#[expect(clippy::cast_possible_truncation, reason = "PIDs fit in u32")]
+pub(super) fn sys_child_waitid(
+    idtype: usize,
+    id: usize,
+    info_ptr: usize,
+    options: usize,
+    rusage_ptr: usize,
+) -> isize {
+    if options & !(WNOHANG | WNOWAIT | WEXITED | WSTOPPED | WCONTINUED) != 0
+        || options & (WEXITED | WSTOPPED | WCONTINUED) == 0
+    {
+        return -EINVAL;
+    }
+    let target = match idtype {
+        P_ALL => WaitTarget::Any,
+        P_PID if id != 0 => WaitTarget::Pid(Pid::new(id as u32)),
+        P_PGID if id == 0 => WaitTarget::Group(current_pgid()),
+        P_PGID => WaitTarget::Group(id as u32),
+        _ => return -EINVAL,
+    };
+    let output = WaitOutput {
+        info_ptr: info_ptr as u64,
+        rusage_ptr: rusage_ptr as u64,
+        waitid: true,
+        ..WaitOutput::default()
+    };
+    super::process::trap_wait(target, options, output)
+}
+
+/// `sys_child_set_subreaper(enable)` — marks or unmarks the caller as a
+/// child subreaper.
+pub(super) fn sys_child_set_subreaper(enable: usize) -> isize {
+    ProcessTable::with_current(|p| p.child_subreaper.store(enable != 0, Ordering::Release));
+    0
+}
+
+/// `sys_child_get_subreaper()` — returns 1 if the caller is a child
+/// subreaper, otherwise 0.
+pub(super) fn sys_child_get_subreaper() -> isize {
+    ProcessTable::with_current(|p| isize::from(p.child_subreaper.load(Ordering::Acquire)))
+}
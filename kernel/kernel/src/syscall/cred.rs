@@ -0,0 +1,144 @@
+//! Credential syscall handlers: get and set the user and group IDs and the
+//! supplementary groups of the calling process.
+//!
+//! The transition rules live in [`Credentials`]; these handlers only copy
+//! arguments in and out and translate [`CredError`] into errnos. Changes
+//! apply to every thread of the process.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use crate::cred::{CredError, Credentials};
+use crate::id::{Gid, Uid};
+use crate::proc::ProcessTable;
+use crate::syscall::userptr::{UserPtr, read_user_array, write_user_array};
+use crate::syscall::{CredInfo, EINVAL, EPERM, ID_UNCHANGED, NGROUPS_MAX};
+
+/// Converts a [`CredError`] into a negated errno.
+fn cred_errno(e: CredError) -> isize {
+    match e {
+        CredError::NotPermitted => -EPERM,
+        CredError::TooManyGroups => -EINVAL,
+    }
+}
+
+/// Validates an ID argument: it must fit in 32 bits and must not be
+/// [`ID_UNCHANGED`].
+fn id_arg(id: usize) -> Result<u32, isize> {
+    u32::try_from(id)
+        .ok()
+        .filter(|_| id != ID_UNCHANGED)
+        .ok_or(-EINVAL)
+}
+
+/// Like [`id_arg`], but maps [`ID_UNCHANGED`] to `None`.
+fn opt_id_arg(id: usize) -> Result<Option<u32>, isize> {
+    if id == ID_UNCHANGED {
+        Ok(None)
+    } else {
+        id_arg(id).map(Some)
+    }
+}
+
+/// Applies `f` to the calling process's credentials.
+fn update(f: impl FnOnce(&mut Credentials) -> Result<(), CredError>) -> isize {
+    match ProcessTable::with_current(|p| p.update_cred(f)) {
+        Ok(()) => 0,
+        Err(e) => cred_errno(e),
+    }
+}
+
+/// `sys_cred_get` — write the caller's user and group IDs as a [`CredInfo`].
+pub(super) fn sys_cred_get(buf_ptr: usize, buf_len: usize) -> isize {
+    if buf_len < core::mem::size_of::<CredInfo>() {
+        return -EINVAL;
+    }
+    let cred = ProcessTable::with_current(|p| p.cred());
+    let info = CredInfo {
+        uid: cred.uid().as_u32(),
+        euid: cred.euid().as_u32(),
+        suid: cred.suid().as_u32(),
+        gid: cred.gid().as_u32(),
+        egid: cred.egid().as_u32(),
+        sgid: cred.sgid().as_u32(),
+    };
+    match UserPtr::<CredInfo>::new(buf_ptr).and_then(|p| p.write(info)) {
+        Ok(()) => 0,
+        Err(e) => e,
+    }
+}
+
+/// `sys_cred_getgroups` — copy out the supplementary groups.
+///
+/// With `count` 0, only returns the number of groups.
+#[expect(
+    clippy::cast_possible_wrap,
+    reason = "at most NGROUPS_MAX groups, wrap is impossible"
+)]
+pub(super) fn sys_cred_getgroups(buf_ptr: usize, count: usize) -> isize {
+    let cred = ProcessTable::with_current(|p| p.cred());
+    let groups = cred.groups();
+    if count == 0 {
+        return groups.len() as isize;
+    }
+    if count < groups.len() {
+        return -EINVAL;
+    }
+    let ids: Vec<u32> = groups.iter().map(|g| g.as_u32()).collect();
+    match write_user_array(buf_ptr, &ids) {
+        Ok(()) => ids.len() as isize,
+        Err(e) => e,
+    }
+}
+
+/// `sys_cred_setuid` — POSIX `setuid`.
+pub(super) fn sys_cred_setuid(uid: usize) -> isize {
+    match id_arg(uid) {
+        Ok(uid) => update(|c| c.set_uid(Uid::new(uid))),
+        Err(e) => e,
+    }
+}
+
+/// `sys_cred_setgid` — POSIX `setgid`.
+pub(super) fn sys_cred_setgid(gid: usize) -> isize {
+    match id_arg(gid) {
+        Ok(gid) => update(|c| c.set_gid(Gid::new(gid))),
+        Err(e) => e,
+    }
+}
+
+/// `sys_cred_setresuid` — set the real, effective and saved user IDs.
+pub(super) fn sys_cred_setresuid(uid: usize, euid: usize, suid: usize) -> isize {
+    let (Ok(uid), Ok(euid), Ok(suid)) = (opt_id_arg(uid), opt_id_arg(euid), opt_id_arg(suid))
+    else {
+        return -EINVAL;
+    };
+    update(|c| c.set_resuid(uid.map(Uid::new), euid.map(Uid::new), suid.map(Uid::new)))
+}
+
+/// `sys_cred_setresgid` — set the real, effective and saved group IDs.
+pub(super) fn sys_cred_setresgid(gid: usize, egid: usize, sgid: usize) -> isize {
+    let (Ok(gid), Ok(egid), Ok(sgid)) = (opt_id_arg(gid), opt_id_arg(egid), opt_id_arg(sgid))
+    else {
+        return -EINVAL;
+    };
+    update(|c| c.set_resgid(gid.map(Gid::new), egid.map(Gid::new), sgid.map(Gid::new)))
+}
+
+/// `sys_cred_setgroups` — replace the supplementary groups.
+pub(super) fn sys_cred_setgroups(buf_ptr: usize, count: usize) -> isize {
+    if count > NGROUPS_MAX {
+        return -EINVAL;
+    }
+    let ids = if count == 0 {
+        Vec::new()
+    } else {
+        match read_user_array::<u32>(buf_ptr, count) {
+            Ok(ids) => ids,
+            Err(e) => return e,
+        }
+    };
+    let groups: Vec<Gid> = ids.into_iter().map(Gid::new).collect();
+    update(|c| c.set_groups(&groups))
+}
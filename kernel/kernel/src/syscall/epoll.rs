@@ -0,0 +1,168 @@
+//! Epoll syscall handlers: `epoll_create`, `epoll_ctl` and `epoll_wait`.
+//!
+//! The interest list itself is [`Epoll`]; these handlers resolve fds and
+//! copy events in and out. A blocking `epoll_wait` reuses `TRAP_POLL`, with
+//! `process_task` waiting on the interest list instead of a `PollFd` array.
+
+use alloc::sync::Arc;
+
+use crate::fs::Inode;
+use crate::fs::file::OpenFlags;
+use crate::id::Fd;
+use crate::ipc::epoll::Epoll;
+use crate::syscall::userptr::{UserPtr, UserSlice, write_user_array};
+use crate::syscall::{
+    EBADF, EINVAL, EPOLL_CLOEXEC, EPOLL_CTL_ADD, EPOLL_CTL_DEL, EPOLL_CTL_MOD, EpollEvent,
+};
+
+/// Most events one `epoll_wait` returns.
+const MAX_EVENTS: usize = 1024;
+
+/// Looks up epoll fd `epfd`, returning `-EBADF` if it is not open and
+/// `-EINVAL` if it is not an epoll fd.
+fn epoll_inode(epfd: usize) -> Result<Arc<dyn Inode>, isize> {
+    let epfd = u32::try_from(epfd).map_err(|_| -EBADF)?;
+    let inode = super::vfs::fd_inode(Fd::new(epfd))?;
+    if is_epoll(&inode) {
+        Ok(inode)
+    } else {
+        Err(-EINVAL)
+    }
+}
+
+fn is_epoll(inode: &Arc<dyn Inode>) -> bool {
+    inode.as_any().is_some_and(|any| any.is::<Epoll>())
+}
+
+fn as_epoll(inode: &Arc<dyn Inode>) -> &Epoll {
+    inode
+        .as_any()
+        .and_then(|any| any.downcast_ref::<Epoll>())
+        .expect("checked by epoll_inode")
+}
+
+/// `sys_epoll_create` — creates an empty interest list.
+pub(super) fn sys_epoll_create(flags: usize) -> isize {
+    if flags & !EPOLL_CLOEXEC != 0 {
+        return -EINVAL;
+    }
+    super::vfs::open_anon(Arc::new(Epoll::new()), OpenFlags::READ, flags)
+}
+
+/// `sys_epoll_ctl` — adds, changes or removes the interest of `epfd` in
+/// `fd`.
+pub(super) fn sys_epoll_ctl(epfd: usize, op: usize, fd: usize, event_ptr: usize) -> isize {
+    let inode = match epoll_inode(epfd) {
+        Ok(inode) => inode,
+        Err(e) => return e,
+    };
+    let Ok(fd) = u32::try_from(fd) else {
+        return -EBADF;
+    };
+    let target = match super::vfs::fd_inode(Fd::new(fd)) {
+        Ok(target) => target,
+        Err(e) => return e,
+    };
+    // Nested interest lists are not supported.
+    if is_epoll(&target) {
+        return -EINVAL;
+    }
+    let epoll = as_epoll(&inode);
+
+    let result = match op {
+        EPOLL_CTL_ADD | EPOLL_CTL_MOD => {
+            let event = match UserPtr::<EpollEvent>::new(event_ptr).and_then(|p| p.read()) {
+                Ok(event) => event,
+                Err(e) => return e,
+            };
+            if op == EPOLL_CTL_ADD {
+                epoll.add(fd, &target, event)
+            } else {
+                epoll.modify(fd, event)
+            }
+        }
+        EPOLL_CTL_DEL => epoll.remove(fd),
+        _ => return -EINVAL,
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => -e.to_errno(),
+    }
+}
+
+/// `sys_epoll_wait` — reports ready fds in the interest list of `epfd`.
+///
+/// `timeout_ns`: 0 = non-blocking, `usize::MAX` = infinite, other values =
+/// timeout in nanoseconds. When nothing is ready and `timeout_ns > 0`,
+/// longjmps back to `process_task` via `TRAP_POLL`.
+#[expect(clippy::cast_possible_wrap, reason = "at most MAX_EVENTS events")]
+pub(super) fn sys_epoll_wait(
+    epfd: usize,
+    events_ptr: usize,
+    max_events: usize,
+    timeout_ns: usize,
+) -> isize {
+    let inode = match epoll_inode(epfd) {
+        Ok(inode) => inode,
+        Err(e) => return e,
+    };
+    if max_events == 0 {
+        return -EINVAL;
+    }
+    let max_events = max_events.min(MAX_EVENTS);
+    let Some(len) = max_events.checked_mul(size_of::<EpollEvent>()) else {
+        return -EINVAL;
+    };
+    if let Err(e) = UserSlice::new(events_ptr, len) {
+        return e;
+    }
+
+    let events = as_epoll(&inode).collect(max_events);
+    if events.is_empty() {
+        if timeout_ns > 0 {
+            drop(inode);
+            trap_epoll_wait(epfd, events_ptr, max_events, timeout_ns);
+        }
+        return 0;
+    }
+    match write_user_array(events_ptr, &events) {
+        Ok(()) => events.len() as isize,
+        Err(e) => e,
+    }
+}
+
+/// Trigger a `TRAP_POLL` longjmp back to `process_task` for an
+/// `epoll_wait`.
+///
+/// Sets the epoll parameters, restores kernel CR3 and GS bases, then calls
+/// `restore_kernel_context` — never returns.
+fn trap_epoll_wait(epfd: usize, events_ptr: usize, max_events: usize, timeout_ns: usize) -> ! {
+    use crate::arch::x86_64::registers::control::Cr3;
+    use crate::arch::x86_64::registers::model_specific::{IA32_GS_BASE, IA32_KERNEL_GS_BASE};
+    use crate::arch::x86_64::userspace::restore_kernel_context;
+
+    let kernel_cr3 = crate::proc::TrapContext::kernel_cr3();
+
+    // SAFETY: Restoring kernel CR3 and GS bases is the standard pattern
+    // for returning from userspace context to kernel context.
+    unsafe {
+        Cr3::write(kernel_cr3);
+        let percpu = IA32_GS_BASE.read();
+        IA32_KERNEL_GS_BASE.write(percpu);
+    }
+
+    crate::proc::PollState::set_epoll_params(
+        epfd as u64,
+        events_ptr as u64,
+        max_events as u64,
+        timeout_ns as u64,
+    );
+    crate::proc::TrapContext::set_trap_reason(crate::proc::TrapReason::Poll);
+
+    let saved_rsp = crate::proc::TrapContext::saved_kernel_rsp();
+    // SAFETY: saved_rsp is the kernel RSP saved by enter_userspace_save,
+    // still valid on the executor stack.
+    unsafe {
+        restore_kernel_context(saved_rsp);
+    }
+}
@@ -1,26 +1,41 @@
-//! Event syscall handlers: `event_wait_many` (poll) and `futex`.
+//! Event syscall handlers: `event_create` (eventfd), `event_wait_many`
+//! (poll) and `futex`.
 
 use alloc::sync::Arc;
 use alloc::vec::Vec;
 
 use crate::fs::Inode;
+use crate::fs::file::OpenFlags;
 use crate::id::Fd;
+use crate::ipc::eventfd::EventFd;
 use crate::proc::ProcessTable;
-use crate::syscall::userptr::UserSlice;
-use crate::syscall::{EFAULT, EINVAL, POLLNVAL};
+use crate::syscall::userptr::{UserSlice, read_user_array, write_user_array};
+use crate::syscall::{EFAULT, EFD_CLOEXEC, EFD_NONBLOCK, EFD_SEMAPHORE, EINVAL, POLLNVAL};
 use hadron_syscall::PollFd;
 
+/// `sys_event_create` — creates an eventfd whose counter starts at
+/// `initval`.
+pub(super) fn sys_event_create(initval: usize, flags: usize) -> isize {
+    if flags & !(EFD_SEMAPHORE | EFD_CLOEXEC | EFD_NONBLOCK) != 0 {
+        return -EINVAL;
+    }
+    let eventfd = EventFd::new(initval as u64, flags & EFD_SEMAPHORE != 0);
+    super::vfs::open_anon(Arc::new(eventfd), OpenFlags::READ | OpenFlags::WRITE, flags)
+}
+
 /// `sys_event_wait_many` — poll multiple file descriptors for readiness.
 ///
 /// Scans each fd for readiness (POLLIN/POLLOUT), fills in `revents`, and
 /// returns the count of fds with non-zero `revents`.
 ///
-/// `timeout_ms`: 0 = non-blocking, `usize::MAX` = infinite (blocks via trap),
-/// other values = timeout in ms (blocks via trap).
+/// `timeout_ns`: 0 = non-blocking, `usize::MAX` = infinite (blocks via trap),
+/// other values = timeout in nanoseconds (blocks via trap).
 ///
-/// When nothing is ready and `timeout_ms > 0`, longjmps back to `process_task`
+/// When nothing is ready and `timeout_ns > 0`, longjmps back to `process_task`
 /// via `trap_poll()`, where the executor `.await`s on fd readiness with a
-/// timeout (same mechanism as `TrapReason::Futex` and `TrapReason::Sleep`).
+/// high-resolution timeout (same mechanism as `TrapReason::Futex` and
+/// `TrapReason::Sleep`). With no fds and a finite timeout this is a plain
+/// sleep.
 #[expect(
     clippy::cast_possible_wrap,
     reason = "returning negated errno or small count as isize"
@@ -29,25 +44,20 @@ use hadron_syscall::PollFd;
     clippy::cast_possible_truncation,
     reason = "fd fits in u32; count fits in isize"
 )]
-pub(super) fn sys_event_wait_many(fds_ptr: usize, nfds: usize, timeout_ms: usize) -> isize {
+pub(super) fn sys_event_wait_many(fds_ptr: usize, nfds: usize, timeout_ns: usize) -> isize {
     if nfds == 0 {
-        if timeout_ms > 0 && timeout_ms != usize::MAX {
-            // Pure sleep — delegate to nanosleep equivalent.
-            // For now, return 0 (no fds ready).
-            return 0;
+        if timeout_ns > 0 && timeout_ns != usize::MAX {
+            // Pure sleep until the timeout.
+            trap_poll(fds_ptr, 0, timeout_ns);
         }
         return 0;
     }
 
-    // Validate user pointer covers the entire PollFd array.
-    let total_size = nfds.saturating_mul(core::mem::size_of::<PollFd>());
-    if UserSlice::new(fds_ptr, total_size).is_err() {
-        return -EFAULT;
-    }
-
-    // Read the PollFd array from user memory.
-    // SAFETY: UserSlice validated the pointer is in user space and properly sized.
-    let poll_fds = unsafe { core::slice::from_raw_parts_mut(fds_ptr as *mut PollFd, nfds) };
+    // Copy the PollFd array in from user memory.
+    let mut poll_fds = match read_user_array::<PollFd>(fds_ptr, nfds) {
+        Ok(fds) => fds,
+        Err(e) => return e,
+    };
 
     // Phase 1: clone all inodes while holding fd_table.
     //
@@ -88,9 +98,13 @@ pub(super) fn sys_event_wait_many(fds_ptr: usize, nfds: usize, timeout_ms: usize
         }
     }
 
+    if let Err(e) = write_user_array(fds_ptr, &poll_fds) {
+        return e;
+    }
+
     // If nothing is ready and timeout > 0, block via trap mechanism.
-    if ready_count == 0 && timeout_ms > 0 {
-        trap_poll(fds_ptr, nfds, timeout_ms);
+    if ready_count == 0 && timeout_ns > 0 {
+        trap_poll(fds_ptr, nfds, timeout_ns);
     }
 
     ready_count
@@ -98,7 +112,8 @@ pub(super) fn sys_event_wait_many(fds_ptr: usize, nfds: usize, timeout_ms: usize
 
 /// `sys_futex` — fast userspace mutex operations.
 ///
-/// - `FUTEX_WAIT` (op=0): If `*(u32*)addr == val`, sleep until woken.
+/// - `FUTEX_WAIT` (op=0): If `*(u32*)addr == val`, sleep until woken or
+///   until the relative timeout at `timeout_ptr` (0 = none) passes.
 ///   Uses the trap mechanism to longjmp back to `process_task` for async await.
 /// - `FUTEX_WAKE` (op=1): Wake up to `val` waiters sleeping on `addr`.
 ///   Returns the number of waiters actually woken.
@@ -106,7 +121,7 @@ pub(super) fn sys_event_wait_many(fds_ptr: usize, nfds: usize, timeout_ms: usize
     clippy::cast_possible_wrap,
     reason = "returning negated errno or small count as isize"
 )]
-pub(super) fn sys_futex(addr: usize, op: usize, val: usize, _timeout_ms: usize) -> isize {
+pub(super) fn sys_futex(addr: usize, op: usize, val: usize, timeout_ptr: usize) -> isize {
     use hadron_syscall::{FUTEX_WAIT, FUTEX_WAKE};
 
     match op {
@@ -119,8 +134,13 @@ pub(super) fn sys_futex(addr: usize, op: usize, val: usize, _timeout_ms: usize)
             #[expect(clippy::cast_possible_truncation, reason = "futex values are u32")]
             let expected = val as u32;
 
+            let timeout_ns = match super::time::read_timeout(timeout_ptr) {
+                Ok(timeout_ns) => timeout_ns,
+                Err(e) => return e,
+            };
+
             // Longjmp to process_task for async futex wait.
-            trap_futex(addr, expected);
+            trap_futex(addr, expected, timeout_ns);
         }
         FUTEX_WAKE => {
             let woken = crate::ipc::futex::futex_wake(addr, val);
@@ -137,7 +157,7 @@ pub(super) fn sys_futex(addr: usize, op: usize, val: usize, _timeout_ms: usize)
 ///
 /// Sets the poll parameters (fds pointer, count, timeout), restores kernel
 /// CR3 and GS bases, then calls `restore_kernel_context` — never returns.
-fn trap_poll(fds_ptr: usize, nfds: usize, timeout_ms: usize) -> ! {
+fn trap_poll(fds_ptr: usize, nfds: usize, timeout_ns: usize) -> ! {
     use crate::arch::x86_64::registers::control::Cr3;
     use crate::arch::x86_64::registers::model_specific::{IA32_GS_BASE, IA32_KERNEL_GS_BASE};
     use crate::arch::x86_64::userspace::restore_kernel_context;
@@ -152,7 +172,7 @@ fn trap_poll(fds_ptr: usize, nfds: usize, timeout_ms: usize) -> ! {
         IA32_KERNEL_GS_BASE.write(percpu);
     }
 
-    crate::proc::PollState::set_params(fds_ptr as u64, nfds as u64, timeout_ms as u64);
+    crate::proc::PollState::set_params(fds_ptr as u64, nfds as u64, timeout_ns as u64);
     crate::proc::TrapContext::set_trap_reason(crate::proc::TrapReason::Poll);
 
     let saved_rsp = crate::proc::TrapContext::saved_kernel_rsp();
@@ -165,9 +185,9 @@ fn trap_poll(fds_ptr: usize, nfds: usize, timeout_ms: usize) -> ! {
 
 /// Trigger a `TRAP_FUTEX` longjmp back to `process_task`.
 ///
-/// Sets the futex address and expected value, restores kernel CR3 and
-/// GS bases, then calls `restore_kernel_context` — never returns.
-fn trap_futex(addr: usize, expected: u32) -> ! {
+/// Sets the futex address, expected value and timeout, restores kernel CR3
+/// and GS bases, then calls `restore_kernel_context` — never returns.
+fn trap_futex(addr: usize, expected: u32, timeout_ns: u64) -> ! {
     use crate::arch::x86_64::registers::control::Cr3;
     use crate::arch::x86_64::registers::model_specific::{IA32_GS_BASE, IA32_KERNEL_GS_BASE};
     use crate::arch::x86_64::userspace::restore_kernel_context;
@@ -182,7 +202,7 @@ fn trap_futex(addr: usize, expected: u32) -> ! {
         IA32_KERNEL_GS_BASE.write(percpu);
     }
 
-    crate::proc::FutexState::set_params(addr as u64, u64::from(expected));
+    crate::proc::FutexState::set_params(addr as u64, u64::from(expected), timeout_ns);
     crate::proc::TrapContext::set_trap_reason(crate::proc::TrapReason::Futex);
 
     let saved_rsp = crate::proc::TrapContext::saved_kernel_rsp();
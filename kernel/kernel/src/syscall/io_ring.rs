@@ -0,0 +1,425 @@
+//! I/O ring syscall handlers: `io_ring_create` and `io_ring_enter`.
+//!
+//! The ring itself is [`IoRing`]. `io_ring_enter` takes submitted
+//! operations off it and spawns one executor task per chain of linked
+//! operations, so chains run concurrently and the operations of a chain
+//! run in order. A task copies user buffers under the submitting process's
+//! CR3, as `process_task` does for `TRAP_IO`. It holds the process weakly,
+//! since the process's fd table holds the ring whose closing cancels it,
+//! and holds the cancel token of the image it was submitted under, which
+//! `execve` cancels (see `Process::user_image`).
+
+extern crate alloc;
+
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+
+use crate::arch::x86_64::registers::control::Cr3;
+use crate::fs::file::OpenFlags;
+use crate::fs::{FsError, Inode, InodeType};
+use crate::id::Fd;
+use crate::ipc::io_ring::{CancelToken, IoRing, RingState};
+use crate::proc::{Process, ProcessTable, TrapContext};
+use crate::sched::TaskMeta;
+use crate::syscall::userptr::{UserPtr, UserSlice};
+use crate::syscall::{
+    EBADF, EBUSY, ECANCELED, EINVAL, ENOMEM, ENOTSOCK, ESPIPE, IO_OP_ACCEPT, IO_OP_CONNECT,
+    IO_OP_FSYNC, IO_OP_LINK, IO_OP_NOP, IO_OP_POLL, IO_OP_PREAD, IO_OP_PWRITE, IO_OP_READ,
+    IO_OP_TIMEOUT, IO_OP_WRITE, IO_RING_CLOEXEC, IoCompletion, IoOp, IoRingParams, POLLERR,
+    POLLHUP, SIGPIPE,
+};
+
+/// Most bytes one read or write operation transfers.
+const MAX_TRANSFER: usize = 1 << 20;
+
+/// Looks up ring fd `fd`, returning `-EBADF` if it is not open and
+/// `-EINVAL` if it is not an I/O ring.
+fn ring_inode(fd: usize) -> Result<Arc<dyn Inode>, isize> {
+    let fd = u32::try_from(fd).map_err(|_| -EBADF)?;
+    let inode = super::vfs::fd_inode(Fd::new(fd))?;
+    if inode.as_any().is_some_and(|any| any.is::<IoRing>()) {
+        Ok(inode)
+    } else {
+        Err(-EINVAL)
+    }
+}
+
+fn as_ring(inode: &Arc<dyn Inode>) -> &IoRing {
+    inode
+        .as_any()
+        .and_then(|any| any.downcast_ref::<IoRing>())
+        .expect("checked by ring_inode")
+}
+
+/// `sys_io_ring_create` — creates a ring and writes its layout to
+/// `params_ptr`.
+pub(super) fn sys_io_ring_create(entries: usize, params_ptr: usize, flags: usize) -> isize {
+    if flags & !IO_RING_CLOEXEC != 0 {
+        return -EINVAL;
+    }
+    let params_ptr = match UserPtr::<IoRingParams>::new(params_ptr) {
+        Ok(ptr) => ptr,
+        Err(e) => return e,
+    };
+    if entries == 0 || entries > crate::syscall::IO_RING_MAX_ENTRIES {
+        return -EINVAL;
+    }
+    let Some(ring) = IoRing::new(entries) else {
+        return -ENOMEM;
+    };
+    if let Err(e) = params_ptr.write(ring.params()) {
+        return e;
+    }
+    super::vfs::open_anon(Arc::new(ring), OpenFlags::READ | OpenFlags::WRITE, flags)
+}
+
+/// `sys_io_ring_enter` — submits up to `to_submit` operations, then waits
+/// for `min_complete` completions.
+///
+/// `timeout_ns` is as for `event_wait_many`. When fewer completions are
+/// queued and `timeout_ns > 0`, longjmps back to `process_task` via
+/// `TRAP_POLL`.
+#[expect(clippy::cast_possible_wrap, reason = "at most IO_RING_MAX_ENTRIES")]
+pub(super) fn sys_io_ring_enter(
+    fd: usize,
+    to_submit: usize,
+    min_complete: usize,
+    timeout_ns: usize,
+) -> isize {
+    let inode = match ring_inode(fd) {
+        Ok(inode) => inode,
+        Err(e) => return e,
+    };
+    let ring = as_ring(&inode).state();
+
+    let submitted = if to_submit == 0 {
+        0
+    } else {
+        let Some(ops) = ring.take_ops(to_submit) else {
+            return -EBUSY;
+        };
+        let count = ops.len();
+        spawn_chains(ring, ops);
+        count
+    };
+
+    let min_complete = min_complete.min(ring.cq_entries());
+    if min_complete > 0 && timeout_ns > 0 && ring.completions() < min_complete {
+        drop(inode);
+        trap_io_ring_wait(fd, submitted, min_complete, timeout_ns);
+    }
+    submitted as isize
+}
+
+/// Spawns a task for each chain of linked operations in `ops`.
+fn spawn_chains(ring: &Arc<RingState>, ops: Vec<IoOp>) {
+    let (process, image) =
+        ProcessTable::with_current(|p| (Arc::downgrade(p), Arc::clone(&p.user_image.read())));
+    let mut chain = Vec::new();
+    for op in ops {
+        let linked = op.flags & IO_OP_LINK != 0;
+        chain.push(op);
+        if !linked {
+            spawn_chain(ring, &process, &image, core::mem::take(&mut chain));
+        }
+    }
+    // A link on the last operation submitted links to nothing.
+    if !chain.is_empty() {
+        spawn_chain(ring, &process, &image, chain);
+    }
+}
+
+fn spawn_chain(
+    ring: &Arc<RingState>,
+    process: &Weak<Process>,
+    image: &Arc<CancelToken>,
+    chain: Vec<IoOp>,
+) {
+    let ring = Arc::clone(ring);
+    let process = process.clone();
+    let image = Arc::clone(image);
+    crate::sched::spawn_with(
+        run_chain(ring, process, image, chain),
+        TaskMeta::new("io-ring"),
+    );
+}
+
+/// Runs a chain in order, posting a completion for each operation. Once
+/// one fails, the rest complete with `-ECANCELED`.
+async fn run_chain(
+    ring: Arc<RingState>,
+    process: Weak<Process>,
+    image: Arc<CancelToken>,
+    chain: Vec<IoOp>,
+) {
+    let mut failed = false;
+    for op in chain {
+        let result = if failed {
+            -ECANCELED
+        } else {
+            ring.cancellable(&image, run_op(&process, &image, &op))
+                .await
+                .unwrap_or(-ECANCELED)
+        };
+        failed |= result < 0;
+        #[expect(
+            clippy::cast_possible_truncation,
+            reason = "at most MAX_TRANSFER or an errno"
+        )]
+        ring.complete(IoCompletion {
+            user_data: op.user_data,
+            result: result as i32,
+            _reserved: 0,
+        });
+    }
+}
+
+/// Runs one operation, returning what the equivalent syscall would.
+async fn run_op(process: &Weak<Process>, image: &CancelToken, op: &IoOp) -> isize {
+    if op.flags & !IO_OP_LINK != 0 {
+        return -EINVAL;
+    }
+    let result = match op.opcode {
+        IO_OP_NOP => Ok(0),
+        IO_OP_READ => read(process, image, op, false).await,
+        IO_OP_WRITE => write(process, image, op, false).await,
+        IO_OP_PREAD => read(process, image, op, true).await,
+        IO_OP_PWRITE => write(process, image, op, true).await,
+        IO_OP_ACCEPT => accept(process, op).await,
+        IO_OP_CONNECT => connect(process, image, op),
+        IO_OP_POLL => poll(process, op).await,
+        IO_OP_TIMEOUT => {
+            crate::sched::primitives::sleep_nanos(op.offset).await;
+            Ok(0)
+        }
+        IO_OP_FSYNC => fsync(process, op).await,
+        _ => Err(-EINVAL),
+    };
+    result.unwrap_or_else(|e| e)
+}
+
+// ── Operations ────────────────────────────────────────────────────────────────
+
+/// The open file an operation applies to.
+struct OpFile {
+    fd: Fd,
+    inode: Arc<dyn Inode>,
+    offset: usize,
+    flags: OpenFlags,
+}
+
+/// Returns the process, or `-ECANCELED` if it has exited.
+fn upgrade(process: &Weak<Process>) -> Result<Arc<Process>, isize> {
+    process
+        .upgrade()
+        .filter(|p| p.exit_status.lock().is_none())
+        .ok_or(-ECANCELED)
+}
+
+/// Looks up `fd` in the process's fd table.
+fn open_file(process: &Weak<Process>, fd: i32) -> Result<OpFile, isize> {
+    let process = upgrade(process)?;
+    let fd = Fd::new(u32::try_from(fd).map_err(|_| -EBADF)?);
+    let fd_table = process.fd_table.lock();
+    let file = fd_table.get(fd).ok_or(-EBADF)?;
+    Ok(OpFile {
+        fd,
+        inode: file.inode.clone(),
+        offset: file.offset,
+        flags: file.flags,
+    })
+}
+
+/// Runs `f` under the process's address space, unless `image`, the image
+/// the operation was submitted under, has been replaced.
+///
+/// Holds the process's image for reading, so `execve` and exit cannot tear
+/// the address space down under `f`.
+fn with_user<R>(
+    process: &Weak<Process>,
+    image: &CancelToken,
+    f: impl FnOnce() -> Result<R, isize>,
+) -> Result<R, isize> {
+    let process = upgrade(process)?;
+    let _image = process.user_image.read();
+    if image.is_cancelled() || process.exit_status.lock().is_some() {
+        return Err(-ECANCELED);
+    }
+    // SAFETY: The process is alive and its image is held, so its address
+    // space is, and kernel CR3 is restored before the image is released.
+    unsafe {
+        process.load_user_cr3();
+    }
+    let result = f();
+    // SAFETY: Restore kernel CR3.
+    unsafe {
+        Cr3::write(TrapContext::kernel_cr3());
+    }
+    result
+}
+
+/// Advances the offset of `fd` by `n` after a non-positional transfer.
+fn advance(process: &Weak<Process>, fd: Fd, n: usize) {
+    if let Ok(process) = upgrade(process)
+        && let Some(file) = process.fd_table.lock().get_mut(fd)
+    {
+        file.offset += n;
+    }
+}
+
+/// Returns the offset a transfer starts at: `op.offset` for a positional
+/// one, which needs a seekable file, or else the fd's offset.
+fn start_offset(file: &OpFile, op: &IoOp, positional: bool) -> Result<usize, isize> {
+    if !positional {
+        return Ok(file.offset);
+    }
+    if !matches!(
+        file.inode.inode_type(),
+        InodeType::File | InodeType::BlockDevice
+    ) {
+        return Err(-ESPIPE);
+    }
+    usize::try_from(op.offset).map_err(|_| -EINVAL)
+}
+
+#[expect(clippy::cast_possible_wrap, reason = "at most MAX_TRANSFER")]
+async fn read(
+    process: &Weak<Process>,
+    image: &CancelToken,
+    op: &IoOp,
+    positional: bool,
+) -> Result<isize, isize> {
+    let file = open_file(process, op.fd)?;
+    if !file.flags.contains(OpenFlags::READ) {
+        return Err(-EBADF);
+    }
+    let offset = start_offset(&file, op, positional)?;
+    let slice = UserSlice::new(op.addr as usize, (op.len as usize).min(MAX_TRANSFER))?;
+    let mut kbuf = slice.kernel_buffer()?;
+    let n = file
+        .inode
+        .read(offset, &mut kbuf)
+        .await
+        .map_err(|e| -e.to_errno())?;
+    with_user(process, image, || slice.write_from(&kbuf[..n]))?;
+    if !positional {
+        advance(process, file.fd, n);
+    }
+    Ok(n as isize)
+}
+
+#[expect(clippy::cast_possible_wrap, reason = "at most MAX_TRANSFER")]
+async fn write(
+    process: &Weak<Process>,
+    image: &CancelToken,
+    op: &IoOp,
+    positional: bool,
+) -> Result<isize, isize> {
+    let file = open_file(process, op.fd)?;
+    if !file.flags.contains(OpenFlags::WRITE) {
+        return Err(-EBADF);
+    }
+    let offset = start_offset(&file, op, positional)?;
+    let slice = UserSlice::new(op.addr as usize, (op.len as usize).min(MAX_TRANSFER))?;
+    let kbuf = with_user(process, image, || slice.read_to_vec())?;
+    match file.inode.write(offset, &kbuf).await {
+        Ok(n) => {
+            if !positional {
+                advance(process, file.fd, n);
+            }
+            Ok(n as isize)
+        }
+        Err(e) => {
+            if e == FsError::BrokenPipe
+                && let Ok(process) = upgrade(process)
+            {
+                process.signals.post(SIGPIPE);
+            }
+            Err(-e.to_errno())
+        }
+    }
+}
+
+#[expect(clippy::cast_possible_wrap, reason = "fd numbers are small")]
+async fn accept(process: &Weak<Process>, op: &IoOp) -> Result<isize, isize> {
+    let file = open_file(process, op.fd)?;
+    if file.inode.inode_type() != InodeType::Socket {
+        return Err(-ENOTSOCK);
+    }
+    let accepted = file
+        .inode
+        .accept_connection()
+        .await
+        .map_err(|e| -e.to_errno())?;
+    let process = upgrade(process)?;
+    let fd = process
+        .fd_table
+        .lock()
+        .open(accepted, OpenFlags::READ | OpenFlags::WRITE);
+    Ok(fd.as_u32() as isize)
+}
+
+fn connect(process: &Weak<Process>, image: &CancelToken, op: &IoOp) -> Result<isize, isize> {
+    let file = open_file(process, op.fd)?;
+    let path = with_user(process, image, || {
+        super::net::read_sockaddr_un_path(op.addr as usize, op.len as usize)
+    })?;
+    Ok(super::net::connect_inode(&*file.inode, &path))
+}
+
+async fn poll(process: &Weak<Process>, op: &IoOp) -> Result<isize, isize> {
+    let file = open_file(process, op.fd)?;
+    #[expect(clippy::cast_possible_truncation, reason = "poll events fit in u16")]
+    let events = op.events as u16 | POLLERR | POLLHUP;
+    let ready = core::future::poll_fn(|cx| {
+        let ready = file.inode.poll_readiness(Some(cx.waker())) & events;
+        if ready == 0 {
+            core::task::Poll::Pending
+        } else {
+            core::task::Poll::Ready(ready)
+        }
+    })
+    .await;
+    Ok(ready as isize)
+}
+
+async fn fsync(process: &Weak<Process>, op: &IoOp) -> Result<isize, isize> {
+    let file = open_file(process, op.fd)?;
+    file.inode.sync().await.map_err(|e| -e.to_errno())?;
+    Ok(0)
+}
+
+/// Trigger a `TRAP_POLL` longjmp back to `process_task` to wait for
+/// completions.
+///
+/// Sets the ring parameters, restores kernel CR3 and GS bases, then calls
+/// `restore_kernel_context` — never returns.
+fn trap_io_ring_wait(fd: usize, submitted: usize, min_complete: usize, timeout_ns: usize) -> ! {
+    use crate::arch::x86_64::registers::model_specific::{IA32_GS_BASE, IA32_KERNEL_GS_BASE};
+    use crate::arch::x86_64::userspace::restore_kernel_context;
+
+    let kernel_cr3 = TrapContext::kernel_cr3();
+
+    // SAFETY: Restoring kernel CR3 and GS bases is the standard pattern
+    // for returning from userspace context to kernel context.
+    unsafe {
+        Cr3::write(kernel_cr3);
+        let percpu = IA32_GS_BASE.read();
+        IA32_KERNEL_GS_BASE.write(percpu);
+    }
+
+    crate::proc::PollState::set_io_ring_params(
+        fd as u64,
+        submitted as u64,
+        min_complete as u64,
+        timeout_ns as u64,
+    );
+    TrapContext::set_trap_reason(crate::proc::TrapReason::Poll);
+
+    let saved_rsp = TrapContext::saved_kernel_rsp();
+    // SAFETY: saved_rsp is the kernel RSP saved by enter_userspace_save,
+    // still valid on the executor stack.
+    unsafe {
+        restore_kernel_context(saved_rsp);
+    }
+}
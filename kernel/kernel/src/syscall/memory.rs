@@ -7,29 +7,68 @@
 //! the mmap virtual address region. Physical frames are allocated from the PMM
 //! (anonymous) or come from device MMIO regions (device-backed) or shared
 //! memory objects (shared).
+//!
+//! Mappings of at least 2 MiB get a 2 MiB-aligned virtual region, and every
+//! aligned 2 MiB chunk whose backing memory is physically contiguous and
+//! aligned is mapped with a huge page. Anonymous mappings fall back to 4 KiB
+//! pages when no 2 MiB block is free, unless `MAP_HUGE` makes huge pages
+//! mandatory.
 
-use crate::addr::VirtAddr;
+use crate::addr::{PhysAddr, VirtAddr};
 use crate::fs::file::OpenFlags;
 use crate::id::Fd;
 use crate::mm::PAGE_SIZE;
+use crate::mm::address_space::HUGE_PAGE_PAGES;
 use crate::mm::mapper::MapFlags;
-use crate::mm::pmm::{self, BitmapFrameAllocRef};
-use crate::paging::{Page, PhysFrame, Size4KiB};
-use crate::proc::{MappingKind, ProcessTable};
+use crate::mm::oom;
+use crate::mm::pmm::{self, BuddyFrameAllocRef};
+use crate::paging::{Page, PhysFrame, Size2MiB, Size4KiB};
+use crate::proc::{MappingKind, Process, ProcessTable};
 use crate::syscall::{EBADF, EINVAL, ENOMEM, ENOSYS};
 
+/// Size in bytes of a 2 MiB huge page.
+const HUGE_PAGE_SIZE: usize = HUGE_PAGE_PAGES * PAGE_SIZE;
+
 /// Page-align `size` upward (round to next 4 KiB boundary).
 const fn page_align_up(size: usize) -> usize {
     (size + PAGE_SIZE - 1) & !(PAGE_SIZE - 1)
 }
 
+/// Allocates a virtual region of `length` bytes from the process's mmap
+/// allocator.
+///
+/// Regions of at least 2 MiB are 2 MiB-aligned when possible so that they
+/// can be mapped with huge pages. With `require_huge`, only an aligned
+/// region is acceptable.
+fn alloc_user_region(process: &Process, length: usize, require_huge: bool) -> Option<VirtAddr> {
+    let mut mmap = process.mmap_alloc.lock();
+    if length >= HUGE_PAGE_SIZE
+        && let Some(vaddr) = mmap.allocate_aligned(length as u64, HUGE_PAGE_SIZE as u64)
+    {
+        return Some(vaddr);
+    }
+    if require_huge {
+        return None;
+    }
+    mmap.allocate(length as u64)
+}
+
+/// Returns `true` if the 2 MiB chunk starting at page `index` of a mapping
+/// at `base` with `page_count` pages can be a huge page: the chunk is
+/// virtually aligned and lies entirely inside the mapping.
+fn huge_chunk_at(base: VirtAddr, index: usize, page_count: usize) -> bool {
+    let vaddr = base + (index * PAGE_SIZE) as u64;
+    vaddr.is_aligned(HUGE_PAGE_SIZE as u64) && page_count - index >= HUGE_PAGE_PAGES
+}
+
 /// `sys_mem_map` — map memory into the calling process's address space.
 ///
 /// `addr_hint` is currently ignored (kernel always chooses the address).
 /// `length` is rounded up to page alignment. `prot` is a bitmask of
 /// `PROT_READ`/`PROT_WRITE`/`PROT_EXEC`. `flags` must include
-/// `MAP_ANONYMOUS` or `MAP_SHARED`. `fd` is the file descriptor for
-/// device-backed mappings (ignored for anonymous).
+/// `MAP_ANONYMOUS` or `MAP_SHARED`, and may include `MAP_HUGE` for anonymous
+/// mappings. `fd` is the file descriptor for device-backed mappings (ignored
+/// for anonymous).
 ///
 /// Returns the mapped virtual address on success, or negated errno on failure.
 #[expect(
@@ -43,7 +82,7 @@ pub(super) fn sys_mem_map(
     flags: usize,
     fd: usize,
 ) -> isize {
-    use hadron_syscall::{MAP_ANONYMOUS, MAP_SHARED};
+    use hadron_syscall::{MAP_ANONYMOUS, MAP_HUGE, MAP_SHARED};
 
     if length == 0 {
         return -EINVAL;
@@ -54,21 +93,29 @@ pub(super) fn sys_mem_map(
     }
 
     if flags & MAP_ANONYMOUS != 0 {
-        return sys_mem_map_anonymous(length, prot);
+        return sys_mem_map_anonymous(length, prot, flags & MAP_HUGE != 0);
     }
 
     -ENOSYS // Neither MAP_ANONYMOUS nor MAP_SHARED.
 }
 
 /// Anonymous mapping: allocate physical frames from PMM.
+///
+/// Aligned 2 MiB chunks use huge pages while 2 MiB blocks are available.
+/// With `huge`, the length is rounded up to 2 MiB and every chunk must be
+/// a huge page.
 #[expect(
     clippy::cast_possible_wrap,
     reason = "returning virtual address as isize; upper bit is never set for user addresses"
 )]
-fn sys_mem_map_anonymous(length: usize, prot: usize) -> isize {
+fn sys_mem_map_anonymous(length: usize, prot: usize, huge: bool) -> isize {
     use hadron_syscall::{PROT_EXEC, PROT_READ, PROT_WRITE};
 
-    let aligned_length = page_align_up(length);
+    let aligned_length = if huge {
+        length.next_multiple_of(HUGE_PAGE_SIZE)
+    } else {
+        page_align_up(length)
+    };
     let page_count = aligned_length / PAGE_SIZE;
 
     // Build page table flags from prot.
@@ -83,29 +130,56 @@ fn sys_mem_map_anonymous(length: usize, prot: usize) -> isize {
     let _ = prot & PROT_READ;
 
     // Allocate virtual region from the process's mmap allocator.
-    let vaddr = ProcessTable::with_current(|process| {
-        let mut mmap = process.mmap_alloc.lock();
-        mmap.allocate(aligned_length as u64)
-    });
+    let vaddr =
+        ProcessTable::with_current(|process| alloc_user_region(process, aligned_length, huge));
 
     let base_vaddr = match vaddr {
         Some(v) => v,
         None => return -EINVAL, // Region exhausted.
     };
 
-    // Allocate physical frames and map pages.
+    // Allocate physical frames and map pages. `Err(i)` means the first `i`
+    // pages are mapped.
     let hhdm_offset = crate::mm::hhdm::offset();
     let map_result = ProcessTable::with_current(|process| {
         pmm::with(|pmm| {
-            let mut alloc = BitmapFrameAllocRef(pmm);
-            for i in 0..page_count {
-                let page_vaddr = base_vaddr.as_u64() + (i as u64) * PAGE_SIZE as u64;
-                let frame = match alloc.0.allocate_frame() {
+            let mut alloc = BuddyFrameAllocRef(pmm);
+            let mut i = 0;
+            while i < page_count {
+                let page_vaddr = base_vaddr + (i * PAGE_SIZE) as u64;
+
+                if huge_chunk_at(base_vaddr, i, page_count)
+                    && let Some(frame) = oom::alloc_user_huge_frame(alloc.0)
+                {
+                    let page = Page::<Size2MiB>::containing_address(page_vaddr);
+                    if process
+                        .address_space()
+                        .map_user_huge_page(page, frame, map_flags, &mut alloc)
+                        .is_err()
+                    {
+                        return Err(i);
+                    }
+
+                    // Zero the huge page via HHDM.
+                    let frame_ptr =
+                        (hhdm_offset + frame.start_address().as_u64()).as_mut_ptr::<u8>();
+                    // SAFETY: Frame was just allocated; zeroing via HHDM is safe.
+                    unsafe {
+                        core::ptr::write_bytes(frame_ptr, 0, HUGE_PAGE_SIZE);
+                    }
+                    i += HUGE_PAGE_PAGES;
+                    continue;
+                }
+                if huge {
+                    return Err(i); // MAP_HUGE requires a 2 MiB block.
+                }
+
+                let frame = match oom::alloc_user_frame(alloc.0) {
                     Some(f) => f,
                     None => return Err(i), // Out of memory — need to unwind.
                 };
 
-                let page = Page::<Size4KiB>::containing_address(VirtAddr::new(page_vaddr));
+                let page = Page::<Size4KiB>::containing_address(page_vaddr);
                 if let Err(_e) = process
                     .address_space()
                     .map_user_page(page, frame, map_flags, &mut alloc)
@@ -119,6 +193,7 @@ fn sys_mem_map_anonymous(length: usize, prot: usize) -> isize {
                 unsafe {
                     core::ptr::write_bytes(frame_ptr, 0, PAGE_SIZE);
                 }
+                i += 1;
             }
             Ok(())
         })
@@ -128,20 +203,24 @@ fn sys_mem_map_anonymous(length: usize, prot: usize) -> isize {
         // Unmap and free all pages that were successfully mapped before the failure.
         ProcessTable::with_current(|process| {
             pmm::with(|pmm| {
-                for i in 0..mapped_count {
-                    let page_vaddr = base_vaddr.as_u64() + (i as u64) * PAGE_SIZE as u64;
-                    let page = Page::<Size4KiB>::containing_address(VirtAddr::new(page_vaddr));
-                    if let Ok(frame) = process.address_space().unmap_user_page(page) {
-                        // SAFETY: Frame was allocated by PMM during this call and is no
-                        // longer referenced by any page table entry.
-                        let _ = unsafe { pmm.deallocate_frame(frame) };
-                    }
-                }
+                // Whole huge pages only, so no split is needed and this
+                // cannot fail.
+                let _ = process.address_space().unmap_user_range(
+                    base_vaddr,
+                    mapped_count,
+                    &mut BuddyFrameAllocRef(pmm),
+                    true,
+                );
             });
             // Return the virtual region to the mmap allocator.
             let mut mmap = process.mmap_alloc.lock();
             let _ = mmap.deallocate(base_vaddr, aligned_length as u64);
         });
+        // A missing 2 MiB block is fragmentation, not exhaustion; killing a
+        // process is unlikely to produce one.
+        if !huge {
+            oom::out_of_memory(page_count);
+        }
         return -ENOMEM;
     }
 
@@ -201,10 +280,15 @@ fn sys_mem_map_device(length: usize, prot: usize, fd: usize) -> isize {
     }
     let _ = prot & PROT_READ;
 
-    // Allocate virtual region from the process's mmap allocator.
+    // Allocate virtual region from the process's mmap allocator. Only a
+    // 2 MiB-aligned device base can be mapped with huge pages.
+    let huge_capable = phys_base.is_aligned(HUGE_PAGE_SIZE as u64);
     let vaddr = ProcessTable::with_current(|process| {
-        let mut mmap = process.mmap_alloc.lock();
-        mmap.allocate(aligned_length as u64)
+        if huge_capable {
+            alloc_user_region(process, aligned_length, false)
+        } else {
+            process.mmap_alloc.lock().allocate(aligned_length as u64)
+        }
     });
 
     let base_vaddr = match vaddr {
@@ -215,20 +299,35 @@ fn sys_mem_map_device(length: usize, prot: usize, fd: usize) -> isize {
     // Map device physical pages into user address space.
     let map_result = ProcessTable::with_current(|process| {
         pmm::with(|pmm| {
-            let mut alloc = BitmapFrameAllocRef(pmm);
-            for i in 0..page_count {
-                let page_vaddr = base_vaddr.as_u64() + (i as u64) * PAGE_SIZE as u64;
-                let phys_addr = phys_base + (i as u64) * PAGE_SIZE as u64;
-
-                let page = Page::<Size4KiB>::containing_address(VirtAddr::new(page_vaddr));
-                let frame = PhysFrame::<Size4KiB>::containing_address(phys_addr);
+            let mut alloc = BuddyFrameAllocRef(pmm);
+            let mut i = 0;
+            while i < page_count {
+                let page_vaddr = base_vaddr + (i * PAGE_SIZE) as u64;
+                let phys_addr = phys_base + (i * PAGE_SIZE) as u64;
 
-                if let Err(_e) = process
-                    .address_space()
-                    .map_user_page(page, frame, map_flags, &mut alloc)
-                {
+                if !oom::has_table_reserve(alloc.0) {
                     return Err(i);
                 }
+
+                let mapped = if huge_capable && huge_chunk_at(base_vaddr, i, page_count) {
+                    let page = Page::<Size2MiB>::containing_address(page_vaddr);
+                    let frame = PhysFrame::<Size2MiB>::containing_address(phys_addr);
+                    process
+                        .address_space()
+                        .map_user_foreign_huge_page(page, frame, map_flags, &mut alloc)
+                        .map(|_| HUGE_PAGE_PAGES)
+                } else {
+                    let page = Page::<Size4KiB>::containing_address(page_vaddr);
+                    let frame = PhysFrame::<Size4KiB>::containing_address(phys_addr);
+                    process
+                        .address_space()
+                        .map_user_foreign_page(page, frame, map_flags, &mut alloc)
+                        .map(|_| 1)
+                };
+                match mapped {
+                    Ok(pages) => i += pages,
+                    Err(_e) => return Err(i),
+                }
             }
             Ok(())
         })
@@ -238,11 +337,14 @@ fn sys_mem_map_device(length: usize, prot: usize, fd: usize) -> isize {
         // Unmap PTEs for all pages that were successfully mapped. Device frames
         // are not freed — they belong to the hardware.
         ProcessTable::with_current(|process| {
-            for i in 0..mapped_count {
-                let page_vaddr = base_vaddr.as_u64() + (i as u64) * PAGE_SIZE as u64;
-                let page = Page::<Size4KiB>::containing_address(VirtAddr::new(page_vaddr));
-                let _ = process.address_space().unmap_user_page(page);
-            }
+            pmm::with(|pmm| {
+                let _ = process.address_space().unmap_user_range(
+                    base_vaddr,
+                    mapped_count,
+                    &mut BuddyFrameAllocRef(pmm),
+                    false,
+                );
+            });
             // Return the virtual region to the mmap allocator.
             let mut mmap = process.mmap_alloc.lock();
             let _ = mmap.deallocate(base_vaddr, aligned_length as u64);
@@ -262,14 +364,13 @@ fn sys_mem_map_device(length: usize, prot: usize, fd: usize) -> isize {
 /// `sys_mem_unmap` — unmap previously mapped memory from the process's address space.
 ///
 /// `addr` must be the exact address returned by `mem_map`. `length` must match
-/// the original mapping size.
-#[expect(clippy::cast_possible_wrap, reason = "returning 0 on success as isize")]
+/// the original mapping size. Returns `ENOMEM` if a huge page that is only
+/// partially covered cannot be split.
 pub(super) fn sys_mem_unmap(addr: usize, length: usize) -> isize {
     if length == 0 || addr == 0 {
         return -EINVAL;
     }
 
-    let aligned_length = page_align_up(length);
     let base = VirtAddr::new(addr as u64);
 
     ProcessTable::with_current(|process| {
@@ -279,41 +380,51 @@ pub(super) fn sys_mem_unmap(addr: usize, length: usize) -> isize {
             mappings.remove(&base.as_u64())
         };
 
-        let page_count = aligned_length / PAGE_SIZE;
+        // A `MAP_HUGE` mapping is larger than the length the caller asked
+        // for; the recorded size covers the whole mapping.
+        let page_count = match mapping_kind {
+            Some(
+                MappingKind::Anonymous { page_count }
+                | MappingKind::Device { page_count }
+                | MappingKind::Shared { page_count },
+            ) => page_count.max(page_align_up(length) / PAGE_SIZE),
+            None => page_align_up(length) / PAGE_SIZE,
+        };
+        let aligned_length = page_count * PAGE_SIZE;
+
+        // Device frames belong to hardware; shared frames are owned by the
+        // ShmObject and freed when its last Arc ref is dropped. Anonymous (or
+        // legacy untracked) frames were allocated by the PMM during mem_map
+        // and are freed once no page table entry references them.
+        let free_frames = !matches!(
+            mapping_kind,
+            Some(MappingKind::Device { .. } | MappingKind::Shared { .. })
+        );
+
+        let result = pmm::with(|pmm| {
+            process.address_space().unmap_user_range(
+                base,
+                page_count,
+                &mut BuddyFrameAllocRef(pmm),
+                free_frames,
+            )
+        });
 
-        match mapping_kind {
-            Some(MappingKind::Device { .. } | MappingKind::Shared { .. }) => {
-                // Device/shared mapping: unmap PTEs but do NOT free physical frames.
-                // Device frames belong to hardware; shared frames are owned by
-                // the ShmObject and freed when its last Arc ref is dropped.
-                for i in 0..page_count {
-                    let page_vaddr = base.as_u64() + (i as u64) * PAGE_SIZE as u64;
-                    let page = Page::<Size4KiB>::containing_address(VirtAddr::new(page_vaddr));
-                    let _ = process.address_space().unmap_user_page(page);
-                }
-            }
-            _ => {
-                // Anonymous (or legacy untracked): unmap and free frames.
-                pmm::with(|pmm| {
-                    for i in 0..page_count {
-                        let page_vaddr = base.as_u64() + (i as u64) * PAGE_SIZE as u64;
-                        let page = Page::<Size4KiB>::containing_address(VirtAddr::new(page_vaddr));
-                        if let Ok(frame) = process.address_space().unmap_user_page(page) {
-                            // SAFETY: The frame was allocated by the PMM during mem_map
-                            // and is no longer referenced by any page table entry.
-                            let _ = unsafe { pmm.deallocate_frame(frame) };
-                        }
-                    }
-                });
+        if result.is_err() {
+            // Splitting a partially unmapped huge page failed. The pages
+            // already unmapped stay unmapped; keep the record so the rest
+            // can be unmapped by a retry or at exit.
+            if let Some(kind) = mapping_kind {
+                process.mmap_mappings.lock().insert(base.as_u64(), kind);
             }
+            return -ENOMEM;
         }
 
         // Return the virtual region to the mmap allocator.
         let mut mmap = process.mmap_alloc.lock();
         let _ = mmap.deallocate(base, aligned_length as u64);
-    });
-
-    0
+        0
+    })
 }
 
 /// `sys_mem_brk` — adjust the program break (heap boundary).
@@ -330,13 +441,15 @@ pub(super) fn sys_mem_unmap(addr: usize, length: usize) -> isize {
 pub(super) fn sys_mem_brk(addr: usize) -> isize {
     let addr = addr as u64;
 
-    ProcessTable::with_current(|process| {
+    // `Err(pages)` means the expansion ran out of memory; the OOM handler is
+    // invoked once the program break and PMM locks have been released.
+    let result = ProcessTable::with_current(|process| {
         let mut brk = process.program_break.lock();
         let current = *brk;
 
         // Query current break.
         if addr == 0 {
-            return current as isize;
+            return Ok(current as isize);
         }
 
         let new_brk = page_align_up(addr as usize) as u64;
@@ -348,12 +461,12 @@ pub(super) fn sys_mem_brk(addr: usize) -> isize {
             let pages_needed = ((new_brk - old_brk) / PAGE_SIZE as u64) as usize;
 
             let result = pmm::with(|pmm| {
-                let mut alloc = BitmapFrameAllocRef(pmm);
+                let mut alloc = BuddyFrameAllocRef(pmm);
                 for i in 0..pages_needed {
                     let page_vaddr = old_brk + (i as u64) * PAGE_SIZE as u64;
-                    let frame = match alloc.0.allocate_frame() {
+                    let frame = match oom::alloc_user_frame(alloc.0) {
                         Some(f) => f,
-                        None => return Err(()),
+                        None => return Err(i),
                     };
 
                     let page = Page::<Size4KiB>::containing_address(VirtAddr::new(page_vaddr));
@@ -362,7 +475,7 @@ pub(super) fn sys_mem_brk(addr: usize) -> isize {
                         .map_user_page(page, frame, MapFlags::USER | MapFlags::WRITABLE, &mut alloc)
                         .is_err()
                     {
-                        return Err(());
+                        return Err(i);
                     }
 
                     // Zero the page via HHDM.
@@ -376,8 +489,21 @@ pub(super) fn sys_mem_brk(addr: usize) -> isize {
                 Ok(())
             });
 
-            if result.is_err() {
-                return -ENOMEM;
+            if let Err(mapped_count) = result {
+                // Unmap and free the pages mapped before the failure so the
+                // break stays consistent with what is actually mapped.
+                pmm::with(|pmm| {
+                    for i in 0..mapped_count {
+                        let page_vaddr = old_brk + (i as u64) * PAGE_SIZE as u64;
+                        let page = Page::<Size4KiB>::containing_address(VirtAddr::new(page_vaddr));
+                        if let Ok(frame) = process.address_space().unmap_user_page(page) {
+                            // SAFETY: The frame was allocated above and is no
+                            // longer referenced by any page table entry.
+                            let _ = unsafe { pmm.deallocate_frame(frame) };
+                        }
+                    }
+                });
+                return Err(pages_needed);
             }
         } else if new_brk < old_brk {
             // Shrink: unmap and free pages.
@@ -397,7 +523,12 @@ pub(super) fn sys_mem_brk(addr: usize) -> isize {
         }
 
         *brk = addr;
-        addr as isize
+        Ok(addr as isize)
+    });
+
+    result.unwrap_or_else(|pages_needed| {
+        oom::out_of_memory(pages_needed);
+        -ENOMEM
     })
 }
 
@@ -405,19 +536,28 @@ pub(super) fn sys_mem_brk(addr: usize) -> isize {
 ///
 /// Allocates `size` bytes of zeroed physical memory (page-aligned) and
 /// returns a file descriptor referring to the `ShmObject`. Multiple
-/// processes can map this fd to share the same physical pages.
+/// processes can map this fd to share the same physical pages. `MAP_HUGE`
+/// in `flags` backs the object with 2 MiB blocks.
 #[expect(
     clippy::cast_possible_wrap,
     reason = "fd numbers are small, wrap is impossible"
 )]
-pub(super) fn sys_mem_create_shared(size: usize) -> isize {
-    if size == 0 {
+pub(super) fn sys_mem_create_shared(size: usize, flags: usize) -> isize {
+    use hadron_syscall::MAP_HUGE;
+
+    if size == 0 || flags & !MAP_HUGE != 0 {
         return -EINVAL;
     }
 
-    let shm = match crate::ipc::shm::ShmObject::new(size) {
+    let huge = flags & MAP_HUGE != 0;
+    let shm = match crate::ipc::shm::ShmObject::new(size, huge) {
         Some(s) => s,
-        None => return -ENOMEM,
+        None => {
+            if !huge {
+                oom::out_of_memory(page_align_up(size) / PAGE_SIZE);
+            }
+            return -ENOMEM;
+        }
     };
 
     let fd = ProcessTable::with_current(|process| {
@@ -482,31 +622,48 @@ pub(super) fn sys_mem_map_shared(fd: usize, size: usize, prot: usize) -> isize {
     let _ = prot & PROT_READ;
 
     // Allocate virtual region from the process's mmap allocator.
-    let vaddr = ProcessTable::with_current(|process| {
-        let mut mmap = process.mmap_alloc.lock();
-        mmap.allocate(aligned_size as u64)
-    });
+    let vaddr =
+        ProcessTable::with_current(|process| alloc_user_region(process, aligned_size, false));
 
     let base_vaddr = match vaddr {
         Some(v) => v,
         None => return -EINVAL,
     };
 
-    // Map the shared physical frames into user address space.
+    // Map the shared physical frames into user address space, using a huge
+    // page wherever a 2 MiB chunk is backed by one contiguous aligned block.
     let map_result = ProcessTable::with_current(|process| {
         pmm::with(|pmm| {
-            let mut alloc = BitmapFrameAllocRef(pmm);
-            for i in 0..page_count {
-                let page_vaddr = base_vaddr.as_u64() + (i as u64) * PAGE_SIZE as u64;
-                let page = Page::<Size4KiB>::containing_address(VirtAddr::new(page_vaddr));
-                let frame = PhysFrame::<Size4KiB>::containing_address(phys_addrs[i]);
+            let mut alloc = BuddyFrameAllocRef(pmm);
+            let mut i = 0;
+            while i < page_count {
+                let page_vaddr = base_vaddr + (i * PAGE_SIZE) as u64;
 
-                if let Err(_e) = process
-                    .address_space()
-                    .map_user_page(page, frame, map_flags, &mut alloc)
-                {
+                if !oom::has_table_reserve(alloc.0) {
                     return Err(i);
                 }
+
+                let mapped = if huge_chunk_at(base_vaddr, i, page_count)
+                    && is_huge_block(&phys_addrs[i..i + HUGE_PAGE_PAGES])
+                {
+                    let page = Page::<Size2MiB>::containing_address(page_vaddr);
+                    let frame = PhysFrame::<Size2MiB>::containing_address(phys_addrs[i]);
+                    process
+                        .address_space()
+                        .map_user_foreign_huge_page(page, frame, map_flags, &mut alloc)
+                        .map(|_| HUGE_PAGE_PAGES)
+                } else {
+                    let page = Page::<Size4KiB>::containing_address(page_vaddr);
+                    let frame = PhysFrame::<Size4KiB>::containing_address(phys_addrs[i]);
+                    process
+                        .address_space()
+                        .map_user_foreign_page(page, frame, map_flags, &mut alloc)
+                        .map(|_| 1)
+                };
+                match mapped {
+                    Ok(pages) => i += pages,
+                    Err(_e) => return Err(i),
+                }
             }
             Ok(())
         })
@@ -525,13 +682,24 @@ pub(super) fn sys_mem_map_shared(fd: usize, size: usize, prot: usize) -> isize {
     base_vaddr.as_u64() as isize
 }
 
+/// Returns `true` if `frames` are physically contiguous and start on a 2 MiB
+/// boundary.
+fn is_huge_block(frames: &[PhysAddr]) -> bool {
+    frames[0].is_aligned(HUGE_PAGE_SIZE as u64)
+        && frames
+            .iter()
+            .enumerate()
+            .all(|(i, &addr)| addr == frames[0] + (i * PAGE_SIZE) as u64)
+}
+
 /// `sys_mem_protect` — change protection flags on a mapped memory region.
 ///
 /// `addr` must be page-aligned. `length` is rounded up to page alignment.
 /// `prot` is a bitmask of `PROT_READ`/`PROT_WRITE`/`PROT_EXEC`.
 ///
 /// Only modifies flags on already-mapped pages; returns `ENOMEM` if any page
-/// in the range is not currently mapped. Returns `EINVAL` if `addr` is not
+/// in the range is not currently mapped, or if a partially covered huge page
+/// cannot be split. Returns `EINVAL` if `addr` is not
 /// page-aligned or `length` is zero.
 pub(super) fn sys_mem_protect(addr: usize, length: usize, prot: usize) -> isize {
     use hadron_syscall::{PROT_EXEC, PROT_READ, PROT_WRITE};
@@ -562,13 +730,18 @@ pub(super) fn sys_mem_protect(addr: usize, length: usize, prot: usize) -> isize
     let base = VirtAddr::new(addr as u64);
 
     let result = ProcessTable::with_current(|process| {
-        process
-            .address_space()
-            .protect_range(base, page_count, map_flags)
+        pmm::with(|pmm| {
+            process.address_space().protect_range(
+                base,
+                page_count,
+                map_flags,
+                &mut BuddyFrameAllocRef(pmm),
+            )
+        })
     });
 
     match result {
         Ok(()) => 0,
-        Err(()) => -ENOMEM,
+        Err(_) => -ENOMEM,
     }
 }
@@ -4,14 +4,22 @@
 //! generated [`SyscallHandler`] trait from `hadron-syscall`.
 
 mod channel;
+mod child;
+mod cred;
+mod epoll;
 mod event;
 mod io;
+mod io_ring;
 mod ioctl;
 mod memory;
 mod net;
 mod process;
 mod query;
+mod sched;
+mod signal;
+mod thread;
 mod time;
+mod timer;
 pub mod userptr;
 mod vfs;
 
@@ -43,14 +51,8 @@ impl SyscallHandler for HadronDispatch {
         process::sys_task_info()
     }
 
-    fn sys_task_sigaction(
-        &self,
-        signum: usize,
-        handler: usize,
-        flags: usize,
-        old_handler_out: usize,
-    ) -> isize {
-        process::sys_task_sigaction(signum, handler, flags, old_handler_out)
+    fn sys_task_sigaction(&self, signum: usize, act_ptr: usize, oldact_ptr: usize) -> isize {
+        process::sys_task_sigaction(signum, act_ptr, oldact_ptr)
     }
 
     fn sys_task_sigreturn(&self) -> isize {
@@ -267,8 +269,8 @@ impl SyscallHandler for HadronDispatch {
         memory::sys_mem_brk(addr)
     }
 
-    fn sys_mem_create_shared(&self, size: usize) -> isize {
-        memory::sys_mem_create_shared(size)
+    fn sys_mem_create_shared(&self, size: usize, flags: usize) -> isize {
+        memory::sys_mem_create_shared(size, flags)
     }
 
     fn sys_mem_map_shared(&self, fd: usize, size: usize, prot: usize) -> isize {
@@ -297,12 +299,62 @@ impl SyscallHandler for HadronDispatch {
         process::sys_task_clone(flags, stack_ptr, tls_ptr)
     }
 
-    fn sys_event_wait_many(&self, fds_ptr: usize, nfds: usize, timeout_ms: usize) -> isize {
-        event::sys_event_wait_many(fds_ptr, nfds, timeout_ms)
+    fn sys_event_create(&self, initval: usize, flags: usize) -> isize {
+        event::sys_event_create(initval, flags)
+    }
+
+    fn sys_timerfd_create(&self, clock_id: usize, flags: usize) -> isize {
+        timer::sys_timerfd_create(clock_id, flags)
+    }
+
+    fn sys_timerfd_settime(
+        &self,
+        fd: usize,
+        flags: usize,
+        new_ptr: usize,
+        old_ptr: usize,
+    ) -> isize {
+        timer::sys_timerfd_settime(fd, flags, new_ptr, old_ptr)
+    }
+
+    fn sys_event_wait_many(&self, fds_ptr: usize, nfds: usize, timeout_ns: usize) -> isize {
+        event::sys_event_wait_many(fds_ptr, nfds, timeout_ns)
+    }
+
+    fn sys_futex(&self, addr: usize, op: usize, val: usize, timeout_ptr: usize) -> isize {
+        event::sys_futex(addr, op, val, timeout_ptr)
+    }
+
+    fn sys_timer_create(&self, clock_id: usize, sevp_ptr: usize, id_ptr: usize) -> isize {
+        timer::sys_timer_create(clock_id, sevp_ptr, id_ptr)
+    }
+
+    fn sys_timer_settime(&self, id: usize, flags: usize, new_ptr: usize, old_ptr: usize) -> isize {
+        timer::sys_timer_settime(id, flags, new_ptr, old_ptr)
+    }
+
+    fn sys_timer_gettime(&self, id: usize, cur_ptr: usize) -> isize {
+        timer::sys_timer_gettime(id, cur_ptr)
+    }
+
+    fn sys_timer_delete(&self, id: usize) -> isize {
+        timer::sys_timer_delete(id)
+    }
+
+    fn sys_timer_getoverrun(&self, id: usize) -> isize {
+        timer::sys_timer_getoverrun(id)
+    }
+
+    fn sys_itimer_set(&self, which: usize, new_ptr: usize, old_ptr: usize) -> isize {
+        timer::sys_itimer_set(which, new_ptr, old_ptr)
     }
 
-    fn sys_futex(&self, addr: usize, op: usize, val: usize, timeout_ms: usize) -> isize {
-        event::sys_futex(addr, op, val, timeout_ms)
+    fn sys_itimer_get(&self, which: usize, cur_ptr: usize) -> isize {
+        timer::sys_itimer_get(which, cur_ptr)
+    }
+
+    fn sys_timerfd_gettime(&self, fd: usize, cur_ptr: usize) -> isize {
+        timer::sys_timerfd_gettime(fd, cur_ptr)
     }
 
     fn sys_query(&self, topic: usize, sub_id: usize, out_buf: usize, out_len: usize) -> isize {
@@ -344,6 +396,151 @@ impl SyscallHandler for HadronDispatch {
     fn sys_shutdown(&self, fd: usize, how: usize) -> isize {
         net::sys_shutdown(fd, how)
     }
+
+    fn sys_cred_get(&self, buf_ptr: usize, buf_len: usize) -> isize {
+        cred::sys_cred_get(buf_ptr, buf_len)
+    }
+
+    fn sys_cred_getgroups(&self, buf_ptr: usize, count: usize) -> isize {
+        cred::sys_cred_getgroups(buf_ptr, count)
+    }
+
+    fn sys_cred_setuid(&self, uid: usize) -> isize {
+        cred::sys_cred_setuid(uid)
+    }
+
+    fn sys_cred_setgid(&self, gid: usize) -> isize {
+        cred::sys_cred_setgid(gid)
+    }
+
+    fn sys_cred_setresuid(&self, uid: usize, euid: usize, suid: usize) -> isize {
+        cred::sys_cred_setresuid(uid, euid, suid)
+    }
+
+    fn sys_cred_setresgid(&self, gid: usize, egid: usize, sgid: usize) -> isize {
+        cred::sys_cred_setresgid(gid, egid, sgid)
+    }
+
+    fn sys_cred_setgroups(&self, buf_ptr: usize, count: usize) -> isize {
+        cred::sys_cred_setgroups(buf_ptr, count)
+    }
+
+    fn sys_thread_set_tls(&self, tls_ptr: usize) -> isize {
+        thread::sys_thread_set_tls(tls_ptr)
+    }
+
+    fn sys_thread_get_tls(&self) -> isize {
+        thread::sys_thread_get_tls()
+    }
+
+    fn sys_sig_queue(&self, pid: usize, signum: usize, value: usize) -> isize {
+        signal::sys_sig_queue(pid, signum, value)
+    }
+
+    fn sys_sig_altstack(&self, ss_ptr: usize, old_ss_ptr: usize) -> isize {
+        signal::sys_sig_altstack(ss_ptr, old_ss_ptr)
+    }
+
+    fn sys_sig_pending(&self, set_out: usize) -> isize {
+        signal::sys_sig_pending(set_out)
+    }
+
+    fn sys_sig_suspend(&self, mask: usize) -> isize {
+        signal::sys_sig_suspend(mask)
+    }
+
+    fn sys_sig_timedwait(&self, set: usize, info_ptr: usize, timeout_ptr: usize) -> isize {
+        signal::sys_sig_timedwait(set, info_ptr, timeout_ptr)
+    }
+
+    fn sys_sig_fd(&self, fd: usize, mask: usize, flags: usize) -> isize {
+        signal::sys_sig_fd(fd, mask, flags)
+    }
+
+    fn sys_sched_getpriority(&self, which: usize, who: usize) -> isize {
+        sched::sys_sched_getpriority(which, who)
+    }
+
+    fn sys_sched_setpriority(&self, which: usize, who: usize, nice: usize) -> isize {
+        sched::sys_sched_setpriority(which, who, nice)
+    }
+
+    fn sys_sched_getaffinity(&self, pid: usize, mask_len: usize, mask_ptr: usize) -> isize {
+        sched::sys_sched_getaffinity(pid, mask_len, mask_ptr)
+    }
+
+    fn sys_sched_setaffinity(&self, pid: usize, mask_len: usize, mask_ptr: usize) -> isize {
+        sched::sys_sched_setaffinity(pid, mask_len, mask_ptr)
+    }
+
+    fn sys_sched_setattr(&self, pid: usize, attr_ptr: usize, flags: usize) -> isize {
+        sched::sys_sched_setattr(pid, attr_ptr, flags)
+    }
+
+    fn sys_sched_getattr(&self, pid: usize, attr_ptr: usize, size: usize) -> isize {
+        sched::sys_sched_getattr(pid, attr_ptr, size)
+    }
+
+    fn sys_epoll_create(&self, flags: usize) -> isize {
+        epoll::sys_epoll_create(flags)
+    }
+
+    fn sys_epoll_ctl(&self, epfd: usize, op: usize, fd: usize, event_ptr: usize) -> isize {
+        epoll::sys_epoll_ctl(epfd, op, fd, event_ptr)
+    }
+
+    fn sys_epoll_wait(
+        &self,
+        epfd: usize,
+        events_ptr: usize,
+        max_events: usize,
+        timeout_ns: usize,
+    ) -> isize {
+        epoll::sys_epoll_wait(epfd, events_ptr, max_events, timeout_ns)
+    }
+
+    fn sys_io_ring_create(&self, entries: usize, params_ptr: usize, flags: usize) -> isize {
+        io_ring::sys_io_ring_create(entries, params_ptr, flags)
+    }
+
+    fn sys_io_ring_enter(
+        &self,
+        fd: usize,
+        to_submit: usize,
+        min_complete: usize,
+        timeout_ns: usize,
+    ) -> isize {
+        io_ring::sys_io_ring_enter(fd, to_submit, min_complete, timeout_ns)
+    }
+
+    fn sys_child_wait4(
+        &self,
+        pid: usize,
+        status_ptr: usize,
+        options: usize,
+        rusage_ptr: usize,
+    ) -> isize {
+        child::sys_child_wait4(pid, status_ptr, options, rusage_ptr)
+    }
+
+    fn sys_child_waitid(
+        &self,
+        idtype: usize,
+        id: usize,
+        info_ptr: usize,
+        options: usize,
+        rusage_ptr: usize,
+    ) -> isize {
+        child::sys_child_waitid(idtype, id, info_ptr, options, rusage_ptr)
+    }
+
+    fn sys_child_set_subreaper(&self, enable: usize) -> isize {
+        child::sys_child_set_subreaper(enable)
+    }
+
+    fn sys_child_get_subreaper(&self) -> isize {
+        child::sys_child_get_subreaper()
+    }
 }
 
 /// Global dispatch instance.
@@ -354,11 +551,12 @@ static DISPATCH: HadronDispatch = HadronDispatch;
 /// Matches the syscall number and forwards to the appropriate handler.
 /// Unknown syscall numbers return `-ENOSYS`.
 ///
-/// After the handler returns, processes any pending keyboard input and
+/// The time spent in the handler is charged to the calling thread as system
+/// time. After the handler returns, processes any pending keyboard input and
 /// checks for pending signals. This ensures Ctrl+C is recognised even
 /// during tight syscall loops where the normal TTY read path never runs.
 #[unsafe(no_mangle)]
-extern "C" fn syscall_dispatch(
+pub(crate) extern "C" fn syscall_dispatch(
     nr: usize,
     a0: usize,
     a1: usize,
@@ -367,6 +565,7 @@ extern "C" fn syscall_dispatch(
     a4: usize,
 ) -> isize {
     crate::ktrace_subsys!(syscall, "syscall nr={} a0={:#x} a1={:#x}", nr, a0, a1);
+    crate::proc::ProcessTable::try_current(|p| crate::proc::acct::syscall_entry(p));
     let result = dispatch(&DISPATCH, nr, a0, a1, a2, a3, a4);
 
     // Process any keyboard input that arrived during this syscall
@@ -375,7 +574,10 @@ extern "C" fn syscall_dispatch(
 
     // If the current process has pending signals, longjmp back to
     // process_task for delivery instead of returning via sysretq.
-    let has_signal = crate::proc::ProcessTable::try_current(|p| p.signals.has_pending());
+    let has_signal = crate::proc::ProcessTable::try_current(|p| {
+        crate::proc::acct::syscall_exit(p);
+        p.signals.has_pending()
+    });
     if has_signal == Some(true) {
         trap_signal_pending(result);
     }
@@ -386,7 +588,8 @@ extern "C" fn syscall_dispatch(
 /// Longjmp back to `process_task` for signal delivery.
 ///
 /// Populates `USER_CONTEXT` from `SYSCALL_SAVED_REGS` + the syscall
-/// return value, restores kernel CR3 and GS, sets `TrapReason::Preempted`,
+/// return value and `USER_FPU_CONTEXT` from the live FPU registers,
+/// restores kernel CR3 and GS, sets `TrapReason::Preempted`,
 /// and calls `restore_kernel_context`. process_task will then check
 /// signals and either terminate or deliver a handler.
 fn trap_signal_pending(result: isize) -> ! {
@@ -421,6 +624,12 @@ fn trap_signal_pending(result: isize) -> ! {
         ctx.r11 = 0;
     }
 
+    // The syscall path leaves the FPU alone, so it still holds user state.
+    // SAFETY: USER_FPU_CONTEXT is per-CPU, 64-byte aligned and 512 bytes.
+    unsafe {
+        crate::arch::x86_64::fpu::save_user_state(crate::proc::TrapContext::user_fpu_context_ptr());
+    }
+
     let kernel_cr3 = crate::proc::TrapContext::kernel_cr3();
 
     // SAFETY: Standard kernel context restore pattern (same as trap_io / sys_task_sigreturn).
@@ -74,7 +74,10 @@ fn fd_inode(fd: Fd) -> Result<Arc<dyn Inode>, isize> {
 }
 
 /// Read the socket path out of a `struct sockaddr_un` at `addr_ptr`.
-fn read_sockaddr_un_path(addr_ptr: usize, addr_len: usize) -> Result<alloc::string::String, isize> {
+pub(super) fn read_sockaddr_un_path(
+    addr_ptr: usize,
+    addr_len: usize,
+) -> Result<alloc::string::String, isize> {
     // Minimum: 2 bytes sun_family + at least 1 path byte + null terminator.
     if addr_ptr == 0 || addr_len < 3 {
         return Err(-EINVAL);
@@ -83,8 +86,7 @@ fn read_sockaddr_un_path(addr_ptr: usize, addr_len: usize) -> Result<alloc::stri
     let Ok(slice) = UserSlice::new(addr_ptr, read_len) else {
         return Err(-EFAULT);
     };
-    // SAFETY: UserSlice validated that [addr_ptr, addr_ptr+read_len) is in user space.
-    let bytes = unsafe { slice.as_slice() };
+    let bytes = slice.read_to_vec()?;
     // Skip sun_family (2 bytes), then find the null-terminated path.
     let path_bytes = &bytes[2..];
     let path_len = path_bytes
@@ -105,10 +107,10 @@ fn read_msghdr(msg_ptr: usize) -> Result<MsgHdr, isize> {
     let Ok(slice) = UserSlice::new(msg_ptr, core::mem::size_of::<MsgHdr>()) else {
         return Err(-EFAULT);
     };
-    // SAFETY: UserSlice validated pointer range; MsgHdr is repr(C) with no
-    // invalid bit patterns (all fields are integers).
-    let hdr: MsgHdr =
-        unsafe { core::ptr::read_unaligned(slice.as_slice().as_ptr() as *const MsgHdr) };
+    let bytes = slice.read_to_vec()?;
+    // SAFETY: The buffer holds size_of::<MsgHdr>() bytes; MsgHdr is repr(C)
+    // with no invalid bit patterns (all fields are integers).
+    let hdr: MsgHdr = unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const MsgHdr) };
     Ok(hdr)
 }
 
@@ -122,9 +124,9 @@ fn read_iov0(msghdr: &MsgHdr) -> Result<(usize, usize), isize> {
     let Ok(slice) = UserSlice::new(msghdr.msg_iov as usize, core::mem::size_of::<Iovec>()) else {
         return Err(-EFAULT);
     };
+    let bytes = slice.read_to_vec()?;
     // SAFETY: Same as read_msghdr.
-    let iov: Iovec =
-        unsafe { core::ptr::read_unaligned(slice.as_slice().as_ptr() as *const Iovec) };
+    let iov: Iovec = unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const Iovec) };
     Ok((iov.iov_base as usize, iov.iov_len as usize))
 }
 
@@ -142,8 +144,9 @@ fn process_send_ancillary(inode: &Arc<dyn Inode>, msg_control: u64, msg_controll
     let Ok(slice) = UserSlice::new(ctrl_ptr, ctrl_len) else {
         return;
     };
-    // SAFETY: UserSlice validated pointer range.
-    let ctrl_bytes = unsafe { slice.as_slice() };
+    let Ok(ctrl_bytes) = slice.read_to_vec() else {
+        return;
+    };
 
     let cmsg_hdr_size = core::mem::size_of::<CmsgHdr>(); // 16
     let mut offset = 0usize;
@@ -359,14 +362,19 @@ pub(super) fn sys_connect(fd: usize, addr_ptr: usize, addr_len: usize) -> isize
         Ok(p) => p,
         Err(e) => return e,
     };
-    let inode = match fd_inode(Fd::new(fd as u32)) {
-        Ok(i) => i,
-        Err(e) => return e,
-    };
+    match fd_inode(Fd::new(fd as u32)) {
+        Ok(inode) => connect_inode(&*inode, &path),
+        Err(e) => e,
+    }
+}
+
+/// Connect socket `inode` to the peer listening at `path`, returning 0 or a
+/// negated errno.
+pub(super) fn connect_inode(inode: &dyn Inode, path: &str) -> isize {
     if inode.inode_type() != InodeType::Socket {
         return -ENOTSOCK;
     }
-    match inode.unix_connect(&path) {
+    match inode.unix_connect(path) {
         Ok(()) => 0,
         // Backlog full — translate IoError → ECONNREFUSED.
         Err(crate::fs::FsError::IoError) => -ECONNREFUSED,
@@ -402,13 +410,10 @@ pub(super) fn sys_sendmsg(fd: usize, msg_ptr: usize, _flags: usize) -> isize {
     process_send_ancillary(&inode, msghdr.msg_control, msghdr.msg_controllen);
 
     // Copy iov data into a kernel buffer for the synchronous attempt.
-    let mut kbuf = vec![0u8; iov_len];
-    let Ok(uslice) = UserSlice::new(iov_base, iov_len) else {
-        return -EFAULT;
+    let kbuf = match UserSlice::new(iov_base, iov_len).and_then(|s| s.read_to_vec()) {
+        Ok(buf) => buf,
+        Err(e) => return e,
     };
-    // SAFETY: UserSlice validated the user pointer range.
-    let src = unsafe { uslice.as_slice() };
-    kbuf.copy_from_slice(src);
 
     // Try synchronous write first.
     match try_poll_immediate(inode.write(0, &kbuf)) {
@@ -473,12 +478,9 @@ pub(super) fn sys_recvmsg(fd: usize, msg_ptr: usize, _flags: usize) -> isize {
     };
 
     // Copy kernel buffer to user iov.
-    let Ok(uslice) = UserSlice::new(iov_base, n) else {
-        return -EFAULT;
-    };
-    // SAFETY: UserSlice validated the user pointer range.
-    let dst = unsafe { uslice.as_mut_slice() };
-    dst.copy_from_slice(&kbuf[..n]);
+    if let Err(e) = UserSlice::new(iov_base, n).and_then(|s| s.write_from(&kbuf[..n])) {
+        return e;
+    }
 
     // Dequeue SCM_RIGHTS fds into msg_control.
     let cmsg_ptr = msghdr.msg_control as usize;
@@ -498,20 +500,18 @@ pub(super) fn sys_recvmsg(fd: usize, msg_ptr: usize, _flags: usize) -> isize {
             let cmsg_offset = cmsg_ptr + written_cmsgs;
             // Write cmsg: [cmsg_len:u64=20][SOL_SOCKET:i32=1][SCM_RIGHTS:i32=1][fd:i32]
             if let Ok(sl) = UserSlice::new(cmsg_offset, 20) {
-                // SAFETY: UserSlice validated pointer range.
-                let buf = unsafe { sl.as_mut_slice() };
+                let mut buf = [0u8; 20];
                 buf[0..8].copy_from_slice(&20u64.to_le_bytes());
                 buf[8..12].copy_from_slice(&1i32.to_le_bytes());
                 buf[12..16].copy_from_slice(&1i32.to_le_bytes());
                 buf[16..20].copy_from_slice(&(new_fd.as_u32() as i32).to_le_bytes());
+                let _ = sl.write_from(&buf);
             }
             written_cmsgs += CMSG_SPACE_1FD;
         }
         // Update msg_controllen at byte offset 40 of the msghdr.
         if let Ok(sl) = UserSlice::new(msg_ptr + 40, 8) {
-            // SAFETY: UserSlice validated pointer range.
-            let buf = unsafe { sl.as_mut_slice() };
-            buf.copy_from_slice(&(written_cmsgs as u64).to_le_bytes());
+            let _ = sl.write_from(&(written_cmsgs as u64).to_le_bytes());
         }
     }
 
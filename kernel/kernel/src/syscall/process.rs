@@ -5,8 +5,10 @@ extern crate alloc;
 
 use crate::arch::x86_64::registers::control::Cr3;
 use crate::arch::x86_64::registers::model_specific::{IA32_GS_BASE, IA32_KERNEL_GS_BASE};
-use crate::arch::x86_64::userspace::restore_kernel_context;
-use crate::syscall::userptr::UserSlice;
+use crate::arch::x86_64::userspace::{USER_RFLAGS, restore_kernel_context};
+use crate::id::{Gid, Uid};
+use crate::proc::binfmt::BinaryError;
+use crate::syscall::userptr::{USER_ADDR_MAX, UserPtr, UserSlice, read_user_array};
 
 /// `sys_task_exit` — terminates the current user process.
 ///
@@ -76,23 +78,13 @@ fn read_spawn_args(
         return Err(-(crate::syscall::EINVAL));
     }
 
-    let desc_size = core::mem::size_of::<hadron_syscall::SpawnArg>() * descs_count;
-    let desc_slice = UserSlice::new(descs_ptr, desc_size).map_err(|e| e)?;
-
-    // SAFETY: The user slice was validated; user CR3 is still active.
-    let desc_bytes = unsafe { desc_slice.as_slice() };
-    // SAFETY: SpawnArg is repr(C) with only usize fields; any bit pattern is valid.
-    let descs = unsafe {
-        core::slice::from_raw_parts(
-            desc_bytes.as_ptr().cast::<hadron_syscall::SpawnArg>(),
-            descs_count,
-        )
-    };
+    // SpawnArg is repr(C) with only usize fields; any bit pattern is valid.
+    let descs = read_user_array::<hadron_syscall::SpawnArg>(descs_ptr, descs_count)?;
 
     let mut count = 0usize;
     let mut total_bytes = 0usize;
 
-    for desc in descs {
+    for desc in &descs {
         if desc.len == 0 {
             offsets[count] = (total_bytes, 0);
             count += 1;
@@ -101,13 +93,11 @@ fn read_spawn_args(
         if total_bytes + desc.len > storage.len() {
             return Err(-(crate::syscall::EINVAL));
         }
-        let arg_slice = UserSlice::new(desc.ptr, desc.len).map_err(|e| e)?;
-        // SAFETY: Validated by UserSlice; user CR3 still active.
-        let arg_bytes = unsafe { arg_slice.as_slice() };
+        let arg_bytes = &mut storage[total_bytes..total_bytes + desc.len];
+        UserSlice::new(desc.ptr, desc.len)?.read_into(arg_bytes)?;
         if core::str::from_utf8(arg_bytes).is_err() {
             return Err(-(crate::syscall::EINVAL));
         }
-        storage[total_bytes..total_bytes + desc.len].copy_from_slice(arg_bytes);
         offsets[count] = (total_bytes, desc.len);
         total_bytes += desc.len;
         count += 1;
@@ -144,23 +134,19 @@ pub(super) fn sys_task_spawn(info_ptr: usize, info_len: usize) -> isize {
         return -(crate::syscall::EINVAL);
     }
 
-    let info_slice = match UserSlice::new(info_ptr, expected_size) {
-        Ok(s) => s,
+    // SpawnInfo is repr(C) with only usize fields so any bit pattern is valid.
+    let info = match UserPtr::<hadron_syscall::SpawnInfo>::new(info_ptr).and_then(|p| p.read()) {
+        Ok(info) => info,
         Err(e) => return e,
     };
 
-    // SAFETY: UserSlice validated the pointer; SpawnInfo is repr(C) with only
-    // usize fields so any bit pattern is valid.
-    let info: hadron_syscall::SpawnInfo =
-        unsafe { core::ptr::read(info_slice.addr() as *const hadron_syscall::SpawnInfo) };
-
     // Read path.
-    let path_uslice = match UserSlice::new(info.path_ptr, info.path_len) {
-        Ok(s) => s,
-        Err(e) => return e,
-    };
-    let path_bytes = unsafe { path_uslice.as_slice() };
-    let path = match core::str::from_utf8(path_bytes) {
+    let path_bytes =
+        match UserSlice::new(info.path_ptr, info.path_len).and_then(|s| s.read_to_vec()) {
+            Ok(bytes) => bytes,
+            Err(e) => return e,
+        };
+    let path = match core::str::from_utf8(&path_bytes) {
         Ok(s) => s,
         Err(_) => return -(crate::syscall::EINVAL),
     };
@@ -210,19 +196,13 @@ pub(super) fn sys_task_spawn(info_ptr: usize, info_len: usize) -> isize {
         if info.fd_map_count > MAX_FD_MAP {
             return -(crate::syscall::EINVAL);
         }
-        let entry_size = core::mem::size_of::<hadron_syscall::FdMapEntry>();
-        let map_bytes = entry_size * info.fd_map_count;
-        let map_slice = match UserSlice::new(info.fd_map_ptr, map_bytes) {
-            Ok(s) => s,
-            Err(e) => return e,
-        };
-        // SAFETY: FdMapEntry is repr(C) with only u32 fields; any bit pattern is valid.
-        let entries = unsafe {
-            core::slice::from_raw_parts(
-                map_slice.addr() as *const hadron_syscall::FdMapEntry,
-                info.fd_map_count,
-            )
-        };
+        // FdMapEntry is repr(C) with only u32 fields; any bit pattern is valid.
+        let entries =
+            match read_user_array::<hadron_syscall::FdMapEntry>(info.fd_map_ptr, info.fd_map_count)
+            {
+                Ok(entries) => entries,
+                Err(e) => return e,
+            };
         for (i, entry) in entries.iter().enumerate() {
             fd_map_storage[i] = (entry.child_fd, entry.parent_fd);
         }
@@ -233,12 +213,12 @@ pub(super) fn sys_task_spawn(info_ptr: usize, info_len: usize) -> isize {
 
     // Read CWD path if provided.
     let cwd = if info.cwd_ptr != 0 && info.cwd_len > 0 {
-        let cwd_slice = match UserSlice::new(info.cwd_ptr, info.cwd_len) {
-            Ok(s) => s,
-            Err(e) => return e,
-        };
-        let cwd_bytes = unsafe { cwd_slice.as_slice() };
-        match core::str::from_utf8(cwd_bytes) {
+        let cwd_bytes =
+            match UserSlice::new(info.cwd_ptr, info.cwd_len).and_then(|s| s.read_to_vec()) {
+                Ok(bytes) => bytes,
+                Err(e) => return e,
+            };
+        match core::str::from_utf8(&cwd_bytes) {
             Ok(s) => Some(alloc::string::String::from(s)),
             Err(_) => return -(crate::syscall::EINVAL),
         }
@@ -246,6 +226,18 @@ pub(super) fn sys_task_spawn(info_ptr: usize, info_len: usize) -> isize {
         None
     };
 
+    // Read the requested identity, if any.
+    let id = |id: usize| match id {
+        crate::syscall::ID_UNCHANGED => Ok(None),
+        id => u32::try_from(id)
+            .map(Some)
+            .map_err(|_| -(crate::syscall::EINVAL)),
+    };
+    let (uid, gid) = match (id(info.uid), id(info.gid)) {
+        (Ok(uid), Ok(gid)) => (uid.map(Uid::new), gid.map(Gid::new)),
+        (Err(e), _) | (_, Err(e)) => return e,
+    };
+
     let opts = crate::proc::exec::SpawnOptions {
         fd_map: if fd_map_count > 0 {
             Some(&fd_map_storage[..fd_map_count])
@@ -253,12 +245,16 @@ pub(super) fn sys_task_spawn(info_ptr: usize, info_len: usize) -> isize {
             None
         },
         cwd,
+        uid,
+        gid,
     };
 
     let parent_pid = crate::proc::ProcessTable::with_current(|p| p.pid);
 
     match crate::proc::exec::spawn_process(path, parent_pid, args, envs, Some(opts)) {
         Ok(child) => child.pid.as_u32() as isize,
+        Err(BinaryError::PermissionDenied) => -(crate::syscall::EACCES),
+        Err(BinaryError::NotPermitted) => -(crate::syscall::EPERM),
         Err(e) => {
             crate::kwarn!("sys_task_spawn: failed to spawn '{}': {:?}", path, e);
             -(crate::syscall::ENOENT)
@@ -266,21 +262,60 @@ pub(super) fn sys_task_spawn(info_ptr: usize, info_len: usize) -> isize {
     }
 }
 
-/// `sys_task_wait` — waits for a child process to exit.
+/// `sys_task_wait` — waits for a child process to exit, stop or continue.
 ///
-/// This is a blocking syscall implemented via the TRAP_WAIT mechanism.
-/// Sets up the wait parameters and longjmps back to `process_task`,
-/// which handles the async wait in its event loop.
+/// `pid` 0 selects any child. `flags` is a bitmask: `WNOHANG` for
+/// non-blocking, `WUNTRACED` to also report stopped children, `WCONTINUED`
+/// to also report continued ones.
 ///
-/// `flags` is a bitmask: `WNOHANG` for non-blocking, `WUNTRACED` to
-/// also report stopped children.
+/// Never returns to the caller on success — see [`trap_wait`].
+pub(super) fn sys_task_wait(pid: usize, status_ptr: usize, flags: usize) -> isize {
+    use crate::proc::{WaitOutput, WaitTarget};
+    use hadron_syscall::{WCONTINUED, WEXITED, WNOHANG, WUNTRACED};
+
+    if flags & !(WNOHANG | WUNTRACED | WCONTINUED) != 0 {
+        return -(crate::syscall::EINVAL);
+    }
+
+    #[expect(clippy::cast_possible_truncation, reason = "PID fits in u32")]
+    let target = match pid {
+        0 => WaitTarget::Any,
+        pid => WaitTarget::Pid(crate::id::Pid::new(pid as u32)),
+    };
+    let output = WaitOutput {
+        status_ptr: status_ptr as u64,
+        ..WaitOutput::default()
+    };
+    trap_wait(target, flags | WEXITED, output)
+}
+
+/// Validates the output pointers of a wait syscall and traps to
+/// `process_task`, which awaits a child selected by `target` as
+/// `flags` asks.
 ///
-/// Never returns to the caller — execution resumes when `process_task`
+/// This is a blocking syscall implemented via the TRAP_WAIT mechanism:
+/// it sets up the wait parameters and longjmps back to `process_task`,
+/// which handles the async wait in its event loop. Returns only on
+/// invalid pointers; otherwise execution resumes when `process_task`
 /// re-enters userspace with the result in RAX.
-pub(super) fn sys_task_wait(pid: usize, status_ptr: usize, flags: usize) -> isize {
-    // Validate status_ptr if non-null.
-    if status_ptr != 0 {
-        if let Err(e) = UserSlice::new(status_ptr, core::mem::size_of::<u64>()) {
+pub(super) fn trap_wait(
+    target: crate::proc::WaitTarget,
+    flags: usize,
+    output: crate::proc::WaitOutput,
+) -> isize {
+    // Validate output pointers if non-null.
+    if output.status_ptr != 0 {
+        if let Err(e) = UserPtr::<u64>::new(output.status_ptr as usize) {
+            return e;
+        }
+    }
+    if output.info_ptr != 0 {
+        if let Err(e) = UserPtr::<crate::syscall::SigInfo>::new(output.info_ptr as usize) {
+            return e;
+        }
+    }
+    if output.rusage_ptr != 0 {
+        if let Err(e) = UserPtr::<crate::syscall::RusageInfo>::new(output.rusage_ptr as usize) {
             return e;
         }
     }
@@ -297,12 +332,7 @@ pub(super) fn sys_task_wait(pid: usize, status_ptr: usize, flags: usize) -> isiz
     }
 
     // Set up wait parameters for process_task to read.
-    #[expect(clippy::cast_possible_truncation, reason = "PID fits in u32")]
-    crate::proc::WaitState::set_params(
-        crate::id::Pid::new(pid as u32),
-        status_ptr as u64,
-        flags as u64,
-    );
+    crate::proc::WaitState::set_params(target, flags as u64, output);
     crate::proc::TrapContext::set_trap_reason(crate::proc::TrapReason::Wait);
 
     let saved_rsp = crate::proc::TrapContext::saved_kernel_rsp();
@@ -315,20 +345,53 @@ pub(super) fn sys_task_wait(pid: usize, status_ptr: usize, flags: usize) -> isiz
 
 /// `sys_task_kill` — sends a signal to a process.
 ///
-/// Returns 0 on success, or a negated errno on failure.
-#[expect(clippy::cast_possible_truncation, reason = "PID fits in u32")]
+/// The sender needs permission to signal the target (see
+/// [`Credentials::may_signal`](crate::cred::Credentials::may_signal)).
+/// Signal 0 only checks that the target exists and may be signalled.
+/// Returns 0 on success, or a negated errno on failure: `-EPERM` if the
+/// sender lacks permission, `-EAGAIN` if the target's real-time signal
+/// queue is full.
 pub(super) fn sys_task_kill(pid: usize, signum: usize) -> isize {
-    use crate::proc::signal::Signal;
+    send_signal(pid, signum, |sender, uid| {
+        crate::proc::signal::SignalInfo::user(signum, sender, uid)
+    })
+}
 
-    if !Signal::is_valid(signum) {
+/// Sends the signal built by `info` from the current process to process
+/// `pid`, after the checks shared by `task_kill` and `sig_queue`.
+///
+/// `info` receives the sender's PID and real user ID.
+#[expect(clippy::cast_possible_truncation, reason = "PID fits in u32")]
+pub(super) fn send_signal(
+    pid: usize,
+    signum: usize,
+    info: impl FnOnce(u32, u32) -> crate::proc::signal::SignalInfo,
+) -> isize {
+    use crate::proc::signal::{PostError, Signal};
+
+    if signum != 0 && !Signal::is_valid(signum) {
         return -(crate::syscall::EINVAL);
     }
 
     let target = crate::proc::ProcessTable::lookup(crate::id::Pid::new(pid as u32));
     match target {
         Some(proc) => {
-            proc.signals.post(signum);
-            0
+            let (sender_pid, sender) =
+                crate::proc::ProcessTable::with_current(|p| (p.pid, p.cred()));
+            if !sender.may_signal(&proc.cred()) {
+                return -(crate::syscall::EPERM);
+            }
+            if signum == 0 {
+                return 0;
+            }
+            match proc
+                .signals
+                .post_info(info(sender_pid.as_u32(), sender.uid().as_u32()))
+            {
+                Ok(()) => 0,
+                Err(PostError::QueueFull) => -(crate::syscall::EAGAIN),
+                Err(PostError::Invalid) => -(crate::syscall::EINVAL),
+            }
         }
         None => -(crate::syscall::EINVAL),
     }
@@ -360,7 +423,7 @@ pub(super) fn sys_task_setpgid(pid: usize, pgid: usize) -> isize {
     };
 
     // Must be self or a child of the caller.
-    if target.pid != current_pid && target.parent_pid != Some(current_pid) {
+    if target.pid != current_pid && target.parent_pid() != Some(current_pid) {
         return -(crate::syscall::EACCES);
     }
 
@@ -391,86 +454,103 @@ pub(super) fn sys_task_getpgid(pid: usize) -> isize {
     }
 }
 
-/// `sys_task_sigaction` — register a signal handler with flags.
+/// `sys_task_sigaction` — examine or change a signal action.
 ///
-/// `signum` is the signal number (1-63). SIGKILL and SIGSTOP cannot be caught.
-/// `handler` is `SIG_DFL` (0), `SIG_IGN` (1), or a userspace function pointer.
-/// `flags` is a bitmask of `SA_RESTART`, `SA_RESETHAND`, etc.
-/// If `old_handler_out` is non-zero, the previous handler address is written there.
+/// `signum` is the signal number (1-64). SIGKILL and SIGSTOP cannot be caught.
+/// If `act_ptr` is non-zero, the [`SigAction`](crate::syscall::SigAction)
+/// there becomes the new action. If `oldact_ptr` is non-zero, the previous
+/// action is written there.
 ///
 /// Returns 0 on success, or a negated errno on failure.
-pub(super) fn sys_task_sigaction(
-    signum: usize,
-    handler: usize,
-    flags: usize,
-    old_handler_out: usize,
-) -> isize {
-    crate::proc::ProcessTable::with_current(|process| {
-        let old = match process
-            .signals
-            .set_handler(signum, handler as u64, flags as u32)
-        {
-            Some(old) => old,
-            None => return -(crate::syscall::EINVAL),
-        };
+pub(super) fn sys_task_sigaction(signum: usize, act_ptr: usize, oldact_ptr: usize) -> isize {
+    use crate::proc::signal::Signal;
+    use crate::syscall::{SIGKILL, SIGSTOP, SigAction};
 
-        // Write old handler to userspace if requested.
-        if old_handler_out != 0 {
-            match UserSlice::new(old_handler_out, core::mem::size_of::<u64>()) {
-                Ok(slice) => {
-                    // SAFETY: The pointer was validated by UserSlice and user CR3
-                    // is still active (we're in a syscall from userspace).
-                    unsafe {
-                        core::ptr::write(slice.addr() as *mut u64, old);
-                    }
-                }
-                Err(e) => return e,
-            }
+    if !Signal::is_valid(signum) {
+        return -(crate::syscall::EINVAL);
+    }
+
+    // Copies run outside `with_current`: a fault on the user stack
+    // re-enters the process table.
+    let new = if act_ptr == 0 {
+        None
+    } else {
+        match UserPtr::<SigAction>::new(act_ptr).and_then(|p| p.read()) {
+            Ok(act) => Some(act),
+            Err(e) => return e,
         }
+    };
+    if new.is_some() && (signum == SIGKILL || signum == SIGSTOP) {
+        return -(crate::syscall::EINVAL);
+    }
 
-        0
-    })
+    let old = crate::proc::ProcessTable::with_current(|process| match &new {
+        Some(act) => process.signals.set_action(signum, act),
+        None => Some(process.signals.action(signum)),
+    });
+    let Some(old) = old else {
+        return -(crate::syscall::EINVAL);
+    };
+
+    if oldact_ptr != 0 {
+        if let Err(e) = UserPtr::<SigAction>::new(oldact_ptr).and_then(|p| p.write(old)) {
+            return e;
+        }
+    }
+
+    0
 }
 
+/// RFLAGS bits a signal handler may change through its `UContext`: the
+/// status flags, `TF`, `DF` and `AC`. Everything else, notably `IOPL`, keeps
+/// its user-mode default.
+const SIGRETURN_RFLAGS_MASK: u64 = 0x4_0DD5;
+
 /// `sys_task_sigreturn` — restore pre-signal context.
 ///
 /// Called from the signal return trampoline after a signal handler finishes.
-/// Reads the [`SignalFrame`] from the user stack and restores all registers,
-/// then resumes execution at the interrupted instruction.
+/// Reads the [`SignalFrame`] from the user stack and restores all registers
+/// and the FPU state that `fpregs` points to, then resumes execution at the
+/// interrupted instruction.
 ///
 /// This works by restoring the kernel context from the syscall entry (like
 /// other blocking syscalls) and setting up the TRAP to re-enter userspace
 /// at the restored instruction pointer.
 pub(super) fn sys_task_sigreturn() -> isize {
+    use crate::arch::x86_64::fpu;
     use crate::proc::SignalFrame;
-
-    // Read the SignalFrame from the user stack.
-    // The current user RSP points to just past the return address that was
-    // consumed by the `ret` from the signal handler. The frame starts at
-    // the current RSP (which is the RSP we set up, pointing to the frame).
-    //
-    // Actually, when the signal handler does `ret`, it pops ret_addr and RSP
-    // advances past it. But we set RSP to point to the start of the frame
-    // (where ret_addr lives), so after `ret`, RSP = &frame + 8. However,
-    // the trampoline then does `syscall` which enters the kernel. At that
-    // point, the user RSP in the percpu struct points past ret_addr.
-    //
-    // We need to read from (user_rsp - 8) to get back to the frame start,
-    // or we can read from the user RSP that was saved in the SignalFrame itself.
-    //
-    // Simpler approach: the trampoline calls sigreturn immediately, so
-    // user RSP = &frame.signum (right after ret_addr was popped by `ret`).
-    // We read from (user_rsp - 8) to get the full frame.
-
-    let frame: SignalFrame = crate::proc::ProcessTable::with_current(|_process| {
-        let user_rsp = crate::percpu::PerCpuState::current().user_rsp;
-        let frame_addr = user_rsp - 8; // Back up past the popped ret_addr.
-
-        // Read the frame from user memory.
-        // SAFETY: User CR3 is still active (we're in a syscall). The frame
-        // was written by deliver_signal_to_handler at a validated address.
-        unsafe { core::ptr::read(frame_addr as *const SignalFrame) }
-    });
+    use hadron_syscall::FpState;
+
+    // The handler's `ret` popped `ret_addr` and the trampoline called us
+    // immediately, so the frame starts 8 bytes below the user RSP.
+    let user_rsp = crate::percpu::PerCpuState::current().user_rsp;
+    let frame_addr = user_rsp.wrapping_sub(8) as usize; // Back up past the popped ret_addr.
+
+    // Read the frame from user memory. A frame the process has unmapped or
+    // corrupted is fatal, as on a failed signal delivery.
+    // The handler may have edited the context, so the resume point must
+    // still be in user space, and its FPU state readable.
+    let (frame, fpstate) = match UserPtr::<SignalFrame>::new(frame_addr)
+        .and_then(|p| p.read())
+        .and_then(|frame| {
+            let regs = &frame.uc.mcontext;
+            if regs.rip as usize >= USER_ADDR_MAX || regs.rsp as usize >= USER_ADDR_MAX {
+                return Err(-(crate::syscall::EFAULT));
+            }
+            let fpstate = match regs.fpregs {
+                0 => None,
+                addr => Some(UserPtr::<FpState>::new(addr as usize)?.read()?),
+            };
+            Ok((frame, fpstate))
+        }) {
+        Ok(restored) => restored,
+        Err(e) => {
+            crate::proc::ProcessTable::with_current(|p| {
+                p.signals.post(crate::syscall::SIGSEGV);
+            });
+            return e;
+        }
+    };
 
     // Now we need to restore the kernel context and set up for re-entry.
     // We use the same TRAP mechanism as sys_task_wait: longjmp back to
@@ -484,28 +564,49 @@ pub(super) fn sys_task_sigreturn() -> isize {
         IA32_KERNEL_GS_BASE.write(percpu);
     }
 
+    // Restore the signal mask saved when the handler was entered.
+    crate::proc::ProcessTable::with_current(|p| {
+        p.signals
+            .set_mask(crate::syscall::SIG_SETMASK, frame.uc.sigmask);
+    });
+
     // Populate USER_CONTEXT with the saved registers from the SignalFrame.
     // SAFETY: USER_CONTEXT is per-CPU and we are the only accessor right now.
     unsafe {
+        let regs = &frame.uc.mcontext;
         let ctx = &mut *crate::proc::USER_CONTEXT.get().get();
-        ctx.rax = frame.rax;
-        ctx.rbx = frame.rbx;
-        ctx.rcx = frame.rcx;
-        ctx.rdx = frame.rdx;
-        ctx.rsi = frame.rsi;
-        ctx.rdi = frame.rdi;
-        ctx.rbp = frame.rbp;
-        ctx.r8 = frame.r8;
-        ctx.r9 = frame.r9;
-        ctx.r10 = frame.r10;
-        ctx.r11 = frame.r11;
-        ctx.r12 = frame.r12;
-        ctx.r13 = frame.r13;
-        ctx.r14 = frame.r14;
-        ctx.r15 = frame.r15;
-        ctx.rip = frame.rip;
-        ctx.rsp = frame.rsp;
-        ctx.rflags = frame.rflags;
+        ctx.rax = regs.rax;
+        ctx.rbx = regs.rbx;
+        ctx.rcx = regs.rcx;
+        ctx.rdx = regs.rdx;
+        ctx.rsi = regs.rsi;
+        ctx.rdi = regs.rdi;
+        ctx.rbp = regs.rbp;
+        ctx.r8 = regs.r8;
+        ctx.r9 = regs.r9;
+        ctx.r10 = regs.r10;
+        ctx.r11 = regs.r11;
+        ctx.r12 = regs.r12;
+        ctx.r13 = regs.r13;
+        ctx.r14 = regs.r14;
+        ctx.r15 = regs.r15;
+        ctx.rip = regs.rip;
+        ctx.rsp = regs.rsp;
+        ctx.rflags = (regs.rflags & SIGRETURN_RFLAGS_MASK) | USER_RFLAGS;
+    }
+
+    // Resume with the saved FPU state, or the handler's if `fpregs` was
+    // cleared. MXCSR comes from user memory, so drop bits FXRSTOR rejects.
+    // SAFETY: USER_FPU_CONTEXT is per-CPU, 64-byte aligned and 512 bytes.
+    unsafe {
+        let area = crate::proc::TrapContext::user_fpu_context_ptr();
+        match fpstate {
+            Some(mut fpstate) => {
+                fpstate.mxcsr = fpu::sanitize_mxcsr(fpstate.mxcsr);
+                area.cast::<FpState>().write(fpstate);
+            }
+            None => fpu::save_user_state(area),
+        }
     }
 
     // Set trap reason to Preempted so process_task resumes via enter_userspace_resume_wrapper
@@ -528,7 +629,7 @@ pub(super) fn sys_task_sigreturn() -> isize {
 )]
 pub(super) fn sys_task_getppid() -> isize {
     crate::proc::ProcessTable::with_current(|process| {
-        process.parent_pid.map_or(0, |pid| pid.as_u32() as isize)
+        process.parent_pid().map_or(0, |pid| pid.as_u32() as isize)
     })
 }
 
@@ -544,19 +645,17 @@ pub(super) fn sys_task_getcwd(buf_ptr: usize, buf_len: usize) -> isize {
         return -crate::syscall::EFAULT;
     };
 
-    crate::proc::ProcessTable::with_current(|process| {
-        let cwd = process.cwd.lock();
-        let cwd_bytes = cwd.as_bytes();
+    let cwd = crate::proc::ProcessTable::with_current(|process| process.cwd.lock().clone());
+    let cwd_bytes = cwd.as_bytes();
 
-        if cwd_bytes.len() > buf_len {
-            return -crate::syscall::EINVAL;
-        }
+    if cwd_bytes.len() > buf_len {
+        return -crate::syscall::EINVAL;
+    }
 
-        // SAFETY: UserSlice validated the pointer range is in user space.
-        let buf = unsafe { user_slice.as_mut_slice() };
-        buf[..cwd_bytes.len()].copy_from_slice(cwd_bytes);
-        cwd_bytes.len() as isize
-    })
+    if let Err(e) = user_slice.write_from(cwd_bytes) {
+        return e;
+    }
+    cwd_bytes.len() as isize
 }
 
 /// `sys_task_chdir` — change the current working directory.
@@ -567,9 +666,11 @@ pub(super) fn sys_task_chdir(path_ptr: usize, path_len: usize) -> isize {
         return -crate::syscall::EFAULT;
     };
 
-    // SAFETY: UserSlice validated the pointer range is in user space.
-    let path_bytes = unsafe { user_slice.as_slice() };
-    let Ok(path) = core::str::from_utf8(path_bytes) else {
+    let path_bytes = match user_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
+    let Ok(path) = core::str::from_utf8(&path_bytes) else {
         return -crate::syscall::EINVAL;
     };
 
@@ -628,22 +729,18 @@ pub(super) fn sys_task_setsid() -> isize {
 ///
 /// Returns 0 on success, or a negated errno on failure.
 pub(super) fn sys_task_sigprocmask(how: usize, set: usize, oldset_out: usize) -> isize {
-    crate::proc::ProcessTable::with_current(|process| {
-        let old_mask = process.signals.set_mask(how, set as u64);
+    let old_mask = crate::proc::ProcessTable::with_current(|process| {
+        process.signals.set_mask(how, set as u64)
+    });
 
-        if oldset_out != 0 {
-            // Write old mask to user pointer.
-            let Ok(user_ptr) = crate::syscall::userptr::UserPtr::<u64>::new(oldset_out) else {
-                return -crate::syscall::EFAULT;
-            };
-            // SAFETY: UserPtr validated alignment and range.
-            unsafe {
-                core::ptr::write(user_ptr.addr() as *mut u64, old_mask);
-            }
+    if oldset_out != 0 {
+        // Write old mask to user pointer.
+        if let Err(e) = UserPtr::<u64>::new(oldset_out).and_then(|p| p.write(old_mask)) {
+            return e;
         }
+    }
 
-        0
-    })
+    0
 }
 
 /// `sys_task_execve` — replace the current process image with a new program.
@@ -738,23 +835,26 @@ pub(super) fn sys_task_clone(flags: usize, stack_ptr: usize, tls_ptr: usize) ->
     // first_entry. For a clone, we need the child to resume at the
     // exact instruction after the syscall with specific register state.
     // We use a dedicated clone_task instead.
-    crate::sched::spawn(clone_task(
-        child,
-        child_rip,
-        stack_ptr as u64,
-        child_rflags,
-        child_rbx,
-        child_rbp,
-        child_r12,
-        child_r13,
-        child_r14,
-        child_r15,
-        if flags & hadron_syscall::CLONE_SETTLS != 0 {
-            Some(tls_ptr as u64)
-        } else {
-            None
-        },
-    ));
+    crate::sched::spawn_user(
+        "thread",
+        clone_task(
+            child,
+            child_rip,
+            stack_ptr as u64,
+            child_rflags,
+            child_rbx,
+            child_rbp,
+            child_r12,
+            child_r13,
+            child_r14,
+            child_r15,
+            if flags & hadron_syscall::CLONE_SETTLS != 0 {
+                Some(tls_ptr as u64)
+            } else {
+                None
+            },
+        ),
+    );
 
     child_pid.as_u32() as isize
 }
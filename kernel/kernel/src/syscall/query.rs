@@ -5,12 +5,13 @@
 
 use core::mem::size_of;
 
+use crate::cputime::CpuTimes;
 use crate::mm::PAGE_SIZE;
 use crate::syscall::userptr::is_kernel_caller;
 use crate::syscall::{
-    CpuInfo, EFAULT, EINVAL, KernelVersionInfo, MemoryInfo, ProcessInfo, QUERY_CPU_INFO,
-    QUERY_KERNEL_VERSION, QUERY_MEMORY, QUERY_PROCESSES, QUERY_UPTIME, QUERY_VMAPS, UptimeInfo,
-    VmapEntry,
+    CpuInfo, EFAULT, EINVAL, ESRCH, KernelVersionInfo, MemoryInfo, ProcessInfo, QUERY_CPU_INFO,
+    QUERY_KERNEL_VERSION, QUERY_MEMORY, QUERY_PROCESSES, QUERY_RUSAGE, QUERY_UPTIME, QUERY_VMAPS,
+    RUSAGE_CHILDREN, RUSAGE_SELF, RUSAGE_THREAD, RusageInfo, UptimeInfo, VmapEntry,
 };
 
 /// Kernel version: major.
@@ -30,10 +31,12 @@ const KERNEL_NAME: &[u8; 6] = b"Hadron";
 /// # Arguments
 ///
 /// * `topic`   — one of the `QUERY_*` constants selecting the information type.
-/// * `_sub_id` — reserved for future per-topic sub-selection (e.g. per-CPU info).
+/// * `sub_id`  — per-topic sub-selection (the PID for `QUERY_PROCESSES`,
+///   a `RUSAGE_*` selector for `QUERY_RUSAGE`; reserved and ignored by the
+///   other topics).
 /// * `out_buf` — user-space pointer to the output buffer.
 /// * `out_len` — size of the output buffer in bytes.
-pub(super) fn sys_query(topic: usize, _sub_id: usize, out_buf: usize, out_len: usize) -> isize {
+pub(super) fn sys_query(topic: usize, sub_id: usize, out_buf: usize, out_len: usize) -> isize {
     #[expect(clippy::cast_possible_truncation, reason = "query topics fit in u64")]
     let topic = topic as u64;
 
@@ -41,9 +44,10 @@ pub(super) fn sys_query(topic: usize, _sub_id: usize, out_buf: usize, out_len: u
         QUERY_MEMORY => query_memory(out_buf, out_len),
         QUERY_UPTIME => query_uptime(out_buf, out_len),
         QUERY_KERNEL_VERSION => query_kernel_version(out_buf, out_len),
-        QUERY_PROCESSES => query_processes(out_buf, out_len),
+        QUERY_PROCESSES => query_processes(sub_id, out_buf, out_len),
         QUERY_VMAPS => query_vmaps(out_buf, out_len),
         QUERY_CPU_INFO => query_cpu_info(out_buf, out_len),
+        QUERY_RUSAGE => query_rusage(sub_id, out_buf, out_len),
         _ => -EINVAL,
     }
 }
@@ -52,27 +56,22 @@ pub(super) fn sys_query(topic: usize, _sub_id: usize, out_buf: usize, out_len: u
 /// user-mode callers.
 ///
 /// Returns `size_of::<T>()` on success or `-errno` on failure.
-fn write_response<T>(out_buf: usize, out_len: usize, value: &T) -> isize {
+fn write_response<T: Copy>(out_buf: usize, out_len: usize, value: &T) -> isize {
     let needed = size_of::<T>();
     if out_len < needed {
         return -EINVAL;
     }
 
-    if is_kernel_caller(out_buf) {
+    if is_kernel_caller() {
         // Kernel-mode test: out_buf is a kernel address, write directly.
-        // SAFETY: Kernel-mode callers pass a valid pointer in the upper half
-        // (verified by `is_kernel_caller`). The caller ensures the buffer is
-        // large enough.
+        // SAFETY: Kernel-mode callers pass a valid kernel pointer. The
+        // caller ensures the buffer is large enough.
         unsafe { core::ptr::write(out_buf as *mut T, core::ptr::read(value)) };
     } else {
         use crate::syscall::userptr::UserPtr;
-        let Ok(user_ptr) = UserPtr::<T>::new(out_buf) else {
-            return -EFAULT;
-        };
-        // SAFETY: UserPtr validated that the address is in user space,
-        // properly aligned, and does not overflow. The caller is responsible
-        // for ensuring the page is mapped and writable.
-        unsafe { core::ptr::write(user_ptr.addr() as *mut T, core::ptr::read(value)) };
+        if let Err(e) = UserPtr::<T>::new(out_buf).and_then(|p| p.write(*value)) {
+            return e;
+        }
     }
 
     // Response structs are small fixed-size types; their size always fits in isize.
@@ -88,6 +87,7 @@ fn write_response<T>(out_buf: usize, out_len: usize, value: &T) -> isize {
 fn query_memory(out_buf: usize, out_len: usize) -> isize {
     let (total_frames, free_frames) =
         crate::mm::pmm::with(|pmm| (pmm.total_frames(), pmm.free_frames()));
+    let free_frames = free_frames + crate::mm::pmm::hot_cached_frames();
 
     let info = MemoryInfo {
         total_bytes: (total_frames * PAGE_SIZE) as u64,
@@ -124,12 +124,72 @@ fn query_kernel_version(out_buf: usize, out_len: usize) -> isize {
     write_response(out_buf, out_len, &info)
 }
 
-/// Handle `QUERY_PROCESSES`: return process table statistics.
-#[expect(clippy::cast_possible_truncation, reason = "process count fits in u32")]
-fn query_processes(out_buf: usize, out_len: usize) -> isize {
+/// Handle `QUERY_PROCESSES`: return process table statistics and the memory
+/// accounting of the process selected by `pid` (0 = the calling process).
+///
+/// Returns `-ESRCH` if `pid` does not name a live process.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "process count and PID fit in u32"
+)]
+fn query_processes(pid: usize, out_buf: usize, out_len: usize) -> isize {
+    use crate::proc::ProcessTable;
+    use hadron_core::sync::atomic::Ordering;
+
+    let process = if pid == 0 {
+        ProcessTable::try_current(alloc::sync::Arc::clone)
+    } else {
+        ProcessTable::lookup(crate::id::Pid::new(pid as u32))
+    };
+    if pid != 0 && process.is_none() {
+        return -ESRCH;
+    }
+
+    let (rss_pages, table_pages, oom_score, oom_score_adj, cpu) =
+        process.map_or((0, 0, 0, 0, CpuTimes::ZERO), |p| {
+            (
+                p.resident_pages(),
+                p.page_table_pages(),
+                crate::mm::oom::score_of(&p),
+                p.oom_score_adj.load(Ordering::Relaxed),
+                p.process_cpu_times(),
+            )
+        });
+
     let info = ProcessInfo {
-        count: crate::proc::ProcessTable::count() as u32,
+        count: ProcessTable::count() as u32,
         _pad: 0,
+        rss_bytes: (rss_pages * PAGE_SIZE) as u64,
+        page_table_bytes: (table_pages * PAGE_SIZE) as u64,
+        oom_score,
+        oom_score_adj,
+        user_ns: cpu.user_ns,
+        system_ns: cpu.system_ns,
+    };
+
+    write_response(out_buf, out_len, &info)
+}
+
+/// Handle `QUERY_RUSAGE`: return the CPU time of the calling process
+/// (`RUSAGE_SELF`), thread (`RUSAGE_THREAD`) or reaped children
+/// (`RUSAGE_CHILDREN`).
+///
+/// Returns `-EINVAL` for any other selector. Kernel-mode callers have no
+/// process and get zero times.
+fn query_rusage(who: usize, out_buf: usize, out_len: usize) -> isize {
+    use crate::proc::ProcessTable;
+
+    let times = match who {
+        RUSAGE_SELF => ProcessTable::try_current(|p| p.process_cpu_times()),
+        RUSAGE_THREAD => ProcessTable::try_current(|p| p.thread_cpu_times()),
+        RUSAGE_CHILDREN => ProcessTable::try_current(|p| p.children_cpu_times()),
+        _ => return -EINVAL,
+    }
+    .unwrap_or(CpuTimes::ZERO);
+
+    let info = RusageInfo {
+        user_ns: times.user_ns,
+        system_ns: times.system_ns,
     };
 
     write_response(out_buf, out_len, &info)
@@ -185,17 +245,17 @@ fn query_vmaps(out_buf: usize, out_len: usize) -> isize {
 
     // Write entries directly into the output buffer.
     for (i, entry) in entries.iter().take(n).enumerate() {
-        let dst = out_buf + i * entry_size;
-        if is_kernel_caller(dst) {
+        let Some(dst) = out_buf.checked_add(i * entry_size) else {
+            return -EFAULT;
+        };
+        if is_kernel_caller() {
             // SAFETY: Kernel-mode callers pass a valid kernel-space pointer.
             unsafe { core::ptr::write(dst as *mut VmapEntry, *entry) };
         } else {
             use crate::syscall::userptr::UserPtr;
-            let Ok(user_ptr) = UserPtr::<VmapEntry>::new(dst) else {
-                return -EFAULT;
-            };
-            // SAFETY: UserPtr validated alignment and user-space bounds.
-            unsafe { core::ptr::write(user_ptr.addr() as *mut VmapEntry, *entry) };
+            if let Err(e) = UserPtr::<VmapEntry>::new(dst).and_then(|p| p.write(*entry)) {
+                return e;
+            }
         }
     }
 
@@ -0,0 +1,283 @@
+//! Scheduling syscall handlers: nice values, CPU affinity and scheduling
+//! classes.
+//!
+//! All three are stored per thread in [`Process`] and reported to the
+//! executor by the thread's process task each time it is polled; see
+//! [`hadron_sched::executor`] for how the User tier uses them.
+
+extern crate alloc;
+
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use hadron_core::rt::{DeadlineParams, SchedPolicy};
+use hadron_core::sched::{NICE_MAX, NICE_MIN};
+use hadron_core::sync::atomic::Ordering;
+
+use crate::id::Pid;
+use crate::percpu::PerCpuState;
+use crate::proc::{Process, ProcessTable};
+use crate::syscall::userptr::{UserPtr, read_user_array};
+use crate::syscall::{
+    EACCES, EBUSY, EINVAL, EPERM, ESRCH, PRIO_PGRP, PRIO_PROCESS, PRIO_USER, SCHED_DEADLINE,
+    SCHED_FIFO, SCHED_OTHER, SCHED_RR, SchedAttr,
+};
+
+/// Size of the affinity mask the kernel reads and writes.
+const MASK_BYTES: usize = core::mem::size_of::<u64>();
+
+/// Returns the mask of online CPUs.
+fn online_cpus() -> u64 {
+    let count = PerCpuState::cpu_count();
+    if count >= u64::BITS {
+        u64::MAX
+    } else {
+        (1 << count) - 1
+    }
+}
+
+/// Returns the live processes selected by a `PRIO_*` `which` and `who`.
+fn select(which: usize, who: usize) -> Result<Vec<Arc<Process>>, isize> {
+    let who = u32::try_from(who).map_err(|_| -EINVAL)?;
+    let (caller_pid, caller_pgid, caller_uid) = ProcessTable::with_current(|p| {
+        (
+            p.pid,
+            p.pgid.load(Ordering::Acquire),
+            p.cred().uid().as_u32(),
+        )
+    });
+    let or_caller = |id: u32| if who == 0 { id } else { who };
+
+    let matches: &dyn Fn(&Process) -> bool = match which {
+        PRIO_PROCESS => {
+            let pid = Pid::new(or_caller(caller_pid.as_u32()));
+            let target = ProcessTable::lookup(pid).filter(|p| p.exit_status.lock().is_none());
+            return Ok(target.into_iter().collect());
+        }
+        PRIO_PGRP => &|p| p.pgid.load(Ordering::Acquire) == or_caller(caller_pgid),
+        PRIO_USER => &|p| p.cred().uid().as_u32() == or_caller(caller_uid),
+        _ => return Err(-EINVAL),
+    };
+    Ok(ProcessTable::all_pids()
+        .into_iter()
+        .filter_map(ProcessTable::lookup)
+        .filter(|p| p.exit_status.lock().is_none() && matches(p))
+        .collect())
+}
+
+/// `sys_sched_getpriority` — returns `20 - nice` of the highest-priority
+/// process selected by `which` and `who`.
+pub(super) fn sys_sched_getpriority(which: usize, who: usize) -> isize {
+    let targets = match select(which, who) {
+        Ok(targets) => targets,
+        Err(e) => return e,
+    };
+    targets
+        .iter()
+        .map(|p| p.nice.load(Ordering::Relaxed))
+        .min()
+        .map_or(-ESRCH, |nice| (20 - nice) as isize)
+}
+
+/// `sys_sched_setpriority` — sets the nice value of every process selected
+/// by `which` and `who`.
+///
+/// Processes the caller may not change are skipped and reported with the
+/// last error; the others still change.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "nice is passed as a sign-extended i32"
+)]
+pub(super) fn sys_sched_setpriority(which: usize, who: usize, nice: usize) -> isize {
+    let targets = match select(which, who) {
+        Ok(targets) => targets,
+        Err(e) => return e,
+    };
+    if targets.is_empty() {
+        return -ESRCH;
+    }
+
+    let nice = (nice as i32).clamp(NICE_MIN, NICE_MAX);
+    let caller = ProcessTable::with_current(|p| p.cred());
+    let mut ret = 0;
+    for target in &targets {
+        if !caller.may_reschedule(&target.cred()) {
+            ret = -EPERM;
+        } else if nice < target.nice.load(Ordering::Relaxed) && !caller.is_privileged() {
+            ret = -EACCES;
+        } else {
+            target.nice.store(nice, Ordering::Relaxed);
+        }
+    }
+    ret
+}
+
+/// Looks up the target of a per-thread call: `pid`, or the caller if `pid`
+/// is 0.
+fn lookup_target(pid: usize) -> Result<Arc<Process>, isize> {
+    let pid = u32::try_from(pid).map_err(|_| -ESRCH)?;
+    if pid == 0 {
+        return Ok(ProcessTable::with_current(Arc::clone));
+    }
+    ProcessTable::lookup(Pid::new(pid)).ok_or(-ESRCH)
+}
+
+/// `sys_sched_getaffinity` — writes the affinity mask of `pid` to
+/// `mask_ptr` and returns its size.
+#[expect(clippy::cast_possible_wrap, reason = "MASK_BYTES is 8")]
+pub(super) fn sys_sched_getaffinity(pid: usize, mask_len: usize, mask_ptr: usize) -> isize {
+    if mask_len < MASK_BYTES {
+        return -EINVAL;
+    }
+    let target = match lookup_target(pid) {
+        Ok(target) => target,
+        Err(e) => return e,
+    };
+    let mask = target.affinity.load(Ordering::Relaxed) & online_cpus();
+    match UserPtr::<u64>::new(mask_ptr).and_then(|p| p.write(mask)) {
+        Ok(()) => MASK_BYTES as isize,
+        Err(e) => e,
+    }
+}
+
+/// `sys_sched_setaffinity` — restricts `pid` to the online CPUs in the mask
+/// at `mask_ptr`.
+///
+/// Bytes beyond the first 8 are ignored, since the executor can address at
+/// most 64 CPUs.
+pub(super) fn sys_sched_setaffinity(pid: usize, mask_len: usize, mask_ptr: usize) -> isize {
+    let bytes = match read_user_array::<u8>(mask_ptr, mask_len.min(MASK_BYTES)) {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
+    let mut raw = [0u8; MASK_BYTES];
+    raw[..bytes.len()].copy_from_slice(&bytes);
+    let mask = u64::from_le_bytes(raw) & online_cpus();
+    if mask == 0 {
+        return -EINVAL;
+    }
+
+    let target = match lookup_target(pid) {
+        Ok(target) => target,
+        Err(e) => return e,
+    };
+    let caller = ProcessTable::with_current(|p| p.cred());
+    if !caller.may_reschedule(&target.cred()) {
+        return -EPERM;
+    }
+    target.affinity.store(mask, Ordering::Relaxed);
+    0
+}
+
+/// Decodes the policy in `attr`, without validating its parameters.
+fn policy_from_attr(attr: &SchedAttr) -> Result<SchedPolicy, isize> {
+    let priority = || u8::try_from(attr.priority).map_err(|_| -EINVAL);
+    match attr.policy {
+        SCHED_OTHER if attr.priority == 0 => Ok(SchedPolicy::Normal),
+        SCHED_FIFO => Ok(SchedPolicy::Fifo(priority()?)),
+        SCHED_RR => Ok(SchedPolicy::RoundRobin(priority()?)),
+        SCHED_DEADLINE => Ok(SchedPolicy::Deadline(DeadlineParams {
+            runtime_ns: attr.runtime,
+            deadline_ns: attr.deadline,
+            period_ns: if attr.period == 0 {
+                attr.deadline
+            } else {
+                attr.period
+            },
+        })),
+        _ => Err(-EINVAL),
+    }
+}
+
+/// `sys_sched_setattr` — sets the scheduling class of `pid` from the
+/// [`SchedAttr`] at `attr_ptr`.
+///
+/// Unprivileged callers may only lower a real-time priority or return to
+/// `SCHED_OTHER`. A deadline reservation must pass admission control.
+pub(super) fn sys_sched_setattr(pid: usize, attr_ptr: usize, flags: usize) -> isize {
+    if flags != 0 {
+        return -EINVAL;
+    }
+    let attr = match UserPtr::<SchedAttr>::new(attr_ptr).and_then(|p| p.read()) {
+        Ok(attr) => attr,
+        Err(e) => return e,
+    };
+    if attr.flags != 0 {
+        return -EINVAL;
+    }
+    let policy = match policy_from_attr(&attr) {
+        Ok(policy) if policy.is_valid() => policy,
+        Ok(_) => return -EINVAL,
+        Err(e) => return e,
+    };
+    let nice = attr.nice.clamp(NICE_MIN, NICE_MAX);
+
+    let target = match lookup_target(pid) {
+        Ok(target) => target,
+        Err(e) => return e,
+    };
+    let caller = ProcessTable::with_current(|p| p.cred());
+    if !caller.may_reschedule(&target.cred()) {
+        return -EPERM;
+    }
+
+    let mut current = target.sched_policy.lock();
+    if !caller.is_privileged() {
+        let allowed = match (policy, *current) {
+            (SchedPolicy::Normal, _) => nice >= target.nice.load(Ordering::Relaxed),
+            (SchedPolicy::Fifo(new), SchedPolicy::Fifo(old))
+            | (SchedPolicy::RoundRobin(new), SchedPolicy::RoundRobin(old)) => new <= old,
+            _ => false,
+        };
+        if !allowed {
+            return -EPERM;
+        }
+    }
+    if !crate::sched::change_deadline_reservation(current.deadline(), policy.deadline()) {
+        return -EBUSY;
+    }
+    *current = policy;
+    if policy == SchedPolicy::Normal {
+        target.nice.store(nice, Ordering::Relaxed);
+    }
+    0
+}
+
+/// `sys_sched_getattr` — writes the scheduling class of `pid` to the
+/// [`SchedAttr`] at `attr_ptr`.
+#[expect(clippy::cast_possible_truncation, reason = "SchedAttr is 48 bytes")]
+pub(super) fn sys_sched_getattr(pid: usize, attr_ptr: usize, size: usize) -> isize {
+    if size < core::mem::size_of::<SchedAttr>() {
+        return -EINVAL;
+    }
+    let target = match lookup_target(pid) {
+        Ok(target) => target,
+        Err(e) => return e,
+    };
+    let mut attr = SchedAttr {
+        size: core::mem::size_of::<SchedAttr>() as u32,
+        nice: target.nice.load(Ordering::Relaxed),
+        ..SchedAttr::default()
+    };
+    match *target.sched_policy.lock() {
+        SchedPolicy::Normal => attr.policy = SCHED_OTHER,
+        SchedPolicy::Fifo(priority) => {
+            attr.policy = SCHED_FIFO;
+            attr.priority = u32::from(priority);
+        }
+        SchedPolicy::RoundRobin(priority) => {
+            attr.policy = SCHED_RR;
+            attr.priority = u32::from(priority);
+        }
+        SchedPolicy::Deadline(params) => {
+            attr.policy = SCHED_DEADLINE;
+            attr.runtime = params.runtime_ns;
+            attr.deadline = params.deadline_ns;
+            attr.period = params.period_ns;
+        }
+    }
+    match UserPtr::<SchedAttr>::new(attr_ptr).and_then(|p| p.write(attr)) {
+        Ok(()) => 0,
+        Err(e) => e,
+    }
+}
@@ -0,0 +1,178 @@
+//! Signal syscall handlers: queued signals, the alternate signal stack,
+//! waiting for signals (`sig_suspend`, `sig_timedwait`) and signalfds
+//! (`sig_fd`).
+//!
+//! Sending with `task_kill`, changing actions with `task_sigaction`, and the
+//! mask with `task_sigprocmask` live in [`super::process`]; delivery itself
+//! happens in `process_task` (see [`crate::proc::signal`]).
+
+use alloc::sync::Arc;
+
+use crate::fs::file::OpenFlags;
+use crate::id::Fd;
+use crate::ipc::signalfd::SignalFd;
+use crate::percpu::PerCpuState;
+use crate::proc::ProcessTable;
+use crate::proc::signal::{SignalInfo, sig_bit};
+use crate::syscall::userptr::UserPtr;
+use crate::syscall::{
+    EAGAIN, EBADF, EINVAL, SFD_CLOEXEC, SFD_NONBLOCK, SIGKILL, SIGSTOP, SigAltStack, SigInfo,
+};
+
+/// `sys_sig_queue` — sends `signum` with `value` to process `pid`.
+///
+/// Same checks as `task_kill`; the receiver sees `SI_QUEUE` and `value`.
+pub(super) fn sys_sig_queue(pid: usize, signum: usize, value: usize) -> isize {
+    super::process::send_signal(pid, signum, |sender, uid| {
+        SignalInfo::queued(signum, sender, uid, value as u64)
+    })
+}
+
+/// `sys_sig_altstack` — sets and/or gets the alternate signal stack.
+///
+/// Whether the thread is on the stack is judged from the user stack pointer
+/// at syscall entry.
+pub(super) fn sys_sig_altstack(ss_ptr: usize, old_ss_ptr: usize) -> isize {
+    let rsp = PerCpuState::current().user_rsp;
+
+    let new = if ss_ptr == 0 {
+        None
+    } else {
+        match UserPtr::<SigAltStack>::new(ss_ptr).and_then(|p| p.read()) {
+            Ok(ss) => Some(ss),
+            Err(e) => return e,
+        }
+    };
+
+    let old = ProcessTable::with_current(|process| {
+        let old = process.signals.alt_stack(rsp);
+        match &new {
+            Some(ss) => process.signals.set_alt_stack(ss, rsp).map(|()| old),
+            None => Ok(old),
+        }
+    });
+    let old = match old {
+        Ok(old) => old,
+        Err(errno) => return -errno,
+    };
+
+    if old_ss_ptr != 0 {
+        if let Err(e) = UserPtr::<SigAltStack>::new(old_ss_ptr).and_then(|p| p.write(old)) {
+            return e;
+        }
+    }
+    0
+}
+
+/// `sys_sig_pending` — writes the set of pending, blocked signals.
+pub(super) fn sys_sig_pending(set_out: usize) -> isize {
+    let pending = ProcessTable::with_current(|p| p.signals.pending_blocked());
+    match UserPtr::<u64>::new(set_out).and_then(|p| p.write(pending)) {
+        Ok(()) => 0,
+        Err(e) => e,
+    }
+}
+
+/// `sys_sig_suspend` — installs `mask` and waits for a signal handler to run.
+///
+/// The previous mask is restored when the handler returns, via the
+/// `UContext` of its frame. Does not return directly.
+pub(super) fn sys_sig_suspend(mask: usize) -> isize {
+    ProcessTable::with_current(|p| p.signals.suspend_with_mask(mask as u64));
+    crate::proc::SigWaitState::set_suspend();
+    trap_sigwait()
+}
+
+/// `sys_sig_timedwait` — waits for and dequeues a signal in `set`.
+///
+/// SIGKILL and SIGSTOP are silently removed from `set`. A pending signal is
+/// taken without blocking; otherwise a zero timeout fails with `-EAGAIN`
+/// and any other timeout blocks via `TRAP_SIGWAIT`.
+#[expect(clippy::cast_possible_wrap, reason = "signal numbers are at most 64")]
+pub(super) fn sys_sig_timedwait(set: usize, info_ptr: usize, timeout_ptr: usize) -> isize {
+    let set = set as u64 & !(sig_bit(SIGKILL) | sig_bit(SIGSTOP));
+
+    let timeout_ns = match super::time::read_timeout(timeout_ptr) {
+        Ok(timeout_ns) => timeout_ns,
+        Err(e) => return e,
+    };
+
+    if let Some(info) = ProcessTable::with_current(|p| p.signals.dequeue_from(set)) {
+        if info_ptr != 0 {
+            if let Err(e) = UserPtr::<SigInfo>::new(info_ptr).and_then(|p| p.write(info.to_user()))
+            {
+                return e;
+            }
+        }
+        return info.signo as isize;
+    }
+    if timeout_ns == 0 {
+        return -EAGAIN;
+    }
+
+    crate::proc::SigWaitState::set_timedwait(set, info_ptr as u64, timeout_ns);
+    trap_sigwait()
+}
+
+/// Trigger a `TRAP_SIGWAIT` longjmp back to `process_task`.
+///
+/// The caller has already set the wait parameters through
+/// [`SigWaitState`](crate::proc::SigWaitState). Restores kernel CR3 and GS
+/// bases, then calls `restore_kernel_context` — never returns.
+fn trap_sigwait() -> ! {
+    use crate::arch::x86_64::registers::control::Cr3;
+    use crate::arch::x86_64::registers::model_specific::{IA32_GS_BASE, IA32_KERNEL_GS_BASE};
+    use crate::arch::x86_64::userspace::restore_kernel_context;
+
+    let kernel_cr3 = crate::proc::TrapContext::kernel_cr3();
+
+    // SAFETY: Restoring kernel CR3 and GS bases is the standard pattern
+    // for returning from userspace context to kernel context.
+    unsafe {
+        Cr3::write(kernel_cr3);
+        let percpu = IA32_GS_BASE.read();
+        IA32_KERNEL_GS_BASE.write(percpu);
+    }
+
+    crate::proc::TrapContext::set_trap_reason(crate::proc::TrapReason::SigWait);
+
+    let saved_rsp = crate::proc::TrapContext::saved_kernel_rsp();
+    // SAFETY: saved_rsp is the kernel RSP saved by enter_userspace_save,
+    // still valid on the executor stack.
+    unsafe {
+        restore_kernel_context(saved_rsp);
+    }
+}
+
+/// `sys_sig_fd` — creates a signalfd for the signals in `mask`, or replaces
+/// the mask of signalfd `fd` unless it is `usize::MAX`.
+pub(super) fn sys_sig_fd(fd: usize, mask: usize, flags: usize) -> isize {
+    if flags & !(SFD_CLOEXEC | SFD_NONBLOCK) != 0 {
+        return -EINVAL;
+    }
+    let mask = mask as u64;
+    if fd != usize::MAX {
+        let Ok(fd_num) = u32::try_from(fd) else {
+            return -EBADF;
+        };
+        let inode = match super::vfs::fd_inode(Fd::new(fd_num)) {
+            Ok(inode) => inode,
+            Err(e) => return e,
+        };
+        let Some(signalfd) = inode
+            .as_any()
+            .and_then(|any| any.downcast_ref::<SignalFd>())
+        else {
+            return -EINVAL;
+        };
+        signalfd.set_mask(mask);
+        #[expect(clippy::cast_possible_wrap, reason = "fd numbers are small")]
+        return fd as isize;
+    }
+    let thread = ProcessTable::with_current(Arc::clone);
+    super::vfs::open_anon(
+        Arc::new(SignalFd::new(&thread, mask)),
+        OpenFlags::READ,
+        flags,
+    )
+}
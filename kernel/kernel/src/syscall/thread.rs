@@ -0,0 +1,37 @@
+//! Per-thread syscall handlers: the thread pointer used for TLS.
+//!
+//! The FS base lives in [`Process::fs_base`](crate::proc::Process) and is
+//! loaded into `IA32_FS_BASE` on every entry to userspace. Setting it also
+//! writes the MSR directly, since a syscall may return without passing
+//! through that path.
+
+use crate::arch::x86_64::registers::model_specific::IA32_FS_BASE;
+use crate::proc::ProcessTable;
+use crate::syscall::EINVAL;
+use crate::syscall::userptr::USER_ADDR_MAX;
+use hadron_core::sync::atomic::Ordering;
+
+/// `sys_thread_set_tls(tls_ptr)` — sets the calling thread's FS base.
+///
+/// Returns 0, or `-EINVAL` if `tls_ptr` is not a user address.
+pub(super) fn sys_thread_set_tls(tls_ptr: usize) -> isize {
+    if tls_ptr >= USER_ADDR_MAX {
+        return -EINVAL;
+    }
+    ProcessTable::with_current(|process| {
+        process.fs_base.store(tls_ptr as u64, Ordering::Relaxed);
+    });
+    // SAFETY: `tls_ptr` is a canonical user address; the kernel does not
+    // use FS, so only user code observes the new base.
+    unsafe { IA32_FS_BASE.write(tls_ptr as u64) };
+    0
+}
+
+/// `sys_thread_get_tls()` — returns the calling thread's FS base.
+#[expect(
+    clippy::cast_possible_wrap,
+    reason = "user addresses are below 2^47 and fit in isize"
+)]
+pub(super) fn sys_thread_get_tls() -> isize {
+    ProcessTable::with_current(|process| process.fs_base.load(Ordering::Relaxed)) as isize
+}
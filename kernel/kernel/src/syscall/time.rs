@@ -1,16 +1,28 @@
 //! Time syscall handlers: clock_gettime, clock_nanosleep.
 
+use crate::proc::ProcessTable;
 use crate::syscall::userptr::{UserPtr, is_kernel_caller};
-use crate::syscall::{CLOCK_MONOTONIC, CLOCK_REALTIME, EFAULT, EINVAL, Timespec};
+use crate::syscall::{
+    CLOCK_MONOTONIC, CLOCK_PROCESS_CPUTIME_ID, CLOCK_REALTIME, CLOCK_THREAD_CPUTIME_ID, EINVAL,
+    Timespec,
+};
 
-/// `sys_clock_gettime` — returns monotonic or real-time clock value.
+/// `sys_clock_gettime` — returns the value of a clock.
 ///
-/// Supports `CLOCK_MONOTONIC` (boot-relative via HPET) and `CLOCK_REALTIME`
-/// (Unix epoch via CMOS RTC + HPET).
+/// Supports `CLOCK_MONOTONIC` (boot-relative via HPET), `CLOCK_REALTIME`
+/// (Unix epoch via CMOS RTC + HPET), and the CPU-time clocks
+/// `CLOCK_PROCESS_CPUTIME_ID` and `CLOCK_THREAD_CPUTIME_ID` (user plus
+/// system time of the calling process or thread).
 pub(super) fn sys_clock_gettime(clock_id: usize, tp: usize) -> isize {
     let nanos = match clock_id {
         CLOCK_MONOTONIC => crate::time::Time::boot_nanos(),
         CLOCK_REALTIME => crate::time::Time::realtime_nanos(),
+        CLOCK_PROCESS_CPUTIME_ID => {
+            ProcessTable::try_current(|p| p.process_cpu_times().total_ns()).unwrap_or(0)
+        }
+        CLOCK_THREAD_CPUTIME_ID => {
+            ProcessTable::try_current(|p| p.thread_cpu_times().total_ns()).unwrap_or(0)
+        }
         _ => return -EINVAL,
     };
 
@@ -19,20 +31,14 @@ pub(super) fn sys_clock_gettime(clock_id: usize, tp: usize) -> isize {
         tv_nsec: nanos % 1_000_000_000,
     };
 
-    if is_kernel_caller(tp) {
+    if is_kernel_caller() {
         // Kernel-mode test: tp is a kernel address, write directly.
         // SAFETY: Kernel-mode callers pass a valid stack-local `&mut Timespec`.
-        // The address is in the upper half (verified by `is_kernel_caller`).
         unsafe { core::ptr::write(tp as *mut Timespec, ts) };
     } else {
-        let user_ptr = match UserPtr::<Timespec>::new(tp) {
-            Ok(p) => p,
-            Err(_) => return -EFAULT,
-        };
-        // SAFETY: UserPtr validated that the address is in user space,
-        // properly aligned, and does not overflow. The caller is responsible
-        // for ensuring the page is mapped and writable.
-        unsafe { core::ptr::write(user_ptr.addr() as *mut Timespec, ts) };
+        if let Err(e) = UserPtr::<Timespec>::new(tp).and_then(|p| p.write(ts)) {
+            return e;
+        }
     }
 
     0
@@ -45,7 +51,7 @@ pub(super) fn sys_clock_gettime(clock_id: usize, tp: usize) -> isize {
 /// must be 0.
 ///
 /// This is a blocking syscall — it triggers a TRAP_SLEEP longjmp back to
-/// `process_task`, which awaits the sleep future.
+/// `process_task`, which awaits a high-resolution sleep future.
 pub(super) fn sys_clock_nanosleep(
     clock_id: usize,
     _flags: usize,
@@ -57,37 +63,58 @@ pub(super) fn sys_clock_nanosleep(
     }
 
     // Read the requested duration from user memory.
-    let ts = if is_kernel_caller(req_ptr) {
+    let ts = if is_kernel_caller() {
         // SAFETY: Kernel-mode callers pass a valid stack-local `&Timespec`.
         unsafe { core::ptr::read(req_ptr as *const Timespec) }
     } else {
-        let user_ptr = match UserPtr::<Timespec>::new(req_ptr) {
-            Ok(p) => p,
-            Err(_) => return -EFAULT,
-        };
-        // SAFETY: UserPtr validated the address.
-        unsafe { core::ptr::read(user_ptr.addr() as *const Timespec) }
+        match UserPtr::<Timespec>::new(req_ptr).and_then(|p| p.read()) {
+            Ok(ts) => ts,
+            Err(e) => return e,
+        }
     };
 
-    // Convert to milliseconds (timer ticks at 1kHz).
-    let ms = ts
-        .tv_sec
-        .saturating_mul(1000)
-        .saturating_add(ts.tv_nsec / 1_000_000);
+    let nanos = match timespec_nanos(ts) {
+        Ok(nanos) => nanos,
+        Err(e) => return e,
+    };
 
-    if ms == 0 {
+    if nanos == 0 {
         return 0; // Zero sleep, return immediately.
     }
 
     // Trigger TRAP_SLEEP to block in process_task.
-    trap_sleep(ms)
+    trap_sleep(nanos)
+}
+
+/// Converts a relative [`Timespec`] to nanoseconds, saturating.
+///
+/// Fails with `-EINVAL` if `tv_nsec` is not below one second.
+pub(super) fn timespec_nanos(ts: Timespec) -> Result<u64, isize> {
+    if ts.tv_nsec >= 1_000_000_000 {
+        return Err(-EINVAL);
+    }
+    Ok(ts
+        .tv_sec
+        .saturating_mul(1_000_000_000)
+        .saturating_add(ts.tv_nsec))
+}
+
+/// Reads a relative timeout from the user [`Timespec`] at `ptr`, in
+/// nanoseconds. A null `ptr` means no timeout and gives `u64::MAX`.
+pub(super) fn read_timeout(ptr: usize) -> Result<u64, isize> {
+    if ptr == 0 {
+        return Ok(u64::MAX);
+    }
+    UserPtr::<Timespec>::new(ptr)
+        .and_then(|p| p.read())
+        .and_then(timespec_nanos)
 }
 
 /// Trigger a TRAP_SLEEP longjmp back to `process_task`.
 ///
 /// Sets the sleep duration, restores kernel CR3 and GS bases, then
 /// calls `restore_kernel_context` — never returns.
-fn trap_sleep(ms: u64) -> ! {
+fn trap_sleep(nanos: u64) -> ! {
     use crate::arch::x86_64::registers::control::Cr3;
     use crate::arch::x86_64::registers::model_specific::{IA32_GS_BASE, IA32_KERNEL_GS_BASE};
     use crate::arch::x86_64::userspace::restore_kernel_context;
@@ -102,7 +129,7 @@ fn trap_sleep(ms: u64) -> ! {
         IA32_KERNEL_GS_BASE.write(percpu);
     }
 
-    crate::proc::SleepState::set_ms(ms);
+    crate::proc::SleepState::set_nanos(nanos);
     crate::proc::TrapContext::set_trap_reason(crate::proc::TrapReason::Sleep);
 
     let saved_rsp = crate::proc::TrapContext::saved_kernel_rsp();
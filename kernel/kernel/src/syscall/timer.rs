@@ -0,0 +1,302 @@
+//! Interval timer syscall handlers: `setitimer`/`getitimer`, the POSIX
+//! `timer_*` calls and the `timerfd_*` calls.
+//!
+//! The timers themselves, and how they expire, are in
+//! [`crate::proc::timers`]; timerfds are in [`crate::ipc::timerfd`].
+
+extern crate alloc;
+
+use alloc::sync::Arc;
+
+use crate::fs::file::OpenFlags;
+use crate::id::{Fd, Pid};
+use crate::ipc::timerfd::TimerFd;
+use crate::proc::signal::{Signal, SignalInfo};
+use crate::proc::timers::{IntervalTimer, TimerClock, TimerNotify};
+use crate::proc::{Process, ProcessTable};
+use crate::syscall::userptr::UserPtr;
+use crate::syscall::{
+    CLOCK_MONOTONIC, CLOCK_PROCESS_CPUTIME_ID, CLOCK_REALTIME, CLOCK_THREAD_CPUTIME_ID, EAGAIN,
+    EINVAL, ITimerSpec, SIGALRM, SIGEV_NONE, SIGEV_SIGNAL, SIGEV_THREAD_ID, SigEvent, TFD_CLOEXEC,
+    TFD_NONBLOCK, TFD_TIMER_ABSTIME, TIMER_ABSTIME, Timespec,
+};
+
+/// Converts nanoseconds to a [`Timespec`].
+const fn to_timespec(nanos: u64) -> Timespec {
+    Timespec {
+        tv_sec: nanos / 1_000_000_000,
+        tv_nsec: nanos % 1_000_000_000,
+    }
+}
+
+/// Builds the [`ITimerSpec`] for a time left and interval in nanoseconds.
+const fn to_itimerspec((value, interval): (u64, u64)) -> ITimerSpec {
+    ITimerSpec {
+        interval: to_timespec(interval),
+        value: to_timespec(value),
+    }
+}
+
+/// Reads an [`ITimerSpec`] from user memory as a value and interval in
+/// nanoseconds.
+fn read_itimerspec(ptr: usize) -> Result<(u64, u64), isize> {
+    let spec = UserPtr::<ITimerSpec>::new(ptr).and_then(|p| p.read())?;
+    Ok((
+        super::time::timespec_nanos(spec.value)?,
+        super::time::timespec_nanos(spec.interval)?,
+    ))
+}
+
+/// Writes a setting to the user [`ITimerSpec`] at `ptr`, unless `ptr` is 0.
+fn write_itimerspec(ptr: usize, setting: (u64, u64)) -> isize {
+    if ptr == 0 {
+        return 0;
+    }
+    match UserPtr::<ITimerSpec>::new(ptr).and_then(|p| p.write(to_itimerspec(setting))) {
+        Ok(()) => 0,
+        Err(e) => e,
+    }
+}
+
+/// Arms `timer` of `process` from the user [`ITimerSpec`] at `new_ptr` and
+/// writes the previous setting to `old_ptr`.
+fn set_timer(
+    process: &Process,
+    timer: &Arc<IntervalTimer>,
+    absolute: bool,
+    new_ptr: usize,
+    old_ptr: usize,
+) -> isize {
+    let (value, interval) = match read_itimerspec(new_ptr) {
+        Ok(setting) => setting,
+        Err(e) => return e,
+    };
+    let now = timer.clock().now(process);
+    let expiry = match value {
+        0 => 0,
+        // An absolute time already past expires right away.
+        _ if absolute => value.max(1),
+        _ => now.saturating_add(value),
+    };
+    let old = process.timers.set(timer, expiry, interval, now);
+    write_itimerspec(old_ptr, old)
+}
+
+/// `sys_itimer_set` — arms or disarms `setitimer` timer `which`.
+pub(super) fn sys_itimer_set(which: usize, new_ptr: usize, old_ptr: usize) -> isize {
+    let process = ProcessTable::with_current(Arc::clone);
+    let Some(timer) = process.timers.itimer(which) else {
+        return -EINVAL;
+    };
+    set_timer(&process, timer, false, new_ptr, old_ptr)
+}
+
+/// `sys_itimer_get` — writes the setting of `setitimer` timer `which`.
+pub(super) fn sys_itimer_get(which: usize, cur_ptr: usize) -> isize {
+    let process = ProcessTable::with_current(Arc::clone);
+    let Some(timer) = process.timers.itimer(which) else {
+        return -EINVAL;
+    };
+    let setting = timer.get(timer.clock().now(&process));
+    write_itimerspec(cur_ptr, setting)
+}
+
+/// Reads the user [`SigEvent`] at `sevp_ptr` (0 for the default,
+/// `SIGALRM` to the process) and returns how to build the notification of
+/// the new timer from its ID.
+fn read_notify(
+    process: &Process,
+    sevp_ptr: usize,
+) -> Result<impl FnOnce(u32) -> TimerNotify, isize> {
+    // Process-directed signals go to the thread that created the process,
+    // as for `setitimer`. The default signal value is the timer ID.
+    let leader = process.timers.leader();
+    let (target, signo, value) = if sevp_ptr == 0 {
+        (Some(leader), SIGALRM, None)
+    } else {
+        let event = UserPtr::<SigEvent>::new(sevp_ptr).and_then(|p| p.read())?;
+        let signo = usize::try_from(event.signo).map_err(|_| -EINVAL)?;
+        let target = match event.notify {
+            SIGEV_NONE => None,
+            SIGEV_SIGNAL => Some(leader),
+            SIGEV_THREAD_ID => {
+                // The thread must belong to the calling process.
+                let tid = Pid::new(event.tid);
+                let thread = ProcessTable::lookup(tid).ok_or(-EINVAL)?;
+                if !Arc::ptr_eq(&thread.timers, &process.timers) {
+                    return Err(-EINVAL);
+                }
+                Some(tid)
+            }
+            _ => return Err(-EINVAL),
+        };
+        if target.is_some() && !Signal::is_valid(signo) {
+            return Err(-EINVAL);
+        }
+        (target, signo, Some(event.value))
+    };
+    Ok(move |id| match target {
+        Some(target) => TimerNotify::Signal {
+            target,
+            info: SignalInfo::timer(signo, value.unwrap_or(u64::from(id))),
+        },
+        None => TimerNotify::None,
+    })
+}
+
+/// `sys_timer_create` — creates a POSIX timer on `clock_id`.
+pub(super) fn sys_timer_create(clock_id: usize, sevp_ptr: usize, id_ptr: usize) -> isize {
+    let process = ProcessTable::with_current(Arc::clone);
+    let clock = match clock_id {
+        CLOCK_MONOTONIC => TimerClock::Monotonic,
+        CLOCK_REALTIME => TimerClock::Realtime,
+        CLOCK_PROCESS_CPUTIME_ID => TimerClock::ProcessCpu,
+        CLOCK_THREAD_CPUTIME_ID => TimerClock::ThreadCpu(process.pid),
+        _ => return -EINVAL,
+    };
+    let notify = match read_notify(&process, sevp_ptr) {
+        Ok(notify) => notify,
+        Err(e) => return e,
+    };
+    let id_out = match UserPtr::<u32>::new(id_ptr) {
+        Ok(p) => p,
+        Err(e) => return e,
+    };
+    let Some(id) = process.timers.create(clock, notify) else {
+        return -EAGAIN;
+    };
+
+    if let Err(e) = id_out.write(id) {
+        process.timers.delete(id);
+        return e;
+    }
+    0
+}
+
+/// Looks up POSIX timer `id` of the calling process.
+fn lookup_timer(id: usize) -> Result<(Arc<Process>, Arc<IntervalTimer>), isize> {
+    let process = ProcessTable::with_current(Arc::clone);
+    let timer = u32::try_from(id)
+        .ok()
+        .and_then(|id| process.timers.get(id))
+        .ok_or(-EINVAL)?;
+    Ok((process, timer))
+}
+
+/// `sys_timer_settime` — arms or disarms POSIX timer `id`.
+pub(super) fn sys_timer_settime(id: usize, flags: usize, new_ptr: usize, old_ptr: usize) -> isize {
+    if flags & !TIMER_ABSTIME != 0 {
+        return -EINVAL;
+    }
+    match lookup_timer(id) {
+        Ok((process, timer)) => set_timer(
+            &process,
+            &timer,
+            flags & TIMER_ABSTIME != 0,
+            new_ptr,
+            old_ptr,
+        ),
+        Err(e) => e,
+    }
+}
+
+/// `sys_timer_gettime` — writes the time left and interval of POSIX timer
+/// `id`.
+pub(super) fn sys_timer_gettime(id: usize, cur_ptr: usize) -> isize {
+    match lookup_timer(id) {
+        Ok((process, timer)) => {
+            let setting = timer.get(timer.clock().now(&process));
+            match UserPtr::<ITimerSpec>::new(cur_ptr).and_then(|p| p.write(to_itimerspec(setting)))
+            {
+                Ok(()) => 0,
+                Err(e) => e,
+            }
+        }
+        Err(e) => e,
+    }
+}
+
+/// `sys_timer_delete` — disarms and deletes POSIX timer `id`.
+pub(super) fn sys_timer_delete(id: usize) -> isize {
+    let timers = ProcessTable::with_current(|p| Arc::clone(&p.timers));
+    match u32::try_from(id) {
+        Ok(id) if timers.delete(id) => 0,
+        _ => -EINVAL,
+    }
+}
+
+/// `sys_timer_getoverrun` — returns the overrun count of POSIX timer `id`,
+/// capped at `i32::MAX` (`DELAYTIMER_MAX`).
+pub(super) fn sys_timer_getoverrun(id: usize) -> isize {
+    match lookup_timer(id) {
+        Ok((_, timer)) => i32::try_from(timer.overrun()).unwrap_or(i32::MAX) as isize,
+        Err(e) => e,
+    }
+}
+
+/// `sys_timerfd_create` — creates a timerfd on `clock_id`.
+pub(super) fn sys_timerfd_create(clock_id: usize, flags: usize) -> isize {
+    let clock = match clock_id {
+        CLOCK_MONOTONIC => TimerClock::Monotonic,
+        CLOCK_REALTIME => TimerClock::Realtime,
+        _ => return -EINVAL,
+    };
+    if flags & !(TFD_CLOEXEC | TFD_NONBLOCK) != 0 {
+        return -EINVAL;
+    }
+    super::vfs::open_anon(Arc::new(TimerFd::new(clock)), OpenFlags::READ, flags)
+}
+
+/// Runs `f` on timerfd `fd` of the calling process.
+fn with_timerfd(fd: usize, f: impl FnOnce(&TimerFd) -> isize) -> isize {
+    let Ok(fd) = u32::try_from(fd) else {
+        return -crate::syscall::EBADF;
+    };
+    let inode = match super::vfs::fd_inode(Fd::new(fd)) {
+        Ok(inode) => inode,
+        Err(e) => return e,
+    };
+    match inode.as_any().and_then(|any| any.downcast_ref::<TimerFd>()) {
+        Some(timerfd) => f(timerfd),
+        None => -EINVAL,
+    }
+}
+
+/// `sys_timerfd_settime` — arms or disarms timerfd `fd`.
+pub(super) fn sys_timerfd_settime(
+    fd: usize,
+    flags: usize,
+    new_ptr: usize,
+    old_ptr: usize,
+) -> isize {
+    if flags & !TFD_TIMER_ABSTIME != 0 {
+        return -EINVAL;
+    }
+    let (value, interval) = match read_itimerspec(new_ptr) {
+        Ok(setting) => setting,
+        Err(e) => return e,
+    };
+    with_timerfd(fd, |timerfd| {
+        let expiry = match value {
+            0 => 0,
+            _ if flags & TFD_TIMER_ABSTIME != 0 => value.max(1),
+            _ => timerfd.now().saturating_add(value),
+        };
+        let old = timerfd.set(expiry, interval);
+        write_itimerspec(old_ptr, old)
+    })
+}
+
+/// `sys_timerfd_gettime` — writes the time left and interval of timerfd
+/// `fd`.
+pub(super) fn sys_timerfd_gettime(fd: usize, cur_ptr: usize) -> isize {
+    let Ok(cur) = UserPtr::<ITimerSpec>::new(cur_ptr) else {
+        return -crate::syscall::EFAULT;
+    };
+    with_timerfd(fd, |timerfd| {
+        match cur.write(to_itimerspec(timerfd.get())) {
+            Ok(()) => 0,
+            Err(e) => e,
+        }
+    })
+}
@@ -3,16 +3,23 @@
 //! Provides [`UserPtr`] and [`UserSlice`] types that validate pointers passed
 //! from user space before dereferencing, preventing the kernel from blindly
 //! trusting user-supplied addresses.
+//!
+//! The kernel never dereferences a validated address directly. All access
+//! goes through the fault-safe copy routines in
+//! [`crate::arch::copy_from_user`] / [`crate::arch::copy_to_user`], which are
+//! the only code allowed to touch user pages under SMAP and which turn a
+//! fault on an unmapped page into `EFAULT`.
 
+use alloc::vec::Vec;
 use core::marker::PhantomData;
 
-use super::EFAULT;
+use super::{EFAULT, ENOMEM};
 
 /// Upper bound of canonical user-space addresses on x86_64.
 ///
 /// Addresses with bit 63 set are kernel addresses (upper-half). User
 /// pointers must be below this boundary.
-const USER_ADDR_MAX: usize = 0x0000_8000_0000_0000;
+pub(crate) const USER_ADDR_MAX: usize = 0x0000_8000_0000_0000;
 
 /// A validated pointer to user-space memory of type `T`.
 ///
@@ -22,8 +29,9 @@ const USER_ADDR_MAX: usize = 0x0000_8000_0000_0000;
 /// - Does not overflow when combined with `size_of::<T>()`
 ///
 /// This type does **not** guarantee that the memory is mapped or readable;
-/// page faults can still occur. It only ensures the address is in the user
-/// half of the address space.
+/// [`read`](Self::read) and [`write`](Self::write) return `EFAULT` if it is
+/// not. It only ensures the address is in the user half of the address
+/// space.
 #[derive(Debug, Clone, Copy)]
 pub struct UserPtr<T> {
     addr: usize,
@@ -62,33 +70,37 @@ impl<T> UserPtr<T> {
     pub fn addr(&self) -> usize {
         self.addr
     }
+}
 
-    /// Dereference the validated pointer.
+impl<T: Copy> UserPtr<T> {
+    /// Copy the `T` at this address into the kernel.
     ///
-    /// # Safety
+    /// `T` must be a plain-data ABI type for which every bit pattern is a
+    /// valid value (integers and `repr(C)` structs of them).
     ///
-    /// The caller must ensure:
-    /// - The memory at this address is mapped and readable
-    /// - The memory contains a valid `T`
-    /// - No mutable aliases exist
-    pub unsafe fn as_ref(&self) -> &T {
-        unsafe { &*(self.addr as *const T) }
+    /// Returns `Err(-EFAULT)` if the memory is not mapped readable.
+    pub fn read(&self) -> Result<T, isize> {
+        let mut value = core::mem::MaybeUninit::<T>::zeroed();
+        // SAFETY: The zeroed MaybeUninit is size_of::<T>() initialized bytes.
+        let dst = unsafe {
+            core::slice::from_raw_parts_mut(value.as_mut_ptr().cast::<u8>(), size_of::<T>())
+        };
+        // SAFETY: The address range was validated in `new()`.
+        unsafe { crate::arch::copy_from_user(dst, self.addr) }.map_err(|_| -EFAULT)?;
+        // SAFETY: Every bit pattern is a valid `T` (see above).
+        Ok(unsafe { value.assume_init() })
     }
 
-    /// Write a value to the validated user-space address.
-    ///
-    /// Uses `write_volatile` to ensure the write is not elided. The address
-    /// has already been validated (user-space, aligned, no overflow) during
-    /// construction.
+    /// Copy `value` to this user-space address.
     ///
-    /// # Safety
-    ///
-    /// The caller must ensure:
-    /// - The memory at this address is mapped and writable
-    /// - No concurrent reads or writes that would cause a data race
-    pub unsafe fn write(&self, value: T) {
-        // SAFETY: Address was validated in `new()` as aligned and in user space.
-        unsafe { core::ptr::write_volatile(self.addr as *mut T, value) }
+    /// Returns `Err(-EFAULT)` if the memory is not mapped writable.
+    pub fn write(&self, value: T) -> Result<(), isize> {
+        // SAFETY: `value` lives on the stack for the duration of the copy.
+        let src = unsafe {
+            core::slice::from_raw_parts(core::ptr::addr_of!(value).cast::<u8>(), size_of::<T>())
+        };
+        // SAFETY: The address range was validated in `new()`.
+        unsafe { crate::arch::copy_to_user(self.addr, src) }.map_err(|_| -EFAULT)
     }
 }
 
@@ -120,30 +132,49 @@ impl UserSlice {
         Ok(Self { addr, len })
     }
 
-    /// Convert the validated range to a byte slice.
-    ///
-    /// # Safety
+    /// Copy the first `dst.len()` bytes of the range into `dst`.
     ///
-    /// The caller must ensure the memory is mapped, readable, and not
-    /// mutably aliased.
-    pub unsafe fn as_slice(&self) -> &[u8] {
-        if self.len == 0 {
-            return &[];
+    /// Returns `Err(-EFAULT)` if `dst` is longer than the range or the
+    /// memory is not mapped readable.
+    pub fn read_into(&self, dst: &mut [u8]) -> Result<(), isize> {
+        if dst.len() > self.len {
+            return Err(-EFAULT);
         }
-        unsafe { core::slice::from_raw_parts(self.addr as *const u8, self.len) }
+        // SAFETY: The address range was validated in `new()`.
+        unsafe { crate::arch::copy_from_user(dst, self.addr) }.map_err(|_| -EFAULT)
     }
 
-    /// Convert the validated range to a mutable byte slice.
+    /// Copy the whole range into a newly allocated kernel buffer.
     ///
-    /// # Safety
+    /// Returns `Err(-ENOMEM)` if the buffer cannot be allocated, or
+    /// `Err(-EFAULT)` if the memory is not mapped readable.
+    pub fn read_to_vec(&self) -> Result<Vec<u8>, isize> {
+        let mut buf = self.kernel_buffer()?;
+        self.read_into(&mut buf)?;
+        Ok(buf)
+    }
+
+    /// Copy `src` to the start of the range.
     ///
-    /// The caller must ensure the memory is mapped, writable, and not
-    /// aliased.
-    pub unsafe fn as_mut_slice(&self) -> &mut [u8] {
-        if self.len == 0 {
-            return &mut [];
+    /// Returns `Err(-EFAULT)` if `src` is longer than the range or the
+    /// memory is not mapped writable.
+    pub fn write_from(&self, src: &[u8]) -> Result<(), isize> {
+        if src.len() > self.len {
+            return Err(-EFAULT);
         }
-        unsafe { core::slice::from_raw_parts_mut(self.addr as *mut u8, self.len) }
+        // SAFETY: The address range was validated in `new()`.
+        unsafe { crate::arch::copy_to_user(self.addr, src) }.map_err(|_| -EFAULT)
+    }
+
+    /// Allocate a zeroed kernel buffer as long as the range, to stage data
+    /// that is later copied out with [`write_from`](Self::write_from).
+    ///
+    /// Returns `Err(-ENOMEM)` if the buffer cannot be allocated.
+    pub fn kernel_buffer(&self) -> Result<Vec<u8>, isize> {
+        let mut buf = Vec::new();
+        buf.try_reserve_exact(self.len).map_err(|_| -ENOMEM)?;
+        buf.resize(self.len, 0);
+        Ok(buf)
     }
 
     /// Returns the raw address.
@@ -162,6 +193,51 @@ impl UserSlice {
     }
 }
 
+/// Copy `count` consecutive `T` values starting at user address `addr`
+/// into a kernel vector.
+///
+/// `T` must be a plain-data ABI type, as for [`UserPtr::read`]. Returns
+/// `Err(-EFAULT)` if the array is misaligned, leaves user space, or is not
+/// mapped readable, and `Err(-ENOMEM)` if the vector cannot be allocated.
+pub fn read_user_array<T: Copy>(addr: usize, count: usize) -> Result<Vec<T>, isize> {
+    let slice = array_slice::<T>(addr, count)?;
+    let mut values = Vec::<T>::new();
+    values.try_reserve_exact(count).map_err(|_| -ENOMEM)?;
+    // SAFETY: The capacity holds `slice.len()` bytes; they are zeroed before
+    // being viewed as a byte slice.
+    let dst = unsafe {
+        let ptr = values.as_mut_ptr().cast::<u8>();
+        core::ptr::write_bytes(ptr, 0, slice.len());
+        core::slice::from_raw_parts_mut(ptr, slice.len())
+    };
+    slice.read_into(dst)?;
+    // SAFETY: All `count` elements were initialized by the copy, and every
+    // bit pattern is a valid `T`.
+    unsafe { values.set_len(count) };
+    Ok(values)
+}
+
+/// Copy `values` to consecutive `T` slots starting at user address `addr`.
+///
+/// Returns `Err(-EFAULT)` if the array is misaligned, leaves user space, or
+/// is not mapped writable.
+pub fn write_user_array<T: Copy>(addr: usize, values: &[T]) -> Result<(), isize> {
+    let slice = array_slice::<T>(addr, values.len())?;
+    // SAFETY: `values` is a live slice of `slice.len()` bytes.
+    let src = unsafe { core::slice::from_raw_parts(values.as_ptr().cast::<u8>(), slice.len()) };
+    slice.write_from(src)
+}
+
+/// Validates a user array of `count` values of `T` at `addr`.
+fn array_slice<T>(addr: usize, count: usize) -> Result<UserSlice, isize> {
+    let align = core::mem::align_of::<T>();
+    if align > 1 && addr % align != 0 {
+        return Err(-EFAULT);
+    }
+    let len = count.checked_mul(size_of::<T>()).ok_or(-EFAULT)?;
+    UserSlice::new(addr, len)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -220,30 +296,48 @@ mod tests {
     }
 
     #[test]
-    fn is_kernel_caller_low() {
-        assert!(!is_kernel_caller(0x1000));
-        assert!(!is_kernel_caller(USER_ADDR_MAX - 1));
+    fn user_return_rip_is_user_mode() {
+        assert!(!returns_to_kernel(0x1000));
+        assert!(!returns_to_kernel(USER_ADDR_MAX - 1));
     }
 
     #[test]
-    fn is_kernel_caller_high() {
-        assert!(is_kernel_caller(USER_ADDR_MAX));
-        assert!(is_kernel_caller(usize::MAX));
+    fn kernel_return_rip_is_kernel_mode() {
+        assert!(returns_to_kernel(0xFFFF_8000_0000_0000));
+        assert!(returns_to_kernel(usize::MAX));
     }
 }
 
-/// Check whether we are currently handling a kernel-mode syscall test.
+/// Returns `true` if the syscall being handled on this CPU was issued from
+/// ring 0, i.e. by a kernel-mode syscall test.
 ///
 /// During early boot testing (before userspace exists), syscalls are invoked
-/// from kernel space where addresses have bit 63 set. In that context,
-/// `UserPtr` / `UserSlice` validation would reject every pointer. Callers
-/// can use this function to skip validation when the saved return RIP
-/// indicates a kernel-mode caller.
+/// from kernel space with kernel buffers, which `UserPtr` / `UserSlice`
+/// validation would reject. Callers use this function to access those
+/// buffers directly instead.
 ///
-/// # Safety
+/// The decision comes from the syscall frame, never from a pointer
+/// argument: a ring-3 caller passing a kernel address still goes through
+/// `UserPtr` / `UserSlice` and gets `EFAULT`. `SYSCALL` does not save the
+/// caller's CS, so the mode is taken from the return RIP the entry stub
+/// saved, exactly as its exit path chooses between `sysretq` and `iretq`.
+///
+/// Must only be called from a syscall handler, before it blocks.
+pub fn is_kernel_caller() -> bool {
+    let regs = crate::arch::x86_64::syscall::SYSCALL_SAVED_REGS.get();
+    // SAFETY: The entry stub wrote this CPU's saved registers when the
+    // current syscall was issued; nothing else writes them until the next
+    // syscall on this CPU.
+    #[expect(clippy::cast_possible_truncation, reason = "x86_64 only")]
+    let return_rip = unsafe { (*regs.get()).user_rip } as usize;
+    returns_to_kernel(return_rip)
+}
+
+/// Returns `true` if a syscall returning to `return_rip` was issued from
+/// ring 0.
 ///
-/// This must only be used in the syscall dispatch path and never to bypass
-/// security checks for actual user-space callers.
-pub fn is_kernel_caller(saved_rip: usize) -> bool {
-    saved_rip >= USER_ADDR_MAX
+/// Ring-3 code can only execute from lower-half pages, so an upper-half
+/// return address means the caller ran in ring 0.
+fn returns_to_kernel(return_rip: usize) -> bool {
+    return_rip >= USER_ADDR_MAX
 }
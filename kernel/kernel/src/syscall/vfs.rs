@@ -1,19 +1,25 @@
 //! VFS syscall handlers: open, read, write, close, stat, readdir, dup, seek,
 //! mkdir, unlink, and related operations.
+//!
+//! Path-based operations check the caller's credentials against the mode
+//! bits of the inodes they touch: opening needs read or write access to the
+//! file, and adding or removing a directory entry needs write and search
+//! access to the directory. Path walks themselves are not checked.
 
+use crate::cred::{Access, Credentials};
 use crate::id::Fd;
-use crate::syscall::EFAULT;
-use crate::syscall::userptr::UserSlice;
+use crate::syscall::userptr::{UserPtr, UserSlice};
+use crate::syscall::{EACCES, EFAULT};
 
 use alloc::sync::Arc;
 
 use crate::fs::file::OpenFlags;
-use crate::fs::{Inode, poll_immediate, try_poll_immediate};
+use crate::fs::{Inode, Ownership, Permissions, poll_immediate, try_poll_immediate};
 
 // ── Shared helpers ──────────────────────────────────────────────────────
 
 /// Look up an fd and clone its inode, returning `-EBADF` on failure.
-fn fd_inode(fd: Fd) -> Result<Arc<dyn Inode>, isize> {
+pub(super) fn fd_inode(fd: Fd) -> Result<Arc<dyn Inode>, isize> {
     crate::proc::ProcessTable::with_current(|process| {
         let fd_table = process.fd_table.lock();
         let Some(file) = fd_table.get(fd) else {
@@ -23,6 +29,24 @@ fn fd_inode(fd: Fd) -> Result<Arc<dyn Inode>, isize> {
     })
 }
 
+/// Open `inode` in the current process with `access`, adding `CLOEXEC`
+/// and `NONBLOCK` for the `*_CLOEXEC` (0x20) and `*_NONBLOCK` (0x40) bits of
+/// `flags`, as `pipe2` does. Returns the new fd.
+#[expect(clippy::cast_possible_wrap, reason = "fd numbers are small")]
+pub(super) fn open_anon(inode: Arc<dyn Inode>, access: OpenFlags, flags: usize) -> isize {
+    let mut open_flags = access;
+    if flags & crate::syscall::PIPE_CLOEXEC != 0 {
+        open_flags |= OpenFlags::CLOEXEC;
+    }
+    if flags & crate::syscall::PIPE_NONBLOCK != 0 {
+        open_flags |= OpenFlags::NONBLOCK;
+    }
+    let fd = crate::proc::ProcessTable::with_current(|process| {
+        process.fd_table.lock().open(inode, open_flags)
+    });
+    fd.as_usize() as isize
+}
+
 /// Look up an fd, verify `required_flags`, and return (inode, offset).
 ///
 /// Returns `-EBADF` if the fd is invalid or the required flags are not set.
@@ -55,6 +79,71 @@ pub(super) fn resolve_cwd_path(path: &str) -> alloc::string::String {
     }
 }
 
+/// Permission bits cleared on files and directories created through the
+/// syscall layer: a fixed `umask` of `022`.
+const CREATE_UMASK: u16 = 0o022;
+
+/// Returns the calling process's credentials.
+fn current_cred() -> Arc<Credentials> {
+    crate::proc::ProcessTable::with_current(|p| p.cred())
+}
+
+/// Checks that `cred` grants `access` to `inode`, returning `-EACCES` if not.
+fn check_access(cred: &Credentials, inode: &dyn Inode, access: Access) -> Result<(), isize> {
+    let own = inode.ownership();
+    if cred.may_access(own.uid, own.gid, own.mode, access) {
+        Ok(())
+    } else {
+        Err(-EACCES)
+    }
+}
+
+/// Resolves the directory at `path` in order to add or remove entries,
+/// which needs write and search access to it.
+fn resolve_parent_for_write(path: &str, cred: &Credentials) -> Result<Arc<dyn Inode>, isize> {
+    let parent = crate::fs::vfs::with_vfs(|vfs| vfs.resolve(path)).map_err(|e| -e.to_errno())?;
+    check_access(cred, &*parent, Access::WRITE | Access::EXECUTE)?;
+    Ok(parent)
+}
+
+/// Checks that `cred` may remove the entry `name` from `parent`, which
+/// must already have passed [`resolve_parent_for_write`]. Only adds the
+/// sticky-directory rule; fails with `-EPERM`.
+fn check_delete(cred: &Credentials, parent: &dyn Inode, name: &str) -> Result<(), isize> {
+    let dir = parent.ownership();
+    let entry = poll_immediate(parent.lookup(name)).map_err(|e| -e.to_errno())?;
+    if cred.may_delete(dir.uid, dir.mode, entry.ownership().uid) {
+        Ok(())
+    } else {
+        Err(-crate::syscall::EPERM)
+    }
+}
+
+/// Hands a newly created inode to its creator: owned by the effective user
+/// and group, with `perms` granted to every class less [`CREATE_UMASK`].
+/// Filesystems that do not track ownership keep their defaults.
+fn set_creator(inode: &dyn Inode, cred: &Credentials, perms: Permissions) {
+    let ownership = Ownership::new(cred.euid(), cred.egid(), perms.mode() & !CREATE_UMASK);
+    let _ = inode.set_ownership(ownership);
+}
+
+/// Creates the regular file at absolute `path` for `open(O_CREAT)`.
+fn create_file(path: &str, cred: &Credentials) -> Result<Arc<dyn Inode>, isize> {
+    let (parent_path, name) = match path.rsplit_once('/') {
+        Some((p, n)) => (if p.is_empty() { "/" } else { p }, n),
+        None => return Err(-crate::syscall::ENOENT),
+    };
+    if name.is_empty() {
+        return Err(-crate::syscall::EISDIR);
+    }
+    let parent = resolve_parent_for_write(parent_path, cred)?;
+    let perms = Permissions::read_write();
+    let inode = poll_immediate(parent.create(name, crate::fs::InodeType::File, perms))
+        .map_err(|e| -e.to_errno())?;
+    set_creator(&*inode, cred, perms);
+    Ok(inode)
+}
+
 /// `sys_vnode_open` — open a file by path, returning a file descriptor.
 ///
 /// Arguments:
@@ -62,6 +151,10 @@ pub(super) fn resolve_cwd_path(path: &str) -> alloc::string::String {
 /// - `path_len`: length of the path string
 /// - `flags`: open flags (bitwise OR of `OpenFlags` values)
 ///
+/// Reading needs read access; writing or truncating needs write access.
+/// With `CREATE`, a missing file is created with mode `0644`, owned by the
+/// caller; `EXCL` then fails if it already exists.
+///
 /// Returns a non-negative fd on success, or a negative errno on failure.
 #[expect(
     clippy::cast_possible_wrap,
@@ -72,18 +165,43 @@ pub(super) fn sys_vnode_open(path_ptr: usize, path_len: usize, flags: usize) ->
         return -EFAULT;
     };
 
-    // SAFETY: UserSlice validated that [path_ptr, path_ptr+path_len) is in user space.
-    let path_bytes = unsafe { user_slice.as_slice() };
-    let Ok(path) = core::str::from_utf8(path_bytes) else {
+    let path_bytes = match user_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
+    let Ok(path) = core::str::from_utf8(&path_bytes) else {
         return -crate::syscall::EINVAL;
     };
 
     #[expect(clippy::cast_possible_truncation, reason = "open flags fit in u32")]
     let open_flags = OpenFlags::from_bits_truncate(flags as u32);
 
-    // Resolve path via VFS.
-    let Ok(inode) = crate::fs::vfs::with_vfs(|vfs| vfs.resolve(path)) else {
-        return -crate::syscall::ENOENT;
+    // Resolve path via VFS, creating the file if asked to.
+    let cred = current_cred();
+    let inode = match crate::fs::vfs::with_vfs(|vfs| vfs.resolve(path)) {
+        Ok(inode) => {
+            if open_flags.contains(OpenFlags::CREATE | OpenFlags::EXCL) {
+                return -crate::syscall::EEXIST;
+            }
+            let mut access = Access::empty();
+            if open_flags.contains(OpenFlags::READ) {
+                access |= Access::READ;
+            }
+            if open_flags.intersects(OpenFlags::WRITE | OpenFlags::TRUNCATE) {
+                access |= Access::WRITE;
+            }
+            if let Err(e) = check_access(&cred, &*inode, access) {
+                return e;
+            }
+            inode
+        }
+        Err(crate::fs::FsError::NotFound) if open_flags.contains(OpenFlags::CREATE) => {
+            match create_file(path, &cred) {
+                Ok(inode) => inode,
+                Err(e) => return e,
+            }
+        }
+        Err(_) => return -crate::syscall::ENOENT,
     };
 
     // Check if the inode wants to substitute a different inode on open
@@ -123,8 +241,10 @@ pub(super) fn sys_vnode_read(fd: usize, buf_ptr: usize, buf_len: usize) -> isize
         return -EFAULT;
     };
 
-    // SAFETY: UserSlice validated that [buf_ptr, buf_ptr+buf_len) is in user space.
-    let buf = unsafe { user_slice.as_mut_slice() };
+    let mut buf = match user_slice.kernel_buffer() {
+        Ok(buf) => buf,
+        Err(e) => return e,
+    };
 
     // Extract inode and offset, then release the process lock before I/O.
     // trap_io() does a longjmp and must never run while holding a spinlock.
@@ -133,8 +253,11 @@ pub(super) fn sys_vnode_read(fd: usize, buf_ptr: usize, buf_len: usize) -> isize
         Err(e) => return e,
     };
 
-    match try_poll_immediate(inode.read(offset, buf)) {
+    match try_poll_immediate(inode.read(offset, &mut buf)) {
         Some(Ok(n)) => {
+            if let Err(e) = user_slice.write_from(&buf[..n]) {
+                return e;
+            }
             // Re-acquire to update offset.
             crate::proc::ProcessTable::with_current(|process| {
                 let mut fd_table = process.fd_table.lock();
@@ -176,8 +299,10 @@ pub(super) fn sys_vnode_write(fd: usize, buf_ptr: usize, buf_len: usize) -> isiz
         return -EFAULT;
     };
 
-    // SAFETY: UserSlice validated that [buf_ptr, buf_ptr+buf_len) is in user space.
-    let buf = unsafe { user_slice.as_slice() };
+    let buf = match user_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
 
     // Extract inode and offset, then release the process lock before I/O.
     // trap_io() does a longjmp and must never run while holding a spinlock.
@@ -186,7 +311,7 @@ pub(super) fn sys_vnode_write(fd: usize, buf_ptr: usize, buf_len: usize) -> isiz
         Err(e) => return e,
     };
 
-    match try_poll_immediate(inode.write(offset, buf)) {
+    match try_poll_immediate(inode.write(offset, &buf)) {
         Some(Ok(n)) => {
             // Re-acquire to update offset.
             crate::proc::ProcessTable::with_current(|process| {
@@ -332,23 +457,25 @@ pub(super) fn sys_vnode_stat(fd: usize, buf_ptr: usize, buf_len: usize) -> isize
     let permissions: u32 =
         u32::from(perms.read) | (u32::from(perms.write) << 1) | (u32::from(perms.execute) << 2);
 
+    let own = inode.ownership();
     let info = StatInfo {
         inode_type,
         _pad: [0; 7],
         size: inode.size() as u64,
         permissions,
-        _pad2: 0,
+        mode: u32::from(own.mode),
         rdev: inode.dev_number().0,
+        uid: own.uid.as_u32(),
+        gid: own.gid.as_u32(),
     };
 
-    // SAFETY: UserSlice validated the pointer range is in user space,
-    // and we write exactly stat_size bytes.
-    let out = unsafe { user_slice.as_mut_slice() };
     // SAFETY: StatInfo is repr(C) and contains only scalar fields.
     let info_bytes =
         unsafe { core::slice::from_raw_parts(core::ptr::addr_of!(info).cast::<u8>(), stat_size) };
-    out[..stat_size].copy_from_slice(info_bytes);
-    0
+    match user_slice.write_from(info_bytes) {
+        Ok(()) => 0,
+        Err(e) => e,
+    }
 }
 
 /// `sys_handle_pipe` — create a pipe and return [read_fd, write_fd].
@@ -362,7 +489,7 @@ pub(super) fn sys_vnode_stat(fd: usize, buf_ptr: usize, buf_len: usize) -> isize
     reason = "fd numbers are small, wrap is impossible"
 )]
 pub(super) fn sys_handle_pipe(fds_ptr: usize) -> isize {
-    let Ok(user_slice) = UserSlice::new(fds_ptr, 2 * core::mem::size_of::<usize>()) else {
+    let Ok(user_fds) = UserPtr::<[usize; 2]>::new(fds_ptr) else {
         return -EFAULT;
     };
 
@@ -375,15 +502,11 @@ pub(super) fn sys_handle_pipe(fds_ptr: usize) -> isize {
         (rfd, wfd)
     });
 
-    // SAFETY: UserSlice validated the pointer range is in user space.
     // The ABI returns fd numbers as usize values to userspace.
-    unsafe {
-        let dst = user_slice.addr() as *mut usize;
-        core::ptr::write(dst, read_fd.as_usize());
-        core::ptr::write(dst.add(1), write_fd.as_usize());
+    match user_fds.write([read_fd.as_usize(), write_fd.as_usize()]) {
+        Ok(()) => 0,
+        Err(e) => e,
     }
-
-    0
 }
 
 /// `sys_vnode_readdir` — read directory entries.
@@ -423,8 +546,10 @@ pub(super) fn sys_vnode_readdir(fd: usize, buf_ptr: usize, buf_len: usize) -> is
         Err(e) => return -e.to_errno(),
     };
 
-    // SAFETY: UserSlice validated the pointer range is in user space.
-    let out = unsafe { user_slice.as_mut_slice() };
+    let mut out = match user_slice.kernel_buffer() {
+        Ok(buf) => buf,
+        Err(e) => return e,
+    };
     let mut written = 0;
 
     for entry in &entries {
@@ -461,6 +586,9 @@ pub(super) fn sys_vnode_readdir(fd: usize, buf_ptr: usize, buf_len: usize) -> is
         written += 1;
     }
 
+    if let Err(e) = user_slice.write_from(&out[..written * entry_size]) {
+        return e;
+    }
     written as isize
 }
 
@@ -470,15 +598,20 @@ pub(super) fn sys_vnode_readdir(fd: usize, buf_ptr: usize, buf_len: usize) -> is
 /// - `path_ptr`: user-space pointer to the path string
 /// - `path_len`: length of the path string
 ///
+/// Needs write and search access to the parent directory; in a sticky
+/// directory, the caller must also own the entry or the directory.
+///
 /// Returns 0 on success, or a negative errno on failure.
 pub(super) fn sys_vnode_unlink(path_ptr: usize, path_len: usize) -> isize {
     let Ok(user_slice) = UserSlice::new(path_ptr, path_len) else {
         return -EFAULT;
     };
 
-    // SAFETY: UserSlice validated that [path_ptr, path_ptr+path_len) is in user space.
-    let path_bytes = unsafe { user_slice.as_slice() };
-    let Ok(path) = core::str::from_utf8(path_bytes) else {
+    let path_bytes = match user_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
+    let Ok(path) = core::str::from_utf8(&path_bytes) else {
         return -crate::syscall::EINVAL;
     };
 
@@ -495,11 +628,15 @@ pub(super) fn sys_vnode_unlink(path_ptr: usize, path_len: usize) -> isize {
         return -crate::syscall::EINVAL;
     }
 
-    // Resolve the parent directory.
-    let parent_inode = match crate::fs::vfs::with_vfs(|vfs| vfs.resolve(parent_path)) {
+    // Resolve the parent directory and check that the entry may go.
+    let cred = current_cred();
+    let parent_inode = match resolve_parent_for_write(parent_path, &cred) {
         Ok(inode) => inode,
-        Err(e) => return -e.to_errno(),
+        Err(e) => return e,
     };
+    if let Err(e) = check_delete(&cred, &*parent_inode, name) {
+        return e;
+    }
 
     // Call unlink on the parent.
     match crate::fs::poll_immediate(parent_inode.unlink(name)) {
@@ -563,15 +700,21 @@ pub(super) fn sys_vnode_seek(fd: usize, offset: usize, whence: usize) -> isize {
 /// - `path_len`: length of the path string
 /// - `permissions`: permission bitmask (bit 0=read, 1=write, 2=exec)
 ///
+/// Needs write and search access to the parent directory. The new
+/// directory is owned by the caller; `permissions` apply to every class,
+/// less write access for group and others.
+///
 /// Returns 0 on success, or a negative errno on failure.
 pub(super) fn sys_vnode_mkdir(path_ptr: usize, path_len: usize, permissions: usize) -> isize {
     let Ok(user_slice) = UserSlice::new(path_ptr, path_len) else {
         return -EFAULT;
     };
 
-    // SAFETY: UserSlice validated that [path_ptr, path_ptr+path_len) is in user space.
-    let path_bytes = unsafe { user_slice.as_slice() };
-    let Ok(path) = core::str::from_utf8(path_bytes) else {
+    let path_bytes = match user_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
+    let Ok(path) = core::str::from_utf8(&path_bytes) else {
         return -crate::syscall::EINVAL;
     };
 
@@ -595,9 +738,10 @@ pub(super) fn sys_vnode_mkdir(path_ptr: usize, path_len: usize, permissions: usi
     };
 
     // Resolve the parent directory.
-    let parent_inode = match crate::fs::vfs::with_vfs(|vfs| vfs.resolve(parent_path)) {
+    let cred = current_cred();
+    let parent_inode = match resolve_parent_for_write(parent_path, &cred) {
         Ok(inode) => inode,
-        Err(e) => return -e.to_errno(),
+        Err(e) => return e,
     };
 
     // Create the directory entry.
@@ -606,7 +750,10 @@ pub(super) fn sys_vnode_mkdir(path_ptr: usize, path_len: usize, permissions: usi
         crate::fs::InodeType::Directory,
         perms,
     )) {
-        Ok(_inode) => 0,
+        Ok(inode) => {
+            set_creator(&*inode, &cred, perms);
+            0
+        }
         Err(e) => -e.to_errno(),
     }
 }
@@ -734,7 +881,7 @@ pub(super) fn sys_handle_fcntl(fd: usize, cmd: usize, arg: usize) -> isize {
     reason = "fd numbers are small, wrap is impossible"
 )]
 pub(super) fn sys_handle_pipe2(fds_ptr: usize, flags: usize) -> isize {
-    let Ok(user_slice) = UserSlice::new(fds_ptr, 2 * core::mem::size_of::<usize>()) else {
+    let Ok(user_fds) = UserPtr::<[usize; 2]>::new(fds_ptr) else {
         return -EFAULT;
     };
 
@@ -759,17 +906,16 @@ pub(super) fn sys_handle_pipe2(fds_ptr: usize, flags: usize) -> isize {
         (rfd, wfd)
     });
 
-    // SAFETY: UserSlice validated the pointer range is in user space.
-    unsafe {
-        let dst = user_slice.addr() as *mut usize;
-        core::ptr::write(dst, read_fd.as_usize());
-        core::ptr::write(dst.add(1), write_fd.as_usize());
+    match user_fds.write([read_fd.as_usize(), write_fd.as_usize()]) {
+        Ok(()) => 0,
+        Err(e) => e,
     }
-
-    0
 }
 
 /// `sys_vnode_rename` — rename (move) a file or directory.
+///
+/// Needs write and search access to both parent directories, and the
+/// sticky-directory rule applies to the old entry.
 pub(super) fn sys_vnode_rename(
     old_ptr: usize,
     old_len: usize,
@@ -783,14 +929,19 @@ pub(super) fn sys_vnode_rename(
         return -EFAULT;
     };
 
-    // SAFETY: UserSlice validated ranges.
-    let old_bytes = unsafe { old_slice.as_slice() };
-    let new_bytes = unsafe { new_slice.as_slice() };
+    let old_bytes = match old_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
+    let new_bytes = match new_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
 
-    let Ok(old_path) = core::str::from_utf8(old_bytes) else {
+    let Ok(old_path) = core::str::from_utf8(&old_bytes) else {
         return -crate::syscall::EINVAL;
     };
-    let Ok(new_path) = core::str::from_utf8(new_bytes) else {
+    let Ok(new_path) = core::str::from_utf8(&new_bytes) else {
         return -crate::syscall::EINVAL;
     };
 
@@ -810,14 +961,19 @@ pub(super) fn sys_vnode_rename(
         return -crate::syscall::EINVAL;
     }
 
-    let old_parent_inode = match crate::fs::vfs::with_vfs(|vfs| vfs.resolve(old_parent)) {
+    // Both directories change, and the entry leaves the old one.
+    let cred = current_cred();
+    let old_parent_inode = match resolve_parent_for_write(old_parent, &cred) {
         Ok(i) => i,
-        Err(e) => return -e.to_errno(),
+        Err(e) => return e,
     };
+    if let Err(e) = check_delete(&cred, &*old_parent_inode, old_name) {
+        return e;
+    }
 
-    let new_parent_inode = match crate::fs::vfs::with_vfs(|vfs| vfs.resolve(new_parent)) {
+    let new_parent_inode = match resolve_parent_for_write(new_parent, &cred) {
         Ok(i) => i,
-        Err(e) => return -e.to_errno(),
+        Err(e) => return e,
     };
 
     match crate::fs::poll_immediate(old_parent_inode.rename(old_name, &*new_parent_inode, new_name))
@@ -828,6 +984,9 @@ pub(super) fn sys_vnode_rename(
 }
 
 /// `sys_vnode_symlink` — create a symbolic link.
+///
+/// Needs write and search access to the parent directory. The link is
+/// owned by the caller.
 pub(super) fn sys_vnode_symlink(
     target_ptr: usize,
     target_len: usize,
@@ -841,13 +1000,19 @@ pub(super) fn sys_vnode_symlink(
         return -EFAULT;
     };
 
-    let target_bytes = unsafe { target_slice.as_slice() };
-    let link_bytes = unsafe { link_slice.as_slice() };
+    let target_bytes = match target_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
+    let link_bytes = match link_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
 
-    let Ok(target) = core::str::from_utf8(target_bytes) else {
+    let Ok(target) = core::str::from_utf8(&target_bytes) else {
         return -crate::syscall::EINVAL;
     };
-    let Ok(link_path) = core::str::from_utf8(link_bytes) else {
+    let Ok(link_path) = core::str::from_utf8(&link_bytes) else {
         return -crate::syscall::EINVAL;
     };
 
@@ -861,9 +1026,10 @@ pub(super) fn sys_vnode_symlink(
         return -crate::syscall::EINVAL;
     }
 
-    let parent_inode = match crate::fs::vfs::with_vfs(|vfs| vfs.resolve(parent_path)) {
+    let cred = current_cred();
+    let parent_inode = match resolve_parent_for_write(parent_path, &cred) {
         Ok(i) => i,
-        Err(e) => return -e.to_errno(),
+        Err(e) => return e,
     };
 
     let perms = crate::fs::Permissions {
@@ -873,12 +1039,17 @@ pub(super) fn sys_vnode_symlink(
     };
 
     match parent_inode.create_symlink(name, target, perms) {
-        Ok(_) => 0,
+        Ok(inode) => {
+            set_creator(&*inode, &cred, perms);
+            0
+        }
         Err(e) => -e.to_errno(),
     }
 }
 
 /// `sys_vnode_link` — create a hard link.
+///
+/// Needs write and search access to the parent directory.
 pub(super) fn sys_vnode_link(
     target_ptr: usize,
     target_len: usize,
@@ -892,13 +1063,19 @@ pub(super) fn sys_vnode_link(
         return -EFAULT;
     };
 
-    let target_bytes = unsafe { target_slice.as_slice() };
-    let link_bytes = unsafe { link_slice.as_slice() };
+    let target_bytes = match target_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
+    let link_bytes = match link_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
 
-    let Ok(target_path) = core::str::from_utf8(target_bytes) else {
+    let Ok(target_path) = core::str::from_utf8(&target_bytes) else {
         return -crate::syscall::EINVAL;
     };
-    let Ok(link_path) = core::str::from_utf8(link_bytes) else {
+    let Ok(link_path) = core::str::from_utf8(&link_bytes) else {
         return -crate::syscall::EINVAL;
     };
 
@@ -918,9 +1095,9 @@ pub(super) fn sys_vnode_link(
         return -crate::syscall::EINVAL;
     }
 
-    let parent_inode = match crate::fs::vfs::with_vfs(|vfs| vfs.resolve(parent_path)) {
+    let parent_inode = match resolve_parent_for_write(parent_path, &current_cred()) {
         Ok(i) => i,
-        Err(e) => return -e.to_errno(),
+        Err(e) => return e,
     };
 
     match crate::fs::poll_immediate(parent_inode.link(name, &*target_inode)) {
@@ -947,8 +1124,11 @@ pub(super) fn sys_vnode_readlink(
         return -EFAULT;
     };
 
-    let path_bytes = unsafe { path_slice.as_slice() };
-    let Ok(path) = core::str::from_utf8(path_bytes) else {
+    let path_bytes = match path_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
+    let Ok(path) = core::str::from_utf8(&path_bytes) else {
         return -crate::syscall::EINVAL;
     };
 
@@ -985,8 +1165,9 @@ pub(super) fn sys_vnode_readlink(
         return -crate::syscall::EINVAL;
     }
 
-    let buf = unsafe { buf_slice.as_mut_slice() };
-    buf[..target_bytes.len()].copy_from_slice(target_bytes);
+    if let Err(e) = buf_slice.write_from(target_bytes) {
+        return e;
+    }
     target_bytes.len() as isize
 }
 
@@ -1028,9 +1209,11 @@ pub(super) fn sys_vnode_fstatat(
         Ok(s) => s,
         Err(e) => return e,
     };
-    let path_bytes =
-        unsafe { core::slice::from_raw_parts(path_slice.addr() as *const u8, path_len) };
-    let path = match core::str::from_utf8(path_bytes) {
+    let path_bytes = match path_slice.read_to_vec() {
+        Ok(bytes) => bytes,
+        Err(e) => return e,
+    };
+    let path = match core::str::from_utf8(&path_bytes) {
         Ok(p) => p,
         Err(_) => return -EINVAL,
     };
@@ -1075,20 +1258,25 @@ pub(super) fn sys_vnode_fstatat(
     let permissions: u32 =
         u32::from(perms.read) | (u32::from(perms.write) << 1) | (u32::from(perms.execute) << 2);
 
+    let own = inode.ownership();
     let info = StatInfo {
         inode_type,
         _pad: [0; 7],
         size: inode.size() as u64,
         permissions,
-        _pad2: 0,
+        mode: u32::from(own.mode),
         rdev: inode.dev_number().0,
+        uid: own.uid.as_u32(),
+        gid: own.gid.as_u32(),
     };
 
-    let out = unsafe { user_slice.as_mut_slice() };
+    // SAFETY: StatInfo is repr(C) and contains only scalar fields.
     let info_bytes =
         unsafe { core::slice::from_raw_parts(core::ptr::addr_of!(info).cast::<u8>(), stat_size) };
-    out[..stat_size].copy_from_slice(info_bytes);
-    0
+    match user_slice.write_from(info_bytes) {
+        Ok(()) => 0,
+        Err(e) => e,
+    }
 }
 
 /// `sys_handle_tcsetpgrp` — set the foreground process group of a TTY.
@@ -7,6 +7,7 @@
 //! Also stores the HPET driver instance as a [`ClockSource`] trait object
 //! for consumers that want the trait-based interface (e.g. future vDSO).
 
+use hadron_core::cputime::TscScale;
 use hadron_core::sync::atomic::{AtomicU64, Ordering};
 
 #[cfg(hadron_hpet)]
@@ -37,6 +38,11 @@ static PIT_NANOS_PER_TICK: AtomicU64 = AtomicU64::new(0);
 /// Unix epoch seconds at the time of boot (from CMOS RTC).
 static BOOT_EPOCH_SECS: AtomicU64 = AtomicU64::new(0);
 
+/// Calibrated TSC frequency in Hz. Zero means "not yet calibrated".
+static TSC_FREQ_HZ: AtomicU64 = AtomicU64::new(0);
+/// [`TscScale`] multiplier for the calibrated TSC frequency.
+static TSC_SCALE_MULT: AtomicU64 = AtomicU64::new(0);
+
 /// Zero-sized facade for the global time subsystem.
 pub struct Time;
 
@@ -120,6 +126,32 @@ impl Time {
             .saturating_add(Self::boot_nanos())
     }
 
+    /// Records the TSC frequency measured during timer calibration.
+    pub fn set_tsc_frequency(freq_hz: u64) {
+        TSC_SCALE_MULT.store(TscScale::from_frequency(freq_hz).mult(), Ordering::Relaxed);
+        TSC_FREQ_HZ.store(freq_hz, Ordering::Release);
+    }
+
+    /// Returns the calibrated TSC frequency in Hz, or 0 before calibration.
+    pub fn tsc_frequency() -> u64 {
+        TSC_FREQ_HZ.load(Ordering::Acquire)
+    }
+
+    /// Converts a TSC cycle count to nanoseconds.
+    ///
+    /// Returns 0 before the TSC is calibrated.
+    pub fn tsc_cycles_to_nanos(cycles: u64) -> u64 {
+        TscScale::from_mult(TSC_SCALE_MULT.load(Ordering::Relaxed)).cycles_to_nanos(cycles)
+    }
+
+    /// Converts nanoseconds to a TSC cycle count.
+    ///
+    /// Returns 0 before the TSC is calibrated.
+    pub fn nanos_to_tsc_cycles(nanos: u64) -> u64 {
+        let freq = u128::from(TSC_FREQ_HZ.load(Ordering::Acquire));
+        u64::try_from(u128::from(nanos) * freq / 1_000_000_000).unwrap_or(u64::MAX)
+    }
+
     /// Stores the HPET driver instance for [`ClockSource`] trait access.
     ///
     /// Called from ACPI init after timer calibration is complete.
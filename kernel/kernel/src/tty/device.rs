@@ -72,6 +72,15 @@ impl Future for TtyReadFuture<'_> {
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
 
+        // A background process group may not read its terminal. Checked on
+        // the first poll, which runs in the reader's syscall context.
+        if !this.subscribed
+            && let Err(e) =
+                super::check_background_access(this.tty.foreground_pgid(), hadron_syscall::SIGTTIN)
+        {
+            return Poll::Ready(Err(e));
+        }
+
         // Always poll hardware and check for data first.
         this.tty.poll_hardware();
         if let Some(n) = this.tty.try_read(this.buf) {
@@ -140,6 +149,12 @@ impl Inode for DevTty {
         buf: &'a [u8],
     ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
         Box::pin(async move {
+            if self.tty.get_termios().lflag & hadron_syscall::TOSTOP != 0 {
+                super::check_background_access(
+                    self.tty.foreground_pgid(),
+                    hadron_syscall::SIGTTOU,
+                )?;
+            }
             if let Ok(s) = core::str::from_utf8(buf) {
                 self.tty.write_output(s);
             } else {
@@ -189,36 +204,31 @@ impl Inode for DevTty {
             TCGETS => {
                 let t = self.tty.get_termios();
                 let ptr = UserPtr::<Termios>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(t) };
+                ptr.write(t).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             TCSETS | TCSETSW | TCSETSF => {
                 let ptr = UserPtr::<Termios>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address; reading a POD struct.
-                let t = unsafe { *ptr.as_ref() };
+                let t = ptr.read().map_err(|_| FsError::Fault)?;
                 self.tty.set_termios(&t);
                 Ok(0)
             }
             TIOCGPGRP => {
                 let pgid = self.tty.foreground_pgid().unwrap_or(0);
                 let ptr = UserPtr::<u32>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(pgid) };
+                ptr.write(pgid).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             TIOCSPGRP => {
                 let ptr = UserPtr::<u32>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address; reading a u32.
-                let pgid = unsafe { *ptr.as_ref() };
+                let pgid = ptr.read().map_err(|_| FsError::Fault)?;
                 self.tty.set_foreground_pgid(pgid);
                 Ok(0)
             }
             TIOCGWINSZ => {
                 let ws = self.tty.get_winsize();
                 let ptr = UserPtr::<Winsize>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(ws) };
+                ptr.write(ws).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             TIOCSWINSZ => {
@@ -62,6 +62,7 @@ const fn default_termios() -> Termios {
     cc[hadron_syscall::VERASE] = 0x7F; // DEL
     cc[hadron_syscall::VKILL] = 0x15; // Ctrl+U
     cc[hadron_syscall::VEOF] = 0x04; // Ctrl+D
+    cc[hadron_syscall::VSUSP] = 0x1A; // Ctrl+Z
     cc[hadron_syscall::VMIN] = 1;
     cc[hadron_syscall::VTIME] = 0;
     Termios {
@@ -313,6 +314,13 @@ impl Tty {
                         crate::proc::signal_process_group(fg_pgid, crate::syscall::SIGINT);
                     }
                 }
+                Some(LdiscAction::Suspend) => {
+                    crate::kprint!("^Z\n");
+                    // Send SIGTSTP to the foreground process group.
+                    if let Some(fg_pgid) = self.foreground_pgid() {
+                        crate::proc::signal_process_group(fg_pgid, crate::syscall::SIGTSTP);
+                    }
+                }
                 Some(LdiscAction::Eof) | Some(LdiscAction::FlushLine) => {}
                 Some(LdiscAction::SwitchVt(vt)) => {
                     switch_vt(*vt);
@@ -337,6 +345,46 @@ impl Tty {
     }
 }
 
+// ── Job control ──────────────────────────────────────────────────────
+
+/// Job-control check for a terminal read (`signum` = `SIGTTIN`) or write
+/// (`SIGTTOU`) by the current process, on a terminal whose foreground
+/// process group is `fg_pgid`.
+///
+/// A caller outside the foreground group has its group sent `signum` and
+/// gets [`FsError::Interrupted`], so it stops before returning to
+/// userspace. If the caller blocks or ignores `signum`, reads fail with
+/// [`FsError::IoError`] and writes go ahead. Outside syscall context there
+/// is no current process and nothing is checked.
+pub(crate) fn check_background_access(
+    fg_pgid: Option<u32>,
+    signum: usize,
+) -> Result<(), crate::fs::FsError> {
+    let Some(fg_pgid) = fg_pgid else {
+        return Ok(());
+    };
+    let Some((pgid, refused)) = crate::proc::ProcessTable::try_current(|process| {
+        let signals = &process.signals;
+        let blocked = signals.get_mask() & crate::proc::signal::sig_bit(signum) != 0;
+        let ignored = signals.action(signum).handler == hadron_syscall::SIG_IGN;
+        (process.pgid.load(Ordering::Acquire), blocked || ignored)
+    }) else {
+        return Ok(());
+    };
+    if pgid == fg_pgid {
+        return Ok(());
+    }
+    if refused {
+        return if signum == hadron_syscall::SIGTTIN {
+            Err(crate::fs::FsError::IoError)
+        } else {
+            Ok(())
+        };
+    }
+    crate::proc::signal_process_group(pgid, signum);
+    Err(crate::fs::FsError::Interrupted)
+}
+
 // ── VT switching ─────────────────────────────────────────────────────
 
 /// Switch the active virtual terminal.
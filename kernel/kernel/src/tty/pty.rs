@@ -22,7 +22,7 @@ use crate::fs::{DirEntry, FsError, Inode, InodeType, Permissions};
 use crate::sync::{HeapWaitQueue, IrqSpinLock, SpinLock};
 use hadron_syscall::Termios;
 
-use super::ldisc::LineDiscipline;
+use super::ldisc::{LdiscAction, LineDiscipline};
 
 /// Default PTY buffer size: 16 KiB per direction.
 const PTY_BUF_SIZE: usize = 16 * 1024;
@@ -58,6 +58,14 @@ struct PtyInner {
     slaves: AtomicUsize,
 }
 
+impl PtyInner {
+    /// Foreground process group of the slave, or `None` if none is set.
+    fn foreground(&self) -> Option<u32> {
+        let raw = self.foreground_pgid.load(Ordering::Acquire);
+        if raw == 0 { None } else { Some(raw) }
+    }
+}
+
 use crate::ipc::circular_buffer::CircularBuffer;
 
 /// Master side of a PTY pair.
@@ -208,16 +216,21 @@ impl Inode for PtyMaster {
 
             if canonical {
                 // Canonical mode: feed bytes through the line discipline for
-                // line editing (backspace, Enter, Ctrl+C/D handling).
+                // line editing (backspace, Enter, Ctrl+C/D/Z handling).
                 // Drain completed data into a local buffer to avoid holding
                 // the ldisc IrqSpinLock while locking the m2s SpinLock.
                 let isig = termios.lflag & hadron_syscall::ISIG != 0;
                 let mut staged = [0u8; 512];
                 let mut staged_len = 0;
+                let mut signal = None;
                 {
                     let mut ldisc = self.0.ldisc.lock();
                     for &byte in buf {
-                        let _ = ldisc.process_ascii_byte(byte, true, isig);
+                        match ldisc.process_ascii_byte(byte, true, isig) {
+                            Some(LdiscAction::Interrupt) => signal = Some(hadron_syscall::SIGINT),
+                            Some(LdiscAction::Suspend) => signal = Some(hadron_syscall::SIGTSTP),
+                            _ => {}
+                        }
                     }
                     let mut tmp = [0u8; 256];
                     while let Some(n) = ldisc.try_read(&mut tmp) {
@@ -232,6 +245,10 @@ impl Inode for PtyMaster {
                     let mut m2s = self.0.m2s_buf.lock();
                     m2s.write(&staged[..staged_len]);
                 }
+                // Signal the slave's foreground process group.
+                if let (Some(signum), Some(fg_pgid)) = (signal, self.0.foreground()) {
+                    crate::proc::signal_process_group(fg_pgid, signum);
+                }
                 self.0.slave_wq.wake_all();
                 Ok(buf.len())
             } else {
@@ -280,14 +297,12 @@ impl Inode for PtyMaster {
             TIOCGPTN => {
                 let index = self.0.index as u32;
                 let ptr = UserPtr::<u32>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(index) };
+                ptr.write(index).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             TIOCSPTLCK => {
                 let ptr = UserPtr::<u32>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address; reading a u32.
-                let lock_val = unsafe { *ptr.as_ref() };
+                let lock_val = ptr.read().map_err(|_| FsError::Fault)?;
                 self.0.locked.store(lock_val != 0, Ordering::Release);
                 Ok(0)
             }
@@ -295,14 +310,12 @@ impl Inode for PtyMaster {
             hadron_syscall::TCGETS => {
                 let t = *self.0.termios.lock();
                 let ptr = UserPtr::<Termios>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(t) };
+                ptr.write(t).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             hadron_syscall::TCSETS | hadron_syscall::TCSETSW | hadron_syscall::TCSETSF => {
                 let ptr = UserPtr::<Termios>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address; reading a POD struct.
-                let t = unsafe { *ptr.as_ref() };
+                let t = ptr.read().map_err(|_| FsError::Fault)?;
                 *self.0.termios.lock() = t;
                 Ok(0)
             }
@@ -310,15 +323,13 @@ impl Inode for PtyMaster {
                 let ws = *self.0.winsize.lock();
                 let ptr =
                     UserPtr::<hadron_syscall::Winsize>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(ws) };
+                ptr.write(ws).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             hadron_syscall::TIOCSWINSZ => {
                 let ptr =
                     UserPtr::<hadron_syscall::Winsize>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address; reading a POD struct.
-                let ws = unsafe { *ptr.as_ref() };
+                let ws = ptr.read().map_err(|_| FsError::Fault)?;
                 *self.0.winsize.lock() = ws;
                 Ok(0)
             }
@@ -375,6 +386,7 @@ impl Inode for PtySlave {
         buf: &'a mut [u8],
     ) -> Pin<Box<dyn Future<Output = Result<usize, FsError>> + Send + 'a>> {
         Box::pin(async move {
+            super::check_background_access(self.0.foreground(), hadron_syscall::SIGTTIN)?;
             loop {
                 core::future::poll_fn(|cx| {
                     self.0.slave_wq.register_waker(cx.waker());
@@ -415,6 +427,9 @@ impl Inode for PtySlave {
                 return Err(FsError::BrokenPipe);
             }
             let termios = *self.0.termios.lock();
+            if termios.lflag & hadron_syscall::TOSTOP != 0 {
+                super::check_background_access(self.0.foreground(), hadron_syscall::SIGTTOU)?;
+            }
             let opost = termios.oflag & hadron_syscall::OPOST != 0;
             let onlcr = termios.oflag & hadron_syscall::ONLCR != 0;
 
@@ -474,28 +489,24 @@ impl Inode for PtySlave {
             TCGETS => {
                 let t = *self.0.termios.lock();
                 let ptr = UserPtr::<Termios>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(t) };
+                ptr.write(t).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             TCSETS | TCSETSW | TCSETSF => {
                 let ptr = UserPtr::<Termios>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address; reading a POD struct.
-                let t = unsafe { *ptr.as_ref() };
+                let t = ptr.read().map_err(|_| FsError::Fault)?;
                 *self.0.termios.lock() = t;
                 Ok(0)
             }
             TIOCGPGRP => {
                 let pgid = self.0.foreground_pgid.load(Ordering::Acquire);
                 let ptr = UserPtr::<u32>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(pgid) };
+                ptr.write(pgid).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             TIOCSPGRP => {
                 let ptr = UserPtr::<u32>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address; reading a u32.
-                let pgid = unsafe { *ptr.as_ref() };
+                let pgid = ptr.read().map_err(|_| FsError::Fault)?;
                 self.0.foreground_pgid.store(pgid, Ordering::Release);
                 Ok(0)
             }
@@ -503,15 +514,13 @@ impl Inode for PtySlave {
                 let ws = *self.0.winsize.lock();
                 let ptr =
                     UserPtr::<hadron_syscall::Winsize>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address is user-space and aligned.
-                unsafe { ptr.write(ws) };
+                ptr.write(ws).map_err(|_| FsError::Fault)?;
                 Ok(0)
             }
             TIOCSWINSZ => {
                 let ptr =
                     UserPtr::<hadron_syscall::Winsize>::new(arg).map_err(|_| FsError::Fault)?;
-                // SAFETY: UserPtr validated address; reading a POD struct.
-                let ws = unsafe { *ptr.as_ref() };
+                let ws = ptr.read().map_err(|_| FsError::Fault)?;
                 *self.0.winsize.lock() = ws;
                 Ok(0)
             }
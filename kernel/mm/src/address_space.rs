@@ -3,16 +3,49 @@
 //! Each process owns an [`AddressSpace`] that holds a per-process PML4
 //! with the kernel upper half copied from the kernel root page table.
 //! User pages are mapped into the lower half (entries 0–255).
+//!
+//! An *isolated* address space ([`AddressSpace::new_user_isolated`]) also
+//! owns a shadow PML4 for kernel page-table isolation: its upper half comes
+//! from a minimal template instead of the kernel root, and its lower half
+//! mirrors the main PML4, so both share every user page table below the
+//! PML4.
+//!
+//! For tagged TLBs, an address space carries a per-CPU *ASID tag* slot for
+//! the architecture's identifier allocator and a *TLB generation* that is
+//! bumped whenever a user translation is removed or downgraded. A CPU that
+//! still caches translations under the address space's identifier compares
+//! the generation it last flushed at against the current one to decide
+//! whether those translations can be reused.
+//!
+//! When the mapper also supports 2 MiB pages, user mappings can use huge
+//! pages. Range operations ([`AddressSpace::unmap_user_range`],
+//! [`AddressSpace::protect_range`]) transparently split a huge page that
+//! is only partially covered.
 
 use hadron_core::addr::{PhysAddr, VirtAddr};
-use hadron_core::paging::{Page, PhysFrame, Size4KiB};
+use hadron_core::cpu_local::MAX_CPUS;
+use hadron_core::paging::{Page, PhysFrame, Size2MiB, Size4KiB};
+use hadron_core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
-use crate::mapper::{MapFlags, MapFlush, PageMapper, PageTranslator, UnmapError};
-use crate::{FrameAllocator, VmmError};
+use crate::mapper::{MapFlags, MapFlush, PageMapper, PageSplitter, PageTranslator, UnmapError};
+use crate::{FrameAllocator, FrameDeallocator, VmmError};
 
 /// Number of PML4 entries in the upper half (indices 256–511).
 const KERNEL_PML4_ENTRIES: usize = 256;
 
+/// Worst-case number of intermediate page table frames that a single
+/// [`AddressSpace::map_user_page`] call may allocate (PDPT, PD, and PT).
+///
+/// Callers that must fail gracefully on memory exhaustion should keep at
+/// least this many frames free before mapping a page.
+pub const USER_MAP_MAX_TABLE_FRAMES: usize = 3;
+
+/// Number of 4 KiB pages covered by one 2 MiB huge page.
+pub const HUGE_PAGE_PAGES: usize = 512;
+
+/// Size in bytes of a 2 MiB huge page.
+const HUGE_PAGE_SIZE: u64 = 0x20_0000;
+
 /// Callback for deallocating a single physical frame.
 ///
 /// Stored at construction time so that `Drop` can free the PML4 frame
@@ -24,14 +57,34 @@ pub type FrameDeallocFn = fn(PhysFrame<Size4KiB>);
 /// The upper half (PML4 entries 256–511) is shared with the kernel;
 /// the lower half (entries 0–255) is process-private.
 ///
-/// On drop, the PML4 frame is freed via the stored deallocation callback.
+/// The address space also tracks its resident set size (in 4 KiB pages; a
+/// huge page counts as [`HUGE_PAGE_PAGES`]) and the number of page table
+/// frames it owns, for memory reporting and OOM victim selection. Only
+/// frames private to the address space count as resident; device and
+/// shared-memory frames mapped with the `map_user_foreign_*` methods do not.
+///
+/// On drop, the PML4 frame (and shadow PML4, if any) is freed via the
+/// stored deallocation callback.
 pub struct AddressSpace<M: PageMapper<Size4KiB> + PageTranslator> {
     /// Physical address of this address space's PML4 frame.
     root_phys: PhysAddr,
+    /// Physical address of the shadow PML4, for isolated address spaces.
+    shadow_root: Option<PhysAddr>,
+    /// HHDM offset, for keeping the shadow PML4 in sync.
+    hhdm_offset: VirtAddr,
     /// Page table mapper (shared, knows HHDM offset).
     mapper: M,
     /// Callback to free physical frames on drop.
     dealloc_fn: FrameDeallocFn,
+    /// Number of private user pages currently mapped.
+    resident_pages: AtomicUsize,
+    /// Number of page table frames owned (including the PML4).
+    table_pages: AtomicUsize,
+    /// Per-CPU ASID tags, owned by the architecture's identifier
+    /// allocator (0 = no identifier assigned on that CPU).
+    asid_tags: [AtomicU64; MAX_CPUS],
+    /// Bumped after every removal or downgrade of a user translation.
+    tlb_gen: AtomicU64,
 }
 
 impl<M: PageMapper<Size4KiB> + PageTranslator> AddressSpace<M> {
@@ -75,22 +128,139 @@ impl<M: PageMapper<Size4KiB> + PageTranslator> AddressSpace<M> {
 
         Ok(Self {
             root_phys: new_pml4_phys,
+            shadow_root: None,
+            hhdm_offset,
             mapper,
             dealloc_fn,
+            resident_pages: AtomicUsize::new(0),
+            table_pages: AtomicUsize::new(1),
+            asid_tags: [const { AtomicU64::new(0) }; MAX_CPUS],
+            tlb_gen: AtomicU64::new(0),
         })
     }
 
+    /// Creates a new user address space with a shadow PML4 for kernel
+    /// page-table isolation.
+    ///
+    /// Like [`new_user`](Self::new_user), and additionally allocates a
+    /// shadow PML4 whose upper half is copied from `shadow_template`. The
+    /// user half of the shadow is kept in sync with the main PML4 as pages
+    /// are mapped.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`new_user`](Self::new_user); `shadow_template` must point
+    /// to a valid PML4 whose upper-half entries stay valid for the lifetime
+    /// of the address space.
+    pub unsafe fn new_user_isolated(
+        kernel_root: PhysAddr,
+        shadow_template: PhysAddr,
+        mapper: M,
+        hhdm_offset: VirtAddr,
+        alloc: &mut impl FrameAllocator<Size4KiB>,
+        dealloc_fn: FrameDeallocFn,
+    ) -> Result<Self, VmmError> {
+        let shadow = alloc.allocate_frame().ok_or(VmmError::OutOfMemory)?;
+        // SAFETY: Forwarded from the caller.
+        let mut space =
+            match unsafe { Self::new_user(kernel_root, mapper, hhdm_offset, alloc, dealloc_fn) } {
+                Ok(space) => space,
+                Err(e) => {
+                    dealloc_fn(shadow);
+                    return Err(e);
+                }
+            };
+        let shadow_phys = shadow.start_address();
+
+        // SAFETY: Both frames are accessible via HHDM; the shadow frame was
+        // just allocated and is not referenced elsewhere.
+        unsafe {
+            let shadow_pml4 = (hhdm_offset + shadow_phys.as_u64()).as_mut_ptr::<u64>();
+            let template = (hhdm_offset + shadow_template.as_u64()).as_ptr::<u64>();
+            core::ptr::write_bytes(shadow_pml4, 0, KERNEL_PML4_ENTRIES);
+            core::ptr::copy_nonoverlapping(
+                template.add(KERNEL_PML4_ENTRIES),
+                shadow_pml4.add(KERNEL_PML4_ENTRIES),
+                KERNEL_PML4_ENTRIES,
+            );
+        }
+
+        space.shadow_root = Some(shadow_phys);
+        space.table_pages.store(2, Ordering::Relaxed);
+        Ok(space)
+    }
+
+    /// Copies the main PML4 entry covering `addr` into the shadow PML4.
+    ///
+    /// Called after every mapping that may have allocated a new PDPT.
+    /// Entries are only ever added to the user half, never replaced, so a
+    /// copy after the fact is enough.
+    fn sync_shadow(&self, addr: VirtAddr) {
+        let Some(shadow) = self.shadow_root else {
+            return;
+        };
+        let index = ((addr.as_u64() >> 39) & 0x1ff) as usize;
+        // SAFETY: Both PML4 frames are owned by this address space and
+        // accessible via HHDM; `index` is in the user half.
+        unsafe {
+            let root = (self.hhdm_offset + self.root_phys.as_u64()).as_ptr::<u64>();
+            let shadow = (self.hhdm_offset + shadow.as_u64()).as_mut_ptr::<u64>();
+            core::ptr::write_volatile(shadow.add(index), core::ptr::read_volatile(root.add(index)));
+        }
+    }
+
     /// Maps a single 4 KiB page into the user address space.
     ///
-    /// The `USER` flag is always added to `flags`.
+    /// The `USER` flag is always added to `flags`. The page is counted
+    /// towards the resident set, and any intermediate page table frames
+    /// allocated from `alloc` towards the page table overhead.
     ///
     /// Returns a [`MapFlush`] that the caller must handle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alloc` runs out of frames while allocating an intermediate
+    /// page table. Callers can avoid this by keeping
+    /// [`USER_MAP_MAX_TABLE_FRAMES`] frames in reserve.
     pub fn map_user_page(
         &self,
         page: Page<Size4KiB>,
         frame: PhysFrame<Size4KiB>,
         flags: MapFlags,
         alloc: &mut impl FrameAllocator<Size4KiB>,
+    ) -> Result<MapFlush, VmmError> {
+        self.map_page(page, frame, flags, alloc, true)
+    }
+
+    /// Maps a single 4 KiB page of device or shared memory into the user
+    /// address space.
+    ///
+    /// Like [`map_user_page`](Self::map_user_page), except that the frame
+    /// belongs to hardware or a shared memory object, so the page is not
+    /// counted towards the resident set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alloc` runs out of frames while allocating an intermediate
+    /// page table (see [`USER_MAP_MAX_TABLE_FRAMES`]).
+    pub fn map_user_foreign_page(
+        &self,
+        page: Page<Size4KiB>,
+        frame: PhysFrame<Size4KiB>,
+        flags: MapFlags,
+        alloc: &mut impl FrameAllocator<Size4KiB>,
+    ) -> Result<MapFlush, VmmError> {
+        self.map_page(page, frame, flags, alloc, false)
+    }
+
+    /// Maps a 4 KiB page, counting it as resident if `resident` is set.
+    fn map_page(
+        &self,
+        page: Page<Size4KiB>,
+        frame: PhysFrame<Size4KiB>,
+        flags: MapFlags,
+        alloc: &mut impl FrameAllocator<Size4KiB>,
+        resident: bool,
     ) -> Result<MapFlush, VmmError> {
         let flags = flags | MapFlags::USER;
         // SAFETY: The AddressSpace owns its PML4 (root_phys). The caller
@@ -98,30 +268,59 @@ impl<M: PageMapper<Size4KiB> + PageTranslator> AddressSpace<M> {
         let flush = unsafe {
             self.mapper
                 .map(self.root_phys, page, frame, flags, &mut || {
+                    self.table_pages.fetch_add(1, Ordering::Relaxed);
                     alloc
                         .allocate_frame()
                         .expect("PMM: out of memory during user map")
                 })
         };
+        self.sync_shadow(page.start_address());
+        if resident {
+            self.resident_pages.fetch_add(1, Ordering::Relaxed);
+        }
         Ok(flush)
     }
 
-    /// Unmaps a single 4 KiB page from the user address space.
+    /// Unmaps a single private 4 KiB page from the user address space.
     ///
     /// Flushes the TLB internally and returns the freed frame.
+    ///
+    /// Returns [`VmmError::SizeMismatch`] if the page lies inside a huge page.
     pub fn unmap_user_page(&self, page: Page<Size4KiB>) -> Result<PhysFrame<Size4KiB>, VmmError> {
+        self.unmap_page(page, true)
+    }
+
+    /// Unmaps a 4 KiB page, removing it from the resident set if `resident`
+    /// is set.
+    fn unmap_page(
+        &self,
+        page: Page<Size4KiB>,
+        resident: bool,
+    ) -> Result<PhysFrame<Size4KiB>, VmmError> {
         let (frame, flush) = unsafe {
             self.mapper
                 .unmap(self.root_phys, page)
-                .map_err(|e| match e {
-                    UnmapError::NotMapped => VmmError::NotMapped,
-                    UnmapError::SizeMismatch => VmmError::SizeMismatch,
-                })?
+                .map_err(unmap_error)?
         };
         flush.flush();
+        self.invalidated();
+        if resident {
+            self.resident_pages.fetch_sub(1, Ordering::Relaxed);
+        }
         Ok(frame)
     }
 
+    /// Returns the number of user pages currently mapped (resident set size).
+    pub fn resident_pages(&self) -> usize {
+        self.resident_pages.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of page table frames owned by this address space,
+    /// including the PML4.
+    pub fn page_table_pages(&self) -> usize {
+        self.table_pages.load(Ordering::Relaxed)
+    }
+
     /// Returns the physical address of this address space's PML4.
     ///
     /// Used for loading into CR3 on context switch.
@@ -129,45 +328,306 @@ impl<M: PageMapper<Size4KiB> + PageTranslator> AddressSpace<M> {
         self.root_phys
     }
 
+    /// Returns the physical address of the shadow PML4, or `None` if the
+    /// address space was not created with
+    /// [`new_user_isolated`](Self::new_user_isolated).
+    pub fn shadow_root_phys(&self) -> Option<PhysAddr> {
+        self.shadow_root
+    }
+
+    /// Returns this address space's ASID tag slot for CPU `cpu`.
+    ///
+    /// The tag is opaque to this crate; the architecture's identifier
+    /// allocator stores and interprets it (0 = none assigned).
+    pub fn asid_tag(&self, cpu: usize) -> &AtomicU64 {
+        &self.asid_tags[cpu]
+    }
+
+    /// Returns the TLB generation: the number of times a user translation
+    /// has been removed or downgraded.
+    ///
+    /// Those changes are only flushed from the TLB of the CPU that made
+    /// them, so a CPU that cached this address space's translations at an
+    /// older generation must flush them before reusing them.
+    pub fn tlb_generation(&self) -> u64 {
+        self.tlb_gen.load(Ordering::Acquire)
+    }
+
+    /// Records that user translations were removed or downgraded.
+    fn invalidated(&self) {
+        self.tlb_gen.fetch_add(1, Ordering::Release);
+    }
+
     /// Translates a virtual address within this address space.
     pub fn translate(&self, virt: VirtAddr) -> Option<PhysAddr> {
         unsafe { <M as PageTranslator>::translate_addr(&self.mapper, self.root_phys, virt) }
     }
+}
+
+impl<M> AddressSpace<M>
+where
+    M: PageMapper<Size4KiB> + PageMapper<Size2MiB> + PageSplitter<Size2MiB> + PageTranslator,
+{
+    /// Maps a single 2 MiB huge page into the user address space.
+    ///
+    /// The `USER` flag is always added to `flags`. The page counts as
+    /// [`HUGE_PAGE_PAGES`] resident pages.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alloc` runs out of frames while allocating an intermediate
+    /// page table (see [`USER_MAP_MAX_TABLE_FRAMES`]).
+    pub fn map_user_huge_page(
+        &self,
+        page: Page<Size2MiB>,
+        frame: PhysFrame<Size2MiB>,
+        flags: MapFlags,
+        alloc: &mut impl FrameAllocator<Size4KiB>,
+    ) -> Result<MapFlush, VmmError> {
+        self.map_huge_page(page, frame, flags, alloc, true)
+    }
+
+    /// Maps a single 2 MiB huge page of device or shared memory into the
+    /// user address space, without counting it towards the resident set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alloc` runs out of frames while allocating an intermediate
+    /// page table (see [`USER_MAP_MAX_TABLE_FRAMES`]).
+    pub fn map_user_foreign_huge_page(
+        &self,
+        page: Page<Size2MiB>,
+        frame: PhysFrame<Size2MiB>,
+        flags: MapFlags,
+        alloc: &mut impl FrameAllocator<Size4KiB>,
+    ) -> Result<MapFlush, VmmError> {
+        self.map_huge_page(page, frame, flags, alloc, false)
+    }
+
+    /// Maps a 2 MiB page, counting it as resident if `resident` is set.
+    fn map_huge_page(
+        &self,
+        page: Page<Size2MiB>,
+        frame: PhysFrame<Size2MiB>,
+        flags: MapFlags,
+        alloc: &mut impl FrameAllocator<Size4KiB>,
+        resident: bool,
+    ) -> Result<MapFlush, VmmError> {
+        let flags = flags | MapFlags::USER;
+        // SAFETY: The AddressSpace owns its PML4 (root_phys). The caller
+        // provides a valid 2 MiB frame and allocator for page table pages.
+        let flush = unsafe {
+            <M as PageMapper<Size2MiB>>::map(
+                &self.mapper,
+                self.root_phys,
+                page,
+                frame,
+                flags,
+                &mut || {
+                    self.table_pages.fetch_add(1, Ordering::Relaxed);
+                    alloc
+                        .allocate_frame()
+                        .expect("PMM: out of memory during user map")
+                },
+            )
+        };
+        self.sync_shadow(page.start_address());
+        if resident {
+            self.resident_pages
+                .fetch_add(HUGE_PAGE_PAGES, Ordering::Relaxed);
+        }
+        Ok(flush)
+    }
+
+    /// Unmaps a single private 2 MiB huge page from the user address space.
+    ///
+    /// Flushes the TLB internally and returns the freed frame. Returns
+    /// [`VmmError::SizeMismatch`] if the range is mapped with 4 KiB pages.
+    pub fn unmap_user_huge_page(
+        &self,
+        page: Page<Size2MiB>,
+    ) -> Result<PhysFrame<Size2MiB>, VmmError> {
+        self.unmap_huge_page(page, true)
+    }
+
+    /// Unmaps a 2 MiB page, removing it from the resident set if
+    /// `resident` is set.
+    fn unmap_huge_page(
+        &self,
+        page: Page<Size2MiB>,
+        resident: bool,
+    ) -> Result<PhysFrame<Size2MiB>, VmmError> {
+        // SAFETY: The AddressSpace owns its root page table.
+        let (frame, flush) = unsafe {
+            <M as PageMapper<Size2MiB>>::unmap(&self.mapper, self.root_phys, page)
+                .map_err(unmap_error)?
+        };
+        flush.flush();
+        self.invalidated();
+        if resident {
+            self.resident_pages
+                .fetch_sub(HUGE_PAGE_PAGES, Ordering::Relaxed);
+        }
+        Ok(frame)
+    }
+
+    /// Splits a 2 MiB huge page into 512 4 KiB pages with the same frames
+    /// and flags.
+    ///
+    /// The new page table frame is taken from `alloc` and counted towards
+    /// the page table overhead.
+    pub fn split_user_huge_page(
+        &self,
+        page: Page<Size2MiB>,
+        alloc: &mut (impl FrameAllocator<Size4KiB> + FrameDeallocator<Size4KiB>),
+    ) -> Result<(), VmmError> {
+        let table = alloc.allocate_frame().ok_or(VmmError::OutOfMemory)?;
+        // SAFETY: The AddressSpace owns its root page table. `table` is a
+        // fresh frame that only the split will reference.
+        let result = unsafe {
+            <M as PageSplitter<Size2MiB>>::split(&self.mapper, self.root_phys, page, &mut || table)
+        };
+        match result {
+            Ok(flush) => {
+                flush.flush();
+                self.table_pages.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+            Err(e) => {
+                // SAFETY: The split failed, so nothing references `table`.
+                unsafe { alloc.deallocate_frame(table) };
+                Err(unmap_error(e))
+            }
+        }
+    }
+
+    /// Unmaps `page_count` 4 KiB pages starting at `base`.
+    ///
+    /// Huge pages fully inside the range are unmapped whole; a huge page
+    /// that straddles either end is split first. Pages that are not mapped
+    /// are skipped. If `owns_frames` is set, the frames are private: they
+    /// leave the resident set and are returned to `alloc`. Otherwise they
+    /// were mapped with the `map_user_foreign_*` methods and are left alone.
+    ///
+    /// Returns [`VmmError::OutOfMemory`] if a split needs a page table frame
+    /// and none is available; pages before that point stay unmapped.
+    pub fn unmap_user_range(
+        &self,
+        base: VirtAddr,
+        page_count: usize,
+        alloc: &mut (impl FrameAllocator<Size4KiB> + FrameDeallocator<Size4KiB>),
+        owns_frames: bool,
+    ) -> Result<(), VmmError> {
+        let mut i = 0;
+        while i < page_count {
+            let vaddr = base + (i as u64) * 0x1000;
+            match self.unmap_page(Page::containing_address(vaddr), owns_frames) {
+                Ok(frame) => {
+                    if owns_frames {
+                        // SAFETY: The frame is no longer mapped.
+                        unsafe { alloc.deallocate_frame(frame) };
+                    }
+                    i += 1;
+                }
+                Err(VmmError::SizeMismatch) => {
+                    let huge = Page::<Size2MiB>::containing_address(vaddr);
+                    if vaddr.is_aligned(HUGE_PAGE_SIZE) && page_count - i >= HUGE_PAGE_PAGES {
+                        let frame = self.unmap_huge_page(huge, owns_frames)?;
+                        if owns_frames {
+                            for j in 0..HUGE_PAGE_PAGES as u64 {
+                                let frame = PhysFrame::containing_address(
+                                    frame.start_address() + j * 0x1000,
+                                );
+                                // SAFETY: The huge page is no longer mapped.
+                                unsafe { alloc.deallocate_frame(frame) };
+                            }
+                        }
+                        i += HUGE_PAGE_PAGES;
+                    } else {
+                        self.split_user_huge_page(huge, alloc)?;
+                    }
+                }
+                Err(_) => i += 1,
+            }
+        }
+        Ok(())
+    }
 
     /// Updates the protection flags for a page-aligned range of pages.
     ///
     /// `base` must be page-aligned. `page_count` is the number of 4 KiB pages
     /// to update. `flags` replaces the flags on every page in the range; the
-    /// `USER` flag is always preserved.
+    /// `USER` flag is always preserved. A huge page that is only partially
+    /// covered is split first, taking a page table frame from `alloc`.
     ///
-    /// Returns `Ok(())` if all pages were updated, or `Err(())` if any page
-    /// in the range is not currently mapped.
+    /// Returns [`VmmError::NotMapped`] if any page in the range is not
+    /// currently mapped, or [`VmmError::OutOfMemory`] if a split fails.
     pub fn protect_range(
         &self,
         base: VirtAddr,
         page_count: usize,
         flags: MapFlags,
-    ) -> Result<(), ()> {
+        alloc: &mut (impl FrameAllocator<Size4KiB> + FrameDeallocator<Size4KiB>),
+    ) -> Result<(), VmmError> {
         let flags = flags | MapFlags::USER;
-        for i in 0..page_count {
+        let mut i = 0;
+        while i < page_count {
             let vaddr = base + (i as u64) * 0x1000;
             let page = Page::<Size4KiB>::containing_address(vaddr);
             // SAFETY: The AddressSpace owns its root page table; `root_phys`
             // is valid. We only update flags on pages that are already mapped.
-            let flush = unsafe {
-                self.mapper
-                    .update_flags(self.root_phys, page, flags)
-                    .map_err(|_| ())?
+            let result = unsafe {
+                <M as PageMapper<Size4KiB>>::update_flags(&self.mapper, self.root_phys, page, flags)
             };
-            flush.flush();
+            match result {
+                Ok(flush) => {
+                    flush.flush();
+                    i += 1;
+                }
+                Err(UnmapError::SizeMismatch) => {
+                    let huge = Page::<Size2MiB>::containing_address(vaddr);
+                    if vaddr.is_aligned(HUGE_PAGE_SIZE) && page_count - i >= HUGE_PAGE_PAGES {
+                        // SAFETY: As above; the huge page is mapped.
+                        let flush = unsafe {
+                            <M as PageMapper<Size2MiB>>::update_flags(
+                                &self.mapper,
+                                self.root_phys,
+                                huge,
+                                flags,
+                            )
+                            .map_err(unmap_error)?
+                        };
+                        flush.flush();
+                        i += HUGE_PAGE_PAGES;
+                    } else {
+                        self.split_user_huge_page(huge, alloc)?;
+                    }
+                }
+                Err(UnmapError::NotMapped) => {
+                    self.invalidated();
+                    return Err(VmmError::NotMapped);
+                }
+            }
         }
+        self.invalidated();
         Ok(())
     }
 }
 
+/// Converts a mapper [`UnmapError`] into the corresponding [`VmmError`].
+fn unmap_error(e: UnmapError) -> VmmError {
+    match e {
+        UnmapError::NotMapped => VmmError::NotMapped,
+        UnmapError::SizeMismatch => VmmError::SizeMismatch,
+    }
+}
+
 impl<M: PageMapper<Size4KiB> + PageTranslator> Drop for AddressSpace<M> {
     fn drop(&mut self) {
         let frame = PhysFrame::containing_address(self.root_phys);
         (self.dealloc_fn)(frame);
+        if let Some(shadow) = self.shadow_root {
+            (self.dealloc_fn)(PhysFrame::containing_address(shadow));
+        }
     }
 }
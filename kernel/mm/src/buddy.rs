@@ -0,0 +1,696 @@
+//! Buddy physical frame allocator.
+//!
+//! Free memory is kept as naturally aligned blocks of `2^order` frames, with
+//! `order` in `0..=MAX_ORDER`. Each order has a doubly linked free list
+//! threaded through a per-frame metadata table, so a freed block can locate
+//! its buddy, unlink it in O(1), and coalesce into the next order. Allocation
+//! takes the smallest block that is large enough and splits it down.
+//!
+//! Because blocks are naturally aligned, a power-of-two allocation of
+//! `2^k` frames is always `2^k * 4 KiB` aligned — order 9 yields a 2 MiB
+//! frame suitable for a huge page mapping.
+//!
+//! The metadata table (12 bytes per frame) lives in HHDM-accessible memory
+//! carved out of the first usable region large enough to hold it, just like
+//! the bitmap of [`BitmapAllocator`](crate::pmm::BitmapAllocator).
+
+use hadron_core::addr::{PhysAddr, VirtAddr};
+use hadron_core::paging::{PhysFrame, Size2MiB, Size4KiB};
+
+use crate::kasan;
+use crate::pmm::{FRAME_SIZE, check_page_poison, poison_page};
+use crate::{FrameAllocator, FrameDeallocator, PhysMemoryRegion, PmmError};
+
+/// Largest block order managed by the allocator (`2^10` frames = 4 MiB).
+pub const MAX_ORDER: usize = 10;
+
+/// Block order of a 2 MiB huge page (`2^9` frames).
+pub const HUGE_PAGE_ORDER: usize = 9;
+
+/// Number of distinct block orders.
+const NUM_ORDERS: usize = MAX_ORDER + 1;
+
+/// Null link in the intrusive free lists.
+const NIL: u32 = u32::MAX;
+
+/// State of a single frame in the metadata table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum FrameState {
+    /// Not managed by the allocator (hole, firmware, or allocator metadata).
+    Reserved,
+    /// Handed out to a caller.
+    Allocated,
+    /// First frame of a free block; `order` and the list links are valid.
+    FreeHead,
+    /// Any other frame of a free block.
+    FreeTail,
+}
+
+/// Per-frame metadata.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+struct FrameMeta {
+    /// Next free block of the same order (valid for `FreeHead` only).
+    next: u32,
+    /// Previous free block of the same order (valid for `FreeHead` only).
+    prev: u32,
+    /// Block order (valid for `FreeHead` only).
+    order: u8,
+    /// Frame state.
+    state: FrameState,
+}
+
+impl FrameMeta {
+    const RESERVED: Self = Self {
+        next: NIL,
+        prev: NIL,
+        order: 0,
+        state: FrameState::Reserved,
+    };
+}
+
+/// A binary buddy physical frame allocator.
+///
+/// Like [`BitmapAllocator`](crate::pmm::BitmapAllocator), all mutation goes
+/// through `&mut self` and the global `PMM` lock provides thread safety.
+pub struct BuddyAllocator {
+    /// Per-frame metadata, indexed by frame number relative to `base_frame`.
+    meta: &'static mut [FrameMeta],
+    /// Absolute frame number of `meta[0]`, aligned to `2^MAX_ORDER`.
+    base_frame: usize,
+    /// Head of the free list for each order.
+    free_heads: [u32; NUM_ORDERS],
+    /// Number of free blocks on each order's list.
+    free_blocks: [usize; NUM_ORDERS],
+    /// Number of usable frames managed by the allocator.
+    total_frames: usize,
+    /// Number of currently free frames.
+    free_count: usize,
+    /// HHDM offset for physical-to-virtual translation (used by page poisoning).
+    hhdm_offset: VirtAddr,
+}
+
+impl BuddyAllocator {
+    /// Creates a new buddy allocator from a slice of physical memory regions.
+    ///
+    /// # Safety
+    ///
+    /// - `hhdm_offset` must be the correct HHDM offset.
+    /// - `regions` must accurately describe physical memory.
+    /// - The usable regions must not be in use by anything else.
+    pub unsafe fn new(
+        regions: &[PhysMemoryRegion],
+        hhdm_offset: VirtAddr,
+    ) -> Result<Self, PmmError> {
+        // 1. Find the span of usable frames. Only frames within it need
+        // metadata; the base is aligned down so that block alignment relative
+        // to the table equals physical alignment.
+        let (first_frame, end_frame) = usable_span(regions).ok_or(PmmError::OutOfMemory)?;
+        let base_frame = first_frame & !((1 << MAX_ORDER) - 1);
+        let table_len = end_frame - base_frame;
+        if table_len >= NIL as usize {
+            return Err(PmmError::InvalidFrame);
+        }
+
+        let meta_bytes = table_len * core::mem::size_of::<FrameMeta>();
+        let meta_frames = (meta_bytes as u64).div_ceil(FRAME_SIZE) as usize;
+
+        // 2. Find the first usable region large enough for the table.
+        let meta_start_frame = regions
+            .iter()
+            .filter(|r| r.usable)
+            .map(region_frames)
+            .find(|&(start, end)| end.saturating_sub(start) >= meta_frames)
+            .map(|(start, _)| start)
+            .ok_or(PmmError::NoBitmapRegion)?;
+
+        // 3. Map the table via HHDM.
+        // SAFETY: The HHDM offset is valid, and the region is usable, unused,
+        // and large enough for `table_len` entries.
+        let meta = unsafe {
+            let ptr =
+                (hhdm_offset + meta_start_frame as u64 * FRAME_SIZE).as_mut_ptr::<FrameMeta>();
+            core::slice::from_raw_parts_mut(ptr, table_len)
+        };
+        meta.fill(FrameMeta::RESERVED);
+
+        Ok(Self::build(
+            meta,
+            base_frame,
+            regions,
+            meta_start_frame..meta_start_frame + meta_frames,
+            hhdm_offset,
+        ))
+    }
+
+    /// Builds the allocator over `meta`, freeing every usable frame outside
+    /// of `reserved` (absolute frame numbers).
+    fn build(
+        meta: &'static mut [FrameMeta],
+        base_frame: usize,
+        regions: &[PhysMemoryRegion],
+        reserved: core::ops::Range<usize>,
+        hhdm_offset: VirtAddr,
+    ) -> Self {
+        let mut allocator = Self {
+            meta,
+            base_frame,
+            free_heads: [NIL; NUM_ORDERS],
+            free_blocks: [0; NUM_ORDERS],
+            total_frames: reserved.len(),
+            free_count: 0,
+            hhdm_offset,
+        };
+
+        let table_end = base_frame + allocator.meta.len();
+        for region in regions.iter().filter(|r| r.usable) {
+            let (start, end) = region_frames(region);
+            let (start, end) = (start.max(base_frame), end.min(table_end));
+            if start >= end {
+                continue;
+            }
+            // Split the region around the reserved range.
+            let below = (start, end.min(reserved.start));
+            let above = (start.max(reserved.end), end);
+            for (s, e) in [below, above] {
+                if s < e {
+                    allocator.total_frames += e - s;
+                    allocator.free_range(s - base_frame, e - base_frame);
+                }
+            }
+        }
+        allocator
+    }
+
+    // -- Free list primitives ----------------------------------------------
+
+    /// Pushes the free block at `idx` onto the list for `order`.
+    fn push(&mut self, idx: usize, order: usize) {
+        let head = self.free_heads[order];
+        self.meta[idx] = FrameMeta {
+            next: head,
+            prev: NIL,
+            order: order as u8,
+            state: FrameState::FreeHead,
+        };
+        if head != NIL {
+            self.meta[head as usize].prev = idx as u32;
+        }
+        self.free_heads[order] = idx as u32;
+        self.free_blocks[order] += 1;
+    }
+
+    /// Unlinks the free block at `idx` from the list for `order`.
+    fn unlink(&mut self, idx: usize, order: usize) {
+        let FrameMeta { next, prev, .. } = self.meta[idx];
+        if prev == NIL {
+            self.free_heads[order] = next;
+        } else {
+            self.meta[prev as usize].next = next;
+        }
+        if next != NIL {
+            self.meta[next as usize].prev = prev;
+        }
+        self.meta[idx].state = FrameState::FreeTail;
+        self.free_blocks[order] -= 1;
+    }
+
+    /// Inserts the block `idx..idx + 2^order` into the free lists, merging it
+    /// with its buddy as long as the buddy is a free block of the same order.
+    fn free_block(&mut self, mut idx: usize, mut order: usize) {
+        for m in &mut self.meta[idx..idx + (1 << order)] {
+            m.state = FrameState::FreeTail;
+        }
+        while order < MAX_ORDER {
+            let buddy = idx ^ (1 << order);
+            let mergeable = self
+                .meta
+                .get(buddy)
+                .is_some_and(|m| m.state == FrameState::FreeHead && usize::from(m.order) == order);
+            if !mergeable {
+                break;
+            }
+            self.unlink(buddy, order);
+            idx = idx.min(buddy);
+            order += 1;
+        }
+        self.push(idx, order);
+    }
+
+    /// Frees the (arbitrarily aligned) table range `start..end` by splitting
+    /// it into maximal naturally aligned blocks.
+    fn free_range(&mut self, start: usize, end: usize) {
+        let mut idx = start;
+        while idx < end {
+            let mut order = (idx.trailing_zeros() as usize).min(MAX_ORDER);
+            while idx + (1 << order) > end {
+                order -= 1;
+            }
+            self.free_block(idx, order);
+            idx += 1 << order;
+        }
+        self.free_count += end - start;
+    }
+
+    /// Removes a block of exactly `order` from the free lists, splitting a
+    /// larger block if necessary, and marks its frames allocated.
+    fn take_block(&mut self, order: usize) -> Option<usize> {
+        let mut current = (order..NUM_ORDERS).find(|&o| self.free_heads[o] != NIL)?;
+        let idx = self.free_heads[current] as usize;
+        self.unlink(idx, current);
+
+        // Return the upper halves to the lower-order lists.
+        while current > order {
+            current -= 1;
+            self.push(idx + (1 << current), current);
+        }
+
+        for m in &mut self.meta[idx..idx + (1 << order)] {
+            m.state = FrameState::Allocated;
+        }
+        self.free_count -= 1 << order;
+        Some(idx)
+    }
+
+    // -- Address translation -----------------------------------------------
+
+    /// Converts a table index to a physical frame.
+    fn frame_at(&self, idx: usize) -> PhysFrame<Size4KiB> {
+        PhysFrame::containing_address(PhysAddr::new((self.base_frame + idx) as u64 * FRAME_SIZE))
+    }
+
+    /// Converts a physical frame to a table index, if it is tracked.
+    fn index_of(&self, frame: PhysFrame<Size4KiB>) -> Option<usize> {
+        let abs = (frame.start_address().as_u64() / FRAME_SIZE) as usize;
+        abs.checked_sub(self.base_frame)
+            .filter(|&idx| idx < self.meta.len())
+    }
+
+    /// Verifies the poison pattern of `count` frames starting at `idx` and
+    /// unpoisons their KASAN shadow.
+    fn check_poison(&self, idx: usize, count: usize) {
+        // NOTE: No logging here — PMM lock is held and logging would
+        // acquire LOGGER, creating a PMM → LOGGER lock ordering violation.
+        if cfg!(hadron_debug_pmm_poison) {
+            for i in idx..idx + count {
+                let phys = self.frame_at(i).start_address();
+                if !check_page_poison(self.hhdm_offset, phys) {
+                    panic!(
+                        "PMM: page at {:#x} modified after free (use-after-free)",
+                        phys.as_u64()
+                    );
+                }
+            }
+        }
+        if cfg!(hadron_kasan) {
+            let virt = self.hhdm_offset + self.frame_at(idx).start_address().as_u64();
+            kasan::unpoison(virt.as_u64() as usize, count * FRAME_SIZE as usize);
+        }
+    }
+
+    /// Validates that `idx..idx + count` is allocated and poisons it.
+    fn prepare_free(&self, idx: usize, count: usize) -> Result<(), PmmError> {
+        if idx + count > self.meta.len() {
+            return Err(PmmError::InvalidFrame);
+        }
+        for i in idx..idx + count {
+            match self.meta[i].state {
+                FrameState::Allocated => {}
+                FrameState::FreeHead | FrameState::FreeTail => {
+                    debug_assert!(
+                        false,
+                        "double free of frame {:#x}",
+                        self.frame_at(i).start_address().as_u64()
+                    );
+                    return Err(PmmError::InvalidFrame);
+                }
+                FrameState::Reserved => return Err(PmmError::InvalidFrame),
+            }
+        }
+        // Poison the freed pages so use-after-free is detectable on
+        // re-allocation.
+        if cfg!(hadron_debug_pmm_poison) {
+            for i in idx..idx + count {
+                poison_page(self.hhdm_offset, self.frame_at(i).start_address());
+            }
+        }
+        if cfg!(hadron_kasan) {
+            let virt = self.hhdm_offset + self.frame_at(idx).start_address().as_u64();
+            kasan::poison(
+                virt.as_u64() as usize,
+                count * FRAME_SIZE as usize,
+                kasan::PAGE_FREE,
+            );
+        }
+        Ok(())
+    }
+
+    // -- Public API --------------------------------------------------------
+
+    /// Allocates a single 4 KiB physical frame.
+    pub fn allocate_frame(&mut self) -> Option<PhysFrame<Size4KiB>> {
+        self.allocate_block(0)
+    }
+
+    /// Allocates a naturally aligned block of `2^order` frames.
+    ///
+    /// Returns the first frame; the block must be freed with
+    /// [`deallocate_block`](Self::deallocate_block) (or
+    /// [`deallocate_frames`](Self::deallocate_frames) with the same count).
+    pub fn allocate_block(&mut self, order: usize) -> Option<PhysFrame<Size4KiB>> {
+        if order > MAX_ORDER {
+            return None;
+        }
+        let idx = self.take_block(order)?;
+        self.check_poison(idx, 1 << order);
+        Some(self.frame_at(idx))
+    }
+
+    /// Allocates `count` contiguous 4 KiB physical frames. Returns the first frame.
+    ///
+    /// The run is aligned to the next power of two of `count`. Frames beyond
+    /// `count` in the underlying block are returned to the free lists
+    /// immediately.
+    pub fn allocate_frames(&mut self, count: usize) -> Option<PhysFrame<Size4KiB>> {
+        if count == 0 {
+            return None;
+        }
+        let order = count.next_power_of_two().trailing_zeros() as usize;
+        if order > MAX_ORDER || self.free_count < count {
+            return None;
+        }
+        let idx = self.take_block(order)?;
+        let block_end = idx + (1 << order);
+        if idx + count < block_end {
+            self.free_range(idx + count, block_end);
+        }
+        self.check_poison(idx, count);
+        Some(self.frame_at(idx))
+    }
+
+    /// Deallocates a single 4 KiB physical frame.
+    ///
+    /// # Safety
+    ///
+    /// The frame must have been previously allocated by this allocator and
+    /// must not be in use.
+    pub unsafe fn deallocate_frame(&mut self, frame: PhysFrame<Size4KiB>) -> Result<(), PmmError> {
+        // SAFETY: Forwarded from the caller.
+        unsafe { self.deallocate_frames(frame, 1) }
+    }
+
+    /// Deallocates a block previously returned by
+    /// [`allocate_block`](Self::allocate_block) with the same `order`.
+    ///
+    /// # Safety
+    ///
+    /// The block must have been previously allocated by this allocator and
+    /// must not be in use.
+    pub unsafe fn deallocate_block(
+        &mut self,
+        frame: PhysFrame<Size4KiB>,
+        order: usize,
+    ) -> Result<(), PmmError> {
+        if order > MAX_ORDER {
+            return Err(PmmError::InvalidFrame);
+        }
+        // SAFETY: Forwarded from the caller.
+        unsafe { self.deallocate_frames(frame, 1 << order) }
+    }
+
+    /// Deallocates `count` contiguous 4 KiB physical frames starting at `frame`.
+    ///
+    /// The range need not match a single allocation; every frame in it is
+    /// returned and coalesced with free neighbours.
+    ///
+    /// # Safety
+    ///
+    /// All frames in the range must have been previously allocated by this
+    /// allocator and must not be in use.
+    pub unsafe fn deallocate_frames(
+        &mut self,
+        frame: PhysFrame<Size4KiB>,
+        count: usize,
+    ) -> Result<(), PmmError> {
+        let idx = self.index_of(frame).ok_or(PmmError::InvalidFrame)?;
+        self.prepare_free(idx, count)?;
+        self.free_range(idx, idx + count);
+        Ok(())
+    }
+
+    /// Returns the number of free frames.
+    pub fn free_frames(&self) -> usize {
+        self.free_count
+    }
+
+    /// Returns the total number of usable frames, including the frames
+    /// holding the allocator's own metadata.
+    pub fn total_frames(&self) -> usize {
+        self.total_frames
+    }
+
+    /// Returns the number of free blocks of the given order.
+    pub fn free_blocks(&self, order: usize) -> usize {
+        self.free_blocks.get(order).copied().unwrap_or(0)
+    }
+}
+
+/// Returns the frame range `[start, end)` fully covered by `region`.
+fn region_frames(region: &PhysMemoryRegion) -> (usize, usize) {
+    let start = region.start.as_u64().div_ceil(FRAME_SIZE) as usize;
+    let end = ((region.start.as_u64() + region.size) / FRAME_SIZE) as usize;
+    (start, end.max(start))
+}
+
+/// Returns the frame span `[first, end)` covering all usable regions.
+fn usable_span(regions: &[PhysMemoryRegion]) -> Option<(usize, usize)> {
+    regions
+        .iter()
+        .filter(|r| r.usable)
+        .map(region_frames)
+        .filter(|&(start, end)| start < end)
+        .fold(None, |span, (start, end)| match span {
+            None => Some((start, end)),
+            Some((s, e)) => Some((s.min(start), e.max(end))),
+        })
+}
+
+/// Wrapper that implements `FrameAllocator` / `FrameDeallocator` by
+/// forwarding to `&mut BuddyAllocator`.
+pub struct BuddyFrameAllocRef<'a>(pub &'a mut BuddyAllocator);
+
+unsafe impl FrameAllocator<Size4KiB> for BuddyFrameAllocRef<'_> {
+    fn allocate_frame(&mut self) -> Option<PhysFrame<Size4KiB>> {
+        self.0.allocate_frame()
+    }
+}
+
+unsafe impl FrameDeallocator<Size4KiB> for BuddyFrameAllocRef<'_> {
+    unsafe fn deallocate_frame(&mut self, frame: PhysFrame<Size4KiB>) {
+        let _ = unsafe { self.0.deallocate_frame(frame) };
+    }
+}
+
+unsafe impl FrameAllocator<Size2MiB> for BuddyFrameAllocRef<'_> {
+    fn allocate_frame(&mut self) -> Option<PhysFrame<Size2MiB>> {
+        let frame = self.0.allocate_block(HUGE_PAGE_ORDER)?;
+        Some(PhysFrame::containing_address(frame.start_address()))
+    }
+}
+
+unsafe impl FrameDeallocator<Size2MiB> for BuddyFrameAllocRef<'_> {
+    unsafe fn deallocate_frame(&mut self, frame: PhysFrame<Size2MiB>) {
+        let frame = PhysFrame::containing_address(frame.start_address());
+        let _ = unsafe { self.0.deallocate_block(frame, HUGE_PAGE_ORDER) };
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Creates an allocator over the given `(start_frame, frame_count)`
+    /// usable regions with a heap-backed metadata table.
+    pub(crate) fn allocator(regions: &[(usize, usize)]) -> BuddyAllocator {
+        let regions: Vec<PhysMemoryRegion> = regions
+            .iter()
+            .map(|&(start, count)| PhysMemoryRegion {
+                start: PhysAddr::new(start as u64 * FRAME_SIZE),
+                size: count as u64 * FRAME_SIZE,
+                usable: true,
+            })
+            .collect();
+        let (first, end) = usable_span(&regions).unwrap();
+        let base = first & !((1 << MAX_ORDER) - 1);
+        let meta = Vec::leak(vec![FrameMeta::RESERVED; end - base]);
+        BuddyAllocator::build(meta, base, &regions, 0..0, VirtAddr::new(0))
+    }
+
+    fn frame_number(frame: PhysFrame<Size4KiB>) -> usize {
+        frame_number_of(frame.start_address())
+    }
+
+    fn frame_number_of(addr: PhysAddr) -> usize {
+        (addr.as_u64() / FRAME_SIZE) as usize
+    }
+
+    #[test]
+    fn init_builds_maximal_blocks() {
+        let a = allocator(&[(0, 2048)]);
+        assert_eq!(a.free_frames(), 2048);
+        assert_eq!(a.total_frames(), 2048);
+        assert_eq!(a.free_blocks(MAX_ORDER), 2);
+        assert!((0..MAX_ORDER).all(|o| a.free_blocks(o) == 0));
+    }
+
+    #[test]
+    fn unaligned_region_is_split() {
+        // Frames 3..10: blocks [3], [4..8], [8..10].
+        let a = allocator(&[(3, 7)]);
+        assert_eq!(a.free_frames(), 7);
+        assert_eq!(a.free_blocks(0), 1);
+        assert_eq!(a.free_blocks(1), 1);
+        assert_eq!(a.free_blocks(2), 1);
+    }
+
+    #[test]
+    fn single_frames_are_unique() {
+        let mut a = allocator(&[(0, 64)]);
+        let mut seen = Vec::new();
+        while let Some(frame) = a.allocate_frame() {
+            let n = frame_number(frame);
+            assert!(n < 64);
+            assert!(!seen.contains(&n));
+            seen.push(n);
+        }
+        assert_eq!(seen.len(), 64);
+        assert_eq!(a.free_frames(), 0);
+    }
+
+    #[test]
+    fn free_coalesces_back_to_max_order() {
+        let mut a = allocator(&[(0, 1024)]);
+        let frames: Vec<_> = (0..1024).map(|_| a.allocate_frame().unwrap()).collect();
+        assert_eq!(a.free_frames(), 0);
+        for frame in frames.into_iter().rev() {
+            unsafe { a.deallocate_frame(frame).unwrap() };
+        }
+        assert_eq!(a.free_frames(), 1024);
+        assert_eq!(a.free_blocks(MAX_ORDER), 1);
+        assert!((0..MAX_ORDER).all(|o| a.free_blocks(o) == 0));
+    }
+
+    #[test]
+    fn blocks_are_naturally_aligned() {
+        let mut a = allocator(&[(1, 4095)]);
+        for order in [0, 3, 9, 5] {
+            let frame = a.allocate_block(order).unwrap();
+            assert_eq!(frame_number(frame) % (1 << order), 0, "order {order}");
+        }
+    }
+
+    #[test]
+    fn huge_frames_via_alloc_ref() {
+        // Frames 1..2048: only [512, 1024, 1536] start aligned 512-frame blocks.
+        let mut a = allocator(&[(1, 2047)]);
+        let mut alloc = BuddyFrameAllocRef(&mut a);
+        let huge: Vec<PhysFrame<Size2MiB>> =
+            core::iter::from_fn(|| alloc.allocate_frame()).collect();
+        assert_eq!(huge.len(), 3);
+        assert!(
+            huge.iter()
+                .all(|f| frame_number_of(f.start_address()) % 512 == 0)
+        );
+        for frame in huge {
+            unsafe { FrameDeallocator::<Size2MiB>::deallocate_frame(&mut alloc, frame) };
+        }
+        assert_eq!(a.free_frames(), 2047);
+    }
+
+    #[test]
+    fn allocate_frames_returns_excess() {
+        let mut a = allocator(&[(0, 16)]);
+        let frame = a.allocate_frames(5).unwrap();
+        assert_eq!(frame_number(frame) % 8, 0);
+        assert_eq!(a.free_frames(), 11);
+        unsafe { a.deallocate_frames(frame, 5).unwrap() };
+        assert_eq!(a.free_frames(), 16);
+        assert_eq!(a.free_blocks(4), 1);
+    }
+
+    #[test]
+    fn partial_free_of_block() {
+        let mut a = allocator(&[(0, 16)]);
+        let frame = a.allocate_block(3).unwrap();
+        let tail = PhysFrame::containing_address(frame.start_address() + 4 * FRAME_SIZE);
+        unsafe { a.deallocate_frames(tail, 4).unwrap() };
+        assert_eq!(a.free_frames(), 12);
+        unsafe { a.deallocate_frames(frame, 4).unwrap() };
+        assert_eq!(a.free_blocks(4), 1);
+    }
+
+    #[test]
+    fn fragmented_memory_rejects_large_request() {
+        let mut a = allocator(&[(0, 16)]);
+        let frames: Vec<_> = (0..16).map(|_| a.allocate_frame().unwrap()).collect();
+        // Free every other frame: 8 free frames, none contiguous.
+        for frame in frames.iter().step_by(2) {
+            unsafe { a.deallocate_frame(*frame).unwrap() };
+        }
+        assert_eq!(a.free_frames(), 8);
+        assert!(a.allocate_frames(2).is_none());
+        assert!(a.allocate_frame().is_some());
+    }
+
+    #[test]
+    fn too_large_requests_fail() {
+        let mut a = allocator(&[(0, 4096)]);
+        assert!(a.allocate_frames(0).is_none());
+        assert!(a.allocate_frames((1 << MAX_ORDER) + 1).is_none());
+        assert!(a.allocate_block(MAX_ORDER + 1).is_none());
+        assert_eq!(a.free_frames(), 4096);
+    }
+
+    #[test]
+    fn invalid_frees_are_rejected() {
+        let mut a = allocator(&[(16, 16)]);
+        let outside = PhysFrame::containing_address(PhysAddr::new(4096 * FRAME_SIZE));
+        assert_eq!(
+            unsafe { a.deallocate_frame(outside) },
+            Err(PmmError::InvalidFrame)
+        );
+        // Frame 0 lies in the table (base is aligned down) but is reserved.
+        let reserved = PhysFrame::containing_address(PhysAddr::new(0));
+        assert_eq!(
+            unsafe { a.deallocate_frame(reserved) },
+            Err(PmmError::InvalidFrame)
+        );
+    }
+
+    #[test]
+    fn holes_are_never_allocated() {
+        let mut a = allocator(&[(0, 8), (16, 8)]);
+        assert_eq!(a.free_frames(), 16);
+        while let Some(frame) = a.allocate_frame() {
+            let n = frame_number(frame);
+            assert!(n < 8 || (16..24).contains(&n));
+        }
+    }
+
+    #[test]
+    fn reserved_range_is_excluded() {
+        let regions = [PhysMemoryRegion {
+            start: PhysAddr::new(0),
+            size: 32 * FRAME_SIZE,
+            usable: true,
+        }];
+        let meta = Vec::leak(vec![FrameMeta::RESERVED; 32]);
+        let mut a = BuddyAllocator::build(meta, 0, &regions, 4..6, VirtAddr::new(0));
+        assert_eq!(a.total_frames(), 32);
+        assert_eq!(a.free_frames(), 30);
+        while let Some(frame) = a.allocate_frame() {
+            assert!(!(4..6).contains(&frame_number(frame)));
+        }
+    }
+}
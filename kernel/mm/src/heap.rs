@@ -3,11 +3,18 @@
 //! First-fit free list sorted by address for O(1) coalescing on dealloc.
 //! Supports a growth callback so the heap can request more pages from the
 //! VMM/PMM when it runs out.
+//!
+//! The global allocator puts the [slab caches](crate::slab) in front of the
+//! linked list, so only large or unusual allocations take the heap lock.
 
 use core::alloc::{GlobalAlloc, Layout};
-use core::ptr;
+use core::ptr::{self, NonNull};
 
 use hadron_core::sync::SpinLock;
+use hadron_core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::kasan;
+use crate::layout::HEAP_MAX_SIZE;
 
 /// Minimum block size (must fit a `FreeBlock` header).
 const MIN_BLOCK_SIZE: usize = 32;
@@ -263,6 +270,10 @@ impl LinkedListAllocator {
             (*block).next = ptr::null_mut();
         }
         inner.head = block;
+
+        if cfg!(hadron_kasan) {
+            kasan::poison(heap_start, heap_size, kasan::HEAP_UNALLOCATED);
+        }
     }
 
     /// Registers a callback that the allocator uses to request more heap pages.
@@ -425,15 +436,7 @@ unsafe impl GlobalAlloc for LinkedListAllocator {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
         let user_size = layout.size().max(MIN_BLOCK_SIZE);
         let align = layout.align().max(BLOCK_ALIGN);
-
-        // When heap poisoning is enabled, allocate extra space for red zones.
-        // front_pad is aligned up to guarantee the user pointer stays aligned.
-        let (alloc_size, front_pad) = if cfg!(hadron_debug_heap_poison) {
-            let fp = align_up(poison::REDZONE_SIZE, align);
-            (fp + user_size + poison::REDZONE_SIZE, fp)
-        } else {
-            (user_size, 0)
-        };
+        let (alloc_size, front_pad) = block_layout(user_size, align);
 
         let mut inner = self.inner.lock_unchecked();
 
@@ -444,11 +447,8 @@ unsafe impl GlobalAlloc for LinkedListAllocator {
                 drop(inner);
                 track_alloc(user_size);
             }
-            if cfg!(hadron_debug_heap_poison) {
-                // SAFETY: addr points to a valid region of at least alloc_size bytes.
-                return unsafe { poison::fill_alloc(addr, user_size, front_pad) };
-            }
-            return addr as *mut u8;
+            // SAFETY: addr points to a valid region of at least alloc_size bytes.
+            return unsafe { prepare_block(addr, alloc_size, front_pad, user_size, layout.size()) };
         }
 
         // Try growing the heap.
@@ -458,6 +458,9 @@ unsafe impl GlobalAlloc for LinkedListAllocator {
             drop(inner); // Release lock before calling grow_fn (it may need the PMM lock).
 
             if let Some((ptr, actual_size)) = grow(min_grow) {
+                if cfg!(hadron_kasan) {
+                    kasan::poison(ptr as usize, actual_size, kasan::HEAP_UNALLOCATED);
+                }
                 let mut inner = self.inner.lock_unchecked();
                 unsafe {
                     Self::add_free_region(&mut inner, ptr as usize, actual_size);
@@ -470,11 +473,10 @@ unsafe impl GlobalAlloc for LinkedListAllocator {
                         drop(inner);
                         track_alloc(user_size);
                     }
-                    if cfg!(hadron_debug_heap_poison) {
-                        // SAFETY: addr points to a valid region of at least alloc_size bytes.
-                        return unsafe { poison::fill_alloc(addr, user_size, front_pad) };
-                    }
-                    return addr as *mut u8;
+                    // SAFETY: addr points to a valid region of at least alloc_size bytes.
+                    return unsafe {
+                        prepare_block(addr, alloc_size, front_pad, user_size, layout.size())
+                    };
                 }
             }
         }
@@ -485,17 +487,17 @@ unsafe impl GlobalAlloc for LinkedListAllocator {
     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
         let user_size = layout.size().max(MIN_BLOCK_SIZE);
         let align = layout.align().max(BLOCK_ALIGN);
+        let (block_size, front_pad) = block_layout(user_size, align);
+        let block_addr = ptr as usize - front_pad;
 
-        // When heap poisoning is enabled, recover the full block (including red zones).
-        let (block_addr, block_size) = if cfg!(hadron_debug_heap_poison) {
-            let front_pad = align_up(poison::REDZONE_SIZE, align);
+        // When heap poisoning is enabled, verify the red zones.
+        if cfg!(hadron_debug_heap_poison) {
             // SAFETY: ptr was returned by our alloc with the same layout.
             unsafe { poison::check_and_fill_dealloc(ptr, user_size, front_pad) };
-            let base = ptr as usize - front_pad;
-            (base, front_pad + user_size + poison::REDZONE_SIZE)
-        } else {
-            (ptr as usize, user_size)
-        };
+        }
+        if cfg!(hadron_kasan) {
+            kasan::poison(block_addr, block_size, kasan::HEAP_FREE);
+        }
 
         if cfg!(hadron_debug_alloc_track) {
             track_dealloc(user_size);
@@ -510,26 +512,150 @@ unsafe impl GlobalAlloc for LinkedListAllocator {
     }
 }
 
+/// Returns `(block_size, front_pad)` for a block serving `user_size` bytes
+/// at `align`.
+///
+/// Heap poisoning adds red zones on both sides; front_pad is aligned up to
+/// guarantee the user pointer stays aligned. KASAN reuses the back red zone,
+/// or adds one of its own.
+fn block_layout(user_size: usize, align: usize) -> (usize, usize) {
+    if cfg!(hadron_debug_heap_poison) {
+        let fp = align_up(poison::REDZONE_SIZE, align);
+        (fp + user_size + poison::REDZONE_SIZE, fp)
+    } else if cfg!(hadron_kasan) {
+        (user_size + kasan::REDZONE_SIZE, 0)
+    } else {
+        (user_size, 0)
+    }
+}
+
+/// Prepares a block taken from the free list for its user: fills the red
+/// zones and marks the KASAN shadow. Returns the user pointer.
+///
+/// `layout_size` is the size the caller asked for; `user_size` is that size
+/// raised to the minimum block size.
+///
+/// # Safety
+///
+/// `addr` must point to a valid, writable region of `block_size` bytes laid
+/// out by [`block_layout`].
+unsafe fn prepare_block(
+    addr: usize,
+    block_size: usize,
+    front_pad: usize,
+    user_size: usize,
+    layout_size: usize,
+) -> *mut u8 {
+    let user_ptr = if cfg!(hadron_debug_heap_poison) {
+        // SAFETY: Forwarded from the caller.
+        unsafe { poison::fill_alloc(addr, user_size, front_pad) }
+    } else {
+        addr as *mut u8
+    };
+    if cfg!(hadron_kasan) {
+        kasan::mark_alloc(
+            addr,
+            block_size,
+            user_ptr as usize,
+            layout_size,
+            kasan::HEAP_REDZONE,
+        );
+    }
+    user_ptr
+}
+
 #[inline]
 fn align_up(addr: usize, align: usize) -> usize {
     (addr + align - 1) & !(align - 1)
 }
 
+// ---------------------------------------------------------------------------
+// Global allocator: slab front-end over the linked-list heap
+// ---------------------------------------------------------------------------
+
+/// The kernel's global allocator.
+///
+/// Requests that match a slab cache (see [`crate::slab::cache_for`]) are
+/// served from it; everything else, and anything allocated before the slab
+/// layer is enabled, comes from the linked-list heap. Deallocation is routed
+/// by address: pointers inside the heap's virtual region go back to the
+/// linked list, all others are slab objects.
+///
+/// The slab layer is bypassed when heap poisoning or allocation tracking is
+/// enabled so that every allocation gets red zones and is counted.
+struct KernelAllocator {
+    /// Backing linked-list heap.
+    heap: LinkedListAllocator,
+    /// Base of the heap's virtual region (0 until [`init_raw`]).
+    heap_base: AtomicUsize,
+}
+
+impl KernelAllocator {
+    /// Returns `true` if `ptr` was allocated from the linked-list heap.
+    fn in_heap(&self, ptr: *mut u8) -> bool {
+        let base = self.heap_base.load(Ordering::Relaxed);
+        (ptr as usize).wrapping_sub(base) < HEAP_MAX_SIZE as usize
+    }
+}
+
+unsafe impl GlobalAlloc for KernelAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if !cfg!(hadron_debug_heap_poison)
+            && !cfg!(hadron_debug_alloc_track)
+            && let Some(cache) = crate::slab::cache_for(layout)
+            && let Some(obj) = cache.alloc()
+        {
+            // A `kmalloc-*` object may be larger than the request: the
+            // rest of it is out of bounds.
+            if cfg!(hadron_kasan) && layout.size() < cache.layout().size() {
+                let obj = obj.as_ptr() as usize;
+                kasan::mark_alloc(
+                    obj,
+                    cache.layout().size(),
+                    obj,
+                    layout.size(),
+                    kasan::SLAB_REDZONE,
+                );
+            }
+            return obj.as_ptr();
+        }
+        // SAFETY: Forwarded from the caller.
+        unsafe { self.heap.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if self.in_heap(ptr) {
+            // SAFETY: Forwarded from the caller.
+            unsafe { self.heap.dealloc(ptr, layout) };
+        } else if let Some(obj) = NonNull::new(ptr) {
+            // SAFETY: Everything outside the heap region is a slab object.
+            unsafe { crate::slab::free(obj) };
+        }
+    }
+}
+
 #[cfg_attr(target_os = "none", global_allocator)]
-static HEAP: LinkedListAllocator = LinkedListAllocator::new();
+static HEAP: KernelAllocator = KernelAllocator {
+    heap: LinkedListAllocator::new(),
+    heap_base: AtomicUsize::new(0),
+};
 
 /// Initializes the global heap allocator with a pre-mapped memory region.
 ///
+/// `heap_start` must be the base of the kernel heap's virtual region; all
+/// later growth must stay within [`HEAP_MAX_SIZE`] bytes of it.
+///
 /// # Safety
 ///
 /// `heap_start` must point to a mapped, zeroed region of `heap_size` bytes.
 pub unsafe fn init_raw(heap_start: usize, heap_size: usize) {
-    unsafe { HEAP.init(heap_start, heap_size) };
+    HEAP.heap_base.store(heap_start, Ordering::Relaxed);
+    unsafe { HEAP.heap.init(heap_start, heap_size) };
 }
 
 /// Registers a growth callback for the global heap allocator.
 pub fn register_grow_fn(f: fn(usize) -> Option<(*mut u8, usize)>) {
-    HEAP.register_grow_fn(f);
+    HEAP.heap.register_grow_fn(f);
 }
 
 // ---------------------------------------------------------------------------
@@ -0,0 +1,504 @@
+//! Kernel address sanitizer (KASAN) shadow memory.
+//!
+//! With `hadron_kasan`, gluon builds the kernel crates with
+//! `-Zsanitizer=kernel-address`, which makes the compiler call an
+//! `__asan_load*` / `__asan_store*` hook before every memory access. The
+//! kernel's runtime answers those hooks with [`check`], which consults a
+//! byte of shadow memory per [`GRANULE_SIZE`] bytes of kernel memory:
+//!
+//! - `0`: all eight bytes are accessible.
+//! - `1..=7`: only the first `n` bytes are accessible.
+//! - `0x80..=0xFF`: none are; the value says why (see [`BadAccess::kind`]).
+//!
+//! Only two windows have shadow: the kernel heap, grown in step with the
+//! heap itself, and the part of the HHDM that covers physical memory, which
+//! is where slab objects and PMM pages are accessed. Addresses outside them
+//! (the kernel image, stacks, MMIO, user memory) always pass.
+//!
+//! The heap, slab caches and PMM keep the shadow up to date: allocations are
+//! unpoisoned to their exact requested size, heap blocks carry a
+//! [`REDZONE_SIZE`] tail redzone, and freed memory is poisoned until it is
+//! reused. There is no quarantine, so a use-after-free is only caught while
+//! the memory has not been handed out again.
+//!
+//! This crate itself is built without instrumentation: the allocators keep
+//! their metadata inside poisoned memory, and the checks here must not
+//! recurse into the hooks. For the same reason this module uses
+//! `core::sync::atomic` directly rather than the `hadron_core` wrappers.
+
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::layout::{KASAN_SHADOW_MAX_SIZE, MemoryLayout};
+
+/// Bytes of kernel memory described by one shadow byte, as a shift.
+pub const GRANULE_SHIFT: u32 = 3;
+/// Bytes of kernel memory described by one shadow byte.
+pub const GRANULE_SIZE: usize = 1 << GRANULE_SHIFT;
+
+/// Size of the redzone the heap appends to every block.
+pub const REDZONE_SIZE: usize = 16;
+
+/// Shadow code: freed PMM page.
+pub const PAGE_FREE: u8 = 0xFF;
+/// Shadow code: heap memory that has never been allocated.
+pub const HEAP_UNALLOCATED: u8 = 0xFE;
+/// Shadow code: slab slot slack past the end of an object.
+pub const SLAB_REDZONE: u8 = 0xFC;
+/// Shadow code: freed slab object.
+pub const SLAB_FREE: u8 = 0xFB;
+/// Shadow code: heap block redzone or padding.
+pub const HEAP_REDZONE: u8 = 0xFA;
+/// Shadow code: freed heap block.
+pub const HEAP_FREE: u8 = 0xF9;
+
+/// Offset of the HHDM shadow within the KASAN shadow region. The heap's
+/// shadow comes first.
+pub const HHDM_SHADOW_OFFSET: u64 = crate::layout::HEAP_MAX_SIZE >> GRANULE_SHIFT;
+
+/// Largest extent of the HHDM that can have shadow: 14 TiB.
+pub const HHDM_MAX_COVERAGE: u64 = (KASAN_SHADOW_MAX_SIZE - HHDM_SHADOW_OFFSET) << GRANULE_SHIFT;
+
+/// A range of kernel memory with shadow, mapped linearly from `shadow`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    /// First covered address.
+    pub base: usize,
+    /// Covered size in bytes.
+    pub size: usize,
+    /// Shadow byte of `base`.
+    pub shadow: usize,
+}
+
+impl Window {
+    /// A window that covers nothing.
+    pub const EMPTY: Self = Self {
+        base: 0,
+        size: 0,
+        shadow: 0,
+    };
+
+    /// The heap window of `layout`, covering the first `size` bytes of the
+    /// heap region.
+    pub fn heap(layout: &MemoryLayout, size: usize) -> Self {
+        Self {
+            base: layout.heap.base().as_u64() as usize,
+            size,
+            shadow: layout.kasan_shadow.base().as_u64() as usize,
+        }
+    }
+
+    /// The HHDM window of `layout`, covering physical memory below
+    /// `phys_end` (capped at [`HHDM_MAX_COVERAGE`]).
+    pub fn hhdm(layout: &MemoryLayout, phys_end: u64) -> Self {
+        Self {
+            base: layout.hhdm_base.as_u64() as usize,
+            size: phys_end.min(HHDM_MAX_COVERAGE) as usize,
+            shadow: (layout.kasan_shadow.base().as_u64() + HHDM_SHADOW_OFFSET) as usize,
+        }
+    }
+
+    /// Returns the address of the shadow byte for `addr`, if covered.
+    #[inline]
+    pub fn shadow_of(&self, addr: usize) -> Option<usize> {
+        let offset = addr.wrapping_sub(self.base);
+        (offset < self.size).then(|| self.shadow + (offset >> GRANULE_SHIFT))
+    }
+
+    /// Returns the shadow bytes `[start, end)` describing `[addr, addr + size)`,
+    /// clipped to the window.
+    pub fn shadow_range(&self, addr: usize, size: usize) -> Option<(usize, usize)> {
+        let start = addr.max(self.base);
+        let end = addr.saturating_add(size).min(self.base + self.size);
+        (start < end).then(|| {
+            (
+                self.shadow + ((start - self.base) >> GRANULE_SHIFT),
+                self.shadow + (end - self.base).div_ceil(GRANULE_SIZE),
+            )
+        })
+    }
+}
+
+/// An access [`check`] rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BadAccess {
+    /// First inaccessible byte of the access.
+    pub addr: usize,
+    /// Shadow code explaining why it is inaccessible, or 0 if unknown.
+    pub code: u8,
+}
+
+impl BadAccess {
+    /// Returns a short description of the bug class.
+    pub fn kind(&self) -> &'static str {
+        match self.code {
+            PAGE_FREE => "page-use-after-free",
+            HEAP_UNALLOCATED => "heap-unallocated",
+            SLAB_REDZONE => "slab-out-of-bounds",
+            SLAB_FREE => "slab-use-after-free",
+            HEAP_REDZONE => "heap-out-of-bounds",
+            HEAP_FREE => "heap-use-after-free",
+            _ => "out-of-bounds",
+        }
+    }
+}
+
+/// Shadow memory for a heap window and an HHDM window.
+///
+/// All methods assume the shadow bytes of both windows are mapped and
+/// writable.
+#[derive(Debug, Clone, Copy)]
+pub struct Shadow {
+    windows: [Window; 2],
+}
+
+impl Shadow {
+    /// Creates a shadow description from its two windows.
+    pub const fn new(heap: Window, hhdm: Window) -> Self {
+        Self {
+            windows: [heap, hhdm],
+        }
+    }
+
+    /// Returns the shadow byte of `addr`, if it is covered.
+    #[inline]
+    fn byte(&self, addr: usize) -> Option<*mut u8> {
+        self.windows
+            .iter()
+            .find_map(|w| w.shadow_of(addr))
+            .map(|s| s as *mut u8)
+    }
+
+    /// Fills the shadow of `[addr, addr + size)` with `value`, rounding
+    /// `size` up to whole granules.
+    fn fill(&self, addr: usize, size: usize, value: u8) {
+        debug_assert!(addr.is_multiple_of(GRANULE_SIZE));
+        for window in &self.windows {
+            if let Some((start, end)) = window.shadow_range(addr, size) {
+                // SAFETY: The shadow of every window is mapped.
+                unsafe { core::ptr::write_bytes(start as *mut u8, value, end - start) };
+            }
+        }
+    }
+
+    /// Marks `[addr, addr + size)` inaccessible with `code`.
+    ///
+    /// `addr` must be granule-aligned; a trailing partial granule is
+    /// poisoned whole.
+    pub fn poison(&self, addr: usize, size: usize, code: u8) {
+        self.fill(addr, size, code);
+    }
+
+    /// Marks exactly `[addr, addr + size)` accessible.
+    ///
+    /// `addr` must be granule-aligned. If `size` ends mid-granule, the rest
+    /// of that granule stays inaccessible.
+    pub fn unpoison(&self, addr: usize, size: usize) {
+        let whole = size & !(GRANULE_SIZE - 1);
+        self.fill(addr, whole, 0);
+        if whole != size
+            && let Some(byte) = self.byte(addr + whole)
+        {
+            // SAFETY: The shadow of every window is mapped.
+            unsafe { *byte = (size - whole) as u8 };
+        }
+    }
+
+    /// Checks that all of `[addr, addr + size)` is accessible.
+    pub fn check(&self, addr: usize, size: usize) -> Result<(), BadAccess> {
+        let Some(last) = size.checked_sub(1).and_then(|n| addr.checked_add(n)) else {
+            return Ok(());
+        };
+        let mut granule = addr & !(GRANULE_SIZE - 1);
+        while granule <= last {
+            // SAFETY: The shadow of every window is mapped.
+            let value = self.byte(granule).map_or(0, |b| unsafe { *b });
+            if value >= 0x80 {
+                return Err(BadAccess {
+                    addr: addr.max(granule),
+                    code: value,
+                });
+            }
+            if value != 0 && last.min(granule + GRANULE_SIZE - 1) >= granule + value as usize {
+                // The granule is partially accessible: the next one says
+                // what lies past its end.
+                // SAFETY: The shadow of every window is mapped.
+                let next = self
+                    .byte(granule + GRANULE_SIZE)
+                    .map_or(0, |b| unsafe { *b });
+                return Err(BadAccess {
+                    addr: addr.max(granule + value as usize),
+                    code: if next >= 0x80 { next } else { 0 },
+                });
+            }
+            granule += GRANULE_SIZE;
+        }
+        Ok(())
+    }
+
+    /// Marks a freshly allocated block: `[block, block + block_size)`
+    /// becomes a `redzone` except for the `user_size` bytes at `user`.
+    pub fn mark_alloc(
+        &self,
+        block: usize,
+        block_size: usize,
+        user: usize,
+        user_size: usize,
+        redzone: u8,
+    ) {
+        self.poison(block, block_size, redzone);
+        self.unpoison(user, user_size);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Global shadow
+// ---------------------------------------------------------------------------
+
+/// Whether the shadow is mapped and checks are live.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Heap window bounds. The heap window grows with the heap.
+static HEAP_BASE: AtomicUsize = AtomicUsize::new(0);
+static HEAP_SIZE: AtomicUsize = AtomicUsize::new(0);
+static HEAP_SHADOW: AtomicUsize = AtomicUsize::new(0);
+
+/// HHDM window bounds.
+static HHDM_BASE: AtomicUsize = AtomicUsize::new(0);
+static HHDM_SIZE: AtomicUsize = AtomicUsize::new(0);
+static HHDM_SHADOW: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the global shadow.
+#[inline]
+fn global() -> Shadow {
+    Shadow::new(
+        Window {
+            base: HEAP_BASE.load(Ordering::Relaxed),
+            size: HEAP_SIZE.load(Ordering::Acquire),
+            shadow: HEAP_SHADOW.load(Ordering::Relaxed),
+        },
+        Window {
+            base: HHDM_BASE.load(Ordering::Relaxed),
+            size: HHDM_SIZE.load(Ordering::Relaxed),
+            shadow: HHDM_SHADOW.load(Ordering::Relaxed),
+        },
+    )
+}
+
+/// Installs the global windows and turns checking on.
+///
+/// The heap window may start out empty and be extended with
+/// [`extend_heap`].
+///
+/// # Safety
+///
+/// The shadow of both windows must be mapped, writable and zeroed.
+pub unsafe fn enable(heap: Window, hhdm: Window) {
+    HEAP_BASE.store(heap.base, Ordering::Relaxed);
+    HEAP_SHADOW.store(heap.shadow, Ordering::Relaxed);
+    HEAP_SIZE.store(heap.size, Ordering::Relaxed);
+    HHDM_BASE.store(hhdm.base, Ordering::Relaxed);
+    HHDM_SHADOW.store(hhdm.shadow, Ordering::Relaxed);
+    HHDM_SIZE.store(hhdm.size, Ordering::Relaxed);
+    ENABLED.store(true, Ordering::Release);
+}
+
+/// Returns `true` once [`enable`] has run.
+#[inline]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Acquire)
+}
+
+/// Extends the heap window to the first `size` bytes of the heap region.
+///
+/// # Safety
+///
+/// The shadow of the extended window must be mapped, writable and zeroed.
+pub unsafe fn extend_heap(size: usize) {
+    HEAP_SIZE.fetch_max(size, Ordering::Release);
+}
+
+/// Marks `[addr, addr + size)` inaccessible with `code`. No-op until
+/// [`enable`].
+pub fn poison(addr: usize, size: usize, code: u8) {
+    if is_enabled() {
+        global().poison(addr, size, code);
+    }
+}
+
+/// Marks exactly `[addr, addr + size)` accessible. No-op until [`enable`].
+pub fn unpoison(addr: usize, size: usize) {
+    if is_enabled() {
+        global().unpoison(addr, size);
+    }
+}
+
+/// Marks a freshly allocated block (see [`Shadow::mark_alloc`]). No-op
+/// until [`enable`].
+pub fn mark_alloc(block: usize, block_size: usize, user: usize, user_size: usize, redzone: u8) {
+    if is_enabled() {
+        global().mark_alloc(block, block_size, user, user_size, redzone);
+    }
+}
+
+/// Checks that all of `[addr, addr + size)` is accessible. Always passes
+/// until [`enable`].
+///
+/// Never inlined, so the check is not instrumented when called from an
+/// instrumented crate.
+#[inline(never)]
+pub fn check(addr: usize, size: usize) -> Result<(), BadAccess> {
+    if !is_enabled() {
+        return Ok(());
+    }
+    global().check(addr, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hadron_core::addr::VirtAddr;
+
+    /// Heap-like test window: 256 bytes at a fake base, shadow in a vector.
+    const BASE: usize = 0x1000;
+    const SIZE: usize = 256;
+
+    fn with_shadow<F: FnOnce(&Shadow)>(f: F) {
+        let mut shadow = vec![0u8; SIZE / GRANULE_SIZE];
+        let window = Window {
+            base: BASE,
+            size: SIZE,
+            shadow: shadow.as_mut_ptr() as usize,
+        };
+        f(&Shadow::new(window, Window::EMPTY));
+    }
+
+    #[test]
+    fn window_maps_granules() {
+        let w = Window {
+            base: 0x1000,
+            size: 0x100,
+            shadow: 0x9000,
+        };
+        assert_eq!(w.shadow_of(0x1000), Some(0x9000));
+        assert_eq!(w.shadow_of(0x1007), Some(0x9000));
+        assert_eq!(w.shadow_of(0x1008), Some(0x9001));
+        assert_eq!(w.shadow_of(0x10FF), Some(0x901F));
+        assert_eq!(w.shadow_of(0x1100), None);
+        assert_eq!(w.shadow_of(0xFFF), None);
+    }
+
+    #[test]
+    fn window_shadow_range_is_clipped() {
+        let w = Window {
+            base: 0x1000,
+            size: 0x100,
+            shadow: 0x9000,
+        };
+        assert_eq!(w.shadow_range(0x1010, 0x11), Some((0x9002, 0x9005)));
+        assert_eq!(w.shadow_range(0xF00, 0x200), Some((0x9000, 0x9020)));
+        assert_eq!(w.shadow_range(0x2000, 0x10), None);
+    }
+
+    #[test]
+    fn layout_windows_fit_shadow_region() {
+        let layout = MemoryLayout::new(VirtAddr::new(0xFFFF_8000_0000_0000), 0x1_0000_0000);
+        let heap = Window::heap(&layout, layout.heap.max_size() as usize);
+        let hhdm = Window::hhdm(&layout, u64::MAX);
+        let shadow_end = layout.kasan_shadow.end().as_u64() as usize;
+        assert_eq!(
+            heap.shadow_range(heap.base, heap.size).unwrap().1,
+            hhdm.shadow
+        );
+        assert_eq!(
+            hhdm.shadow_range(hhdm.base, hhdm.size).unwrap().1,
+            shadow_end
+        );
+    }
+
+    #[test]
+    fn unpoisoned_memory_passes() {
+        with_shadow(|s| {
+            assert_eq!(s.check(BASE, SIZE), Ok(()));
+            assert_eq!(s.check(BASE + 3, 8), Ok(()));
+        });
+    }
+
+    #[test]
+    fn uncovered_memory_passes() {
+        with_shadow(|s| {
+            s.poison(BASE, SIZE, HEAP_FREE);
+            assert_eq!(s.check(BASE - 8, 8), Ok(()));
+            assert_eq!(s.check(BASE + SIZE, 64), Ok(()));
+        });
+    }
+
+    #[test]
+    fn poisoned_memory_fails() {
+        with_shadow(|s| {
+            s.poison(BASE + 16, 16, HEAP_FREE);
+            assert_eq!(s.check(BASE, 16), Ok(()));
+            assert_eq!(
+                s.check(BASE + 12, 8),
+                Err(BadAccess {
+                    addr: BASE + 16,
+                    code: HEAP_FREE
+                })
+            );
+            assert_eq!(s.check(BASE + 32, 8), Ok(()));
+        });
+    }
+
+    #[test]
+    fn partial_granule_tracks_exact_size() {
+        with_shadow(|s| {
+            s.mark_alloc(BASE, 32, BASE, 13, SLAB_REDZONE);
+            assert_eq!(s.check(BASE, 13), Ok(()));
+            assert_eq!(s.check(BASE + 12, 1), Ok(()));
+            let err = s.check(BASE + 12, 2).unwrap_err();
+            assert_eq!(err.addr, BASE + 13);
+            assert_eq!(err.code, SLAB_REDZONE);
+            assert_eq!(err.kind(), "slab-out-of-bounds");
+        });
+    }
+
+    #[test]
+    fn mark_alloc_poisons_redzones() {
+        with_shadow(|s| {
+            s.mark_alloc(BASE, 64, BASE + 16, 24, HEAP_REDZONE);
+            assert_eq!(s.check(BASE + 8, 1).unwrap_err().code, HEAP_REDZONE);
+            assert_eq!(s.check(BASE + 16, 24), Ok(()));
+            let err = s.check(BASE + 32, 16).unwrap_err();
+            assert_eq!(err.addr, BASE + 40);
+            assert_eq!(err.kind(), "heap-out-of-bounds");
+        });
+    }
+
+    #[test]
+    fn free_then_realloc() {
+        with_shadow(|s| {
+            s.mark_alloc(BASE, 32, BASE, 32, SLAB_REDZONE);
+            s.poison(BASE, 32, SLAB_FREE);
+            assert_eq!(
+                s.check(BASE + 4, 4).unwrap_err().kind(),
+                "slab-use-after-free"
+            );
+            s.mark_alloc(BASE, 32, BASE, 8, SLAB_REDZONE);
+            assert_eq!(s.check(BASE, 8), Ok(()));
+            assert_eq!(s.check(BASE + 8, 1).unwrap_err().code, SLAB_REDZONE);
+        });
+    }
+
+    #[test]
+    fn zero_sized_access_passes() {
+        with_shadow(|s| {
+            s.poison(BASE, SIZE, PAGE_FREE);
+            assert_eq!(s.check(BASE, 0), Ok(()));
+        });
+    }
+
+    #[test]
+    fn global_is_disabled_by_default() {
+        assert!(!is_enabled());
+        assert_eq!(check(0x1000, 8), Ok(()));
+    }
+}
@@ -1,9 +1,11 @@
 //! Kernel virtual address space layout.
 //!
 //! Defines the [`MemoryLayout`] describing where kernel regions (heap, stacks,
-//! MMIO, per-CPU, vDSO) live in the virtual address space. The layout is
-//! KASLR-ready: all regions are defined as const offsets from a runtime
-//! `regions_base` that can be randomized.
+//! MMIO, per-CPU, vDSO) live in the virtual address space. All regions are
+//! defined as const offsets from a runtime `regions_base`, which
+//! [`randomize_regions_base`] places at a random 1 GiB-aligned slot between
+//! the end of the HHDM and the kernel image (KASLR). The kernel image itself
+//! is slid by the boot stub within [`KERNEL_IMAGE_WINDOW`].
 
 use hadron_core::addr::VirtAddr;
 
@@ -35,24 +37,40 @@ pub const VDSO_OFFSET: u64 = 48 * 1024 * 1024 * 1024 * 1024; // +48 TiB
 /// Maximum vDSO region size: 2 MiB.
 pub const VDSO_MAX_SIZE: u64 = 2 * 1024 * 1024;
 
-/// Offset from regions_base to the KASAN shadow memory region (design sketch).
+/// Offset from regions_base to the KASAN shadow memory region.
 ///
 /// Shadow memory uses a 1:8 ratio — one shadow byte per eight real bytes.
-/// The shadow for address `addr` is at `(addr >> 3) + KASAN_SHADOW_OFFSET`.
-/// This region is reserved for future KASAN implementation; it is not mapped
-/// or used until the sanitizer runtime is in place.
-#[allow(dead_code)] // KASAN: used by future sanitizer runtime
+/// The region holds the shadow of the heap followed by the shadow of the
+/// HHDM (see [`crate::kasan`]). It is only mapped when the kernel is built
+/// with `hadron_kasan`.
 pub const KASAN_SHADOW_OFFSET: u64 = 56 * 1024 * 1024 * 1024 * 1024; // +56 TiB
 
 /// Maximum KASAN shadow region size: 2 TiB (covers 16 TiB of kernel address space).
-#[allow(dead_code)] // KASAN: used by future sanitizer runtime
 pub const KASAN_SHADOW_MAX_SIZE: u64 = 2 * 1024 * 1024 * 1024 * 1024;
 
-/// Fixed kernel image base address (not KASLR-shifted).
+/// Total span of the regions block, from `regions_base` to the end of the
+/// KASAN shadow region.
+pub const REGIONS_SPAN: u64 = KASAN_SHADOW_OFFSET + KASAN_SHADOW_MAX_SIZE;
+
+/// Alignment of a randomized `regions_base`: 1 GiB.
+pub const KASLR_REGIONS_ALIGN: u64 = 1024 * 1024 * 1024;
+
+/// Lowest address the regions block may extend to. The last PML4 slot is
+/// reserved for the kernel image.
+pub const KASLR_REGIONS_LIMIT: u64 = 0xFFFF_FF80_0000_0000;
+
+/// Link-time kernel image base address.
 pub const KERNEL_IMAGE_BASE: u64 = 0xFFFF_FFFF_8000_0000;
 /// Maximum kernel image size: 128 MiB.
 pub const KERNEL_IMAGE_MAX_SIZE: u64 = 128 * 1024 * 1024;
 
+/// Window above [`KERNEL_IMAGE_BASE`] within which the boot stub may slide
+/// the kernel image: 1 GiB. The whole window stays inside the top 2 GiB
+/// required by the kernel code model.
+pub const KERNEL_IMAGE_WINDOW: u64 = 1024 * 1024 * 1024;
+/// Alignment of the kernel image slide: 2 MiB.
+pub const KERNEL_IMAGE_SLIDE_ALIGN: u64 = 2 * 1024 * 1024;
+
 /// Initial heap size: 4 MiB.
 pub const INITIAL_HEAP_SIZE: u64 = 4 * 1024 * 1024;
 /// Minimum heap growth increment: 64 KiB.
@@ -115,8 +133,10 @@ pub struct MemoryLayout {
     pub percpu: VirtRegion,
     /// vDSO/VVAR region.
     pub vdso: VirtRegion,
-    /// Kernel image region (fixed, not KASLR-shifted).
+    /// Kernel image region (slid by the boot stub).
     pub kernel_image: VirtRegion,
+    /// KASAN shadow memory region.
+    pub kasan_shadow: VirtRegion,
 }
 
 /// Identifies which kernel virtual address region a faulting address belongs to.
@@ -134,6 +154,8 @@ pub enum FaultRegion {
     KernelImage,
     /// Higher-half direct map.
     Hhdm,
+    /// KASAN shadow memory.
+    KasanShadow,
     /// Address does not belong to any known region.
     Unknown,
 }
@@ -146,6 +168,10 @@ impl MemoryLayout {
     }
 
     /// Creates a new `MemoryLayout` with a custom regions base (for KASLR).
+    ///
+    /// The kernel image region starts at the link-time base; use
+    /// [`with_kernel_image_base`](Self::with_kernel_image_base) when the
+    /// image has been slid.
     pub fn with_regions_base(hhdm_offset: VirtAddr, max_phys: u64, regions_base: u64) -> Self {
         let rb = VirtAddr::new_truncate(regions_base);
         Self {
@@ -161,9 +187,16 @@ impl MemoryLayout {
                 VirtAddr::new_truncate(KERNEL_IMAGE_BASE),
                 KERNEL_IMAGE_MAX_SIZE,
             ),
+            kasan_shadow: VirtRegion::new(rb + KASAN_SHADOW_OFFSET, KASAN_SHADOW_MAX_SIZE),
         }
     }
 
+    /// Returns this layout with the kernel image region moved to `base`.
+    pub fn with_kernel_image_base(mut self, base: VirtAddr) -> Self {
+        self.kernel_image = VirtRegion::new(base, KERNEL_IMAGE_MAX_SIZE);
+        self
+    }
+
     /// Identifies which kernel region contains `addr`.
     pub fn identify_region(&self, addr: VirtAddr) -> FaultRegion {
         if self.heap.contains(addr) {
@@ -176,6 +209,8 @@ impl MemoryLayout {
             FaultRegion::PerCpu
         } else if self.kernel_image.contains(addr) {
             FaultRegion::KernelImage
+        } else if self.kasan_shadow.contains(addr) {
+            FaultRegion::KasanShadow
         } else if addr.as_u64() >= self.hhdm_base.as_u64()
             && addr.as_u64() < self.hhdm_base.as_u64() + self.hhdm_size
         {
@@ -186,6 +221,42 @@ impl MemoryLayout {
     }
 }
 
+/// Picks a random `regions_base` for the given HHDM extent.
+///
+/// The regions block is placed at a [`KASLR_REGIONS_ALIGN`]-aligned slot
+/// above the end of the HHDM (`hhdm_offset + max_phys`) such that the whole
+/// [`REGIONS_SPAN`] ends below [`KASLR_REGIONS_LIMIT`]. `entropy` selects the
+/// slot. Falls back to [`DEFAULT_REGIONS_BASE`] if no slot fits.
+pub fn randomize_regions_base(hhdm_offset: VirtAddr, max_phys: u64, entropy: u64) -> u64 {
+    let hhdm_end = hhdm_offset.as_u64().saturating_add(max_phys);
+    let Some(lowest) = hhdm_end.checked_next_multiple_of(KASLR_REGIONS_ALIGN) else {
+        return DEFAULT_REGIONS_BASE;
+    };
+    let highest = KASLR_REGIONS_LIMIT - REGIONS_SPAN;
+    if lowest > highest {
+        return DEFAULT_REGIONS_BASE;
+    }
+    let slots = (highest - lowest) / KASLR_REGIONS_ALIGN + 1;
+    lowest + (entropy % slots) * KASLR_REGIONS_ALIGN
+}
+
+/// Picks a random kernel image slide for an image of `image_size` bytes.
+///
+/// The slid image never overlaps the link-time image (the boot stub keeps
+/// both mapped while it jumps across) and stays inside
+/// [`KERNEL_IMAGE_WINDOW`]. Returns 0 if the image is too large to slide.
+pub fn randomize_kernel_slide(image_size: u64, entropy: u64) -> u64 {
+    let min = image_size.next_multiple_of(KERNEL_IMAGE_SLIDE_ALIGN);
+    let Some(max) = KERNEL_IMAGE_WINDOW.checked_sub(min) else {
+        return 0;
+    };
+    if min == 0 || max < min {
+        return 0;
+    }
+    let slots = (max - min) / KERNEL_IMAGE_SLIDE_ALIGN + 1;
+    min + (entropy % slots) * KERNEL_IMAGE_SLIDE_ALIGN
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -248,6 +319,14 @@ mod tests {
             layout.mmio.end().as_u64() <= layout.percpu.base().as_u64(),
             "mmio must end before percpu"
         );
+        assert!(
+            layout.vdso.end().as_u64() <= layout.kasan_shadow.base().as_u64(),
+            "vdso must end before the KASAN shadow"
+        );
+        assert_eq!(
+            layout.kasan_shadow.end().as_u64(),
+            layout.regions_base.as_u64() + REGIONS_SPAN
+        );
     }
 
     #[test]
@@ -303,6 +382,13 @@ mod tests {
         assert_eq!(layout.identify_region(addr), FaultRegion::KernelImage);
     }
 
+    #[test]
+    fn identify_region_kasan_shadow() {
+        let layout = MemoryLayout::new(VirtAddr::new(0xFFFF_8000_0000_0000), 0x1_0000_0000);
+        let addr = layout.kasan_shadow.base() + 0x1000;
+        assert_eq!(layout.identify_region(addr), FaultRegion::KasanShadow);
+    }
+
     #[test]
     fn identify_region_hhdm() {
         let hhdm_offset = VirtAddr::new(0xFFFF_8000_0000_0000);
@@ -317,4 +403,65 @@ mod tests {
         let addr = VirtAddr::new(0x1000);
         assert_eq!(layout.identify_region(addr), FaultRegion::Unknown);
     }
+
+    #[test]
+    fn randomized_base_above_hhdm_and_aligned() {
+        let hhdm = VirtAddr::new(0xFFFF_8000_0000_0000);
+        let max_phys = 0x4_0000_0000;
+        for entropy in [0, 1, 12345, u64::MAX] {
+            let base = randomize_regions_base(hhdm, max_phys, entropy);
+            assert_eq!(base % KASLR_REGIONS_ALIGN, 0);
+            assert!(base >= hhdm.as_u64() + max_phys);
+            assert!(base + REGIONS_SPAN <= KASLR_REGIONS_LIMIT);
+        }
+    }
+
+    #[test]
+    fn randomized_base_depends_on_entropy() {
+        let hhdm = VirtAddr::new(0xFFFF_8000_0000_0000);
+        let a = randomize_regions_base(hhdm, 0x1_0000_0000, 1);
+        let b = randomize_regions_base(hhdm, 0x1_0000_0000, 2);
+        assert_eq!(b - a, KASLR_REGIONS_ALIGN);
+    }
+
+    #[test]
+    fn randomized_base_falls_back_when_hhdm_too_large() {
+        let hhdm = VirtAddr::new(0xFFFF_8000_0000_0000);
+        let base = randomize_regions_base(hhdm, 100 * 1024 * 1024 * 1024 * 1024, 7);
+        assert_eq!(base, DEFAULT_REGIONS_BASE);
+    }
+
+    #[test]
+    fn kernel_slide_within_window_and_disjoint() {
+        let image_size = 5 * 1024 * 1024 + 123;
+        for entropy in [0, 1, 999, u64::MAX] {
+            let slide = randomize_kernel_slide(image_size, entropy);
+            assert_eq!(slide % KERNEL_IMAGE_SLIDE_ALIGN, 0);
+            assert!(
+                slide >= image_size,
+                "slid image must not overlap the original"
+            );
+            assert!(slide + image_size <= KERNEL_IMAGE_WINDOW);
+        }
+    }
+
+    #[test]
+    fn kernel_slide_zero_for_oversized_image() {
+        assert_eq!(randomize_kernel_slide(KERNEL_IMAGE_WINDOW, 42), 0);
+    }
+
+    #[test]
+    fn identify_region_slid_kernel_image() {
+        let slid = VirtAddr::new_truncate(KERNEL_IMAGE_BASE + 0x2000_0000);
+        let layout = MemoryLayout::new(VirtAddr::new(0xFFFF_8000_0000_0000), 0x1_0000_0000)
+            .with_kernel_image_base(slid);
+        assert_eq!(
+            layout.identify_region(slid + 0x1000),
+            FaultRegion::KernelImage
+        );
+        assert_eq!(
+            layout.identify_region(VirtAddr::new_truncate(KERNEL_IMAGE_BASE)),
+            FaultRegion::Unknown
+        );
+    }
 }
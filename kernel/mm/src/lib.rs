@@ -2,14 +2,22 @@
 
 #![cfg_attr(not(test), no_std)]
 #![warn(missing_docs)]
+// The allocators keep their metadata in memory the KASAN shadow marks as
+// inaccessible, and the shadow checks themselves must not be instrumented.
+#![cfg_attr(hadron_kasan, feature(sanitize), sanitize(address = "off"))]
 
 pub mod address_space;
+pub mod buddy;
 pub mod heap;
 pub mod hhdm;
+pub mod kasan;
 pub mod layout;
 pub mod mapper;
+pub mod oom;
 pub mod pmm;
 pub mod region;
+pub mod slab;
+pub mod user_layout;
 pub mod vmm;
 pub mod zone;
 
@@ -77,7 +85,8 @@ pub enum PmmError {
     InvalidFrame,
     /// The PMM has already been initialized.
     AlreadyInitialized,
-    /// No usable region large enough for the bitmap was found.
+    /// No usable region large enough for the allocator metadata (bitmap or
+    /// buddy frame table) was found.
     NoBitmapRegion,
 }
 
@@ -87,7 +96,7 @@ impl fmt::Display for PmmError {
             PmmError::OutOfMemory => write!(f, "out of physical memory"),
             PmmError::InvalidFrame => write!(f, "invalid frame address"),
             PmmError::AlreadyInitialized => write!(f, "PMM already initialized"),
-            PmmError::NoBitmapRegion => write!(f, "no region large enough for bitmap"),
+            PmmError::NoBitmapRegion => write!(f, "no region large enough for allocator metadata"),
         }
     }
 }
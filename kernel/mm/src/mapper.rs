@@ -7,6 +7,8 @@
 //! [`PageMapper<S>`] is parameterised by [`PageSize`]: an architecture
 //! implements the trait for each page size it supports. [`PageTranslator`]
 //! is separate because address translation is inherently page-size-agnostic.
+//! [`PageSplitter<S>`] breaks a huge page into smaller ones so that part of
+//! it can be unmapped or re-protected.
 //!
 //! # TLB Flush Decoupling
 //!
@@ -188,6 +190,38 @@ pub unsafe trait PageMapper<S: PageSize> {
     ) -> Result<MapFlush, UnmapError>;
 }
 
+/// Splitting of huge page mappings, generic over the huge page size.
+///
+/// An architecture implements this trait for each huge page size it can
+/// split. For example, x86_64 implements `PageSplitter<Size2MiB>`, which
+/// replaces a 2 MiB mapping with a page table of 512 4 KiB mappings.
+///
+/// # Safety
+///
+/// Implementations must preserve the physical addresses, permissions and
+/// memory type of every byte covered by the split page.
+pub unsafe trait PageSplitter<S: PageSize> {
+    /// Replaces the huge page mapping `page` with a table of mappings of the
+    /// next smaller page size covering the same physical memory with the
+    /// same flags.
+    ///
+    /// `alloc` is called exactly once, for the new table, and only if the
+    /// split succeeds. It must return a 4 KiB frame; it need not be zeroed.
+    ///
+    /// Returns [`UnmapError::NotMapped`] if nothing is mapped at `page`, or
+    /// [`UnmapError::SizeMismatch`] if it is not mapped as a page of size `S`.
+    ///
+    /// # Safety
+    ///
+    /// `root` must point to a valid root page table.
+    unsafe fn split(
+        &self,
+        root: PhysAddr,
+        page: Page<S>,
+        alloc: &mut dyn FnMut() -> PhysFrame<Size4KiB>,
+    ) -> Result<MapFlush, UnmapError>;
+}
+
 /// Architecture-independent virtual address translation.
 ///
 /// Separated from [`PageMapper`] because translation is inherently
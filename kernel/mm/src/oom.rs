@@ -0,0 +1,174 @@
+//! Out-of-memory victim selection.
+//!
+//! When physical memory is exhausted the kernel picks a process to kill
+//! rather than panicking. Each candidate is scored by its memory footprint
+//! (resident pages plus page table overhead) as a proportion of total
+//! memory, shifted by a per-process bias in the range
+//! [`OOM_SCORE_ADJ_MIN`]..=[`OOM_SCORE_ADJ_MAX`]. The candidate with the
+//! highest score is chosen. A bias of [`OOM_SCORE_ADJ_MIN`] exempts the
+//! process from selection entirely.
+//!
+//! The scoring mirrors Linux's `oom_score_adj` semantics so that the
+//! `/proc/<pid>/oom_score` and `/proc/<pid>/oom_score_adj` values behave as
+//! userspace expects.
+
+/// Lowest `oom_score_adj` value; processes with this bias are never killed.
+pub const OOM_SCORE_ADJ_MIN: i32 = -1000;
+
+/// Highest `oom_score_adj` value; processes with this bias are killed first.
+pub const OOM_SCORE_ADJ_MAX: i32 = 1000;
+
+/// Memory footprint and bias of a process considered for OOM killing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OomCandidate {
+    /// Process identifier (opaque to this module).
+    pub id: u32,
+    /// Number of resident user pages.
+    pub resident_pages: usize,
+    /// Number of page table frames owned by the process.
+    pub page_table_pages: usize,
+    /// User-adjustable bias (`oom_score_adj`).
+    pub score_adj: i32,
+}
+
+impl OomCandidate {
+    /// Total number of frames charged to this candidate.
+    #[must_use]
+    pub fn charged_pages(&self) -> usize {
+        self.resident_pages + self.page_table_pages
+    }
+}
+
+/// Computes the badness of `candidate` in page units.
+///
+/// Returns `None` if the candidate is exempt from OOM killing. The bias is
+/// scaled so that each unit of `score_adj` is worth one thousandth of
+/// `total_pages`.
+#[expect(
+    clippy::cast_possible_wrap,
+    reason = "page counts are far below i64::MAX"
+)]
+#[must_use]
+pub fn badness(candidate: &OomCandidate, total_pages: usize) -> Option<i64> {
+    if candidate.score_adj <= OOM_SCORE_ADJ_MIN {
+        return None;
+    }
+    let adj = i64::from(candidate.score_adj.min(OOM_SCORE_ADJ_MAX));
+    let points = candidate.charged_pages() as i64 + adj * (total_pages as i64) / 1000;
+    // Every killable process keeps a minimal score so that a large negative
+    // bias never makes it look "free" to kill.
+    Some(points.max(1))
+}
+
+/// Returns the normalized OOM score (0..=1000) reported via
+/// `/proc/<pid>/oom_score`.
+#[expect(
+    clippy::cast_possible_wrap,
+    clippy::cast_sign_loss,
+    reason = "page counts fit in i64 and the result is clamped to 0..=1000"
+)]
+#[must_use]
+pub fn oom_score(candidate: &OomCandidate, total_pages: usize) -> u32 {
+    if total_pages == 0 {
+        return 0;
+    }
+    match badness(candidate, total_pages) {
+        Some(points) => (points * 1000 / total_pages as i64).clamp(0, 1000) as u32,
+        None => 0,
+    }
+}
+
+/// Selects the candidate with the highest badness.
+///
+/// Ties are broken in favour of the candidate that appears last, so that
+/// more recently started processes (higher PIDs, when candidates are listed
+/// in PID order) are preferred over long-lived ones. Returns `None` if no
+/// candidate is killable.
+pub fn select_victim<'a>(
+    candidates: impl IntoIterator<Item = &'a OomCandidate>,
+    total_pages: usize,
+) -> Option<&'a OomCandidate> {
+    let mut best: Option<(&OomCandidate, i64)> = None;
+    for candidate in candidates {
+        let Some(points) = badness(candidate, total_pages) else {
+            continue;
+        };
+        if best.is_none_or(|(_, best_points)| points >= best_points) {
+            best = Some((candidate, points));
+        }
+    }
+    best.map(|(candidate, _)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: u32, resident_pages: usize, score_adj: i32) -> OomCandidate {
+        OomCandidate {
+            id,
+            resident_pages,
+            page_table_pages: 0,
+            score_adj,
+        }
+    }
+
+    #[test]
+    fn largest_process_is_selected() {
+        let list = [
+            candidate(1, 10, 0),
+            candidate(2, 500, 0),
+            candidate(3, 40, 0),
+        ];
+        assert_eq!(select_victim(&list, 1000).map(|c| c.id), Some(2));
+    }
+
+    #[test]
+    fn page_tables_count_towards_footprint() {
+        let mut a = candidate(1, 100, 0);
+        a.page_table_pages = 50;
+        let b = candidate(2, 120, 0);
+        assert_eq!(select_victim(&[a, b], 1000).map(|c| c.id), Some(1));
+    }
+
+    #[test]
+    fn min_adj_is_exempt() {
+        let list = [candidate(1, 900, OOM_SCORE_ADJ_MIN), candidate(2, 1, 0)];
+        assert_eq!(select_victim(&list, 1000).map(|c| c.id), Some(2));
+        assert_eq!(badness(&list[0], 1000), None);
+    }
+
+    #[test]
+    fn positive_adj_prefers_small_process() {
+        // +500 on a 1000-page system is worth 500 pages.
+        let list = [candidate(1, 300, 0), candidate(2, 10, 500)];
+        assert_eq!(select_victim(&list, 1000).map(|c| c.id), Some(2));
+    }
+
+    #[test]
+    fn negative_adj_protects_large_process() {
+        let list = [candidate(1, 600, -700), candidate(2, 100, 0)];
+        assert_eq!(select_victim(&list, 1000).map(|c| c.id), Some(2));
+    }
+
+    #[test]
+    fn no_killable_candidates() {
+        let list = [candidate(1, 100, OOM_SCORE_ADJ_MIN)];
+        assert!(select_victim(&list, 1000).is_none());
+        assert!(select_victim(&[], 1000).is_none());
+    }
+
+    #[test]
+    fn ties_prefer_later_candidate() {
+        let list = [candidate(1, 100, 0), candidate(2, 100, 0)];
+        assert_eq!(select_victim(&list, 1000).map(|c| c.id), Some(2));
+    }
+
+    #[test]
+    fn oom_score_is_normalized() {
+        assert_eq!(oom_score(&candidate(1, 250, 0), 1000), 250);
+        assert_eq!(oom_score(&candidate(1, 250, 1000), 1000), 1000);
+        assert_eq!(oom_score(&candidate(1, 250, OOM_SCORE_ADJ_MIN), 1000), 0);
+        assert_eq!(oom_score(&candidate(1, 250, 0), 0), 0);
+    }
+}
@@ -1,17 +1,27 @@
-//! Bitmap-based physical frame allocator.
+//! Physical memory manager.
 //!
-//! Uses a bitmap stored in HHDM-accessible memory where each bit represents
-//! one 4 KiB frame. Bit = 1 means allocated/reserved, bit = 0 means free.
+//! The global PMM is a [`BuddyAllocator`] behind a spinlock, fronted by
+//! small per-CPU hot caches of single frames ([`alloc_frame`] /
+//! [`free_frame`]) that absorb most order-0 traffic without touching the
+//! global lock.
+//!
+//! This module also provides the original bitmap-based allocator. It uses a
+//! bitmap stored in HHDM-accessible memory where each bit represents one
+//! 4 KiB frame. Bit = 1 means allocated/reserved, bit = 0 means free.
 //! Word-level scanning with `trailing_zeros()` (compiles to TZCNT/BSF on
-//! x86_64) provides efficient allocation.
+//! x86_64) provides efficient single-frame allocation, but contiguous runs
+//! require a linear scan.
 
 use hadron_core::addr::{PhysAddr, VirtAddr};
+use hadron_core::cpu_local::{CpuLocal, MAX_CPUS};
 use hadron_core::paging::{PhysFrame, Size4KiB};
 use hadron_core::sync::SpinLock;
+use hadron_core::sync::atomic::{AtomicUsize, Ordering};
 
+pub use crate::buddy::{BuddyAllocator, BuddyFrameAllocRef, HUGE_PAGE_ORDER, MAX_ORDER};
 use crate::{FrameAllocator, FrameDeallocator, PhysMemoryRegion, PmmError};
 
-const FRAME_SIZE: u64 = 4096;
+pub(crate) const FRAME_SIZE: u64 = 4096;
 const BITS_PER_WORD: usize = 64;
 
 // ---------------------------------------------------------------------------
@@ -21,6 +31,35 @@ const BITS_PER_WORD: usize = 64;
 /// Poison pattern written to freed pages: `0xDEAD_DEAD` repeated.
 const PAGE_POISON_PATTERN: u32 = 0xDEAD_DEAD;
 
+/// Writes the poison pattern across a 4 KiB page via HHDM.
+pub(crate) fn poison_page(hhdm_offset: VirtAddr, phys_addr: PhysAddr) {
+    let virt = (hhdm_offset + phys_addr.as_u64()).as_mut_ptr::<u32>();
+    for i in 0..(FRAME_SIZE as usize / 4) {
+        // SAFETY: The virtual address is within the HHDM region and the page
+        // has just been freed (no longer in use).
+        unsafe { virt.add(i).write_volatile(PAGE_POISON_PATTERN) };
+    }
+}
+
+/// Checks whether a previously poisoned page is still intact.
+pub(crate) fn check_page_poison(hhdm_offset: VirtAddr, phys_addr: PhysAddr) -> bool {
+    let virt = (hhdm_offset + phys_addr.as_u64()).as_ptr::<u32>();
+    // Quick check: if the first word isn't poison, page was never poisoned
+    // (first allocation after boot). Skip verification.
+    // SAFETY: The virtual address is within the HHDM region.
+    if unsafe { virt.read_volatile() } != PAGE_POISON_PATTERN {
+        return true;
+    }
+    // First word matches; verify the rest of the page.
+    for i in 1..(FRAME_SIZE as usize / 4) {
+        // SAFETY: The virtual address is within the HHDM region.
+        if unsafe { virt.add(i).read_volatile() } != PAGE_POISON_PATTERN {
+            return false;
+        }
+    }
+    true
+}
+
 /// A bitmap-based physical frame allocator.
 ///
 /// All mutation goes through `&mut self`; the outer `PMM: SpinLock<Option<…>>`
@@ -41,31 +80,12 @@ pub struct BitmapAllocator {
 impl BitmapAllocator {
     /// Writes the poison pattern across a 4 KiB page via HHDM.
     fn poison_page(&self, phys_addr: PhysAddr) {
-        let virt = (self.hhdm_offset + phys_addr.as_u64()).as_mut_ptr::<u32>();
-        for i in 0..(FRAME_SIZE as usize / 4) {
-            // SAFETY: The virtual address is within the HHDM region and the page
-            // has just been freed (no longer in use).
-            unsafe { virt.add(i).write_volatile(PAGE_POISON_PATTERN) };
-        }
+        poison_page(self.hhdm_offset, phys_addr);
     }
 
     /// Checks whether a previously poisoned page is still intact.
     fn check_page_poison(&self, phys_addr: PhysAddr) -> bool {
-        let virt = (self.hhdm_offset + phys_addr.as_u64()).as_ptr::<u32>();
-        // Quick check: if the first word isn't poison, page was never poisoned
-        // (first allocation after boot). Skip verification.
-        // SAFETY: The virtual address is within the HHDM region.
-        if unsafe { virt.read_volatile() } != PAGE_POISON_PATTERN {
-            return true;
-        }
-        // First word matches; verify the rest of the page.
-        for i in 1..(FRAME_SIZE as usize / 4) {
-            // SAFETY: The virtual address is within the HHDM region.
-            if unsafe { virt.add(i).read_volatile() } != PAGE_POISON_PATTERN {
-                return false;
-            }
-        }
-        true
+        check_page_poison(self.hhdm_offset, phys_addr)
     }
 
     /// Creates a new bitmap allocator from a slice of physical memory regions.
@@ -424,7 +444,7 @@ unsafe impl FrameDeallocator<Size4KiB> for BitmapFrameAllocRef<'_> {
 // ---------------------------------------------------------------------------
 
 /// Global physical memory manager.
-static PMM: SpinLock<Option<BitmapAllocator>> = SpinLock::leveled("PMM", 3, None);
+static PMM: SpinLock<Option<BuddyAllocator>> = SpinLock::leveled("PMM", 3, None);
 
 /// Initializes the PMM from a slice of physical memory regions.
 ///
@@ -432,7 +452,7 @@ static PMM: SpinLock<Option<BitmapAllocator>> = SpinLock::leveled("PMM", 3, None
 /// into [`PhysMemoryRegion`] descriptors before calling this function.
 pub fn init(regions: &[PhysMemoryRegion], hhdm_offset: VirtAddr) {
     let allocator =
-        unsafe { BitmapAllocator::new(regions, hhdm_offset).expect("failed to initialize PMM") };
+        unsafe { BuddyAllocator::new(regions, hhdm_offset).expect("failed to initialize PMM") };
 
     let mut pmm = PMM.lock();
     assert!(pmm.is_none(), "PMM already initialized");
@@ -441,10 +461,13 @@ pub fn init(regions: &[PhysMemoryRegion], hhdm_offset: VirtAddr) {
 
 /// Executes a closure with an exclusive reference to the global PMM.
 ///
+/// Frames held in the per-CPU hot caches are not visible through this
+/// interface; see [`hot_cached_frames`].
+///
 /// # Panics
 ///
 /// Panics if the PMM has not been initialized.
-pub fn with<R>(f: impl FnOnce(&mut BitmapAllocator) -> R) -> R {
+pub fn with<R>(f: impl FnOnce(&mut BuddyAllocator) -> R) -> R {
     let mut pmm = PMM.lock();
     f(pmm.as_mut().expect("PMM not initialized"))
 }
@@ -453,11 +476,178 @@ pub fn with<R>(f: impl FnOnce(&mut BitmapAllocator) -> R) -> R {
 ///
 /// Returns `None` if the PMM lock is already held (avoiding deadlock in
 /// fault handlers) or if the PMM has not been initialized yet.
-pub fn try_with<R>(f: impl FnOnce(&mut BitmapAllocator) -> R) -> Option<R> {
+pub fn try_with<R>(f: impl FnOnce(&mut BuddyAllocator) -> R) -> Option<R> {
     let mut pmm = PMM.try_lock()?;
     Some(f(pmm.as_mut()?))
 }
 
+// ---------------------------------------------------------------------------
+// Per-CPU hot page caches
+// ---------------------------------------------------------------------------
+
+/// Maximum number of frames held by one CPU's hot cache.
+const HOT_CACHE_CAPACITY: usize = 32;
+
+/// Number of frames moved between a hot cache and the buddy allocator at once.
+const HOT_CACHE_BATCH: usize = 8;
+
+/// A per-CPU stack of free single frames.
+///
+/// Frames in a hot cache are allocated from the buddy allocator's point of
+/// view; they are returned to it in batches when the cache overflows or is
+/// drained.
+struct HotCache {
+    /// Physical addresses of the cached frames.
+    frames: [u64; HOT_CACHE_CAPACITY],
+    /// Number of valid entries in `frames`.
+    len: usize,
+}
+
+impl HotCache {
+    const fn new() -> Self {
+        Self {
+            frames: [0; HOT_CACHE_CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Pops a frame, if any.
+    fn pop(&mut self) -> Option<PhysFrame<Size4KiB>> {
+        self.len = self.len.checked_sub(1)?;
+        Some(PhysFrame::containing_address(PhysAddr::new(
+            self.frames[self.len],
+        )))
+    }
+
+    /// Pushes a frame. The cache must not be full.
+    fn push(&mut self, frame: PhysFrame<Size4KiB>) {
+        self.frames[self.len] = frame.start_address().as_u64();
+        self.len += 1;
+    }
+
+    /// Moves up to [`HOT_CACHE_BATCH`] frames from `pmm` into the cache.
+    /// Returns the number of frames moved.
+    fn refill(&mut self, pmm: &mut BuddyAllocator) -> usize {
+        let mut moved = 0;
+        while moved < HOT_CACHE_BATCH && self.len < HOT_CACHE_CAPACITY {
+            let Some(frame) = pmm.allocate_frame() else {
+                break;
+            };
+            self.push(frame);
+            moved += 1;
+        }
+        moved
+    }
+
+    /// Returns up to `count` frames from the cache to `pmm`. Returns the
+    /// number of frames moved.
+    fn drain(&mut self, pmm: &mut BuddyAllocator, count: usize) -> usize {
+        let mut moved = 0;
+        while moved < count {
+            let Some(frame) = self.pop() else {
+                break;
+            };
+            // SAFETY: Cached frames were allocated from `pmm` and are unused.
+            let _ = unsafe { pmm.deallocate_frame(frame) };
+            moved += 1;
+        }
+        moved
+    }
+}
+
+/// Per-CPU hot caches. Level 3 like `PMM`, which is acquired while a cache
+/// lock is held to refill or drain it.
+static HOT_CACHES: CpuLocal<SpinLock<HotCache>> =
+    CpuLocal::new([const { SpinLock::leveled("PMM_HOT", 3, HotCache::new()) }; MAX_CPUS]);
+
+/// Total number of frames currently held in hot caches.
+static HOT_CACHED: AtomicUsize = AtomicUsize::new(0);
+
+/// Allocates a single 4 KiB frame, preferring the current CPU's hot cache.
+///
+/// Must not be called from within [`with`] (the cache refill takes the PMM
+/// lock).
+pub fn alloc_frame() -> Option<PhysFrame<Size4KiB>> {
+    let mut cache = HOT_CACHES.get().lock();
+    if cache.len == 0 {
+        let moved = with(|pmm| cache.refill(pmm));
+        HOT_CACHED.fetch_add(moved, Ordering::Relaxed);
+    }
+    let frame = cache.pop()?;
+    HOT_CACHED.fetch_sub(1, Ordering::Relaxed);
+    drop(cache);
+
+    if cfg!(hadron_debug_pmm_poison) {
+        let hhdm_offset = crate::hhdm::offset();
+        if !check_page_poison(hhdm_offset, frame.start_address()) {
+            panic!(
+                "PMM: page at {:#x} modified after free (use-after-free)",
+                frame.start_address().as_u64()
+            );
+        }
+    }
+    if cfg!(hadron_kasan) {
+        let virt = crate::hhdm::phys_to_virt(frame.start_address());
+        crate::kasan::unpoison(virt.as_u64() as usize, FRAME_SIZE as usize);
+    }
+    Some(frame)
+}
+
+/// Frees a single 4 KiB frame into the current CPU's hot cache.
+///
+/// Must not be called from within [`with`] (the cache drain takes the PMM
+/// lock).
+///
+/// # Safety
+///
+/// The frame must have been allocated from the global PMM and must not be
+/// in use.
+pub unsafe fn free_frame(frame: PhysFrame<Size4KiB>) {
+    if cfg!(hadron_debug_pmm_poison) {
+        poison_page(crate::hhdm::offset(), frame.start_address());
+    }
+    if cfg!(hadron_kasan) {
+        let virt = crate::hhdm::phys_to_virt(frame.start_address());
+        crate::kasan::poison(
+            virt.as_u64() as usize,
+            FRAME_SIZE as usize,
+            crate::kasan::PAGE_FREE,
+        );
+    }
+
+    let mut cache = HOT_CACHES.get().lock();
+    if cache.len == HOT_CACHE_CAPACITY {
+        let moved = with(|pmm| cache.drain(pmm, HOT_CACHE_BATCH));
+        HOT_CACHED.fetch_sub(moved, Ordering::Relaxed);
+    }
+    cache.push(frame);
+    HOT_CACHED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Returns every frame held in the per-CPU hot caches to the buddy
+/// allocator. Returns the number of frames reclaimed.
+///
+/// Used when memory is tight, so that cached frames can satisfy
+/// allocations going through [`with`].
+pub fn drain_hot_caches() -> usize {
+    let mut total = 0;
+    for cpu in 0..MAX_CPUS {
+        let mut cache = HOT_CACHES.get_for(cpu as u32).lock();
+        if cache.len == 0 {
+            continue;
+        }
+        let moved = with(|pmm| cache.drain(pmm, HOT_CACHE_CAPACITY));
+        HOT_CACHED.fetch_sub(moved, Ordering::Relaxed);
+        total += moved;
+    }
+    total
+}
+
+/// Returns the number of free frames currently held in per-CPU hot caches.
+pub fn hot_cached_frames() -> usize {
+    HOT_CACHED.load(Ordering::Relaxed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -488,6 +678,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn hot_cache_refills_in_batches() {
+        let mut pmm = crate::buddy::tests::allocator(&[(0, 64)]);
+        let mut cache = HotCache::new();
+        assert_eq!(cache.refill(&mut pmm), HOT_CACHE_BATCH);
+        assert_eq!(cache.len, HOT_CACHE_BATCH);
+        assert_eq!(pmm.free_frames(), 64 - HOT_CACHE_BATCH);
+        assert!(cache.pop().is_some());
+        assert_eq!(cache.len, HOT_CACHE_BATCH - 1);
+    }
+
+    #[test]
+    fn hot_cache_refill_stops_when_full_or_empty() {
+        let mut pmm = crate::buddy::tests::allocator(&[(0, 4)]);
+        let mut cache = HotCache::new();
+        assert_eq!(cache.refill(&mut pmm), 4);
+        assert_eq!(cache.refill(&mut pmm), 0);
+
+        let mut pmm = crate::buddy::tests::allocator(&[(0, 1024)]);
+        let mut cache = HotCache::new();
+        while cache.refill(&mut pmm) > 0 {}
+        assert_eq!(cache.len, HOT_CACHE_CAPACITY);
+    }
+
+    #[test]
+    fn hot_cache_drain_returns_frames() {
+        let mut pmm = crate::buddy::tests::allocator(&[(0, 64)]);
+        let mut cache = HotCache::new();
+        cache.refill(&mut pmm);
+        cache.refill(&mut pmm);
+        assert_eq!(cache.drain(&mut pmm, 4), 4);
+        assert_eq!(
+            cache.drain(&mut pmm, HOT_CACHE_CAPACITY),
+            2 * HOT_CACHE_BATCH - 4
+        );
+        assert_eq!(cache.len, 0);
+        assert_eq!(pmm.free_frames(), 64);
+        assert_eq!(pmm.free_blocks(6), 1);
+    }
+
     #[test]
     fn test_poison_page_writes_pattern() {
         let buf = alloc_page();
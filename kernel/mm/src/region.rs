@@ -168,6 +168,79 @@ impl<const N: usize> FreeRegionAllocator<N> {
         Some(VirtAddr::new_truncate(base))
     }
 
+    /// Allocates `size` bytes (rounded up to page alignment) starting at a
+    /// multiple of `align`, which must be a power of two of at least a page.
+    ///
+    /// Like [`allocate`](Self::allocate), tries the free list first and then
+    /// the watermark. The unaligned head of the chosen range stays free and
+    /// can be handed out by later allocations. Returns `None` if the region
+    /// is exhausted or the free list has no room to record the head.
+    pub fn allocate_aligned(&mut self, size: u64, align: u64) -> Option<VirtAddr> {
+        debug_assert!(align.is_power_of_two() && align >= super::PAGE_SIZE as u64);
+        let aligned_size = page_align_up(size);
+        if aligned_size == 0 {
+            return self.allocate(0);
+        }
+
+        // First-fit scan of free list.
+        for i in 0..self.free_list.len() {
+            let entry = self.free_list[i];
+            let base = entry.base.next_multiple_of(align);
+            let head = base - entry.base;
+            if head >= entry.size || entry.size - head < aligned_size {
+                continue;
+            }
+            let tail = entry.size - head - aligned_size;
+            match (head, tail) {
+                (0, 0) => {
+                    self.free_list.remove(i);
+                }
+                (0, _) => {
+                    self.free_list[i] = FreeRange {
+                        base: base + aligned_size,
+                        size: tail,
+                    };
+                }
+                (_, 0) => self.free_list[i].size = head,
+                (_, _) => {
+                    if self.free_list.is_full() {
+                        continue;
+                    }
+                    self.free_list[i].size = head;
+                    self.free_list.insert(
+                        i + 1,
+                        FreeRange {
+                            base: base + aligned_size,
+                            size: tail,
+                        },
+                    );
+                }
+            }
+            return Some(VirtAddr::new_truncate(base));
+        }
+
+        // Fall back to bump allocation, leaving the alignment gap free.
+        let base = self.watermark.next_multiple_of(align);
+        let end = base + aligned_size;
+        let region_end = self.region.base().as_u64() + self.region.max_size();
+        if end > region_end {
+            return None;
+        }
+        if base > self.watermark {
+            // The last free range never abuts the watermark, so the gap is
+            // a new entry.
+            self.free_list
+                .try_push(FreeRange {
+                    base: self.watermark,
+                    size: base - self.watermark,
+                })
+                .ok()?;
+        }
+
+        self.watermark = end;
+        Some(VirtAddr::new_truncate(base))
+    }
+
     /// Returns a previously allocated range to the allocator.
     ///
     /// `addr` must be the exact base returned by [`allocate`](Self::allocate),
@@ -602,4 +675,45 @@ mod tests {
         assert!(alloc.allocate(0x1000).is_some());
         assert!(alloc.allocate(0x1000).is_none());
     }
+
+    #[test]
+    fn free_alloc_aligned_bump_leaves_gap_free() {
+        let mut alloc = test_free_region::<16>(0x1000, 0x100_0000);
+        let a = alloc.allocate(0x1000).unwrap();
+        let b = alloc.allocate_aligned(0x20_0000, 0x20_0000).unwrap();
+        assert_eq!(b.as_u64(), 0x20_0000);
+        assert_eq!(alloc.free_list_len(), 1);
+        assert_eq!(alloc.free_bytes(), 0x20_0000 - 0x2000);
+
+        // The gap is reused by ordinary allocations.
+        let c = alloc.allocate(0x1000).unwrap();
+        assert_eq!(c.as_u64(), a.as_u64() + 0x1000);
+    }
+
+    #[test]
+    fn free_alloc_aligned_splits_free_range() {
+        let mut alloc = test_free_region::<16>(0x1000, 0x100_0000);
+        let a = alloc.allocate(0x40_0000).unwrap();
+        let _guard = alloc.allocate(0x1000).unwrap();
+        alloc.deallocate(a, 0x40_0000).unwrap();
+
+        // Free range [0x1000, 0x401000): aligned block at 0x200000 leaves a
+        // head and a tail.
+        let b = alloc.allocate_aligned(0x20_0000, 0x20_0000).unwrap();
+        assert_eq!(b.as_u64(), 0x20_0000);
+        assert_eq!(alloc.free_list_len(), 2);
+        assert_eq!(alloc.free_bytes(), 0x40_0000 - 0x20_0000);
+
+        alloc.deallocate(b, 0x20_0000).unwrap();
+        assert_eq!(alloc.free_list_len(), 1);
+        assert_eq!(alloc.free_bytes(), 0x40_0000);
+    }
+
+    #[test]
+    fn free_alloc_aligned_exhausted() {
+        let mut alloc = test_free_region::<16>(0x1000, 0x20_0000);
+        assert!(alloc.allocate_aligned(0x20_0000, 0x20_0000).is_none());
+        assert_eq!(alloc.watermark_used(), 0);
+        assert_eq!(alloc.free_list_len(), 0);
+    }
 }
@@ -0,0 +1,635 @@
+//! Slab allocator with per-CPU magazines.
+//!
+//! A [`SlabCache`] hands out fixed-size objects carved from single 4 KiB
+//! pages ("slabs"). Allocation goes through three layers:
+//!
+//! 1. **Magazine** — a small per-CPU stack of free objects. The common case
+//!    only touches the current CPU's magazine lock, which is uncontended.
+//! 2. **Depot** — the cache-wide free list. Magazines are refilled from and
+//!    flushed to it in batches of [`MAGAZINE_BATCH`].
+//! 3. **Slabs** — when the depot is empty a new page is taken from the PMM
+//!    hot cache and carved into objects. Like the zone allocator, slab pages
+//!    are never returned to the PMM.
+//!
+//! Every slab begins with a [`SlabHeader`] pointing back to its cache, so an
+//! object can be freed from its address alone (see [`free`]).
+//!
+//! There are two kinds of caches:
+//!
+//! - The `kmalloc-*` size classes (16 to 1024 bytes), used by the global
+//!   allocator for small requests of any type.
+//! - Typed caches for frequently allocated kernel objects, declared as
+//!   statics and [`register`]ed. The global allocator routes any allocation
+//!   whose [`Layout`] matches a registered cache exactly to that cache, so
+//!   `Arc::new(process)` is served by the `process` cache without changing
+//!   the `Arc` type (see [`arc_layout`]).
+//!
+//! Typed caches may have a constructor hook, which runs once per object
+//! when its slab is carved. Objects in such caches are expected to be
+//! returned in their constructed state; the depot's free-list link is kept
+//! in a word after the object so it never clobbers constructed contents.
+
+use core::alloc::Layout;
+use core::ptr::NonNull;
+
+use hadron_core::cpu_local::{CpuLocal, MAX_CPUS};
+use hadron_core::sync::SpinLock;
+use hadron_core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize, Ordering};
+
+use crate::PAGE_SIZE;
+use crate::kasan;
+
+/// Number of objects a per-CPU magazine can hold.
+pub const MAGAZINE_ROUNDS: usize = 16;
+
+/// Number of objects moved between a magazine and the depot at once.
+pub const MAGAZINE_BATCH: usize = MAGAZINE_ROUNDS / 2;
+
+/// Object sizes of the `kmalloc-*` caches.
+const KMALLOC_SIZES: [usize; 7] = [16, 32, 64, 128, 256, 512, 1024];
+
+/// Largest request served by the `kmalloc-*` caches.
+pub const KMALLOC_MAX_SIZE: usize = KMALLOC_SIZES[KMALLOC_SIZES.len() - 1];
+
+/// Maximum number of typed caches that can be registered.
+const MAX_TYPED_CACHES: usize = 16;
+
+/// Magic value identifying a slab header ("SLABSLAB").
+const SLAB_MAGIC: usize = 0x534C_4142_534C_4142;
+
+/// Header at the start of every slab page.
+#[repr(C)]
+struct SlabHeader {
+    /// [`SLAB_MAGIC`].
+    magic: usize,
+    /// Owning cache.
+    cache: *const SlabCache,
+}
+
+/// Size of [`SlabHeader`].
+const HEADER_SIZE: usize = core::mem::size_of::<SlabHeader>();
+
+/// A per-CPU stack of free objects.
+struct Magazine {
+    /// Object addresses.
+    rounds: [usize; MAGAZINE_ROUNDS],
+    /// Number of valid entries in `rounds`.
+    len: usize,
+}
+
+impl Magazine {
+    const fn new() -> Self {
+        Self {
+            rounds: [0; MAGAZINE_ROUNDS],
+            len: 0,
+        }
+    }
+}
+
+/// The cache-wide free list.
+struct Depot {
+    /// First free object, or 0.
+    head: usize,
+}
+
+/// A snapshot of a cache's statistics, as reported by `/proc/slabinfo`.
+#[derive(Debug, Clone, Copy)]
+pub struct SlabStats {
+    /// Cache name.
+    pub name: &'static str,
+    /// Bytes per object, including alignment padding and the free-list
+    /// link of constructed caches.
+    pub object_size: usize,
+    /// Objects currently handed out to callers.
+    pub active_objects: usize,
+    /// Objects carved from slabs (active, in magazines, or in the depot).
+    pub total_objects: usize,
+    /// Objects per slab.
+    pub objects_per_slab: usize,
+    /// Pages per slab (always 1).
+    pub pages_per_slab: usize,
+    /// Slabs allocated by this cache.
+    pub slabs: usize,
+}
+
+/// A named cache of fixed-size objects.
+pub struct SlabCache {
+    /// Name reported in `/proc/slabinfo`.
+    name: &'static str,
+    /// Layout the cache was created for. Typed caches serve allocations
+    /// with exactly this layout.
+    layout: Layout,
+    /// Distance between consecutive objects.
+    stride: usize,
+    /// Offset of the depot free-list link within an object.
+    link_offset: usize,
+    /// Offset of the first object from the slab base.
+    first_offset: usize,
+    /// Objects carved from each slab.
+    objects_per_slab: usize,
+    /// Constructor run on every object when its slab is carved.
+    ctor: Option<fn(*mut u8)>,
+    /// Per-CPU magazines. Level 1, like `HEAP`.
+    magazines: CpuLocal<SpinLock<Magazine>>,
+    /// Cache-wide free list. Level 1, acquired while holding a magazine.
+    depot: SpinLock<Depot>,
+    /// Objects currently handed out.
+    active: AtomicUsize,
+    /// Slabs carved so far.
+    slabs: AtomicUsize,
+}
+
+impl SlabCache {
+    /// Creates a cache for objects of the given layout.
+    ///
+    /// `ctor`, if any, runs once on every object when its slab is carved.
+    ///
+    /// # Panics
+    ///
+    /// Panics (at compile time, when used to initialize a static) if an
+    /// object does not fit in a single slab page.
+    #[must_use]
+    pub const fn new(name: &'static str, layout: Layout, ctor: Option<fn(*mut u8)>) -> Self {
+        let align = if layout.align() > 8 {
+            layout.align()
+        } else {
+            8
+        };
+        let size = if layout.size() > 8 { layout.size() } else { 8 };
+        // Constructed objects keep their contents while free, so the depot
+        // link goes in an extra word after the object.
+        let (link_offset, stride) = if ctor.is_some() {
+            let link = align_up(size, 8);
+            (link, align_up(link + 8, align))
+        } else {
+            (0, align_up(size, align))
+        };
+        let first_offset = align_up(HEADER_SIZE, align);
+        assert!(
+            first_offset + stride <= PAGE_SIZE,
+            "slab object does not fit in a page"
+        );
+
+        Self {
+            name,
+            layout,
+            stride,
+            link_offset,
+            first_offset,
+            objects_per_slab: (PAGE_SIZE - first_offset) / stride,
+            ctor,
+            magazines: CpuLocal::new(
+                [const { SpinLock::leveled("SLAB_MAGAZINE", 1, Magazine::new()) }; MAX_CPUS],
+            ),
+            depot: SpinLock::leveled("SLAB_DEPOT", 1, Depot { head: 0 }),
+            active: AtomicUsize::new(0),
+            slabs: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates a `kmalloc-*` size-class cache with naturally aligned objects.
+    const fn kmalloc(name: &'static str, size: usize) -> Self {
+        match Layout::from_size_align(size, size) {
+            Ok(layout) => Self::new(name, layout, None),
+            Err(_) => panic!("invalid kmalloc size class"),
+        }
+    }
+
+    /// Returns the cache name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the layout this cache was created for.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// Allocates an object, taking a fresh slab page from the PMM if needed.
+    ///
+    /// Returns `None` if physical memory is exhausted.
+    pub fn alloc(&self) -> Option<NonNull<u8>> {
+        self.alloc_with(alloc_slab_page)
+    }
+
+    /// Returns an object to the cache.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`alloc`](Self::alloc) on this cache
+    /// and must not be used afterwards.
+    pub unsafe fn free(&self, ptr: NonNull<u8>) {
+        if cfg!(hadron_kasan) {
+            kasan::poison(ptr.as_ptr() as usize, self.stride, kasan::SLAB_FREE);
+        }
+        let mut magazine = self.magazines.get().lock_unchecked();
+        if magazine.len == MAGAZINE_ROUNDS {
+            self.flush(&mut magazine, MAGAZINE_BATCH);
+        }
+        let len = magazine.len;
+        magazine.rounds[len] = ptr.as_ptr() as usize;
+        magazine.len += 1;
+        drop(magazine);
+        self.active.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Returns a snapshot of this cache's statistics.
+    pub fn stats(&self) -> SlabStats {
+        let slabs = self.slabs.load(Ordering::Relaxed);
+        SlabStats {
+            name: self.name,
+            object_size: self.stride,
+            active_objects: self.active.load(Ordering::Relaxed),
+            total_objects: slabs * self.objects_per_slab,
+            objects_per_slab: self.objects_per_slab,
+            pages_per_slab: 1,
+            slabs,
+        }
+    }
+
+    /// Allocates an object, using `page_source` to obtain new slab pages.
+    fn alloc_with(&self, page_source: fn() -> Option<usize>) -> Option<NonNull<u8>> {
+        let mut magazine = self.magazines.get().lock_unchecked();
+        if magazine.len == 0 {
+            self.refill(&mut magazine, page_source);
+        }
+        magazine.len = magazine.len.checked_sub(1)?;
+        let addr = magazine.rounds[magazine.len];
+        drop(magazine);
+        self.active.fetch_add(1, Ordering::Relaxed);
+        if cfg!(hadron_kasan) {
+            kasan::mark_alloc(
+                addr,
+                self.stride,
+                addr,
+                self.layout.size(),
+                kasan::SLAB_REDZONE,
+            );
+        }
+        NonNull::new(addr as *mut u8)
+    }
+
+    /// Moves up to [`MAGAZINE_BATCH`] objects from the depot into
+    /// `magazine`, carving a new slab only if the depot is empty.
+    fn refill(&self, magazine: &mut Magazine, page_source: fn() -> Option<usize>) {
+        let mut depot = self.depot.lock_unchecked();
+        while magazine.len < MAGAZINE_BATCH {
+            if depot.head == 0 && (magazine.len > 0 || !self.grow(&mut depot, page_source)) {
+                break;
+            }
+            let obj = depot.head;
+            // SAFETY: `obj` is a free object of this cache; its link word
+            // holds the next free object.
+            depot.head = unsafe { *((obj + self.link_offset) as *const usize) };
+            magazine.rounds[magazine.len] = obj;
+            magazine.len += 1;
+        }
+    }
+
+    /// Returns up to `count` objects from `magazine` to the depot.
+    fn flush(&self, magazine: &mut Magazine, count: usize) {
+        let mut depot = self.depot.lock_unchecked();
+        for _ in 0..count.min(magazine.len) {
+            magazine.len -= 1;
+            let obj = magazine.rounds[magazine.len];
+            // SAFETY: `obj` is a free object of this cache; its link word is
+            // not in use.
+            unsafe { *((obj + self.link_offset) as *mut usize) = depot.head };
+            depot.head = obj;
+        }
+    }
+
+    /// Carves a new slab page into the depot. Returns `false` if no page
+    /// could be obtained.
+    fn grow(&self, depot: &mut Depot, page_source: fn() -> Option<usize>) -> bool {
+        let Some(base) = page_source() else {
+            return false;
+        };
+        // SAFETY: `base` is a fresh, exclusively owned, page-aligned page.
+        unsafe {
+            (base as *mut SlabHeader).write(SlabHeader {
+                magic: SLAB_MAGIC,
+                cache: self,
+            });
+        }
+        // Push in reverse so objects are handed out in address order.
+        for i in (0..self.objects_per_slab).rev() {
+            let obj = base + self.first_offset + i * self.stride;
+            if let Some(ctor) = self.ctor {
+                ctor(obj as *mut u8);
+            }
+            // SAFETY: `obj` lies within the new slab page.
+            unsafe { *((obj + self.link_offset) as *mut usize) = depot.head };
+            depot.head = obj;
+        }
+        if cfg!(hadron_kasan) {
+            kasan::poison(base, PAGE_SIZE, kasan::SLAB_FREE);
+        }
+        self.slabs.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+}
+
+/// Allocates a slab page from the PMM hot cache and returns its HHDM address.
+fn alloc_slab_page() -> Option<usize> {
+    let frame = crate::pmm::alloc_frame()?;
+    Some(crate::hhdm::phys_to_virt(frame.start_address()).as_u64() as usize)
+}
+
+const fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+/// Returns the layout of the allocation made by `Arc::new(value)` for a
+/// `value: T`.
+///
+/// `alloc::sync::Arc` stores its value in a `repr(C)` `ArcInner` that
+/// places the strong and weak counters before the value, so the allocation
+/// has the layout of `ArcLayout<T>` below.
+#[must_use]
+pub const fn arc_layout<T>() -> Layout {
+    /// Mirror of `alloc::sync::ArcInner<T>`.
+    #[repr(C)]
+    struct ArcLayout<T> {
+        strong: AtomicUsize,
+        weak: AtomicUsize,
+        data: T,
+    }
+    Layout::new::<ArcLayout<T>>()
+}
+
+// ---------------------------------------------------------------------------
+// Global caches
+// ---------------------------------------------------------------------------
+
+/// The `kmalloc-*` size-class caches.
+static KMALLOC_CACHES: [SlabCache; KMALLOC_SIZES.len()] = [
+    SlabCache::kmalloc("kmalloc-16", 16),
+    SlabCache::kmalloc("kmalloc-32", 32),
+    SlabCache::kmalloc("kmalloc-64", 64),
+    SlabCache::kmalloc("kmalloc-128", 128),
+    SlabCache::kmalloc("kmalloc-256", 256),
+    SlabCache::kmalloc("kmalloc-512", 512),
+    SlabCache::kmalloc("kmalloc-1024", 1024),
+];
+
+/// Registered typed caches. Entries are only ever appended.
+static TYPED_CACHES: [AtomicPtr<SlabCache>; MAX_TYPED_CACHES] =
+    [const { AtomicPtr::new(core::ptr::null_mut()) }; MAX_TYPED_CACHES];
+
+/// Serializes [`register`].
+static REGISTER_LOCK: SpinLock<()> = SpinLock::leveled("SLAB_REGISTER", 1, ());
+
+/// Whether the global allocator may use slab caches (set by [`enable`]).
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Allows the global allocator to serve small allocations from slab caches.
+///
+/// Must be called after the PMM and HHDM are initialized.
+pub fn enable() {
+    ENABLED.store(true, Ordering::Release);
+}
+
+/// Registers a typed cache so that allocations with exactly its layout are
+/// served from it. Registering the same cache twice is a no-op.
+///
+/// Returns `false` if the registry is full.
+pub fn register(cache: &'static SlabCache) -> bool {
+    let _guard = REGISTER_LOCK.lock_unchecked();
+    let ptr = core::ptr::from_ref(cache).cast_mut();
+    for slot in &TYPED_CACHES {
+        let current = slot.load(Ordering::Acquire);
+        if current == ptr {
+            return true;
+        }
+        if current.is_null() {
+            slot.store(ptr, Ordering::Release);
+            return true;
+        }
+    }
+    false
+}
+
+/// Returns the cache that serves allocations of `layout`, if any.
+///
+/// Typed caches take precedence over the `kmalloc-*` size classes. Returns
+/// `None` before [`enable`] and for requests larger than
+/// [`KMALLOC_MAX_SIZE`].
+pub fn cache_for(layout: Layout) -> Option<&'static SlabCache> {
+    if !ENABLED.load(Ordering::Acquire) {
+        return None;
+    }
+    for slot in &TYPED_CACHES {
+        let ptr = slot.load(Ordering::Acquire);
+        if ptr.is_null() {
+            break;
+        }
+        // SAFETY: Registered caches are `'static`.
+        let cache = unsafe { &*ptr };
+        if cache.layout == layout {
+            return Some(cache);
+        }
+    }
+    kmalloc_cache(layout)
+}
+
+/// Returns the `kmalloc-*` cache for `layout`, if it is small enough.
+fn kmalloc_cache(layout: Layout) -> Option<&'static SlabCache> {
+    let size = layout.size().max(layout.align());
+    if size > KMALLOC_MAX_SIZE {
+        return None;
+    }
+    let class = size.next_power_of_two().max(KMALLOC_SIZES[0]);
+    let index = (class.trailing_zeros() - KMALLOC_SIZES[0].trailing_zeros()) as usize;
+    Some(&KMALLOC_CACHES[index])
+}
+
+/// Frees an object allocated from any slab cache.
+///
+/// # Safety
+///
+/// `ptr` must have been returned by [`SlabCache::alloc`] and must not be
+/// used afterwards.
+pub unsafe fn free(ptr: NonNull<u8>) {
+    let base = ptr.as_ptr() as usize & !(PAGE_SIZE - 1);
+    // SAFETY: Every slab page starts with a header.
+    let header = unsafe { &*(base as *const SlabHeader) };
+    debug_assert_eq!(header.magic, SLAB_MAGIC, "slab::free: not a slab object");
+    // SAFETY: The header's cache pointer refers to a `'static` cache.
+    unsafe { (*header.cache).free(ptr) };
+}
+
+/// Calls `f` with the statistics of every cache: the `kmalloc-*` caches
+/// first, then typed caches in registration order.
+pub fn for_each_stats(mut f: impl FnMut(SlabStats)) {
+    for cache in &KMALLOC_CACHES {
+        f(cache.stats());
+    }
+    for slot in &TYPED_CACHES {
+        let ptr = slot.load(Ordering::Acquire);
+        if ptr.is_null() {
+            break;
+        }
+        // SAFETY: Registered caches are `'static`.
+        f(unsafe { &*ptr }.stats());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Leaks a page-aligned host page to use as a slab.
+    fn host_page() -> Option<usize> {
+        let layout = Layout::from_size_align(PAGE_SIZE, PAGE_SIZE).unwrap();
+        // SAFETY: The layout has non-zero size.
+        let ptr = unsafe { std::alloc::alloc_zeroed(layout) };
+        (!ptr.is_null()).then_some(ptr as usize)
+    }
+
+    fn no_page() -> Option<usize> {
+        None
+    }
+
+    fn leak(cache: SlabCache) -> &'static SlabCache {
+        Box::leak(Box::new(cache))
+    }
+
+    #[test]
+    fn geometry_of_plain_cache() {
+        let cache = SlabCache::new("t", Layout::new::<[u64; 3]>(), None);
+        assert_eq!(cache.stride, 24);
+        assert_eq!(cache.first_offset, HEADER_SIZE);
+        assert_eq!(cache.link_offset, 0);
+        assert_eq!(cache.objects_per_slab, (PAGE_SIZE - HEADER_SIZE) / 24);
+    }
+
+    #[test]
+    fn geometry_of_constructed_cache() {
+        fn ctor(_: *mut u8) {}
+        let cache = SlabCache::new("t", Layout::new::<[u8; 20]>(), Some(ctor));
+        // 20 bytes rounded to 24, plus the link word.
+        assert_eq!(cache.link_offset, 24);
+        assert_eq!(cache.stride, 32);
+    }
+
+    #[test]
+    fn kmalloc_objects_are_naturally_aligned() {
+        for cache in &KMALLOC_CACHES {
+            let size = cache.layout.size();
+            assert_eq!(cache.stride, size);
+            assert_eq!(cache.first_offset % size, 0);
+            assert!(cache.objects_per_slab >= 3, "{}", cache.name);
+        }
+    }
+
+    #[test]
+    fn kmalloc_size_class_selection() {
+        let class = |size, align| {
+            kmalloc_cache(Layout::from_size_align(size, align).unwrap()).map(SlabCache::name)
+        };
+        assert_eq!(class(1, 1), Some("kmalloc-16"));
+        assert_eq!(class(16, 8), Some("kmalloc-16"));
+        assert_eq!(class(17, 8), Some("kmalloc-32"));
+        assert_eq!(class(8, 64), Some("kmalloc-64"));
+        assert_eq!(class(1000, 8), Some("kmalloc-1024"));
+        assert_eq!(class(1025, 8), None);
+        assert_eq!(class(8, 2048), None);
+    }
+
+    #[test]
+    fn alloc_free_round_trip() {
+        let cache = leak(SlabCache::new("t", Layout::new::<u64>(), None));
+        let a = cache.alloc_with(host_page).unwrap();
+        let b = cache.alloc_with(host_page).unwrap();
+        assert_ne!(a, b);
+        assert_eq!(a.as_ptr() as usize % 8, 0);
+        assert_eq!(cache.stats().active_objects, 2);
+        assert_eq!(cache.stats().slabs, 1);
+
+        unsafe { free(a) };
+        assert_eq!(cache.stats().active_objects, 1);
+        // The magazine is LIFO.
+        assert_eq!(cache.alloc_with(host_page), Some(a));
+    }
+
+    #[test]
+    fn objects_are_unique_across_slabs() {
+        let cache = leak(SlabCache::new("t", Layout::new::<[u8; 512]>(), None));
+        let per_slab = cache.objects_per_slab;
+        let mut seen: Vec<NonNull<u8>> = Vec::new();
+        for _ in 0..per_slab * 3 {
+            let obj = cache.alloc_with(host_page).unwrap();
+            assert!(!seen.contains(&obj));
+            seen.push(obj);
+        }
+        let stats = cache.stats();
+        assert_eq!(stats.slabs, 3);
+        assert_eq!(stats.total_objects, per_slab * 3);
+        assert_eq!(stats.active_objects, per_slab * 3);
+        for obj in seen {
+            unsafe { cache.free(obj) };
+        }
+        assert_eq!(cache.stats().active_objects, 0);
+    }
+
+    #[test]
+    fn magazine_overflow_flushes_to_depot() {
+        let cache = leak(SlabCache::new("t", Layout::new::<u64>(), None));
+        let objs: Vec<_> = (0..MAGAZINE_ROUNDS * 2)
+            .map(|_| cache.alloc_with(host_page).unwrap())
+            .collect();
+        for obj in &objs {
+            unsafe { cache.free(*obj) };
+        }
+        assert!(cache.magazines.get().lock_unchecked().len <= MAGAZINE_ROUNDS);
+        // Everything can be reallocated without carving a new slab.
+        let slabs = cache.stats().slabs;
+        for _ in 0..objs.len() {
+            cache.alloc_with(no_page).unwrap();
+        }
+        assert_eq!(cache.stats().slabs, slabs);
+    }
+
+    #[test]
+    fn exhaustion_returns_none() {
+        let cache = leak(SlabCache::new("t", Layout::new::<u64>(), None));
+        assert!(cache.alloc_with(no_page).is_none());
+        assert_eq!(cache.stats().active_objects, 0);
+    }
+
+    #[test]
+    fn ctor_runs_once_per_object_and_state_survives_free() {
+        fn ctor(obj: *mut u8) {
+            unsafe { (obj as *mut u64).write(0xC0FFEE) };
+        }
+        let cache = leak(SlabCache::new("t", Layout::new::<u64>(), Some(ctor)));
+        let objs: Vec<_> = (0..cache.objects_per_slab)
+            .map(|_| cache.alloc_with(host_page).unwrap())
+            .collect();
+        for obj in &objs {
+            assert_eq!(unsafe { *(obj.as_ptr() as *const u64) }, 0xC0FFEE);
+        }
+        // Freed objects pass through the depot without losing their state.
+        for obj in &objs {
+            unsafe { cache.free(*obj) };
+        }
+        for _ in 0..objs.len() {
+            let obj = cache.alloc_with(no_page).unwrap();
+            assert_eq!(unsafe { *(obj.as_ptr() as *const u64) }, 0xC0FFEE);
+        }
+    }
+
+    #[test]
+    fn arc_layout_matches_arc_allocation() {
+        use std::sync::Arc;
+        // The data pointer of an Arc sits right after the two counters.
+        let arc = Arc::new([0u8; 40]);
+        let layout = arc_layout::<[u8; 40]>();
+        assert_eq!(layout.size(), 16 + 40);
+        assert_eq!(layout.align(), 8);
+        let data = Arc::as_ptr(&arc) as usize;
+        assert_eq!(data % layout.align(), 0);
+        assert_eq!(arc_layout::<u128>().size(), 32);
+    }
+}
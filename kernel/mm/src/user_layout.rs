@@ -0,0 +1,263 @@
+//! User virtual address space layout.
+//!
+//! Describes where a freshly exec'd process gets its main stack, mmap
+//! region, position-independent image, program interpreter, and program
+//! break. A [`UserLayout`] is either [`UserLayout::FIXED`] (deterministic, for
+//! debugging) or [`UserLayout::randomized`] from a per-exec seed (user
+//! ASLR).
+//!
+//! ```text
+//! 0x7FFF_FFFE_0000  sigreturn trampoline
+//! USER_STACK_CEILING
+//!   ... up to USER_STACK_RANDOM_RANGE ...
+//! stack_top         main stack, grows down on demand to top - USER_STACK_MAX_SIZE
+//!   ...
+//! USER_INTERP_BASE + up to USER_INTERP_RANDOM_RANGE  program interpreter (ld.so)
+//! USER_INTERP_BASE
+//! USER_PIE_BASE + up to USER_PIE_RANDOM_RANGE     ET_DYN image, brk after it
+//! USER_PIE_BASE
+//! USER_MMAP_END
+//! mmap_base         mmap region (USER_MMAP_BASE + up to USER_MMAP_RANDOM_RANGE)
+//! ```
+//!
+//! [`UserStack`] tracks the mapped extent of the main stack so that the
+//! page fault handler can grow it below its current bottom.
+
+use crate::PAGE_SIZE;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+const TIB: u64 = 1024 * GIB;
+
+/// Highest possible main stack top. Kept well below the sigreturn
+/// trampoline page at `0x7FFF_FFFE_0000`.
+pub const USER_STACK_CEILING: u64 = 0x7FFF_F000_0000;
+/// Range below [`USER_STACK_CEILING`] over which the stack top is
+/// randomized: 16 GiB.
+pub const USER_STACK_RANDOM_RANGE: u64 = 16 * GIB;
+/// Number of bytes of the main stack mapped at exec time: 64 KiB.
+pub const USER_STACK_INITIAL_SIZE: u64 = 64 * 1024;
+/// Maximum size the main stack may grow to: 8 MiB.
+pub const USER_STACK_MAX_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Lowest possible base of the mmap region.
+pub const USER_MMAP_BASE: u64 = 0x0000_4000_0000_0000;
+/// Range above [`USER_MMAP_BASE`] over which the mmap base is randomized:
+/// 1 TiB.
+pub const USER_MMAP_RANDOM_RANGE: u64 = TIB;
+/// End (exclusive) of the mmap region, just below the PIE window.
+pub const USER_MMAP_END: u64 = 0x0000_5000_0000_0000;
+
+/// Lowest possible load base of a position-independent (`ET_DYN`) image.
+pub const USER_PIE_BASE: u64 = 0x0000_5555_5540_0000;
+/// Range above [`USER_PIE_BASE`] over which the load base is randomized:
+/// 1 TiB.
+pub const USER_PIE_RANDOM_RANGE: u64 = TIB;
+/// Alignment of the randomized load base: 2 MiB, so that segments with
+/// large `p_align` keep their alignment.
+pub const USER_PIE_ALIGN: u64 = 2 * 1024 * 1024;
+
+/// Lowest possible load base of the program interpreter named by an
+/// image's `PT_INTERP`, well above the PIE window.
+pub const USER_INTERP_BASE: u64 = 0x0000_7000_0000_0000;
+/// Range above [`USER_INTERP_BASE`] over which the interpreter base is
+/// randomized: 1 TiB. Aligned to [`USER_PIE_ALIGN`] like the image base.
+pub const USER_INTERP_RANDOM_RANGE: u64 = TIB;
+
+/// Range above the end of the image over which the initial program break
+/// is randomized: 32 MiB.
+pub const USER_BRK_RANDOM_RANGE: u64 = 32 * 1024 * 1024;
+
+/// Placement of the per-process user regions chosen at exec time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserLayout {
+    /// Top of the main stack (exclusive, page-aligned).
+    pub stack_top: u64,
+    /// Base of the mmap region (page-aligned).
+    pub mmap_base: u64,
+    /// Load base for position-independent images (2 MiB-aligned).
+    pub pie_base: u64,
+    /// Gap between the end of the image and the initial program break
+    /// (page-aligned).
+    pub brk_offset: u64,
+    /// Load base for the program interpreter (2 MiB-aligned).
+    pub interp_base: u64,
+}
+
+impl UserLayout {
+    /// The deterministic layout used when user ASLR is disabled.
+    pub const FIXED: Self = Self {
+        stack_top: USER_STACK_CEILING,
+        mmap_base: USER_MMAP_BASE,
+        pie_base: USER_PIE_BASE,
+        brk_offset: 0,
+        interp_base: USER_INTERP_BASE,
+    };
+
+    /// Derives a randomized layout from `seed`.
+    ///
+    /// Each region gets an independent offset drawn from its own
+    /// sub-seed, so learning one base does not reveal the others.
+    pub fn randomized(seed: u64) -> Self {
+        let mut state = seed;
+        let mut next = || {
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        let page = PAGE_SIZE as u64;
+
+        Self {
+            stack_top: USER_STACK_CEILING - random_offset(next(), USER_STACK_RANDOM_RANGE, page),
+            mmap_base: USER_MMAP_BASE + random_offset(next(), USER_MMAP_RANDOM_RANGE, page),
+            pie_base: USER_PIE_BASE + random_offset(next(), USER_PIE_RANDOM_RANGE, USER_PIE_ALIGN),
+            brk_offset: random_offset(next(), USER_BRK_RANDOM_RANGE, page),
+            interp_base: USER_INTERP_BASE
+                + random_offset(next(), USER_INTERP_RANDOM_RANGE, USER_PIE_ALIGN),
+        }
+    }
+
+    /// Size of the mmap region starting at [`mmap_base`](Self::mmap_base).
+    pub fn mmap_size(&self) -> u64 {
+        USER_MMAP_END - self.mmap_base
+    }
+
+    /// Returns the initial program break for an image whose highest
+    /// segment ends at `image_end`.
+    pub fn brk_start(&self, image_end: u64) -> u64 {
+        page_align_up(image_end) + self.brk_offset
+    }
+
+    /// Returns the main stack as mapped at exec time.
+    pub fn initial_stack(&self) -> UserStack {
+        UserStack {
+            top: self.stack_top,
+            bottom: self.stack_top - USER_STACK_INITIAL_SIZE,
+            limit: self.stack_top - USER_STACK_MAX_SIZE,
+        }
+    }
+}
+
+/// Mapped extent of a process's main stack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserStack {
+    /// Top of the stack (exclusive).
+    pub top: u64,
+    /// Lowest mapped address. Pages in `[bottom, top)` are mapped.
+    pub bottom: u64,
+    /// Lowest address the stack may grow down to.
+    pub limit: u64,
+}
+
+impl UserStack {
+    /// Returns `true` if `addr` lies in the mapped part of the stack.
+    pub fn contains(&self, addr: u64) -> bool {
+        (self.bottom..self.top).contains(&addr)
+    }
+
+    /// Returns the new bottom if a fault at `addr` should grow the stack.
+    ///
+    /// The stack grows when `addr` lies below the mapped bottom but not
+    /// below [`limit`](Self::limit); every page from the faulting one up
+    /// to the current bottom is then mapped.
+    pub fn grow_to(&self, addr: u64) -> Option<u64> {
+        (self.limit..self.bottom)
+            .contains(&addr)
+            .then(|| addr & !(PAGE_SIZE as u64 - 1))
+    }
+}
+
+/// Returns a multiple of `align` in `[0, range)` derived from `random`.
+fn random_offset(random: u64, range: u64, align: u64) -> u64 {
+    (random % (range / align)) * align
+}
+
+/// Rounds `addr` up to the next page boundary.
+fn page_align_up(addr: u64) -> u64 {
+    let mask = PAGE_SIZE as u64 - 1;
+    (addr + mask) & !mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: u64 = PAGE_SIZE as u64;
+
+    #[test]
+    fn fixed_layout_is_consistent() {
+        let layout = UserLayout::FIXED;
+        assert_eq!(layout.stack_top, USER_STACK_CEILING);
+        assert_eq!(layout.mmap_size(), USER_MMAP_END - USER_MMAP_BASE);
+        assert_eq!(layout.brk_start(0x40_1234), 0x40_2000);
+    }
+
+    #[test]
+    fn randomized_layout_stays_in_bounds() {
+        for seed in 0..1000u64 {
+            let layout = UserLayout::randomized(seed.wrapping_mul(0x1234_5678_9ABC_DEF1));
+
+            assert_eq!(layout.stack_top % PAGE, 0);
+            assert!(layout.stack_top <= USER_STACK_CEILING);
+            assert!(layout.stack_top > USER_STACK_CEILING - USER_STACK_RANDOM_RANGE);
+
+            assert_eq!(layout.mmap_base % PAGE, 0);
+            assert!(layout.mmap_base >= USER_MMAP_BASE);
+            assert!(layout.mmap_base < USER_MMAP_BASE + USER_MMAP_RANDOM_RANGE);
+
+            assert_eq!(layout.pie_base % USER_PIE_ALIGN, 0);
+            assert!(layout.pie_base >= USER_PIE_BASE);
+            assert!(layout.pie_base < USER_PIE_BASE + USER_PIE_RANDOM_RANGE);
+
+            assert_eq!(layout.brk_offset % PAGE, 0);
+            assert!(layout.brk_offset < USER_BRK_RANDOM_RANGE);
+
+            assert_eq!(layout.interp_base % USER_PIE_ALIGN, 0);
+            assert!(layout.interp_base >= USER_INTERP_BASE);
+            assert!(layout.interp_base < USER_INTERP_BASE + USER_INTERP_RANDOM_RANGE);
+        }
+    }
+
+    #[test]
+    fn regions_do_not_overlap() {
+        assert!(USER_MMAP_BASE + USER_MMAP_RANDOM_RANGE < USER_MMAP_END);
+        assert!(USER_MMAP_END <= USER_PIE_BASE);
+        assert!(USER_PIE_BASE + USER_PIE_RANDOM_RANGE + USER_BRK_RANDOM_RANGE < USER_INTERP_BASE);
+        assert!(
+            USER_INTERP_BASE + USER_INTERP_RANDOM_RANGE
+                < USER_STACK_CEILING - USER_STACK_RANDOM_RANGE - USER_STACK_MAX_SIZE
+        );
+    }
+
+    #[test]
+    fn randomized_layout_varies_with_seed() {
+        let a = UserLayout::randomized(1);
+        let b = UserLayout::randomized(2);
+        assert_ne!(a.stack_top, b.stack_top);
+        assert_ne!(a.mmap_base, b.mmap_base);
+        assert_ne!(a.pie_base, b.pie_base);
+        assert_ne!(a.interp_base, b.interp_base);
+        assert_eq!(a, UserLayout::randomized(1));
+    }
+
+    #[test]
+    fn initial_stack_extent() {
+        let stack = UserLayout::FIXED.initial_stack();
+        assert_eq!(stack.top - stack.bottom, USER_STACK_INITIAL_SIZE);
+        assert_eq!(stack.top - stack.limit, USER_STACK_MAX_SIZE);
+        assert!(stack.contains(stack.top - 8));
+        assert!(!stack.contains(stack.top));
+        assert!(!stack.contains(stack.bottom - 1));
+    }
+
+    #[test]
+    fn stack_grows_only_within_limit() {
+        let stack = UserLayout::FIXED.initial_stack();
+        assert_eq!(stack.grow_to(stack.bottom - 1), Some(stack.bottom - PAGE));
+        assert_eq!(stack.grow_to(stack.limit + 5), Some(stack.limit));
+        assert_eq!(stack.grow_to(stack.limit - 1), None);
+        assert_eq!(stack.grow_to(stack.bottom), None);
+        assert_eq!(stack.grow_to(stack.top), None);
+    }
+}
@@ -4,7 +4,8 @@
 //! regions (heap, stacks, MMIO). Uses [`RegionAllocator`] for the heap
 //! (bump-only) and [`FreeRegionAllocator`] for stacks and MMIO (with
 //! deallocation support). A [`PageMapper`] implementation handles page
-//! table manipulation.
+//! table manipulation; when it also handles 2 MiB pages, the heap is backed
+//! by huge pages wherever the new range covers an aligned 2 MiB chunk.
 
 use hadron_core::addr::{PhysAddr, VirtAddr};
 
@@ -12,7 +13,10 @@ use crate::layout::{INITIAL_HEAP_SIZE, MemoryLayout};
 use crate::mapper::{MapFlags, MapFlush, PageMapper, PageTranslator, UnmapError};
 use crate::region::{FreeRegionAllocator, RegionAllocator};
 use crate::{FrameAllocator, PAGE_SIZE, VmmError};
-use hadron_core::paging::{Page, PhysFrame, Size4KiB};
+use hadron_core::paging::{Page, PhysFrame, Size2MiB, Size4KiB};
+
+/// Size of a 2 MiB huge page.
+const HUGE_PAGE_SIZE: u64 = 0x20_0000;
 
 /// Default kernel stack size: 64 KiB (16 pages).
 const KERNEL_STACK_SIZE: u64 = 64 * 1024;
@@ -134,7 +138,11 @@ pub struct Vmm<M: PageMapper<Size4KiB> + PageTranslator> {
 impl<M: PageMapper<Size4KiB> + PageTranslator> Vmm<M> {
     /// Creates a new VMM wrapping the given root page table.
     pub fn new(root_phys: PhysAddr, mapper: M, hhdm_offset: VirtAddr, max_phys: u64) -> Self {
-        let layout = MemoryLayout::new(hhdm_offset, max_phys);
+        Self::with_layout(root_phys, mapper, MemoryLayout::new(hhdm_offset, max_phys))
+    }
+
+    /// Creates a new VMM with an explicit (e.g. KASLR-randomized) layout.
+    pub fn with_layout(root_phys: PhysAddr, mapper: M, layout: MemoryLayout) -> Self {
         Self {
             root_phys,
             mapper,
@@ -378,6 +386,55 @@ impl<M: PageMapper<Size4KiB> + PageTranslator> Vmm<M> {
         Ok(frame)
     }
 
+    /// Changes the flags of a mapped 4 KiB page and flushes the TLB.
+    ///
+    /// Used to tighten permissions on kernel image pages, e.g. making the
+    /// read-only-after-init section read-only once boot is done.
+    pub fn protect_page(&mut self, page: Page<Size4KiB>, flags: MapFlags) -> Result<(), VmmError> {
+        // SAFETY: The Vmm owns the root page table; only the permission
+        // bits of an existing mapping change.
+        let flush = unsafe {
+            self.mapper
+                .update_flags(self.root_phys, page, flags)
+                .map_err(|e| match e {
+                    UnmapError::NotMapped => VmmError::NotMapped,
+                    UnmapError::SizeMismatch => VmmError::SizeMismatch,
+                })?
+        };
+        flush.flush();
+        Ok(())
+    }
+
+    /// Maps a zeroed 4 KiB page at every page of `[start, end)` that is not
+    /// mapped yet, leaving existing mappings alone.
+    ///
+    /// Used for KASAN shadow memory, whose pages are shared by the shadow
+    /// of neighbouring ranges.
+    pub fn map_zeroed(
+        &mut self,
+        start: VirtAddr,
+        end: VirtAddr,
+        alloc: &mut impl FrameAllocator<Size4KiB>,
+    ) -> Result<(), VmmError> {
+        let page_size = PAGE_SIZE as u64;
+        let flags = MapFlags::WRITABLE | MapFlags::GLOBAL;
+        let mut virt = start.align_down(page_size);
+        while virt < end {
+            if self.translate(virt).is_none() {
+                let frame = alloc.allocate_frame().ok_or(VmmError::OutOfMemory)?;
+                // Fresh mapping, never in TLB.
+                self.map_page(Page::containing_address(virt), frame, flags, alloc)?
+                    .ignore();
+                // SAFETY: `virt` was just mapped to a valid physical frame.
+                unsafe {
+                    core::ptr::write_bytes(virt.as_mut_ptr::<u8>(), 0, PAGE_SIZE);
+                }
+            }
+            virt = virt + page_size;
+        }
+        Ok(())
+    }
+
     /// Translates a virtual address to a physical address.
     pub fn translate(&self, virt: VirtAddr) -> Option<PhysAddr> {
         // SAFETY: The Vmm owns root_phys; a read-only page table walk is safe.
@@ -402,3 +459,83 @@ impl<M: PageMapper<Size4KiB> + PageTranslator> Vmm<M> {
             .map_err(|_| VmmError::RegionExhausted)
     }
 }
+
+impl<M: PageMapper<Size4KiB> + PageMapper<Size2MiB> + PageTranslator> Vmm<M> {
+    /// Grows the kernel heap like [`grow_heap`](Self::grow_heap), but maps
+    /// each 2 MiB-aligned 2 MiB chunk of the new range with a huge page
+    /// when `alloc` can provide a 2 MiB frame, falling back to 4 KiB pages.
+    ///
+    /// Returns `(base_address_of_new_pages, actual_bytes_mapped)`.
+    pub fn grow_heap_huge(
+        &mut self,
+        bytes: u64,
+        alloc: &mut (impl FrameAllocator<Size4KiB> + FrameAllocator<Size2MiB>),
+    ) -> Result<(VirtAddr, u64), VmmError> {
+        let page_size = PAGE_SIZE as u64;
+        let page_count = (bytes + page_size - 1) / page_size;
+        let actual_bytes = page_count * page_size;
+
+        let base = self
+            .heap_alloc
+            .allocate(actual_bytes)
+            .ok_or(VmmError::RegionExhausted)?;
+        let end = base + actual_bytes;
+
+        let flags = MapFlags::WRITABLE | MapFlags::GLOBAL;
+
+        let mut virt = base;
+        while virt < end {
+            let huge_fits = virt.is_aligned(HUGE_PAGE_SIZE) && (end - virt) >= HUGE_PAGE_SIZE;
+            if huge_fits && let Some(frame) = FrameAllocator::<Size2MiB>::allocate_frame(alloc) {
+                // SAFETY: Same as grow_heap -- mapping within an allocated region.
+                let flush = unsafe {
+                    <M as PageMapper<Size2MiB>>::map(
+                        &self.mapper,
+                        self.root_phys,
+                        Page::containing_address(virt),
+                        frame,
+                        flags,
+                        &mut || {
+                            FrameAllocator::<Size4KiB>::allocate_frame(alloc)
+                                .expect("PMM: out of memory during heap grow")
+                        },
+                    )
+                };
+                // Fresh mapping, never in TLB.
+                flush.ignore();
+                // SAFETY: `virt` was just mapped to a valid 2 MiB frame.
+                unsafe {
+                    core::ptr::write_bytes(virt.as_mut_ptr::<u8>(), 0, HUGE_PAGE_SIZE as usize);
+                }
+                virt = virt + HUGE_PAGE_SIZE;
+                continue;
+            }
+
+            let frame =
+                FrameAllocator::<Size4KiB>::allocate_frame(alloc).ok_or(VmmError::OutOfMemory)?;
+            // SAFETY: Same as grow_heap -- mapping within an allocated region.
+            let flush = unsafe {
+                <M as PageMapper<Size4KiB>>::map(
+                    &self.mapper,
+                    self.root_phys,
+                    Page::containing_address(virt),
+                    frame,
+                    flags,
+                    &mut || {
+                        FrameAllocator::<Size4KiB>::allocate_frame(alloc)
+                            .expect("PMM: out of memory during heap grow")
+                    },
+                )
+            };
+            // Fresh mapping, never in TLB.
+            flush.ignore();
+            // SAFETY: `virt` was just mapped to a valid physical frame.
+            unsafe {
+                core::ptr::write_bytes(virt.as_mut_ptr::<u8>(), 0, PAGE_SIZE);
+            }
+            virt = virt + page_size;
+        }
+
+        Ok((base, actual_bytes))
+    }
+}
@@ -8,9 +8,8 @@ use core::alloc::Layout;
 
 use hadron_core::sync::SpinLock;
 
-use crate::FrameAllocator;
 use crate::hhdm;
-use crate::pmm::{BitmapFrameAllocRef, with};
+use crate::pmm::alloc_frame;
 
 const NUM_ZONES: usize = 8;
 const ZONE_SIZES: [usize; NUM_ZONES] = [32, 64, 128, 256, 512, 1024, 2048, 4096];
@@ -75,31 +74,28 @@ impl Zone {
 
     /// Allocates a new page from the PMM and carves it into blocks.
     fn grow(&mut self) -> Result<(), ZoneAllocError> {
-        with(|pmm| {
-            let mut alloc = BitmapFrameAllocRef(pmm);
-            let frame = alloc.allocate_frame().ok_or(ZoneAllocError::OutOfMemory)?;
+        let frame = alloc_frame().ok_or(ZoneAllocError::OutOfMemory)?;
 
-            let virt = hhdm::phys_to_virt(frame.start_address());
-            let page_ptr = virt.as_u64() as *mut u8;
+        let virt = hhdm::phys_to_virt(frame.start_address());
+        let page_ptr = virt.as_u64() as *mut u8;
 
-            // Zero the page.
-            unsafe {
-                core::ptr::write_bytes(page_ptr, 0, PAGE_SIZE);
-            }
+        // Zero the page.
+        unsafe {
+            core::ptr::write_bytes(page_ptr, 0, PAGE_SIZE);
+        }
 
-            // Carve page into blocks.
-            let blocks_per_page = PAGE_SIZE / self.block_size;
-            for i in (0..blocks_per_page).rev() {
-                let block = unsafe { page_ptr.add(i * self.block_size) as *mut FreeZoneBlock };
-                unsafe {
-                    (*block).next = self.free_head;
-                }
-                self.free_head = block;
+        // Carve page into blocks.
+        let blocks_per_page = PAGE_SIZE / self.block_size;
+        for i in (0..blocks_per_page).rev() {
+            let block = unsafe { page_ptr.add(i * self.block_size) as *mut FreeZoneBlock };
+            unsafe {
+                (*block).next = self.free_head;
             }
+            self.free_head = block;
+        }
 
-            self.pages_allocated += 1;
-            Ok(())
-        })
+        self.pages_allocated += 1;
+        Ok(())
     }
 }
 
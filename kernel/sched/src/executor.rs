@@ -6,19 +6,31 @@
 //! [`for_cpu`]). Tasks stay on their spawning CPU unless migrated by work
 //! stealing.
 //!
-//! Tasks are organized into three strict priority tiers: Critical, Normal,
+//! Tasks are organized into strict priority tiers: Critical, Normal, User,
 //! and Background. The executor always drains higher-priority tiers first.
+//! User tasks share their tier by weighted-fair scheduling: after each poll
+//! the executor charges the elapsed time, measured with the clock registered
+//! via [`set_clock_fn`], at the weight the task reported with
+//! [`set_task_weight`]. A User task that reports a real-time or deadline
+//! policy with [`set_task_policy`] runs ahead of the fair tasks instead; see
+//! [`hadron_core::rt`].
+//!
+//! A task may restrict the CPUs it runs on with [`set_task_affinity`]. The
+//! executor hands a task polled on a CPU outside its mask over to an allowed
+//! CPU, and work stealing never takes a task the thief may not run.
 
 use alloc::boxed::Box;
 use alloc::collections::BTreeMap;
 use core::future::Future;
 use core::pin::Pin;
 use core::task::{Context, Poll};
-use hadron_core::sync::atomic::{AtomicU64, Ordering};
+use hadron_core::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 
-use hadron_core::cpu_local::{CpuLocal, MAX_CPUS};
+use hadron_core::cpu_local::{CpuLocal, MAX_CPUS, current_cpu_id};
 use hadron_core::id::CpuId;
-use hadron_core::sync::{IrqSpinLock, LazyLock};
+use hadron_core::rt::{DeadlineParams, SchedPolicy};
+use hadron_core::sched::{Migration, NICE_0_WEIGHT};
+use hadron_core::sync::{AtomicFn, IrqSpinLock, LazyLock};
 use hadron_core::task::{Priority, TaskId, TaskMeta};
 
 /// Global task ID counter shared by all executors.
@@ -35,6 +47,123 @@ pub use hadron_core::sched::ReadyQueues;
 static EXECUTORS: CpuLocal<LazyLock<Executor>> =
     CpuLocal::new([const { LazyLock::new(Executor::new as fn() -> Executor) }; MAX_CPUS]);
 
+/// Registered nanosecond clock used to measure how long each poll ran.
+static CLOCK_FN: AtomicFn<fn() -> u64> = AtomicFn::null();
+
+/// Weight reported by the task being polled on each CPU.
+static TASK_WEIGHT: CpuLocal<AtomicU32> =
+    CpuLocal::new([const { AtomicU32::new(NICE_0_WEIGHT) }; MAX_CPUS]);
+
+/// CPU affinity mask reported by the task being polled on each CPU.
+static TASK_AFFINITY: CpuLocal<AtomicU64> =
+    CpuLocal::new([const { AtomicU64::new(u64::MAX) }; MAX_CPUS]);
+
+/// Scheduling policy reported by the task being polled on each CPU.
+static TASK_POLICY: CpuLocal<PolicySlot> = CpuLocal::new([const { PolicySlot::new() }; MAX_CPUS]);
+
+/// A [`SchedPolicy`] stored in atomics, so each CPU can hold the policy of
+/// the task it is polling without a lock.
+///
+/// Only its own CPU touches a slot, so relaxed ordering is enough.
+struct PolicySlot {
+    /// 0 = Normal, 1 = FIFO, 2 = round-robin, 3 = deadline.
+    kind: AtomicU32,
+    /// Real-time priority, for FIFO and round-robin.
+    priority: AtomicU32,
+    /// Deadline reservation.
+    runtime_ns: AtomicU64,
+    deadline_ns: AtomicU64,
+    period_ns: AtomicU64,
+}
+
+impl PolicySlot {
+    const fn new() -> Self {
+        Self {
+            kind: AtomicU32::new(0),
+            priority: AtomicU32::new(0),
+            runtime_ns: AtomicU64::new(0),
+            deadline_ns: AtomicU64::new(0),
+            period_ns: AtomicU64::new(0),
+        }
+    }
+
+    fn store(&self, policy: SchedPolicy) {
+        let kind = match policy {
+            SchedPolicy::Normal => 0,
+            SchedPolicy::Fifo(_) => 1,
+            SchedPolicy::RoundRobin(_) => 2,
+            SchedPolicy::Deadline(params) => {
+                self.runtime_ns.store(params.runtime_ns, Ordering::Relaxed);
+                self.deadline_ns
+                    .store(params.deadline_ns, Ordering::Relaxed);
+                self.period_ns.store(params.period_ns, Ordering::Relaxed);
+                3
+            }
+        };
+        let priority = policy.rt_priority().unwrap_or(0);
+        self.priority.store(u32::from(priority), Ordering::Relaxed);
+        self.kind.store(kind, Ordering::Relaxed);
+    }
+
+    fn load(&self) -> SchedPolicy {
+        #[expect(clippy::cast_possible_truncation, reason = "stored from a u8")]
+        let priority = self.priority.load(Ordering::Relaxed) as u8;
+        match self.kind.load(Ordering::Relaxed) {
+            1 => SchedPolicy::Fifo(priority),
+            2 => SchedPolicy::RoundRobin(priority),
+            3 => SchedPolicy::Deadline(DeadlineParams {
+                runtime_ns: self.runtime_ns.load(Ordering::Relaxed),
+                deadline_ns: self.deadline_ns.load(Ordering::Relaxed),
+                period_ns: self.period_ns.load(Ordering::Relaxed),
+            }),
+            _ => SchedPolicy::Normal,
+        }
+    }
+}
+
+/// Registers the clock used to charge run time to User tasks.
+///
+/// Must return monotonic nanoseconds. Until a clock is registered, every
+/// poll is charged a nominal [`UNCLOCKED_POLL_NS`].
+pub fn set_clock_fn(f: fn() -> u64) {
+    CLOCK_FN.store(f);
+}
+
+/// Run time charged per poll when no clock is registered.
+const UNCLOCKED_POLL_NS: u64 = 1_000_000;
+
+fn now_ns() -> Option<u64> {
+    CLOCK_FN.load_optional().map(|f| f())
+}
+
+/// Sets the scheduling weight of the task being polled on this CPU.
+///
+/// Only User tasks are weighted. The weight sticks with the task until it
+/// reports another one; see [`hadron_core::sched::nice_to_weight`].
+pub fn set_task_weight(weight: u32) {
+    TASK_WEIGHT.get().store(weight.max(1), Ordering::Relaxed);
+}
+
+/// Sets the scheduling policy of the task being polled on this CPU.
+///
+/// Only User tasks have a policy. It takes effect when the current poll
+/// returns and sticks until the task reports another one. The caller is
+/// responsible for validating the policy and for deadline admission.
+pub fn set_task_policy(policy: SchedPolicy) {
+    TASK_POLICY.get().store(policy);
+}
+
+/// Sets the CPUs the task being polled on this CPU may run on, as a mask
+/// with bit `n` for CPU `n`.
+///
+/// Takes effect when the current poll returns. The waker can only address
+/// CPUs below 64, so the mask covers all of them. An empty mask is ignored.
+pub fn set_task_affinity(mask: u64) {
+    if mask != 0 {
+        TASK_AFFINITY.get().store(mask, Ordering::Relaxed);
+    }
+}
+
 /// Returns a reference to the current CPU's executor.
 pub fn global() -> &'static Executor {
     EXECUTORS.get()
@@ -52,12 +181,29 @@ type TaskFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
 
 /// A stored task: its future plus metadata.
 ///
-/// Public so the kernel glue layer can pass `TaskEntry` values through
-/// work-stealing function pointers.
+/// Entries are boxed so the task map only moves pointers; the kernel serves
+/// the boxes from a dedicated slab cache. Public so the kernel glue layer
+/// can pass `TaskEntry` values through work-stealing function pointers.
 pub struct TaskEntry {
     future: TaskFuture,
     #[allow(dead_code, reason = "reserved for task debugging diagnostics")]
     meta: TaskMeta,
+    /// Last weight reported with [`set_task_weight`].
+    weight: u32,
+    /// Last policy reported with [`set_task_policy`].
+    policy: SchedPolicy,
+    /// CPUs the task may run on (bit `n` = CPU `n`).
+    affinity: u64,
+    /// Scheduling state carried from the CPU the task last left; see
+    /// [`ReadyQueues::push_migrated`].
+    migration: Migration,
+}
+
+impl TaskEntry {
+    /// Returns `true` if the task may run on `cpu`.
+    fn may_run_on(&self, cpu: CpuId) -> bool {
+        cpu.as_u32() < u64::BITS && self.affinity & (1 << cpu.as_u32()) != 0
+    }
 }
 
 /// Architecture-specific idle wait (enable interrupts + halt).
@@ -81,7 +227,7 @@ pub trait ArchHalt {
 /// CPU's executor and stay there unless migrated by work stealing.
 pub struct Executor {
     /// Task storage: maps TaskId -> task entry (future + metadata).
-    tasks: IrqSpinLock<BTreeMap<TaskId, TaskEntry>>,
+    tasks: IrqSpinLock<BTreeMap<TaskId, Box<TaskEntry>>>,
     /// Priority-aware ready queues.
     pub(crate) ready_queues: IrqSpinLock<ReadyQueues>,
 }
@@ -95,7 +241,7 @@ impl Executor {
         }
     }
 
-    /// Attempts to steal one task from this executor for work stealing.
+    /// Attempts to steal one task from this executor for `thief`.
     ///
     /// Steals from the back of the ready queue (to preserve locality for
     /// the victim's hot tasks) and removes the corresponding `TaskEntry`
@@ -104,26 +250,31 @@ impl Executor {
     /// Returns `None` if:
     /// - The ready queues or task map can't be locked (contention)
     /// - No stealable tasks exist (Critical tasks are never stolen)
-    /// - The task is currently being polled (entry not in task map)
+    /// - Every candidate is being polled (entry not in task map) or has an
+    ///   affinity mask that excludes `thief`
     /// Public so the kernel glue layer can call this for work stealing.
-    pub fn steal_task(&self) -> Option<(TaskId, Priority, TaskEntry)> {
+    pub fn steal_task(&self, thief: CpuId) -> Option<(TaskId, Priority, Box<TaskEntry>)> {
         let mut rq = self.ready_queues.try_lock()?;
-        let (priority, id) = rq.steal_one()?;
         // Hold ready_queues lock while checking tasks to prevent the
         // stolen task ID from being lost. try_lock avoids deadlock with
         // spawn (which takes tasks then ready_queues — opposite order).
-        let entry = self
-            .tasks
-            .try_lock()
-            .and_then(|mut tasks| tasks.remove(&id));
-        match entry {
-            Some(entry) => Some((id, priority, entry)),
-            None => {
-                // Task is being polled or tasks lock contended — put it back.
-                rq.push(priority, id);
-                None
-            }
-        }
+        let mut tasks = self.tasks.try_lock()?;
+        let (priority, id) =
+            rq.steal_one_where(|id| tasks.get(&id).is_some_and(|entry| entry.may_run_on(thief)))?;
+        let mut entry = tasks.remove(&id)?;
+        entry.migration = rq.forget(id);
+        Some((id, priority, entry))
+    }
+
+    /// Returns `true` if a task is waiting to be polled.
+    pub fn has_ready(&self) -> bool {
+        self.ready_queues.lock().has_ready()
+    }
+
+    /// Returns when the next throttled deadline task gets a new budget, in
+    /// the clock registered with [`set_clock_fn`].
+    pub fn next_release(&self) -> Option<u64> {
+        self.ready_queues.lock().next_release()
     }
 
     /// Spawns a new async task with default metadata (Normal priority).
@@ -139,16 +290,21 @@ impl Executor {
     ) -> TaskId {
         let id = TaskId(NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed));
         let priority = meta.priority;
-        // Allocate the boxed future BEFORE acquiring the tasks lock to avoid
+        // Allocate the boxed entry BEFORE acquiring the tasks lock to avoid
         // a level ordering violation (tasks=14 → HEAP=1 is descending).
-        let boxed_future = Box::pin(future);
-        self.tasks.lock().insert(
-            id,
-            TaskEntry {
-                future: boxed_future,
-                meta,
-            },
-        );
+        let affinity = match meta.affinity {
+            Some(cpu) if cpu.as_u32() < u64::BITS => 1 << cpu.as_u32(),
+            _ => u64::MAX,
+        };
+        let entry = Box::new(TaskEntry {
+            future: Box::pin(future),
+            meta,
+            weight: NICE_0_WEIGHT,
+            policy: SchedPolicy::Normal,
+            affinity,
+            migration: Migration::default(),
+        });
+        self.tasks.lock().insert(id, entry);
         self.ready_queues.lock().push(priority, id);
         id
     }
@@ -157,11 +313,12 @@ impl Executor {
     ///
     /// The `halt` parameter provides architecture-specific idle wait
     /// (enable interrupts + halt). The `steal_fn` attempts to steal a
-    /// task from another CPU's executor when this one is idle.
+    /// task from another CPU's executor when this one is idle; it must
+    /// only return tasks allowed to run on this CPU.
     pub fn run(
         &self,
         halt: &dyn ArchHalt,
-        steal_fn: fn() -> Option<(TaskId, Priority, TaskEntry)>,
+        steal_fn: fn() -> Option<(TaskId, Priority, Box<TaskEntry>)>,
     ) -> ! {
         loop {
             // Clear the stale preempt_pending flag left by the timer
@@ -184,8 +341,7 @@ impl Executor {
                 // Insert the stolen task into our local task map and
                 // ready queue. When polled, the new waker will encode
                 // this CPU's ID, effectively migrating the task.
-                self.tasks.lock().insert(id, entry);
-                self.ready_queues.lock().push(priority, id);
+                self.adopt(id, priority, entry);
                 continue;
             }
 
@@ -213,9 +369,15 @@ impl Executor {
     /// after it is re-inserted.
     fn poll_ready_tasks(&self) {
         loop {
-            let (priority, id) = match self.ready_queues.lock().pop() {
-                Some(pair) => pair,
-                None => break,
+            let next = {
+                let mut rq = self.ready_queues.lock();
+                if let Some(now) = now_ns() {
+                    rq.update_clock(now);
+                }
+                rq.pop()
+            };
+            let Some((priority, id)) = next else {
+                break;
             };
 
             let waker = super::waker::task_waker(id, priority);
@@ -232,15 +394,50 @@ impl Executor {
             // ring-3 timer trap inside process_task will longjmp back,
             // causing the future to yield and re-queue itself at the back.
             if let Some(mut entry) = entry {
-                match entry.future.as_mut().poll(&mut cx) {
+                TASK_WEIGHT.get().store(entry.weight, Ordering::Relaxed);
+                TASK_POLICY.get().store(entry.policy);
+                TASK_AFFINITY.get().store(entry.affinity, Ordering::Relaxed);
+                let start = now_ns();
+
+                let poll = entry.future.as_mut().poll(&mut cx);
+
+                let ran = match (start, now_ns()) {
+                    (Some(start), Some(end)) => end.saturating_sub(start),
+                    _ => UNCLOCKED_POLL_NS,
+                };
+                entry.weight = TASK_WEIGHT.get().load(Ordering::Relaxed);
+                entry.affinity = TASK_AFFINITY.get().load(Ordering::Relaxed);
+                let policy = TASK_POLICY.get().load();
+                if policy != entry.policy {
+                    entry.policy = policy;
+                    if priority == Priority::User {
+                        self.ready_queues.lock().set_policy(id, policy);
+                    }
+                }
+
+                match poll {
                     Poll::Ready(()) => {
                         // Task complete — don't store it back.
+                        if priority == Priority::User {
+                            self.ready_queues.lock().forget(id);
+                        }
                     }
                     Poll::Pending => {
-                        // Brief lock: put future back.
-                        self.tasks.lock().insert(id, entry);
+                        if priority == Priority::User {
+                            self.ready_queues.lock().charge(id, ran, entry.weight);
+                        }
+                        let cpu = CpuId::new(current_cpu_id());
+                        if entry.may_run_on(cpu) {
+                            // Brief lock: put future back.
+                            self.tasks.lock().insert(id, entry);
+                        } else {
+                            self.hand_over(id, priority, entry);
+                        }
                     }
                 }
+            } else if priority == Priority::User {
+                // Stale wakeup for a task that finished or left this CPU.
+                self.ready_queues.lock().forget(id);
             }
 
             // Yield point: if a ring-3 timer preemption set the flag during
@@ -255,6 +452,31 @@ impl Executor {
     }
 }
 
+impl Executor {
+    /// Inserts a task that arrived from another CPU and makes it runnable.
+    fn adopt(&self, id: TaskId, priority: Priority, entry: Box<TaskEntry>) {
+        let (policy, migration) = (entry.policy, entry.migration);
+        self.tasks.lock().insert(id, entry);
+        let mut rq = self.ready_queues.lock();
+        if priority == Priority::User {
+            rq.set_policy(id, policy);
+        }
+        rq.push_migrated(priority, id, migration);
+    }
+
+    /// Moves a task whose affinity excludes this CPU to the lowest CPU in
+    /// its mask and wakes that CPU.
+    ///
+    /// The task is made runnable there even if it was waiting, so its next
+    /// poll registers wakers that point at its new CPU.
+    fn hand_over(&self, id: TaskId, priority: Priority, mut entry: Box<TaskEntry>) {
+        let target = CpuId::new(entry.affinity.trailing_zeros());
+        entry.migration = self.ready_queues.lock().forget(id);
+        for_cpu(target).adopt(id, priority, entry);
+        super::waker::send_wake_ipi(target);
+    }
+}
+
 impl Default for Executor {
     fn default() -> Self {
         Self::new()
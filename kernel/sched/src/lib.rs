@@ -20,7 +20,7 @@ pub mod primitives;
 pub mod timer;
 pub mod waker;
 
-pub use executor::Executor;
+pub use executor::{Executor, set_task_affinity, set_task_policy, set_task_weight};
 pub use hadron_core::task::{Priority, TaskMeta};
 
 use hadron_core::sync::atomic::{AtomicBool, Ordering};
@@ -72,6 +72,17 @@ pub fn spawn_background(
     )
 }
 
+/// Spawns a User-priority task (a user process or thread).
+///
+/// User tasks share the CPU by weight unless they report a real-time or
+/// deadline policy; see [`set_task_weight`] and [`set_task_policy`].
+pub fn spawn_user(
+    name: &'static str,
+    future: impl core::future::Future<Output = ()> + Send + 'static,
+) -> TaskId {
+    executor().spawn_with_meta(future, TaskMeta::new(name).with_priority(Priority::User))
+}
+
 /// Sets the preemption-pending flag on the current CPU (called from timer interrupt).
 pub fn set_preempt_pending() {
     PREEMPT_PENDING.get().store(true, Ordering::Release);
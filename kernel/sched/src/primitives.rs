@@ -1,8 +1,8 @@
 //! Async scheduling primitives.
 //!
 //! Provides cooperative yielding and async combinators for kernel tasks.
-//! Timer-based sleeping (`sleep_ticks`, `sleep_ms`) stays in the kernel
-//! because it depends on `time::timer_ticks()`.
+//! Timer-based sleeping (`sleep_ticks`, `sleep_ms`, `sleep_nanos`) stays in
+//! the kernel because it depends on the kernel clock.
 
 use core::future::Future;
 use core::pin::Pin;
@@ -1,78 +1,223 @@
-//! Timer-based waker registry.
+//! Per-CPU timer queues.
 //!
-//! Sleeping tasks register their waker and deadline here. The timer
-//! interrupt handler calls [`wake_expired`] each tick to wake tasks
-//! whose deadline has passed.
+//! Each CPU keeps a [`TimerWheel`] for coarse timeouts in ticks of
+//! [`TICK_NS`] and an [`HrTimerQueue`] for nanosecond deadlines. A task
+//! waits on either through a [`Timer`], which registers its waker on the
+//! current CPU's queues, follows the task to another CPU that stole it, and
+//! cancels itself when dropped.
+//!
+//! The timer interrupt handler calls [`wake_expired`] to wake tasks whose
+//! deadline has passed. Timers are measured against the monotonic clock
+//! the caller passes in; the kernel glue registers a callback with
+//! [`set_program_fn`] to reprogram the CPU's timer interrupt when a
+//! high-resolution timer becomes due before the next tick.
 
-use alloc::collections::BinaryHeap;
-use core::cmp::{Ordering, Reverse};
 use core::task::Waker;
 
 use planck_noalloc::vec::ArrayVec;
 
-use hadron_core::sync::IrqSpinLock;
+use hadron_core::cpu_local::{CpuLocal, MAX_CPUS, current_cpu_id};
+use hadron_core::sync::{AtomicFn, IrqSpinLock};
+use hadron_core::timer::{HrTimerQueue, TimerKey, TimerWheel};
+
+/// Length of a timer-wheel tick, in nanoseconds.
+pub const TICK_NS: u64 = 1_000_000;
 
-struct SleepEntry {
-    deadline: u64,
-    waker: Waker,
+/// The timers of one CPU.
+struct Queues {
+    wheel: TimerWheel<Waker>,
+    hr: HrTimerQueue<Waker>,
 }
 
-impl PartialEq for SleepEntry {
-    fn eq(&self, other: &Self) -> bool {
-        self.deadline == other.deadline
-    }
+static QUEUES: CpuLocal<IrqSpinLock<Queues>> = CpuLocal::new(
+    [const {
+        IrqSpinLock::leveled(
+            "TIMER_QUEUES",
+            12,
+            Queues {
+                wheel: TimerWheel::new(),
+                hr: HrTimerQueue::new(),
+            },
+        )
+    }; MAX_CPUS],
+);
+
+/// Called with the deadline of a high-resolution timer that became the
+/// earliest on this CPU.
+static PROGRAM_FN: AtomicFn<fn(u64)> = AtomicFn::null();
+
+/// Registers the callback that reprograms this CPU's timer interrupt for
+/// a new earliest high-resolution deadline.
+pub fn set_program_fn(f: fn(u64)) {
+    PROGRAM_FN.store(f);
+}
+
+/// When a [`Timer`] fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expiry {
+    /// At a tick, on the timer wheel.
+    Tick(u64),
+    /// At a monotonic nanosecond time, on the high-resolution queue.
+    Nanos(u64),
+}
+
+/// A one-shot timer that wakes a task at a deadline.
+///
+/// [`register`](Self::register) arms it with the waker of the task being
+/// polled; dropping it cancels it. Whether the deadline has passed is
+/// checked with [`is_expired`](Self::is_expired), since the waker may also
+/// be woken for other reasons.
+pub struct Timer {
+    expiry: Expiry,
+    /// CPU and key of the armed timer.
+    armed: Option<(u32, TimerKey)>,
 }
 
-impl Eq for SleepEntry {}
+impl Timer {
+    /// Creates a timer that fires at `tick` (see [`TICK_NS`]).
+    pub const fn at_tick(tick: u64) -> Self {
+        Self {
+            expiry: Expiry::Tick(tick),
+            armed: None,
+        }
+    }
+
+    /// Creates a high-resolution timer that fires at `nanos` on the
+    /// monotonic clock.
+    pub const fn at_nanos(nanos: u64) -> Self {
+        Self {
+            expiry: Expiry::Nanos(nanos),
+            armed: None,
+        }
+    }
+
+    /// Returns `true` if the deadline has passed at monotonic time
+    /// `now_ns`.
+    pub fn is_expired(&self, now_ns: u64) -> bool {
+        match self.expiry {
+            Expiry::Tick(tick) => now_ns / TICK_NS >= tick,
+            Expiry::Nanos(nanos) => now_ns >= nanos,
+        }
+    }
+
+    /// Arms the timer to wake `waker` at the deadline.
+    ///
+    /// A timer already armed on this CPU only has its waker updated. One
+    /// armed on another CPU, because the task was stolen since, moves to
+    /// this CPU's queues.
+    pub fn register(&mut self, waker: &Waker) {
+        let cpu = current_cpu_id();
+        if let Some((armed_cpu, key)) = self.armed {
+            if armed_cpu == cpu {
+                let mut queues = QUEUES.get().lock();
+                let current = match self.expiry {
+                    Expiry::Tick(_) => queues.wheel.get_mut(key),
+                    Expiry::Nanos(_) => queues.hr.get_mut(key),
+                };
+                if let Some(current) = current {
+                    if !current.will_wake(waker) {
+                        let old = core::mem::replace(current, waker.clone());
+                        drop(queues);
+                        drop(old);
+                    }
+                    return;
+                }
+                // Fired already: arm it again below.
+            } else {
+                self.cancel();
+            }
+        }
+
+        let mut queues = QUEUES.get().lock();
+        let (key, program) = match self.expiry {
+            Expiry::Tick(tick) => (queues.wheel.insert(tick, waker.clone()), None),
+            Expiry::Nanos(nanos) => {
+                let key = queues.hr.insert(nanos, waker.clone());
+                let earliest = queues.hr.next_expiry() == Some(nanos);
+                (key, earliest.then_some(nanos))
+            }
+        };
+        drop(queues);
+        self.armed = Some((cpu, key));
 
-impl PartialOrd for SleepEntry {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+        if let (Some(nanos), Some(program)) = (program, PROGRAM_FN.load_optional()) {
+            program(nanos);
+        }
+    }
+
+    /// Disarms the timer if it is armed.
+    pub fn cancel(&mut self) {
+        let Some((cpu, key)) = self.armed.take() else {
+            return;
+        };
+        let mut queues = QUEUES.get_for(cpu).lock();
+        let waker = match self.expiry {
+            Expiry::Tick(_) => queues.wheel.cancel(key),
+            Expiry::Nanos(_) => queues.hr.cancel(key),
+        };
+        // Drop the waker after releasing the lock.
+        drop(queues);
+        drop(waker);
     }
 }
 
-impl Ord for SleepEntry {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.deadline.cmp(&other.deadline)
+impl Drop for Timer {
+    fn drop(&mut self) {
+        self.cancel();
     }
 }
 
-static SLEEP_QUEUE: IrqSpinLock<BinaryHeap<Reverse<SleepEntry>>> =
-    IrqSpinLock::leveled("SLEEP_QUEUE", 12, BinaryHeap::new());
+/// Returns the monotonic time of the next timer event on this CPU, or
+/// `None` if it has no timers.
+///
+/// Lets a CPU that stops its periodic tick program a timer interrupt for
+/// the next wakeup instead. For timers far out on the wheel this may be an
+/// earlier tick at which they only move closer.
+pub fn next_expiry() -> Option<u64> {
+    let mut queues = QUEUES.get().lock();
+    let tick = queues
+        .wheel
+        .next_expiry()
+        .map(|tick| tick.saturating_mul(TICK_NS));
+    match (tick, queues.hr.next_expiry()) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
 
-/// Registers a waker to be called when `deadline` tick is reached.
-pub fn register_sleep_waker(deadline: u64, waker: Waker) {
-    SLEEP_QUEUE
-        .lock()
-        .push(Reverse(SleepEntry { deadline, waker }));
+/// Returns the earliest high-resolution deadline on this CPU.
+pub fn next_hr_expiry() -> Option<u64> {
+    QUEUES.get().lock().hr.next_expiry()
 }
 
-/// Maximum wakers drained per tick. If more are expired, they are deferred
-/// to the next tick (1 ms later). Keeps the ISR bounded and stack-allocated.
+/// Maximum wakers drained per call. If more are expired, they are left for
+/// the next timer interrupt. Keeps the ISR bounded and stack-allocated.
 const WAKE_BATCH_SIZE: usize = 32;
 
-/// Wakes all tasks whose sleep deadline has passed.
+/// Wakes the tasks whose timers on this CPU expired by monotonic time
+/// `now_ns`.
 ///
-/// Called from the timer interrupt handler on every tick. Drains expired
-/// entries into a stack-allocated batch, drops the SLEEP_QUEUE lock, then
-/// wakes outside the lock to avoid holding SLEEP_QUEUE while calling into
-/// the executor's ready queues.
-pub fn wake_expired(current_tick: u64) {
+/// Called from the timer interrupt handler. Drains expired timers into a
+/// stack-allocated batch, drops the queue lock, then wakes outside the
+/// lock to avoid holding it while calling into the executor's ready
+/// queues.
+pub fn wake_expired(now_ns: u64) {
     let mut batch = ArrayVec::<Waker, WAKE_BATCH_SIZE>::new();
 
     {
-        let mut queue = SLEEP_QUEUE.lock();
-        while batch.len() < WAKE_BATCH_SIZE {
-            match queue.peek() {
-                Some(entry) if entry.0.deadline <= current_tick => {
-                    let entry = queue.pop().unwrap();
-                    batch.push(entry.0.waker);
-                }
-                _ => break,
-            }
+        let mut queues = QUEUES.get().lock();
+        queues.hr.expire(now_ns, |waker| {
+            batch.push(waker);
+            batch.len() < WAKE_BATCH_SIZE
+        });
+        if batch.len() < WAKE_BATCH_SIZE {
+            queues.wheel.advance(now_ns / TICK_NS, |waker| {
+                batch.push(waker);
+                batch.len() < WAKE_BATCH_SIZE
+            });
         }
-        // Lock dropped here — remaining expired entries (if batch was full)
-        // will be picked up on the next tick (1 ms later).
+        // Lock dropped here — remaining expired timers (if the batch was
+        // full) are picked up by the next timer interrupt.
     }
 
     while let Some(waker) = batch.pop() {
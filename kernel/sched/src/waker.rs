@@ -5,7 +5,7 @@
 //! queue in the **originating CPU's** executor — not the current CPU's.
 //!
 //! Encoding (64-bit data pointer):
-//! - Bits 63-62: Priority (2 bits, 3 levels used)
+//! - Bits 63-62: Priority (2 bits, all 4 levels used)
 //! - Bits 61-56: CPU ID (6 bits, supports up to 64 CPUs)
 //! - Bits 55-0:  TaskId (56 bits)
 
@@ -90,10 +90,13 @@ fn wake_by_ref(data: *const ()) {
         .lock()
         .push(priority, id);
 
-    // If the target is a different CPU, send an IPI to wake it from HLT
-    // so it processes the newly enqueued task promptly.
-    let current = current_cpu_id();
-    if target_cpu.as_u32() != current {
+    send_wake_ipi(target_cpu);
+}
+
+/// Sends a wake IPI to `target_cpu` if it is not the current CPU, so it
+/// processes a newly enqueued task promptly instead of sleeping in HLT.
+pub(crate) fn send_wake_ipi(target_cpu: CpuId) {
+    if target_cpu.as_u32() != current_cpu_id() {
         if let Some(f) = WAKE_IPI_FN.load_optional() {
             f(target_cpu);
         }
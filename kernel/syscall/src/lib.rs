@@ -13,6 +13,8 @@
 
 hadron_syscall_macros::define_syscalls! {
     errors {
+        /// `EPERM` — operation not permitted.
+        EPERM = 1;
         /// `ENOENT` — no such file or directory.
         ENOENT = 2;
         /// `ESRCH` — no such process.
@@ -31,6 +33,8 @@ hadron_syscall_macros::define_syscalls! {
         EACCES = 13;
         /// `EFAULT` — bad address.
         EFAULT = 14;
+        /// `EBUSY` — device or resource busy.
+        EBUSY = 16;
         /// `EEXIST` — file exists.
         EEXIST = 17;
         /// `ENOTDIR` — not a directory.
@@ -59,10 +63,14 @@ hadron_syscall_macros::define_syscalls! {
         ENOTCONN = 107;
         /// `EISCONN` — transport endpoint is already connected.
         EISCONN = 106;
+        /// `ETIMEDOUT` — a wait timed out.
+        ETIMEDOUT = 110;
         /// `ECONNREFUSED` — connection refused (target not listening).
         ECONNREFUSED = 111;
         /// `EMSGSIZE` — message too long.
         EMSGSIZE = 90;
+        /// `ECANCELED` — operation canceled.
+        ECANCELED = 125;
     }
 
     types {
@@ -70,7 +78,7 @@ hadron_syscall_macros::define_syscalls! {
         ///
         /// Uses `u64` fields (not `i64`) because Hadron only supports monotonic
         /// boot-relative time — negative timestamps are impossible.
-        #[derive(Debug, Clone, Copy)]
+        #[derive(Debug, Clone, Copy, Default)]
         struct Timespec {
             /// Seconds since boot.
             tv_sec: u64,
@@ -111,13 +119,37 @@ hadron_syscall_macros::define_syscalls! {
             name: [u8; 32],
         }
 
-        /// Response for [`QUERY_PROCESSES`]: process table statistics.
+        /// Response for [`QUERY_PROCESSES`]: process table statistics and
+        /// memory accounting for the process selected by `sub_id`
+        /// (0 = the calling process).
         #[derive(Debug, Clone, Copy)]
         struct ProcessInfo {
             /// Number of active processes.
             count: u32,
             /// Padding for alignment.
             _pad: u32,
+            /// Resident set size of the selected process in bytes.
+            rss_bytes: u64,
+            /// Memory used by the selected process's page tables in bytes.
+            page_table_bytes: u64,
+            /// OOM killer score of the selected process (`0..=1000`).
+            oom_score: u32,
+            /// OOM killer bias of the selected process (`-1000..=1000`).
+            oom_score_adj: i32,
+            /// User time of all threads of the selected process, in nanoseconds.
+            user_ns: u64,
+            /// System time of all threads of the selected process, in nanoseconds.
+            system_ns: u64,
+        }
+
+        /// Response for [`QUERY_RUSAGE`]: CPU time of the calling thread,
+        /// process, or reaped children.
+        #[derive(Debug, Clone, Copy)]
+        struct RusageInfo {
+            /// Time spent executing in user mode, in nanoseconds.
+            user_ns: u64,
+            /// Time spent in the kernel on the caller's behalf, in nanoseconds.
+            system_ns: u64,
         }
 
         /// One entry in the [`QUERY_VMAPS`] response array.
@@ -161,10 +193,14 @@ hadron_syscall_macros::define_syscalls! {
             size: u64,
             /// Permissions: bit 0=read, bit 1=write, bit 2=exec.
             permissions: u32,
-            /// Padding to align rdev.
-            _pad2: u32,
+            /// POSIX permission bits (`0o7777`), without the file type.
+            mode: u32,
             /// Device number (makedev encoding) for char/block devices; 0 for others.
             rdev: u64,
+            /// Owning user ID.
+            uid: u32,
+            /// Owning group ID.
+            gid: u32,
         }
 
         /// Framebuffer information returned by `FBIOGET_INFO` ioctl.
@@ -250,6 +286,32 @@ hadron_syscall_macros::define_syscalls! {
             cwd_ptr: usize,
             /// Length of the CWD path string.
             cwd_len: usize,
+            /// User ID the child runs as (real, effective and saved).
+            /// [`ID_UNCHANGED`] inherits the parent's user IDs; anything else
+            /// requires a privileged parent.
+            uid: usize,
+            /// Group ID the child runs as (real, effective and saved).
+            /// [`ID_UNCHANGED`] inherits the parent's group IDs; anything
+            /// else requires a privileged parent and clears the
+            /// supplementary groups.
+            gid: usize,
+        }
+
+        /// Credentials of the calling process, returned by [`cred_get`].
+        #[derive(Debug, Clone, Copy)]
+        struct CredInfo {
+            /// Real user ID.
+            uid: u32,
+            /// Effective user ID.
+            euid: u32,
+            /// Saved set-user-ID.
+            suid: u32,
+            /// Real group ID.
+            gid: u32,
+            /// Effective group ID.
+            egid: u32,
+            /// Saved set-group-ID.
+            sgid: u32,
         }
 
         /// A poll descriptor for [`event_wait_many`].
@@ -266,6 +328,103 @@ hadron_syscall_macros::define_syscalls! {
             revents: u16,
         }
 
+        /// A ready event or an interest for [`epoll_wait`] and
+        /// [`epoll_ctl`], packed like Linux's x86-64 `struct epoll_event`.
+        #[derive(Debug, Clone, Copy, Default)]
+        #[repr(packed)]
+        struct EpollEvent {
+            /// Events bitmask (`POLLIN`, `POLLOUT`, …), plus [`EPOLLET`] and
+            /// [`EPOLLONESHOT`] in an interest.
+            events: u32,
+            /// Caller data, returned unchanged with each event.
+            data: u64,
+        }
+
+        /// Layout of an I/O ring, written by [`io_ring_create`].
+        ///
+        /// The ring is one shared memory region of `size` bytes, mapped
+        /// with [`mem_map_shared`] on the ring fd: an [`IoRingHeader`] at
+        /// offset 0, `sq_entries` [`IoOp`]s at `ops_offset` and
+        /// `cq_entries` [`IoCompletion`]s at `completions_offset`.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct IoRingParams {
+            /// Submission queue length, a power of two.
+            sq_entries: u32,
+            /// Completion queue length, twice `sq_entries`.
+            cq_entries: u32,
+            /// Offset of the submission queue entries.
+            ops_offset: u64,
+            /// Offset of the completion queue entries.
+            completions_offset: u64,
+            /// Size of the region to map.
+            size: u64,
+        }
+
+        /// Queue indices at the start of an I/O ring.
+        ///
+        /// Both queues are arrays indexed by `index & mask`; the indices
+        /// run freely and wrap. The producer of a queue advances its tail
+        /// and the consumer its head, and each side must read the other's
+        /// index with acquire ordering and publish its own with release
+        /// ordering. Userspace produces operations and consumes
+        /// completions.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct IoRingHeader {
+            /// Next operation the kernel will take.
+            sq_head: u32,
+            /// One past the last operation userspace has filled in.
+            sq_tail: u32,
+            /// `sq_entries - 1`.
+            sq_mask: u32,
+            /// Next completion userspace will take.
+            cq_head: u32,
+            /// One past the last completion the kernel has posted.
+            cq_tail: u32,
+            /// `cq_entries - 1`.
+            cq_mask: u32,
+            /// Reserved; zero.
+            _reserved: [u32; 10],
+        }
+
+        /// An I/O ring submission queue entry.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct IoOp {
+            /// Operation, one of the `IO_OP_*` codes.
+            opcode: u8,
+            /// [`IO_OP_LINK`] or 0.
+            flags: u8,
+            /// Reserved; zero.
+            _pad: u16,
+            /// File descriptor the operation applies to.
+            fd: i32,
+            /// File offset for [`IO_OP_PREAD`] and [`IO_OP_PWRITE`], or
+            /// nanoseconds for [`IO_OP_TIMEOUT`].
+            offset: u64,
+            /// User buffer, or a `struct sockaddr_un` for
+            /// [`IO_OP_CONNECT`].
+            addr: u64,
+            /// Buffer or address length.
+            len: u32,
+            /// Poll events for [`IO_OP_POLL`].
+            events: u32,
+            /// Caller data, returned unchanged in the completion.
+            user_data: u64,
+            /// Reserved; zero.
+            _reserved: [u64; 3],
+        }
+
+        /// An I/O ring completion queue entry.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct IoCompletion {
+            /// The operation's `user_data`.
+            user_data: u64,
+            /// What the equivalent syscall would return, or a negated
+            /// errno.
+            result: i32,
+            /// Reserved; zero.
+            _reserved: u32,
+        }
+
         /// Terminal I/O settings (POSIX `termios`).
         ///
         /// Controls line discipline behavior: canonical vs raw mode, echo,
@@ -325,6 +484,200 @@ hadron_syscall_macros::define_syscalls! {
             /// Entry name as UTF-8 bytes, not NUL-terminated.
             name: [u8; 60],
         }
+
+        /// Signal action for `task_sigaction`, laid out like C `struct sigaction`.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct SigAction {
+            /// `SIG_DFL`, `SIG_IGN`, or the handler's address.
+            handler: usize,
+            /// Signals blocked while the handler runs, in addition to the
+            /// delivered signal itself (unless `SA_NODEFER`).
+            mask: u64,
+            /// `SA_*` flags.
+            flags: u32,
+            /// Padding for alignment.
+            _pad: u32,
+            /// Ignored: handlers return through the kernel's trampoline.
+            restorer: usize,
+        }
+
+        /// Alternate signal stack for `sig_altstack`, laid out like C `stack_t`.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct SigAltStack {
+            /// Lowest address of the stack.
+            sp: usize,
+            /// `SS_DISABLE`, or `SS_ONSTACK` when reported.
+            flags: i32,
+            /// Padding for alignment.
+            _pad: u32,
+            /// Size of the stack in bytes.
+            size: usize,
+        }
+
+        /// Signal information, laid out like C `siginfo_t`.
+        ///
+        /// Passed to `SA_SIGINFO` handlers and returned by `sig_timedwait`.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct SigInfo {
+            /// Signal number.
+            signo: i32,
+            /// Always 0.
+            errno: i32,
+            /// Origin of the signal: `SI_USER`, `SI_QUEUE`, `SI_KERNEL`, …
+            code: i32,
+            /// PID of the sender, for `SI_USER` and `SI_QUEUE`.
+            pid: u32,
+            /// Real user ID of the sender, for `SI_USER` and `SI_QUEUE`.
+            uid: u32,
+            /// Exit status or signal, for `SIGCHLD`.
+            status: i32,
+            /// Faulting address, for fault signals.
+            addr: u64,
+            /// Value passed to `sig_queue`.
+            value: u64,
+            /// Reserved; zero.
+            _reserved: [u64; 8],
+        }
+
+        /// x87/SSE state at the point a signal interrupted the thread, in the
+        /// 512-byte `fxsave64` format and laid out like C `struct _libc_fpstate`.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct FpState {
+            /// x87 control word.
+            cwd: u16,
+            /// x87 status word.
+            swd: u16,
+            /// Abridged x87 tag word.
+            ftw: u16,
+            /// Last x87 opcode.
+            fop: u16,
+            /// Last x87 instruction pointer.
+            rip: u64,
+            /// Last x87 data pointer.
+            rdp: u64,
+            /// SSE control and status register.
+            mxcsr: u32,
+            /// Bits of `mxcsr` the CPU supports.
+            mxcsr_mask: u32,
+            /// `st0`–`st7`, 80 bits each padded to 16 bytes.
+            st: [[u16; 8]; 8],
+            /// `xmm0`–`xmm15`.
+            xmm: [[u32; 4]; 16],
+            /// Reserved; ignored on restore.
+            _reserved: [u32; 24],
+        }
+
+        /// User registers at the point a signal interrupted the thread, laid
+        /// out like the `gregs` of the C `mcontext_t`.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct MContext {
+            r8: u64,
+            r9: u64,
+            r10: u64,
+            r11: u64,
+            r12: u64,
+            r13: u64,
+            r14: u64,
+            r15: u64,
+            rdi: u64,
+            rsi: u64,
+            rbp: u64,
+            rbx: u64,
+            rdx: u64,
+            rax: u64,
+            rcx: u64,
+            rsp: u64,
+            rip: u64,
+            rflags: u64,
+            /// Segment selectors; always 0.
+            cs_gs_fs: u64,
+            /// Hardware error code; always 0.
+            err: u64,
+            /// Exception vector; always 0.
+            trapno: u64,
+            /// Old-style signal mask; always 0 (see `UContext::sigmask`).
+            oldmask: u64,
+            /// Faulting address; always 0.
+            cr2: u64,
+            /// Address of the saved [`FpState`] in the signal frame.
+            ///
+            /// `task_sigreturn` restores the FPU from it; 0 keeps the state
+            /// the handler left.
+            fpregs: u64,
+            /// Reserved; zero.
+            _reserved: [u64; 8],
+        }
+
+        /// Context saved when a signal handler runs, laid out like C
+        /// `ucontext_t`.
+        ///
+        /// `task_sigreturn` restores the registers in `mcontext` and the
+        /// mask in `sigmask`, so a handler may edit either to change where
+        /// and how the interrupted code resumes.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct UContext {
+            /// Always 0.
+            flags: u64,
+            /// Always 0.
+            link: u64,
+            /// Alternate stack in effect when the signal arrived.
+            stack: SigAltStack,
+            /// Interrupted registers.
+            mcontext: MContext,
+            /// Signal mask to restore when the handler returns.
+            sigmask: u64,
+        }
+
+        /// Scheduling policy and parameters for `sched_setattr` and
+        /// `sched_getattr`, laid out like Linux `struct sched_attr`.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct SchedAttr {
+            /// Size of this struct in bytes.
+            size: u32,
+            /// [`SCHED_OTHER`], [`SCHED_FIFO`], [`SCHED_RR`] or
+            /// [`SCHED_DEADLINE`].
+            policy: u32,
+            /// Reserved; must be 0.
+            flags: u64,
+            /// Nice value, for [`SCHED_OTHER`].
+            nice: i32,
+            /// Static priority (1 to 99), for [`SCHED_FIFO`] and [`SCHED_RR`].
+            priority: u32,
+            /// Run time per period in nanoseconds, for [`SCHED_DEADLINE`].
+            runtime: u64,
+            /// Relative deadline in nanoseconds, for [`SCHED_DEADLINE`].
+            deadline: u64,
+            /// Period in nanoseconds (0 = the deadline), for
+            /// [`SCHED_DEADLINE`].
+            period: u64,
+        }
+
+        /// Timer setting for `itimer_set` and `timer_settime`, laid out like
+        /// C `struct itimerspec`.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct ITimerSpec {
+            /// Period after the first expiry; zero for a one-shot timer.
+            interval: Timespec,
+            /// Time until the first expiry (or absolute expiry time with
+            /// [`TIMER_ABSTIME`]); zero disarms the timer.
+            value: Timespec,
+        }
+
+        /// Notification of a `timer_create` timer, laid out like the start
+        /// of C `struct sigevent`.
+        #[derive(Debug, Clone, Copy, Default)]
+        struct SigEvent {
+            /// Value passed to the handler in [`SigInfo::value`].
+            value: u64,
+            /// Signal to send.
+            signo: i32,
+            /// [`SIGEV_SIGNAL`], [`SIGEV_NONE`] or [`SIGEV_THREAD_ID`].
+            notify: i32,
+            /// Thread to signal, for [`SIGEV_THREAD_ID`].
+            tid: u32,
+            /// Padding for alignment.
+            _pad: u32,
+        }
     }
 
     constants {
@@ -334,16 +687,48 @@ hadron_syscall_macros::define_syscalls! {
         QUERY_UPTIME: u64 = 1;
         /// Query topic: kernel version information.
         QUERY_KERNEL_VERSION: u64 = 2;
-        /// Query topic: process table statistics.
+        /// Query topic: process table statistics and per-process memory usage.
+        /// `sub_id` selects the process by PID (0 = the calling process).
         QUERY_PROCESSES: u64 = 3;
         /// Query topic: virtual memory map for the calling process.
         QUERY_VMAPS: u64 = 4;
         /// Query topic: CPU capabilities (core count + feature flags + model string).
         QUERY_CPU_INFO: u64 = 5;
+        /// Query topic: CPU time of the caller. `sub_id` is one of
+        /// [`RUSAGE_SELF`], [`RUSAGE_THREAD`] or [`RUSAGE_CHILDREN`].
+        QUERY_RUSAGE: u64 = 6;
+        /// [`QUERY_RUSAGE`] selector: all threads of the calling process.
+        RUSAGE_SELF: usize = 0;
+        /// [`QUERY_RUSAGE`] selector: the calling thread.
+        RUSAGE_THREAD: usize = 1;
+        /// [`QUERY_RUSAGE`] selector: reaped children of the calling process
+        /// and their reaped descendants.
+        RUSAGE_CHILDREN: usize = 2;
+        /// [`sched_getpriority`] / [`sched_setpriority`] target: the process
+        /// `who` (0 = the caller).
+        PRIO_PROCESS: usize = 0;
+        /// [`sched_getpriority`] / [`sched_setpriority`] target: every process
+        /// in process group `who` (0 = the caller's group).
+        PRIO_PGRP: usize = 1;
+        /// [`sched_getpriority`] / [`sched_setpriority`] target: every process
+        /// whose real user ID is `who` (0 = the caller's real user ID).
+        PRIO_USER: usize = 2;
+        /// Scheduling policy: weighted-fair by nice value.
+        SCHED_OTHER: u32 = 0;
+        /// Scheduling policy: static priority, first in first out.
+        SCHED_FIFO: u32 = 1;
+        /// Scheduling policy: static priority with a time slice.
+        SCHED_RR: u32 = 2;
+        /// Scheduling policy: earliest deadline first with a CPU reservation.
+        SCHED_DEADLINE: u32 = 6;
         /// Monotonic clock: nanoseconds since boot, never adjusted.
         CLOCK_MONOTONIC: usize = 0;
         /// Real-time clock: Unix epoch seconds (wall-clock time).
         CLOCK_REALTIME: usize = 1;
+        /// CPU-time clock: user plus system time of the calling process.
+        CLOCK_PROCESS_CPUTIME_ID: usize = 2;
+        /// CPU-time clock: user plus system time of the calling thread.
+        CLOCK_THREAD_CPUTIME_ID: usize = 3;
         /// Inode type: regular file.
         INODE_TYPE_FILE: u8 = 0;
         /// Inode type: directory.
@@ -380,6 +765,8 @@ hadron_syscall_macros::define_syscalls! {
         MAP_ANONYMOUS: usize = 0x1;
         /// Memory mapping flag: shared/device-backed mapping.
         MAP_SHARED: usize = 0x2;
+        /// Memory mapping flag: back the mapping with 2 MiB huge pages.
+        MAP_HUGE: usize = 0x4;
         /// Open flag: open for reading.
         OPEN_READ: usize = 0x0001;
         /// Open flag: open for writing.
@@ -434,6 +821,66 @@ hadron_syscall_macros::define_syscalls! {
         PIPE_CLOEXEC: usize = 0x0020;
         /// Pipe2 flag: set `O_NONBLOCK` on both pipe fds.
         PIPE_NONBLOCK: usize = 0x0040;
+        /// `event_create` flag: reads return 1 and decrement the counter.
+        EFD_SEMAPHORE: usize = 0x0001;
+        /// `event_create` flag: set `O_CLOEXEC` on the fd.
+        EFD_CLOEXEC: usize = 0x0020;
+        /// `event_create` flag: set `O_NONBLOCK` on the fd.
+        EFD_NONBLOCK: usize = 0x0040;
+        /// `timerfd_create` flag: set `O_CLOEXEC` on the fd.
+        TFD_CLOEXEC: usize = 0x0020;
+        /// `timerfd_create` flag: set `O_NONBLOCK` on the fd.
+        TFD_NONBLOCK: usize = 0x0040;
+        /// `timerfd_settime` flag: the value is an absolute time.
+        TFD_TIMER_ABSTIME: usize = 0x0001;
+        /// `sig_fd` flag: set `O_CLOEXEC` on the fd.
+        SFD_CLOEXEC: usize = 0x0020;
+        /// `sig_fd` flag: set `O_NONBLOCK` on the fd.
+        SFD_NONBLOCK: usize = 0x0040;
+        /// `epoll_create` flag: set `O_CLOEXEC` on the fd.
+        EPOLL_CLOEXEC: usize = 0x0020;
+        /// `epoll_ctl` operation: add an fd to the interest list.
+        EPOLL_CTL_ADD: usize = 1;
+        /// `epoll_ctl` operation: remove an fd from the interest list.
+        EPOLL_CTL_DEL: usize = 2;
+        /// `epoll_ctl` operation: change the events and data of an fd.
+        EPOLL_CTL_MOD: usize = 3;
+        /// Interest flag: report an fd only when it becomes ready, not
+        /// while it stays ready.
+        EPOLLET: u32 = 1 << 31;
+        /// Interest flag: disable the interest after one event until it is
+        /// re-armed with [`EPOLL_CTL_MOD`].
+        EPOLLONESHOT: u32 = 1 << 30;
+        /// `io_ring_create` flag: set `O_CLOEXEC` on the fd.
+        IO_RING_CLOEXEC: usize = 0x0020;
+        /// Largest submission queue an I/O ring can have.
+        IO_RING_MAX_ENTRIES: usize = 4096;
+        /// I/O ring operation: do nothing and complete with 0.
+        IO_OP_NOP: u8 = 0;
+        /// I/O ring operation: `read` at the fd's offset, advancing it.
+        IO_OP_READ: u8 = 1;
+        /// I/O ring operation: `write` at the fd's offset, advancing it.
+        IO_OP_WRITE: u8 = 2;
+        /// I/O ring operation: read at `offset`, leaving the fd's offset.
+        IO_OP_PREAD: u8 = 3;
+        /// I/O ring operation: write at `offset`, leaving the fd's offset.
+        IO_OP_PWRITE: u8 = 4;
+        /// I/O ring operation: accept a connection; completes with the
+        /// new fd.
+        IO_OP_ACCEPT: u8 = 5;
+        /// I/O ring operation: connect a socket to the path in `addr`.
+        IO_OP_CONNECT: u8 = 6;
+        /// I/O ring operation: wait until the fd has one of `events`;
+        /// completes with the ready events.
+        IO_OP_POLL: u8 = 7;
+        /// I/O ring operation: complete with 0 after `offset` nanoseconds.
+        IO_OP_TIMEOUT: u8 = 8;
+        /// I/O ring operation: flush the fd's file to its backing store.
+        IO_OP_FSYNC: u8 = 9;
+        /// I/O ring operation flag: start the next operation only after
+        /// this one succeeds; if it fails, the rest of the chain completes
+        /// with `-ECANCELED`.
+        IO_OP_LINK: u8 = 1 << 0;
         /// Framebuffer ioctl: get framebuffer info.
         FBIOGET_INFO: u32 = 0x4600;
         /// Framebuffer ioctl: disable/enable kernel console (fbcon) output.
@@ -442,34 +889,156 @@ hadron_syscall_macros::define_syscalls! {
         /// Framebuffer ioctl: flush a dirty rectangle to the display.
         /// arg is a pointer to an [`FbDirtyRect`].
         FBIODIRTY: u32 = 0x4602;
+        /// Signal: hangup on the controlling terminal.
+        SIGHUP: usize = 1;
         /// Signal: interrupt (Ctrl+C).
         SIGINT: usize = 2;
         /// Signal: quit (Ctrl+\).
         SIGQUIT: usize = 3;
+        /// Signal: illegal instruction.
+        SIGILL: usize = 4;
+        /// Signal: trace or breakpoint trap.
+        SIGTRAP: usize = 5;
+        /// Signal: abort (`abort()`).
+        SIGABRT: usize = 6;
+        /// Signal: bus error (misaligned or nonexistent memory).
+        SIGBUS: usize = 7;
+        /// Signal: arithmetic exception.
+        SIGFPE: usize = 8;
         /// Signal: kill (cannot be caught or ignored).
         SIGKILL: usize = 9;
+        /// Signal: user-defined signal 1.
+        SIGUSR1: usize = 10;
         /// Signal: segmentation fault.
         SIGSEGV: usize = 11;
+        /// Signal: user-defined signal 2.
+        SIGUSR2: usize = 12;
         /// Signal: broken pipe.
         SIGPIPE: usize = 13;
+        /// Signal: real-time timer expired.
+        SIGALRM: usize = 14;
         /// Signal: terminate.
         SIGTERM: usize = 15;
-        /// Signal: child process exited.
+        /// Signal: coprocessor stack fault (unused).
+        SIGSTKFLT: usize = 16;
+        /// Signal: child process exited, stopped or continued.
         SIGCHLD: usize = 17;
+        /// Signal: continue if stopped.
+        SIGCONT: usize = 18;
         /// Signal: stop (cannot be caught or ignored).
         SIGSTOP: usize = 19;
+        /// Signal: terminal stop (Ctrl+Z).
+        SIGTSTP: usize = 20;
+        /// Signal: background read from the controlling terminal.
+        SIGTTIN: usize = 21;
+        /// Signal: background write to the controlling terminal.
+        SIGTTOU: usize = 22;
+        /// Signal: urgent data on a socket.
+        SIGURG: usize = 23;
+        /// Signal: CPU time limit exceeded.
+        SIGXCPU: usize = 24;
+        /// Signal: file size limit exceeded.
+        SIGXFSZ: usize = 25;
+        /// Signal: virtual (user CPU time) timer expired.
+        SIGVTALRM: usize = 26;
+        /// Signal: profiling timer expired.
+        SIGPROF: usize = 27;
+        /// Signal: terminal window size changed.
+        SIGWINCH: usize = 28;
+        /// Signal: I/O possible on a descriptor.
+        SIGIO: usize = 29;
+        /// Signal: power failure.
+        SIGPWR: usize = 30;
+        /// Signal: bad system call.
+        SIGSYS: usize = 31;
+        /// Lowest real-time signal. Real-time signals are queued: each
+        /// `sig_queue` or `task_kill` is delivered once, in order.
+        SIGRTMIN: usize = 34;
+        /// Highest real-time signal (and highest signal number).
+        SIGRTMAX: usize = 64;
         /// Signal disposition: default action.
         SIG_DFL: usize = 0;
         /// Signal disposition: ignore the signal.
         SIG_IGN: usize = 1;
+        /// Sigaction flag: don't send `SIGCHLD` when a child stops.
+        SA_NOCLDSTOP: u32 = 0x0000_0001;
+        /// Sigaction flag: don't turn exited children into zombies.
+        SA_NOCLDWAIT: u32 = 0x0000_0002;
+        /// Sigaction flag: the handler takes `(signo, *SigInfo, *UContext)`.
+        SA_SIGINFO: u32 = 0x0000_0004;
+        /// Sigaction flag: run the handler on the alternate signal stack.
+        SA_ONSTACK: u32 = 0x0800_0000;
         /// Sigaction flag: restart interrupted syscalls after handler returns.
-        SA_RESTART: usize = 0x1000_0000;
+        SA_RESTART: u32 = 0x1000_0000;
+        /// Sigaction flag: don't block the signal while its handler runs.
+        SA_NODEFER: u32 = 0x4000_0000;
         /// Sigaction flag: reset handler to `SIG_DFL` after delivery.
-        SA_RESETHAND: usize = 0x8000_0000;
+        SA_RESETHAND: u32 = 0x8000_0000;
+        /// `sig_altstack` flag: the thread is running on the alternate stack
+        /// (reported only).
+        SS_ONSTACK: i32 = 1;
+        /// `sig_altstack` flag: the alternate stack is disabled.
+        SS_DISABLE: i32 = 2;
+        /// Smallest alternate signal stack `sig_altstack` accepts, in bytes.
+        MINSIGSTKSZ: usize = 2048;
+        /// Recommended alternate signal stack size, in bytes.
+        SIGSTKSZ: usize = 8192;
+        /// `SigInfo::code`: sent by `task_kill`.
+        SI_USER: i32 = 0;
+        /// `SigInfo::code`: sent by the kernel.
+        SI_KERNEL: i32 = 0x80;
+        /// `SigInfo::code`: sent by `sig_queue`.
+        SI_QUEUE: i32 = -1;
+        /// `SigInfo::code`: expiry of a `timer_create` timer.
+        SI_TIMER: i32 = -2;
+        /// `sigev_notify`: send [`SigEvent::signo`] to the process.
+        SIGEV_SIGNAL: i32 = 0;
+        /// `sigev_notify`: no notification.
+        SIGEV_NONE: i32 = 1;
+        /// `sigev_notify`: send [`SigEvent::signo`] to thread
+        /// [`SigEvent::tid`] of the calling process.
+        SIGEV_THREAD_ID: i32 = 4;
+        /// `itimer_set` timer: real time, delivers `SIGALRM`.
+        ITIMER_REAL: usize = 0;
+        /// `itimer_set` timer: user CPU time of the process, delivers
+        /// `SIGVTALRM`.
+        ITIMER_VIRTUAL: usize = 1;
+        /// `itimer_set` timer: user and system CPU time of the process,
+        /// delivers `SIGPROF`.
+        ITIMER_PROF: usize = 2;
+        /// `timer_settime` flag: [`ITimerSpec::value`] is an absolute time
+        /// on the timer's clock.
+        TIMER_ABSTIME: usize = 1;
         /// `task_wait` flag: return immediately if no child has exited.
         WNOHANG: usize = 1;
         /// `task_wait` flag: also report stopped children.
         WUNTRACED: usize = 2;
+        /// `task_wait` flag: also report stopped children resumed by
+        /// `SIGCONT`.
+        WCONTINUED: usize = 8;
+        /// `child_waitid` flag: report stopped children (same as
+        /// [`WUNTRACED`]).
+        WSTOPPED: usize = 2;
+        /// `child_waitid` flag: report exited children.
+        WEXITED: usize = 4;
+        /// `child_waitid` flag: leave the child waitable, so a later wait
+        /// reports the same event again.
+        WNOWAIT: usize = 0x0100_0000;
+        /// `child_waitid` id type: any child; the id is ignored.
+        P_ALL: usize = 0;
+        /// `child_waitid` id type: the child with PID `id`.
+        P_PID: usize = 1;
+        /// `child_waitid` id type: any child in process group `id`
+        /// (0 = the caller's group).
+        P_PGID: usize = 2;
+        /// `SigInfo::code` for `SIGCHLD`: the child exited.
+        CLD_EXITED: i32 = 1;
+        /// `SigInfo::code` for `SIGCHLD`: the child was killed by a signal.
+        CLD_KILLED: i32 = 2;
+        /// `SigInfo::code` for `SIGCHLD`: the child stopped.
+        CLD_STOPPED: i32 = 5;
+        /// `SigInfo::code` for `SIGCHLD`: the stopped child continued.
+        CLD_CONTINUED: i32 = 6;
         /// Poll event: data available for reading.
         POLLIN: u16 = 0x0001;
         /// Poll event: writing will not block.
@@ -502,6 +1071,9 @@ hadron_syscall_macros::define_syscalls! {
         ECHO: u32 = 0x0008;
         /// Termios lflag: echo newline even if ECHO is off.
         ECHONL: u32 = 0x0040;
+        /// Termios lflag: send `SIGTTOU` to background process groups that
+        /// write to the terminal.
+        TOSTOP: u32 = 0x0100;
         /// Termios lflag: generate signals (SIGINT, SIGQUIT, SIGTSTP).
         ISIG: u32 = 0x0001;
         /// Termios iflag: translate CR to NL on input.
@@ -524,6 +1096,8 @@ hadron_syscall_macros::define_syscalls! {
         VMIN: usize = 6;
         /// Index into `cc` array: quit character (default Ctrl+\ = 0x1C).
         VQUIT: usize = 1;
+        /// Index into `cc` array: suspend character (default Ctrl+Z = 0x1A).
+        VSUSP: usize = 10;
         /// Index into `cc` array: timeout for non-canonical read (tenths of sec).
         VTIME: usize = 5;
         /// Clone flag: share address space (threads).
@@ -538,6 +1112,42 @@ hadron_syscall_macros::define_syscalls! {
         FUTEX_WAIT: usize = 0;
         /// Futex operation: wake up to `val` waiters.
         FUTEX_WAKE: usize = 1;
+        /// Credential ID argument meaning "leave this ID unchanged"
+        /// (`(uid_t)-1` in C).
+        ID_UNCHANGED: usize = 0xFFFF_FFFF;
+        /// Maximum number of supplementary groups per process.
+        NGROUPS_MAX: usize = 32;
+        /// Auxiliary vector tag: end of the vector.
+        AT_NULL: usize = 0;
+        /// Auxiliary vector tag: address of the program headers.
+        AT_PHDR: usize = 3;
+        /// Auxiliary vector tag: size of one program header entry.
+        AT_PHENT: usize = 4;
+        /// Auxiliary vector tag: number of program headers.
+        AT_PHNUM: usize = 5;
+        /// Auxiliary vector tag: system page size.
+        AT_PAGESZ: usize = 6;
+        /// Auxiliary vector tag: load base of the program interpreter.
+        AT_BASE: usize = 7;
+        /// Auxiliary vector tag: entry point of the program (not the interpreter).
+        AT_ENTRY: usize = 9;
+        /// Auxiliary vector tag: real user ID.
+        AT_UID: usize = 11;
+        /// Auxiliary vector tag: effective user ID.
+        AT_EUID: usize = 12;
+        /// Auxiliary vector tag: real group ID.
+        AT_GID: usize = 13;
+        /// Auxiliary vector tag: effective group ID.
+        AT_EGID: usize = 14;
+        /// Auxiliary vector tag: nonzero if the program runs set-user-ID or
+        /// set-group-ID.
+        AT_SECURE: usize = 23;
+        /// Auxiliary vector tag: address of 16 random bytes.
+        AT_RANDOM: usize = 25;
+        /// Auxiliary vector tag (Hadron): address of the dynamic linker's
+        /// `dlopen`/`dlsym` entry table. The kernel always passes 0; a
+        /// dynamic linker fills the slot in before jumping to the program.
+        AT_HADRON_DL: usize = 0x1000;
         /// PTY ioctl: get slave PTY number.
         TIOCGPTN: u32 = 0x5430;
         /// PTY ioctl: unlock slave PTY.
@@ -556,10 +1166,20 @@ hadron_syscall_macros::define_syscalls! {
         /// and envp descriptors.
         fn task_spawn(info_ptr: usize, info_len: usize) = 0x01;
 
-        /// Wait for a child task to exit. Returns child PID on success.
+        /// Wait for a child task to change state. Returns child PID on
+        /// success.
+        ///
+        /// `flags` is a bitmask of `WNOHANG` (non-blocking), `WUNTRACED`
+        /// (also report stopped children) and `WCONTINUED` (also report
+        /// children resumed by `SIGCONT`). Pass 0 for default blocking wait.
         ///
-        /// `flags` is a bitmask of `WNOHANG` (non-blocking) and `WUNTRACED`
-        /// (also report stopped children). Pass 0 for default blocking wait.
+        /// The u64 written to `status_ptr` uses the Linux wait-status
+        /// encoding: `code << 8` for an exit, the signal number for a death
+        /// by signal, `(signum << 8) | 0x7F` for a stop, and `0xFFFF` for a
+        /// continue. Only an exited child is reaped.
+        ///
+        /// `pid` 0 waits for any child; [`child_wait4`] also waits on
+        /// process groups.
         fn task_wait(pid: usize, status_ptr: usize, flags: usize) = 0x02;
 
         /// Send a signal to a task.
@@ -579,20 +1199,20 @@ hadron_syscall_macros::define_syscalls! {
         /// Query task information (returns task ID for now).
         fn task_info() = 0x05;
 
-        /// Register a signal handler for a signal number.
+        /// Examine or change the action for a signal number.
         ///
-        /// `signum` is the signal number (1-63, cannot be SIGKILL or SIGSTOP).
-        /// `handler` is `SIG_DFL` (0), `SIG_IGN` (1), or a function pointer.
-        /// `flags` is a bitmask of `SA_RESTART`, `SA_RESETHAND`, etc.
-        /// If `old_handler_out` is non-zero, the previous handler is written there.
-        /// Returns 0 on success, or a negated errno on failure.
-        fn task_sigaction(signum: usize, handler: usize, flags: usize, old_handler_out: usize) = 0x06;
+        /// `signum` is the signal number (1-64). If `act_ptr` is non-zero it
+        /// points to the new [`SigAction`]; SIGKILL and SIGSTOP cannot be
+        /// caught or ignored. If `oldact_ptr` is non-zero, the previous
+        /// action is written there. Returns 0 on success, or a negated errno
+        /// on failure.
+        fn task_sigaction(signum: usize, act_ptr: usize, oldact_ptr: usize) = 0x06;
 
         /// Restore pre-signal user context after a signal handler returns.
         ///
-        /// Called from the signal trampoline. Restores all registers from
-        /// the `SignalFrame` saved on the user stack before re-entering
-        /// userspace at the interrupted instruction.
+        /// Called from the signal trampoline. Restores the registers and
+        /// signal mask from the [`UContext`] in the `SignalFrame` saved on
+        /// the user stack before re-entering userspace.
         fn task_sigreturn() = 0x07;
 
         /// Set process group ID.
@@ -642,7 +1262,7 @@ hadron_syscall_macros::define_syscalls! {
         ///
         /// `info_ptr` points to a [`SpawnInfo`] struct containing the new
         /// program path, argv, and envp. The PID, parent, fd table, and CWD
-        /// are preserved. Signal handlers are reset to `SIG_DFL`.
+        /// are preserved. Caught signals are reset to `SIG_DFL`.
         /// Does not return on success. Returns negated errno on failure.
         fn task_execve(info_ptr: usize, info_len: usize) = 0x0F;
     }
@@ -829,6 +1449,10 @@ hadron_syscall_macros::define_syscalls! {
         /// include `MAP_ANONYMOUS` or `MAP_SHARED`. `fd` is the file
         /// descriptor for device-backed mappings (ignored for anonymous).
         ///
+        /// Large anonymous mappings use 2 MiB pages where possible. With
+        /// `MAP_HUGE`, an anonymous mapping is rounded up to 2 MiB and fails
+        /// with `ENOMEM` unless it can be backed entirely by huge pages.
+        ///
         /// Returns the mapped virtual address on success, or negated errno.
         fn mem_map(addr_hint: usize, length: usize, prot: usize, flags: usize, fd: usize) = 0x00;
 
@@ -851,8 +1475,10 @@ hadron_syscall_macros::define_syscalls! {
         /// Allocates `size` bytes of physical memory (page-aligned) and
         /// returns a file descriptor referring to the shared memory object.
         /// The memory is zero-filled. Multiple processes can map the same
-        /// object to share memory.
-        fn mem_create_shared(size: usize) = 0x03;
+        /// object to share memory. With `MAP_HUGE` in `flags`, the size is
+        /// rounded up to 2 MiB and the object is backed by 2 MiB frames, so
+        /// its mappings can use huge pages.
+        fn mem_create_shared(size: usize, flags: usize) = 0x03;
 
         /// Map a shared memory object into the calling process's address space.
         ///
@@ -876,26 +1502,39 @@ hadron_syscall_macros::define_syscalls! {
 
     /// Events and time.
     group event(0x50..0x60) {
-        /// Create an event object.
-        #[reserved]
-        fn event_create() = 0x00;
+        /// Create an event counter fd (Linux `eventfd`) starting at
+        /// `initval`.
+        ///
+        /// Writing an 8-byte value adds it to the counter; reading 8 bytes
+        /// waits for a non-zero counter and returns and resets it, or with
+        /// [`EFD_SEMAPHORE`] returns 1 and decrements it. `flags` may also
+        /// include [`EFD_CLOEXEC`] and [`EFD_NONBLOCK`]. Returns the fd.
+        fn event_create(initval: usize, flags: usize) = 0x00;
 
-        /// Signal an event.
-        #[reserved]
-        fn event_signal(handle: usize) = 0x01;
+        /// Create a timer fd on `clock_id` (Linux `timerfd_create`).
+        ///
+        /// `clock_id` is `CLOCK_MONOTONIC` or `CLOCK_REALTIME`; `flags` may
+        /// include [`TFD_CLOEXEC`] and [`TFD_NONBLOCK`]. Reading 8 bytes
+        /// waits for an expiry and returns the number of expiries since the
+        /// last read. Returns the fd.
+        fn timerfd_create(clock_id: usize, flags: usize) = 0x01;
 
-        /// Wait for an event.
-        #[reserved]
-        fn event_wait(handle: usize) = 0x02;
+        /// Arm or disarm timer fd `fd` from the [`ITimerSpec`] at `new_ptr`
+        /// (Linux `timerfd_settime`).
+        ///
+        /// `flags` may contain [`TFD_TIMER_ABSTIME`]. If `old_ptr` is
+        /// non-zero the previous setting is written there. Returns 0,
+        /// `-EBADF`, or `-EINVAL` if `fd` is not a timer fd.
+        fn timerfd_settime(fd: usize, flags: usize, new_ptr: usize, old_ptr: usize) = 0x02;
 
         /// Poll multiple file descriptors for readiness.
         ///
         /// `fds_ptr` is a user pointer to an array of [`PollFd`] structs.
-        /// `nfds` is the number of entries. `timeout_ms` is the timeout in
-        /// milliseconds (`usize::MAX` for infinite, `0` for non-blocking).
+        /// `nfds` is the number of entries. `timeout_ns` is the timeout in
+        /// nanoseconds (`usize::MAX` for infinite, `0` for non-blocking).
         ///
         /// Returns the number of fds with non-zero `revents`, or negated errno.
-        fn event_wait_many(fds_ptr: usize, nfds: usize, timeout_ms: usize) = 0x03;
+        fn event_wait_many(fds_ptr: usize, nfds: usize, timeout_ns: usize) = 0x03;
 
         /// Get current time.
         fn clock_gettime(clock_id: usize, tp: usize) = 0x04;
@@ -914,11 +1553,55 @@ hadron_syscall_macros::define_syscalls! {
         /// `addr` is the userspace address of a `u32` futex word.
         /// `op` is `FUTEX_WAIT` or `FUTEX_WAKE`.
         /// `val` is the expected value (for WAIT) or count to wake (for WAKE).
-        /// `timeout_ms` is the timeout in ms (0 = infinite, only for WAIT).
+        /// `timeout_ptr` points to a relative [`Timespec`] (0 = infinite, only
+        /// for WAIT).
         ///
         /// FUTEX_WAIT: if `*addr == val`, sleep until woken or timeout.
+        /// Returns `-ETIMEDOUT` if the timeout passes first.
         /// FUTEX_WAKE: wake up to `val` threads sleeping on `addr`.
-        fn futex(addr: usize, op: usize, val: usize, timeout_ms: usize) = 0x06;
+        fn futex(addr: usize, op: usize, val: usize, timeout_ptr: usize) = 0x06;
+
+        /// Create a timer on `clock_id` (POSIX `timer_create`).
+        ///
+        /// `sevp_ptr` points to a [`SigEvent`] saying how expiries are
+        /// reported; 0 sends `SIGALRM` to the process. Writes the new timer
+        /// ID as a `u32` to `id_ptr`. Returns 0, `-EINVAL` for a bad clock or
+        /// notification, or `-EAGAIN` if the process has too many timers.
+        fn timer_create(clock_id: usize, sevp_ptr: usize, id_ptr: usize) = 0x07;
+
+        /// Arm or disarm timer `id` from the [`ITimerSpec`] at `new_ptr`
+        /// (POSIX `timer_settime`).
+        ///
+        /// `flags` may contain [`TIMER_ABSTIME`]. If `old_ptr` is non-zero
+        /// the previous setting is written there. Returns 0 or `-EINVAL`.
+        fn timer_settime(id: usize, flags: usize, new_ptr: usize, old_ptr: usize) = 0x08;
+
+        /// Write the time until the next expiry of timer `id` and its
+        /// interval to the [`ITimerSpec`] at `cur_ptr`.
+        fn timer_gettime(id: usize, cur_ptr: usize) = 0x09;
+
+        /// Disarm and delete timer `id`.
+        fn timer_delete(id: usize) = 0x0A;
+
+        /// Return the number of extra expiries of timer `id` since it last
+        /// sent a signal (POSIX `timer_getoverrun`).
+        fn timer_getoverrun(id: usize) = 0x0B;
+
+        /// Arm or disarm interval timer `which` (POSIX `setitimer`).
+        ///
+        /// `which` is [`ITIMER_REAL`], [`ITIMER_VIRTUAL`] or [`ITIMER_PROF`].
+        /// `new_ptr` and `old_ptr` point to [`ITimerSpec`]s; `old_ptr` may
+        /// be 0. Returns 0 or `-EINVAL`.
+        fn itimer_set(which: usize, new_ptr: usize, old_ptr: usize) = 0x0C;
+
+        /// Write the current setting of interval timer `which` to the
+        /// [`ITimerSpec`] at `cur_ptr` (POSIX `getitimer`).
+        fn itimer_get(which: usize, cur_ptr: usize) = 0x0D;
+
+        /// Write the time until the next expiry of timer fd `fd` and its
+        /// interval to the [`ITimerSpec`] at `cur_ptr` (Linux
+        /// `timerfd_gettime`).
+        fn timerfd_gettime(fd: usize, cur_ptr: usize) = 0x0E;
     }
 
     /// AF_UNIX socket operations.
@@ -974,6 +1657,236 @@ hadron_syscall_macros::define_syscalls! {
         fn shutdown(fd: usize, how: usize) = 0x07;
     }
 
+    /// User and group credentials.
+    group cred(0x70..0x80) {
+        /// Get the real, effective and saved user and group IDs.
+        ///
+        /// Writes a [`CredInfo`] to `buf_ptr`; `buf_len` must be at least
+        /// `size_of::<CredInfo>()`. Returns 0 on success.
+        fn cred_get(buf_ptr: usize, buf_len: usize) = 0x00;
+
+        /// Get the supplementary group IDs.
+        ///
+        /// Writes up to `count` `u32` group IDs to `buf_ptr`. With `count`
+        /// 0, only returns the number of groups. Returns the number of
+        /// groups, or `-EINVAL` if `count` is nonzero but too small.
+        fn cred_getgroups(buf_ptr: usize, count: usize) = 0x01;
+
+        /// Set the user ID (POSIX `setuid`).
+        ///
+        /// A privileged caller sets the real, effective and saved user IDs;
+        /// otherwise only the effective user ID may change, to the real or
+        /// saved one. Returns 0, or `-EPERM`.
+        fn cred_setuid(uid: usize) = 0x02;
+
+        /// Set the group ID (POSIX `setgid`), with the same rules as
+        /// [`cred_setuid`].
+        fn cred_setgid(gid: usize) = 0x03;
+
+        /// Set the real, effective and saved user IDs.
+        ///
+        /// [`ID_UNCHANGED`] leaves an ID as it is. An unprivileged caller
+        /// may only use its current real, effective or saved user ID.
+        /// Returns 0, or `-EPERM`.
+        fn cred_setresuid(uid: usize, euid: usize, suid: usize) = 0x04;
+
+        /// Set the real, effective and saved group IDs, with the same rules
+        /// as [`cred_setresuid`].
+        fn cred_setresgid(gid: usize, egid: usize, sgid: usize) = 0x05;
+
+        /// Replace the supplementary groups with `count` `u32` group IDs
+        /// read from `buf_ptr`.
+        ///
+        /// Requires a privileged caller. Returns 0, `-EPERM`, or `-EINVAL`
+        /// if `count` exceeds [`NGROUPS_MAX`].
+        fn cred_setgroups(buf_ptr: usize, count: usize) = 0x06;
+    }
+
+    /// Per-thread state.
+    group thread(0x80..0x90) {
+        /// Set the calling thread's thread pointer (the x86-64 FS base).
+        ///
+        /// `tls_ptr` must be 0 or a user address. Returns 0, or `-EINVAL`.
+        fn thread_set_tls(tls_ptr: usize) = 0x00;
+
+        /// Get the calling thread's thread pointer (the x86-64 FS base).
+        fn thread_get_tls() = 0x01;
+    }
+
+    /// Signal queueing, waiting and alternate stacks.
+    group signal(0x90..0xA0) {
+        /// Queue `signum` with `value` for process `pid` (POSIX `sigqueue`).
+        ///
+        /// The receiver sees `SI_QUEUE` and `value` in its [`SigInfo`].
+        /// Returns 0, or `-EAGAIN` if the receiver's real-time signal queue
+        /// is full.
+        fn sig_queue(pid: usize, signum: usize, value: usize) = 0x00;
+
+        /// Set and/or get the calling thread's alternate signal stack.
+        ///
+        /// `ss_ptr` and `old_ss_ptr` point to [`SigAltStack`]s and may be 0.
+        /// Returns `-EPERM` when changing the stack while running on it, and
+        /// `-ENOMEM` for a stack smaller than `MINSIGSTKSZ`.
+        fn sig_altstack(ss_ptr: usize, old_ss_ptr: usize) = 0x01;
+
+        /// Write the set of pending, blocked signals to the `u64` at `set_out`.
+        fn sig_pending(set_out: usize) = 0x02;
+
+        /// Replace the signal mask with `mask` and wait until a signal handler
+        /// runs or the process is killed (POSIX `sigsuspend`).
+        ///
+        /// The original mask is restored when the handler returns. Always
+        /// returns `-EINTR`.
+        fn sig_suspend(mask: usize) = 0x03;
+
+        /// Wait for one of the signals in `set` and dequeue it (POSIX
+        /// `sigtimedwait`).
+        ///
+        /// If `info_ptr` is non-zero the signal's [`SigInfo`] is written
+        /// there. `timeout_ptr` points to a relative [`Timespec`]; 0 waits
+        /// forever. Returns the signal number, `-EAGAIN` on timeout, or
+        /// `-EINTR` if a handler ran for a signal outside `set`.
+        fn sig_timedwait(set: usize, info_ptr: usize, timeout_ptr: usize) = 0x04;
+
+        /// Create a signal fd for the signals in `mask` (Linux `signalfd`),
+        /// or replace the mask of signal fd `fd` unless `fd` is
+        /// `usize::MAX`.
+        ///
+        /// Reading waits for one of the signals to be pending for the
+        /// calling thread and dequeues as many as fit as [`SigInfo`]
+        /// records. `flags` may include [`SFD_CLOEXEC`] and
+        /// [`SFD_NONBLOCK`]. Returns the fd.
+        fn sig_fd(fd: usize, mask: usize, flags: usize) = 0x05;
+    }
+
+    /// Scheduling parameters of user processes.
+    group sched(0xA0..0xB0) {
+        /// Get the nice value of the processes selected by `which` and `who`
+        /// (POSIX `getpriority`).
+        ///
+        /// `which` is [`PRIO_PROCESS`], [`PRIO_PGRP`] or [`PRIO_USER`].
+        /// Returns `20 - nice` of the highest-priority match (1 to 40, so it
+        /// cannot be mistaken for an error), or `-ESRCH` if none matches.
+        fn sched_getpriority(which: usize, who: usize) = 0x00;
+
+        /// Set the nice value of the processes selected by `which` and `who`
+        /// (POSIX `setpriority`).
+        ///
+        /// `nice` is an `i32` and is clamped to -20..=19. Returns 0,
+        /// `-ESRCH` if nothing matches, `-EPERM` if the caller may not
+        /// change a match, or `-EACCES` if an unprivileged caller lowers a
+        /// nice value.
+        fn sched_setpriority(which: usize, who: usize, nice: usize) = 0x01;
+
+        /// Get the CPU affinity mask of process `pid` (0 = the caller).
+        ///
+        /// Writes a `u64` with bit `n` set for CPU `n` to `mask_ptr`;
+        /// `mask_len` must be at least 8. Returns the number of bytes
+        /// written.
+        fn sched_getaffinity(pid: usize, mask_len: usize, mask_ptr: usize) = 0x02;
+
+        /// Set the CPU affinity mask of process `pid` (0 = the caller).
+        ///
+        /// Reads `mask_len` bytes (at most 8 are used) from `mask_ptr`.
+        /// CPUs that are not online are dropped; returns `-EINVAL` if none
+        /// remain, or `-EPERM` if the caller may not change the target.
+        fn sched_setaffinity(pid: usize, mask_len: usize, mask_ptr: usize) = 0x03;
+
+        /// Set the scheduling policy of process `pid` (0 = the caller) from
+        /// the [`SchedAttr`] at `attr_ptr`.
+        ///
+        /// `flags` must be 0. Real-time and deadline policies need root.
+        /// Returns 0, `-EINVAL` for a bad policy or parameters, `-EPERM` if
+        /// the caller may not make the change, or `-EBUSY` if a deadline
+        /// reservation does not fit in the CPUs' remaining bandwidth.
+        fn sched_setattr(pid: usize, attr_ptr: usize, flags: usize) = 0x04;
+
+        /// Write the scheduling policy of process `pid` (0 = the caller) to
+        /// the [`SchedAttr`] at `attr_ptr`; `size` must be at least
+        /// `size_of::<SchedAttr>()`.
+        fn sched_getattr(pid: usize, attr_ptr: usize, size: usize) = 0x05;
+    }
+
+    /// Interest lists for scalable readiness notification (Linux `epoll`).
+    group epoll(0xB0..0xC0) {
+        /// Create an empty interest list fd. `flags` may be
+        /// [`EPOLL_CLOEXEC`]. Returns the fd.
+        fn epoll_create(flags: usize) = 0x00;
+
+        /// Add, change or remove the interest of epoll fd `epfd` in `fd`.
+        ///
+        /// `op` is [`EPOLL_CTL_ADD`], [`EPOLL_CTL_MOD`] or
+        /// [`EPOLL_CTL_DEL`]; `event_ptr` points to an [`EpollEvent`] for
+        /// the first two. Returns 0, `-EBADF`, `-EEXIST` if `fd` is already
+        /// added, `-ENOENT` if it is not, or `-EINVAL` if `epfd` is not an
+        /// epoll fd or `fd` is an epoll fd.
+        fn epoll_ctl(epfd: usize, op: usize, fd: usize, event_ptr: usize) = 0x01;
+
+        /// Wait for ready fds in the interest list of `epfd`.
+        ///
+        /// Writes up to `max_events` [`EpollEvent`]s to `events_ptr`.
+        /// `timeout_ns` is as for [`event_wait_many`]. Returns the number
+        /// of events, or 0 on timeout.
+        fn epoll_wait(epfd: usize, events_ptr: usize, max_events: usize, timeout_ns: usize) = 0x02;
+    }
+
+    /// Shared-memory submission and completion rings for asynchronous I/O.
+    group io_ring(0xC0..0xD0) {
+        /// Create an I/O ring with `entries` submission queue entries
+        /// (rounded up to a power of two, at most [`IO_RING_MAX_ENTRIES`]).
+        ///
+        /// Writes its [`IoRingParams`] to `params_ptr`. `flags` may be
+        /// [`IO_RING_CLOEXEC`]. Returns the ring fd, which must stay open
+        /// while the ring is mapped.
+        fn io_ring_create(entries: usize, params_ptr: usize, flags: usize) = 0x00;
+
+        /// Submit up to `to_submit` queued [`IoOp`]s of ring `fd`, then
+        /// wait until at least `min_complete` completions are queued.
+        ///
+        /// `timeout_ns` bounds the wait as for [`event_wait_many`]. Returns
+        /// the number of operations submitted, or `-EBUSY` if none could
+        /// be because the ring already has a full completion queue's worth
+        /// in flight.
+        fn io_ring_enter(fd: usize, to_submit: usize, min_complete: usize, timeout_ns: usize) = 0x01;
+    }
+
+    /// Waiting for children and adopting orphans.
+    group child(0xD0..0xE0) {
+        /// Wait for a child to change state (BSD `wait4`).
+        ///
+        /// `pid` is an `isize`: a positive value selects that child, 0 any
+        /// child in the caller's process group, -1 any child, and less than
+        /// -1 any child in process group `-pid`. `options` and the status
+        /// written to `status_ptr` are as for [`task_wait`]. If
+        /// `rusage_ptr` is non-zero, the CPU time of the child and of the
+        /// children it reaped is written there as an [`RusageInfo`].
+        /// Returns the child PID, 0 if `WNOHANG` is set and no child has
+        /// changed state, or `-ECHILD` if no child matches.
+        fn child_wait4(pid: usize, status_ptr: usize, options: usize, rusage_ptr: usize) = 0x00;
+
+        /// Wait for a child to change state (POSIX `waitid`).
+        ///
+        /// `idtype` is [`P_ALL`], [`P_PID`] or [`P_PGID`]. `options` must
+        /// include [`WEXITED`], [`WSTOPPED`] or [`WCONTINUED`], and may add
+        /// [`WNOHANG`] and [`WNOWAIT`]. The event is written to `info_ptr`
+        /// as a [`SigInfo`] with `signo` `SIGCHLD`, a `CLD_*` code, the
+        /// child's PID and real user ID, and its exit code or signal in
+        /// `status`; if `WNOHANG` finds nothing, the record is zeroed.
+        /// `rusage_ptr` is as for [`child_wait4`]. Returns 0.
+        fn child_waitid(idtype: usize, id: usize, info_ptr: usize, options: usize, rusage_ptr: usize) = 0x01;
+
+        /// Make the caller a child subreaper, or stop being one if `enable`
+        /// is 0 (Linux `PR_SET_CHILD_SUBREAPER`).
+        ///
+        /// When a process exits, its children are adopted by the nearest
+        /// living ancestor that is a subreaper, or by init if there is
+        /// none. Not inherited by spawned children.
+        fn child_set_subreaper(enable: usize) = 0x02;
+
+        /// Returns 1 if the caller is a child subreaper, otherwise 0.
+        fn child_get_subreaper() = 0x03;
+    }
+
     /// System services.
     group system(0xF0..0x100) {
         /// Query system information via typed `#[repr(C)]` response structs.
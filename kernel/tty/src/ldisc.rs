@@ -1,8 +1,9 @@
 //! Line discipline — cooked-mode line editing and scancode processing.
 //!
-//! Handles buffering, backspace, Enter, Ctrl+C (interrupt), Ctrl+D (EOF),
-//! shift, caps lock, and extended scancode decoding. Produces [`LdiscAction`]
-//! events that the owning TTY interprets (echo, signal delivery, etc.).
+//! Handles buffering, backspace, Enter, Ctrl+C (interrupt), Ctrl+Z (suspend),
+//! Ctrl+D (EOF), shift, caps lock, and extended scancode decoding. Produces
+//! [`LdiscAction`] events that the owning TTY interprets (echo, signal
+//! delivery, etc.).
 
 use hadron_driver_api::input::KeyCode;
 use planck_noalloc::ringbuf::RingBuf;
@@ -25,6 +26,8 @@ pub enum LdiscAction {
     Char(u8),
     /// Ctrl+C: line discarded, caller should send SIGINT to foreground.
     Interrupt,
+    /// Ctrl+Z: line discarded, caller should send SIGTSTP to foreground.
+    Suspend,
     /// Ctrl+D on empty line: EOF marker set.
     Eof,
     /// Ctrl+D on non-empty line: line flushed without trailing newline.
@@ -79,8 +82,9 @@ impl LineDiscipline {
     /// Decodes the scancode, updates modifier state, and buffers the resulting
     /// character. When `icanon` is true, performs cooked-mode line editing;
     /// when false, pushes bytes directly to the ready buffer (raw mode).
-    /// When `isig` is false, Ctrl+C is delivered as a literal `0x03` byte
-    /// instead of generating an [`LdiscAction::Interrupt`].
+    /// When `isig` is false, Ctrl+C and Ctrl+Z are delivered as literal
+    /// `0x03` and `0x1A` bytes instead of generating an
+    /// [`LdiscAction::Interrupt`] or [`LdiscAction::Suspend`].
     pub fn process_scancode(
         &mut self,
         scancode: u8,
@@ -158,6 +162,16 @@ impl LineDiscipline {
             return Some(LdiscAction::Char(0x03));
         }
 
+        // Ctrl+Z handling.
+        if self.ctrl_held && key == KeyCode::Z {
+            if isig {
+                self.line_len = 0;
+                return Some(LdiscAction::Suspend);
+            }
+            let _ = self.ready_buf.try_push(0x1A);
+            return Some(LdiscAction::Char(0x1A));
+        }
+
         // Ctrl+D handling.
         if self.ctrl_held && key == KeyCode::D {
             if icanon {
@@ -229,7 +243,7 @@ impl LineDiscipline {
     /// that arrive via `PtyMaster::write()`. No modifier tracking or
     /// scancode decoding is needed since the bytes are already characters.
     ///
-    /// In canonical mode: performs line editing (backspace, Enter, Ctrl+C/D).
+    /// In canonical mode: performs line editing (backspace, Enter, Ctrl+C/D/Z).
     /// In raw mode: pushes the byte directly to the ready buffer.
     pub fn process_ascii_byte(
         &mut self,
@@ -247,6 +261,16 @@ impl LineDiscipline {
             return Some(LdiscAction::Char(0x03));
         }
 
+        // Ctrl+Z (0x1A): signal or literal depending on isig.
+        if byte == 0x1A {
+            if isig {
+                self.line_len = 0;
+                return Some(LdiscAction::Suspend);
+            }
+            let _ = self.ready_buf.try_push(0x1A);
+            return Some(LdiscAction::Char(0x1A));
+        }
+
         // Ctrl+D (0x04): EOF/flush or literal depending on icanon.
         if byte == 0x04 {
             if icanon {
@@ -97,6 +97,29 @@ mod tests {
         assert_eq!(data, &[0x03]);
     }
 
+    #[test]
+    fn ctrl_z_generates_suspend() {
+        let mut ld = LineDiscipline::new();
+        feed_ascii(&mut ld, b"partial");
+        let actions = feed_ascii(&mut ld, &[0x1A]);
+        assert!(matches!(actions[0], LdiscAction::Suspend));
+        feed_ascii(&mut ld, b"\n");
+        let data = read_all(&mut ld);
+        assert_eq!(data, b"\n");
+    }
+
+    #[test]
+    fn ctrl_z_raw_signal_delivers_literal() {
+        let mut ld = LineDiscipline::new();
+        let actions: Vec<_> = [0x1Au8]
+            .iter()
+            .filter_map(|&b| ld.process_ascii_byte(b, true, false))
+            .collect();
+        assert!(matches!(actions[0], LdiscAction::Char(0x1A)));
+        let data = read_all(&mut ld);
+        assert_eq!(data, &[0x1A]);
+    }
+
     #[test]
     fn ctrl_d_eof_on_empty_line() {
         let mut ld = LineDiscipline::new();
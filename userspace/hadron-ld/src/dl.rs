@@ -0,0 +1,230 @@
+//! The `dlopen` family, published to libc as a [`DlApi`] table.
+//!
+//! libc's `dlopen`/`dlsym`/`dlclose`/`dlerror` find the table through the
+//! `AT_HADRON_DL` auxiliary vector slot, which [`publish`] fills in. The
+//! same functions, plus `__tls_get_addr`, are also linker built-ins that
+//! satisfy shared objects' references when nothing else defines them.
+
+use core::cell::UnsafeCell;
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use hadron_libc_core::dlfcn::{DL_API_VERSION, DlApi, RTLD_GLOBAL, RTLD_NOW};
+
+use crate::error::{BufWriter, Error};
+use crate::load;
+use crate::mem;
+use crate::object::{self, Hashes, Object, objects};
+use crate::reloc;
+use crate::tls;
+
+/// Serialises loading and relocation between threads.
+static LOCK: AtomicBool = AtomicBool::new(false);
+
+/// Holds [`LOCK`] until dropped.
+struct Guard;
+
+impl Guard {
+    fn lock() -> Self {
+        while LOCK
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        Self
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        LOCK.store(false, Ordering::Release);
+    }
+}
+
+/// Length of the `dlerror` message buffer.
+const ERROR_LEN: usize = 256;
+
+/// Last error message, NUL-terminated. Shared by all threads.
+struct ErrorBuf(UnsafeCell<[u8; ERROR_LEN]>);
+
+// SAFETY: Written only under `LOCK`; readers accept a possibly newer message.
+unsafe impl Sync for ErrorBuf {}
+
+static ERROR: ErrorBuf = ErrorBuf(UnsafeCell::new([0; ERROR_LEN]));
+/// Whether [`ERROR`] holds a message `dlerror` has not returned yet.
+static ERROR_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Record `err` for `dlerror`. Callers must hold the linker lock.
+fn set_error(err: &Error) {
+    // SAFETY: The caller holds the linker lock.
+    let buf = unsafe { &mut *ERROR.0.get() };
+    let mut w = BufWriter::new(buf);
+    let _ = write!(w, "{err}");
+    w.finish();
+    ERROR_PENDING.store(true, Ordering::Release);
+}
+
+static mut API: DlApi = DlApi {
+    version: DL_API_VERSION,
+    tls_static_size: 0,
+    tls_align: 0,
+    dlopen,
+    dlsym,
+    dlclose,
+    dlerror,
+    tls_init: tls::tls_init,
+};
+
+/// Fill in the TLS geometry and store the table's address in `slot`.
+pub fn publish(slot: *mut usize) {
+    let api = &raw mut API;
+    // SAFETY: Runs once at startup, before any thread can read the table.
+    unsafe {
+        (*api).tls_static_size = tls::static_size();
+        (*api).tls_align = tls::align();
+        *slot = api as usize;
+    }
+}
+
+/// Address of a linker built-in named `name`.
+pub fn builtin(name: &[u8]) -> Option<usize> {
+    let addr = match name {
+        b"dlopen" => dlopen as usize,
+        b"dlsym" => dlsym as usize,
+        b"dlclose" => dlclose as usize,
+        b"dlerror" => dlerror as usize,
+        b"__tls_get_addr" => tls::tls_get_addr as usize,
+        _ => return None,
+    };
+    Some(addr)
+}
+
+/// Load `name` and its dependencies, returning the handle's index and the
+/// range of newly loaded objects.
+fn open_locked(name: &[u8], flags: i32) -> Result<(usize, core::ops::Range<usize>), Error> {
+    let first = objects().len();
+    if let Some(index) = load::find_loaded(name) {
+        let obj = &objects()[index];
+        obj.refcount.fetch_add(1, Ordering::Relaxed);
+        if flags & RTLD_GLOBAL != 0 {
+            obj.global.store(true, Ordering::Release);
+        }
+        return Ok((index, first..first));
+    }
+
+    let root = load::load_library(name, first)?;
+    load::load_dependencies(root, first)?;
+    let end = objects().len();
+    for obj in objects()[first..end].iter().rev() {
+        reloc::relocate(obj, flags & RTLD_NOW != 0)?;
+        reloc::protect(obj);
+    }
+    for obj in &objects()[first..end] {
+        if let Some(module) = &obj.tls {
+            tls::init_current_thread(module);
+        }
+        if flags & RTLD_GLOBAL != 0 {
+            obj.global.store(true, Ordering::Release);
+        }
+    }
+    Ok((root, first..end))
+}
+
+/// `dlopen` — load a shared object; a null `filename` names the executable.
+///
+/// # Safety
+///
+/// `filename` must be null or a valid NUL-terminated string.
+unsafe extern "C" fn dlopen(filename: *const u8, flags: i32) -> *mut u8 {
+    if filename.is_null() {
+        return (&objects()[0] as *const Object).cast_mut().cast();
+    }
+    // SAFETY: The caller passes a NUL-terminated string.
+    let name = unsafe { mem::cstr(filename) };
+    let guard = Guard::lock();
+    let first = objects().len();
+    match open_locked(name, flags) {
+        Ok((index, new)) => {
+            drop(guard);
+            object::run_initializers(new);
+            (&objects()[index] as *const Object).cast_mut().cast()
+        }
+        Err(err) => {
+            object::truncate(first);
+            set_error(&err);
+            core::ptr::null_mut()
+        }
+    }
+}
+
+/// `dlsym` — look up `symbol` in `handle` (the object, then the objects
+/// loaded with it, then the global scope), or in the global scope for
+/// `RTLD_DEFAULT`.
+///
+/// # Safety
+///
+/// `symbol` must be a valid NUL-terminated string.
+unsafe extern "C" fn dlsym(handle: *mut u8, symbol: *const u8) -> *mut u8 {
+    // SAFETY: The caller passes a NUL-terminated string.
+    let name = unsafe { mem::cstr(symbol) };
+    let def = if handle.is_null() {
+        object::resolve(name, None, false)
+    } else {
+        let Some(obj) = object::from_handle(handle) else {
+            let _guard = Guard::lock();
+            set_error(&Error::new("invalid handle", b""));
+            return core::ptr::null_mut();
+        };
+        let hashes = Hashes::new(name);
+        obj.lookup(name, &hashes)
+            .or_else(|| {
+                objects()
+                    .iter()
+                    .filter(|o| o.group == obj.group)
+                    .find_map(|o| o.lookup(name, &hashes))
+            })
+            .or_else(|| object::resolve(name, None, false))
+    };
+
+    match def {
+        Some(def) => match def.obj.and_then(|o| o.tls).filter(|_| def.tls) {
+            Some(module) => (tls::thread_pointer() - module.offset + def.value) as *mut u8,
+            None => def.value as *mut u8,
+        },
+        None => match builtin(name) {
+            Some(addr) => addr as *mut u8,
+            None => {
+                let _guard = Guard::lock();
+                set_error(&Error::new("undefined symbol", name));
+                core::ptr::null_mut()
+            }
+        },
+    }
+}
+
+/// `dlclose` — drop a reference to `handle`. Objects are never unmapped.
+unsafe extern "C" fn dlclose(handle: *mut u8) -> i32 {
+    match object::from_handle(handle) {
+        Some(obj) => {
+            let _ = obj
+                .refcount
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1));
+            0
+        }
+        None => {
+            let _guard = Guard::lock();
+            set_error(&Error::new("invalid handle", b""));
+            -1
+        }
+    }
+}
+
+/// `dlerror` — return the last error once, then null.
+unsafe extern "C" fn dlerror() -> *const u8 {
+    if ERROR_PENDING.swap(false, Ordering::Acquire) {
+        ERROR.0.get().cast::<u8>()
+    } else {
+        core::ptr::null()
+    }
+}
@@ -0,0 +1,87 @@
+//! Linker errors and diagnostics output.
+
+use core::fmt::{self, Write};
+
+use hadron_libc_core::sys;
+
+/// Longest object or symbol name kept in an [`Error`].
+const NAME_MAX: usize = 128;
+
+/// A failure while loading or linking, naming the object or symbol involved.
+pub struct Error {
+    what: &'static str,
+    name: [u8; NAME_MAX],
+    name_len: usize,
+}
+
+impl Error {
+    /// Create an error described by `what`, about `name` (may be empty).
+    pub fn new(what: &'static str, name: &[u8]) -> Self {
+        let name_len = name.len().min(NAME_MAX);
+        let mut buf = [0u8; NAME_MAX];
+        buf[..name_len].copy_from_slice(&name[..name_len]);
+        Self {
+            what,
+            name: buf,
+            name_len,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.what)?;
+        if self.name_len != 0 {
+            let name = core::str::from_utf8(&self.name[..self.name_len]).unwrap_or("<non-UTF-8>");
+            write!(f, ": {name}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Unbuffered writer for standard error.
+pub struct Stderr;
+
+impl Write for Stderr {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        sys::sys_write(2, s.as_bytes())
+            .map(|_| ())
+            .map_err(|_| fmt::Error)
+    }
+}
+
+/// Writer into a fixed buffer that silently truncates, keeping room for a
+/// NUL terminator.
+pub struct BufWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> BufWriter<'a> {
+    /// Wrap `buf`, which must be at least one byte long.
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    /// NUL-terminate the written text and return its length.
+    pub fn finish(self) -> usize {
+        self.buf[self.len] = 0;
+        self.len
+    }
+}
+
+impl Write for BufWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let room = self.buf.len() - 1 - self.len;
+        let n = s.len().min(room);
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
+
+/// Report `err` on standard error and terminate the process.
+pub fn fatal(err: &Error) -> ! {
+    let _ = writeln!(Stderr, "ld-hadron.so: {err}");
+    sys::sys_exit(127)
+}
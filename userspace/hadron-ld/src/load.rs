@@ -0,0 +1,211 @@
+//! Finding, reading and mapping shared objects.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use hadron_elf::{ELF64_PHDR_SIZE, ElfFile, ElfType};
+use hadron_libc_core::sys;
+use hadron_syscall::{OPEN_READ, StatInfo};
+
+use crate::error::Error;
+use crate::mem::{self, PAGE_SIZE, align_down, align_up};
+use crate::object::{self, Object, objects};
+
+/// Directories searched after `LD_LIBRARY_PATH`.
+const DEFAULT_PATH: &[&[u8]] = &[b"/lib", b"/usr/lib"];
+
+/// Libraries whose contents are linked statically into every executable
+/// through libc, so a `DT_NEEDED` entry for them is already satisfied.
+const PROVIDED_BY_LIBC: &[&[u8]] = &[
+    b"libc.so",
+    b"libc.so.6",
+    b"libpthread.so.0",
+    b"libdl.so.2",
+    b"libm.so.6",
+    b"librt.so.1",
+    b"ld-hadron.so.1",
+];
+
+/// Longest path tried while searching for a library.
+const PATH_MAX: usize = 256;
+
+/// `LD_LIBRARY_PATH` (address, length); empty if unset or ignored.
+static LIBRARY_PATH: [AtomicUsize; 2] = [const { AtomicUsize::new(0) }; 2];
+
+/// Search the colon-separated `path` before the default directories.
+pub fn set_library_path(path: &'static [u8]) {
+    LIBRARY_PATH[0].store(path.as_ptr() as usize, Ordering::Relaxed);
+    LIBRARY_PATH[1].store(path.len(), Ordering::Relaxed);
+}
+
+fn library_path() -> &'static [u8] {
+    let ptr = LIBRARY_PATH[0].load(Ordering::Relaxed);
+    if ptr == 0 {
+        return b"";
+    }
+    let len = LIBRARY_PATH[1].load(Ordering::Relaxed);
+    // SAFETY: `set_library_path` stored a `'static` slice.
+    unsafe { core::slice::from_raw_parts(ptr as *const u8, len) }
+}
+
+/// Return the index of a loaded object that `name` refers to.
+pub fn find_loaded(name: &[u8]) -> Option<usize> {
+    objects().iter().position(|obj| obj.matches_name(name))
+}
+
+/// Load every missing `DT_NEEDED` dependency of objects `first..`,
+/// breadth-first, into `group`.
+///
+/// Callers must hold the linker lock, or run before the program starts.
+pub fn load_dependencies(first: usize, group: usize) -> Result<(), Error> {
+    let mut index = first;
+    while index < objects().len() {
+        let obj = &objects()[index];
+        for name in obj.needed() {
+            if PROVIDED_BY_LIBC.contains(&name) || find_loaded(name).is_some() {
+                continue;
+            }
+            load_library(name, group)?;
+        }
+        index += 1;
+    }
+    Ok(())
+}
+
+/// Find, read and map the shared object `name`, returning its index.
+///
+/// A name containing `/` is used as a path; anything else is searched for
+/// in `LD_LIBRARY_PATH` and the default directories.
+pub fn load_library(name: &[u8], group: usize) -> Result<usize, Error> {
+    let (fd, path) = open_library(name)?;
+    let file = read_file(fd, path);
+    let _ = sys::sys_close(fd);
+    let (data, len) = file?;
+    // SAFETY: `read_file` returned a mapping holding `len` bytes of the file.
+    let bytes = unsafe { core::slice::from_raw_parts(data, len) };
+    let obj = map_object(bytes, path, group);
+    mem::unmap(data, len);
+    object::push(obj?)
+}
+
+/// Open `name` for reading, returning the descriptor and the path used.
+fn open_library(name: &[u8]) -> Result<(usize, &'static [u8]), Error> {
+    if name.contains(&b'/') {
+        let fd = sys::sys_open(name, OPEN_READ)
+            .map_err(|_| Error::new("cannot open shared object", name))?;
+        let path = mem::alloc_str(name).ok_or_else(|| Error::new("out of memory", name))?;
+        return Ok((fd, path));
+    }
+    let search = library_path()
+        .split(|&b| b == b':')
+        .filter(|dir| !dir.is_empty())
+        .chain(DEFAULT_PATH.iter().copied());
+    let mut buf = [0u8; PATH_MAX];
+    for dir in search {
+        let len = dir.len() + 1 + name.len();
+        if len > PATH_MAX {
+            continue;
+        }
+        buf[..dir.len()].copy_from_slice(dir);
+        buf[dir.len()] = b'/';
+        buf[dir.len() + 1..len].copy_from_slice(name);
+        if let Ok(fd) = sys::sys_open(&buf[..len], OPEN_READ) {
+            let path =
+                mem::alloc_str(&buf[..len]).ok_or_else(|| Error::new("out of memory", name))?;
+            return Ok((fd, path));
+        }
+    }
+    Err(Error::new("shared object not found", name))
+}
+
+/// Read the whole of `fd` into a fresh mapping, returning it and its length.
+fn read_file(fd: usize, path: &[u8]) -> Result<(*mut u8, usize), Error> {
+    let mut stat = core::mem::MaybeUninit::<StatInfo>::uninit();
+    sys::sys_stat(
+        fd,
+        stat.as_mut_ptr().cast(),
+        core::mem::size_of::<StatInfo>(),
+    )
+    .map_err(|_| Error::new("cannot stat shared object", path))?;
+    // SAFETY: A successful `vnode_stat` filled in the whole structure.
+    let len = unsafe { stat.assume_init() }.size as usize;
+    if len == 0 {
+        return Err(Error::new("empty shared object", path));
+    }
+    let data = mem::map(len).ok_or_else(|| Error::new("out of memory", path))?;
+    let mut done = 0;
+    while done < len {
+        // SAFETY: `data` is a writable mapping of `len` bytes.
+        let rest = unsafe { core::slice::from_raw_parts_mut(data.add(done), len - done) };
+        match sys::sys_read(fd, rest) {
+            Ok(0) | Err(_) => {
+                mem::unmap(data, len);
+                return Err(Error::new("cannot read shared object", path));
+            }
+            Ok(n) => done += n,
+        }
+    }
+    Ok((data, len))
+}
+
+/// Copy the `PT_LOAD` segments of `data` into a fresh mapping and describe
+/// the result.
+fn map_object(data: &[u8], path: &'static [u8], group: usize) -> Result<Object, Error> {
+    let elf = ElfFile::parse(data).map_err(|_| Error::new("not a valid ELF file", path))?;
+    if elf.elf_type() != ElfType::Dyn {
+        return Err(Error::new("not a shared object", path));
+    }
+
+    let mut lo = usize::MAX;
+    let mut hi = 0;
+    for seg in elf.load_segments() {
+        lo = lo.min(align_down(seg.vaddr as usize, PAGE_SIZE));
+        hi = hi.max(align_up((seg.vaddr + seg.memsz) as usize, PAGE_SIZE));
+    }
+    if lo >= hi {
+        return Err(Error::new("no loadable segments", path));
+    }
+    let map = mem::map(hi - lo).ok_or_else(|| Error::new("out of memory", path))? as usize;
+    let base = map - lo;
+
+    for seg in elf.load_segments() {
+        // SAFETY: The segment lies inside the mapping just created.
+        unsafe {
+            core::ptr::copy_nonoverlapping(
+                seg.data.as_ptr(),
+                (base + seg.vaddr as usize) as *mut u8,
+                seg.data.len(),
+            );
+        }
+    }
+
+    let phdr = match place_phdrs(&elf, base, path) {
+        Ok(phdr) => phdr,
+        Err(err) => {
+            mem::unmap(map as *mut u8, hi - lo);
+            return Err(err);
+        }
+    };
+    let phnum = usize::from(elf.header().e_phnum);
+    Object::new(path, base, phdr, phnum, (map, hi - lo), group).inspect_err(|_| {
+        mem::unmap(map as *mut u8, hi - lo);
+    })
+}
+
+/// Return the runtime address of `elf`'s program header table, copying it
+/// into linker memory if no segment loads it.
+fn place_phdrs(elf: &ElfFile<'_>, base: usize, path: &[u8]) -> Result<usize, Error> {
+    let header = elf.header();
+    if usize::from(header.e_phentsize) != ELF64_PHDR_SIZE {
+        return Err(Error::new("unexpected program header size", path));
+    }
+    if let Some(vaddr) = elf.phdr_vaddr() {
+        return Ok(base + vaddr as usize);
+    }
+    let offset = header.e_phoff as usize;
+    let size = usize::from(header.e_phnum) * ELF64_PHDR_SIZE;
+    let table = &elf.raw_data()[offset..offset + size];
+    let copy = mem::alloc(size, 8).ok_or_else(|| Error::new("out of memory", path))?;
+    // SAFETY: `copy` is a fresh allocation of `size` bytes.
+    unsafe { core::ptr::copy_nonoverlapping(table.as_ptr(), copy, size) };
+    Ok(copy as usize)
+}
@@ -0,0 +1,179 @@
+//! Hadron dynamic linker (`/lib/ld-hadron.so.1`).
+//!
+//! The kernel maps this image next to any executable whose `PT_INTERP`
+//! names it and enters it with the executable's initial stack. The kernel
+//! has already applied this image's own relative relocations, so ordinary
+//! Rust code runs from the first instruction. The auxiliary vector says
+//! where the executable's program headers are (`AT_PHDR`), where this image
+//! was loaded (`AT_BASE`) and where the executable starts (`AT_ENTRY`).
+//!
+//! Startup:
+//! 1. Describe the executable as object 0 and load its `DT_NEEDED`
+//!    dependencies breadth-first ([`load`]), laying out their static TLS
+//!    blocks as they appear ([`tls`]).
+//! 2. Install the main thread's TCB and TLS.
+//! 3. Relocate dependencies before their dependents, the executable last
+//!    ([`reloc`]).
+//! 4. Publish the [`dl`] table in `AT_HADRON_DL` and run initialisers.
+//! 5. Restore the original stack pointer and jump to `AT_ENTRY`.
+
+#![no_std]
+#![no_main]
+
+mod dl;
+mod error;
+mod load;
+mod mem;
+mod object;
+mod reloc;
+mod tls;
+
+use core::fmt::Write;
+
+use hadron_elf::{ELF64_PHDR_SIZE, Elf64ProgramHeader, PT_PHDR};
+use hadron_libc_core::auxv::{auxv_entry, init_auxv};
+use hadron_syscall::{AT_BASE, AT_ENTRY, AT_HADRON_DL, AT_PHDR, AT_PHNUM, AT_SECURE};
+
+use crate::error::{Error, Stderr};
+use crate::object::Object;
+
+/// Naked entry point: passes the initial stack pointer to [`ld_main`].
+#[unsafe(no_mangle)]
+#[unsafe(naked)]
+pub extern "C" fn _start() -> ! {
+    core::arch::naked_asm!(
+        "mov rdi, rsp", // pass original stack pointer (points to argc)
+        "and rsp, -16", // ensure 16-byte alignment for call
+        "call {ld_main}",
+        "ud2",
+        ld_main = sym ld_main,
+    );
+}
+
+/// Link the program, then enter it with the stack the kernel built.
+#[expect(clippy::similar_names, reason = "C `main` argument names")]
+extern "C" fn ld_main(stack: *mut usize) -> ! {
+    // SAFETY: The kernel wrote argc, argv and envp at the stack pointer.
+    let (argc, argv, envp) = unsafe {
+        let argc = *stack;
+        let argv = stack.add(1) as *const *const u8;
+        (argc, argv, argv.add(argc + 1))
+    };
+    // SAFETY: `envp` is the kernel-provided, NULL-terminated environment,
+    // followed by the auxiliary vector.
+    unsafe { init_auxv(envp) };
+
+    match startup(argc, argv, envp) {
+        // SAFETY: `stack` is untouched and `entry` is the program's entry.
+        Ok(entry) => unsafe { enter(entry, stack) },
+        Err(err) => error::fatal(&err),
+    }
+}
+
+/// Read auxiliary vector entry `ty`, or 0 if absent.
+fn aux(ty: usize) -> usize {
+    // SAFETY: `auxv_entry` returns a pointer into the live auxv array.
+    auxv_entry(ty).map_or(0, |slot| unsafe { *slot })
+}
+
+/// Load, relocate and initialise the program; return its entry point.
+#[expect(clippy::similar_names, reason = "C `main` argument names")]
+fn startup(argc: usize, argv: *const *const u8, envp: *const *const u8) -> Result<usize, Error> {
+    if aux(AT_BASE) == 0 {
+        return Err(Error::new(
+            "this is the dynamic linker; it cannot be run directly",
+            b"",
+        ));
+    }
+    read_environment(envp, aux(AT_SECURE) != 0);
+
+    let phdr = aux(AT_PHDR);
+    let phnum = aux(AT_PHNUM);
+    let exe = Object::new(b"", executable_base(phdr, phnum), phdr, phnum, (0, 0), 0)?;
+    object::push(exe)?;
+    load::load_dependencies(0, 0)?;
+    tls::finish_startup();
+
+    // IFUNC resolvers may already use the stack protector, which reads the
+    // TCB, so the thread pointer must be valid before relocation.
+    tls::init_main_thread()?;
+    let objects = object::objects();
+    for obj in objects.iter().rev() {
+        reloc::relocate(obj, false)?;
+        reloc::protect(obj);
+    }
+    // Copy the now-relocated TLS images again.
+    // SAFETY: The main thread's static TLS area lies below its TCB.
+    unsafe { tls::tls_init(tls::thread_pointer() as *mut u8) };
+    if let Some(slot) = auxv_entry(AT_HADRON_DL) {
+        dl::publish(slot);
+    }
+    object::set_init_args(argc, argv, envp);
+    object::run_initializers(0..objects.len());
+
+    Ok(aux(AT_ENTRY))
+}
+
+/// Compute the executable's load bias from its `PT_PHDR` entry.
+///
+/// An executable without `PT_PHDR` is assumed to be position-dependent.
+fn executable_base(phdr: usize, phnum: usize) -> usize {
+    // SAFETY: The kernel passes the address and count of the executable's
+    // mapped program header table.
+    let table = unsafe { core::slice::from_raw_parts(phdr as *const u8, phnum * ELF64_PHDR_SIZE) };
+    (0..phnum)
+        .map(|i| Elf64ProgramHeader::parse(table, i * ELF64_PHDR_SIZE))
+        .find(|ph| ph.seg_type == PT_PHDR)
+        .map_or(0, |ph| phdr - ph.vaddr as usize)
+}
+
+/// Apply `LD_BIND_NOW` and `LD_LIBRARY_PATH`. Set-ID programs ignore the
+/// search path.
+fn read_environment(envp: *const *const u8, secure: bool) {
+    let mut p = envp;
+    // SAFETY: `envp` is NULL-terminated and its strings live on the initial
+    // stack for the life of the process.
+    unsafe {
+        while !(*p).is_null() {
+            let var = mem::cstr(*p);
+            if let Some(value) = var.strip_prefix(b"LD_BIND_NOW=") {
+                if !value.is_empty() {
+                    reloc::force_bind_now();
+                }
+            } else if let Some(value) = var.strip_prefix(b"LD_LIBRARY_PATH=")
+                && !secure
+            {
+                load::set_library_path(value);
+            }
+            p = p.add(1);
+        }
+    }
+}
+
+/// Switch to the kernel-built stack and jump to the program's entry point.
+///
+/// `rdx` is cleared: the ELF ABI uses it for a termination function, and
+/// there is none.
+///
+/// # Safety
+///
+/// `stack` must be the initial stack pointer and `entry` the entry point.
+unsafe fn enter(entry: usize, stack: *mut usize) -> ! {
+    // SAFETY: The caller guarantees both values.
+    unsafe {
+        core::arch::asm!(
+            "mov rsp, rsi",
+            "xor edx, edx",
+            "jmp rax",
+            in("rax") entry,
+            in("rsi") stack,
+            options(noreturn),
+        );
+    }
+}
+
+#[panic_handler]
+fn panic(info: &core::panic::PanicInfo<'_>) -> ! {
+    let _ = writeln!(Stderr, "ld-hadron.so: {info}");
+    hadron_libc_core::sys::sys_exit(127)
+}
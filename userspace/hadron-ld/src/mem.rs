@@ -0,0 +1,93 @@
+//! Page mappings and a bump allocator for the linker's own bookkeeping.
+//!
+//! Nothing the linker allocates is ever freed: objects stay mapped for the
+//! life of the process, and so do their names and the main thread's TLS.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use hadron_libc_core::sys;
+use hadron_syscall::{MAP_ANONYMOUS, PROT_READ, PROT_WRITE};
+
+/// Size of a page.
+pub const PAGE_SIZE: usize = 4096;
+
+/// Granularity of bump allocator refills.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Round `value` up to a multiple of `align` (a power of two).
+pub const fn align_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+/// Round `value` down to a multiple of `align` (a power of two).
+pub const fn align_down(value: usize, align: usize) -> usize {
+    value & !(align - 1)
+}
+
+/// Map `len` bytes of zeroed, writable anonymous memory.
+pub fn map(len: usize) -> Option<*mut u8> {
+    let len = align_up(len, PAGE_SIZE);
+    sys::sys_mmap(0, len, PROT_READ | PROT_WRITE, MAP_ANONYMOUS, usize::MAX)
+        .ok()
+        .filter(|ptr| !ptr.is_null())
+}
+
+/// Unmap a region returned by [`map`].
+pub fn unmap(ptr: *mut u8, len: usize) {
+    let _ = sys::sys_munmap(ptr, align_up(len, PAGE_SIZE));
+}
+
+/// Change the protection of the pages covering `[addr, addr + len)`.
+pub fn protect(addr: usize, len: usize, prot: usize) -> bool {
+    let start = align_down(addr, PAGE_SIZE);
+    let end = align_up(addr + len, PAGE_SIZE);
+    sys::sys_mprotect(start as *mut u8, end - start, prot).is_ok()
+}
+
+/// Next free byte in the current chunk.
+static NEXT: AtomicUsize = AtomicUsize::new(0);
+/// End of the current chunk.
+static END: AtomicUsize = AtomicUsize::new(0);
+
+/// Allocate `size` zeroed bytes aligned to `align` (a power of two).
+///
+/// Callers must hold the linker lock, or run before the program starts.
+pub fn alloc(size: usize, align: usize) -> Option<*mut u8> {
+    let mut start = align_up(NEXT.load(Ordering::Relaxed), align);
+    if start == 0 || start + size > END.load(Ordering::Relaxed) {
+        let len = align_up((size + align).max(CHUNK_SIZE), PAGE_SIZE);
+        let chunk = map(len)? as usize;
+        END.store(chunk + len, Ordering::Relaxed);
+        start = align_up(chunk, align);
+    }
+    NEXT.store(start + size, Ordering::Relaxed);
+    Some(start as *mut u8)
+}
+
+/// Copy `s` into linker memory, returning the copy without its NUL
+/// terminator (which is still present in memory).
+pub fn alloc_str(s: &[u8]) -> Option<&'static [u8]> {
+    let ptr = alloc(s.len() + 1, 1)?;
+    // SAFETY: `ptr` is a fresh allocation of `s.len() + 1` zeroed bytes that
+    // is never freed.
+    unsafe {
+        core::ptr::copy_nonoverlapping(s.as_ptr(), ptr, s.len());
+        Some(core::slice::from_raw_parts(ptr, s.len()))
+    }
+}
+
+/// Borrow a NUL-terminated string, without the terminator.
+///
+/// # Safety
+///
+/// `ptr` must point to a NUL-terminated string that lives for `'a`.
+pub unsafe fn cstr<'a>(ptr: *const u8) -> &'a [u8] {
+    let mut len = 0;
+    // SAFETY: The caller guarantees a terminator is reachable.
+    unsafe {
+        while *ptr.add(len) != 0 {
+            len += 1;
+        }
+        core::slice::from_raw_parts(ptr, len)
+    }
+}
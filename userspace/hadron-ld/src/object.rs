@@ -0,0 +1,499 @@
+//! Loaded objects, the object table and symbol lookup.
+//!
+//! Objects are described entirely by their in-memory image: the program
+//! headers locate `PT_DYNAMIC` and `PT_TLS`, and the dynamic section locates
+//! everything else. Object 0 is always the executable.
+//!
+//! Lookup follows the ELF rules: the global scope (the executable, its
+//! startup dependencies and anything opened with `RTLD_GLOBAL`) in load
+//! order, then the requesting object's own `dlopen` group.
+
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use hadron_elf::dynamic::{
+    DF_1_NOW, DF_BIND_NOW, DT_BIND_NOW, DT_FLAGS, DT_FLAGS_1, DT_GNU_HASH, DT_HASH, DT_INIT,
+    DT_INIT_ARRAY, DT_INIT_ARRAYSZ, DT_JMPREL, DT_NEEDED, DT_PLTGOT, DT_PLTREL, DT_PLTRELSZ,
+    DT_RELA, DT_RELASZ, DT_SONAME, DT_STRSZ, DT_STRTAB, DT_SYMBOLIC, DT_SYMTAB,
+};
+use hadron_elf::{
+    DynamicIter, ELF64_PHDR_SIZE, ELF64_SYM_SIZE, Elf64ProgramHeader, Elf64Symbol, PT_DYNAMIC,
+    PT_TLS, SHN_UNDEF, STB_GLOBAL, STB_WEAK, STT_GNU_IFUNC, STT_TLS, elf_hash, gnu_hash,
+};
+
+use crate::error::Error;
+use crate::mem;
+use crate::tls::{self, TlsModule};
+
+/// Maximum number of objects, including the executable.
+pub const MAX_OBJECTS: usize = 128;
+
+/// Maximum number of `DT_NEEDED` entries per object.
+const MAX_NEEDED: usize = 32;
+
+/// Addresses and sizes taken from an object's dynamic section, already
+/// adjusted by the load bias.
+#[derive(Clone, Copy, Default)]
+pub struct DynInfo {
+    pub strtab: usize,
+    pub strsz: usize,
+    pub symtab: usize,
+    pub hash: usize,
+    pub gnu_hash: usize,
+    pub rela: usize,
+    pub relasz: usize,
+    pub jmprel: usize,
+    pub pltrelsz: usize,
+    pub pltgot: usize,
+    pub init: usize,
+    pub init_array: usize,
+    pub init_arraysz: usize,
+    /// String table offset of `DT_SONAME`.
+    pub soname: Option<usize>,
+    /// `DT_SYMBOLIC`: search this object before the global scope.
+    pub symbolic: bool,
+    /// `DT_BIND_NOW`, `DF_BIND_NOW` or `DF_1_NOW`.
+    pub bind_now: bool,
+    needed: [usize; MAX_NEEDED],
+    needed_count: usize,
+}
+
+impl DynInfo {
+    /// Parse the contents of a `PT_DYNAMIC` segment.
+    fn parse(base: usize, dynamic: &[u8], name: &[u8]) -> Result<Self, Error> {
+        let mut info = Self::default();
+        for entry in DynamicIter::new(dynamic) {
+            let val = entry.d_val as usize;
+            let addr = base.wrapping_add(val);
+            match entry.d_tag {
+                DT_NEEDED => {
+                    if info.needed_count == MAX_NEEDED {
+                        return Err(Error::new("too many DT_NEEDED entries", name));
+                    }
+                    info.needed[info.needed_count] = val;
+                    info.needed_count += 1;
+                }
+                DT_PLTRELSZ => info.pltrelsz = val,
+                DT_PLTGOT => info.pltgot = addr,
+                DT_HASH => info.hash = addr,
+                DT_GNU_HASH => info.gnu_hash = addr,
+                DT_STRTAB => info.strtab = addr,
+                DT_SYMTAB => info.symtab = addr,
+                DT_RELA => info.rela = addr,
+                DT_RELASZ => info.relasz = val,
+                DT_STRSZ => info.strsz = val,
+                DT_INIT => info.init = addr,
+                DT_SONAME => info.soname = Some(val),
+                DT_SYMBOLIC => info.symbolic = true,
+                DT_PLTREL if entry.d_val != DT_RELA => {
+                    return Err(Error::new("PLT uses REL relocations", name));
+                }
+                DT_JMPREL => info.jmprel = addr,
+                DT_BIND_NOW => info.bind_now = true,
+                DT_INIT_ARRAY => info.init_array = addr,
+                DT_INIT_ARRAYSZ => info.init_arraysz = val,
+                DT_FLAGS if entry.d_val & DF_BIND_NOW != 0 => info.bind_now = true,
+                DT_FLAGS_1 if entry.d_val & DF_1_NOW != 0 => info.bind_now = true,
+                _ => {}
+            }
+        }
+        if info.needed_count != 0 && info.strtab == 0 {
+            return Err(Error::new("DT_NEEDED without a string table", name));
+        }
+        Ok(info)
+    }
+}
+
+/// A loaded ELF object.
+pub struct Object {
+    /// Path the object was loaded from; empty for the executable.
+    pub name: &'static [u8],
+    /// Load bias: runtime address minus link-time address.
+    pub base: usize,
+    /// Runtime address of the program header table.
+    pub phdr: usize,
+    /// Number of program headers.
+    pub phnum: usize,
+    /// Anonymous mapping holding the image, or `(0, 0)` if the kernel
+    /// mapped it.
+    pub map: (usize, usize),
+    /// Parsed dynamic section.
+    pub dyn_info: DynInfo,
+    /// Static TLS block, if the object has `PT_TLS`.
+    pub tls: Option<TlsModule>,
+    /// Index of the object whose `dlopen` loaded this one (0 at startup).
+    pub group: usize,
+    /// Whether the object is part of the global lookup scope.
+    pub global: AtomicBool,
+    /// `dlopen` reference count.
+    pub refcount: AtomicUsize,
+    /// Whether the object's initialisers have been started.
+    pub initialized: AtomicBool,
+}
+
+/// A resolved symbol definition.
+#[derive(Clone, Copy)]
+pub struct Def {
+    /// Defining object; `None` for linker built-ins.
+    pub obj: Option<&'static Object>,
+    /// Absolute address, or the offset within the TLS block for TLS symbols.
+    pub value: usize,
+    /// Symbol size in bytes (used by copy relocations).
+    pub size: usize,
+    /// Whether the symbol is a TLS variable.
+    pub tls: bool,
+}
+
+impl Object {
+    /// Describe an image that is already in memory.
+    ///
+    /// `phdr` is the runtime address of its `phnum` program headers.
+    pub fn new(
+        name: &'static [u8],
+        base: usize,
+        phdr: usize,
+        phnum: usize,
+        map: (usize, usize),
+        group: usize,
+    ) -> Result<Self, Error> {
+        let mut obj = Self {
+            name,
+            base,
+            phdr,
+            phnum,
+            map,
+            dyn_info: DynInfo::default(),
+            tls: None,
+            group,
+            global: AtomicBool::new(group == 0),
+            refcount: AtomicUsize::new(1),
+            initialized: AtomicBool::new(false),
+        };
+        for ph in obj.program_headers() {
+            let start = base.wrapping_add(ph.vaddr as usize);
+            match ph.seg_type {
+                PT_DYNAMIC => {
+                    // SAFETY: PT_DYNAMIC lies inside a loaded segment.
+                    let dynamic = unsafe {
+                        core::slice::from_raw_parts(start as *const u8, ph.memsz as usize)
+                    };
+                    obj.dyn_info = DynInfo::parse(base, dynamic, name)?;
+                }
+                PT_TLS => {
+                    obj.tls = Some(tls::allocate(
+                        start,
+                        ph.filesz as usize,
+                        ph.memsz as usize,
+                        ph.align as usize,
+                        name,
+                    )?);
+                }
+                _ => {}
+            }
+        }
+        Ok(obj)
+    }
+
+    /// Iterate over the object's program headers.
+    pub fn program_headers(&self) -> impl Iterator<Item = Elf64ProgramHeader> + 'static {
+        // SAFETY: `phdr` points at `phnum` in-memory program headers that
+        // stay mapped for the life of the process.
+        let table = unsafe {
+            core::slice::from_raw_parts(self.phdr as *const u8, self.phnum * ELF64_PHDR_SIZE)
+        };
+        (0..self.phnum).map(move |i| Elf64ProgramHeader::parse(table, i * ELF64_PHDR_SIZE))
+    }
+
+    /// Return the string at `offset` in the dynamic string table.
+    pub fn string(&self, offset: usize) -> &'static [u8] {
+        if offset >= self.dyn_info.strsz {
+            return b"";
+        }
+        // SAFETY: The string table is mapped for the life of the process and
+        // its strings are NUL-terminated.
+        unsafe { mem::cstr((self.dyn_info.strtab + offset) as *const u8) }
+    }
+
+    /// Return dynamic symbol `index`.
+    pub fn symbol(&self, index: usize) -> Elf64Symbol {
+        // SAFETY: Relocations and hash chains only name valid symbol indices;
+        // the table is mapped for the life of the process.
+        let bytes = unsafe {
+            core::slice::from_raw_parts(
+                (self.dyn_info.symtab + index * ELF64_SYM_SIZE) as *const u8,
+                ELF64_SYM_SIZE,
+            )
+        };
+        Elf64Symbol::parse(bytes, 0)
+    }
+
+    /// Iterate over the `DT_NEEDED` names.
+    pub fn needed(&self) -> impl Iterator<Item = &'static [u8]> + '_ {
+        self.dyn_info.needed[..self.dyn_info.needed_count]
+            .iter()
+            .map(|&off| self.string(off))
+    }
+
+    /// Whether `name` (a `DT_NEEDED` or `dlopen` argument) refers to this
+    /// object: its `DT_SONAME`, its path, or its path's final component.
+    pub fn matches_name(&self, name: &[u8]) -> bool {
+        if self.name.is_empty() {
+            return false;
+        }
+        if self
+            .dyn_info
+            .soname
+            .is_some_and(|off| self.string(off) == name)
+        {
+            return true;
+        }
+        let file = match self.name.iter().rposition(|&b| b == b'/') {
+            Some(slash) => &self.name[slash + 1..],
+            None => self.name,
+        };
+        self.name == name || file == name
+    }
+
+    /// Look up a defined, exported symbol in this object only.
+    pub fn lookup(&'static self, name: &[u8], hashes: &Hashes) -> Option<Def> {
+        let index = if self.dyn_info.gnu_hash != 0 {
+            self.gnu_lookup(name, hashes.gnu)
+        } else if self.dyn_info.hash != 0 {
+            self.sysv_lookup(name, hashes.sysv)
+        } else {
+            None
+        }?;
+        let sym = self.symbol(index);
+        let value = if sym.sym_type() == STT_TLS {
+            sym.st_value as usize
+        } else if sym.sym_type() == STT_GNU_IFUNC {
+            // SAFETY: An IFUNC symbol's value is a resolver function taking
+            // no arguments and returning the implementation's address.
+            let resolver: extern "C" fn() -> usize =
+                unsafe { core::mem::transmute(self.base + sym.st_value as usize) };
+            resolver()
+        } else {
+            self.base + sym.st_value as usize
+        };
+        Some(Def {
+            obj: Some(self),
+            value,
+            size: sym.st_size as usize,
+            tls: sym.sym_type() == STT_TLS,
+        })
+    }
+
+    /// Whether dynamic symbol `index` is a definition visible to `name`.
+    fn is_match(&self, index: usize, name: &[u8]) -> bool {
+        let sym = self.symbol(index);
+        let bind = sym.sym_bind();
+        sym.st_shndx != SHN_UNDEF
+            && (sym.st_value != 0 || sym.sym_type() == STT_TLS)
+            && (bind == STB_GLOBAL || bind == STB_WEAK)
+            && self.string(sym.st_name as usize) == name
+    }
+
+    /// Look `name` up through `DT_GNU_HASH`.
+    fn gnu_lookup(&self, name: &[u8], hash: u32) -> Option<usize> {
+        let table = self.dyn_info.gnu_hash as *const u32;
+        // SAFETY: DT_GNU_HASH points at a well-formed table in mapped memory.
+        unsafe {
+            let nbuckets = *table as usize;
+            let symoffset = *table.add(1) as usize;
+            let bloom_size = *table.add(2) as usize;
+            let bloom_shift = *table.add(3);
+            if nbuckets == 0 || bloom_size == 0 {
+                return None;
+            }
+            let bloom = table.add(4) as *const u64;
+            let word = *bloom.add((hash as usize / 64) % bloom_size);
+            let mask = (1u64 << (hash % 64)) | (1u64 << ((hash >> bloom_shift) % 64));
+            if word & mask != mask {
+                return None;
+            }
+            let buckets = bloom.add(bloom_size) as *const u32;
+            let chain = buckets.add(nbuckets);
+            let mut index = *buckets.add(hash as usize % nbuckets) as usize;
+            if index < symoffset {
+                return None;
+            }
+            loop {
+                let entry = *chain.add(index - symoffset);
+                if (entry | 1) == (hash | 1) && self.is_match(index, name) {
+                    return Some(index);
+                }
+                if entry & 1 != 0 {
+                    return None;
+                }
+                index += 1;
+            }
+        }
+    }
+
+    /// Look `name` up through `DT_HASH`.
+    fn sysv_lookup(&self, name: &[u8], hash: u32) -> Option<usize> {
+        let table = self.dyn_info.hash as *const u32;
+        // SAFETY: DT_HASH points at a well-formed table in mapped memory.
+        unsafe {
+            let nbucket = *table as usize;
+            if nbucket == 0 {
+                return None;
+            }
+            let bucket = table.add(2);
+            let chain = bucket.add(nbucket);
+            let mut index = *bucket.add(hash as usize % nbucket) as usize;
+            while index != 0 {
+                if self.is_match(index, name) {
+                    return Some(index);
+                }
+                index = *chain.add(index) as usize;
+            }
+        }
+        None
+    }
+}
+
+/// Precomputed hashes of a symbol name.
+pub struct Hashes {
+    gnu: u32,
+    sysv: u32,
+}
+
+impl Hashes {
+    pub fn new(name: &[u8]) -> Self {
+        Self {
+            gnu: gnu_hash(name),
+            sysv: elf_hash(name),
+        }
+    }
+}
+
+/// Search the global scope, then `requester`'s `dlopen` group.
+///
+/// `skip_executable` excludes object 0, as copy relocations require.
+pub fn resolve(name: &[u8], requester: Option<&Object>, skip_executable: bool) -> Option<Def> {
+    let hashes = Hashes::new(name);
+    let objects = objects();
+    let first = usize::from(skip_executable);
+    let in_global = objects[first..]
+        .iter()
+        .filter(|obj| obj.global.load(Ordering::Acquire))
+        .find_map(|obj| obj.lookup(name, &hashes));
+    if in_global.is_some() {
+        return in_global;
+    }
+    let group = requester
+        .filter(|obj| !obj.global.load(Ordering::Acquire))?
+        .group;
+    objects[first..]
+        .iter()
+        .filter(|obj| obj.group == group)
+        .find_map(|obj| obj.lookup(name, &hashes))
+}
+
+// ---- Object table -----------------------------------------------------------
+
+struct Table {
+    slots: UnsafeCell<[MaybeUninit<Object>; MAX_OBJECTS]>,
+    count: AtomicUsize,
+}
+
+// SAFETY: Slots below `count` are immutable apart from their atomics; slots
+// at or above it are only written by the holder of the linker lock before
+// `count` is raised with release ordering.
+unsafe impl Sync for Table {}
+
+static TABLE: Table = Table {
+    slots: UnsafeCell::new([const { MaybeUninit::uninit() }; MAX_OBJECTS]),
+    count: AtomicUsize::new(0),
+};
+
+/// All objects loaded so far, in load order.
+pub fn objects() -> &'static [Object] {
+    let count = TABLE.count.load(Ordering::Acquire);
+    // SAFETY: The first `count` slots are initialised and never moved.
+    unsafe { core::slice::from_raw_parts(TABLE.slots.get().cast::<Object>(), count) }
+}
+
+/// Append `obj` to the table and return its index.
+///
+/// Callers must hold the linker lock, or run before the program starts.
+pub fn push(obj: Object) -> Result<usize, Error> {
+    let index = TABLE.count.load(Ordering::Relaxed);
+    if index == MAX_OBJECTS {
+        return Err(Error::new("too many loaded objects", obj.name));
+    }
+    // SAFETY: Slot `index` is unpublished and we hold the linker lock.
+    unsafe { (*TABLE.slots.get())[index].write(obj) };
+    TABLE.count.store(index + 1, Ordering::Release);
+    Ok(index)
+}
+
+/// Drop every object from index `len` on, unmapping their images.
+///
+/// Used to undo a failed `dlopen`. Callers must hold the linker lock, and
+/// the dropped objects must not have been added to the global scope.
+pub fn truncate(len: usize) {
+    for obj in &objects()[len.min(objects().len())..] {
+        if obj.map.1 != 0 {
+            mem::unmap(obj.map.0 as *mut u8, obj.map.1);
+        }
+    }
+    if len < TABLE.count.load(Ordering::Relaxed) {
+        TABLE.count.store(len, Ordering::Release);
+    }
+}
+
+/// Map a `dlopen` handle back to the object it names.
+pub fn from_handle(handle: *const u8) -> Option<&'static Object> {
+    objects()
+        .iter()
+        .find(|obj| core::ptr::eq(*obj as *const Object as *const u8, handle))
+}
+
+// ---- Initialisers -----------------------------------------------------------
+
+/// `(argc, argv, envp)` passed to `DT_INIT_ARRAY` functions.
+static INIT_ARGS: [AtomicUsize; 3] = [const { AtomicUsize::new(0) }; 3];
+
+/// Record the program arguments for initialisers run by later `dlopen`s.
+#[expect(clippy::similar_names, reason = "C `main` argument names")]
+pub fn set_init_args(argc: usize, argv: *const *const u8, envp: *const *const u8) {
+    INIT_ARGS[0].store(argc, Ordering::Relaxed);
+    INIT_ARGS[1].store(argv as usize, Ordering::Relaxed);
+    INIT_ARGS[2].store(envp as usize, Ordering::Relaxed);
+}
+
+/// Run `DT_INIT` and `DT_INIT_ARRAY` for objects `range`, dependencies
+/// (later indices) first. Must be called without the linker lock held,
+/// since initialisers may call `dlopen`.
+#[expect(clippy::similar_names, reason = "C `main` argument names")]
+pub fn run_initializers(range: core::ops::Range<usize>) {
+    type InitFn = extern "C" fn(i32, *const *const u8, *const *const u8);
+
+    let argc = INIT_ARGS[0].load(Ordering::Relaxed) as i32;
+    let argv = INIT_ARGS[1].load(Ordering::Relaxed) as *const *const u8;
+    let envp = INIT_ARGS[2].load(Ordering::Relaxed) as *const *const u8;
+    for obj in objects()[range].iter().rev() {
+        if obj.initialized.swap(true, Ordering::AcqRel) {
+            continue;
+        }
+        let info = &obj.dyn_info;
+        if info.init != 0 {
+            // SAFETY: DT_INIT is a function taking no arguments.
+            let init: extern "C" fn() = unsafe { core::mem::transmute(info.init) };
+            init();
+        }
+        let count = info.init_arraysz / core::mem::size_of::<usize>();
+        for i in 0..count {
+            // SAFETY: DT_INIT_ARRAY holds `count` relocated function pointers.
+            let f = unsafe { *(info.init_array as *const usize).add(i) };
+            if f != 0 && f != usize::MAX {
+                // SAFETY: Entries are initialisers with the C `main` signature.
+                let init: InitFn = unsafe { core::mem::transmute(f) };
+                init(argc, argv, envp);
+            }
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Relocation processing and lazy PLT binding.
+//!
+//! `DT_RELA` is always processed eagerly. `DT_JMPREL` (`R_X86_64_JUMP_SLOT`)
+//! entries are bound lazily by default: each GOT slot is pointed back at its
+//! PLT stub, `GOT[1]` holds the object and `GOT[2]` the resolver trampoline,
+//! so the first call through a slot enters [`runtime_resolve`] with the
+//! object and relocation index pushed by the PLT.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use hadron_elf::{
+    ELF64_RELA_SIZE, Elf64Rela, PF_W, PF_X, PT_LOAD, R_X86_64_COPY, R_X86_64_DTPMOD64,
+    R_X86_64_DTPOFF64, R_X86_64_IRELATIVE, R_X86_64_JUMP_SLOT, R_X86_64_NONE, R_X86_64_TPOFF64,
+    RelaIter, RelocValue, STB_LOCAL, STB_WEAK, STT_TLS, compute_x86_64_reloc,
+};
+use hadron_syscall::{PROT_EXEC, PROT_READ};
+
+use crate::dl;
+use crate::error::{self, Error};
+use crate::mem::{self, PAGE_SIZE, align_down, align_up};
+use crate::object::{self, Def, Object};
+
+/// Set from `LD_BIND_NOW`: bind every PLT slot before the program starts.
+static FORCE_BIND_NOW: AtomicBool = AtomicBool::new(false);
+
+/// Make every later [`relocate`] bind PLT slots eagerly.
+pub fn force_bind_now() {
+    FORCE_BIND_NOW.store(true, Ordering::Relaxed);
+}
+
+/// Iterate over the `Rela` table at `addr` (of `size` bytes).
+fn rela_table(addr: usize, size: usize) -> RelaIter<'static> {
+    if addr == 0 {
+        return RelaIter::new(&[], 0, 0);
+    }
+    // SAFETY: DT_RELA/DT_JMPREL tables are mapped for the life of the
+    // process.
+    let data = unsafe { core::slice::from_raw_parts(addr as *const u8, size) };
+    RelaIter::new(data, 0, size)
+}
+
+/// Apply all of `obj`'s relocations.
+///
+/// Objects must be relocated after the objects they depend on, so that copy
+/// relocations and IFUNC resolvers see relocated data.
+pub fn relocate(obj: &'static Object, bind_now: bool) -> Result<(), Error> {
+    let info = &obj.dyn_info;
+    for rela in rela_table(info.rela, info.relasz) {
+        apply(obj, &rela)?;
+    }
+
+    let lazy = !(bind_now || info.bind_now || FORCE_BIND_NOW.load(Ordering::Relaxed));
+    for rela in rela_table(info.jmprel, info.pltrelsz) {
+        if lazy && rela.r_type == R_X86_64_JUMP_SLOT {
+            let slot = (obj.base + rela.r_offset as usize) as *mut usize;
+            // SAFETY: The slot lies in the object's writable GOT and holds
+            // the link-time address of its PLT stub.
+            unsafe { *slot = (*slot).wrapping_add(obj.base) };
+        } else {
+            apply(obj, &rela)?;
+        }
+    }
+    if lazy && info.jmprel != 0 && info.pltgot != 0 {
+        let got = info.pltgot as *mut usize;
+        // SAFETY: GOT[1] and GOT[2] are reserved for the dynamic linker.
+        unsafe {
+            *got.add(1) = obj as *const Object as usize;
+            *got.add(2) = runtime_resolve as usize;
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the symbol named by relocation symbol `index` of `obj`.
+///
+/// Returns `Ok(None)` for an undefined weak symbol.
+fn lookup(obj: &'static Object, index: u32, copy: bool) -> Result<Option<Def>, Error> {
+    if index == 0 {
+        return Ok(Some(Def {
+            obj: Some(obj),
+            value: 0,
+            size: 0,
+            tls: false,
+        }));
+    }
+    let sym = obj.symbol(index as usize);
+    if sym.sym_bind() == STB_LOCAL {
+        let tls = sym.sym_type() == STT_TLS;
+        let value = if tls {
+            sym.st_value as usize
+        } else {
+            obj.base + sym.st_value as usize
+        };
+        return Ok(Some(Def {
+            obj: Some(obj),
+            value,
+            size: sym.st_size as usize,
+            tls,
+        }));
+    }
+    let name = obj.string(sym.st_name as usize);
+    if obj.dyn_info.symbolic
+        && let Some(def) = obj.lookup(name, &object::Hashes::new(name))
+    {
+        return Ok(Some(def));
+    }
+    if let Some(def) = object::resolve(name, Some(obj), copy) {
+        return Ok(Some(def));
+    }
+    if let Some(value) = dl::builtin(name) {
+        return Ok(Some(Def {
+            obj: None,
+            value,
+            size: 0,
+            tls: false,
+        }));
+    }
+    if sym.sym_bind() == STB_WEAK {
+        return Ok(None);
+    }
+    Err(Error::new("undefined symbol", name))
+}
+
+/// TLS block of the object defining `def`.
+fn tls_module(def: &Def, obj: &Object) -> Result<crate::tls::TlsModule, Error> {
+    def.obj
+        .and_then(|o| o.tls)
+        .ok_or_else(|| Error::new("TLS relocation against a non-TLS symbol", obj.name))
+}
+
+/// Apply one relocation of `obj`.
+fn apply(obj: &'static Object, rela: &Elf64Rela) -> Result<(), Error> {
+    if rela.r_type == R_X86_64_NONE {
+        return Ok(());
+    }
+    let place = obj.base + rela.r_offset as usize;
+    let slot = place as *mut u64;
+    let addend = rela.r_addend as u64;
+
+    let value = match rela.r_type {
+        R_X86_64_IRELATIVE => {
+            // SAFETY: The addend is the link-time address of a resolver
+            // taking no arguments.
+            let resolver: extern "C" fn() -> u64 =
+                unsafe { core::mem::transmute(obj.base + rela.r_addend as usize) };
+            resolver()
+        }
+        R_X86_64_COPY => {
+            let def = lookup(obj, rela.r_sym, true)?
+                .ok_or_else(|| Error::new("copy relocation against weak symbol", obj.name))?;
+            // SAFETY: The executable reserved `size` bytes at `place` for the
+            // library's definition, which is mapped and relocated.
+            unsafe {
+                core::ptr::copy_nonoverlapping(def.value as *const u8, place as *mut u8, def.size);
+            }
+            return Ok(());
+        }
+        R_X86_64_DTPMOD64 => {
+            let def = lookup(obj, rela.r_sym, false)?;
+            match def {
+                Some(def) => tls_module(&def, obj)?.id as u64,
+                None => 0,
+            }
+        }
+        R_X86_64_DTPOFF64 => {
+            let value = lookup(obj, rela.r_sym, false)?.map_or(0, |def| def.value);
+            (value as u64).wrapping_add(addend)
+        }
+        R_X86_64_TPOFF64 => {
+            let def = lookup(obj, rela.r_sym, false)?
+                .ok_or_else(|| Error::new("TPOFF64 against undefined weak symbol", obj.name))?;
+            let module = tls_module(&def, obj)?;
+            (def.value as u64)
+                .wrapping_add(addend)
+                .wrapping_sub(module.offset as u64)
+        }
+        _ => {
+            let sym_value = lookup(obj, rela.r_sym, false)?.map_or(0, |def| def.value);
+            match compute_x86_64_reloc(rela, sym_value as u64, obj.base as u64, place as u64) {
+                Ok((_, RelocValue::U64(value))) => value,
+                Ok((_, RelocValue::U32(value))) => {
+                    // SAFETY: `place` lies in a writable part of the image.
+                    unsafe { (place as *mut u32).write_unaligned(value) };
+                    return Ok(());
+                }
+                Err(_) => return Err(Error::new("unsupported relocation", obj.name)),
+            }
+        }
+    };
+    // SAFETY: `place` lies in a writable part of the image.
+    unsafe { slot.write_unaligned(value) };
+    Ok(())
+}
+
+/// Bind PLT relocation `index` of `obj` on its first call.
+extern "C" fn fixup(obj: &'static Object, index: usize) -> usize {
+    let info = &obj.dyn_info;
+    let offset = index * ELF64_RELA_SIZE;
+    if info.jmprel == 0 || offset + ELF64_RELA_SIZE > info.pltrelsz {
+        error::fatal(&Error::new("bad PLT relocation index", obj.name));
+    }
+    // SAFETY: The entry lies inside DT_JMPREL, which stays mapped.
+    let entry = unsafe {
+        core::slice::from_raw_parts((info.jmprel + offset) as *const u8, ELF64_RELA_SIZE)
+    };
+    let rela = Elf64Rela::parse(entry, 0);
+    match lookup(obj, rela.r_sym, false) {
+        Ok(Some(def)) => {
+            let slot = (obj.base + rela.r_offset as usize) as *mut usize;
+            // SAFETY: The slot lies in the object's writable GOT.
+            unsafe { *slot = def.value };
+            def.value
+        }
+        Ok(None) => {
+            let sym = obj.symbol(rela.r_sym as usize);
+            let name = obj.string(sym.st_name as usize);
+            error::fatal(&Error::new("call to undefined weak function", name));
+        }
+        Err(err) => error::fatal(&err),
+    }
+}
+
+/// Lazy binding trampoline, reached through `GOT[2]`.
+///
+/// On entry the PLT has pushed the relocation index and `GOT[1]` (the
+/// object); the caller's return address is above them. Every
+/// argument-passing register is preserved across [`fixup`], then the
+/// pushed words are dropped and control jumps to the bound function as if
+/// the caller had called it directly.
+#[unsafe(naked)]
+unsafe extern "C" fn runtime_resolve() {
+    core::arch::naked_asm!(
+        // Entry RSP is 8 mod 16; seven pushes make it 16-byte aligned.
+        "push rax",
+        "push rcx",
+        "push rdx",
+        "push rsi",
+        "push rdi",
+        "push r8",
+        "push r9",
+        "sub rsp, 128",
+        "movdqu [rsp + 0x00], xmm0",
+        "movdqu [rsp + 0x10], xmm1",
+        "movdqu [rsp + 0x20], xmm2",
+        "movdqu [rsp + 0x30], xmm3",
+        "movdqu [rsp + 0x40], xmm4",
+        "movdqu [rsp + 0x50], xmm5",
+        "movdqu [rsp + 0x60], xmm6",
+        "movdqu [rsp + 0x70], xmm7",
+        "mov rdi, [rsp + 128 + 56]", // object (GOT[1])
+        "mov rsi, [rsp + 128 + 64]", // relocation index
+        "call {fixup}",
+        "mov r11, rax",
+        "movdqu xmm0, [rsp + 0x00]",
+        "movdqu xmm1, [rsp + 0x10]",
+        "movdqu xmm2, [rsp + 0x20]",
+        "movdqu xmm3, [rsp + 0x30]",
+        "movdqu xmm4, [rsp + 0x40]",
+        "movdqu xmm5, [rsp + 0x50]",
+        "movdqu xmm6, [rsp + 0x60]",
+        "movdqu xmm7, [rsp + 0x70]",
+        "add rsp, 128",
+        "pop r9",
+        "pop r8",
+        "pop rdi",
+        "pop rsi",
+        "pop rdx",
+        "pop rcx",
+        "pop rax",
+        "add rsp, 16",
+        "jmp r11",
+        fixup = sym fixup,
+    );
+}
+
+/// Drop write access from `obj`'s read-only segments once it is relocated.
+///
+/// Pages shared with a writable segment stay writable.
+pub fn protect(obj: &Object) {
+    if obj.map.1 == 0 {
+        return;
+    }
+    let writable = |start: usize, end: usize| {
+        obj.program_headers().any(|ph| {
+            let ph_start = align_down(obj.base + ph.vaddr as usize, PAGE_SIZE);
+            let ph_end = align_up(obj.base + (ph.vaddr + ph.memsz) as usize, PAGE_SIZE);
+            ph.seg_type == PT_LOAD && ph.flags & PF_W != 0 && ph_start < end && start < ph_end
+        })
+    };
+    for ph in obj.program_headers() {
+        if ph.seg_type != PT_LOAD || ph.flags & PF_W != 0 || ph.filesz == 0 {
+            continue;
+        }
+        let start = align_down(obj.base + ph.vaddr as usize, PAGE_SIZE);
+        let end = align_up(obj.base + (ph.vaddr + ph.filesz) as usize, PAGE_SIZE);
+        if writable(start, end) {
+            continue;
+        }
+        let prot = if ph.flags & PF_X != 0 {
+            PROT_READ | PROT_EXEC
+        } else {
+            PROT_READ
+        };
+        mem::protect(start, end - start, prot);
+    }
+}
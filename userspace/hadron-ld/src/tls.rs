@@ -0,0 +1,205 @@
+//! Thread-local storage (x86-64 variant II).
+//!
+//! Every module's TLS block lives at a fixed negative offset from the thread
+//! pointer, which points at the TCB:
+//!
+//! ```text
+//!   tp - static_size        tp - offset(2)   tp - offset(1)   tp
+//!   | surplus ...           | module 2       | module 1       | TCB ...
+//! ```
+//!
+//! The executable is module 1, so its block sits where the static linker
+//! assumed (`tp - round_up(memsz, align)`). Modules loaded at startup are
+//! laid out in load order; objects opened later take their blocks from the
+//! surplus, so their offsets are static too and `__tls_get_addr` is a simple
+//! subtraction.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use hadron_libc_core::dlfcn::TCB_RESERVE;
+use hadron_libc_core::sys;
+
+use crate::error::Error;
+use crate::mem::{self, align_up};
+use crate::object::{MAX_OBJECTS, objects};
+
+/// Static TLS reserved for modules with TLS that are opened after startup.
+const SURPLUS: usize = 16 * 1024;
+
+/// Minimum alignment of the thread pointer.
+const MIN_ALIGN: usize = 16;
+
+/// A module's static TLS block.
+#[derive(Clone, Copy)]
+pub struct TlsModule {
+    /// Module ID (1-based), as used by `R_X86_64_DTPMOD64`.
+    pub id: usize,
+    /// Distance from the thread pointer down to the block.
+    pub offset: usize,
+    /// Address of the initialisation image.
+    pub image: usize,
+    /// Bytes of the image to copy; the rest of the block is zeroed.
+    pub filesz: usize,
+    /// Size of the block.
+    pub memsz: usize,
+}
+
+/// Bytes of static TLS handed out so far.
+static USED: AtomicUsize = AtomicUsize::new(0);
+/// Total static TLS per thread; 0 until startup layout is finished.
+static STATIC_SIZE: AtomicUsize = AtomicUsize::new(0);
+/// Alignment of the thread pointer.
+static ALIGN: AtomicUsize = AtomicUsize::new(MIN_ALIGN);
+/// Next module ID to hand out.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+/// Block offset of each module ID, for `__tls_get_addr`.
+static OFFSETS: [AtomicUsize; MAX_OBJECTS + 1] = [const { AtomicUsize::new(0) }; MAX_OBJECTS + 1];
+
+/// Assign a module ID and a static block to a `PT_TLS` segment.
+///
+/// Callers must hold the linker lock, or run before the program starts.
+pub fn allocate(
+    image: usize,
+    filesz: usize,
+    memsz: usize,
+    align: usize,
+    name: &[u8],
+) -> Result<TlsModule, Error> {
+    let align = align.max(1);
+    if !align.is_power_of_two() {
+        return Err(Error::new("bad PT_TLS alignment", name));
+    }
+    let id = NEXT_ID.load(Ordering::Relaxed);
+    if id > MAX_OBJECTS {
+        return Err(Error::new("too many TLS modules", name));
+    }
+    let offset = align_up(USED.load(Ordering::Relaxed) + memsz, align);
+    let static_size = STATIC_SIZE.load(Ordering::Relaxed);
+    if static_size != 0 {
+        // The thread pointer alignment is fixed once threads exist.
+        if offset > static_size || align > ALIGN.load(Ordering::Relaxed) {
+            return Err(Error::new("no static TLS space left", name));
+        }
+    } else {
+        ALIGN.fetch_max(align, Ordering::Relaxed);
+    }
+    USED.store(offset, Ordering::Relaxed);
+    NEXT_ID.store(id + 1, Ordering::Relaxed);
+    OFFSETS[id].store(offset, Ordering::Release);
+    Ok(TlsModule {
+        id,
+        offset,
+        image,
+        filesz,
+        memsz,
+    })
+}
+
+/// Fix the static TLS size once the startup modules are laid out.
+pub fn finish_startup() {
+    let align = ALIGN.load(Ordering::Relaxed);
+    let size = align_up(USED.load(Ordering::Relaxed) + SURPLUS, align);
+    STATIC_SIZE.store(size, Ordering::Relaxed);
+}
+
+/// Total static TLS below each thread pointer.
+pub fn static_size() -> usize {
+    STATIC_SIZE.load(Ordering::Relaxed)
+}
+
+/// Required alignment of the thread pointer.
+pub fn align() -> usize {
+    ALIGN.load(Ordering::Relaxed)
+}
+
+/// Copy `module`'s initialisation image into the block below `tp`.
+///
+/// # Safety
+///
+/// The static TLS area below `tp` must be writable.
+unsafe fn init_block(tp: usize, module: &TlsModule) {
+    let block = (tp - module.offset) as *mut u8;
+    // SAFETY: The block lies inside the static TLS area, and the image is
+    // mapped for the life of the process.
+    unsafe {
+        core::ptr::copy_nonoverlapping(module.image as *const u8, block, module.filesz);
+        core::ptr::write_bytes(block.add(module.filesz), 0, module.memsz - module.filesz);
+    }
+}
+
+/// Initialise the static TLS area below a new thread pointer.
+///
+/// Published to libc as [`DlApi::tls_init`](hadron_libc_core::dlfcn::DlApi)
+/// and called by `pthread_create` before the thread starts.
+///
+/// # Safety
+///
+/// The `static_size()` bytes below `tp` must be writable.
+pub unsafe extern "C" fn tls_init(tp: *mut u8) {
+    let tp = tp as usize;
+    let size = static_size();
+    // SAFETY: The caller guarantees the area is writable.
+    unsafe { core::ptr::write_bytes((tp - size) as *mut u8, 0, size) };
+    for module in objects().iter().filter_map(|obj| obj.tls.as_ref()) {
+        // SAFETY: As above.
+        unsafe { init_block(tp, module) };
+    }
+}
+
+/// Initialise `module`'s block for the calling thread (after `dlopen`).
+pub fn init_current_thread(module: &TlsModule) {
+    // SAFETY: Every thread's static area covers all allocated modules.
+    unsafe { init_block(thread_pointer(), module) };
+}
+
+/// Read the thread pointer (`%fs:0`).
+pub fn thread_pointer() -> usize {
+    let tp: usize;
+    // SAFETY: FS_BASE points at a TCB whose first word is its own address.
+    unsafe {
+        core::arch::asm!(
+            "mov {}, qword ptr fs:[0]",
+            out(reg) tp,
+            options(nostack, preserves_flags, readonly),
+        );
+    }
+    tp
+}
+
+/// Argument of `__tls_get_addr`, emitted by the static linker.
+#[repr(C)]
+pub struct TlsIndex {
+    module: usize,
+    offset: usize,
+}
+
+/// `__tls_get_addr` — address of a TLS variable for the calling thread.
+///
+/// # Safety
+///
+/// `index` must point to a `TlsIndex` filled in by `DTPMOD64`/`DTPOFF64`
+/// relocations.
+pub unsafe extern "C" fn tls_get_addr(index: *const TlsIndex) -> *mut u8 {
+    // SAFETY: The caller passes a relocated TLS index.
+    let (module, offset) = unsafe { ((*index).module, (*index).offset) };
+    let block_offset = OFFSETS[module].load(Ordering::Acquire);
+    (thread_pointer() - block_offset + offset) as *mut u8
+}
+
+/// Allocate the main thread's TLS area and TCB and point FS_BASE at it.
+///
+/// The TCB is `TCB_RESERVE` zeroed bytes starting with a self-pointer, which
+/// is what libc's pthread layer expects to find.
+pub fn init_main_thread() -> Result<(), Error> {
+    let size = static_size();
+    let area = mem::alloc(size + TCB_RESERVE, align())
+        .ok_or_else(|| Error::new("out of memory for TLS", b""))?;
+    // SAFETY: `area` holds `size + TCB_RESERVE` fresh, zeroed bytes.
+    unsafe {
+        let tp = area.add(size);
+        *(tp as *mut usize) = tp as usize;
+        tls_init(tp);
+        sys::sys_thread_set_tls(tp as usize)
+            .map_err(|_| Error::new("cannot set the thread pointer", b""))
+    }
+}
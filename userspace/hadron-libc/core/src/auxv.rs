@@ -0,0 +1,66 @@
+//! Auxiliary vector access — `getauxval`.
+//!
+//! The kernel places the auxiliary vector directly after the `envp` NULL
+//! terminator on the initial stack. `_start` (or the dynamic linker, which
+//! runs before it) records its address here so that later lookups do not
+//! need the original stack pointer.
+
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::errno::{self, ENOENT};
+
+/// Start of the `(type, value)` pair array, or null before initialisation.
+static AUXV: AtomicPtr<usize> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Record the auxiliary vector that follows `envp`.
+///
+/// # Safety
+///
+/// `envp` must point to the NULL-terminated environment array on the
+/// initial process stack, as laid out by the kernel.
+pub unsafe fn init_auxv(envp: *const *const u8) {
+    let mut p = envp;
+    // SAFETY: The caller guarantees `envp` is NULL-terminated.
+    unsafe {
+        while !(*p).is_null() {
+            p = p.add(1);
+        }
+        AUXV.store(p.add(1) as *mut usize, Ordering::Release);
+    }
+}
+
+/// Return a pointer to the value slot for `ty`, if present.
+pub fn auxv_entry(ty: usize) -> Option<*mut usize> {
+    let mut p = AUXV.load(Ordering::Acquire);
+    if p.is_null() {
+        return None;
+    }
+    // SAFETY: `init_auxv` stored a pointer to an AT_NULL-terminated array.
+    unsafe {
+        loop {
+            let key = *p;
+            if key == hadron_syscall::AT_NULL {
+                return None;
+            }
+            if key == ty {
+                return Some(p.add(1));
+            }
+            p = p.add(2);
+        }
+    }
+}
+
+/// `getauxval` — look up an auxiliary vector entry.
+///
+/// Returns 0 and sets `errno` to `ENOENT` if `ty` is not present.
+#[unsafe(no_mangle)]
+pub extern "C" fn getauxval(ty: u64) -> u64 {
+    match auxv_entry(ty as usize) {
+        // SAFETY: `auxv_entry` returns a pointer into the live auxv array.
+        Some(slot) => unsafe { *slot as u64 },
+        None => {
+            errno::set_errno(ENOENT);
+            0
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! Dynamic loading — `dlopen`, `dlsym`, `dlclose`, `dlerror`.
+//!
+//! libc is always linked statically into the executable, so these functions
+//! cannot call into the dynamic linker by symbol. Instead, `ld-hadron.so`
+//! publishes a [`DlApi`] table through the `AT_HADRON_DL` auxiliary vector
+//! entry before it jumps to the program, and the functions below forward to
+//! it. A statically linked program has no table; every call then fails and
+//! `dlerror` reports why.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::auxv::auxv_entry;
+
+/// `dlopen` flag: resolve PLT entries on first call.
+pub const RTLD_LAZY: i32 = 0x0001;
+/// `dlopen` flag: resolve every relocation before returning.
+pub const RTLD_NOW: i32 = 0x0002;
+/// `dlopen` flag: make the object's symbols available to later loads.
+pub const RTLD_GLOBAL: i32 = 0x0100;
+/// `dlopen` flag: keep the object's symbols out of the global scope.
+pub const RTLD_LOCAL: i32 = 0x0000;
+
+/// Layout version of [`DlApi`]; bumped on any incompatible change.
+pub const DL_API_VERSION: u32 = 1;
+
+/// Bytes the dynamic linker reserves at the main thread's thread pointer.
+///
+/// libpthread keeps its per-thread state in a TCB at the thread pointer, so
+/// the linker-allocated main-thread TCB must be at least this large, zeroed,
+/// and start with a self-pointer.
+pub const TCB_RESERVE: usize = 2048;
+
+/// Entry table shared between libc and the dynamic linker.
+#[repr(C)]
+pub struct DlApi {
+    /// Must equal [`DL_API_VERSION`].
+    pub version: u32,
+    /// Bytes of static TLS that sit directly below every thread pointer.
+    /// Always a multiple of `tls_align`.
+    pub tls_static_size: usize,
+    /// Alignment the thread pointer must satisfy.
+    pub tls_align: usize,
+    /// `dlopen` implementation.
+    pub dlopen: unsafe extern "C" fn(*const u8, i32) -> *mut u8,
+    /// `dlsym` implementation.
+    pub dlsym: unsafe extern "C" fn(*mut u8, *const u8) -> *mut u8,
+    /// `dlclose` implementation.
+    pub dlclose: unsafe extern "C" fn(*mut u8) -> i32,
+    /// `dlerror` implementation.
+    pub dlerror: unsafe extern "C" fn() -> *const u8,
+    /// Initialise the `tls_static_size` bytes below a new thread pointer
+    /// from the loaded modules' TLS images.
+    pub tls_init: unsafe extern "C" fn(*mut u8),
+}
+
+/// Return the dynamic linker's entry table, if the program was started by one.
+pub fn dl_api() -> Option<&'static DlApi> {
+    let slot = auxv_entry(hadron_syscall::AT_HADRON_DL)?;
+    // SAFETY: `auxv_entry` returns a pointer into the live auxv array.
+    let addr = unsafe { *slot };
+    if addr == 0 {
+        return None;
+    }
+    // SAFETY: A non-zero AT_HADRON_DL value is the address of a `DlApi`
+    // that lives for the rest of the process.
+    let api = unsafe { &*(addr as *const DlApi) };
+    (api.version == DL_API_VERSION).then_some(api)
+}
+
+static NO_LINKER_MSG: &[u8] = b"dynamic linking unavailable: program is statically linked\0";
+
+/// Set when a call failed for lack of a dynamic linker; cleared by `dlerror`.
+static NO_LINKER_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// `dlopen` — load a shared object and its dependencies.
+///
+/// # Safety
+///
+/// `filename` must be null or a valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dlopen(filename: *const u8, flags: i32) -> *mut u8 {
+    match dl_api() {
+        // SAFETY: Forwarded with the caller's guarantees.
+        Some(api) => unsafe { (api.dlopen)(filename, flags) },
+        None => {
+            NO_LINKER_PENDING.store(true, Ordering::Relaxed);
+            core::ptr::null_mut()
+        }
+    }
+}
+
+/// `dlsym` — look up a symbol in a handle returned by `dlopen`.
+///
+/// # Safety
+///
+/// `handle` must be `RTLD_DEFAULT` or a live handle; `symbol` must be a
+/// valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dlsym(handle: *mut u8, symbol: *const u8) -> *mut u8 {
+    match dl_api() {
+        // SAFETY: Forwarded with the caller's guarantees.
+        Some(api) => unsafe { (api.dlsym)(handle, symbol) },
+        None => {
+            NO_LINKER_PENDING.store(true, Ordering::Relaxed);
+            core::ptr::null_mut()
+        }
+    }
+}
+
+/// `dlclose` — drop a reference to a handle returned by `dlopen`.
+///
+/// # Safety
+///
+/// `handle` must be a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn dlclose(handle: *mut u8) -> i32 {
+    match dl_api() {
+        // SAFETY: Forwarded with the caller's guarantees.
+        Some(api) => unsafe { (api.dlclose)(handle) },
+        None => {
+            NO_LINKER_PENDING.store(true, Ordering::Relaxed);
+            -1
+        }
+    }
+}
+
+/// `dlerror` — return and clear the last dynamic-linking error, or null.
+#[unsafe(no_mangle)]
+pub extern "C" fn dlerror() -> *const u8 {
+    match dl_api() {
+        // SAFETY: The linker's `dlerror` takes no arguments.
+        Some(api) => unsafe { (api.dlerror)() },
+        None if NO_LINKER_PENDING.swap(false, Ordering::Relaxed) => NO_LINKER_MSG.as_ptr(),
+        None => core::ptr::null(),
+    }
+}
@@ -0,0 +1,90 @@
+//! Scalable I/O readiness notification (`epoll`).
+//!
+//! Linux functions: `epoll_create`, `epoll_create1`, `epoll_ctl`,
+//! `epoll_wait`.
+//!
+//! `struct epoll_event` has the same packed layout as Hadron's
+//! `EpollEvent`: `{events: u32, data: u64}` = 12 bytes. The pointer is
+//! passed directly to the kernel without any translation.
+
+use crate::{errno, sys};
+
+pub use hadron_syscall::EpollEvent;
+
+/// Converts a `Result` into the fd-or-`-1`-and-errno convention.
+fn to_fd(result: Result<usize, errno::Errno>) -> i32 {
+    match result {
+        Ok(fd) => fd as i32,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Create an epoll fd. `size` is ignored but must be positive.
+#[unsafe(no_mangle)]
+pub extern "C" fn epoll_create(size: i32) -> i32 {
+    if size <= 0 {
+        errno::set_errno(errno::EINVAL);
+        return -1;
+    }
+    epoll_create1(0)
+}
+
+/// Create an epoll fd. `flags` may be `EPOLL_CLOEXEC`.
+#[unsafe(no_mangle)]
+pub extern "C" fn epoll_create1(flags: i32) -> i32 {
+    let Some(flags) = crate::flags::posix_fd_flags_to_hadron(flags as u32, 0)
+        .filter(|&f| f & !hadron_syscall::EPOLL_CLOEXEC == 0)
+    else {
+        errno::set_errno(errno::EINVAL);
+        return -1;
+    };
+    to_fd(sys::sys_epoll_create(flags))
+}
+
+/// Add, change or remove the interest of epoll fd `epfd` in `fd`.
+///
+/// # Safety
+///
+/// `event` must be a valid pointer for `EPOLL_CTL_ADD` and `EPOLL_CTL_MOD`;
+/// it is ignored for `EPOLL_CTL_DEL`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn epoll_ctl(epfd: i32, op: i32, fd: i32, event: *const EpollEvent) -> i32 {
+    match sys::sys_epoll_ctl(epfd as usize, op as usize, fd as usize, event) {
+        Ok(()) => 0,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Wait up to `timeout` milliseconds (negative = forever) for ready fds in
+/// the interest list of `epfd`, writing up to `maxevents` events.
+///
+/// Returns the number of events, 0 on timeout, or -1 on error (with errno
+/// set).
+///
+/// # Safety
+///
+/// `events` must point to an array of `maxevents` `struct epoll_event`s.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn epoll_wait(
+    epfd: i32,
+    events: *mut EpollEvent,
+    maxevents: i32,
+    timeout: i32,
+) -> i32 {
+    let Ok(maxevents) = usize::try_from(maxevents) else {
+        errno::set_errno(errno::EINVAL);
+        return -1;
+    };
+    to_fd(sys::sys_epoll_wait(
+        epfd as usize,
+        events,
+        maxevents,
+        timeout as isize,
+    ))
+}
@@ -68,7 +68,9 @@ pub const EAFNOSUPPORT: Errno = Errno(97);
 pub const EADDRINUSE: Errno = Errno(98);
 pub const ENOTCONN: Errno = Errno(107);
 pub const EISCONN: Errno = Errno(106);
+pub const ETIMEDOUT: Errno = Errno(110);
 pub const ECONNREFUSED: Errno = Errno(111);
+pub const ECANCELED: Errno = Errno(125);
 
 /// C ABI: `int *__errno_location(void)` — returns pointer to errno storage.
 ///
@@ -66,11 +66,68 @@ pub fn posix_open_to_hadron(flags: u32) -> usize {
     out
 }
 
+// ---- eventfd, timerfd, signalfd and epoll flags -----------------------------
+
+pub const EFD_SEMAPHORE: u32 = 1;
+pub const EFD_CLOEXEC: u32 = O_CLOEXEC;
+pub const EFD_NONBLOCK: u32 = O_NONBLOCK;
+pub const TFD_CLOEXEC: u32 = O_CLOEXEC;
+pub const TFD_NONBLOCK: u32 = O_NONBLOCK;
+pub const TFD_TIMER_ABSTIME: u32 = 1;
+pub const SFD_CLOEXEC: u32 = O_CLOEXEC;
+pub const SFD_NONBLOCK: u32 = O_NONBLOCK;
+
+pub const EPOLL_CLOEXEC: u32 = O_CLOEXEC;
+pub const EPOLL_CTL_ADD: i32 = 1;
+pub const EPOLL_CTL_DEL: i32 = 2;
+pub const EPOLL_CTL_MOD: i32 = 3;
+pub const EPOLLIN: u32 = 0x0001;
+pub const EPOLLOUT: u32 = 0x0004;
+pub const EPOLLERR: u32 = 0x0008;
+pub const EPOLLHUP: u32 = 0x0010;
+pub const EPOLLONESHOT: u32 = 1 << 30;
+pub const EPOLLET: u32 = 1 << 31;
+
+// ---- I/O ring flags ----------------------------------------------------------
+
+pub const IO_RING_CLOEXEC: u32 = O_CLOEXEC;
+pub const IO_OP_NOP: u8 = 0;
+pub const IO_OP_READ: u8 = 1;
+pub const IO_OP_WRITE: u8 = 2;
+pub const IO_OP_PREAD: u8 = 3;
+pub const IO_OP_PWRITE: u8 = 4;
+pub const IO_OP_ACCEPT: u8 = 5;
+pub const IO_OP_CONNECT: u8 = 6;
+pub const IO_OP_POLL: u8 = 7;
+pub const IO_OP_TIMEOUT: u8 = 8;
+pub const IO_OP_FSYNC: u8 = 9;
+pub const IO_OP_LINK: u8 = 1 << 0;
+
+/// Translate the flags of `eventfd`, `timerfd_create` or `signalfd` to
+/// Hadron's, passing the bits in `extra` (such as `EFD_SEMAPHORE`, which has
+/// the same value in both) through unchanged.
+///
+/// Returns `None` if any other bit is set.
+pub fn posix_fd_flags_to_hadron(flags: u32, extra: u32) -> Option<usize> {
+    if flags & !(O_CLOEXEC | O_NONBLOCK | extra) != 0 {
+        return None;
+    }
+    let mut out = (flags & extra) as usize;
+    if flags & O_CLOEXEC != 0 {
+        out |= HADRON_OPEN_CLOEXEC;
+    }
+    if flags & O_NONBLOCK != 0 {
+        out |= HADRON_OPEN_NONBLOCK;
+    }
+    Some(out)
+}
+
 // ---- POSIX mmap flags --------------------------------------------------------
 
 pub const MAP_SHARED: u32 = 0x01;
 pub const MAP_PRIVATE: u32 = 0x02;
 pub const MAP_ANONYMOUS: u32 = 0x20;
+pub const MAP_HUGETLB: u32 = 0x40000;
 pub const MAP_FAILED: *mut u8 = usize::MAX as *mut u8;
 
 pub const PROT_NONE: u32 = 0x0;
@@ -81,6 +138,7 @@ pub const PROT_EXEC: u32 = 0x4;
 // Hadron mmap flags
 const HADRON_MAP_ANONYMOUS: usize = 0x1;
 const HADRON_MAP_SHARED: usize = 0x2;
+const HADRON_MAP_HUGE: usize = 0x4;
 
 /// Translate POSIX mmap flags to Hadron internal flags.
 pub fn posix_mmap_to_hadron(flags: u32) -> usize {
@@ -91,6 +149,9 @@ pub fn posix_mmap_to_hadron(flags: u32) -> usize {
     if flags & MAP_SHARED != 0 {
         out |= HADRON_MAP_SHARED;
     }
+    if flags & MAP_HUGETLB != 0 {
+        out |= HADRON_MAP_HUGE;
+    }
     out
 }
 
@@ -121,10 +182,14 @@ pub const SIGHUP: i32 = 1;
 pub const SIGINT: i32 = 2;
 pub const SIGQUIT: i32 = 3;
 pub const SIGILL: i32 = 4;
+pub const SIGTRAP: i32 = 5;
 pub const SIGABRT: i32 = 6;
+pub const SIGBUS: i32 = 7;
 pub const SIGFPE: i32 = 8;
 pub const SIGKILL: i32 = 9;
+pub const SIGUSR1: i32 = 10;
 pub const SIGSEGV: i32 = 11;
+pub const SIGUSR2: i32 = 12;
 pub const SIGPIPE: i32 = 13;
 pub const SIGALRM: i32 = 14;
 pub const SIGTERM: i32 = 15;
@@ -134,13 +199,41 @@ pub const SIGSTOP: i32 = 19;
 pub const SIGTSTP: i32 = 20;
 pub const SIGTTIN: i32 = 21;
 pub const SIGTTOU: i32 = 22;
-pub const SIGUSR1: i32 = 10;
-pub const SIGUSR2: i32 = 12;
+pub const SIGURG: i32 = 23;
+pub const SIGXCPU: i32 = 24;
+pub const SIGXFSZ: i32 = 25;
+pub const SIGVTALRM: i32 = 26;
+pub const SIGPROF: i32 = 27;
+pub const SIGWINCH: i32 = 28;
+pub const SIGIO: i32 = 29;
+pub const SIGPWR: i32 = 30;
+pub const SIGSYS: i32 = 31;
+pub const SIGRTMIN: i32 = 34;
+pub const SIGRTMAX: i32 = 64;
 
 pub const SIG_DFL: usize = 0;
 pub const SIG_IGN: usize = 1;
-pub const SA_RESTART: usize = 0x1000_0000;
-pub const SA_RESETHAND: usize = 0x8000_0000;
+pub const SA_NOCLDSTOP: u32 = 0x0000_0001;
+pub const SA_NOCLDWAIT: u32 = 0x0000_0002;
+pub const SA_SIGINFO: u32 = 0x0000_0004;
+pub const SA_ONSTACK: u32 = 0x0800_0000;
+pub const SA_RESTART: u32 = 0x1000_0000;
+pub const SA_NODEFER: u32 = 0x4000_0000;
+pub const SA_RESETHAND: u32 = 0x8000_0000;
+
+pub const SS_ONSTACK: i32 = 1;
+pub const SS_DISABLE: i32 = 2;
+pub const MINSIGSTKSZ: usize = 2048;
+pub const SIGSTKSZ: usize = 8192;
+
+pub const SI_USER: i32 = 0;
+pub const SI_QUEUE: i32 = -1;
+pub const SI_TIMER: i32 = -2;
+pub const SI_KERNEL: i32 = 0x80;
+
+pub const SIGEV_SIGNAL: i32 = 0;
+pub const SIGEV_NONE: i32 = 1;
+pub const SIGEV_THREAD_ID: i32 = 4;
 
 // ---- Signal mask operations --------------------------------------------------
 
@@ -152,6 +245,21 @@ pub const SIG_SETMASK: i32 = 2;
 
 pub const WNOHANG: i32 = 1;
 pub const WUNTRACED: i32 = 2;
+pub const WSTOPPED: i32 = 2;
+pub const WEXITED: i32 = 4;
+pub const WCONTINUED: i32 = 8;
+pub const WNOWAIT: i32 = 0x0100_0000;
+
+// ---- waitid id types ---------------------------------------------------------
+
+pub const P_ALL: i32 = 0;
+pub const P_PID: i32 = 1;
+pub const P_PGID: i32 = 2;
+
+// ---- prctl options -----------------------------------------------------------
+
+pub const PR_SET_CHILD_SUBREAPER: i32 = 36;
+pub const PR_GET_CHILD_SUBREAPER: i32 = 37;
 
 // ---- Clock IDs ---------------------------------------------------------------
 
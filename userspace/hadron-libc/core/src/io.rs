@@ -1,7 +1,8 @@
 //! Low-level I/O functions (POSIX file descriptor layer).
 //!
 //! POSIX functions: `open`, `close`, `read`, `write`, `lseek`,
-//! `dup`, `dup2`, `pipe`, `pipe2`, `fcntl`, `ioctl`, `stat`, `fstat`, `isatty`.
+//! `dup`, `dup2`, `pipe`, `pipe2`, `fcntl`, `ioctl`, `stat`, `fstat`, `isatty`,
+//! `eventfd`, `eventfd_read`, `eventfd_write`.
 
 use crate::errno;
 use crate::sys;
@@ -178,6 +179,57 @@ pub unsafe extern "C" fn pipe2(fds: *mut i32, flags: i32) -> i32 {
     }
 }
 
+/// Create an eventfd whose counter starts at `initval`.
+#[unsafe(no_mangle)]
+pub extern "C" fn eventfd(initval: u32, flags: i32) -> i32 {
+    let Some(flags) =
+        crate::flags::posix_fd_flags_to_hadron(flags as u32, crate::flags::EFD_SEMAPHORE)
+    else {
+        errno::set_errno(crate::errno::EINVAL);
+        return -1;
+    };
+    match sys::sys_eventfd(initval as usize, flags) {
+        Ok(fd) => fd as i32,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Read the counter of eventfd `fd` into `value`.
+///
+/// # Safety
+///
+/// `value` must be a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn eventfd_read(fd: i32, value: *mut u64) -> i32 {
+    let mut buf = [0u8; 8];
+    match sys::sys_read(fd as usize, &mut buf) {
+        Ok(_) => {
+            // SAFETY: Caller guarantees value is valid.
+            unsafe { *value = u64::from_ne_bytes(buf) };
+            0
+        }
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Add `value` to the counter of eventfd `fd`.
+#[unsafe(no_mangle)]
+pub extern "C" fn eventfd_write(fd: i32, value: u64) -> i32 {
+    match sys::sys_write(fd as usize, &value.to_ne_bytes()) {
+        Ok(_) => 0,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
 /// File control operations.
 #[unsafe(no_mangle)]
 pub extern "C" fn fcntl(fd: i32, cmd: i32, arg: usize) -> i32 {
@@ -297,7 +349,9 @@ pub unsafe extern "C" fn mkdir(path: *const u8, mode: u32) -> i32 {
     // SAFETY: path is NUL-terminated.
     let len = unsafe { crate::string::strlen(path) };
     let slice = unsafe { core::slice::from_raw_parts(path, len) };
-    match sys::sys_mkdir(slice, mode as usize) {
+    // The kernel takes owner permissions as bit 0 = r, 1 = w, 2 = x.
+    let perms = (mode >> 8) & 1 | (mode >> 6) & 2 | (mode >> 4) & 4;
+    match sys::sys_mkdir(slice, perms as usize) {
         Ok(()) => 0,
         Err(e) => {
             errno::set_errno(e);
@@ -0,0 +1,204 @@
+//! Shared-memory I/O submission and completion rings.
+//!
+//! Hadron functions: `io_ring_create`, `io_ring_enter`.
+//!
+//! `struct io_ring_params`, `struct io_op` and `struct io_completion` have
+//! the same layout as Hadron's `IoRingParams`, `IoOp` and `IoCompletion`,
+//! so pointers are passed to the kernel without translation. C callers map
+//! the ring themselves; Rust callers can use [`IoRing`], which maps it and
+//! drives both queues.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use crate::{errno, sys};
+
+pub use hadron_syscall::{IoCompletion, IoOp, IoRingHeader, IoRingParams};
+
+/// Create an I/O ring with at least `entries` submission slots, writing its
+/// layout to `params`. `flags` may be `IO_RING_CLOEXEC`.
+///
+/// Returns the ring fd, or -1 on error (with errno set).
+///
+/// # Safety
+///
+/// `params` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn io_ring_create(
+    entries: u32,
+    params: *mut IoRingParams,
+    flags: i32,
+) -> i32 {
+    let Some(flags) = crate::flags::posix_fd_flags_to_hadron(flags as u32, 0)
+        .filter(|&f| f & !hadron_syscall::IO_RING_CLOEXEC == 0)
+    else {
+        errno::set_errno(errno::EINVAL);
+        return -1;
+    };
+    match sys::sys_io_ring_create(entries as usize, params, flags) {
+        Ok(fd) => fd as i32,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Submit up to `to_submit` queued operations on ring `fd`, then wait up to
+/// `timeout` milliseconds (negative = forever) until `min_complete`
+/// completions are queued.
+///
+/// Returns the number of operations submitted, or -1 on error (with errno
+/// set).
+#[unsafe(no_mangle)]
+pub extern "C" fn io_ring_enter(fd: i32, to_submit: u32, min_complete: u32, timeout: i32) -> i32 {
+    match sys::sys_io_ring_enter(
+        fd as usize,
+        to_submit as usize,
+        min_complete as usize,
+        timeout as isize,
+    ) {
+        Ok(n) => n as i32,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// A mapped I/O ring.
+///
+/// Dropping it unmaps the ring and closes its fd, which cancels any
+/// operations still running.
+pub struct IoRing {
+    fd: usize,
+    base: *mut u8,
+    params: IoRingParams,
+    /// Operations queued with [`push`](Self::push) and not yet submitted.
+    pending: u32,
+}
+
+impl IoRing {
+    /// Create and map a ring with at least `entries` submission slots.
+    pub fn new(entries: u32) -> Result<Self, errno::Errno> {
+        let mut params = IoRingParams::default();
+        let fd = sys::sys_io_ring_create(entries as usize, &raw mut params, 0)?;
+        match sys::sys_mem_map_shared(fd, params.size as usize) {
+            Ok(base) => Ok(Self {
+                fd,
+                base,
+                params,
+                pending: 0,
+            }),
+            Err(e) => {
+                let _ = sys::sys_close(fd);
+                Err(e)
+            }
+        }
+    }
+
+    /// The ring fd.
+    #[must_use]
+    pub fn fd(&self) -> usize {
+        self.fd
+    }
+
+    /// The ring's layout.
+    #[must_use]
+    pub fn params(&self) -> &IoRingParams {
+        &self.params
+    }
+
+    /// Returns the header index at byte offset `offset`.
+    fn index(&self, offset: usize) -> &AtomicU32 {
+        // SAFETY: The header is mapped for the life of `self`, the indices
+        // are naturally aligned, and the kernel only accesses them
+        // atomically.
+        unsafe { AtomicU32::from_ptr(self.base.add(offset).cast()) }
+    }
+
+    fn sq_head(&self) -> &AtomicU32 {
+        self.index(core::mem::offset_of!(IoRingHeader, sq_head))
+    }
+
+    fn sq_tail(&self) -> &AtomicU32 {
+        self.index(core::mem::offset_of!(IoRingHeader, sq_tail))
+    }
+
+    fn cq_head(&self) -> &AtomicU32 {
+        self.index(core::mem::offset_of!(IoRingHeader, cq_head))
+    }
+
+    fn cq_tail(&self) -> &AtomicU32 {
+        self.index(core::mem::offset_of!(IoRingHeader, cq_tail))
+    }
+
+    /// Queue `op` for the next [`submit`](Self::submit).
+    ///
+    /// Returns `false` if the submission queue is full.
+    pub fn push(&mut self, op: IoOp) -> bool {
+        let head = self.sq_head().load(Ordering::Acquire);
+        let tail = self.sq_tail().load(Ordering::Relaxed);
+        if tail.wrapping_sub(head) >= self.params.sq_entries {
+            return false;
+        }
+        let slot = (tail & (self.params.sq_entries - 1)) as usize;
+        // SAFETY: `slot` is within the mapped submission queue, and the
+        // kernel does not read it until the tail is published.
+        unsafe {
+            self.base
+                .add(self.params.ops_offset as usize)
+                .cast::<IoOp>()
+                .add(slot)
+                .write(op);
+        }
+        self.sq_tail()
+            .store(tail.wrapping_add(1), Ordering::Release);
+        self.pending += 1;
+        true
+    }
+
+    /// Submit the queued operations, then wait up to `timeout_ms`
+    /// milliseconds (negative = forever) until `min_complete` completions
+    /// are queued.
+    ///
+    /// Returns the number of operations submitted.
+    pub fn submit(&mut self, min_complete: u32, timeout_ms: i32) -> Result<usize, errno::Errno> {
+        let submitted = sys::sys_io_ring_enter(
+            self.fd,
+            self.pending as usize,
+            min_complete as usize,
+            timeout_ms as isize,
+        )?;
+        self.pending -= submitted as u32;
+        Ok(submitted)
+    }
+
+    /// Take the next completion, if any.
+    pub fn pop(&mut self) -> Option<IoCompletion> {
+        let head = self.cq_head().load(Ordering::Relaxed);
+        let tail = self.cq_tail().load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        let slot = (head & (self.params.cq_entries - 1)) as usize;
+        // SAFETY: `slot` is within the mapped completion queue, and the
+        // kernel published it with the tail.
+        let completion = unsafe {
+            self.base
+                .add(self.params.completions_offset as usize)
+                .cast::<IoCompletion>()
+                .add(slot)
+                .read()
+        };
+        self.cq_head()
+            .store(head.wrapping_add(1), Ordering::Release);
+        Some(completion)
+    }
+}
+
+impl Drop for IoRing {
+    fn drop(&mut self) {
+        let _ = sys::sys_munmap(self.base, self.params.size as usize);
+        let _ = sys::sys_close(self.fd);
+    }
+}
@@ -11,18 +11,26 @@
 #[cfg(feature = "userspace")]
 pub mod alloc;
 pub mod atexit;
+#[cfg(feature = "userspace")]
+pub mod auxv;
 pub mod conv;
 pub mod ctype;
 #[cfg(feature = "userspace")]
 pub mod dirent;
 #[cfg(feature = "userspace")]
+pub mod dlfcn;
+#[cfg(feature = "userspace")]
 pub mod env;
+#[cfg(feature = "userspace")]
+pub mod epoll;
 pub mod errno;
 #[cfg(feature = "userspace")]
 pub mod fenv;
 pub mod flags;
 #[cfg(feature = "userspace")]
 pub mod io;
+#[cfg(feature = "userspace")]
+pub mod io_ring;
 pub mod locale;
 #[cfg(feature = "userspace")]
 pub mod mman;
@@ -32,6 +40,7 @@ pub mod poll;
 pub mod process;
 #[cfg(feature = "userspace")]
 pub mod pthread;
+pub mod pwd;
 pub mod search;
 #[cfg(feature = "userspace")]
 pub mod signal;
@@ -23,7 +23,8 @@ use crate::{errno, sys};
 pub unsafe extern "C" fn poll(fds: *mut u8, nfds: usize, timeout: i32) -> i32 {
     // Map POSIX timeout semantics to Hadron:
     // POSIX: <0 = infinite, 0 = non-blocking, >0 = ms
-    // Hadron: usize::MAX = infinite, 0 = non-blocking, n = ms
+    // Hadron: usize::MAX = infinite, 0 = non-blocking, n = ns (converted
+    // by sys_poll)
     let timeout_ms: isize = if timeout < 0 { -1 } else { timeout as isize };
     match sys::sys_poll(fds, nfds, timeout_ms) {
         Ok(n) => n as i32,
@@ -1,7 +1,16 @@
 //! Process management functions.
 //!
-//! POSIX functions: `_exit`, `exit`, `getpid`, `getppid`, `waitpid`,
-//! `execve`, `kill`, `getcwd`, `chdir`.
+//! POSIX functions: `_exit`, `exit`, `getpid`, `getppid`, `wait`, `waitpid`,
+//! `wait4`, `waitid`, `prctl`,
+//! `execve`, `kill`, `getcwd`, `chdir`, `getuid`, `geteuid`, `getgid`,
+//! `getegid`, `setuid`, `setgid`, `seteuid`, `setegid`, `setresuid`,
+//! `setresgid`, `getgroups`, `setgroups`, `getpriority`, `setpriority`,
+//! `nice`, `sched_getaffinity`, `sched_setaffinity`, `sched_setscheduler`,
+//! `sched_getscheduler`, `sched_setparam`, `sched_getparam`,
+//! `sched_get_priority_max`, `sched_get_priority_min`,
+//! `sched_rr_get_interval`, `sched_setattr`, `sched_getattr`.
+
+pub use hadron_syscall::{SCHED_DEADLINE, SCHED_FIFO, SCHED_OTHER, SCHED_RR, SchedAttr};
 
 use crate::errno;
 use crate::sys;
@@ -44,6 +53,17 @@ pub extern "C" fn getppid() -> i32 {
     sys::sys_getppid() as i32
 }
 
+/// Wait for any child process to terminate.
+///
+/// # Safety
+///
+/// `status` must be null or point to a valid `i32`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wait(status: *mut i32) -> i32 {
+    // SAFETY: Caller guarantees status is null or valid.
+    unsafe { wait4(-1, status, 0, core::ptr::null_mut()) }
+}
+
 /// Wait for a child process to change state.
 ///
 /// # Safety
@@ -51,20 +71,58 @@ pub extern "C" fn getppid() -> i32 {
 /// `status` must be null or point to a valid `i32`.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn waitpid(pid: i32, status: *mut i32, options: i32) -> i32 {
-    // Hadron waitpid uses a u64 status buffer.
+    // SAFETY: Caller guarantees status is null or valid.
+    unsafe { wait4(pid, status, options, core::ptr::null_mut()) }
+}
+
+/// Wait for a child process to change state and return its resource usage.
+///
+/// `pid` selects the child as for `waitpid`: -1 for any child, 0 for the
+/// caller's process group, and `-pgid` for process group `pgid`.
+///
+/// # Safety
+///
+/// `status` must be null or point to a valid `i32`; `rusage` must be null
+/// or point to a valid [`Rusage`](crate::time::Rusage).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wait4(
+    pid: i32,
+    status: *mut i32,
+    options: i32,
+    rusage: *mut crate::time::Rusage,
+) -> i32 {
+    // Hadron wait4 uses a u64 status buffer.
     let mut raw_status: u64 = 0;
     let status_ptr = if status.is_null() {
         core::ptr::null_mut()
     } else {
         &raw mut raw_status
     };
+    let mut info = hadron_syscall::RusageInfo {
+        user_ns: 0,
+        system_ns: 0,
+    };
+    let rusage_ptr = if rusage.is_null() {
+        core::ptr::null_mut()
+    } else {
+        &raw mut info
+    };
 
-    match sys::sys_waitpid(pid as usize, status_ptr, options as usize) {
+    match sys::sys_wait4(pid as usize, status_ptr, options as usize, rusage_ptr) {
         Ok(ret) => {
             if !status.is_null() {
                 // SAFETY: Caller guarantees status is valid.
                 unsafe { *status = raw_status as i32 };
             }
+            if !rusage.is_null() {
+                let ru = crate::time::Rusage {
+                    ru_utime: crate::time::Timeval::from_nanos(info.user_ns),
+                    ru_stime: crate::time::Timeval::from_nanos(info.system_ns),
+                    ..crate::time::Rusage::default()
+                };
+                // SAFETY: Caller guarantees rusage is valid.
+                unsafe { rusage.write(ru) };
+            }
             ret as i32
         }
         Err(e) => {
@@ -74,6 +132,70 @@ pub unsafe extern "C" fn waitpid(pid: i32, status: *mut i32, options: i32) -> i3
     }
 }
 
+/// Wait for a child process to change state and describe it in `info`.
+///
+/// `idtype` is `P_ALL`, `P_PID` or `P_PGID`; `options` must include at
+/// least one of `WEXITED`, `WSTOPPED` or `WCONTINUED`.
+///
+/// # Safety
+///
+/// `info` must be null or point to a valid `siginfo_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn waitid(
+    idtype: i32,
+    id: u32,
+    info: *mut crate::signal::SigInfo,
+    options: i32,
+) -> i32 {
+    match sys::sys_waitid(
+        idtype as usize,
+        id as usize,
+        info,
+        options as usize,
+        core::ptr::null_mut(),
+    ) {
+        Ok(()) => 0,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Process control operations.
+///
+/// Only `PR_SET_CHILD_SUBREAPER` and `PR_GET_CHILD_SUBREAPER` are
+/// supported; for the latter, `arg2` points to an `int`.
+///
+/// # Safety
+///
+/// For `PR_GET_CHILD_SUBREAPER`, `arg2` must point to a valid `i32`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn prctl(option: i32, arg2: usize) -> i32 {
+    let result = match option {
+        crate::flags::PR_SET_CHILD_SUBREAPER => sys::sys_child_set_subreaper(arg2 != 0),
+        crate::flags::PR_GET_CHILD_SUBREAPER => {
+            let out = arg2 as *mut i32;
+            if out.is_null() {
+                Err(errno::EFAULT)
+            } else {
+                sys::sys_child_get_subreaper().map(|enabled| {
+                    // SAFETY: Caller guarantees arg2 points to a valid i32.
+                    unsafe { *out = enabled as i32 };
+                })
+            }
+        }
+        _ => Err(errno::EINVAL),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
 /// Send a signal to a process.
 #[unsafe(no_mangle)]
 pub extern "C" fn kill(pid: i32, sig: i32) -> i32 {
@@ -164,3 +286,351 @@ pub extern "C" fn setsid() -> i32 {
         }
     }
 }
+
+/// Get the real user ID.
+#[unsafe(no_mangle)]
+pub extern "C" fn getuid() -> u32 {
+    sys::sys_cred_get().map_or(u32::MAX, |c| c.uid)
+}
+
+/// Get the effective user ID.
+#[unsafe(no_mangle)]
+pub extern "C" fn geteuid() -> u32 {
+    sys::sys_cred_get().map_or(u32::MAX, |c| c.euid)
+}
+
+/// Get the real group ID.
+#[unsafe(no_mangle)]
+pub extern "C" fn getgid() -> u32 {
+    sys::sys_cred_get().map_or(u32::MAX, |c| c.gid)
+}
+
+/// Get the effective group ID.
+#[unsafe(no_mangle)]
+pub extern "C" fn getegid() -> u32 {
+    sys::sys_cred_get().map_or(u32::MAX, |c| c.egid)
+}
+
+/// Converts a unit syscall result into the C `0`/`-1` convention.
+fn unit_result(r: Result<(), errno::Errno>) -> i32 {
+    match r {
+        Ok(()) => 0,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Set the user ID.
+#[unsafe(no_mangle)]
+pub extern "C" fn setuid(uid: u32) -> i32 {
+    unit_result(sys::sys_setuid(uid))
+}
+
+/// Set the group ID.
+#[unsafe(no_mangle)]
+pub extern "C" fn setgid(gid: u32) -> i32 {
+    unit_result(sys::sys_setgid(gid))
+}
+
+/// Set the effective user ID.
+#[unsafe(no_mangle)]
+pub extern "C" fn seteuid(euid: u32) -> i32 {
+    unit_result(sys::sys_setresuid(u32::MAX, euid, u32::MAX))
+}
+
+/// Set the effective group ID.
+#[unsafe(no_mangle)]
+pub extern "C" fn setegid(egid: u32) -> i32 {
+    unit_result(sys::sys_setresgid(u32::MAX, egid, u32::MAX))
+}
+
+/// Set the real, effective and saved user IDs; `-1` leaves one unchanged.
+#[unsafe(no_mangle)]
+pub extern "C" fn setresuid(uid: u32, euid: u32, suid: u32) -> i32 {
+    unit_result(sys::sys_setresuid(uid, euid, suid))
+}
+
+/// Set the real, effective and saved group IDs; `-1` leaves one unchanged.
+#[unsafe(no_mangle)]
+pub extern "C" fn setresgid(gid: u32, egid: u32, sgid: u32) -> i32 {
+    unit_result(sys::sys_setresgid(gid, egid, sgid))
+}
+
+/// Get the supplementary group IDs.
+///
+/// # Safety
+///
+/// `list` must be valid for `size` writes when `size` is nonzero.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn getgroups(size: i32, list: *mut u32) -> i32 {
+    let Ok(size) = usize::try_from(size) else {
+        errno::set_errno(errno::EINVAL);
+        return -1;
+    };
+    match sys::sys_getgroups(list, size) {
+        Ok(n) => n as i32,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Set the supplementary group IDs.
+///
+/// # Safety
+///
+/// `list` must be valid for `size` reads.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn setgroups(size: usize, list: *const u32) -> i32 {
+    unit_result(sys::sys_setgroups(list, size))
+}
+
+/// Get the nice value of a process, process group or user.
+///
+/// Returns -1 with errno set on error; since -1 is also a valid nice value,
+/// callers must clear errno first to tell the two apart.
+#[unsafe(no_mangle)]
+pub extern "C" fn getpriority(which: i32, who: u32) -> i32 {
+    match sys::sys_getpriority(which as usize, who as usize) {
+        // The kernel returns 20 - nice to keep successful results positive.
+        Ok(prio) => 20 - prio as i32,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Set the nice value of a process, process group or user.
+#[unsafe(no_mangle)]
+pub extern "C" fn setpriority(which: i32, who: u32, prio: i32) -> i32 {
+    unit_result(sys::sys_setpriority(which as usize, who as usize, prio))
+}
+
+/// Add `inc` to the calling process's nice value and return the new value.
+#[unsafe(no_mangle)]
+pub extern "C" fn nice(inc: i32) -> i32 {
+    const PRIO_PROCESS: usize = hadron_syscall::PRIO_PROCESS;
+    let current = match sys::sys_getpriority(PRIO_PROCESS, 0) {
+        Ok(prio) => 20 - prio as i32,
+        Err(e) => {
+            errno::set_errno(e);
+            return -1;
+        }
+    };
+    let new = current.saturating_add(inc).clamp(-20, 19);
+    match sys::sys_setpriority(PRIO_PROCESS, 0, new) {
+        Ok(()) => new,
+        Err(e) => {
+            // POSIX reports a refused decrease as EPERM.
+            errno::set_errno(if e == errno::EACCES { errno::EPERM } else { e });
+            -1
+        }
+    }
+}
+
+/// Get the CPU affinity mask of a process (0 means the caller).
+///
+/// Bytes of `mask` beyond the kernel's 64-CPU mask are cleared.
+///
+/// # Safety
+///
+/// `mask` must be valid for `cpusetsize` writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sched_getaffinity(pid: i32, cpusetsize: usize, mask: *mut u8) -> i32 {
+    match sys::sys_sched_getaffinity(pid as usize, cpusetsize, mask) {
+        Ok(written) => {
+            // SAFETY: Caller guarantees mask is valid for cpusetsize bytes,
+            // and the kernel wrote at most that many.
+            unsafe { crate::string::memset(mask.add(written), 0, cpusetsize - written) };
+            0
+        }
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Restrict a process (0 means the caller) to the CPUs set in `mask`.
+///
+/// # Safety
+///
+/// `mask` must be valid for `cpusetsize` reads.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sched_setaffinity(pid: i32, cpusetsize: usize, mask: *const u8) -> i32 {
+    unit_result(sys::sys_sched_setaffinity(pid as usize, cpusetsize, mask))
+}
+
+/// `struct sched_param` from `<sched.h>`.
+#[repr(C)]
+pub struct SchedParam {
+    pub sched_priority: i32,
+}
+
+/// Round-robin time slice, matching the kernel's.
+const RR_INTERVAL_NS: i64 = 100_000_000;
+
+/// Reads the scheduling attributes of `pid` (0 means the caller).
+fn get_attr(pid: i32) -> Result<SchedAttr, errno::Errno> {
+    let mut attr = SchedAttr::default();
+    sys::sys_sched_getattr(pid as usize, &mut attr, core::mem::size_of::<SchedAttr>())?;
+    Ok(attr)
+}
+
+/// Set the scheduling policy and parameters of a process (0 means the
+/// caller).
+///
+/// # Safety
+///
+/// `attr` must be valid for reads of a `struct sched_attr`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sched_setattr(pid: i32, attr: *const SchedAttr, flags: u32) -> i32 {
+    unit_result(sys::sys_sched_setattr(pid as usize, attr, flags as usize))
+}
+
+/// Get the scheduling policy and parameters of a process (0 means the
+/// caller).
+///
+/// # Safety
+///
+/// `attr` must be valid for `size` writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sched_getattr(
+    pid: i32,
+    attr: *mut SchedAttr,
+    size: u32,
+    flags: u32,
+) -> i32 {
+    if flags != 0 {
+        errno::set_errno(errno::EINVAL);
+        return -1;
+    }
+    unit_result(sys::sys_sched_getattr(pid as usize, attr, size as usize))
+}
+
+/// Set the scheduling policy of a process (0 means the caller) to
+/// `SCHED_OTHER`, `SCHED_FIFO` or `SCHED_RR`.
+///
+/// # Safety
+///
+/// `param` must be valid for reads of a `struct sched_param`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sched_setscheduler(
+    pid: i32,
+    policy: i32,
+    param: *const SchedParam,
+) -> i32 {
+    let Ok(policy @ (SCHED_OTHER | SCHED_FIFO | SCHED_RR)) = u32::try_from(policy) else {
+        errno::set_errno(errno::EINVAL);
+        return -1;
+    };
+    // SAFETY: Caller guarantees param is valid.
+    let priority = unsafe { (*param).sched_priority };
+    let mut attr = match get_attr(pid) {
+        Ok(attr) => attr,
+        Err(e) => {
+            errno::set_errno(e);
+            return -1;
+        }
+    };
+    attr.policy = policy;
+    attr.priority = priority as u32;
+    unit_result(sys::sys_sched_setattr(pid as usize, &attr, 0))
+}
+
+/// Get the scheduling policy of a process (0 means the caller).
+#[unsafe(no_mangle)]
+pub extern "C" fn sched_getscheduler(pid: i32) -> i32 {
+    match get_attr(pid) {
+        Ok(attr) => attr.policy as i32,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Set the real-time priority of a process (0 means the caller), keeping
+/// its policy.
+///
+/// # Safety
+///
+/// `param` must be valid for reads of a `struct sched_param`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sched_setparam(pid: i32, param: *const SchedParam) -> i32 {
+    let policy = sched_getscheduler(pid);
+    if policy < 0 {
+        return -1;
+    }
+    // SAFETY: Caller guarantees param is valid.
+    unsafe { sched_setscheduler(pid, policy, param) }
+}
+
+/// Get the real-time priority of a process (0 means the caller).
+///
+/// # Safety
+///
+/// `param` must be valid for writes of a `struct sched_param`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sched_getparam(pid: i32, param: *mut SchedParam) -> i32 {
+    match get_attr(pid) {
+        Ok(attr) => {
+            // SAFETY: Caller guarantees param is valid.
+            unsafe { (*param).sched_priority = attr.priority as i32 };
+            0
+        }
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Return the highest priority of `policy`.
+#[unsafe(no_mangle)]
+pub extern "C" fn sched_get_priority_max(policy: i32) -> i32 {
+    match u32::try_from(policy) {
+        Ok(SCHED_FIFO | SCHED_RR) => 99,
+        Ok(SCHED_OTHER | SCHED_DEADLINE) => 0,
+        _ => {
+            errno::set_errno(errno::EINVAL);
+            -1
+        }
+    }
+}
+
+/// Return the lowest priority of `policy`.
+#[unsafe(no_mangle)]
+pub extern "C" fn sched_get_priority_min(policy: i32) -> i32 {
+    match u32::try_from(policy) {
+        Ok(SCHED_FIFO | SCHED_RR) => 1,
+        Ok(SCHED_OTHER | SCHED_DEADLINE) => 0,
+        _ => {
+            errno::set_errno(errno::EINVAL);
+            -1
+        }
+    }
+}
+
+/// Get the round-robin time slice of a process (0 means the caller).
+///
+/// # Safety
+///
+/// `tp` must be valid for writes of a `struct timespec`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sched_rr_get_interval(pid: i32, tp: *mut crate::time::Timespec) -> i32 {
+    if let Err(e) = get_attr(pid) {
+        errno::set_errno(e);
+        return -1;
+    }
+    // SAFETY: Caller guarantees tp is valid.
+    unsafe {
+        (*tp).tv_sec = 0;
+        (*tp).tv_nsec = RR_INTERVAL_NS;
+    }
+    0
+}
@@ -7,7 +7,9 @@
 //! CLONE_SIGHAND | CLONE_SETTLS)`. The Thread Control Block (TCB) is
 //! allocated on the thread's stack region and its address is passed as
 //! `tls_ptr` (written to FS_BASE by the kernel). Reading `%fs:0` yields
-//! the self-pointer at the start of the TCB.
+//! the self-pointer at the start of the TCB. When the program was started by
+//! the dynamic linker, the static TLS blocks of the loaded modules sit
+//! directly below each TCB.
 //!
 //! # Mutex implementation
 //!
@@ -109,13 +111,36 @@ unsafe fn current_tcb() -> *mut Tcb {
 /// On Hadron, the kernel sets FS_BASE from `tls_ptr` at thread creation.
 /// We only need this for the main thread, which did not go through
 /// `task_clone`.
-#[allow(dead_code)] // Phase 2: called from thread-initialisation path
 unsafe fn set_current_tcb(tcb: *mut Tcb) {
-    // SAFETY: `tcb` is valid; arch_prctl / WRFSBASE would be the clean way
-    // but Hadron exposes FS_BASE configuration only via task_clone.
-    // For the main thread, FS_BASE may already be set by the kernel.
-    // This is a no-op stub — the main thread accesses keys via a fallback.
-    let _ = tcb;
+    // SAFETY: The caller guarantees `tcb` is a valid, self-pointing TCB.
+    let _ = unsafe { sys::sys_thread_set_tls(tcb as usize) };
+}
+
+// The dynamic linker allocates the main thread's TCB without knowing its
+// layout; it only promises `TCB_RESERVE` zeroed bytes.
+const _: () = assert!(core::mem::size_of::<Tcb>() <= crate::dlfcn::TCB_RESERVE);
+
+/// TCB for the main thread of a statically linked program.
+static mut MAIN_TCB: core::mem::MaybeUninit<Tcb> = core::mem::MaybeUninit::zeroed();
+
+/// Give the main thread a TCB if nothing has set FS_BASE yet.
+///
+/// Under the dynamic linker FS_BASE already points at a linker-allocated
+/// TCB whose static TLS blocks sit below it, so it is left alone.
+///
+/// # Safety
+///
+/// Must be called once, from the main thread, before any other thread exists.
+pub(crate) unsafe fn init_main_thread() {
+    if sys::sys_thread_get_tls() != 0 {
+        return;
+    }
+    let tcb = (&raw mut MAIN_TCB).cast::<Tcb>();
+    // SAFETY: MAIN_TCB is zero-initialised and only the main thread uses it.
+    unsafe {
+        (*tcb).self_ptr = tcb;
+        set_current_tcb(tcb);
+    }
 }
 
 // ---- Stack allocation -------------------------------------------------------
@@ -162,13 +187,18 @@ pub unsafe extern "C" fn pthread_create(
     // Align stack_size up to page boundary (4 KiB).
     let stack_size = (stack_size + 0xFFF) & !0xFFF;
 
-    // Allocate stack + TCB in one contiguous region.
-    // Layout: [guard gap] [stack grows down] [TCB at top]
-    // We put the TCB just above the stack (at the highest address),
-    // so stack_top = region_base + stack_size, TCB starts at stack_top.
+    // Allocate stack + static TLS + TCB in one contiguous region.
+    // Layout: [guard gap] [stack grows down] [static TLS] [TCB at top]
+    // We put the TCB at the highest address with the dynamic linker's
+    // static TLS blocks (if any) directly below it, as x86-64 TLS variant II
+    // requires, so stack_top = region_base + stack_size.
+    let dl = crate::dlfcn::dl_api();
+    let tcb_align = dl.map_or(TCB_ALIGN, |api| api.tls_align.max(TCB_ALIGN));
+    let tls_size = dl.map_or(0, |api| api.tls_static_size);
+    let tls_size = (tls_size + tcb_align - 1) & !(tcb_align - 1);
     let tcb_size = core::mem::size_of::<Tcb>();
     let tcb_size = (tcb_size + TCB_ALIGN - 1) & !(TCB_ALIGN - 1);
-    let total = stack_size + tcb_size;
+    let total = stack_size + tls_size + tcb_size;
 
     let region = unsafe { alloc_stack(total) };
     let Some(region_base) = region else {
@@ -181,10 +211,16 @@ pub unsafe extern "C" fn pthread_create(
     // the red zone / alignment.
     let stack_top = region_base.add(stack_size).sub(16) as usize;
 
-    // TCB is placed at [region_base + stack_size, region_base + total).
+    // TCB is placed at [region_base + stack_size + tls_size, region_base + total).
+    // The region is page-aligned and `tls_size` is a multiple of `tcb_align`,
+    // so the TCB satisfies the linker's alignment.
     // SAFETY: region_base points to a valid, writable mmap region of `total`
-    // bytes; the TCB fits within that region.
-    let tcb = region_base.add(stack_size) as *mut Tcb;
+    // bytes; the TLS blocks and the TCB fit within that region.
+    let tcb = region_base.add(stack_size + tls_size) as *mut Tcb;
+    if let Some(api) = dl {
+        // SAFETY: `tls_static_size` bytes below `tcb` are writable.
+        unsafe { (api.tls_init)(tcb as *mut u8) };
+    }
     unsafe {
         core::ptr::write_bytes(tcb as *mut u8, 0, tcb_size);
         (*tcb).self_ptr = tcb;
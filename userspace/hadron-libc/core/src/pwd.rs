@@ -0,0 +1,396 @@
+//! Password database.
+//!
+//! POSIX functions: `getpwnam`, `getpwuid`, `getpwnam_r`, `getpwuid_r`,
+//! `setpwent`, `getpwent`, `endpwent`.
+//!
+//! Entries come from `/etc/passwd`, one `name:passwd:uid:gid:gecos:dir:shell`
+//! record per line. The file is read in full on every lookup, so only the
+//! first [`DB_MAX`] bytes are seen. The parser is pure and host-testable;
+//! the lookups that read the file are gated behind `userspace`.
+
+use crate::errno::{ERANGE, Errno};
+
+/// Largest password file the lookups read.
+pub const DB_MAX: usize = 4096;
+
+/// C `struct passwd`.
+#[repr(C)]
+pub struct Passwd {
+    pub pw_name: *mut u8,
+    pub pw_passwd: *mut u8,
+    pub pw_uid: u32,
+    pub pw_gid: u32,
+    pub pw_gecos: *mut u8,
+    pub pw_dir: *mut u8,
+    pub pw_shell: *mut u8,
+}
+
+/// One parsed `/etc/passwd` record, borrowing from the file contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry<'a> {
+    pub name: &'a [u8],
+    pub passwd: &'a [u8],
+    pub uid: u32,
+    pub gid: u32,
+    pub gecos: &'a [u8],
+    pub dir: &'a [u8],
+    pub shell: &'a [u8],
+}
+
+/// Parses a decimal user or group ID.
+fn parse_id(s: &[u8]) -> Option<u32> {
+    if s.is_empty() {
+        return None;
+    }
+    s.iter().try_fold(0u32, |acc, &c| {
+        if !c.is_ascii_digit() {
+            return None;
+        }
+        acc.checked_mul(10)?.checked_add(u32::from(c - b'0'))
+    })
+}
+
+/// Parses one line of `/etc/passwd`, returning `None` if it is malformed.
+pub fn parse_entry(line: &[u8]) -> Option<Entry<'_>> {
+    let mut fields = line.split(|&c| c == b':');
+    let entry = Entry {
+        name: fields.next().filter(|n| !n.is_empty())?,
+        passwd: fields.next()?,
+        uid: parse_id(fields.next()?)?,
+        gid: parse_id(fields.next()?)?,
+        gecos: fields.next()?,
+        dir: fields.next()?,
+        shell: fields.next()?,
+    };
+    if fields.next().is_some() {
+        return None;
+    }
+    Some(entry)
+}
+
+/// Iterates over the well-formed records in `db`, skipping blank lines,
+/// comments and malformed lines.
+pub fn entries(db: &[u8]) -> impl Iterator<Item = Entry<'_>> {
+    db.split(|&c| c == b'\n')
+        .filter(|line| !line.starts_with(b"#"))
+        .filter_map(parse_entry)
+}
+
+impl Entry<'_> {
+    /// Bytes of string storage [`Entry::fill`] needs.
+    pub fn buf_len(&self) -> usize {
+        [self.name, self.passwd, self.gecos, self.dir, self.shell]
+            .iter()
+            .map(|s| s.len() + 1)
+            .sum()
+    }
+
+    /// Fills `pwd` with this record, copying the strings into `buf`.
+    ///
+    /// Returns [`ERANGE`] if `buf` is shorter than [`Entry::buf_len`].
+    ///
+    /// # Safety
+    ///
+    /// `pwd` must be valid for writes and `buf` valid for `buflen` bytes.
+    pub unsafe fn fill(&self, pwd: *mut Passwd, buf: *mut u8, buflen: usize) -> Result<(), Errno> {
+        if buflen < self.buf_len() {
+            return Err(ERANGE);
+        }
+        let mut off = 0;
+        let mut put = |s: &[u8]| {
+            // SAFETY: The total of all copies is `buf_len() <= buflen`.
+            unsafe {
+                let dst = buf.add(off);
+                core::ptr::copy_nonoverlapping(s.as_ptr(), dst, s.len());
+                *dst.add(s.len()) = 0;
+                off += s.len() + 1;
+                dst
+            }
+        };
+        let pw = Passwd {
+            pw_name: put(self.name),
+            pw_passwd: put(self.passwd),
+            pw_uid: self.uid,
+            pw_gid: self.gid,
+            pw_gecos: put(self.gecos),
+            pw_dir: put(self.dir),
+            pw_shell: put(self.shell),
+        };
+        // SAFETY: The caller guarantees `pwd` is valid for writes.
+        unsafe { pwd.write(pw) };
+        Ok(())
+    }
+}
+
+// ---- Lookups (read /etc/passwd) ---------------------------------------------
+
+#[cfg(feature = "userspace")]
+use crate::sys;
+
+/// Reads up to [`DB_MAX`] bytes of `/etc/passwd` into `buf`.
+///
+/// A missing or unreadable file reads as empty.
+#[cfg(feature = "userspace")]
+fn read_db(buf: &mut [u8; DB_MAX]) -> usize {
+    let flags = crate::flags::posix_open_to_hadron(crate::flags::O_RDONLY);
+    let Ok(fd) = sys::sys_open(b"/etc/passwd", flags) else {
+        return 0;
+    };
+    let mut len = 0;
+    while len < DB_MAX {
+        match sys::sys_read(fd, &mut buf[len..]) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => len += n,
+        }
+    }
+    let _ = sys::sys_close(fd);
+    len
+}
+
+/// Finds the first record matching `pred` and stores it in `pwd`/`buf`.
+///
+/// Follows the `getpw*_r` convention: returns 0 with `*result` null when
+/// nothing matches, or an error number.
+///
+/// # Safety
+///
+/// Same as [`getpwnam_r`].
+#[cfg(feature = "userspace")]
+unsafe fn lookup(
+    mut pred: impl FnMut(&Entry<'_>) -> bool,
+    pwd: *mut Passwd,
+    buf: *mut u8,
+    buflen: usize,
+    result: *mut *mut Passwd,
+) -> i32 {
+    // SAFETY: The caller guarantees `result` is valid for writes.
+    unsafe { *result = core::ptr::null_mut() };
+    let mut db = [0u8; DB_MAX];
+    let len = read_db(&mut db);
+    let Some(entry) = entries(&db[..len]).find(|e| pred(e)) else {
+        return 0;
+    };
+    // SAFETY: The caller guarantees `pwd` and `buf`.
+    match unsafe { entry.fill(pwd, buf, buflen) } {
+        Ok(()) => {
+            // SAFETY: As above.
+            unsafe { *result = pwd };
+            0
+        }
+        Err(e) => e.0,
+    }
+}
+
+/// Look up a user by name, reentrantly.
+///
+/// # Safety
+///
+/// `name` must be a valid NUL-terminated string, `pwd` and `result` valid
+/// for writes, and `buf` valid for `buflen` bytes.
+#[cfg(feature = "userspace")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn getpwnam_r(
+    name: *const u8,
+    pwd: *mut Passwd,
+    buf: *mut u8,
+    buflen: usize,
+    result: *mut *mut Passwd,
+) -> i32 {
+    // SAFETY: The caller guarantees `name` is NUL-terminated.
+    let name = unsafe { core::slice::from_raw_parts(name, crate::string::strlen(name)) };
+    // SAFETY: Forwarded from the caller.
+    unsafe { lookup(|e| e.name == name, pwd, buf, buflen, result) }
+}
+
+/// Look up a user by ID, reentrantly.
+///
+/// # Safety
+///
+/// `pwd` and `result` must be valid for writes and `buf` valid for
+/// `buflen` bytes.
+#[cfg(feature = "userspace")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn getpwuid_r(
+    uid: u32,
+    pwd: *mut Passwd,
+    buf: *mut u8,
+    buflen: usize,
+    result: *mut *mut Passwd,
+) -> i32 {
+    // SAFETY: Forwarded from the caller.
+    unsafe { lookup(|e| e.uid == uid, pwd, buf, buflen, result) }
+}
+
+/// Static storage for the non-reentrant lookups.
+// Single static (non-thread-safe), like `strtok`.
+#[cfg(feature = "userspace")]
+static mut PWD: Passwd = Passwd {
+    pw_name: core::ptr::null_mut(),
+    pw_passwd: core::ptr::null_mut(),
+    pw_uid: 0,
+    pw_gid: 0,
+    pw_gecos: core::ptr::null_mut(),
+    pw_dir: core::ptr::null_mut(),
+    pw_shell: core::ptr::null_mut(),
+};
+
+/// String storage backing [`PWD`].
+#[cfg(feature = "userspace")]
+static mut PWD_BUF: [u8; 1024] = [0; 1024];
+
+/// Runs a reentrant lookup against the static storage.
+#[cfg(feature = "userspace")]
+fn lookup_static(
+    f: impl FnOnce(*mut Passwd, *mut u8, usize, *mut *mut Passwd) -> i32,
+) -> *mut Passwd {
+    let mut result = core::ptr::null_mut();
+    // The statics are only touched here; single-threaded like strtok.
+    let err = f(
+        core::ptr::addr_of_mut!(PWD),
+        core::ptr::addr_of_mut!(PWD_BUF).cast(),
+        1024,
+        &mut result,
+    );
+    if err != 0 {
+        crate::errno::set_errno(Errno(err));
+    }
+    result
+}
+
+/// Look up a user by name.
+///
+/// Returns a pointer to static storage overwritten by the next call, or
+/// null if there is no such user.
+///
+/// # Safety
+///
+/// `name` must be a valid NUL-terminated string.
+#[cfg(feature = "userspace")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn getpwnam(name: *const u8) -> *mut Passwd {
+    // SAFETY: `name` is forwarded from the caller; the rest is static storage.
+    lookup_static(|pwd, buf, len, result| unsafe { getpwnam_r(name, pwd, buf, len, result) })
+}
+
+/// Look up a user by ID.
+///
+/// Returns a pointer to static storage overwritten by the next call, or
+/// null if there is no such user.
+#[cfg(feature = "userspace")]
+#[unsafe(no_mangle)]
+pub extern "C" fn getpwuid(uid: u32) -> *mut Passwd {
+    // SAFETY: All pointers refer to the static storage.
+    lookup_static(|pwd, buf, len, result| unsafe { getpwuid_r(uid, pwd, buf, len, result) })
+}
+
+/// Index of the next record `getpwent` returns.
+#[cfg(feature = "userspace")]
+static mut ENT_INDEX: usize = 0;
+
+/// Rewind the `getpwent` cursor.
+#[cfg(feature = "userspace")]
+#[unsafe(no_mangle)]
+pub extern "C" fn setpwent() {
+    // SAFETY: Single-threaded cursor, like strtok.
+    unsafe { ENT_INDEX = 0 };
+}
+
+/// Close the password database; rewinds the `getpwent` cursor.
+#[cfg(feature = "userspace")]
+#[unsafe(no_mangle)]
+pub extern "C" fn endpwent() {
+    setpwent();
+}
+
+/// Return the next password record, or null at the end.
+#[cfg(feature = "userspace")]
+#[unsafe(no_mangle)]
+pub extern "C" fn getpwent() -> *mut Passwd {
+    // SAFETY: Single-threaded cursor, like strtok.
+    let index = unsafe { ENT_INDEX };
+    let mut count = 0;
+    let pwd = lookup_static(|pwd, buf, len, result| {
+        // SAFETY: All pointers refer to the static storage.
+        unsafe {
+            lookup(
+                |_| {
+                    count += 1;
+                    count > index
+                },
+                pwd,
+                buf,
+                len,
+                result,
+            )
+        }
+    });
+    if !pwd.is_null() {
+        // SAFETY: As above.
+        unsafe { ENT_INDEX = index + 1 };
+    }
+    pwd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DB: &[u8] = b"root:x:0:0:root:/:/bin/sh\n\
+        # comment\n\
+        \n\
+        bad:x:notanumber:0::/:/bin/sh\n\
+        user:x:1000:100:A User:/home/user:/bin/sh\n";
+
+    #[test]
+    fn parse_valid_entry() {
+        let e = parse_entry(b"user:x:1000:100:A User:/home/user:/bin/sh").unwrap();
+        assert_eq!(e.name, b"user");
+        assert_eq!(e.passwd, b"x");
+        assert_eq!((e.uid, e.gid), (1000, 100));
+        assert_eq!(e.gecos, b"A User");
+        assert_eq!(e.dir, b"/home/user");
+        assert_eq!(e.shell, b"/bin/sh");
+    }
+
+    #[test]
+    fn parse_rejects_malformed() {
+        assert!(parse_entry(b"").is_none());
+        assert!(parse_entry(b":x:0:0::/:/bin/sh").is_none());
+        assert!(parse_entry(b"a:x:0:0::/").is_none());
+        assert!(parse_entry(b"a:x:0:0::/:/bin/sh:extra").is_none());
+        assert!(parse_entry(b"a:x:-1:0::/:/bin/sh").is_none());
+        assert!(parse_entry(b"a:x:4294967296:0::/:/bin/sh").is_none());
+    }
+
+    #[test]
+    fn entries_skip_comments_and_bad_lines() {
+        let names: [&[u8]; 2] = [b"root", b"user"];
+        assert!(entries(DB).map(|e| e.name).eq(names));
+    }
+
+    #[test]
+    fn fill_copies_strings() {
+        let e = entries(DB).find(|e| e.uid == 1000).unwrap();
+        let mut pwd = core::mem::MaybeUninit::<Passwd>::uninit();
+        let mut buf = [0xffu8; 64];
+        // SAFETY: `pwd` and `buf` are valid local storage.
+        unsafe { e.fill(pwd.as_mut_ptr(), buf.as_mut_ptr(), buf.len()) }.unwrap();
+        // SAFETY: `fill` succeeded.
+        let pwd = unsafe { pwd.assume_init() };
+        assert_eq!((pwd.pw_uid, pwd.pw_gid), (1000, 100));
+        // SAFETY: `fill` NUL-terminated every string within `buf`.
+        let dir = unsafe { core::slice::from_raw_parts(pwd.pw_dir, 11) };
+        assert_eq!(dir, b"/home/user\0");
+        assert_eq!(buf[..e.buf_len()].iter().filter(|&&c| c == 0).count(), 5);
+    }
+
+    #[test]
+    fn fill_reports_short_buffer() {
+        let e = parse_entry(b"root:x:0:0:root:/:/bin/sh").unwrap();
+        let mut pwd = core::mem::MaybeUninit::<Passwd>::uninit();
+        let mut buf = [0u8; 8];
+        // SAFETY: `pwd` and `buf` are valid local storage.
+        let r = unsafe { e.fill(pwd.as_mut_ptr(), buf.as_mut_ptr(), buf.len()) };
+        assert_eq!(r, Err(ERANGE));
+    }
+}
@@ -1,34 +1,46 @@
 //! Signal handling functions.
 //!
-//! POSIX functions: `sigaction`, `sigprocmask`, `signal`, `raise`.
+//! POSIX functions: `sigaction`, `sigprocmask`, `signal`, `raise`,
+//! `sigqueue`, `sigaltstack`, `sigpending`, `sigsuspend`, `sigtimedwait`,
+//! `sigwaitinfo`, `sigwait`, `signalfd`.
+//!
+//! `sigset_t` is a `u64` with bit `n - 1` for signal `n`, as in the kernel.
 
-use crate::errno;
+use crate::errno::{self, EINVAL};
 use crate::sys;
+use crate::time::Timespec;
+
+pub use hadron_syscall::{FpState, SigAction, SigAltStack, SigInfo, UContext};
 
 /// Default signal handler.
 pub const SIG_DFL: usize = 0;
 /// Ignore signal.
 pub const SIG_IGN: usize = 1;
 
-/// Install a signal action.
+/// Converts a `Result` into the `0` / `-1`-and-errno convention.
+fn to_ret(result: Result<(), errno::Errno>) -> i32 {
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Examine or change the action for a signal.
 ///
 /// # Safety
 ///
-/// `old_handler` may be null; if non-null, must be valid for one `usize`.
+/// `act` and `oldact` may be null; if non-null, must be valid for one
+/// `struct sigaction`.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn sigaction(
     signum: i32,
-    handler: usize,
-    flags: usize,
-    old_handler: *mut usize,
+    act: *const SigAction,
+    oldact: *mut SigAction,
 ) -> i32 {
-    match sys::sys_sigaction(signum as usize, handler, flags, old_handler) {
-        Ok(()) => 0,
-        Err(e) => {
-            errno::set_errno(e);
-            -1
-        }
-    }
+    to_ret(sys::sys_sigaction(signum as usize, act, oldact))
 }
 
 /// Examine or change the signal mask.
@@ -38,13 +50,14 @@ pub unsafe extern "C" fn sigaction(
 /// `set` and `oldset` may be null; if non-null, must be valid pointers.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn sigprocmask(how: i32, set: *const u64, oldset: *mut u64) -> i32 {
-    match sys::sys_sigprocmask(how as usize, set, oldset) {
-        Ok(()) => 0,
-        Err(e) => {
-            errno::set_errno(e);
-            -1
-        }
-    }
+    // The kernel takes the set by value; a null set only reads the mask.
+    let (how, set) = if set.is_null() {
+        (crate::flags::SIG_BLOCK, 0)
+    } else {
+        // SAFETY: the caller guarantees `set` is valid.
+        (how, unsafe { *set })
+    };
+    to_ret(sys::sys_sigprocmask(how as usize, set, oldset))
 }
 
 /// Simplified signal handler registration (POSIX `signal()`).
@@ -52,9 +65,13 @@ pub unsafe extern "C" fn sigprocmask(how: i32, set: *const u64, oldset: *mut u64
 /// Returns the previous handler, or `SIG_ERR` (usize::MAX) on error.
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn signal(signum: i32, handler: usize) -> usize {
-    let mut old: usize = 0;
-    match sys::sys_sigaction(signum as usize, handler, 0, &raw mut old) {
-        Ok(()) => old,
+    let act = SigAction {
+        handler,
+        ..SigAction::default()
+    };
+    let mut old = SigAction::default();
+    match sys::sys_sigaction(signum as usize, &raw const act, &raw mut old) {
+        Ok(()) => old.handler,
         Err(e) => {
             errno::set_errno(e);
             usize::MAX // SIG_ERR
@@ -66,8 +83,127 @@ pub unsafe extern "C" fn signal(signum: i32, handler: usize) -> usize {
 #[unsafe(no_mangle)]
 pub extern "C" fn raise(sig: i32) -> i32 {
     let pid = sys::sys_getpid();
-    match sys::sys_kill(pid, sig as usize) {
-        Ok(()) => 0,
+    to_ret(sys::sys_kill(pid, sig as usize))
+}
+
+/// Queue a signal with a value for a process (POSIX `sigqueue()`).
+///
+/// `value` is the `union sigval`, passed by value.
+#[unsafe(no_mangle)]
+pub extern "C" fn sigqueue(pid: i32, sig: i32, value: usize) -> i32 {
+    to_ret(sys::sys_sigqueue(pid as usize, sig as usize, value))
+}
+
+/// Set and/or get the alternate signal stack.
+///
+/// # Safety
+///
+/// `ss` and `old_ss` may be null; if non-null, must be valid for one
+/// `stack_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sigaltstack(ss: *const SigAltStack, old_ss: *mut SigAltStack) -> i32 {
+    to_ret(sys::sys_sigaltstack(ss, old_ss))
+}
+
+/// Store the set of signals that are blocked and pending.
+///
+/// # Safety
+///
+/// `set` must be valid for one `sigset_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sigpending(set: *mut u64) -> i32 {
+    to_ret(sys::sys_sigpending(set))
+}
+
+/// Replace the signal mask and wait for a signal handler to run.
+///
+/// Always returns -1 with `errno` set to `EINTR`.
+///
+/// # Safety
+///
+/// `mask` must be valid for one `sigset_t`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sigsuspend(mask: *const u64) -> i32 {
+    // SAFETY: the caller guarantees `mask` is valid.
+    to_ret(sys::sys_sigsuspend(unsafe { *mask }))
+}
+
+/// Wait for a signal in `set` for at most `timeout`, and accept it.
+///
+/// Returns the signal number, or -1 with `errno` set to `EAGAIN` on timeout
+/// or `EINTR` if a handler ran for another signal.
+///
+/// # Safety
+///
+/// `set` must be valid. `info` and `timeout` may be null; if non-null, they
+/// must be valid. A null `timeout` waits forever.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sigtimedwait(
+    set: *const u64,
+    info: *mut SigInfo,
+    timeout: *const Timespec,
+) -> i32 {
+    // SAFETY: the caller guarantees `set` is valid.
+    match sys::sys_sigtimedwait(unsafe { *set }, info, timeout) {
+        Ok(sig) => sig as i32,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Wait for a signal in `set` and accept it, without a timeout.
+///
+/// # Safety
+///
+/// Same as [`sigtimedwait`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sigwaitinfo(set: *const u64, info: *mut SigInfo) -> i32 {
+    unsafe { sigtimedwait(set, info, core::ptr::null()) }
+}
+
+/// Wait for a signal in `set` and store its number in `sig`.
+///
+/// Returns 0, or an error number (not -1) on failure.
+///
+/// # Safety
+///
+/// `set` and `sig` must be valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn sigwait(set: *const u64, sig: *mut i32) -> i32 {
+    if sig.is_null() {
+        return EINVAL.0;
+    }
+    // SAFETY: the caller guarantees `set` is valid.
+    match sys::sys_sigtimedwait(unsafe { *set }, core::ptr::null_mut(), core::ptr::null()) {
+        Ok(signo) => {
+            // SAFETY: checked non-null; the caller guarantees validity.
+            unsafe { *sig = signo as i32 };
+            0
+        }
+        Err(e) => e.0,
+    }
+}
+
+/// Create a signalfd accepting the signals in `mask`, or replace the mask
+/// of signalfd `fd` if it is not -1.
+///
+/// Reading the fd returns one `SigInfo` record per accepted signal.
+///
+/// # Safety
+///
+/// `mask` must be valid.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn signalfd(fd: i32, mask: *const u64, flags: i32) -> i32 {
+    let Some(flags) = crate::flags::posix_fd_flags_to_hadron(flags as u32, 0) else {
+        errno::set_errno(EINVAL);
+        return -1;
+    };
+    let fd = if fd == -1 { usize::MAX } else { fd as usize };
+    // SAFETY: the caller guarantees `mask` is valid.
+    match sys::sys_signalfd(fd, unsafe { *mask }, flags) {
+        Ok(fd) => fd as i32,
         Err(e) => {
             errno::set_errno(e);
             -1
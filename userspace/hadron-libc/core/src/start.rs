@@ -15,6 +15,7 @@
 ///   RSP + 8*(argc+2)    → envp[0]: *const c_char
 ///   ...
 ///   (terminated by NULL)
+///   auxv: (type, value) pairs, terminated by AT_NULL
 /// ```
 #[unsafe(no_mangle)]
 #[unsafe(naked)]
@@ -38,19 +39,25 @@ extern "C" fn _start_rust(stack: *const usize) -> ! {
     let envp = unsafe { argv.add(argc as usize + 1) } as *const *const u8;
 
     // Initialize libc subsystems.
-    // 1. Set up environ from envp.
-    unsafe { crate::env::init_environ(envp) };
+    // 1. Set up environ from envp, and record the auxv that follows it.
+    unsafe {
+        crate::env::init_environ(envp);
+        crate::auxv::init_auxv(envp);
+    }
+
+    // 2. Give the main thread a TCB unless the dynamic linker already did.
+    unsafe { crate::pthread::init_main_thread() };
 
-    // 2. Initialize stdio (streams are const-initialized, just mark ready).
+    // 3. Initialize stdio (streams are const-initialized, just mark ready).
     crate::stdio::init();
 
-    // 3. Call main.
+    // 4. Call main.
     unsafe extern "C" {
         fn main(argc: i32, argv: *const *const u8, envp: *const *const u8) -> i32;
     }
     // SAFETY: The user binary defines `main` with the standard C signature.
     let status = unsafe { main(argc, argv, envp) };
 
-    // 4. Call exit (runs atexit handlers, flushes stdio, terminates).
+    // 5. Call exit (runs atexit handlers, flushes stdio, terminates).
     unsafe { crate::process::exit(status) }
 }
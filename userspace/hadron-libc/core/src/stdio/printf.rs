@@ -335,7 +335,7 @@ pub unsafe extern "C" fn printf(fmt: *const u8, mut args: ...) -> i32 {
     };
 
     // SAFETY: Caller guarantees args match format specifiers.
-    let mut next_arg = || -> usize { unsafe { args.arg::<usize>() } };
+    let mut next_arg = || -> usize { unsafe { args.next_arg::<usize>() } };
 
     format_to(&mut writer, fmt, &mut next_arg);
     count
@@ -356,7 +356,7 @@ pub unsafe extern "C" fn fprintf(stream: *mut FILE, fmt: *const u8, mut args: ..
     };
 
     // SAFETY: Caller guarantees args match format specifiers.
-    let mut next_arg = || -> usize { unsafe { args.arg::<usize>() } };
+    let mut next_arg = || -> usize { unsafe { args.next_arg::<usize>() } };
 
     format_to(&mut writer, fmt, &mut next_arg);
     count
@@ -386,7 +386,7 @@ pub unsafe extern "C" fn snprintf(buf: *mut u8, size: usize, fmt: *const u8, mut
     };
 
     // SAFETY: Caller guarantees args match format specifiers.
-    let mut next_arg = || -> usize { unsafe { args.arg::<usize>() } };
+    let mut next_arg = || -> usize { unsafe { args.next_arg::<usize>() } };
 
     format_to(&mut writer, fmt, &mut next_arg);
 
@@ -420,7 +420,7 @@ pub unsafe extern "C" fn sprintf(buf: *mut u8, fmt: *const u8, mut args: ...) ->
     };
 
     // SAFETY: Caller guarantees args match format specifiers.
-    let mut next_arg = || -> usize { unsafe { args.arg::<usize>() } };
+    let mut next_arg = || -> usize { unsafe { args.next_arg::<usize>() } };
 
     format_to(&mut writer, fmt, &mut next_arg);
 
@@ -463,7 +463,7 @@ pub unsafe extern "C" fn vsnprintf(
     };
 
     // SAFETY: Caller guarantees args match format specifiers.
-    let mut next_arg = || -> usize { unsafe { args.arg::<usize>() } };
+    let mut next_arg = || -> usize { unsafe { args.next_arg::<usize>() } };
     format_to(&mut writer, fmt, &mut next_arg);
 
     if !buf.is_null() && size > 0 {
@@ -497,7 +497,7 @@ pub unsafe extern "C" fn vsprintf(
         }
     };
     // SAFETY: Caller guarantees args match format specifiers.
-    let mut next_arg = || -> usize { unsafe { args.arg::<usize>() } };
+    let mut next_arg = || -> usize { unsafe { args.next_arg::<usize>() } };
     format_to(&mut writer, fmt, &mut next_arg);
     if !buf.is_null() {
         // SAFETY: Caller guarantees sufficient space.
@@ -525,7 +525,7 @@ pub unsafe extern "C" fn vfprintf(
         count += bytes.len() as i32;
     };
     // SAFETY: Caller guarantees args match format specifiers.
-    let mut next_arg = || -> usize { unsafe { args.arg::<usize>() } };
+    let mut next_arg = || -> usize { unsafe { args.next_arg::<usize>() } };
     format_to(&mut writer, fmt, &mut next_arg);
     count
 }
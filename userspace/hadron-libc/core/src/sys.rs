@@ -48,6 +48,46 @@ pub fn sys_waitpid(pid: usize, status: *mut u64, flags: usize) -> Result<usize,
     ))
 }
 
+pub fn sys_wait4(
+    pid: usize,
+    status: *mut u64,
+    flags: usize,
+    rusage: *mut hadron_syscall::RusageInfo,
+) -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_child_wait4(
+        pid,
+        status as usize,
+        flags,
+        rusage as usize,
+    ))
+}
+
+pub fn sys_waitid(
+    idtype: usize,
+    id: usize,
+    info: *mut hadron_syscall::SigInfo,
+    options: usize,
+    rusage: *mut hadron_syscall::RusageInfo,
+) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_child_waitid(
+        idtype,
+        id,
+        info as usize,
+        options,
+        rusage as usize,
+    ))
+}
+
+pub fn sys_child_set_subreaper(enable: bool) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_child_set_subreaper(
+        usize::from(enable),
+    ))
+}
+
+pub fn sys_child_get_subreaper() -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_child_get_subreaper())
+}
+
 pub fn sys_kill(pid: usize, sig: usize) -> Result<(), Errno> {
     check_unit(hadron_syscall::wrappers::sys_task_kill(pid, sig))
 }
@@ -68,19 +108,17 @@ pub fn sys_chdir(path: &[u8]) -> Result<(), Errno> {
 
 pub fn sys_sigaction(
     sig: usize,
-    handler: usize,
-    flags: usize,
-    old_handler: *mut usize,
+    act: *const hadron_syscall::SigAction,
+    oldact: *mut hadron_syscall::SigAction,
 ) -> Result<(), Errno> {
     check_unit(hadron_syscall::wrappers::sys_task_sigaction(
         sig,
-        handler,
-        flags,
-        old_handler as usize,
+        act as usize,
+        oldact as usize,
     ))
 }
 
-pub fn sys_sigprocmask(how: usize, set: *const u64, oldset: *mut u64) -> Result<(), Errno> {
+pub fn sys_sigprocmask(how: usize, set: u64, oldset: *mut u64) -> Result<(), Errno> {
     check_unit(hadron_syscall::wrappers::sys_task_sigprocmask(
         how,
         set as usize,
@@ -88,6 +126,100 @@ pub fn sys_sigprocmask(how: usize, set: *const u64, oldset: *mut u64) -> Result<
     ))
 }
 
+pub fn sys_sigqueue(pid: usize, sig: usize, value: usize) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_sig_queue(pid, sig, value))
+}
+
+pub fn sys_sigaltstack(
+    ss: *const hadron_syscall::SigAltStack,
+    old_ss: *mut hadron_syscall::SigAltStack,
+) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_sig_altstack(
+        ss as usize,
+        old_ss as usize,
+    ))
+}
+
+pub fn sys_sigpending(set: *mut u64) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_sig_pending(set as usize))
+}
+
+pub fn sys_sigsuspend(mask: u64) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_sig_suspend(mask as usize))
+}
+
+pub fn sys_sigtimedwait(
+    set: u64,
+    info: *mut hadron_syscall::SigInfo,
+    timeout: *const crate::time::Timespec,
+) -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_sig_timedwait(
+        set as usize,
+        info as usize,
+        timeout as usize,
+    ))
+}
+
+pub fn sys_signalfd(fd: usize, mask: u64, flags: usize) -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_sig_fd(
+        fd,
+        mask as usize,
+        flags,
+    ))
+}
+
+pub fn sys_getpriority(which: usize, who: usize) -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_sched_getpriority(which, who))
+}
+
+pub fn sys_setpriority(which: usize, who: usize, nice: i32) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_sched_setpriority(
+        which,
+        who,
+        nice as isize as usize,
+    ))
+}
+
+pub fn sys_sched_getaffinity(pid: usize, len: usize, mask: *mut u8) -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_sched_getaffinity(
+        pid,
+        len,
+        mask as usize,
+    ))
+}
+
+pub fn sys_sched_setaffinity(pid: usize, len: usize, mask: *const u8) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_sched_setaffinity(
+        pid,
+        len,
+        mask as usize,
+    ))
+}
+
+pub fn sys_sched_setattr(
+    pid: usize,
+    attr: *const hadron_syscall::SchedAttr,
+    flags: usize,
+) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_sched_setattr(
+        pid,
+        attr as usize,
+        flags,
+    ))
+}
+
+pub fn sys_sched_getattr(
+    pid: usize,
+    attr: *mut hadron_syscall::SchedAttr,
+    size: usize,
+) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_sched_getattr(
+        pid,
+        attr as usize,
+        size,
+    ))
+}
+
 pub fn sys_setpgid(pid: usize, pgid: usize) -> Result<(), Errno> {
     check_unit(hadron_syscall::wrappers::sys_task_setpgid(pid, pgid))
 }
@@ -107,6 +239,56 @@ pub fn sys_execve(info_ptr: *const u8, info_len: usize) -> Result<(), Errno> {
     ))
 }
 
+// ---- Credentials -------------------------------------------------------------
+
+pub fn sys_cred_get() -> Result<hadron_syscall::CredInfo, Errno> {
+    let mut info = core::mem::MaybeUninit::<hadron_syscall::CredInfo>::uninit();
+    check_unit(hadron_syscall::wrappers::sys_cred_get(
+        info.as_mut_ptr() as usize,
+        core::mem::size_of::<hadron_syscall::CredInfo>(),
+    ))?;
+    // SAFETY: The kernel wrote a complete CredInfo on success.
+    Ok(unsafe { info.assume_init() })
+}
+
+pub fn sys_getgroups(buf: *mut u32, count: usize) -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_cred_getgroups(
+        buf as usize,
+        count,
+    ))
+}
+
+pub fn sys_setuid(uid: u32) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_cred_setuid(uid as usize))
+}
+
+pub fn sys_setgid(gid: u32) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_cred_setgid(gid as usize))
+}
+
+pub fn sys_setresuid(uid: u32, euid: u32, suid: u32) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_cred_setresuid(
+        uid as usize,
+        euid as usize,
+        suid as usize,
+    ))
+}
+
+pub fn sys_setresgid(gid: u32, egid: u32, sgid: u32) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_cred_setresgid(
+        gid as usize,
+        egid as usize,
+        sgid as usize,
+    ))
+}
+
+pub fn sys_setgroups(groups: *const u32, count: usize) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_cred_setgroups(
+        groups as usize,
+        count,
+    ))
+}
+
 // ---- File descriptors --------------------------------------------------------
 
 pub fn sys_close(fd: usize) -> Result<(), Errno> {
@@ -256,6 +438,22 @@ pub unsafe fn sys_task_clone(
     ))
 }
 
+/// Set the calling thread's TLS base (the FS segment base).
+///
+/// # Safety
+///
+/// `tls_ptr` must point to a valid Thread Control Block whose first field is
+/// a self-pointer, or be 0. Existing `fs:`-relative accesses observe the new
+/// base immediately.
+pub unsafe fn sys_thread_set_tls(tls_ptr: usize) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_thread_set_tls(tls_ptr))
+}
+
+/// Return the calling thread's TLS base, or 0 if none has been set.
+pub fn sys_thread_get_tls() -> usize {
+    hadron_syscall::wrappers::sys_thread_get_tls() as usize
+}
+
 // ---- Query extensions -------------------------------------------------------
 
 /// `QUERY_VMAPS`: returns the number of bytes written into `buf` on success.
@@ -283,6 +481,22 @@ pub fn sys_query_cpu_info(buf: *mut u8, buf_len: usize) -> Result<usize, Errno>
     check(n)
 }
 
+/// `QUERY_RUSAGE`: CPU time of the calling process, thread or reaped
+/// children, selected by one of the kernel's `RUSAGE_*` values.
+pub fn sys_query_rusage(who: usize) -> Result<hadron_syscall::RusageInfo, Errno> {
+    let mut info = hadron_syscall::RusageInfo {
+        user_ns: 0,
+        system_ns: 0,
+    };
+    let n = hadron_syscall::wrappers::sys_query(
+        hadron_syscall::QUERY_RUSAGE as usize,
+        who,
+        (&raw mut info) as usize,
+        core::mem::size_of::<hadron_syscall::RusageInfo>(),
+    );
+    check(n).map(|_| info)
+}
+
 // ---- Time / Events -----------------------------------------------------------
 
 pub fn sys_clock_gettime(clockid: usize, tp: *mut u8) -> Result<(), Errno> {
@@ -302,11 +516,100 @@ pub fn sys_nanosleep(req: *const u8, rem: *mut u8) -> Result<(), Errno> {
     ))
 }
 
+pub fn sys_eventfd(initval: usize, flags: usize) -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_event_create(initval, flags))
+}
+
+pub fn sys_timerfd_create(clockid: usize, flags: usize) -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_timerfd_create(clockid, flags))
+}
+
+pub fn sys_timerfd_settime(
+    fd: usize,
+    flags: usize,
+    new: *const hadron_syscall::ITimerSpec,
+    old: *mut hadron_syscall::ITimerSpec,
+) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_timerfd_settime(
+        fd,
+        flags,
+        new as usize,
+        old as usize,
+    ))
+}
+
+pub fn sys_timerfd_gettime(fd: usize, cur: *mut hadron_syscall::ITimerSpec) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_timerfd_gettime(
+        fd,
+        cur as usize,
+    ))
+}
+
+pub fn sys_setitimer(
+    which: usize,
+    new: *const hadron_syscall::ITimerSpec,
+    old: *mut hadron_syscall::ITimerSpec,
+) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_itimer_set(
+        which,
+        new as usize,
+        old as usize,
+    ))
+}
+
+pub fn sys_getitimer(which: usize, cur: *mut hadron_syscall::ITimerSpec) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_itimer_get(
+        which,
+        cur as usize,
+    ))
+}
+
+pub fn sys_timer_create(
+    clockid: usize,
+    sevp: *const hadron_syscall::SigEvent,
+    id: *mut u32,
+) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_timer_create(
+        clockid,
+        sevp as usize,
+        id as usize,
+    ))
+}
+
+pub fn sys_timer_settime(
+    id: usize,
+    flags: usize,
+    new: *const hadron_syscall::ITimerSpec,
+    old: *mut hadron_syscall::ITimerSpec,
+) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_timer_settime(
+        id,
+        flags,
+        new as usize,
+        old as usize,
+    ))
+}
+
+pub fn sys_timer_gettime(id: usize, cur: *mut hadron_syscall::ITimerSpec) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_timer_gettime(
+        id,
+        cur as usize,
+    ))
+}
+
+pub fn sys_timer_delete(id: usize) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_timer_delete(id))
+}
+
+pub fn sys_timer_getoverrun(id: usize) -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_timer_getoverrun(id))
+}
+
 pub fn sys_futex(
     addr: *mut u32,
     op: usize,
     val: usize,
-    timeout: *const u8,
+    timeout: *const crate::time::Timespec,
 ) -> Result<usize, Errno> {
     check(hadron_syscall::wrappers::sys_futex(
         addr as usize,
@@ -317,11 +620,97 @@ pub fn sys_futex(
 }
 
 pub fn sys_poll(fds: *mut u8, nfds: usize, timeout_ms: isize) -> Result<usize, Errno> {
+    // The kernel takes the timeout in nanoseconds; usize::MAX waits forever.
+    let timeout_ns = if timeout_ms < 0 {
+        usize::MAX
+    } else {
+        (timeout_ms as usize).saturating_mul(1_000_000)
+    };
     check(hadron_syscall::wrappers::sys_event_wait_many(
         fds as usize,
         nfds,
-        timeout_ms as usize,
+        timeout_ns,
+    ))
+}
+
+pub fn sys_epoll_create(flags: usize) -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_epoll_create(flags))
+}
+
+pub fn sys_epoll_ctl(
+    epfd: usize,
+    op: usize,
+    fd: usize,
+    event: *const hadron_syscall::EpollEvent,
+) -> Result<(), Errno> {
+    check_unit(hadron_syscall::wrappers::sys_epoll_ctl(
+        epfd,
+        op,
+        fd,
+        event as usize,
+    ))
+}
+
+pub fn sys_epoll_wait(
+    epfd: usize,
+    events: *mut hadron_syscall::EpollEvent,
+    max_events: usize,
+    timeout_ms: isize,
+) -> Result<usize, Errno> {
+    // Same timeout convention as `sys_poll`.
+    let timeout_ns = if timeout_ms < 0 {
+        usize::MAX
+    } else {
+        (timeout_ms as usize).saturating_mul(1_000_000)
+    };
+    check(hadron_syscall::wrappers::sys_epoll_wait(
+        epfd,
+        events as usize,
+        max_events,
+        timeout_ns,
+    ))
+}
+
+pub fn sys_io_ring_create(
+    entries: usize,
+    params: *mut hadron_syscall::IoRingParams,
+    flags: usize,
+) -> Result<usize, Errno> {
+    check(hadron_syscall::wrappers::sys_io_ring_create(
+        entries,
+        params as usize,
+        flags,
+    ))
+}
+
+pub fn sys_io_ring_enter(
+    fd: usize,
+    to_submit: usize,
+    min_complete: usize,
+    timeout_ms: isize,
+) -> Result<usize, Errno> {
+    // Same timeout convention as `sys_poll`.
+    let timeout_ns = if timeout_ms < 0 {
+        usize::MAX
+    } else {
+        (timeout_ms as usize).saturating_mul(1_000_000)
+    };
+    check(hadron_syscall::wrappers::sys_io_ring_enter(
+        fd,
+        to_submit,
+        min_complete,
+        timeout_ns,
+    ))
+}
+
+/// Map the shared memory object behind `fd` read-write.
+pub fn sys_mem_map_shared(fd: usize, size: usize) -> Result<*mut u8, Errno> {
+    check(hadron_syscall::wrappers::sys_mem_map_shared(
+        fd,
+        size,
+        hadron_syscall::PROT_READ | hadron_syscall::PROT_WRITE,
     ))
+    .map(|addr| addr as *mut u8)
 }
 
 // ---- Socket ------------------------------------------------------------------
@@ -1,12 +1,16 @@
 //! Time functions.
 //!
-//! POSIX functions: `clock_gettime`, `nanosleep`, `time`, `sleep`, `usleep`.
+//! POSIX functions: `clock_gettime`, `nanosleep`, `time`, `sleep`, `usleep`,
+//! `getrusage`, `times`, `alarm`, `setitimer`, `getitimer`, `timer_create`,
+//! `timer_settime`, `timer_gettime`, `timer_delete`, `timer_getoverrun`,
+//! `timerfd_create`, `timerfd_settime`, `timerfd_gettime`.
 
 use crate::errno;
 use crate::sys;
 
 /// `struct timespec` — POSIX time representation.
 #[repr(C)]
+#[derive(Clone, Copy, Default)]
 pub struct Timespec {
     pub tv_sec: i64,
     pub tv_nsec: i64,
@@ -105,3 +109,421 @@ pub extern "C" fn usleep(usec: u32) -> i32 {
     };
     unsafe { nanosleep(&req, core::ptr::null_mut()) }
 }
+
+// ---- CPU time ----------------------------------------------------------------
+
+/// `struct timeval` — seconds and microseconds.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct Timeval {
+    pub tv_sec: i64,
+    pub tv_usec: i64,
+}
+
+impl Timeval {
+    pub(crate) fn from_nanos(ns: u64) -> Self {
+        Self {
+            tv_sec: (ns / 1_000_000_000) as i64,
+            tv_usec: ((ns % 1_000_000_000) / 1000) as i64,
+        }
+    }
+}
+
+/// `struct rusage` from `<sys/resource.h>`.
+///
+/// Only the CPU times are filled in; Hadron does not count faults, context
+/// switches or I/O operations.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct Rusage {
+    pub ru_utime: Timeval,
+    pub ru_stime: Timeval,
+    pub ru_maxrss: i64,
+    pub ru_ixrss: i64,
+    pub ru_idrss: i64,
+    pub ru_isrss: i64,
+    pub ru_minflt: i64,
+    pub ru_majflt: i64,
+    pub ru_nswap: i64,
+    pub ru_inblock: i64,
+    pub ru_oublock: i64,
+    pub ru_msgsnd: i64,
+    pub ru_msgrcv: i64,
+    pub ru_nsignals: i64,
+    pub ru_nvcsw: i64,
+    pub ru_nivcsw: i64,
+}
+
+/// `struct tms` from `<sys/times.h>`, in clock ticks.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct Tms {
+    pub tms_utime: i64,
+    pub tms_stime: i64,
+    pub tms_cutime: i64,
+    pub tms_cstime: i64,
+}
+
+/// POSIX `RUSAGE_SELF`.
+pub const RUSAGE_SELF: i32 = 0;
+/// POSIX `RUSAGE_CHILDREN`.
+pub const RUSAGE_CHILDREN: i32 = -1;
+/// Linux `RUSAGE_THREAD`.
+pub const RUSAGE_THREAD: i32 = 1;
+
+/// Clock ticks per second reported by `times` and `sysconf(_SC_CLK_TCK)`.
+pub const CLK_TCK: i64 = 100;
+
+/// Converts nanoseconds to [`CLK_TCK`] clock ticks.
+fn nanos_to_ticks(ns: u64) -> i64 {
+    (ns / (1_000_000_000 / CLK_TCK as u64)) as i64
+}
+
+/// Get resource usage of the calling process, thread or reaped children.
+///
+/// # Safety
+///
+/// `usage` must be a valid pointer to an `Rusage`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn getrusage(who: i32, usage: *mut Rusage) -> i32 {
+    let kernel_who = match who {
+        RUSAGE_SELF => hadron_syscall::RUSAGE_SELF,
+        RUSAGE_CHILDREN => hadron_syscall::RUSAGE_CHILDREN,
+        RUSAGE_THREAD => hadron_syscall::RUSAGE_THREAD,
+        _ => {
+            errno::set_errno(errno::EINVAL);
+            return -1;
+        }
+    };
+    if usage.is_null() {
+        errno::set_errno(errno::EFAULT);
+        return -1;
+    }
+    match sys::sys_query_rusage(kernel_who) {
+        Ok(info) => {
+            let ru = Rusage {
+                ru_utime: Timeval::from_nanos(info.user_ns),
+                ru_stime: Timeval::from_nanos(info.system_ns),
+                ..Rusage::default()
+            };
+            // SAFETY: Caller guarantees usage is valid.
+            unsafe { usage.write(ru) };
+            0
+        }
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Get process and reaped-children CPU times in clock ticks.
+///
+/// Returns the clock ticks elapsed since boot, or -1 on error.
+///
+/// # Safety
+///
+/// `buf` must be a valid pointer to a `Tms`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn times(buf: *mut Tms) -> i64 {
+    if buf.is_null() {
+        errno::set_errno(errno::EFAULT);
+        return -1;
+    }
+    let times = sys::sys_query_rusage(hadron_syscall::RUSAGE_SELF).and_then(|own| {
+        sys::sys_query_rusage(hadron_syscall::RUSAGE_CHILDREN).map(|children| (own, children))
+    });
+    let mut now = Timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    let uptime = sys::sys_clock_gettime(hadron_syscall::CLOCK_MONOTONIC, (&raw mut now).cast());
+    match (times, uptime) {
+        (Ok((own, children)), Ok(())) => {
+            let tms = Tms {
+                tms_utime: nanos_to_ticks(own.user_ns),
+                tms_stime: nanos_to_ticks(own.system_ns),
+                tms_cutime: nanos_to_ticks(children.user_ns),
+                tms_cstime: nanos_to_ticks(children.system_ns),
+            };
+            // SAFETY: Caller guarantees buf is valid.
+            unsafe { buf.write(tms) };
+            now.tv_sec * CLK_TCK + now.tv_nsec / (1_000_000_000 / CLK_TCK)
+        }
+        (Err(e), _) | (_, Err(e)) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+// ---- Interval timers ---------------------------------------------------------
+
+/// `struct itimerval` from `<sys/time.h>`.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct Itimerval {
+    pub it_interval: Timeval,
+    pub it_value: Timeval,
+}
+
+/// `struct itimerspec` from `<time.h>`.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct Itimerspec {
+    pub it_interval: Timespec,
+    pub it_value: Timespec,
+}
+
+/// `struct sigevent`: the kernel reads its leading fields.
+pub use hadron_syscall::SigEvent;
+
+/// `setitimer` timer: real time, delivers `SIGALRM`.
+pub const ITIMER_REAL: i32 = 0;
+/// `setitimer` timer: user CPU time, delivers `SIGVTALRM`.
+pub const ITIMER_VIRTUAL: i32 = 1;
+/// `setitimer` timer: user and system CPU time, delivers `SIGPROF`.
+pub const ITIMER_PROF: i32 = 2;
+/// `timer_settime` flag: the value is an absolute time on the timer's clock.
+pub const TIMER_ABSTIME: i32 = 1;
+
+impl Timeval {
+    /// Converts to a kernel timespec, or `None` if out of range.
+    fn to_timespec(self) -> Option<hadron_syscall::Timespec> {
+        if !(0..1_000_000).contains(&self.tv_usec) {
+            return None;
+        }
+        Some(hadron_syscall::Timespec {
+            tv_sec: u64::try_from(self.tv_sec).ok()?,
+            tv_nsec: self.tv_usec as u64 * 1000,
+        })
+    }
+
+    fn from_timespec(ts: hadron_syscall::Timespec) -> Self {
+        Self {
+            tv_sec: ts.tv_sec as i64,
+            tv_usec: (ts.tv_nsec / 1000) as i64,
+        }
+    }
+}
+
+/// Converts a `Result` into the `0` / `-1`-and-errno convention.
+fn to_ret(result: Result<(), errno::Errno>) -> i32 {
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Arms timer `which` from `new` and returns the previous setting.
+fn set_itimer(which: i32, new: &Itimerval) -> Result<Itimerval, errno::Errno> {
+    let (Some(interval), Some(value)) = (new.it_interval.to_timespec(), new.it_value.to_timespec())
+    else {
+        return Err(errno::EINVAL);
+    };
+    let new = hadron_syscall::ITimerSpec { interval, value };
+    let mut old = hadron_syscall::ITimerSpec::default();
+    let which = usize::try_from(which).map_err(|_| errno::EINVAL)?;
+    sys::sys_setitimer(which, &new, &mut old)?;
+    Ok(Itimerval {
+        it_interval: Timeval::from_timespec(old.interval),
+        it_value: Timeval::from_timespec(old.value),
+    })
+}
+
+/// Arm or disarm interval timer `which`.
+///
+/// # Safety
+///
+/// `new_value` must be a valid pointer to an `Itimerval`. `old_value` may be
+/// null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn setitimer(
+    which: i32,
+    new_value: *const Itimerval,
+    old_value: *mut Itimerval,
+) -> i32 {
+    if new_value.is_null() {
+        errno::set_errno(errno::EFAULT);
+        return -1;
+    }
+    // SAFETY: Caller guarantees new_value is valid.
+    let result = set_itimer(which, unsafe { &*new_value }).map(|old| {
+        if !old_value.is_null() {
+            // SAFETY: Caller guarantees old_value is valid if non-null.
+            unsafe { old_value.write(old) };
+        }
+    });
+    to_ret(result)
+}
+
+/// Get the time left and interval of interval timer `which`.
+///
+/// # Safety
+///
+/// `curr_value` must be a valid pointer to an `Itimerval`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn getitimer(which: i32, curr_value: *mut Itimerval) -> i32 {
+    if curr_value.is_null() {
+        errno::set_errno(errno::EFAULT);
+        return -1;
+    }
+    let Ok(which) = usize::try_from(which) else {
+        errno::set_errno(errno::EINVAL);
+        return -1;
+    };
+    let mut cur = hadron_syscall::ITimerSpec::default();
+    let result = sys::sys_getitimer(which, &mut cur).map(|()| {
+        let cur = Itimerval {
+            it_interval: Timeval::from_timespec(cur.interval),
+            it_value: Timeval::from_timespec(cur.value),
+        };
+        // SAFETY: Caller guarantees curr_value is valid.
+        unsafe { curr_value.write(cur) };
+    });
+    to_ret(result)
+}
+
+/// Deliver `SIGALRM` after `seconds` seconds, replacing any pending alarm;
+/// 0 cancels it.
+///
+/// Returns the seconds left on the previous alarm, rounded up, or 0 if
+/// there was none.
+#[unsafe(no_mangle)]
+pub extern "C" fn alarm(seconds: u32) -> u32 {
+    let new = Itimerval {
+        it_value: Timeval {
+            tv_sec: i64::from(seconds),
+            tv_usec: 0,
+        },
+        ..Itimerval::default()
+    };
+    match set_itimer(ITIMER_REAL, &new) {
+        Ok(old) => (old.it_value.tv_sec + i64::from(old.it_value.tv_usec > 0)) as u32,
+        Err(_) => 0,
+    }
+}
+
+/// Create a POSIX timer on `clockid`.
+///
+/// A null `sevp` sends `SIGALRM` to the process with the timer ID as the
+/// signal value. `SIGEV_THREAD` is not supported.
+///
+/// # Safety
+///
+/// `sevp` must be null or a valid pointer to a `SigEvent`. `timerid` must be
+/// a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn timer_create(
+    clockid: i32,
+    sevp: *const SigEvent,
+    timerid: *mut i32,
+) -> i32 {
+    to_ret(sys::sys_timer_create(
+        clockid as usize,
+        sevp,
+        timerid.cast(),
+    ))
+}
+
+/// Arm or disarm POSIX timer `timerid`.
+///
+/// # Safety
+///
+/// `new_value` must be a valid pointer to an `Itimerspec`. `old_value` may
+/// be null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn timer_settime(
+    timerid: i32,
+    flags: i32,
+    new_value: *const Itimerspec,
+    old_value: *mut Itimerspec,
+) -> i32 {
+    to_ret(sys::sys_timer_settime(
+        timerid as usize,
+        flags as usize,
+        new_value.cast(),
+        old_value.cast(),
+    ))
+}
+
+/// Get the time left and interval of POSIX timer `timerid`.
+///
+/// # Safety
+///
+/// `curr_value` must be a valid pointer to an `Itimerspec`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn timer_gettime(timerid: i32, curr_value: *mut Itimerspec) -> i32 {
+    to_ret(sys::sys_timer_gettime(timerid as usize, curr_value.cast()))
+}
+
+/// Disarm and delete POSIX timer `timerid`.
+#[unsafe(no_mangle)]
+pub extern "C" fn timer_delete(timerid: i32) -> i32 {
+    to_ret(sys::sys_timer_delete(timerid as usize))
+}
+
+/// Get the number of expiries of POSIX timer `timerid` missed before its
+/// last signal was delivered.
+#[unsafe(no_mangle)]
+pub extern "C" fn timer_getoverrun(timerid: i32) -> i32 {
+    match sys::sys_timer_getoverrun(timerid as usize) {
+        Ok(n) => n as i32,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Create a timerfd on `clockid`, which must be `CLOCK_MONOTONIC` or
+/// `CLOCK_REALTIME`.
+#[unsafe(no_mangle)]
+pub extern "C" fn timerfd_create(clockid: i32, flags: i32) -> i32 {
+    let Some(flags) = crate::flags::posix_fd_flags_to_hadron(flags as u32, 0) else {
+        errno::set_errno(errno::EINVAL);
+        return -1;
+    };
+    match sys::sys_timerfd_create(clockid as usize, flags) {
+        Ok(fd) => fd as i32,
+        Err(e) => {
+            errno::set_errno(e);
+            -1
+        }
+    }
+}
+
+/// Arm or disarm timerfd `fd`. With `TFD_TIMER_ABSTIME` in `flags`,
+/// `new_value.it_value` is an absolute time on the timer's clock.
+///
+/// # Safety
+///
+/// `new_value` must be a valid pointer to an `Itimerspec`. `old_value` may
+/// be null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn timerfd_settime(
+    fd: i32,
+    flags: i32,
+    new_value: *const Itimerspec,
+    old_value: *mut Itimerspec,
+) -> i32 {
+    to_ret(sys::sys_timerfd_settime(
+        fd as usize,
+        flags as usize,
+        new_value.cast(),
+        old_value.cast(),
+    ))
+}
+
+/// Get the time left and interval of timerfd `fd`.
+///
+/// # Safety
+///
+/// `curr_value` must be a valid pointer to an `Itimerspec`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn timerfd_gettime(fd: i32, curr_value: *mut Itimerspec) -> i32 {
+    to_ret(sys::sys_timerfd_gettime(fd as usize, curr_value.cast()))
+}
@@ -1,8 +1,8 @@
-/* dlfcn.h — Dynamic linking stubs for Hadron libc.
+/* dlfcn.h — Dynamic loading for Hadron libc.
  *
- * Mesa is configured with -Dshared-glapi=disabled and static driver linking
- * so dlopen/dlsym are not called at runtime.  These stubs satisfy the
- * compiler when Mesa's own configure-time probes include this header.
+ * These forward to the dynamic linker (/lib/ld-hadron.so.1) through the
+ * AT_HADRON_DL auxiliary vector entry.  In a statically linked program
+ * every call fails and dlerror() explains why.
  */
 #ifndef _DLFCN_H
 #define _DLFCN_H
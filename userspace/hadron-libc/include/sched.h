@@ -1,10 +1,12 @@
-/* sched.h — POSIX scheduling API stubs for Hadron */
+/* sched.h — POSIX scheduling API for Hadron */
 #ifndef _SCHED_H
 #define _SCHED_H
 
 #include <bits/features.h>
 
 #include <sys/types.h>
+#include <stdint.h>
+#include <time.h>
 
 #ifdef __cplusplus
 extern "C" {
@@ -14,11 +16,24 @@ extern "C" {
 #define SCHED_OTHER 0
 #define SCHED_FIFO  1
 #define SCHED_RR    2
+#define SCHED_DEADLINE 6
 
 struct sched_param {
     int sched_priority;
 };
 
+/* Extended attributes for sched_setattr / sched_getattr */
+struct sched_attr {
+    uint32_t size;
+    uint32_t sched_policy;
+    uint64_t sched_flags;
+    int32_t  sched_nice;
+    uint32_t sched_priority;
+    uint64_t sched_runtime;   /* ns, SCHED_DEADLINE */
+    uint64_t sched_deadline;  /* ns, SCHED_DEADLINE */
+    uint64_t sched_period;    /* ns, SCHED_DEADLINE; 0 = sched_deadline */
+};
+
 /* CPU affinity types (used by pthreads for CPU_SET etc.) */
 typedef struct {
     unsigned long __bits[16]; /* 1024 CPUs */
@@ -44,6 +59,11 @@ int sched_getparam(pid_t pid, struct sched_param *param);
 int sched_setparam(pid_t pid, const struct sched_param *param);
 int sched_getscheduler(pid_t pid);
 int sched_setscheduler(pid_t pid, int policy, const struct sched_param *param);
+int sched_getaffinity(pid_t pid, size_t cpusetsize, cpu_set_t *mask);
+int sched_setaffinity(pid_t pid, size_t cpusetsize, const cpu_set_t *mask);
+int sched_rr_get_interval(pid_t pid, struct timespec *tp);
+int sched_setattr(pid_t pid, struct sched_attr *attr, unsigned int flags);
+int sched_getattr(pid_t pid, struct sched_attr *attr, unsigned int size, unsigned int flags);
 
 #ifdef __cplusplus
 }
@@ -6,6 +6,7 @@
 
 #include <sys/types.h>
 #include <stdint.h>
+#include <time.h>
 
 #define SIG_DFL ((void (*)(int))0)
 #define SIG_IGN ((void (*)(int))1)
@@ -33,7 +34,16 @@
 #define SIGTSTP   20
 #define SIGTTIN   21
 #define SIGTTOU   22
+#define SIGURG    23
+#define SIGXCPU   24
+#define SIGXFSZ   25
+#define SIGVTALRM 26
+#define SIGPROF   27
 #define SIGWINCH  28
+#define SIGIO     29
+#define SIGPOLL   SIGIO
+#define SIGPWR    30
+#define SIGSYS    31
 
 /* Real-time signal range (Linux) */
 #define SIGRTMIN  34
@@ -64,7 +74,43 @@ typedef void (*sighandler_t)(int);
 #define SA_NODEFER    0x40000000
 #define SA_RESETHAND  0x80000000
 
-/* siginfo_t (minimal) */
+/* si_code values */
+#define SI_USER    0
+#define SI_QUEUE   (-1)
+#define SI_TIMER   (-2)
+#define SI_KERNEL  0x80
+
+/* si_code values for SIGCHLD */
+#define CLD_EXITED    1
+#define CLD_KILLED    2
+#define CLD_STOPPED   5
+#define CLD_CONTINUED 6
+
+/* Value passed with sigqueue() */
+union sigval {
+    int   sival_int;
+    void *sival_ptr;
+};
+
+/* sigevent notification types */
+#define SIGEV_SIGNAL    0
+#define SIGEV_NONE      1
+#define SIGEV_THREAD_ID 4
+
+/* Timer expiry notification, for timer_create() */
+struct sigevent {
+    union sigval sigev_value;
+    int          sigev_signo;
+    int          sigev_notify;
+    union {
+        int      _pad[12];
+        int      _tid;
+    } _sigev_un;
+};
+
+#define sigev_notify_thread_id _sigev_un._tid
+
+/* siginfo_t */
 typedef struct {
     int      si_signo;
     int      si_errno;
@@ -73,7 +119,7 @@ typedef struct {
     uid_t    si_uid;
     int      si_status;
     void    *si_addr;
-    long     si_value;
+    union sigval si_value;
     long     _padding[8];
 } siginfo_t;
 
@@ -114,6 +160,12 @@ int  sigaddset(sigset_t *set, int signum);
 int  sigdelset(sigset_t *set, int signum);
 int  sigismember(const sigset_t *set, int signum);
 int  sigaltstack(const stack_t *ss, stack_t *old_ss);
+int  sigqueue(pid_t pid, int sig, union sigval value);
+int  sigpending(sigset_t *set);
+int  sigsuspend(const sigset_t *mask);
+int  sigtimedwait(const sigset_t *set, siginfo_t *info, const struct timespec *timeout);
+int  sigwaitinfo(const sigset_t *set, siginfo_t *info);
+int  sigwait(const sigset_t *set, int *sig);
 
 #ifdef __cplusplus
 }
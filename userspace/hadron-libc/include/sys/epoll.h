@@ -0,0 +1,47 @@
+/* sys/epoll.h — Scalable I/O readiness notification for Hadron libc */
+#ifndef _SYS_EPOLL_H
+#define _SYS_EPOLL_H
+
+#include <bits/features.h>
+#include <fcntl.h>
+#include <stdint.h>
+
+#define EPOLL_CLOEXEC O_CLOEXEC
+
+#define EPOLL_CTL_ADD 1
+#define EPOLL_CTL_DEL 2
+#define EPOLL_CTL_MOD 3
+
+#define EPOLLIN      0x0001
+#define EPOLLOUT     0x0004
+#define EPOLLERR     0x0008
+#define EPOLLHUP     0x0010
+#define EPOLLONESHOT (1u << 30)
+#define EPOLLET      (1u << 31)
+
+typedef union epoll_data {
+    void    *ptr;
+    int      fd;
+    uint32_t u32;
+    uint64_t u64;
+} epoll_data_t;
+
+struct epoll_event {
+    uint32_t     events;
+    epoll_data_t data;
+} __attribute__((packed));
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+int epoll_create(int size);
+int epoll_create1(int flags);
+int epoll_ctl(int epfd, int op, int fd, struct epoll_event *event);
+int epoll_wait(int epfd, struct epoll_event *events, int maxevents, int timeout);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* _SYS_EPOLL_H */
@@ -0,0 +1,74 @@
+/* sys/io_ring.h — Shared-memory I/O rings for Hadron libc */
+#ifndef _SYS_IO_RING_H
+#define _SYS_IO_RING_H
+
+#include <bits/features.h>
+#include <fcntl.h>
+#include <stdint.h>
+
+#define IO_RING_CLOEXEC O_CLOEXEC
+
+#define IO_OP_NOP     0
+#define IO_OP_READ    1
+#define IO_OP_WRITE   2
+#define IO_OP_PREAD   3
+#define IO_OP_PWRITE  4
+#define IO_OP_ACCEPT  5
+#define IO_OP_CONNECT 6
+#define IO_OP_POLL    7
+#define IO_OP_TIMEOUT 8
+#define IO_OP_FSYNC   9
+
+/* Run the next operation only if this one succeeds. */
+#define IO_OP_LINK (1u << 0)
+
+struct io_ring_params {
+    uint32_t sq_entries;
+    uint32_t cq_entries;
+    uint64_t ops_offset;
+    uint64_t completions_offset;
+    uint64_t size;
+};
+
+/* At offset 0 of the ring. Indices run freely; slots are index & mask. */
+struct io_ring_header {
+    uint32_t sq_head;
+    uint32_t sq_tail;
+    uint32_t sq_mask;
+    uint32_t cq_head;
+    uint32_t cq_tail;
+    uint32_t cq_mask;
+    uint32_t __reserved[10];
+};
+
+struct io_op {
+    uint8_t  opcode;
+    uint8_t  flags;
+    uint16_t __pad;
+    int32_t  fd;
+    uint64_t offset;
+    uint64_t addr;
+    uint32_t len;
+    uint32_t events;
+    uint64_t user_data;
+    uint64_t __reserved[3];
+};
+
+struct io_completion {
+    uint64_t user_data;
+    int32_t  result;
+    uint32_t __reserved;
+};
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+int io_ring_create(uint32_t entries, struct io_ring_params *params, int flags);
+int io_ring_enter(int fd, uint32_t to_submit, uint32_t min_complete, int timeout);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* _SYS_IO_RING_H */
@@ -15,6 +15,7 @@
 #define MAP_PRIVATE   0x02
 #define MAP_ANONYMOUS 0x20
 #define MAP_ANON      MAP_ANONYMOUS
+#define MAP_HUGETLB   0x40000
 
 #define MAP_FAILED ((void *)-1)
 
@@ -0,0 +1,13 @@
+/* sys/prctl.h — Process control for Hadron libc */
+#ifndef _SYS_PRCTL_H
+#define _SYS_PRCTL_H
+
+#include <bits/features.h>
+
+/* Options */
+#define PR_SET_CHILD_SUBREAPER 36
+#define PR_GET_CHILD_SUBREAPER 37
+
+int prctl(int option, ...);
+
+#endif /* _SYS_PRCTL_H */
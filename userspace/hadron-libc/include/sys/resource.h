@@ -38,6 +38,7 @@ struct rlimit {
 /* rusage flags */
 #define RUSAGE_SELF     0
 #define RUSAGE_CHILDREN (-1)
+#define RUSAGE_THREAD   1
 
 #include <time.h>
 
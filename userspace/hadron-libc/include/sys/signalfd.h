@@ -0,0 +1,25 @@
+/* sys/signalfd.h — Signal file descriptors for Hadron libc */
+#ifndef _SYS_SIGNALFD_H
+#define _SYS_SIGNALFD_H
+
+#include <bits/features.h>
+#include <fcntl.h>
+#include <signal.h>
+
+#define SFD_CLOEXEC  O_CLOEXEC
+#define SFD_NONBLOCK O_NONBLOCK
+
+/* Reading a signalfd returns one siginfo_t per accepted signal, unlike
+ * Linux's struct signalfd_siginfo. */
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+int signalfd(int fd, const sigset_t *mask, int flags);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* _SYS_SIGNALFD_H */
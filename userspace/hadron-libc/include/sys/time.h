@@ -16,6 +16,17 @@ struct timezone {
     int tz_dsttime;
 };
 
+struct itimerval {
+    struct timeval it_interval;
+    struct timeval it_value;
+};
+
+#define ITIMER_REAL    0
+#define ITIMER_VIRTUAL 1
+#define ITIMER_PROF    2
+
 int gettimeofday(struct timeval *tv, struct timezone *tz);
+int getitimer(int which, struct itimerval *curr_value);
+int setitimer(int which, const struct itimerval *new_value, struct itimerval *old_value);
 
 #endif /* _SYS_TIME_H */
@@ -0,0 +1,26 @@
+/* sys/timerfd.h — Timer file descriptors for Hadron libc */
+#ifndef _SYS_TIMERFD_H
+#define _SYS_TIMERFD_H
+
+#include <bits/features.h>
+#include <fcntl.h>
+#include <time.h>
+
+#define TFD_CLOEXEC       O_CLOEXEC
+#define TFD_NONBLOCK      O_NONBLOCK
+#define TFD_TIMER_ABSTIME 1
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+int timerfd_create(clockid_t clockid, int flags);
+int timerfd_settime(int fd, int flags, const struct itimerspec *new_value,
+                    struct itimerspec *old_value);
+int timerfd_gettime(int fd, struct itimerspec *curr_value);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* _SYS_TIMERFD_H */
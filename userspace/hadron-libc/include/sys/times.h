@@ -0,0 +1,25 @@
+/* sys/times.h — Process times for Hadron libc (POSIX.1-2001) */
+#ifndef _SYS_TIMES_H
+#define _SYS_TIMES_H
+
+#include <bits/features.h>
+#include <sys/types.h>
+
+struct tms {
+    clock_t tms_utime;   /* user time of the process */
+    clock_t tms_stime;   /* system time of the process */
+    clock_t tms_cutime;  /* user time of reaped children */
+    clock_t tms_cstime;  /* system time of reaped children */
+};
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+clock_t times(struct tms *buf);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* _SYS_TIMES_H */
@@ -11,6 +11,7 @@ typedef int           pid_t;
 typedef unsigned int  mode_t;
 typedef unsigned int  uid_t;
 typedef unsigned int  gid_t;
+typedef unsigned int  id_t;
 typedef long          time_t;
 typedef long          clock_t;
 typedef unsigned long ino_t;
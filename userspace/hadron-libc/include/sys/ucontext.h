@@ -0,0 +1,82 @@
+/* sys/ucontext.h — Signal handler context for Hadron libc */
+#ifndef _SYS_UCONTEXT_H
+#define _SYS_UCONTEXT_H
+
+#include <bits/features.h>
+#include <signal.h>
+
+/* Indices into mcontext_t.gregs (Linux x86_64 order) */
+#define REG_R8       0
+#define REG_R9       1
+#define REG_R10      2
+#define REG_R11      3
+#define REG_R12      4
+#define REG_R13      5
+#define REG_R14      6
+#define REG_R15      7
+#define REG_RDI      8
+#define REG_RSI      9
+#define REG_RBP     10
+#define REG_RBX     11
+#define REG_RDX     12
+#define REG_RAX     13
+#define REG_RCX     14
+#define REG_RSP     15
+#define REG_RIP     16
+#define REG_EFL     17
+#define REG_CSGSFS  18
+#define REG_ERR     19
+#define REG_TRAPNO  20
+#define REG_OLDMASK 21
+#define REG_CR2     22
+#define NGREG       23
+
+typedef long greg_t;
+typedef greg_t gregset_t[NGREG];
+
+/* x87/SSE state of the interrupted code, in FXSAVE format. */
+struct _libc_fpxreg {
+    unsigned short significand[4];
+    unsigned short exponent;
+    unsigned short __padding[3];
+};
+
+struct _libc_xmmreg {
+    unsigned int element[4];
+};
+
+struct _libc_fpstate {
+    unsigned short      cwd;
+    unsigned short      swd;
+    unsigned short      ftw;
+    unsigned short      fop;
+    unsigned long       rip;
+    unsigned long       rdp;
+    unsigned int        mxcsr;
+    unsigned int        mxcr_mask;
+    struct _libc_fpxreg _st[8];
+    struct _libc_xmmreg _xmm[16];
+    unsigned int        __reserved1[24];
+};
+
+typedef struct _libc_fpstate *fpregset_t;
+
+/* Registers of the interrupted code. fpregs points into the signal frame;
+ * setting it to NULL keeps the handler's FPU state on return. */
+typedef struct {
+    gregset_t  gregs;
+    fpregset_t fpregs;
+    unsigned long __reserved1[8];
+} mcontext_t;
+
+/* Third argument of an SA_SIGINFO handler. Changes to uc_mcontext and
+ * uc_sigmask take effect when the handler returns. */
+typedef struct ucontext_t {
+    unsigned long      uc_flags;
+    struct ucontext_t *uc_link;
+    stack_t            uc_stack;
+    mcontext_t         uc_mcontext;
+    sigset_t           uc_sigmask;
+} ucontext_t;
+
+#endif /* _SYS_UCONTEXT_H */
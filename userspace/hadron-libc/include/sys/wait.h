@@ -4,6 +4,8 @@
 
 #include <bits/features.h>
 
+#include <signal.h>
+#include <sys/resource.h>
 #include <sys/types.h>
 
 /* Wait status macros (Linux-compatible encoding) */
@@ -13,12 +15,28 @@
 #define WTERMSIG(s)     ((s) & 0x7F)
 #define WIFSTOPPED(s)   (((s) & 0xFF) == 0x7F)
 #define WSTOPSIG(s)     (((s) >> 8) & 0xFF)
+#define WIFCONTINUED(s) ((s) == 0xFFFF)
 
 /* Options */
-#define WNOHANG   1
-#define WUNTRACED 2
+#define WNOHANG    1
+#define WUNTRACED  2
+#define WCONTINUED 8
+
+/* waitid options */
+#define WSTOPPED   2
+#define WEXITED    4
+#define WNOWAIT    0x01000000
+
+/* waitid id types */
+typedef enum {
+    P_ALL  = 0,
+    P_PID  = 1,
+    P_PGID = 2,
+} idtype_t;
 
 pid_t wait(int *status);
 pid_t waitpid(pid_t pid, int *status, int options);
+pid_t wait4(pid_t pid, int *status, int options, struct rusage *rusage);
+int waitid(idtype_t idtype, id_t id, siginfo_t *info, int options);
 
 #endif /* _SYS_WAIT_H */
@@ -29,6 +29,10 @@ struct termios {
 #define ECHO   0x0008
 #define ICANON 0x0002
 #define ISIG   0x0001
+#define TOSTOP 0x0100
+
+/* c_cc indices */
+#define VSUSP  10
 
 int    tcgetattr(int fd, struct termios *termios_p);
 int    tcsetattr(int fd, int action, const struct termios *termios_p);
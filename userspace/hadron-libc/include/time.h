@@ -20,6 +20,15 @@ struct timeval {
     long   tv_usec;
 };
 
+struct itimerspec {
+    struct timespec it_interval;
+    struct timespec it_value;
+};
+
+typedef int timer_t;
+
+struct sigevent;
+
 struct tm {
     int tm_sec;
     int tm_min;
@@ -40,6 +49,8 @@ struct tm {
 #define CLOCK_REALTIME_COARSE    5
 #define CLOCK_MONOTONIC_COARSE   6
 
+#define TIMER_ABSTIME 1
+
 #ifdef __cplusplus
 extern "C" {
 #endif
@@ -53,6 +64,12 @@ struct tm *gmtime(const time_t *timep);
 struct tm *localtime(const time_t *timep);
 time_t mktime(struct tm *tm);
 size_t strftime(char *s, size_t max, const char *format, const struct tm *tm);
+int    timer_create(clockid_t clockid, struct sigevent *sevp, timer_t *timerid);
+int    timer_settime(timer_t timerid, int flags, const struct itimerspec *new_value,
+                     struct itimerspec *old_value);
+int    timer_gettime(timer_t timerid, struct itimerspec *curr_value);
+int    timer_delete(timer_t timerid);
+int    timer_getoverrun(timer_t timerid);
 
 #ifdef __cplusplus
 }
@@ -0,0 +1,7 @@
+/* ucontext.h — User context for Hadron libc */
+#ifndef _UCONTEXT_H
+#define _UCONTEXT_H
+
+#include <sys/ucontext.h>
+
+#endif /* _UCONTEXT_H */
@@ -56,6 +56,7 @@ pid_t getppid(void);
 pid_t fork(void);
 pid_t vfork(void);
 void  _exit(int status) __attribute__((noreturn));
+int   nice(int inc);
 
 /* ---- Working directory (POSIX.1-1990) -------------------------------------- */
 
@@ -74,6 +75,14 @@ uid_t getuid(void);
 uid_t geteuid(void);
 gid_t getgid(void);
 gid_t getegid(void);
+int   setuid(uid_t uid);
+int   setgid(gid_t gid);
+int   seteuid(uid_t euid);
+int   setegid(gid_t egid);
+int   setresuid(uid_t ruid, uid_t euid, uid_t suid);
+int   setresgid(gid_t rgid, gid_t egid, gid_t sgid);
+int   getgroups(int size, gid_t list[]);
+int   setgroups(size_t size, const gid_t *list);
 
 /* ---- Process groups / sessions (POSIX.1-1990) ------------------------------ */
 
@@ -83,6 +92,7 @@ pid_t setsid(void);
 
 /* ---- Sleep (POSIX.1-1990) -------------------------------------------------- */
 
+unsigned int alarm(unsigned int seconds);
 unsigned int sleep(unsigned int seconds);
 int          usleep(unsigned int usec);
 
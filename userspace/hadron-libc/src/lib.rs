@@ -12,8 +12,10 @@ extern crate hadron_libc_core;
 // Re-export all C ABI modules to ensure they're linked into the staticlib.
 pub use hadron_libc_core::alloc;
 pub use hadron_libc_core::atexit;
+pub use hadron_libc_core::auxv;
 pub use hadron_libc_core::ctype;
 pub use hadron_libc_core::dirent;
+pub use hadron_libc_core::dlfcn;
 pub use hadron_libc_core::env;
 pub use hadron_libc_core::errno;
 pub use hadron_libc_core::flags;
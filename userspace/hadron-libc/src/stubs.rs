@@ -97,6 +97,7 @@ pub extern "C" fn labs(x: i64) -> i64 {
 
 // ---- System configuration ---------------------------------------------------
 
+const SC_CLK_TCK: i32 = 2;
 const SC_PAGE_SIZE: i32 = 30;
 const SC_NPROCESSORS_ONLN: i32 = 84;
 const SC_NPROCESSORS_CONF: i32 = 83;
@@ -107,6 +108,7 @@ const PAGE_SIZE: i64 = 4096;
 #[unsafe(no_mangle)]
 pub extern "C" fn sysconf(name: i32) -> i64 {
     match name {
+        SC_CLK_TCK => hadron_libc_core::time::CLK_TCK,
         SC_PAGE_SIZE => PAGE_SIZE,
         SC_NPROCESSORS_ONLN | SC_NPROCESSORS_CONF => 1,
         // ~64 MiB worth of pages — reasonable for early Hadron
@@ -165,66 +167,10 @@ pub unsafe extern "C" fn uname(buf: *mut u8) -> i32 {
     0
 }
 
-// ---- Dynamic linking stubs --------------------------------------------------
-
-/// `dlopen` — stub (no dynamic linker on Hadron).
-#[unsafe(no_mangle)]
-pub extern "C" fn dlopen(_filename: *const u8, _flags: i32) -> *mut u8 {
-    core::ptr::null_mut()
-}
-
-/// `dlsym` — stub (no dynamic linker on Hadron).
-#[unsafe(no_mangle)]
-pub extern "C" fn dlsym(_handle: *mut u8, _symbol: *const u8) -> *mut u8 {
-    core::ptr::null_mut()
-}
-
-/// `dlclose` — stub.
-#[unsafe(no_mangle)]
-pub extern "C" fn dlclose(_handle: *mut u8) -> i32 {
-    0
-}
-
-static DLERROR_MSG: &[u8] = b"dynamic linking not supported on Hadron\0";
-
-/// `dlerror` — return static error message.
-#[unsafe(no_mangle)]
-pub extern "C" fn dlerror() -> *const u8 {
-    DLERROR_MSG.as_ptr()
-}
-
 // ---- Scheduling stubs -------------------------------------------------------
 
-#[unsafe(no_mangle)]
-pub extern "C" fn sched_getaffinity(_pid: i32, cpusetsize: usize, mask: *mut u8) -> i32 {
-    if mask.is_null() || cpusetsize == 0 {
-        errno::set_errno(ENOSYS);
-        return -1;
-    }
-    // Report 1 CPU available.
-    unsafe {
-        hadron_libc_core::string::memset(mask, 0, cpusetsize);
-        *mask = 1; // CPU 0 set
-    }
-    0
-}
-
-#[unsafe(no_mangle)]
-pub extern "C" fn sched_setaffinity(_pid: i32, _cpusetsize: usize, _mask: *const u8) -> i32 {
-    0
-}
-
 // sched_yield — moved to core/src/pthread.rs
-
-#[unsafe(no_mangle)]
-pub extern "C" fn sched_get_priority_max(_policy: i32) -> i32 {
-    0
-}
-
-#[unsafe(no_mangle)]
-pub extern "C" fn sched_get_priority_min(_policy: i32) -> i32 {
-    0
-}
+// sched_get_priority_max, sched_get_priority_min — moved to core/src/process.rs
 
 /// `__sched_cpucount` — count set bits in a CPU set.
 #[unsafe(no_mangle)]
@@ -246,30 +192,6 @@ pub extern "C" fn access(_path: *const u8, _mode: i32) -> i32 {
     0
 }
 
-/// `getuid` — get user ID.
-#[unsafe(no_mangle)]
-pub extern "C" fn getuid() -> u32 {
-    0 // root
-}
-
-/// `geteuid` — get effective user ID.
-#[unsafe(no_mangle)]
-pub extern "C" fn geteuid() -> u32 {
-    0 // root
-}
-
-/// `getgid` — get group ID.
-#[unsafe(no_mangle)]
-pub extern "C" fn getgid() -> u32 {
-    0
-}
-
-/// `getegid` — get effective group ID.
-#[unsafe(no_mangle)]
-pub extern "C" fn getegid() -> u32 {
-    0
-}
-
 /// `flock` — stub for file locking.
 #[unsafe(no_mangle)]
 pub extern "C" fn flock(_fd: i32, _operation: i32) -> i32 {
@@ -481,20 +403,6 @@ pub unsafe extern "C" fn setrlimit(_resource: i32, _rlim: *const [u64; 2]) -> i3
     0 // Pretend success.
 }
 
-/// `getrusage` — get resource usage.
-///
-/// # Safety
-///
-/// `usage` must be a valid pointer to a `rusage` struct.
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn getrusage(_who: i32, usage: *mut u8) -> i32 {
-    if usage.is_null() {
-        return stub_err();
-    }
-    unsafe { core::ptr::write_bytes(usage, 0, 136) }; // zeroed rusage
-    0
-}
-
 // ---- POSIX semaphores -------------------------------------------------------
 
 /// `sem_init` — initialize an unnamed semaphore.
@@ -797,16 +705,6 @@ pub unsafe extern "C" fn sigismember(set: *const u64, signum: i32) -> i32 {
     ((unsafe { *set } >> (signum - 1)) & 1) as i32
 }
 
-/// `sigaltstack` — set or get the alternate signal stack.
-///
-/// # Safety
-///
-/// Parameters must be valid.
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn sigaltstack(_ss: *const u8, _old_ss: *mut u8) -> i32 {
-    0
-}
-
 // ---- `realpath` — resolve a pathname ----------------------------------------
 
 /// `realpath` — resolve a pathname.
@@ -945,52 +843,6 @@ fn format_ipv4(buf: &mut [u8; 16], a: u8, b: u8, c: u8, d: u8) -> &[u8] {
     &buf[..p]
 }
 
-// ---- Password database stubs ------------------------------------------------
-
-#[unsafe(no_mangle)]
-pub extern "C" fn getpwnam(_name: *const u8) -> *mut u8 {
-    core::ptr::null_mut()
-}
-
-#[unsafe(no_mangle)]
-pub extern "C" fn getpwuid(_uid: u32) -> *mut u8 {
-    core::ptr::null_mut()
-}
-
-/// # Safety
-/// All pointers must be valid.
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn getpwnam_r(
-    _name: *const u8,
-    _pwd: *mut u8,
-    _buf: *mut u8,
-    _buflen: usize,
-    result: *mut *mut u8,
-) -> i32 {
-    if !result.is_null() {
-        unsafe { *result = core::ptr::null_mut() };
-    }
-    errno::set_errno(ENOSYS);
-    ENOSYS.0
-}
-
-/// # Safety
-/// All pointers must be valid.
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn getpwuid_r(
-    _uid: u32,
-    _pwd: *mut u8,
-    _buf: *mut u8,
-    _buflen: usize,
-    result: *mut *mut u8,
-) -> i32 {
-    if !result.is_null() {
-        unsafe { *result = core::ptr::null_mut() };
-    }
-    errno::set_errno(ENOSYS);
-    ENOSYS.0
-}
-
 // ---- Resolver stubs ---------------------------------------------------------
 
 #[unsafe(no_mangle)]
@@ -1481,7 +1333,7 @@ pub unsafe extern "C" fn _Exit(status: i32) -> ! {
 // ---- syscall (raw syscall passthrough) ---------------------------------------
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn syscall(number: i64, ...) -> i64 {
+pub unsafe extern "C" fn syscall(number: i64, _: ...) -> i64 {
     // We can't forward variadic args in safe Rust; just return ENOSYS.
     -38 // -ENOSYS
 }
@@ -1509,24 +1361,17 @@ pub unsafe extern "C" fn execvp(_file: *const u8, _argv: *const *const u8) -> i3
 
 // execl / execlp / execle are variadic; stub_err via raw syscall pattern.
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn execl(_path: *const u8, _arg: *const u8, ...) -> i32 {
-    stub_err()
-}
-
-#[unsafe(no_mangle)]
-pub unsafe extern "C" fn execlp(_file: *const u8, _arg: *const u8, ...) -> i32 {
+pub unsafe extern "C" fn execl(_path: *const u8, _arg: *const u8, _: ...) -> i32 {
     stub_err()
 }
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn execle(_path: *const u8, _arg: *const u8, ...) -> i32 {
+pub unsafe extern "C" fn execlp(_file: *const u8, _arg: *const u8, _: ...) -> i32 {
     stub_err()
 }
 
-// ---- wait --------------------------------------------------------------------
-
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn wait(_status: *mut i32) -> i32 {
+pub unsafe extern "C" fn execle(_path: *const u8, _arg: *const u8, _: ...) -> i32 {
     stub_err()
 }
 
@@ -1697,7 +1542,7 @@ pub unsafe extern "C" fn wcscmp(s1: *const i32, s2: *const i32) -> i32 {
 // ---- sem_open / sem_close / sem_unlink (named semaphores) -------------------
 
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn sem_open(_name: *const u8, _oflag: i32, ...) -> *mut u8 {
+pub unsafe extern "C" fn sem_open(_name: *const u8, _oflag: i32, _: ...) -> *mut u8 {
     // Named semaphores require a kernel-side namespace; not implemented.
     core::ptr::null_mut()
 }
@@ -1732,9 +1577,19 @@ pub unsafe extern "C" fn pthread_kill(_thread: u64, _sig: i32) -> i32 {
     stub_err()
 }
 
+/// `pthread_sigmask` — `sigprocmask` for the calling thread, returning an
+/// error number instead of setting `errno`.
+///
+/// # Safety
+///
+/// Same as `sigprocmask`.
 #[unsafe(no_mangle)]
-pub unsafe extern "C" fn pthread_sigmask(_how: i32, _set: *const u8, _oldset: *mut u8) -> i32 {
-    0
+pub unsafe extern "C" fn pthread_sigmask(how: i32, set: *const u64, oldset: *mut u64) -> i32 {
+    if unsafe { hadron_libc_core::signal::sigprocmask(how, set, oldset) } == 0 {
+        0
+    } else {
+        errno::get_errno().0
+    }
 }
 
 #[unsafe(no_mangle)]
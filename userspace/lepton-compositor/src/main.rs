@@ -583,7 +583,7 @@ impl Compositor {
         lepton_syslib::hadron_syscall::wrappers::sys_event_wait_many(
             poll_fds.as_mut_ptr() as usize,
             poll_fds.len(),
-            FRAME_MS as usize,
+            FRAME_MS as usize * 1_000_000,
         );
 
         // ── accept new connections ───────────────────────────────────────────
@@ -222,7 +222,7 @@ impl Display {
         let height = DEFAULT_HEIGHT;
         let shm_size = width as usize * height as usize * BPP;
 
-        let shm_fd = match sys::mem_create_shared(shm_size) {
+        let shm_fd = match sys::mem_create_shared(shm_size, 0) {
             Ok(fd) => fd,
             Err(_) => {
                 sys::close(socket_fd);
@@ -8,7 +8,8 @@ use hadron_syscall::{
 };
 
 pub use hadron_syscall::{
-    SIG_DFL, SIG_IGN, SIGCHLD, SIGINT, SIGKILL, SIGPIPE, SIGQUIT, SIGSEGV, SIGSTOP, SIGTERM,
+    SIG_DFL, SIG_IGN, SIGCHLD, SIGCONT, SIGINT, SIGKILL, SIGPIPE, SIGQUIT, SIGSEGV, SIGSTOP,
+    SIGTERM, SIGTSTP, SIGTTIN, SIGTTOU, WCONTINUED, WNOHANG, WUNTRACED,
 };
 
 // ── Functions ─────────────────────────────────────────────────────────
@@ -89,6 +90,8 @@ pub fn spawn_with_env(path: &str, argv: &[&str], envp: &[&str]) -> isize {
         fd_map_count: 0,
         cwd_ptr: 0,
         cwd_len: 0,
+        uid: hadron_syscall::ID_UNCHANGED,
+        gid: hadron_syscall::ID_UNCHANGED,
     };
 
     wrappers::sys_task_spawn(
@@ -102,11 +105,64 @@ pub fn spawn_with_env(path: &str, argv: &[&str], envp: &[&str]) -> isize {
 /// If `pid` is 0, waits for any child. Returns the child PID on success.
 /// If `status_out` is `Some`, the child's exit status is written there.
 pub fn waitpid(pid: u32, status_out: Option<&mut u64>) -> isize {
+    waitpid_flags(pid, status_out, 0)
+}
+
+/// Wait for a child process to change state.
+///
+/// Like [`waitpid`], with `flags` a bitmask of [`WNOHANG`], [`WUNTRACED`]
+/// and [`WCONTINUED`]. Returns 0 if `WNOHANG` is set and no child has
+/// changed state yet. Decode the status with [`wifexited`] and friends.
+pub fn waitpid_flags(pid: u32, status_out: Option<&mut u64>, flags: usize) -> isize {
     let status_ptr = match status_out {
         Some(s) => s as *mut u64 as usize,
         None => 0,
     };
-    wrappers::sys_task_wait(pid as usize, status_ptr, 0)
+    wrappers::sys_task_wait(pid as usize, status_ptr, flags)
+}
+
+/// Mark (or unmark) the calling process as a child subreaper.
+///
+/// Orphaned descendants are then reparented to it instead of init, so a
+/// service manager can reap the daemons it starts.
+pub fn set_child_subreaper(enable: bool) -> isize {
+    wrappers::sys_child_set_subreaper(usize::from(enable))
+}
+
+/// Returns `true` if the wait status reports a normal exit.
+pub const fn wifexited(status: u64) -> bool {
+    status & 0x7F == 0
+}
+
+/// Exit code of a child for which [`wifexited`] is `true`.
+pub const fn wexitstatus(status: u64) -> u64 {
+    (status >> 8) & 0xFF
+}
+
+/// Returns `true` if the wait status reports death by a signal.
+pub const fn wifsignaled(status: u64) -> bool {
+    let sig = status & 0x7F;
+    sig != 0 && sig != 0x7F
+}
+
+/// Terminating signal of a child for which [`wifsignaled`] is `true`.
+pub const fn wtermsig(status: u64) -> usize {
+    (status & 0x7F) as usize
+}
+
+/// Returns `true` if the wait status reports a stopped child.
+pub const fn wifstopped(status: u64) -> bool {
+    status & 0xFF == 0x7F
+}
+
+/// Stop signal of a child for which [`wifstopped`] is `true`.
+pub const fn wstopsig(status: u64) -> usize {
+    ((status >> 8) & 0xFF) as usize
+}
+
+/// Returns `true` if the wait status reports a child resumed by `SIGCONT`.
+pub const fn wifcontinued(status: u64) -> bool {
+    status == 0xFFFF
 }
 
 /// Send a signal to a process.
@@ -124,14 +180,17 @@ pub fn kill(pid: u32, signum: usize) -> isize {
 ///
 /// Returns the previous handler on success, or a negative errno on failure.
 pub fn signal(signum: usize, handler: usize) -> isize {
-    let mut old_handler: u64 = 0;
+    let act = hadron_syscall::SigAction {
+        handler,
+        ..hadron_syscall::SigAction::default()
+    };
+    let mut old = hadron_syscall::SigAction::default();
     let ret = wrappers::sys_task_sigaction(
         signum,
-        handler,
-        0, // flags
-        &mut old_handler as *mut u64 as usize,
+        &act as *const hadron_syscall::SigAction as usize,
+        &mut old as *mut hadron_syscall::SigAction as usize,
     );
-    if ret < 0 { ret } else { old_handler as isize }
+    if ret < 0 { ret } else { old.handler as isize }
 }
 
 /// Set process group ID.
@@ -236,10 +295,18 @@ pub fn query_kernel_version() -> Option<KernelVersionInfo> {
 
 /// Query process table statistics.
 pub fn query_processes() -> Option<ProcessInfo> {
+    query_process(0)
+}
+
+/// Query process table statistics along with the memory and CPU usage of
+/// process `pid` (0 = the calling process).
+///
+/// Returns `None` if `pid` does not name a live process.
+pub fn query_process(pid: u32) -> Option<ProcessInfo> {
     let mut info = core::mem::MaybeUninit::<ProcessInfo>::uninit();
     let ret = wrappers::sys_query(
         QUERY_PROCESSES as usize,
-        0, // sub_id (reserved)
+        pid as usize,
         info.as_mut_ptr() as usize,
         core::mem::size_of::<ProcessInfo>(),
     );
@@ -417,9 +484,10 @@ pub fn channel_recv_fd(ch_fd: usize, buf: &mut [u8]) -> Result<(usize, Option<us
 
 /// Create a shared memory object of the given size.
 ///
+/// `flags` may contain `MAP_HUGE` to back the object with 2 MiB pages.
 /// Returns a file descriptor on success. The memory is zero-filled.
-pub fn mem_create_shared(size: usize) -> Result<usize, isize> {
-    let ret = wrappers::sys_mem_create_shared(size);
+pub fn mem_create_shared(size: usize, flags: usize) -> Result<usize, isize> {
+    let ret = wrappers::sys_mem_create_shared(size, flags);
     if ret < 0 { Err(ret) } else { Ok(ret as usize) }
 }
 
@@ -501,6 +569,8 @@ pub fn spawn_with_fds(path: &str, argv: &[&str], fd_map: &[(u32, u32)]) -> isize
         fd_map_count: fd_count,
         cwd_ptr: 0,
         cwd_len: 0,
+        uid: hadron_syscall::ID_UNCHANGED,
+        gid: hadron_syscall::ID_UNCHANGED,
     };
 
     wrappers::sys_task_spawn(
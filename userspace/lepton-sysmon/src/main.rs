@@ -1,5 +1,6 @@
-//! System dashboard — renders memory, process count, uptime, and kernel
-//! version to a compositor surface in a 1-second refresh loop.
+//! System dashboard — renders memory, process count, uptime, kernel
+//! version, and per-process CPU usage to a compositor surface in a 1-second
+//! refresh loop.
 //!
 //! Press `q` to quit. When running under the compositor, receives keyboard
 //! events via the display protocol. Falls back to direct framebuffer access
@@ -10,7 +11,8 @@
 
 use lepton_display_client::{Display, Event};
 use lepton_gfx::Surface;
-use lepton_syslib::{println, sys};
+use lepton_syslib::hadron_syscall::DirEntryInfo;
+use lepton_syslib::{io, println, sys};
 
 // ── Colors ───────────────────────────────────────────────────────────
 
@@ -31,6 +33,9 @@ const SECTION_Y: u32 = 30;
 const LINE_H: u32 = 18;
 const BAR_H: u32 = 14;
 
+/// Maximum number of processes listed in the CPU table.
+const MAX_CPU_ROWS: usize = 16;
+
 // ── No-alloc number formatting ───────────────────────────────────────
 
 /// Format a `u64` into a stack buffer, returning the ASCII slice.
@@ -55,8 +60,136 @@ fn write_padded2(buf: &mut [u8; 2], val: u64) {
     buf[1] = b'0' + (val % 10) as u8;
 }
 
+/// Append `bytes` to `label` at `*pos`, truncating at the end of `label`.
+fn push_bytes(label: &mut [u8], pos: &mut usize, bytes: &[u8]) {
+    for &b in bytes {
+        if *pos < label.len() {
+            label[*pos] = b;
+            *pos += 1;
+        }
+    }
+}
+
+/// Append `val` right-aligned in a field of `width` characters.
+fn push_right(label: &mut [u8], pos: &mut usize, val: u64, width: usize) {
+    let mut nbuf = [0u8; 20];
+    let digits = write_u64(&mut nbuf, val);
+    for _ in digits.len()..width {
+        push_bytes(label, pos, b" ");
+    }
+    push_bytes(label, pos, digits.as_bytes());
+}
+
+// ── CPU usage ────────────────────────────────────────────────────────
+
+/// CPU usage of one process over the last refresh interval.
+#[derive(Clone, Copy)]
+struct CpuRow {
+    /// Process ID.
+    pid: u32,
+    /// User plus system time since the process started, in nanoseconds.
+    cpu_ns: u64,
+    /// Share of one CPU used since the previous sample, in tenths of a percent.
+    permille: u64,
+}
+
+impl CpuRow {
+    const EMPTY: Self = Self {
+        pid: 0,
+        cpu_ns: 0,
+        permille: 0,
+    };
+}
+
+/// Per-process CPU samples, kept between refreshes to compute usage.
+struct CpuTable {
+    rows: [CpuRow; MAX_CPU_ROWS],
+    len: usize,
+    uptime_ns: u64,
+}
+
+impl CpuTable {
+    const fn new() -> Self {
+        Self {
+            rows: [CpuRow::EMPTY; MAX_CPU_ROWS],
+            len: 0,
+            uptime_ns: 0,
+        }
+    }
+
+    /// Returns the CPU time the previous sample recorded for `pid`.
+    fn previous(&self, pid: u32) -> Option<u64> {
+        self.rows[..self.len]
+            .iter()
+            .find(|row| row.pid == pid)
+            .map(|row| row.cpu_ns)
+    }
+
+    /// Samples the CPU time of every process listed in `/proc`.
+    fn sample(&mut self) {
+        let Some(uptime) = sys::query_uptime() else {
+            return;
+        };
+        let elapsed = uptime.uptime_ns.saturating_sub(self.uptime_ns);
+
+        let mut entries = [DirEntryInfo {
+            inode_type: 0,
+            name_len: 0,
+            _pad: [0; 2],
+            name: [0; 60],
+        }; 64];
+        let fd = io::open("/proc", 0);
+        if fd < 0 {
+            return;
+        }
+        let count = io::readdir(fd as usize, &mut entries);
+        io::close(fd as usize);
+        if count < 0 {
+            return;
+        }
+
+        let mut rows = [CpuRow::EMPTY; MAX_CPU_ROWS];
+        let mut len = 0;
+        for entry in &entries[..count as usize] {
+            if len == rows.len() {
+                break;
+            }
+            let name = &entry.name[..entry.name_len as usize];
+            let Some(pid) = core::str::from_utf8(name)
+                .ok()
+                .and_then(|n| n.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            let Some(info) = sys::query_process(pid) else {
+                continue;
+            };
+            let cpu_ns = info.user_ns + info.system_ns;
+            let permille = match self.previous(pid) {
+                Some(prev) if elapsed > 0 => cpu_ns.saturating_sub(prev) * 1000 / elapsed,
+                _ => 0,
+            };
+            rows[len] = CpuRow {
+                pid,
+                cpu_ns,
+                permille,
+            };
+            len += 1;
+        }
+
+        self.rows = rows;
+        self.len = len;
+        self.uptime_ns = uptime.uptime_ns;
+    }
+
+    /// Rows from the latest sample.
+    fn rows(&self) -> &[CpuRow] {
+        &self.rows[..self.len]
+    }
+}
+
 /// Render the dashboard onto a surface.
-fn render(s: &mut Surface<'_>) {
+fn render(s: &mut Surface<'_>, cpu: &CpuTable) {
     let width = s.width();
 
     s.fill(BLACK);
@@ -260,6 +393,29 @@ fn render(s: &mut Surface<'_>) {
         y += LINE_H;
     }
 
+    // ── CPU usage ────────────────────────────────────────────────────
+    y += 4;
+    s.draw_str(MARGIN_X, y, "  PID   CPU%       TIME", WHITE, BLACK);
+    y += LINE_H;
+    for row in cpu.rows() {
+        let mut label = [0u8; 40];
+        let mut pos = 0;
+        push_right(&mut label, &mut pos, u64::from(row.pid), 5);
+        push_right(&mut label, &mut pos, row.permille / 10, 5);
+        push_bytes(&mut label, &mut pos, b".");
+        push_right(&mut label, &mut pos, row.permille % 10, 1);
+        let centis = row.cpu_ns / 10_000_000;
+        push_right(&mut label, &mut pos, centis / 100, 7);
+        push_bytes(&mut label, &mut pos, b".");
+        let mut cbuf = [0u8; 2];
+        write_padded2(&mut cbuf, centis % 100);
+        push_bytes(&mut label, &mut pos, &cbuf);
+        push_bytes(&mut label, &mut pos, b"s");
+        let label_str = core::str::from_utf8(&label[..pos]).expect("ASCII label");
+        s.draw_str(MARGIN_X, y, label_str, GREY, BLACK);
+        y += LINE_H;
+    }
+
     // ── Quit hint ────────────────────────────────────────────────────
     let height = s.height();
     let hint_y = height.saturating_sub(20);
@@ -279,9 +435,11 @@ pub extern "C" fn main(_args: &[&str]) -> i32 {
         return 1;
     };
 
+    let mut cpu = CpuTable::new();
     loop {
+        cpu.sample();
         let mut surface = display.surface();
-        render(&mut surface);
+        render(&mut surface, &cpu);
         drop(surface);
         display.commit();
 
@@ -1,9 +1,9 @@
 //! hsh — Hadron SHell.
 //!
 //! A minimal interactive shell supporting pipelines (`cmd1 | cmd2`),
-//! background jobs (`cmd &`), double-quoted strings, I/O redirections
-//! (`>`, `<`, `>>`), `$VAR` expansion, PATH-based command resolution,
-//! `cd`, `export`, and built-in commands.
+//! job control (`cmd &`, Ctrl+Z, `jobs`, `fg`, `bg`), double-quoted
+//! strings, I/O redirections (`>`, `<`, `>>`), `$VAR` expansion,
+//! PATH-based command resolution, `cd`, `export`, and built-in commands.
 
 #![no_std]
 #![no_main]
@@ -327,12 +327,18 @@ fn parse<'a>(tokens: &[Token<'a>], token_count: usize) -> Pipeline<'a> {
 
 // ── Job Tracking ────────────────────────────────────────────────────
 
-/// A background job entry.
+/// A job: one pipeline running in its own process group.
 struct Job {
     /// Whether this slot is active.
     active: bool,
-    /// Process ID of the background job.
-    pid: u32,
+    /// Whether the job is stopped (Ctrl+Z, SIGTSTP/SIGTTIN/SIGTTOU).
+    stopped: bool,
+    /// Process group ID of the pipeline.
+    pgid: u32,
+    /// PIDs of pipeline stages that have not exited yet.
+    pids: [u32; MAX_STAGES],
+    /// Number of valid entries in `pids`.
+    pid_count: usize,
     /// Command name (truncated to fit).
     cmd: [u8; 64],
     /// Command name length.
@@ -343,14 +349,41 @@ impl Job {
     const fn empty() -> Self {
         Self {
             active: false,
-            pid: 0,
+            stopped: false,
+            pgid: 0,
+            pids: [0; MAX_STAGES],
+            pid_count: 0,
             cmd: [0; 64],
             cmd_len: 0,
         }
     }
+
+    fn cmd(&self) -> &str {
+        core::str::from_utf8(&self.cmd[..self.cmd_len]).unwrap_or("???")
+    }
+
+    /// Forget `pid` after it has been reaped. Clears the slot once every
+    /// stage has exited.
+    fn remove_pid(&mut self, pid: u32) {
+        if let Some(i) = self.pids[..self.pid_count].iter().position(|&p| p == pid) {
+            self.pids[i] = self.pids[self.pid_count - 1];
+            self.pid_count -= 1;
+        }
+        if self.pid_count == 0 {
+            self.active = false;
+        }
+    }
+
+    /// Send `SIGCONT` to every remaining stage.
+    fn resume(&mut self) {
+        self.stopped = false;
+        for &pid in &self.pids[..self.pid_count] {
+            sys::kill(pid, sys::SIGCONT);
+        }
+    }
 }
 
-/// Background job table.
+/// Job table, indexed by job number minus one.
 struct JobTable {
     jobs: [Job; MAX_JOBS],
 }
@@ -363,11 +396,14 @@ impl JobTable {
     }
 
     /// Add a job. Returns the job number (1-based) or 0 if full.
-    fn add(&mut self, pid: u32, cmd: &str) -> usize {
+    fn add(&mut self, pgid: u32, pids: &[u32], cmd: &str) -> usize {
         for (i, slot) in self.jobs.iter_mut().enumerate() {
             if !slot.active {
                 slot.active = true;
-                slot.pid = pid;
+                slot.stopped = false;
+                slot.pgid = pgid;
+                slot.pids[..pids.len()].copy_from_slice(pids);
+                slot.pid_count = pids.len();
                 let copy_len = cmd.len().min(64);
                 slot.cmd[..copy_len].copy_from_slice(&cmd.as_bytes()[..copy_len]);
                 slot.cmd_len = copy_len;
@@ -377,14 +413,80 @@ impl JobTable {
         0
     }
 
+    /// Look up a job for `fg`/`bg`: the given job number (`%` prefix
+    /// optional), or the most recent job if `arg` is `None`.
+    fn find(&self, arg: Option<&str>) -> Option<usize> {
+        match arg {
+            Some(arg) => {
+                let n = parse_usize(arg.strip_prefix('%').unwrap_or(arg))?;
+                let idx = n.checked_sub(1)?;
+                self.jobs.get(idx).filter(|j| j.active).map(|_| idx)
+            }
+            None => self.jobs.iter().rposition(|j| j.active),
+        }
+    }
+
     /// List all active jobs.
     fn list(&self) {
         for (i, job) in self.jobs.iter().enumerate() {
             if job.active {
-                let cmd = core::str::from_utf8(&job.cmd[..job.cmd_len]).unwrap_or("???");
-                println!("[{}] {} {}", i + 1, job.pid, cmd);
+                let state = if job.stopped { "Stopped" } else { "Running" };
+                println!("[{}] {} {}  {}", i + 1, job.pgid, state, job.cmd());
+            }
+        }
+    }
+
+    /// Reap background jobs that exited or stopped without blocking,
+    /// reporting each change. Called before every prompt.
+    fn reap(&mut self) {
+        for (i, job) in self.jobs.iter_mut().enumerate() {
+            let mut k = 0;
+            while job.active && k < job.pid_count {
+                let pid = job.pids[k];
+                let mut status: u64 = 0;
+                let ret = sys::waitpid_flags(pid, Some(&mut status), sys::WNOHANG | sys::WUNTRACED);
+                if ret <= 0 {
+                    k += 1;
+                } else if sys::wifstopped(status) {
+                    if !job.stopped {
+                        job.stopped = true;
+                        println!("[{}]+ Stopped  {}", i + 1, job.cmd());
+                    }
+                    k += 1;
+                } else {
+                    job.remove_pid(pid);
+                    if !job.active {
+                        println!("[{}]  Done  {}", i + 1, job.cmd());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Run job `idx` in the foreground: hand it the terminal and wait until
+    /// every stage exits or one of them stops, then take the terminal back.
+    fn wait_foreground(&mut self, idx: usize, shell_pgid: u32) {
+        let job = &mut self.jobs[idx];
+        sys::tcsetpgrp(STDIN, job.pgid);
+
+        while job.active {
+            let pid = job.pids[0];
+            let mut status: u64 = 0;
+            let ret = sys::waitpid_flags(pid, Some(&mut status), sys::WUNTRACED);
+            if ret < 0 {
+                // Already reaped elsewhere; drop it.
+                job.remove_pid(pid);
+            } else if sys::wifstopped(status) {
+                job.stopped = true;
+                println!();
+                println!("[{}]+ Stopped  {}", idx + 1, job.cmd());
+                break;
+            } else {
+                job.remove_pid(pid);
             }
         }
+
+        sys::tcsetpgrp(STDIN, shell_pgid);
     }
 }
 
@@ -445,13 +547,13 @@ fn resolve_command<'a>(cmd: &str, path_buf: &'a mut [u8; PATH_BUF_SIZE]) -> Opti
 // ── Pipeline Execution ──────────────────────────────────────────────
 
 /// Execute a parsed pipeline.
-fn execute(pipeline: &Pipeline<'_>, jobs: &mut JobTable) {
+fn execute(pipeline: &Pipeline<'_>, jobs: &mut JobTable, shell_pgid: u32) {
     let n = pipeline.stage_count;
 
     // Check if the first (and only) stage is a built-in.
     if n == 1 && pipeline.stages[0].argc > 0 {
         let stage = &pipeline.stages[0];
-        if execute_builtin(stage.args[0], &stage.args[..stage.argc], jobs) {
+        if execute_builtin(stage.args[0], &stage.args[..stage.argc], jobs, shell_pgid) {
             return;
         }
     }
@@ -609,34 +711,27 @@ fn execute(pipeline: &Pipeline<'_>, jobs: &mut JobTable) {
     io::close(saved_stdin);
     io::close(saved_stdout);
 
-    if pipeline.background {
-        // Record background jobs.
-        for &pid in &child_pids[..spawned] {
-            let cmd = pipeline.stages[0].args[0];
-            let job_num = jobs.add(pid, cmd);
-            if job_num > 0 {
-                println!("[{}] {}", job_num, pid);
-            }
-        }
-    } else if spawned > 0 {
-        // Set the pipeline's process group as the terminal foreground group.
-        sys::tcsetpgrp(STDIN, pipeline_pgid);
-
-        // Wait for all children.
+    if spawned == 0 {
+        return;
+    }
+    let cmd = pipeline.stages[0].args[0];
+    let job_num = jobs.add(pipeline_pgid, &child_pids[..spawned], cmd);
+    if job_num == 0 {
+        println!("hsh: job table full, waiting for {}", cmd);
         for &pid in &child_pids[..spawned] {
-            let mut status: u64 = 0;
-            sys::waitpid(pid, Some(&mut status));
+            sys::waitpid(pid, None);
         }
-
-        // Restore the shell as the foreground group (pgid 0 = clear).
-        sys::tcsetpgrp(STDIN, 0);
+    } else if pipeline.background {
+        println!("[{}] {}", job_num, pipeline_pgid);
+    } else {
+        jobs.wait_foreground(job_num - 1, shell_pgid);
     }
 }
 
 // ── Built-in Commands ───────────────────────────────────────────────
 
 /// Try to execute a built-in command. Returns `true` if handled.
-fn execute_builtin(cmd: &str, args: &[&str], jobs: &mut JobTable) -> bool {
+fn execute_builtin(cmd: &str, args: &[&str], jobs: &mut JobTable, shell_pgid: u32) -> bool {
     match cmd {
         "exit" => builtin_exit(args),
         "help" => {
@@ -647,6 +742,14 @@ fn execute_builtin(cmd: &str, args: &[&str], jobs: &mut JobTable) -> bool {
             jobs.list();
             true
         }
+        "fg" => {
+            builtin_fg(args, jobs, shell_pgid);
+            true
+        }
+        "bg" => {
+            builtin_bg(args, jobs);
+            true
+        }
         "sysinfo" => {
             builtin_sysinfo();
             true
@@ -682,7 +785,9 @@ fn builtin_help() {
     println!("Built-in commands:");
     println!("  exit [code]      — exit the shell");
     println!("  help             — show this message");
-    println!("  jobs             — list background jobs");
+    println!("  jobs             — list background and stopped jobs");
+    println!("  fg [%n]          — resume a job in the foreground");
+    println!("  bg [%n]          — resume a stopped job in the background");
     println!("  sysinfo          — kernel version, memory, uptime");
     println!("  cd <dir>         — change working directory");
     println!("  export [VAR=val] — set/show environment variables");
@@ -694,6 +799,7 @@ fn builtin_help() {
     println!("Syntax:");
     println!("  cmd1 | cmd2   — pipeline");
     println!("  cmd &         — run in background");
+    println!("  Ctrl+Z        — stop the foreground job");
     println!("  cmd > file    — redirect stdout");
     println!("  cmd >> file   — append stdout");
     println!("  cmd < file    — redirect stdin");
@@ -863,6 +969,35 @@ fn builtin_kill(args: &[&str]) {
     }
 }
 
+/// `fg [%n]` — continue a job and wait for it in the foreground.
+fn builtin_fg(args: &[&str], jobs: &mut JobTable, shell_pgid: u32) {
+    let Some(idx) = jobs.find(args.get(1).copied()) else {
+        println!("fg: no such job");
+        return;
+    };
+    println!("{}", jobs.jobs[idx].cmd());
+    // Hand over the terminal before waking the job so a stage resuming a
+    // read does not immediately hit SIGTTIN again.
+    sys::tcsetpgrp(STDIN, jobs.jobs[idx].pgid);
+    jobs.jobs[idx].resume();
+    jobs.wait_foreground(idx, shell_pgid);
+}
+
+/// `bg [%n]` — continue a stopped job in the background.
+fn builtin_bg(args: &[&str], jobs: &mut JobTable) {
+    let Some(idx) = jobs.find(args.get(1).copied()) else {
+        println!("bg: no such job");
+        return;
+    };
+    let job = &mut jobs.jobs[idx];
+    if !job.stopped {
+        println!("bg: job {} already in background", idx + 1);
+        return;
+    }
+    job.resume();
+    println!("[{}] {} &", idx + 1, job.cmd());
+}
+
 fn builtin_sysinfo() {
     if let Some(ver) = sys::query_kernel_version() {
         let name_len = ver
@@ -956,6 +1091,16 @@ pub extern "C" fn main(_args: &[&str]) -> i32 {
     // not the shell itself.
     sys::signal(sys::SIGINT, sys::SIG_IGN);
     sys::signal(sys::SIGQUIT, sys::SIG_IGN);
+    // Job control: Ctrl+Z stops foreground jobs, never the shell, and the
+    // shell must keep working while it is not the foreground group.
+    sys::signal(sys::SIGTSTP, sys::SIG_IGN);
+    sys::signal(sys::SIGTTIN, sys::SIG_IGN);
+    sys::signal(sys::SIGTTOU, sys::SIG_IGN);
+
+    // The shell owns the terminal between jobs, so background jobs that
+    // read from it are stopped with SIGTTIN.
+    let shell_pgid = sys::getpgid(0) as u32;
+    sys::tcsetpgrp(STDIN, shell_pgid);
 
     println!("hsh — Hadron SHell");
     println!("Type 'help' for available commands.\n");
@@ -964,6 +1109,9 @@ pub extern "C" fn main(_args: &[&str]) -> i32 {
     let mut line_buf = [0u8; LINE_BUF_SIZE];
 
     loop {
+        // Report background jobs that finished or stopped.
+        jobs.reap();
+
         // Show prompt with current directory.
         let cwd = env::getenv("PWD").unwrap_or("/");
         print!("hsh:{}> ", cwd);
@@ -1003,6 +1151,6 @@ pub extern "C" fn main(_args: &[&str]) -> i32 {
         let pipeline = parse(&tokens, token_count);
 
         // Execute.
-        execute(&pipeline, &mut jobs);
+        execute(&pipeline, &mut jobs, shell_pgid);
     }
 }
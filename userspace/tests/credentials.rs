@@ -0,0 +1,190 @@
+//! utest: user and group credentials.
+//!
+//! Covers:
+//! 1. The test starts as root and `getpwnam`/`getpwuid` read `/etc/passwd`
+//! 2. Dropping to the unprivileged `user` account with `setgroups`,
+//!    `setgid` and `setuid`, after which root cannot be regained
+//! 3. VFS permission checks as `user`: root-owned files are read-only,
+//!    `/` is not writable, `/tmp` is, and the sticky bit protects other
+//!    users' entries
+//!
+//! The tests run in order and share one process, so everything after the
+//! privilege drop runs unprivileged.
+
+#![no_std]
+#![no_main]
+
+// Force hadron_libc_core to be linked so its #[no_mangle] symbols (getuid,
+// setuid, open, …) are available to the `extern "C"` declarations below.
+extern crate hadron_libc_core;
+
+use hadron_libc_core::errno::{self, EACCES, EPERM};
+use hadron_libc_core::flags::{O_CREAT, O_RDONLY, O_WRONLY};
+use hadron_libc_core::pwd::Passwd;
+use hadron_utest::utest_main;
+
+utest_main!(
+    test_starts_as_root,
+    test_passwd_lookup,
+    test_root_creates_file,
+    test_drop_privileges,
+    test_cannot_regain_root,
+    test_root_file_read_only,
+    test_root_dir_not_writable,
+    test_tmp_writable,
+    test_sticky_tmp,
+);
+
+// ── constants ─────────────────────────────────────────────────────────────────
+
+/// `user` in the initrd's `/etc/passwd`.
+const USER_UID: u32 = 1000;
+/// `users` in the initrd's `/etc/group`.
+const USERS_GID: u32 = 100;
+
+const ROOT_FILE: &[u8] = b"/tmp/root.txt\0";
+const USER_FILE: &[u8] = b"/tmp/user.txt\0";
+
+// ── extern declarations ───────────────────────────────────────────────────────
+
+unsafe extern "C" {
+    fn getuid() -> u32;
+    fn geteuid() -> u32;
+    fn getgid() -> u32;
+    fn getegid() -> u32;
+    fn setuid(uid: u32) -> i32;
+    fn setgid(gid: u32) -> i32;
+    fn seteuid(euid: u32) -> i32;
+    fn getgroups(size: i32, list: *mut u32) -> i32;
+    fn setgroups(size: usize, list: *const u32) -> i32;
+    fn getpwnam(name: *const u8) -> *mut Passwd;
+    fn getpwuid(uid: u32) -> *mut Passwd;
+    fn open(path: *const u8, flags: i32) -> i32;
+    fn close(fd: i32) -> i32;
+    fn unlink(path: *const u8) -> i32;
+    fn mkdir(path: *const u8, mode: u32) -> i32;
+}
+
+// ── helpers ───────────────────────────────────────────────────────────────────
+
+/// Asserts that a libc call returned -1 with `expected` in errno.
+fn assert_fails(ret: i32, expected: errno::Errno, what: &str) {
+    assert_eq!(ret, -1, "{what} should fail");
+    assert_eq!(errno::get_errno(), expected, "{what}: wrong errno");
+}
+
+/// Opens `path` with `flags` and closes it again, returning `open`'s result.
+fn try_open(path: &[u8], flags: u32) -> i32 {
+    // SAFETY: `path` is a NUL-terminated C string.
+    let fd = unsafe { open(path.as_ptr(), flags as i32) };
+    if fd >= 0 {
+        // SAFETY: `fd` was just opened.
+        unsafe { close(fd) };
+    }
+    fd
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+fn test_starts_as_root() {
+    // SAFETY: The ID getters have no preconditions.
+    unsafe {
+        assert_eq!(getuid(), 0);
+        assert_eq!(geteuid(), 0);
+        assert_eq!(getgid(), 0);
+        assert_eq!(getegid(), 0);
+        assert_eq!(getgroups(0, core::ptr::null_mut()), 0);
+    }
+}
+
+fn test_passwd_lookup() {
+    // SAFETY: The name is NUL-terminated; results point to static storage.
+    unsafe {
+        let pw = getpwnam(b"user\0".as_ptr());
+        assert!(!pw.is_null(), "getpwnam(\"user\") failed");
+        assert_eq!((*pw).pw_uid, USER_UID);
+        assert_eq!((*pw).pw_gid, USERS_GID);
+
+        let pw = getpwuid(0);
+        assert!(!pw.is_null(), "getpwuid(0) failed");
+        assert_eq!(*(*pw).pw_name, b'r');
+
+        assert!(getpwnam(b"nosuchuser\0".as_ptr()).is_null());
+    }
+}
+
+fn test_root_creates_file() {
+    let fd = try_open(ROOT_FILE, O_WRONLY | O_CREAT);
+    assert!(fd >= 0, "root could not create {ROOT_FILE:?}");
+}
+
+fn test_drop_privileges() {
+    // SAFETY: The group list is empty; the setters have no preconditions.
+    unsafe {
+        assert_eq!(setgroups(0, core::ptr::null()), 0);
+        assert_eq!(setgid(USERS_GID), 0);
+        assert_eq!(setuid(USER_UID), 0);
+
+        assert_eq!(getuid(), USER_UID);
+        assert_eq!(geteuid(), USER_UID);
+        assert_eq!(getgid(), USERS_GID);
+        assert_eq!(getegid(), USERS_GID);
+    }
+}
+
+fn test_cannot_regain_root() {
+    // SAFETY: The setters have no preconditions.
+    unsafe {
+        assert_fails(setuid(0), EPERM, "setuid(0)");
+        assert_fails(seteuid(0), EPERM, "seteuid(0)");
+        assert_fails(setgid(0), EPERM, "setgid(0)");
+        let groups = [0u32];
+        assert_fails(setgroups(1, groups.as_ptr()), EPERM, "setgroups([0])");
+        assert_eq!(geteuid(), USER_UID);
+    }
+}
+
+fn test_root_file_read_only() {
+    assert!(try_open(ROOT_FILE, O_RDONLY) >= 0, "root file not readable");
+    assert_fails(
+        try_open(ROOT_FILE, O_WRONLY),
+        EACCES,
+        "open root file for write",
+    );
+}
+
+fn test_root_dir_not_writable() {
+    // SAFETY: The paths are NUL-terminated C strings.
+    unsafe {
+        assert_fails(mkdir(b"/owned\0".as_ptr(), 0o755), EACCES, "mkdir in /");
+    }
+    assert_fails(
+        try_open(b"/owned.txt\0", O_WRONLY | O_CREAT),
+        EACCES,
+        "create in /",
+    );
+}
+
+fn test_tmp_writable() {
+    assert!(
+        try_open(USER_FILE, O_WRONLY | O_CREAT) >= 0,
+        "create in /tmp failed"
+    );
+    assert!(try_open(USER_FILE, O_WRONLY) >= 0, "own file not writable");
+    // SAFETY: The path is a NUL-terminated C string.
+    unsafe {
+        assert_eq!(mkdir(b"/tmp/userdir\0".as_ptr(), 0o755), 0);
+    }
+    assert!(
+        try_open(b"/tmp/userdir/nested.txt\0", O_WRONLY | O_CREAT) >= 0,
+        "create in own directory failed"
+    );
+}
+
+fn test_sticky_tmp() {
+    // SAFETY: The paths are NUL-terminated C strings.
+    unsafe {
+        assert_fails(unlink(ROOT_FILE.as_ptr()), EPERM, "unlink root file");
+        assert_eq!(unlink(USER_FILE.as_ptr()), 0);
+    }
+}
@@ -0,0 +1,238 @@
+//! utest: epoll — interest lists for readiness notification.
+//!
+//! Covers:
+//! 1. Level-triggered interests are reported while the fd stays ready
+//! 2. Edge-triggered interests are reported once per change
+//! 3. One-shot interests are disabled until re-armed with `EPOLL_CTL_MOD`
+//! 4. Only the ready fd out of many is reported, with its data
+//! 5. A blocking `epoll_wait` wakes for a timerfd and times out otherwise
+//! 6. Closed fds are dropped from the interest list
+//! 7. Bad operations fail with `EEXIST`, `ENOENT` and `EINVAL`
+
+#![no_std]
+#![no_main]
+
+// Force hadron_libc_core to be linked so its #[no_mangle] symbols
+// (epoll_create1, epoll_ctl, epoll_wait, …) are available.
+extern crate hadron_libc_core;
+
+use hadron_libc_core::epoll::{EpollEvent, epoll_create1, epoll_ctl, epoll_wait};
+use hadron_libc_core::errno::{self, EEXIST, EINVAL, ENOENT};
+use hadron_libc_core::flags::{
+    CLOCK_MONOTONIC, EPOLL_CTL_ADD, EPOLL_CTL_DEL, EPOLL_CTL_MOD, EPOLLET, EPOLLIN, EPOLLONESHOT,
+};
+use hadron_libc_core::io::{close, eventfd, eventfd_read, eventfd_write, pipe, read, write};
+use hadron_libc_core::time::{Itimerspec, Timespec, timerfd_create, timerfd_settime};
+use hadron_utest::utest_main;
+
+utest_main!(
+    test_level_triggered,
+    test_edge_triggered,
+    test_oneshot,
+    test_many_fds,
+    test_blocking_wait,
+    test_closed_fd_dropped,
+    test_errors,
+);
+
+// ── helpers ───────────────────────────────────────────────────────────────────
+
+fn new_epoll() -> i32 {
+    let epfd = epoll_create1(0);
+    assert!(epfd >= 0, "epoll_create1 failed");
+    epfd
+}
+
+fn ctl(epfd: i32, op: i32, fd: i32, events: u32, data: u64) -> i32 {
+    let event = EpollEvent { events, data };
+    // SAFETY: event is valid.
+    unsafe { epoll_ctl(epfd, op, fd, &raw const event) }
+}
+
+fn add(epfd: i32, fd: i32, events: u32, data: u64) {
+    assert_eq!(
+        ctl(epfd, EPOLL_CTL_ADD, fd, events, data),
+        0,
+        "EPOLL_CTL_ADD failed"
+    );
+}
+
+/// Waits up to `timeout_ms` and returns the `(events, data)` of each event.
+fn wait(epfd: i32, timeout_ms: i32) -> ([(u32, u64); 8], usize) {
+    let mut events = [EpollEvent::default(); 8];
+    // SAFETY: events holds 8 entries.
+    let n = unsafe { epoll_wait(epfd, events.as_mut_ptr(), 8, timeout_ms) };
+    assert!(n >= 0, "epoll_wait failed");
+    let mut out = [(0, 0); 8];
+    for (slot, event) in out.iter_mut().zip(&events[..n as usize]) {
+        *slot = (event.events, event.data);
+    }
+    (out, n as usize)
+}
+
+fn new_eventfd() -> i32 {
+    let fd = eventfd(0, 0);
+    assert!(fd >= 0, "eventfd failed");
+    fd
+}
+
+fn drain(fd: i32) {
+    let mut value = 0u64;
+    // SAFETY: value is valid.
+    assert_eq!(unsafe { eventfd_read(fd, &raw mut value) }, 0);
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+fn test_level_triggered() {
+    let epfd = new_epoll();
+    let mut fds = [0i32; 2];
+    // SAFETY: fds holds two ints.
+    assert_eq!(unsafe { pipe(fds.as_mut_ptr()) }, 0);
+    add(epfd, fds[0], EPOLLIN, 11);
+    assert_eq!(wait(epfd, 0).1, 0, "empty pipe should not be ready");
+
+    // SAFETY: the buffer is valid.
+    assert_eq!(unsafe { write(fds[1], b"x".as_ptr(), 1) }, 1);
+    let (events, n) = wait(epfd, 0);
+    assert_eq!(n, 1);
+    assert_eq!(events[0], (EPOLLIN, 11));
+    assert_eq!(wait(epfd, 0).1, 1, "still readable, so reported again");
+
+    let mut byte = 0u8;
+    // SAFETY: byte is valid.
+    assert_eq!(unsafe { read(fds[0], &raw mut byte, 1) }, 1);
+    assert_eq!(wait(epfd, 0).1, 0, "drained pipe should not be ready");
+
+    close(fds[0]);
+    close(fds[1]);
+    close(epfd);
+}
+
+fn test_edge_triggered() {
+    let epfd = new_epoll();
+    let efd = new_eventfd();
+    add(epfd, efd, EPOLLIN | EPOLLET, 1);
+
+    assert_eq!(eventfd_write(efd, 1), 0);
+    assert_eq!(wait(epfd, 0).1, 1);
+    assert_eq!(wait(epfd, 0).1, 0, "no new edge");
+    assert_eq!(eventfd_write(efd, 1), 0);
+    assert_eq!(wait(epfd, 0).1, 1, "a second write is a new edge");
+
+    close(efd);
+    close(epfd);
+}
+
+fn test_oneshot() {
+    let epfd = new_epoll();
+    let efd = new_eventfd();
+    add(epfd, efd, EPOLLIN | EPOLLONESHOT, 1);
+
+    assert_eq!(eventfd_write(efd, 1), 0);
+    assert_eq!(wait(epfd, 0).1, 1);
+    assert_eq!(eventfd_write(efd, 1), 0);
+    assert_eq!(wait(epfd, 0).1, 0, "disabled after one event");
+
+    assert_eq!(ctl(epfd, EPOLL_CTL_MOD, efd, EPOLLIN | EPOLLONESHOT, 2), 0);
+    let (events, n) = wait(epfd, 0);
+    assert_eq!(n, 1, "re-armed");
+    assert_eq!(events[0].1, 2);
+
+    close(efd);
+    close(epfd);
+}
+
+fn test_many_fds() {
+    let epfd = new_epoll();
+    let mut fds = [0i32; 32];
+    for (i, fd) in fds.iter_mut().enumerate() {
+        *fd = new_eventfd();
+        add(epfd, *fd, EPOLLIN, i as u64);
+    }
+
+    assert_eq!(eventfd_write(fds[17], 1), 0);
+    let (events, n) = wait(epfd, 0);
+    assert_eq!(n, 1);
+    assert_eq!(events[0], (EPOLLIN, 17));
+    drain(fds[17]);
+
+    for fd in fds {
+        close(fd);
+    }
+    close(epfd);
+}
+
+fn test_blocking_wait() {
+    let epfd = new_epoll();
+    let tfd = timerfd_create(CLOCK_MONOTONIC, 0);
+    assert!(tfd >= 0, "timerfd_create failed");
+    add(epfd, tfd, EPOLLIN, 5);
+
+    assert_eq!(wait(epfd, 20).1, 0, "nothing armed, so the wait times out");
+
+    let spec = Itimerspec {
+        it_interval: Timespec::default(),
+        it_value: Timespec {
+            tv_sec: 0,
+            tv_nsec: 10_000_000,
+        },
+    };
+    // SAFETY: spec is valid; old may be null.
+    let ret = unsafe { timerfd_settime(tfd, 0, &raw const spec, core::ptr::null_mut()) };
+    assert_eq!(ret, 0);
+    let (events, n) = wait(epfd, -1);
+    assert_eq!(n, 1, "the timer expiry should end the wait");
+    assert_eq!(events[0], (EPOLLIN, 5));
+
+    close(tfd);
+    close(epfd);
+}
+
+fn test_closed_fd_dropped() {
+    let epfd = new_epoll();
+    let efd = new_eventfd();
+    add(epfd, efd, EPOLLIN, 1);
+    assert_eq!(eventfd_write(efd, 1), 0);
+    close(efd);
+    assert_eq!(wait(epfd, 0).1, 0, "closed fds are not reported");
+
+    // The fd number can be reused and added again.
+    let efd = new_eventfd();
+    add(epfd, efd, EPOLLIN, 2);
+    close(efd);
+    close(epfd);
+}
+
+fn test_errors() {
+    let epfd = new_epoll();
+    let efd = new_eventfd();
+    add(epfd, efd, EPOLLIN, 0);
+
+    assert_eq!(ctl(epfd, EPOLL_CTL_ADD, efd, EPOLLIN, 0), -1);
+    assert_eq!(errno::get_errno(), EEXIST);
+    assert_eq!(ctl(epfd, EPOLL_CTL_DEL, efd, 0, 0), 0);
+    assert_eq!(ctl(epfd, EPOLL_CTL_DEL, efd, 0, 0), -1);
+    assert_eq!(errno::get_errno(), ENOENT);
+    assert_eq!(ctl(epfd, EPOLL_CTL_MOD, efd, EPOLLIN, 0), -1);
+    assert_eq!(errno::get_errno(), ENOENT);
+
+    assert_eq!(
+        ctl(efd, EPOLL_CTL_ADD, epfd, EPOLLIN, 0),
+        -1,
+        "not an epoll fd"
+    );
+    assert_eq!(errno::get_errno(), EINVAL);
+    let inner = new_epoll();
+    assert_eq!(ctl(epfd, EPOLL_CTL_ADD, inner, EPOLLIN, 0), -1, "nesting");
+    assert_eq!(errno::get_errno(), EINVAL);
+
+    let mut events = [EpollEvent::default(); 1];
+    // SAFETY: events holds one entry.
+    assert_eq!(unsafe { epoll_wait(epfd, events.as_mut_ptr(), 0, 0) }, -1);
+    assert_eq!(errno::get_errno(), EINVAL);
+
+    close(inner);
+    close(efd);
+    close(epfd);
+}
@@ -0,0 +1,236 @@
+//! utest: event file descriptors — `eventfd`, `timerfd` and `signalfd`.
+//!
+//! Covers:
+//! 1. An eventfd read takes the whole counter
+//! 2. A semaphore eventfd read takes one at a time
+//! 3. `poll` reports an eventfd readable only while its counter is non-zero
+//! 4. A relative one-shot timerfd expires once and reports zero time left
+//! 5. A periodic timerfd counts every expiry since the last read
+//! 6. An absolute timerfd expires at the given clock time
+//! 7. A signalfd accepts a blocked, queued signal as a `SigInfo` record
+//! 8. Bad flags and non-timerfd fds are rejected with `EINVAL`
+
+#![no_std]
+#![no_main]
+
+// Force hadron_libc_core to be linked so its #[no_mangle] symbols
+// (eventfd, timerfd_create, signalfd, …) are available.
+extern crate hadron_libc_core;
+
+use core::mem::size_of;
+
+use hadron_libc_core::errno::{self, EINVAL};
+use hadron_libc_core::flags::{
+    CLOCK_MONOTONIC, EFD_SEMAPHORE, POLLIN, POLLOUT, SI_QUEUE, SIG_SETMASK, SIGUSR1,
+    TFD_TIMER_ABSTIME,
+};
+use hadron_libc_core::io::{close, eventfd, eventfd_read, eventfd_write, read};
+use hadron_libc_core::poll::poll;
+use hadron_libc_core::process::getpid;
+use hadron_libc_core::signal::{SigInfo, signalfd, sigprocmask, sigqueue};
+use hadron_libc_core::time::{
+    Itimerspec, Timespec, clock_gettime, timerfd_create, timerfd_gettime, timerfd_settime,
+};
+use hadron_utest::utest_main;
+
+utest_main!(
+    test_eventfd_counter,
+    test_eventfd_semaphore,
+    test_eventfd_poll,
+    test_timerfd_relative,
+    test_timerfd_periodic,
+    test_timerfd_absolute,
+    test_signalfd,
+    test_rejects_bad_arguments,
+);
+
+// ── helpers ───────────────────────────────────────────────────────────────────
+
+/// POSIX `struct pollfd`.
+#[repr(C)]
+struct PollFd {
+    fd: i32,
+    events: i16,
+    revents: i16,
+}
+
+/// Polls `fd` for `events` without blocking; returns the ready events.
+fn poll_now(fd: i32, events: i16) -> i16 {
+    let mut pfd = PollFd {
+        fd,
+        events,
+        revents: 0,
+    };
+    // SAFETY: pfd is one valid pollfd.
+    let ret = unsafe { poll((&raw mut pfd).cast(), 1, 0) };
+    assert!(ret >= 0, "poll failed");
+    pfd.revents
+}
+
+fn read_u64(fd: i32) -> u64 {
+    let mut value = 0u64;
+    // SAFETY: value is valid.
+    let ret = unsafe { eventfd_read(fd, &raw mut value) };
+    assert_eq!(ret, 0, "read failed");
+    value
+}
+
+fn millis(ms: i64) -> Timespec {
+    Timespec {
+        tv_sec: ms / 1000,
+        tv_nsec: (ms % 1000) * 1_000_000,
+    }
+}
+
+fn arm(fd: i32, flags: i32, value: Timespec, interval_ms: i64) {
+    let spec = Itimerspec {
+        it_interval: millis(interval_ms),
+        it_value: value,
+    };
+    // SAFETY: spec is valid; old may be null.
+    let ret = unsafe { timerfd_settime(fd, flags, &raw const spec, core::ptr::null_mut()) };
+    assert_eq!(ret, 0, "timerfd_settime failed");
+}
+
+fn gettime(fd: i32) -> Itimerspec {
+    let mut cur = Itimerspec::default();
+    // SAFETY: cur is valid.
+    let ret = unsafe { timerfd_gettime(fd, &raw mut cur) };
+    assert_eq!(ret, 0, "timerfd_gettime failed");
+    cur
+}
+
+fn new_timerfd() -> i32 {
+    let fd = timerfd_create(CLOCK_MONOTONIC, 0);
+    assert!(fd >= 0, "timerfd_create failed");
+    fd
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+fn test_eventfd_counter() {
+    let fd = eventfd(3, 0);
+    assert!(fd >= 0, "eventfd failed");
+    assert_eq!(eventfd_write(fd, 4), 0);
+    assert_eq!(read_u64(fd), 7, "read should take the whole counter");
+    close(fd);
+}
+
+fn test_eventfd_semaphore() {
+    let fd = eventfd(2, EFD_SEMAPHORE as i32);
+    assert!(fd >= 0, "eventfd failed");
+    assert_eq!(read_u64(fd), 1);
+    assert_eq!(read_u64(fd), 1);
+    assert_eq!(
+        poll_now(fd, POLLIN),
+        0,
+        "counter should be exhausted after two reads"
+    );
+    close(fd);
+}
+
+fn test_eventfd_poll() {
+    let fd = eventfd(0, 0);
+    assert!(fd >= 0, "eventfd failed");
+    assert_eq!(poll_now(fd, POLLIN | POLLOUT), POLLOUT);
+    assert_eq!(eventfd_write(fd, 1), 0);
+    assert_eq!(poll_now(fd, POLLIN | POLLOUT), POLLIN | POLLOUT);
+    read_u64(fd);
+    assert_eq!(poll_now(fd, POLLIN), 0);
+    close(fd);
+}
+
+fn test_timerfd_relative() {
+    let fd = new_timerfd();
+    arm(fd, 0, millis(20), 0);
+    let left = gettime(fd).it_value;
+    assert!(
+        left.tv_sec == 0 && left.tv_nsec > 0,
+        "an armed timer should have time left"
+    );
+
+    assert_eq!(read_u64(fd), 1, "a one-shot timer expires once");
+    let cur = gettime(fd);
+    assert_eq!(cur.it_value.tv_sec, 0);
+    assert_eq!(cur.it_value.tv_nsec, 0, "an expired one-shot is disarmed");
+    close(fd);
+}
+
+fn test_timerfd_periodic() {
+    let fd = new_timerfd();
+    arm(fd, 0, millis(5), 5);
+    hadron_libc_core::time::usleep(30_000);
+    let ticks = read_u64(fd);
+    assert!(ticks >= 2, "expected several expiries, got {ticks}");
+    assert_eq!(
+        gettime(fd).it_interval.tv_nsec,
+        5_000_000,
+        "the interval is kept"
+    );
+    arm(fd, 0, millis(0), 0);
+    close(fd);
+}
+
+fn test_timerfd_absolute() {
+    let fd = new_timerfd();
+    let mut now = Timespec::default();
+    // SAFETY: now is valid.
+    assert_eq!(unsafe { clock_gettime(CLOCK_MONOTONIC, &raw mut now) }, 0);
+    let mut at = now;
+    at.tv_nsec += 20_000_000;
+    if at.tv_nsec >= 1_000_000_000 {
+        at.tv_sec += 1;
+        at.tv_nsec -= 1_000_000_000;
+    }
+    arm(fd, TFD_TIMER_ABSTIME as i32, at, 0);
+    assert_eq!(poll_now(fd, POLLIN), 0, "timer should not have expired yet");
+    assert_eq!(read_u64(fd), 1);
+
+    // An absolute time in the past expires immediately.
+    arm(fd, TFD_TIMER_ABSTIME as i32, now, 0);
+    assert_eq!(read_u64(fd), 1);
+    close(fd);
+}
+
+fn test_signalfd() {
+    let mask = 1u64 << (SIGUSR1 - 1);
+    let mut old = 0u64;
+    // SAFETY: both pointers are valid.
+    let ret = unsafe { sigprocmask(SIG_SETMASK, &raw const mask, &raw mut old) };
+    assert_eq!(ret, 0, "sigprocmask failed");
+
+    // SAFETY: mask is valid.
+    let fd = unsafe { signalfd(-1, &raw const mask, 0) };
+    assert!(fd >= 0, "signalfd failed");
+    assert_eq!(poll_now(fd, POLLIN), 0, "no signal should be pending");
+
+    assert_eq!(sigqueue(getpid(), SIGUSR1, 42), 0);
+    assert_eq!(poll_now(fd, POLLIN), POLLIN);
+
+    let mut info = SigInfo::default();
+    // SAFETY: info is valid for size_of::<SigInfo>() bytes.
+    let n = unsafe { read(fd, (&raw mut info).cast(), size_of::<SigInfo>()) };
+    assert_eq!(n, size_of::<SigInfo>() as isize);
+    assert_eq!(info.signo, SIGUSR1);
+    assert_eq!(info.code, SI_QUEUE);
+    assert_eq!(info.value, 42);
+    assert_eq!(poll_now(fd, POLLIN), 0, "the signal should be accepted");
+
+    close(fd);
+    // SAFETY: old is valid.
+    unsafe { sigprocmask(SIG_SETMASK, &raw const old, core::ptr::null_mut()) };
+}
+
+fn test_rejects_bad_arguments() {
+    assert_eq!(eventfd(0, 0x4), -1, "unknown eventfd flags");
+    assert_eq!(errno::get_errno(), EINVAL);
+    assert_eq!(timerfd_create(CLOCK_MONOTONIC, EFD_SEMAPHORE as i32), -1);
+    assert_eq!(errno::get_errno(), EINVAL);
+
+    let fd = eventfd(0, 0);
+    let mut cur = Itimerspec::default();
+    // SAFETY: cur is valid.
+    assert_eq!(unsafe { timerfd_gettime(fd, &raw mut cur) }, -1);
+    assert_eq!(errno::get_errno(), EINVAL, "an eventfd is not a timerfd");
+    close(fd);
+}
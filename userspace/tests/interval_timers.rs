@@ -0,0 +1,298 @@
+//! utest: interval timers — `alarm`, `setitimer` and POSIX `timer_create`.
+//!
+//! Covers:
+//! 1. `alarm` delivers `SIGALRM` and reports the time left on the previous one
+//! 2. `getitimer` reports a shrinking time left; disarming returns zero
+//! 3. A `timer_create` timer sends its `sigevent` signal and value
+//! 4. A periodic timer whose signal stays pending counts overruns
+//! 5. `ITIMER_PROF` fires while the process burns CPU
+//! 6. `ITIMER_VIRTUAL` fires in a loop that never makes a syscall
+//! 7. Deleted timers are rejected with `EINVAL`
+
+#![no_std]
+#![no_main]
+
+// Force hadron_libc_core to be linked so its #[no_mangle] symbols
+// (alarm, setitimer, timer_create, …) are available.
+extern crate hadron_libc_core;
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use hadron_libc_core::errno::{self, EAGAIN, EINVAL};
+use hadron_libc_core::flags::{
+    SI_TIMER, SIG_DFL, SIG_SETMASK, SIGALRM, SIGEV_SIGNAL, SIGPROF, SIGRTMIN, SIGVTALRM,
+};
+use hadron_libc_core::signal::{SigAction, SigInfo, sigaction, sigprocmask, sigtimedwait};
+use hadron_libc_core::time::{
+    ITIMER_PROF, ITIMER_REAL, ITIMER_VIRTUAL, Itimerspec, Itimerval, SigEvent, Timespec, Timeval,
+    alarm, getitimer, setitimer, timer_create, timer_delete, timer_getoverrun, timer_gettime,
+    timer_settime,
+};
+use hadron_utest::utest_main;
+
+utest_main!(
+    test_alarm,
+    test_getitimer_counts_down,
+    test_timer_create_signal,
+    test_periodic_overrun,
+    test_itimer_prof,
+    test_itimer_virtual_without_syscalls,
+    test_timer_delete,
+);
+
+/// `CLOCK_MONOTONIC` as the kernel numbers it.
+const CLOCK_MONOTONIC: i32 = 0;
+
+// ── helpers ───────────────────────────────────────────────────────────────────
+
+const fn bit(sig: i32) -> u64 {
+    1 << (sig - 1)
+}
+
+fn set_mask(mask: u64) -> u64 {
+    let mut old = 0u64;
+    // SAFETY: both pointers are valid.
+    let ret = unsafe { sigprocmask(SIG_SETMASK, &raw const mask, &raw mut old) };
+    assert_eq!(ret, 0, "sigprocmask failed");
+    old
+}
+
+/// Waits up to `timeout_ms` for blocked signal `sig`; returns its info, or
+/// `None` on timeout.
+fn wait_for(sig: i32, timeout_ms: i64) -> Option<SigInfo> {
+    let set = bit(sig);
+    let timeout = Timespec {
+        tv_sec: timeout_ms / 1000,
+        tv_nsec: (timeout_ms % 1000) * 1_000_000,
+    };
+    let mut info = SigInfo::default();
+    // SAFETY: all pointers are valid.
+    let ret = unsafe { sigtimedwait(&raw const set, &raw mut info, &raw const timeout) };
+    if ret == -1 {
+        assert_eq!(errno::get_errno(), EAGAIN, "sigtimedwait failed");
+        return None;
+    }
+    assert_eq!(ret, sig);
+    Some(info)
+}
+
+fn millis(ms: i64) -> Timespec {
+    Timespec {
+        tv_sec: ms / 1000,
+        tv_nsec: (ms % 1000) * 1_000_000,
+    }
+}
+
+fn create_timer(signo: i32, value: u64) -> i32 {
+    let event = SigEvent {
+        value,
+        signo,
+        notify: SIGEV_SIGNAL,
+        ..SigEvent::default()
+    };
+    let mut id = -1;
+    // SAFETY: event and id are valid.
+    let ret = unsafe { timer_create(CLOCK_MONOTONIC, &raw const event, &raw mut id) };
+    assert_eq!(ret, 0, "timer_create failed");
+    id
+}
+
+fn arm(id: i32, value_ms: i64, interval_ms: i64) {
+    let spec = Itimerspec {
+        it_interval: millis(interval_ms),
+        it_value: millis(value_ms),
+    };
+    // SAFETY: spec is valid; old may be null.
+    let ret = unsafe { timer_settime(id, 0, &raw const spec, core::ptr::null_mut()) };
+    assert_eq!(ret, 0, "timer_settime failed");
+}
+
+/// Reads the time-stamp counter.
+fn tsc() -> u64 {
+    // SAFETY: RDTSC has no side effects.
+    unsafe { core::arch::x86_64::_rdtsc() }
+}
+
+/// Set by [`on_vtalrm`].
+static VTALRM: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_vtalrm(_sig: i32) {
+    VTALRM.store(true, Ordering::SeqCst);
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+fn test_alarm() {
+    let old = set_mask(bit(SIGALRM));
+    assert_eq!(alarm(5), 0, "no alarm should be pending");
+    assert_eq!(alarm(1), 5, "previous alarm should have about 5s left");
+
+    let info = wait_for(SIGALRM, 2000).expect("alarm did not fire");
+    assert_eq!(info.signo, SIGALRM);
+    assert_eq!(alarm(0), 0, "alarm should be disarmed after firing");
+    set_mask(old);
+}
+
+fn test_getitimer_counts_down() {
+    let new = Itimerval {
+        it_interval: Timeval::default(),
+        it_value: Timeval {
+            tv_sec: 10,
+            tv_usec: 0,
+        },
+    };
+    // SAFETY: new is valid; old may be null.
+    assert_eq!(
+        unsafe { setitimer(ITIMER_REAL, &raw const new, core::ptr::null_mut()) },
+        0
+    );
+    let mut cur = Itimerval::default();
+    // SAFETY: cur is valid.
+    assert_eq!(unsafe { getitimer(ITIMER_REAL, &raw mut cur) }, 0);
+    assert!(cur.it_value.tv_sec < 10, "time left should be below 10s");
+    assert!(cur.it_value.tv_sec >= 9, "time left dropped too far");
+
+    let mut old = Itimerval::default();
+    let off = Itimerval::default();
+    // SAFETY: both pointers are valid.
+    assert_eq!(
+        unsafe { setitimer(ITIMER_REAL, &raw const off, &raw mut old) },
+        0
+    );
+    assert!(old.it_value.tv_sec >= 9, "old setting not reported");
+    // SAFETY: cur is valid.
+    assert_eq!(unsafe { getitimer(ITIMER_REAL, &raw mut cur) }, 0);
+    assert_eq!((cur.it_value.tv_sec, cur.it_value.tv_usec), (0, 0));
+}
+
+fn test_timer_create_signal() {
+    let old = set_mask(bit(SIGRTMIN));
+    let id = create_timer(SIGRTMIN, 0xfeed);
+    arm(id, 5, 0);
+
+    let info = wait_for(SIGRTMIN, 1000).expect("timer did not fire");
+    assert_eq!(info.code, SI_TIMER);
+    assert_eq!(info.value, 0xfeed);
+
+    let mut cur = Itimerspec::default();
+    // SAFETY: cur is valid.
+    assert_eq!(unsafe { timer_gettime(id, &raw mut cur) }, 0);
+    assert_eq!(
+        cur.it_value.tv_sec + cur.it_value.tv_nsec,
+        0,
+        "one-shot should disarm"
+    );
+    assert_eq!(timer_delete(id), 0);
+    set_mask(old);
+}
+
+fn test_periodic_overrun() {
+    let old = set_mask(bit(SIGRTMIN));
+    let id = create_timer(SIGRTMIN, 1);
+    arm(id, 2, 2);
+
+    // Let several periods pass while the first signal stays pending.
+    let pause = millis(30);
+    // SAFETY: pause is valid; rem may be null.
+    unsafe { hadron_libc_core::time::nanosleep(&raw const pause, core::ptr::null_mut()) };
+
+    // Read the count before taking the signal; the next expiry resets it.
+    assert!(timer_getoverrun(id) >= 5, "overruns not counted");
+    assert!(
+        wait_for(SIGRTMIN, 0).is_some(),
+        "periodic timer did not fire"
+    );
+    assert!(
+        wait_for(SIGRTMIN, 1000).is_some(),
+        "timer stopped after overrun"
+    );
+    assert_eq!(timer_delete(id), 0);
+    // Drop any expiry that raced with the delete.
+    let _ = wait_for(SIGRTMIN, 0);
+    set_mask(old);
+}
+
+fn test_itimer_prof() {
+    let old = set_mask(bit(SIGPROF));
+    let new = Itimerval {
+        it_interval: Timeval::default(),
+        it_value: Timeval {
+            tv_sec: 0,
+            tv_usec: 20_000,
+        },
+    };
+    // SAFETY: new is valid; old may be null.
+    assert_eq!(
+        unsafe { setitimer(ITIMER_PROF, &raw const new, core::ptr::null_mut()) },
+        0
+    );
+
+    // Only CPU time counts: spin until the signal is pending.
+    let mut fired = false;
+    for _ in 0..10_000 {
+        let mut spin = 0u64;
+        for i in 0..100_000u64 {
+            spin = core::hint::black_box(spin.wrapping_add(i));
+        }
+        if wait_for(SIGPROF, 0).is_some() {
+            fired = true;
+            break;
+        }
+    }
+    assert!(fired, "ITIMER_PROF did not fire");
+    set_mask(old);
+}
+
+fn test_itimer_virtual_without_syscalls() {
+    let act = SigAction {
+        handler: on_vtalrm as *const () as usize,
+        ..SigAction::default()
+    };
+    // SAFETY: act is a valid SigAction; oldact may be null.
+    assert_eq!(
+        unsafe { sigaction(SIGVTALRM, &raw const act, core::ptr::null_mut()) },
+        0
+    );
+    let new = Itimerval {
+        it_interval: Timeval::default(),
+        it_value: Timeval {
+            tv_sec: 0,
+            tv_usec: 20_000,
+        },
+    };
+    // SAFETY: new is valid; old may be null.
+    assert_eq!(
+        unsafe { setitimer(ITIMER_VIRTUAL, &raw const new, core::ptr::null_mut()) },
+        0
+    );
+
+    // Spin without entering the kernel, so only the tick charges CPU time.
+    // The TSC bounds the loop without a syscall.
+    let start = tsc();
+    while !VTALRM.load(Ordering::SeqCst) && tsc() - start < 10_000_000_000 {
+        core::hint::spin_loop();
+    }
+    assert!(
+        VTALRM.load(Ordering::SeqCst),
+        "ITIMER_VIRTUAL did not fire in a loop without syscalls"
+    );
+
+    let dfl = SigAction {
+        handler: SIG_DFL,
+        ..SigAction::default()
+    };
+    // SAFETY: dfl is a valid SigAction; oldact may be null.
+    assert_eq!(
+        unsafe { sigaction(SIGVTALRM, &raw const dfl, core::ptr::null_mut()) },
+        0
+    );
+}
+
+fn test_timer_delete() {
+    let id = create_timer(SIGRTMIN, 0);
+    assert_eq!(timer_delete(id), 0);
+    assert_eq!(timer_delete(id), -1);
+    assert_eq!(errno::get_errno(), EINVAL);
+    assert_eq!(timer_getoverrun(id), -1);
+    assert_eq!(errno::get_errno(), EINVAL);
+}
@@ -0,0 +1,261 @@
+//! utest: io_ring — shared-memory I/O submission and completion rings.
+//!
+//! Covers:
+//! 1. A no-op completes with its user data
+//! 2. A linked write then read moves data through a pipe
+//! 3. `IO_OP_PREAD` reads at an offset without moving the file offset
+//! 4. A failed operation cancels the rest of its chain only
+//! 5. `IO_OP_POLL` completes once the fd becomes ready
+//! 6. `io_ring_enter` blocks for `min_complete` completions or its timeout
+//! 7. Bad arguments fail with `EINVAL`
+
+#![no_std]
+#![no_main]
+
+// Force hadron_libc_core to be linked so its #[no_mangle] symbols
+// (io_ring_create, io_ring_enter, …) are available.
+extern crate hadron_libc_core;
+
+use hadron_libc_core::errno::{self, EBADF, ECANCELED, EINVAL};
+use hadron_libc_core::flags::{
+    CLOCK_MONOTONIC, IO_OP_LINK, IO_OP_NOP, IO_OP_POLL, IO_OP_PREAD, IO_OP_READ, IO_OP_TIMEOUT,
+    IO_OP_WRITE, O_CREAT, O_RDWR, O_TRUNC, POLLIN,
+};
+use hadron_libc_core::io::{close, lseek, open, pipe, unlink, write};
+use hadron_libc_core::io_ring::{
+    IoCompletion, IoOp, IoRing, IoRingParams, io_ring_create, io_ring_enter,
+};
+use hadron_libc_core::time::{Timespec, clock_gettime};
+use hadron_utest::utest_main;
+
+utest_main!(
+    test_nop,
+    test_linked_pipe_transfer,
+    test_pread,
+    test_failed_link_cancels_chain,
+    test_poll,
+    test_blocking_enter,
+    test_errors,
+);
+
+const FILE: &[u8] = b"/tmp/io_ring.txt\0";
+
+// ── helpers ───────────────────────────────────────────────────────────────────
+
+fn new_ring() -> IoRing {
+    IoRing::new(8).expect("io_ring_create failed")
+}
+
+fn op(opcode: u8, fd: i32, user_data: u64) -> IoOp {
+    IoOp {
+        opcode,
+        fd,
+        user_data,
+        ..IoOp::default()
+    }
+}
+
+fn push(ring: &mut IoRing, op: IoOp) {
+    assert!(ring.push(op), "submission queue full");
+}
+
+/// Submits the queued operations and waits for `n` completions.
+fn complete(ring: &mut IoRing, n: usize) -> [IoCompletion; 8] {
+    ring.submit(n as u32, -1).expect("io_ring_enter failed");
+    let mut out = [IoCompletion::default(); 8];
+    for slot in &mut out[..n] {
+        *slot = ring.pop().expect("missing completion");
+    }
+    out
+}
+
+/// Returns the result of the completion tagged `user_data`.
+fn result(completions: &[IoCompletion], user_data: u64) -> i32 {
+    completions
+        .iter()
+        .find(|c| c.user_data == user_data)
+        .expect("no completion for user data")
+        .result
+}
+
+fn new_pipe() -> [i32; 2] {
+    let mut fds = [0i32; 2];
+    // SAFETY: fds holds two ints.
+    assert_eq!(unsafe { pipe(fds.as_mut_ptr()) }, 0);
+    fds
+}
+
+fn now_ns() -> u64 {
+    let mut ts = Timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: ts is a valid Timespec.
+    let ret = unsafe { clock_gettime(CLOCK_MONOTONIC, &raw mut ts) };
+    assert_eq!(ret, 0, "clock_gettime failed");
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+fn test_nop() {
+    let mut ring = new_ring();
+    assert!(ring.params().sq_entries >= 8);
+    push(&mut ring, op(IO_OP_NOP, -1, 0xdead_beef));
+    let completions = complete(&mut ring, 1);
+    assert_eq!(completions[0].user_data, 0xdead_beef);
+    assert_eq!(completions[0].result, 0);
+    assert!(ring.pop().is_none(), "one operation, one completion");
+}
+
+fn test_linked_pipe_transfer() {
+    let mut ring = new_ring();
+    let fds = new_pipe();
+    let data = *b"ring";
+    let mut buf = [0u8; 4];
+
+    push(
+        &mut ring,
+        IoOp {
+            flags: IO_OP_LINK,
+            addr: data.as_ptr() as u64,
+            len: 4,
+            ..op(IO_OP_WRITE, fds[1], 1)
+        },
+    );
+    push(
+        &mut ring,
+        IoOp {
+            addr: buf.as_mut_ptr() as u64,
+            len: 4,
+            ..op(IO_OP_READ, fds[0], 2)
+        },
+    );
+    let completions = complete(&mut ring, 2);
+    assert_eq!(result(&completions, 1), 4);
+    assert_eq!(result(&completions, 2), 4);
+    assert_eq!(&buf, b"ring");
+
+    close(fds[0]);
+    close(fds[1]);
+}
+
+fn test_pread() {
+    // SAFETY: FILE is NUL-terminated.
+    let fd = unsafe { open(FILE.as_ptr(), (O_RDWR | O_CREAT | O_TRUNC) as i32) };
+    assert!(fd >= 0, "open failed");
+    // SAFETY: the buffer is valid.
+    assert_eq!(unsafe { write(fd, b"0123456789".as_ptr(), 10) }, 10);
+    let offset = lseek(fd, 0, 1);
+
+    let mut ring = new_ring();
+    let mut buf = [0u8; 3];
+    push(
+        &mut ring,
+        IoOp {
+            offset: 4,
+            addr: buf.as_mut_ptr() as u64,
+            len: 3,
+            ..op(IO_OP_PREAD, fd, 1)
+        },
+    );
+    let completions = complete(&mut ring, 1);
+    assert_eq!(completions[0].result, 3);
+    assert_eq!(&buf, b"456");
+    assert_eq!(lseek(fd, 0, 1), offset, "pread must not move the offset");
+
+    close(fd);
+    // SAFETY: FILE is NUL-terminated.
+    unsafe { unlink(FILE.as_ptr()) };
+}
+
+fn test_failed_link_cancels_chain() {
+    let mut ring = new_ring();
+    let mut byte = 0u8;
+    push(
+        &mut ring,
+        IoOp {
+            flags: IO_OP_LINK,
+            addr: (&raw mut byte) as u64,
+            len: 1,
+            ..op(IO_OP_READ, 999, 1)
+        },
+    );
+    push(&mut ring, op(IO_OP_NOP, -1, 2));
+    // Not linked to the failed chain.
+    push(&mut ring, op(IO_OP_NOP, -1, 3));
+    let completions = complete(&mut ring, 3);
+    assert_eq!(result(&completions, 1), -EBADF.0);
+    assert_eq!(result(&completions, 2), -ECANCELED.0);
+    assert_eq!(result(&completions, 3), 0);
+}
+
+fn test_poll() {
+    let mut ring = new_ring();
+    let fds = new_pipe();
+    push(
+        &mut ring,
+        IoOp {
+            events: POLLIN as u32,
+            ..op(IO_OP_POLL, fds[0], 1)
+        },
+    );
+    assert_eq!(ring.submit(1, 20), Ok(1));
+    assert!(ring.pop().is_none(), "empty pipe should not be ready");
+
+    // SAFETY: the buffer is valid.
+    assert_eq!(unsafe { write(fds[1], b"x".as_ptr(), 1) }, 1);
+    let completions = complete(&mut ring, 1);
+    assert_eq!(completions[0].result, i32::from(POLLIN));
+
+    close(fds[0]);
+    close(fds[1]);
+}
+
+fn test_blocking_enter() {
+    let mut ring = new_ring();
+    push(
+        &mut ring,
+        IoOp {
+            offset: 10_000_000,
+            ..op(IO_OP_TIMEOUT, -1, 1)
+        },
+    );
+    let start = now_ns();
+    let completions = complete(&mut ring, 1);
+    assert_eq!(completions[0].result, 0);
+    assert!(now_ns() - start >= 10_000_000, "woke before the timeout op");
+
+    // A wait for more than will complete ends at its own timeout.
+    push(
+        &mut ring,
+        IoOp {
+            offset: 10_000_000_000,
+            ..op(IO_OP_TIMEOUT, -1, 2)
+        },
+    );
+    assert_eq!(ring.submit(1, 20), Ok(1));
+    assert!(ring.pop().is_none(), "the 10 s timeout is still running");
+    // Dropping the ring cancels it.
+}
+
+fn test_errors() {
+    let mut params = IoRingParams::default();
+    // SAFETY: params is valid.
+    assert_eq!(unsafe { io_ring_create(0, &raw mut params, 0) }, -1);
+    assert_eq!(errno::get_errno(), EINVAL);
+    // SAFETY: params is valid.
+    assert_eq!(unsafe { io_ring_create(8, &raw mut params, 1) }, -1);
+    assert_eq!(errno::get_errno(), EINVAL);
+
+    let fds = new_pipe();
+    assert_eq!(io_ring_enter(fds[0], 0, 0, 0), -1, "not a ring");
+    assert_eq!(errno::get_errno(), EINVAL);
+    close(fds[0]);
+    close(fds[1]);
+
+    let mut ring = new_ring();
+    push(&mut ring, op(200, -1, 1));
+    let completions = complete(&mut ring, 1);
+    assert_eq!(completions[0].result, -EINVAL.0);
+}
@@ -0,0 +1,112 @@
+//! utest: dynamic loading in a statically linked program, and the thread
+//! pointer syscalls the dynamic linker relies on.
+//!
+//! Covers:
+//! 1. `dlopen`/`dlsym`/`dlclose` fail without a dynamic linker, and `dlerror`
+//!    reports it once, then returns null
+//! 2. `thread_set_tls`/`thread_get_tls` round-trip, and `%fs:0` reads through
+//!    the new thread pointer
+//! 3. `thread_set_tls` rejects kernel addresses
+
+#![no_std]
+#![no_main]
+
+// Force hadron_libc_core to be linked so its #[no_mangle] symbols (dlopen,
+// dlerror, …) are available to the `extern "C"` declarations below.
+extern crate hadron_libc_core;
+
+use hadron_libc_core::errno::EINVAL;
+use hadron_libc_core::sys;
+use hadron_utest::utest_main;
+
+utest_main!(
+    test_dlopen_without_linker,
+    test_dlerror_cleared,
+    test_thread_pointer_round_trip,
+    test_thread_pointer_rejects_kernel_address,
+);
+
+// ── extern declarations ───────────────────────────────────────────────────────
+
+unsafe extern "C" {
+    fn dlopen(filename: *const u8, flags: i32) -> *mut u8;
+    fn dlsym(handle: *mut u8, symbol: *const u8) -> *mut u8;
+    fn dlclose(handle: *mut u8) -> i32;
+    fn dlerror() -> *const u8;
+}
+
+/// `RTLD_NOW` from `<dlfcn.h>`.
+const RTLD_NOW: i32 = 2;
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+fn test_dlopen_without_linker() {
+    // SAFETY: All arguments are NUL-terminated strings or null.
+    unsafe {
+        let handle = dlopen(b"libfoo.so\0".as_ptr(), RTLD_NOW);
+        assert!(
+            handle.is_null(),
+            "dlopen succeeded without a dynamic linker"
+        );
+        assert!(!dlerror().is_null(), "dlerror has no message after dlopen");
+
+        let sym = dlsym(core::ptr::null_mut(), b"main\0".as_ptr());
+        assert!(sym.is_null(), "dlsym succeeded without a dynamic linker");
+        assert_eq!(dlclose(core::ptr::null_mut()), -1);
+    }
+}
+
+fn test_dlerror_cleared() {
+    // SAFETY: dlerror takes no arguments.
+    unsafe {
+        // The message from the dlclose above is returned once.
+        assert!(!dlerror().is_null());
+        assert!(dlerror().is_null(), "dlerror did not clear its message");
+    }
+}
+
+/// A minimal TCB: `%fs:0` must hold its own address.
+#[repr(C, align(16))]
+struct Tcb {
+    self_ptr: usize,
+    pad: [usize; 7],
+}
+
+fn test_thread_pointer_round_trip() {
+    static mut TCB: Tcb = Tcb {
+        self_ptr: 0,
+        pad: [0; 7],
+    };
+
+    let old = sys::sys_thread_get_tls();
+    let tcb = &raw mut TCB;
+    // SAFETY: Only this test touches TCB; it is a valid self-pointing TCB
+    // for the rest of the process.
+    unsafe {
+        (*tcb).self_ptr = tcb as usize;
+        sys::sys_thread_set_tls(tcb as usize).expect("thread_set_tls failed");
+    }
+    assert_eq!(sys::sys_thread_get_tls(), tcb as usize);
+
+    let tp: usize;
+    // SAFETY: FS_BASE now points at TCB, whose first word is readable.
+    unsafe {
+        core::arch::asm!(
+            "mov {}, qword ptr fs:[0]",
+            out(reg) tp,
+            options(nostack, preserves_flags, readonly),
+        );
+    }
+    assert_eq!(tp, tcb as usize, "%fs:0 does not read through the new base");
+
+    // SAFETY: `old` is the previous thread pointer (or 0).
+    unsafe { sys::sys_thread_set_tls(old).expect("restoring thread pointer failed") };
+}
+
+fn test_thread_pointer_rejects_kernel_address() {
+    let before = sys::sys_thread_get_tls();
+    // SAFETY: The call is expected to fail without changing FS_BASE.
+    let err = unsafe { sys::sys_thread_set_tls(0xFFFF_8000_0000_0000) };
+    assert_eq!(err, Err(EINVAL));
+    assert_eq!(sys::sys_thread_get_tls(), before);
+}
@@ -0,0 +1,145 @@
+//! utest: CPU time accounting through `getrusage`, `times` and the CPU-time
+//! clocks.
+//!
+//! Covers:
+//! 1. Busy user code advances `CLOCK_THREAD_CPUTIME_ID` and `ru_utime`
+//! 2. Syscalls are charged as system time
+//! 3. Process time is at least thread time
+//! 4. `RUSAGE_CHILDREN` is zero with no reaped children
+//! 5. `getrusage` rejects an unknown `who`
+//! 6. `times` agrees with `getrusage`
+
+#![no_std]
+#![no_main]
+
+// Force hadron_libc_core to be linked so its #[no_mangle] symbols
+// (getrusage, times, clock_gettime, …) are available.
+extern crate hadron_libc_core;
+
+use hadron_libc_core::errno::{self, EINVAL};
+use hadron_libc_core::time::{
+    CLK_TCK, RUSAGE_CHILDREN, RUSAGE_SELF, RUSAGE_THREAD, Rusage, Timespec, Timeval, Tms,
+    clock_gettime, getrusage, times,
+};
+use hadron_utest::utest_main;
+
+utest_main!(
+    test_user_time_advances,
+    test_syscalls_charge_system_time,
+    test_process_covers_thread,
+    test_children_empty,
+    test_invalid_who,
+    test_times_matches_getrusage,
+);
+
+// ── extern declarations ───────────────────────────────────────────────────────
+
+unsafe extern "C" {
+    fn getpid() -> i32;
+}
+
+/// `CLOCK_PROCESS_CPUTIME_ID` from `<time.h>`.
+const CLOCK_PROCESS_CPUTIME_ID: i32 = 2;
+/// `CLOCK_THREAD_CPUTIME_ID` from `<time.h>`.
+const CLOCK_THREAD_CPUTIME_ID: i32 = 3;
+
+// ── helpers ───────────────────────────────────────────────────────────────────
+
+fn cpu_clock_ns(clock: i32) -> u64 {
+    let mut ts = Timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: ts is a valid Timespec.
+    let ret = unsafe { clock_gettime(clock, &raw mut ts) };
+    assert_eq!(ret, 0, "clock_gettime failed");
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+fn rusage(who: i32) -> Rusage {
+    let mut ru = Rusage::default();
+    // SAFETY: ru is a valid Rusage.
+    let ret = unsafe { getrusage(who, &raw mut ru) };
+    assert_eq!(ret, 0, "getrusage failed");
+    ru
+}
+
+fn timeval_us(tv: Timeval) -> i64 {
+    tv.tv_sec * 1_000_000 + tv.tv_usec
+}
+
+/// Spins in user mode until the thread has used at least `ns` of CPU time.
+fn burn_cpu(ns: u64) {
+    let start = cpu_clock_ns(CLOCK_THREAD_CPUTIME_ID);
+    let mut acc = 0u64;
+    for _ in 0..10_000 {
+        for i in 0..100_000u64 {
+            acc = core::hint::black_box(acc.wrapping_mul(31).wrapping_add(i));
+        }
+        if cpu_clock_ns(CLOCK_THREAD_CPUTIME_ID) - start >= ns {
+            return;
+        }
+    }
+    panic!("thread CPU clock did not advance");
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+fn test_user_time_advances() {
+    let before = rusage(RUSAGE_SELF);
+    burn_cpu(5_000_000);
+    let after = rusage(RUSAGE_SELF);
+    assert!(
+        timeval_us(after.ru_utime) - timeval_us(before.ru_utime) >= 1_000,
+        "ru_utime did not advance with user-mode work"
+    );
+}
+
+fn test_syscalls_charge_system_time() {
+    let before = rusage(RUSAGE_THREAD);
+    for _ in 0..20_000 {
+        // SAFETY: getpid has no preconditions.
+        core::hint::black_box(unsafe { getpid() });
+    }
+    let after = rusage(RUSAGE_THREAD);
+    assert!(
+        timeval_us(after.ru_stime) > timeval_us(before.ru_stime),
+        "ru_stime did not advance across syscalls"
+    );
+}
+
+fn test_process_covers_thread() {
+    let thread = cpu_clock_ns(CLOCK_THREAD_CPUTIME_ID);
+    let process = cpu_clock_ns(CLOCK_PROCESS_CPUTIME_ID);
+    assert!(thread > 0);
+    assert!(process >= thread, "process CPU time below thread CPU time");
+}
+
+fn test_children_empty() {
+    let ru = rusage(RUSAGE_CHILDREN);
+    assert_eq!(timeval_us(ru.ru_utime), 0);
+    assert_eq!(timeval_us(ru.ru_stime), 0);
+}
+
+fn test_invalid_who() {
+    let mut ru = Rusage::default();
+    // SAFETY: ru is a valid Rusage.
+    let ret = unsafe { getrusage(42, &raw mut ru) };
+    assert_eq!(ret, -1);
+    assert_eq!(errno::get_errno(), EINVAL);
+}
+
+fn test_times_matches_getrusage() {
+    let mut tms = Tms::default();
+    // SAFETY: tms is a valid Tms.
+    let elapsed = unsafe { times(&raw mut tms) };
+    let ru = rusage(RUSAGE_SELF);
+    assert!(elapsed > 0, "times returned no elapsed ticks");
+
+    let ticks_us = 1_000_000 / CLK_TCK;
+    let utime_ticks = timeval_us(ru.ru_utime) / ticks_us;
+    // getrusage ran after times, so it may be a tick or so ahead.
+    assert!(tms.tms_utime <= utime_ticks && utime_ticks - tms.tms_utime <= 1);
+    assert_eq!(tms.tms_cutime, 0);
+    assert_eq!(tms.tms_cstime, 0);
+}
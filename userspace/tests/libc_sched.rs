@@ -0,0 +1,231 @@
+//! utest: nice values, CPU affinity and scheduling classes.
+//!
+//! Covers:
+//! 1. A new process starts at nice 0
+//! 2. `setpriority` and `nice` raise the nice value, and root may lower it
+//! 3. `getpriority` rejects an unknown `which` and a missing process
+//! 4. `sched_getaffinity` reports CPU 0 and clears the rest of the set
+//! 5. `sched_setaffinity` round-trips and rejects an empty mask
+//! 6. `sched_setscheduler` switches to `SCHED_FIFO`/`SCHED_RR` and back
+//! 7. `sched_setattr` admits a `SCHED_DEADLINE` reservation and rejects bad
+//!    parameters
+//! 8. An unprivileged process may raise but not lower its nice value, and
+//!    may not enter a real-time class
+//!
+//! The tests run in order and share one process, so the last one runs
+//! unprivileged.
+
+#![no_std]
+#![no_main]
+
+// Force hadron_libc_core to be linked so its #[no_mangle] symbols
+// (getpriority, sched_getaffinity, …) are available.
+extern crate hadron_libc_core;
+
+use hadron_libc_core::errno::{self, EINVAL, EPERM, ESRCH};
+use hadron_libc_core::process::{
+    SCHED_DEADLINE, SCHED_FIFO, SCHED_OTHER, SCHED_RR, SchedAttr, SchedParam, getpriority, nice,
+    sched_get_priority_max, sched_get_priority_min, sched_getaffinity, sched_getattr,
+    sched_getparam, sched_getscheduler, sched_setaffinity, sched_setattr, sched_setparam,
+    sched_setscheduler, setpriority, setuid,
+};
+use hadron_utest::utest_main;
+
+utest_main!(
+    test_default_nice,
+    test_raise_and_lower,
+    test_getpriority_errors,
+    test_getaffinity,
+    test_setaffinity,
+    test_rt_policies,
+    test_deadline_policy,
+    test_unprivileged_nice,
+);
+
+// ── constants ─────────────────────────────────────────────────────────────────
+
+/// `PRIO_PROCESS` from `<sys/resource.h>`.
+const PRIO_PROCESS: i32 = 0;
+/// `user` in the initrd's `/etc/passwd`.
+const USER_UID: u32 = 1000;
+/// Size of `cpu_set_t` from `<sched.h>`.
+const CPU_SET_BYTES: usize = 128;
+
+// ── helpers ───────────────────────────────────────────────────────────────────
+
+/// Asserts that a libc call returned -1 with `expected` in errno.
+fn assert_fails(ret: i32, expected: errno::Errno, what: &str) {
+    assert_eq!(ret, -1, "{what} should fail");
+    assert_eq!(errno::get_errno(), expected, "{what}: wrong errno");
+}
+
+fn set_policy(policy: u32, priority: i32) -> i32 {
+    let param = SchedParam {
+        sched_priority: priority,
+    };
+    // SAFETY: param is a valid sched_param.
+    unsafe { sched_setscheduler(0, policy as i32, &param) }
+}
+
+fn own_attr() -> SchedAttr {
+    let mut attr = SchedAttr::default();
+    let size = core::mem::size_of::<SchedAttr>() as u32;
+    // SAFETY: attr is valid for size writes.
+    let ret = unsafe { sched_getattr(0, &mut attr, size, 0) };
+    assert_eq!(ret, 0, "sched_getattr failed");
+    attr
+}
+
+fn own_nice() -> i32 {
+    errno::set_errno(errno::Errno(0));
+    let nice = getpriority(PRIO_PROCESS, 0);
+    assert_eq!(errno::get_errno(), errno::Errno(0), "getpriority failed");
+    nice
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+fn test_default_nice() {
+    assert_eq!(own_nice(), 0);
+}
+
+fn test_raise_and_lower() {
+    assert_eq!(setpriority(PRIO_PROCESS, 0, 5), 0);
+    assert_eq!(own_nice(), 5);
+    assert_eq!(nice(2), 7);
+    assert_eq!(own_nice(), 7);
+
+    // Out-of-range values are clamped.
+    assert_eq!(setpriority(PRIO_PROCESS, 0, 100), 0);
+    assert_eq!(own_nice(), 19);
+
+    // Root may lower it again.
+    assert_eq!(setpriority(PRIO_PROCESS, 0, -5), 0);
+    assert_eq!(own_nice(), -5);
+    assert_eq!(setpriority(PRIO_PROCESS, 0, 0), 0);
+}
+
+fn test_getpriority_errors() {
+    assert_fails(getpriority(7, 0), EINVAL, "getpriority(which=7)");
+    assert_fails(
+        getpriority(PRIO_PROCESS, 0x7fff_fff0),
+        ESRCH,
+        "getpriority(missing pid)",
+    );
+}
+
+fn test_getaffinity() {
+    let mut set = [0xffu8; CPU_SET_BYTES];
+    // SAFETY: set is valid for CPU_SET_BYTES writes.
+    let ret = unsafe { sched_getaffinity(0, CPU_SET_BYTES, set.as_mut_ptr()) };
+    assert_eq!(ret, 0, "sched_getaffinity failed");
+    assert_eq!(set[0] & 1, 1, "CPU 0 should be allowed");
+    assert!(set[8..].iter().all(|&b| b == 0), "tail not cleared");
+
+    let mut short = [0u8; 4];
+    // SAFETY: short is valid for 4 writes.
+    let ret = unsafe { sched_getaffinity(0, short.len(), short.as_mut_ptr()) };
+    assert_fails(ret, EINVAL, "sched_getaffinity(4 bytes)");
+}
+
+fn test_setaffinity() {
+    let mut set = [0u8; CPU_SET_BYTES];
+    set[0] = 1;
+    // SAFETY: set is valid for CPU_SET_BYTES reads.
+    let ret = unsafe { sched_setaffinity(0, CPU_SET_BYTES, set.as_ptr()) };
+    assert_eq!(ret, 0, "sched_setaffinity failed");
+
+    let mut got = [0xffu8; CPU_SET_BYTES];
+    // SAFETY: got is valid for CPU_SET_BYTES writes.
+    let ret = unsafe { sched_getaffinity(0, CPU_SET_BYTES, got.as_mut_ptr()) };
+    assert_eq!(ret, 0, "sched_getaffinity failed");
+    assert_eq!(got, set, "mask did not round-trip");
+
+    let empty = [0u8; CPU_SET_BYTES];
+    // SAFETY: empty is valid for CPU_SET_BYTES reads.
+    let ret = unsafe { sched_setaffinity(0, CPU_SET_BYTES, empty.as_ptr()) };
+    assert_fails(ret, EINVAL, "sched_setaffinity(empty)");
+
+    let all = [0xffu8; CPU_SET_BYTES];
+    // SAFETY: all is valid for CPU_SET_BYTES reads.
+    let ret = unsafe { sched_setaffinity(0, CPU_SET_BYTES, all.as_ptr()) };
+    assert_eq!(ret, 0, "sched_setaffinity(all) failed");
+}
+
+fn test_rt_policies() {
+    let fifo = SCHED_FIFO as i32;
+    assert_eq!(sched_get_priority_min(fifo), 1);
+    assert_eq!(sched_get_priority_max(fifo), 99);
+    assert_fails(
+        sched_get_priority_max(42),
+        EINVAL,
+        "sched_get_priority_max(42)",
+    );
+
+    assert_eq!(sched_getscheduler(0), SCHED_OTHER as i32);
+    assert_eq!(set_policy(SCHED_FIFO, 10), 0, "SCHED_FIFO failed");
+    assert_eq!(sched_getscheduler(0), SCHED_FIFO as i32);
+
+    let param = SchedParam { sched_priority: 20 };
+    // SAFETY: param is a valid sched_param.
+    assert_eq!(unsafe { sched_setparam(0, &param) }, 0);
+    let mut got = SchedParam { sched_priority: 0 };
+    // SAFETY: got is a valid sched_param.
+    assert_eq!(unsafe { sched_getparam(0, &mut got) }, 0);
+    assert_eq!(got.sched_priority, 20);
+
+    assert_eq!(set_policy(SCHED_RR, 5), 0, "SCHED_RR failed");
+    assert_eq!(sched_getscheduler(0), SCHED_RR as i32);
+    assert_fails(
+        set_policy(SCHED_FIFO, 100),
+        EINVAL,
+        "SCHED_FIFO priority 100",
+    );
+    assert_fails(set_policy(SCHED_OTHER, 1), EINVAL, "SCHED_OTHER priority 1");
+
+    assert_eq!(set_policy(SCHED_OTHER, 0), 0);
+    assert_eq!(sched_getscheduler(0), SCHED_OTHER as i32);
+}
+
+fn test_deadline_policy() {
+    let mut attr = SchedAttr {
+        size: core::mem::size_of::<SchedAttr>() as u32,
+        policy: SCHED_DEADLINE,
+        runtime: 30_000_000,
+        deadline: 60_000_000,
+        period: 100_000_000,
+        ..SchedAttr::default()
+    };
+    // SAFETY: attr is a valid sched_attr.
+    assert_eq!(
+        unsafe { sched_setattr(0, &attr, 0) },
+        0,
+        "SCHED_DEADLINE failed"
+    );
+    let got = own_attr();
+    assert_eq!(got.policy, SCHED_DEADLINE);
+    assert_eq!(
+        (got.runtime, got.deadline, got.period),
+        (30_000_000, 60_000_000, 100_000_000)
+    );
+
+    // The run time may not exceed the deadline.
+    attr.runtime = 70_000_000;
+    // SAFETY: attr is a valid sched_attr.
+    let ret = unsafe { sched_setattr(0, &attr, 0) };
+    assert_fails(ret, EINVAL, "sched_setattr(runtime > deadline)");
+    // SAFETY: attr is a valid sched_attr.
+    let ret = unsafe { sched_setattr(0, &attr, 1) };
+    assert_fails(ret, EINVAL, "sched_setattr(flags = 1)");
+
+    assert_eq!(set_policy(SCHED_OTHER, 0), 0);
+    assert_eq!(own_attr().policy, SCHED_OTHER);
+}
+
+fn test_unprivileged_nice() {
+    assert_eq!(setuid(USER_UID), 0);
+    assert_eq!(nice(3), 3);
+    assert_fails(nice(-1), EPERM, "nice(-1)");
+    assert_eq!(own_nice(), 3);
+    assert_fails(set_policy(SCHED_FIFO, 1), EPERM, "unprivileged SCHED_FIFO");
+}
@@ -0,0 +1,429 @@
+//! utest: POSIX signal delivery — siginfo, real-time queueing, alternate
+//! stacks and waiting for signals.
+//!
+//! Covers:
+//! 1. `SA_SIGINFO` handlers see the `sigqueue` sender and value
+//! 2. Real-time signals queue in order; `sigpending` reports them
+//! 3. `sigtimedwait` times out with `EAGAIN`
+//! 4. `SA_ONSTACK` handlers run on the `sigaltstack` stack
+//! 5. `sigsuspend` runs the handler and restores the mask afterwards
+//! 6. The handler's signal is blocked while it runs
+//! 7. A signal frame below the mapped main stack grows the stack
+//! 8. `xmm0` survives a handler that clobbers it, for a signal taken at a
+//!    syscall, and the frame's `fpregs` holds the interrupted value
+//! 9. The same for a timer signal taken while spinning in userspace
+
+#![no_std]
+#![no_main]
+
+// Force hadron_libc_core to be linked so its #[no_mangle] symbols
+// (sigaction, sigqueue, sigtimedwait, …) are available.
+extern crate hadron_libc_core;
+
+use core::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicUsize, Ordering};
+
+use hadron_libc_core::errno::{self, EAGAIN, EINTR};
+use hadron_libc_core::flags::{
+    SA_ONSTACK, SA_SIGINFO, SI_QUEUE, SIG_BLOCK, SIG_SETMASK, SIGALRM, SIGRTMIN, SIGSTKSZ, SIGUSR1,
+    SIGUSR2, SS_DISABLE, SS_ONSTACK,
+};
+use hadron_libc_core::signal::{
+    FpState, SigAction, SigAltStack, SigInfo, UContext, raise, sigaction, sigaltstack, sigpending,
+    sigprocmask, sigqueue, sigsuspend, sigtimedwait,
+};
+use hadron_libc_core::time::{ITIMER_REAL, Itimerval, Timespec, Timeval, setitimer};
+use hadron_utest::utest_main;
+
+utest_main!(
+    test_siginfo_from_sigqueue,
+    test_realtime_queue_order,
+    test_sigtimedwait_timeout,
+    test_altstack_handler,
+    test_sigsuspend,
+    test_signal_blocked_in_handler,
+    test_signal_frame_grows_stack,
+    test_handler_preserves_xmm_sync,
+    test_handler_preserves_xmm_async,
+);
+
+// ── extern declarations ───────────────────────────────────────────────────────
+
+unsafe extern "C" {
+    fn getpid() -> i32;
+}
+
+// ── helpers ───────────────────────────────────────────────────────────────────
+
+const SYS_TASK_KILL: usize = 0x03;
+
+const fn bit(sig: i32) -> u64 {
+    1 << (sig - 1)
+}
+
+fn install(sig: i32, handler: usize, flags: u32) {
+    let act = SigAction {
+        handler,
+        flags,
+        ..SigAction::default()
+    };
+    // SAFETY: act is a valid SigAction; oldact may be null.
+    let ret = unsafe { sigaction(sig, &raw const act, core::ptr::null_mut()) };
+    assert_eq!(ret, 0, "sigaction failed");
+}
+
+fn set_mask(mask: u64) -> u64 {
+    let mut old = 0u64;
+    // SAFETY: both pointers are valid.
+    let ret = unsafe { sigprocmask(SIG_SETMASK, &raw const mask, &raw mut old) };
+    assert_eq!(ret, 0, "sigprocmask failed");
+    old
+}
+
+fn current_mask() -> u64 {
+    let mut mask = 0u64;
+    // SAFETY: a null set only reads the mask into a valid pointer.
+    unsafe { sigprocmask(SIG_BLOCK, core::ptr::null(), &raw mut mask) };
+    mask
+}
+
+// ── handlers ──────────────────────────────────────────────────────────────────
+
+static LAST_SIGNO: AtomicI32 = AtomicI32::new(0);
+static LAST_CODE: AtomicI32 = AtomicI32::new(0);
+static LAST_PID: AtomicU64 = AtomicU64::new(0);
+static LAST_VALUE: AtomicU64 = AtomicU64::new(0);
+static HANDLER_SP: AtomicUsize = AtomicUsize::new(0);
+static HANDLER_ALT_FLAGS: AtomicI32 = AtomicI32::new(0);
+static HANDLER_MASK: AtomicU64 = AtomicU64::new(0);
+
+extern "C" fn record_info(sig: i32, info: *mut SigInfo, _uc: *mut u8) {
+    // SAFETY: the kernel passes a valid SigInfo to SA_SIGINFO handlers.
+    let info = unsafe { &*info };
+    LAST_CODE.store(info.code, Ordering::SeqCst);
+    LAST_PID.store(u64::from(info.pid), Ordering::SeqCst);
+    LAST_VALUE.store(info.value, Ordering::SeqCst);
+    LAST_SIGNO.store(sig, Ordering::SeqCst);
+}
+
+extern "C" fn record_stack(sig: i32) {
+    let local = 0u8;
+    HANDLER_SP.store(
+        core::hint::black_box(&raw const local) as usize,
+        Ordering::SeqCst,
+    );
+    let mut old = SigAltStack::default();
+    // SAFETY: old is a valid SigAltStack.
+    unsafe { sigaltstack(core::ptr::null(), &raw mut old) };
+    HANDLER_ALT_FLAGS.store(old.flags, Ordering::SeqCst);
+    LAST_SIGNO.store(sig, Ordering::SeqCst);
+}
+
+extern "C" fn record_mask(sig: i32) {
+    HANDLER_MASK.store(current_mask(), Ordering::SeqCst);
+    LAST_SIGNO.store(sig, Ordering::SeqCst);
+}
+
+static SAVED_XMM0: AtomicU64 = AtomicU64::new(0);
+static XMM_HANDLER_RAN: AtomicBool = AtomicBool::new(false);
+
+/// Records the low half of the saved `xmm0`, then sets `xmm0` to all ones.
+extern "C" fn clobber_xmm0(_sig: i32, _info: *mut SigInfo, uc: *mut UContext) {
+    // SAFETY: the kernel passes a valid UContext whose fpregs points to the
+    // FpState in the signal frame.
+    let fpstate = unsafe { &*((*uc).mcontext.fpregs as *const FpState) };
+    let xmm0 = fpstate.xmm[0];
+    SAVED_XMM0.store(
+        u64::from(xmm0[0]) | u64::from(xmm0[1]) << 32,
+        Ordering::SeqCst,
+    );
+    // SAFETY: only clobbers xmm0, which is declared.
+    unsafe { core::arch::asm!("pcmpeqd xmm0, xmm0", out("xmm0") _, options(nomem, nostack)) };
+    XMM_HANDLER_RAN.store(true, Ordering::SeqCst);
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+fn test_siginfo_from_sigqueue() {
+    install(SIGUSR1, record_info as *const () as usize, SA_SIGINFO);
+    LAST_SIGNO.store(0, Ordering::SeqCst);
+
+    // SAFETY: getpid has no preconditions.
+    let pid = unsafe { getpid() };
+    assert_eq!(sigqueue(pid, SIGUSR1, 42), 0, "sigqueue failed");
+
+    assert_eq!(
+        LAST_SIGNO.load(Ordering::SeqCst),
+        SIGUSR1,
+        "handler did not run"
+    );
+    assert_eq!(LAST_CODE.load(Ordering::SeqCst), SI_QUEUE);
+    assert_eq!(LAST_PID.load(Ordering::SeqCst), pid as u64);
+    assert_eq!(LAST_VALUE.load(Ordering::SeqCst), 42);
+    install(SIGUSR1, 0, 0);
+}
+
+fn test_realtime_queue_order() {
+    let old = set_mask(bit(SIGRTMIN));
+    // SAFETY: getpid has no preconditions.
+    let pid = unsafe { getpid() };
+    for value in 1..=3 {
+        assert_eq!(sigqueue(pid, SIGRTMIN, value), 0, "sigqueue failed");
+    }
+
+    let mut pending = 0u64;
+    // SAFETY: pending is a valid sigset.
+    assert_eq!(unsafe { sigpending(&raw mut pending) }, 0);
+    assert_eq!(pending, bit(SIGRTMIN), "SIGRTMIN should be pending");
+
+    let set = bit(SIGRTMIN);
+    let zero = Timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    for value in 1..=3 {
+        let mut info = SigInfo::default();
+        // SAFETY: all pointers are valid.
+        let sig = unsafe { sigtimedwait(&raw const set, &raw mut info, &raw const zero) };
+        assert_eq!(sig, SIGRTMIN);
+        assert_eq!(info.value, value as u64, "real-time signals out of order");
+    }
+    // SAFETY: all pointers are valid.
+    let sig = unsafe { sigtimedwait(&raw const set, core::ptr::null_mut(), &raw const zero) };
+    assert_eq!(sig, -1, "queue should be empty");
+    assert_eq!(errno::get_errno(), EAGAIN);
+    set_mask(old);
+}
+
+fn test_sigtimedwait_timeout() {
+    let old = set_mask(bit(SIGUSR2));
+    let set = bit(SIGUSR2);
+    let timeout = Timespec {
+        tv_sec: 0,
+        tv_nsec: 20_000_000,
+    };
+    // SAFETY: all pointers are valid.
+    let sig = unsafe { sigtimedwait(&raw const set, core::ptr::null_mut(), &raw const timeout) };
+    assert_eq!(sig, -1);
+    assert_eq!(errno::get_errno(), EAGAIN);
+    set_mask(old);
+}
+
+fn test_altstack_handler() {
+    static mut ALT_STACK: [u8; SIGSTKSZ] = [0; SIGSTKSZ];
+    let base = &raw mut ALT_STACK as usize;
+
+    let ss = SigAltStack {
+        sp: base,
+        size: SIGSTKSZ,
+        ..SigAltStack::default()
+    };
+    // SAFETY: ss describes a static buffer.
+    assert_eq!(
+        unsafe { sigaltstack(&raw const ss, core::ptr::null_mut()) },
+        0
+    );
+
+    install(SIGUSR2, record_stack as *const () as usize, SA_ONSTACK);
+    assert_eq!(raise(SIGUSR2), 0);
+
+    let sp = HANDLER_SP.load(Ordering::SeqCst);
+    assert!(
+        sp > base && sp < base + SIGSTKSZ,
+        "handler did not run on the alternate stack"
+    );
+    assert_eq!(HANDLER_ALT_FLAGS.load(Ordering::SeqCst), SS_ONSTACK);
+
+    let disable = SigAltStack {
+        flags: SS_DISABLE,
+        ..SigAltStack::default()
+    };
+    // SAFETY: disable is a valid SigAltStack.
+    assert_eq!(
+        unsafe { sigaltstack(&raw const disable, core::ptr::null_mut()) },
+        0
+    );
+    install(SIGUSR2, 0, 0);
+}
+
+fn test_sigsuspend() {
+    install(SIGUSR1, record_mask as *const () as usize, 0);
+    let old = set_mask(bit(SIGUSR1));
+    LAST_SIGNO.store(0, Ordering::SeqCst);
+
+    assert_eq!(raise(SIGUSR1), 0);
+    assert_eq!(
+        LAST_SIGNO.load(Ordering::SeqCst),
+        0,
+        "blocked signal was delivered"
+    );
+
+    let empty = 0u64;
+    // SAFETY: empty is a valid sigset.
+    let ret = unsafe { sigsuspend(&raw const empty) };
+    assert_eq!(ret, -1);
+    assert_eq!(errno::get_errno(), EINTR);
+    assert_eq!(
+        LAST_SIGNO.load(Ordering::SeqCst),
+        SIGUSR1,
+        "handler did not run"
+    );
+    assert_eq!(
+        current_mask(),
+        bit(SIGUSR1),
+        "sigsuspend did not restore the mask"
+    );
+
+    set_mask(old);
+    install(SIGUSR1, 0, 0);
+}
+
+fn test_signal_blocked_in_handler() {
+    install(SIGUSR1, record_mask as *const () as usize, 0);
+    let before = current_mask();
+
+    assert_eq!(raise(SIGUSR1), 0);
+    assert_ne!(
+        HANDLER_MASK.load(Ordering::SeqCst) & bit(SIGUSR1),
+        0,
+        "signal should be blocked inside its handler"
+    );
+    assert_eq!(
+        current_mask(),
+        before,
+        "mask not restored after the handler"
+    );
+    install(SIGUSR1, 0, 0);
+}
+
+fn test_signal_frame_grows_stack() {
+    // Far below anything mapped so far, but inside the 8 MiB growth limit.
+    const GAP: usize = 1 << 20;
+
+    install(SIGUSR1, record_stack as *const () as usize, 0);
+    HANDLER_SP.store(0, Ordering::SeqCst);
+    // SAFETY: getpid has no preconditions.
+    let pid = unsafe { getpid() } as usize;
+
+    let ret: isize;
+    let low_sp: usize;
+    // SAFETY: The stack pointer is lowered only around the syscall and
+    // restored from r12, which the handler's sigreturn preserves. The
+    // kernel writes the signal frame below the lowered stack pointer, on
+    // pages the stack has not grown to yet.
+    unsafe {
+        core::arch::asm!(
+            "mov r12, rsp",
+            "sub rsp, {gap}",
+            "mov r13, rsp",
+            "syscall",
+            "mov rsp, r12",
+            gap = const GAP,
+            inlateout("rax") SYS_TASK_KILL => ret,
+            in("rdi") pid,
+            in("rsi") SIGUSR1 as usize,
+            out("rcx") _,
+            out("r11") _,
+            out("r12") _,
+            out("r13") low_sp,
+        );
+    }
+    assert_eq!(ret, 0);
+
+    let sp = HANDLER_SP.load(Ordering::SeqCst);
+    assert!(
+        sp != 0 && sp < low_sp,
+        "handler did not run below the lowered stack pointer"
+    );
+    install(SIGUSR1, 0, 0);
+}
+
+fn test_handler_preserves_xmm_sync() {
+    const PATTERN: u64 = 0x0123_4567_89AB_CDEF;
+
+    install(SIGUSR1, clobber_xmm0 as *const () as usize, SA_SIGINFO);
+    SAVED_XMM0.store(0, Ordering::SeqCst);
+    XMM_HANDLER_RAN.store(false, Ordering::SeqCst);
+    // SAFETY: getpid has no preconditions.
+    let pid = unsafe { getpid() } as usize;
+
+    let ret: isize;
+    let after: u64;
+    // SAFETY: xmm0 holds the pattern across a kill of this process, whose
+    // handler runs before the syscall returns.
+    unsafe {
+        core::arch::asm!(
+            "movq xmm0, {pattern}",
+            "syscall",
+            "movq {after}, xmm0",
+            pattern = in(reg) PATTERN,
+            after = lateout(reg) after,
+            inlateout("rax") SYS_TASK_KILL => ret,
+            in("rdi") pid,
+            in("rsi") SIGUSR1 as usize,
+            out("rcx") _,
+            out("r11") _,
+            out("xmm0") _,
+        );
+    }
+    assert_eq!(ret, 0);
+    assert!(
+        XMM_HANDLER_RAN.load(Ordering::SeqCst),
+        "handler did not run"
+    );
+    assert_eq!(
+        SAVED_XMM0.load(Ordering::SeqCst),
+        PATTERN,
+        "fpregs should hold the interrupted xmm0"
+    );
+    assert_eq!(after, PATTERN, "xmm0 not restored after the handler");
+    install(SIGUSR1, 0, 0);
+}
+
+fn test_handler_preserves_xmm_async() {
+    const PATTERN: u64 = 0xFEDC_BA98_7654_3210;
+
+    install(SIGALRM, clobber_xmm0 as *const () as usize, SA_SIGINFO);
+    XMM_HANDLER_RAN.store(false, Ordering::SeqCst);
+    let new = Itimerval {
+        it_interval: Timeval::default(),
+        it_value: Timeval {
+            tv_sec: 0,
+            tv_usec: 10_000,
+        },
+    };
+    // SAFETY: new is valid; old may be null.
+    assert_eq!(
+        unsafe { setitimer(ITIMER_REAL, &raw const new, core::ptr::null_mut()) },
+        0
+    );
+
+    let after: u64;
+    // SAFETY: Spins with the pattern in xmm0, making no syscalls, until the
+    // handler has run or the iteration budget is spent; the handler only
+    // reads and writes the flag and its own statics.
+    unsafe {
+        core::arch::asm!(
+            "movq xmm0, {pattern}",
+            "2:",
+            "cmp byte ptr [{ran}], 0",
+            "jne 3f",
+            "pause",
+            "dec {budget}",
+            "jnz 2b",
+            "3:",
+            "movq {after}, xmm0",
+            pattern = in(reg) PATTERN,
+            ran = in(reg) XMM_HANDLER_RAN.as_ptr(),
+            budget = inout(reg) 1usize << 32 => _,
+            after = lateout(reg) after,
+            out("xmm0") _,
+            options(nostack),
+        );
+    }
+    assert!(
+        XMM_HANDLER_RAN.load(Ordering::SeqCst),
+        "SIGALRM not delivered"
+    );
+    assert_eq!(after, PATTERN, "xmm0 not restored after the handler");
+    install(SIGALRM, 0, 0);
+}
@@ -0,0 +1,96 @@
+//! utest: sub-millisecond sleeps and blocking-call timeouts.
+//!
+//! Covers:
+//! 1. `nanosleep` for less than a tick sleeps at least the requested time
+//! 2. `nanosleep` rejects an out-of-range `tv_nsec`
+//! 3. A timed `FUTEX_WAIT` returns `ETIMEDOUT` after its timeout
+//! 4. `poll` with no fds sleeps for its timeout
+
+#![no_std]
+#![no_main]
+
+// Force hadron_libc_core to be linked so its #[no_mangle] symbols
+// (nanosleep, clock_gettime, poll, …) are available.
+extern crate hadron_libc_core;
+
+use core::sync::atomic::AtomicU32;
+
+use hadron_libc_core::errno::{self, EINVAL, ETIMEDOUT};
+use hadron_libc_core::poll::poll;
+use hadron_libc_core::sys::sys_futex;
+use hadron_libc_core::time::{Timespec, clock_gettime, nanosleep};
+use hadron_utest::utest_main;
+
+utest_main!(
+    test_sub_tick_nanosleep,
+    test_nanosleep_invalid,
+    test_futex_wait_times_out,
+    test_poll_without_fds_sleeps,
+);
+
+/// `CLOCK_MONOTONIC` from `<time.h>`.
+const CLOCK_MONOTONIC: i32 = 0;
+/// `FUTEX_WAIT` from `<linux/futex.h>`.
+const FUTEX_WAIT: usize = 0;
+
+// ── helpers ───────────────────────────────────────────────────────────────────
+
+fn now_ns() -> u64 {
+    let mut ts = Timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: ts is a valid Timespec.
+    let ret = unsafe { clock_gettime(CLOCK_MONOTONIC, &raw mut ts) };
+    assert_eq!(ret, 0, "clock_gettime failed");
+    ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+fn test_sub_tick_nanosleep() {
+    let req = Timespec {
+        tv_sec: 0,
+        tv_nsec: 200_000,
+    };
+    let before = now_ns();
+    // SAFETY: req is a valid Timespec; rem may be null.
+    let ret = unsafe { nanosleep(&req, core::ptr::null_mut()) };
+    let elapsed = now_ns() - before;
+    assert_eq!(ret, 0, "nanosleep failed");
+    assert!(elapsed >= 200_000, "woke after {elapsed} ns");
+}
+
+fn test_nanosleep_invalid() {
+    let req = Timespec {
+        tv_sec: 0,
+        tv_nsec: 1_000_000_000,
+    };
+    // SAFETY: req is a valid Timespec; rem may be null.
+    let ret = unsafe { nanosleep(&req, core::ptr::null_mut()) };
+    assert_eq!(ret, -1);
+    assert_eq!(errno::get_errno(), EINVAL);
+}
+
+fn test_futex_wait_times_out() {
+    let word = AtomicU32::new(0);
+    let timeout = Timespec {
+        tv_sec: 0,
+        tv_nsec: 2_000_000,
+    };
+    let before = now_ns();
+    // Nobody wakes the futex, so the wait can only end by timing out.
+    let ret = sys_futex(word.as_ptr(), FUTEX_WAIT, 0, &timeout);
+    let elapsed = now_ns() - before;
+    assert_eq!(ret, Err(ETIMEDOUT));
+    assert!(elapsed >= 2_000_000, "timed out after {elapsed} ns");
+}
+
+fn test_poll_without_fds_sleeps() {
+    let before = now_ns();
+    // SAFETY: with nfds == 0 the fds pointer is never dereferenced.
+    let ret = unsafe { poll(core::ptr::null_mut(), 0, 3) };
+    let elapsed = now_ns() - before;
+    assert_eq!(ret, 0);
+    assert!(elapsed >= 3_000_000, "poll returned after {elapsed} ns");
+}
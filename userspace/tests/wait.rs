@@ -0,0 +1,353 @@
+//! utest: waiting for children with `wait4` and `waitid`, group waits and
+//! child subreapers.
+//!
+//! The test respawns its own binary (`/bin/init`) with a role in
+//! `argv[1]` to get child processes; see [`run_child`].
+//!
+//! Covers:
+//! 1. `wait4` returns the exit status and the child's CPU time in `rusage`
+//! 2. `wait4` with `WNOHANG` returns 0 while the child runs; a killed child
+//!    reports its signal
+//! 3. `waitid` with `WNOHANG` returns 0 and zeroes the `siginfo`
+//! 4. `waitid` with `WNOWAIT` leaves the child waitable
+//! 5. Group waits: `wait4` with pid 0 and `-pgid`, `waitid` with `P_PGID`
+//! 6. Orphans are adopted by the nearest child subreaper
+
+#![no_std]
+#![no_main]
+
+// Force hadron_libc_core to be linked so its #[no_mangle] symbols
+// (wait4, waitid, prctl, …) are available.
+extern crate hadron_libc_core;
+
+use hadron_libc_core::errno::{self, ECHILD};
+use hadron_libc_core::flags::{
+    CLOCK_MONOTONIC, P_ALL, P_PGID, P_PID, PR_GET_CHILD_SUBREAPER, PR_SET_CHILD_SUBREAPER, SIGCHLD,
+    SIGKILL, WEXITED, WNOHANG, WNOWAIT,
+};
+use hadron_libc_core::process::{_exit, getppid, kill, prctl, setpgid, wait4, waitid};
+use hadron_libc_core::signal::SigInfo;
+use hadron_libc_core::time::{Rusage, Timespec, Timeval, clock_gettime, nanosleep};
+use hadron_syscall_user::wrappers::sys_task_spawn;
+use hadron_syscall_user::{CLD_EXITED, CLD_KILLED, ID_UNCHANGED, SpawnArg, SpawnInfo};
+use hadron_utest::utest_main;
+
+utest_main!(
+    child = run_child;
+    test_wait4_rusage,
+    test_wait4_wnohang_and_signal,
+    test_waitid_wnohang_zeroes_info,
+    test_waitid_wnowait,
+    test_group_waits,
+    test_subreaper_adopts_orphans,
+);
+
+// ── helpers ───────────────────────────────────────────────────────────────────
+
+/// Path of this test binary in the utest initrd.
+const SELF: &[u8] = b"/bin/init";
+
+/// Exit code of the `spin` role.
+const SPIN_EXIT: i32 = 7;
+
+/// Spawns this binary with `args` after `argv[0]` and returns the child PID.
+fn spawn(args: &[&[u8]]) -> i32 {
+    let mut argv = [SpawnArg { ptr: 0, len: 0 }; 4];
+    argv[0] = SpawnArg {
+        ptr: SELF.as_ptr() as usize,
+        len: SELF.len(),
+    };
+    for (desc, arg) in argv[1..].iter_mut().zip(args) {
+        *desc = SpawnArg {
+            ptr: arg.as_ptr() as usize,
+            len: arg.len(),
+        };
+    }
+    let info = SpawnInfo {
+        path_ptr: SELF.as_ptr() as usize,
+        path_len: SELF.len(),
+        argv_ptr: argv.as_ptr() as usize,
+        argv_count: 1 + args.len(),
+        envp_ptr: 0,
+        envp_count: 0,
+        fd_map_ptr: 0,
+        fd_map_count: 0,
+        cwd_ptr: 0,
+        cwd_len: 0,
+        uid: ID_UNCHANGED,
+        gid: ID_UNCHANGED,
+    };
+    let pid = sys_task_spawn(&raw const info as usize, core::mem::size_of::<SpawnInfo>());
+    assert!(pid > 0, "spawn failed");
+    pid as i32
+}
+
+/// Formats `n` in decimal into `buf`.
+fn decimal(mut n: u32, buf: &mut [u8; 10]) -> &[u8] {
+    let mut start = buf.len();
+    loop {
+        start -= 1;
+        buf[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return &buf[start..];
+        }
+    }
+}
+
+/// Parses a decimal argument written by [`decimal`].
+fn parse(arg: &[u8]) -> i32 {
+    arg.iter()
+        .fold(0, |n, &digit| n * 10 + i32::from(digit - b'0'))
+}
+
+fn sleep_ms(ms: i64) {
+    let req = Timespec {
+        tv_sec: 0,
+        tv_nsec: ms * 1_000_000,
+    };
+    // SAFETY: req is valid; rem may be null.
+    unsafe { nanosleep(&raw const req, core::ptr::null_mut()) };
+}
+
+fn now_ns() -> i64 {
+    let mut ts = Timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: ts is a valid Timespec.
+    unsafe { clock_gettime(CLOCK_MONOTONIC, &raw mut ts) };
+    ts.tv_sec * 1_000_000_000 + ts.tv_nsec
+}
+
+fn micros(tv: Timeval) -> i64 {
+    tv.tv_sec * 1_000_000 + tv.tv_usec
+}
+
+/// `wait4` for `pid` with `options`; returns the result and the status.
+fn wait(pid: i32, options: i32) -> (i32, i32) {
+    let mut status = 0;
+    // SAFETY: status is valid; rusage may be null.
+    let ret = unsafe { wait4(pid, &raw mut status, options, core::ptr::null_mut()) };
+    (ret, status)
+}
+
+/// `waitid` into a fresh `SigInfo`; returns the result and the record.
+fn wait_info(idtype: i32, id: i32, options: i32) -> (i32, SigInfo) {
+    let mut info = SigInfo::default();
+    // SAFETY: info is a valid SigInfo.
+    let ret = unsafe { waitid(idtype, id as u32, &raw mut info, options) };
+    (ret, info)
+}
+
+const fn exit_code(status: i32) -> Option<i32> {
+    if status & 0x7F == 0 {
+        Some((status >> 8) & 0xFF)
+    } else {
+        None
+    }
+}
+
+const fn term_signal(status: i32) -> i32 {
+    status & 0x7F
+}
+
+fn subreaper() -> i32 {
+    let mut enabled = -1;
+    // SAFETY: PR_GET_CHILD_SUBREAPER writes to a valid i32.
+    let ret = unsafe { prctl(PR_GET_CHILD_SUBREAPER, (&raw mut enabled) as usize) };
+    assert_eq!(ret, 0, "PR_GET_CHILD_SUBREAPER failed");
+    enabled
+}
+
+// ── child roles ───────────────────────────────────────────────────────────────
+
+/// Entry point of a respawned child; `role` is its `argv[1]`.
+///
+/// A failed assertion exits the child with 1, which the parent sees in
+/// the wait status.
+fn run_child(role: &[u8]) -> ! {
+    let code = match role {
+        // Burn ~20 ms of CPU time, then exit with SPIN_EXIT.
+        b"spin" => {
+            let start = now_ns();
+            while now_ns() - start < 20_000_000 {
+                core::hint::spin_loop();
+            }
+            SPIN_EXIT
+        }
+        // Exit with the code in argv[2].
+        b"exit" => parse(hadron_utest::arg(2).expect("exit code")),
+        // Run until killed.
+        b"sleep" => loop {
+            sleep_ms(100);
+        },
+        // Spawn an `adoptee` that expects to be adopted by our parent,
+        // then exit straight away.
+        b"orphan" => {
+            let mut buf = [0; 10];
+            spawn(&[b"adoptee", decimal(getppid() as u32, &mut buf)]);
+            0
+        }
+        // Wait (up to ~1 s) to be reparented to the PID in argv[2].
+        b"adoptee" => {
+            let adopter = parse(hadron_utest::arg(2).expect("adopter PID"));
+            let mut tries = 0;
+            while getppid() != adopter && tries < 1000 {
+                sleep_ms(1);
+                tries += 1;
+            }
+            i32::from(getppid() != adopter)
+        }
+        // Become a subreaper, orphan a grandchild and reap it.
+        b"subreaper" => {
+            // SAFETY: PR_SET_CHILD_SUBREAPER takes no pointer.
+            assert_eq!(unsafe { prctl(PR_SET_CHILD_SUBREAPER, 1) }, 0);
+            assert_eq!(subreaper(), 1);
+
+            let orphan = spawn(&[b"orphan"]);
+            let (ret, status) = wait(orphan, 0);
+            assert_eq!(ret, orphan);
+            assert_eq!(exit_code(status), Some(0), "orphan role failed");
+
+            // The only child left is the adopted grandchild.
+            let (adoptee, status) = wait(-1, 0);
+            assert!(adoptee > 0, "the orphaned grandchild was not adopted");
+            assert_eq!(exit_code(status), Some(0), "adoptee saw the wrong parent");
+            assert_eq!(wait(-1, WNOHANG).0, -1);
+            assert_eq!(errno::get_errno(), ECHILD);
+            0
+        }
+        _ => panic!("unknown child role"),
+    };
+    // SAFETY: the child has no atexit handlers or buffered output to lose.
+    unsafe { _exit(code) }
+}
+
+// ── tests ─────────────────────────────────────────────────────────────────────
+
+/// `wait4` reports the exit code and the CPU time the child used.
+fn test_wait4_rusage() {
+    let pid = spawn(&[b"spin"]);
+    let mut status = 0;
+    let mut ru = Rusage::default();
+    // SAFETY: status and ru are valid.
+    let ret = unsafe { wait4(pid, &raw mut status, 0, &raw mut ru) };
+    assert_eq!(ret, pid);
+    assert_eq!(exit_code(status), Some(SPIN_EXIT));
+    assert!(micros(ru.ru_utime) > 0, "spinning should be user time");
+    assert!(
+        micros(ru.ru_utime) + micros(ru.ru_stime) >= 10_000,
+        "rusage should cover the ~20 ms the child ran"
+    );
+}
+
+/// `WNOHANG` does not block on a running child; `SIGKILL` is reported as
+/// the terminating signal, after which no children remain.
+fn test_wait4_wnohang_and_signal() {
+    let pid = spawn(&[b"sleep"]);
+    assert_eq!(wait(pid, WNOHANG), (0, 0), "child is still running");
+
+    assert_eq!(kill(pid, SIGKILL), 0);
+    let (ret, status) = wait(pid, 0);
+    assert_eq!(ret, pid);
+    assert_eq!(exit_code(status), None);
+    assert_eq!(term_signal(status), SIGKILL);
+
+    assert_eq!(wait(-1, WNOHANG).0, -1);
+    assert_eq!(errno::get_errno(), ECHILD);
+}
+
+/// `waitid` with `WNOHANG` and nothing to report returns 0 with a zeroed
+/// record; a killed child is then reported as `CLD_KILLED`.
+fn test_waitid_wnohang_zeroes_info() {
+    let pid = spawn(&[b"sleep"]);
+
+    let mut info = SigInfo {
+        signo: SIGCHLD,
+        code: CLD_EXITED,
+        pid: 1234,
+        status: 99,
+        ..SigInfo::default()
+    };
+    // SAFETY: info is a valid SigInfo.
+    let ret = unsafe { waitid(P_PID, pid as u32, &raw mut info, WEXITED | WNOHANG) };
+    assert_eq!(ret, 0);
+    assert_eq!(
+        (info.signo, info.code, info.pid, info.status),
+        (0, 0, 0, 0),
+        "WNOHANG with nothing to report should zero the siginfo"
+    );
+
+    assert_eq!(kill(pid, SIGKILL), 0);
+    let (ret, info) = wait_info(P_PID, pid, WEXITED);
+    assert_eq!(ret, 0);
+    assert_eq!(info.signo, SIGCHLD);
+    assert_eq!(info.code, CLD_KILLED);
+    assert_eq!(info.pid, pid as u32);
+    assert_eq!(info.status, SIGKILL);
+}
+
+/// `WNOWAIT` reports the child but leaves it to a later wait.
+fn test_waitid_wnowait() {
+    let pid = spawn(&[b"exit", b"5"]);
+
+    for _ in 0..2 {
+        let (ret, info) = wait_info(P_PID, pid, WEXITED | WNOWAIT);
+        assert_eq!(ret, 0);
+        assert_eq!(info.signo, SIGCHLD);
+        assert_eq!(info.code, CLD_EXITED);
+        assert_eq!(info.pid, pid as u32);
+        assert_eq!(info.status, 5);
+    }
+
+    let (ret, info) = wait_info(P_ALL, 0, WEXITED);
+    assert_eq!(ret, 0);
+    assert_eq!(info.pid, pid as u32, "WNOWAIT should not have reaped it");
+
+    assert_eq!(wait_info(P_ALL, 0, WEXITED | WNOHANG).0, -1);
+    assert_eq!(errno::get_errno(), ECHILD);
+}
+
+/// `wait4(0)` only sees children in the caller's group, `wait4(-pgid)`
+/// and `waitid(P_PGID)` only those in `pgid`.
+fn test_group_waits() {
+    // Two sleepers in their own group, led by the first.
+    let leader = spawn(&[b"sleep"]);
+    assert_eq!(setpgid(leader, leader), 0);
+    let member = spawn(&[b"sleep"]);
+    assert_eq!(setpgid(member, leader), 0);
+    // One child that stays in our group.
+    let local = spawn(&[b"exit", b"3"]);
+
+    let (ret, status) = wait(0, 0);
+    assert_eq!(ret, local, "wait4(0) should only see our own group");
+    assert_eq!(exit_code(status), Some(3));
+    assert_eq!(wait(0, WNOHANG).0, -1);
+    assert_eq!(errno::get_errno(), ECHILD);
+
+    assert_eq!(wait(-leader, WNOHANG), (0, 0), "the group is still running");
+    assert_eq!(kill(leader, SIGKILL), 0);
+    let (ret, status) = wait(-leader, 0);
+    assert_eq!(ret, leader);
+    assert_eq!(term_signal(status), SIGKILL);
+
+    assert_eq!(kill(member, SIGKILL), 0);
+    let (ret, info) = wait_info(P_PGID, leader, WEXITED);
+    assert_eq!(ret, 0);
+    assert_eq!(info.pid, member as u32);
+    assert_eq!(info.code, CLD_KILLED);
+}
+
+/// A subreaper adopts the children of its exited child, rather than init.
+///
+/// This test runs as init, so the subreaper is a child: it orphans a
+/// grandchild and checks that it inherits and reaps it.
+fn test_subreaper_adopts_orphans() {
+    assert_eq!(subreaper(), 0, "subreaper is off by default");
+
+    let pid = spawn(&[b"subreaper"]);
+    let (ret, status) = wait(pid, 0);
+    assert_eq!(ret, pid);
+    assert_eq!(exit_code(status), Some(0), "subreaper role failed");
+}